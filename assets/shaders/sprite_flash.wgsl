@@ -0,0 +1,12 @@
+#import bevy_sprite::{
+    sprite_vertex_output::VertexOutput,
+    sprite_view_bindings::{sprite_texture, sprite_sampler},
+}
+
+@group(2) @binding(0) var<uniform> flash_color: vec4<f32>;
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let color = in.color * textureSample(sprite_texture, sprite_sampler, in.uv);
+    return vec4<f32>(mix(color.rgb, flash_color.rgb, flash_color.a), color.a);
+}
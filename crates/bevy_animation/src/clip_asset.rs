@@ -0,0 +1,282 @@
+//! Loading and saving [`AnimationClip`]s as RON assets.
+
+use std::io;
+
+use bevy_asset::io::{Reader, Writer};
+use bevy_asset::saver::{AssetSaver, SavedAsset};
+use bevy_asset::{AssetLoader, AsyncReadExt as _, AsyncWriteExt as _, LoadContext};
+use bevy_math::{Quat, Vec3};
+use ron::de::SpannedError;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::{AnimationClip, AnimationTargetId, Interpolation, Keyframes, VariableCurve};
+
+/// An [`AssetLoader`] that can load [`AnimationClip`]s saved by the
+/// [`AnimationClipSaver`].
+///
+/// The canonical extension for [`AnimationClip`]s is `.anim.ron`. Plain
+/// `.anim` is supported as well.
+#[derive(Default)]
+pub struct AnimationClipLoader;
+
+/// An [`AssetSaver`] that writes [`AnimationClip`]s as RON, so that they can
+/// be loaded back with the [`AnimationClipLoader`].
+#[derive(Default)]
+pub struct AnimationClipSaver;
+
+/// Various errors that can occur when serializing or deserializing animation
+/// clips to and from RON, respectively.
+#[derive(Error, Debug)]
+pub enum AnimationClipLoadError {
+    /// An I/O error occurred.
+    #[error("could not read or write the animation clip: {0}")]
+    Io(#[from] io::Error),
+    /// An error occurred in RON serialization or deserialization.
+    #[error("could not convert the animation clip to or from RON: {0}")]
+    Ron(#[from] ron::Error),
+    /// An error occurred in RON deserialization, and the location of the error
+    /// is supplied.
+    #[error("could not parse the animation clip RON: {0}")]
+    SpannedRon(#[from] SpannedError),
+}
+
+/// A version of [`AnimationClip`] suitable for serializing as an asset.
+///
+/// Target IDs are stored as the raw bits of their UUID, and keyframes as plain
+/// arrays, so that the format doesn't depend on the `serde` support of other
+/// crates.
+#[derive(Serialize, Deserialize)]
+struct SerializedAnimationClipAsset {
+    duration: f32,
+    curves: Vec<SerializedVariableCurve>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SerializedVariableCurve {
+    target: (u64, u64),
+    keyframe_timestamps: Vec<f32>,
+    keyframes: SerializedKeyframes,
+    interpolation: SerializedInterpolation,
+}
+
+#[derive(Serialize, Deserialize)]
+enum SerializedKeyframes {
+    Rotation(Vec<[f32; 4]>),
+    Translation(Vec<[f32; 3]>),
+    Scale(Vec<[f32; 3]>),
+    Weights(Vec<f32>),
+}
+
+#[derive(Serialize, Deserialize)]
+enum SerializedInterpolation {
+    Linear,
+    Step,
+    CubicSpline,
+}
+
+impl AnimationClip {
+    /// Serializes the animation clip to the given [`io::Write`]r in RON format.
+    ///
+    /// If writing to a file, it can later be loaded with the
+    /// [`AnimationClipLoader`] to reconstruct the clip.
+    pub fn save<W>(&self, writer: &mut W) -> Result<(), AnimationClipLoadError>
+    where
+        W: io::Write,
+    {
+        let mut ron_serializer = ron::ser::Serializer::new(writer, None)?;
+        Ok(SerializedAnimationClipAsset::from(self).serialize(&mut ron_serializer)?)
+    }
+}
+
+impl AssetLoader for AnimationClipLoader {
+    type Asset = AnimationClip;
+
+    type Settings = ();
+
+    type Error = AnimationClipLoadError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _: &'a Self::Settings,
+        _: &'a mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let mut deserializer = ron::de::Deserializer::from_bytes(&bytes)?;
+        let serialized_clip = SerializedAnimationClipAsset::deserialize(&mut deserializer)
+            .map_err(|err| deserializer.span_error(err))?;
+        Ok(serialized_clip.into())
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["anim", "anim.ron"]
+    }
+}
+
+impl AssetSaver for AnimationClipSaver {
+    type Asset = AnimationClip;
+
+    type Settings = ();
+
+    type OutputLoader = AnimationClipLoader;
+
+    type Error = AnimationClipLoadError;
+
+    async fn save<'a>(
+        &'a self,
+        writer: &'a mut Writer,
+        clip: SavedAsset<'a, Self::Asset>,
+        _: &'a Self::Settings,
+    ) -> Result<(), Self::Error> {
+        let mut bytes = Vec::new();
+        clip.save(&mut bytes)?;
+        writer.write_all(&bytes).await?;
+        Ok(())
+    }
+}
+
+impl From<&AnimationClip> for SerializedAnimationClipAsset {
+    fn from(clip: &AnimationClip) -> Self {
+        // Sort the targets so that saving the same clip twice produces the
+        // same file.
+        let mut targets = clip.curves.keys().copied().collect::<Vec<_>>();
+        targets.sort();
+
+        Self {
+            duration: clip.duration,
+            curves: targets
+                .into_iter()
+                .flat_map(|target| {
+                    clip.curves[&target]
+                        .iter()
+                        .map(move |curve| SerializedVariableCurve {
+                            target: target.0.as_u64_pair(),
+                            keyframe_timestamps: curve.keyframe_timestamps.clone(),
+                            keyframes: match &curve.keyframes {
+                                Keyframes::Rotation(values) => SerializedKeyframes::Rotation(
+                                    values.iter().map(|value| value.to_array()).collect(),
+                                ),
+                                Keyframes::Translation(values) => SerializedKeyframes::Translation(
+                                    values.iter().map(|value| value.to_array()).collect(),
+                                ),
+                                Keyframes::Scale(values) => SerializedKeyframes::Scale(
+                                    values.iter().map(|value| value.to_array()).collect(),
+                                ),
+                                Keyframes::Weights(values) => {
+                                    SerializedKeyframes::Weights(values.clone())
+                                }
+                            },
+                            interpolation: match curve.interpolation {
+                                Interpolation::Linear => SerializedInterpolation::Linear,
+                                Interpolation::Step => SerializedInterpolation::Step,
+                                Interpolation::CubicSpline => SerializedInterpolation::CubicSpline,
+                            },
+                        })
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<SerializedAnimationClipAsset> for AnimationClip {
+    fn from(serialized_clip: SerializedAnimationClipAsset) -> Self {
+        let mut clip = AnimationClip::default();
+        for curve in serialized_clip.curves {
+            clip.add_curve_to_target(
+                AnimationTargetId(Uuid::from_u64_pair(curve.target.0, curve.target.1)),
+                VariableCurve {
+                    keyframe_timestamps: curve.keyframe_timestamps,
+                    keyframes: match curve.keyframes {
+                        SerializedKeyframes::Rotation(values) => {
+                            Keyframes::Rotation(values.into_iter().map(Quat::from_array).collect())
+                        }
+                        SerializedKeyframes::Translation(values) => Keyframes::Translation(
+                            values.into_iter().map(Vec3::from_array).collect(),
+                        ),
+                        SerializedKeyframes::Scale(values) => {
+                            Keyframes::Scale(values.into_iter().map(Vec3::from_array).collect())
+                        }
+                        SerializedKeyframes::Weights(values) => Keyframes::Weights(values),
+                    },
+                    interpolation: match curve.interpolation {
+                        SerializedInterpolation::Linear => Interpolation::Linear,
+                        SerializedInterpolation::Step => Interpolation::Step,
+                        SerializedInterpolation::CubicSpline => Interpolation::CubicSpline,
+                    },
+                },
+            );
+        }
+        // The duration can be set explicitly and be longer than the curves.
+        clip.duration = serialized_clip.duration;
+        clip
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let target = AnimationTargetId(Uuid::from_u128(42));
+        let mut clip = AnimationClip::default();
+        clip.add_curve_to_target(
+            target,
+            VariableCurve {
+                keyframe_timestamps: vec![0.0, 1.0],
+                keyframes: Keyframes::Rotation(vec![Quat::IDENTITY, Quat::from_rotation_y(1.0)]),
+                interpolation: Interpolation::Linear,
+            },
+        );
+        clip.add_curve_to_target(
+            target,
+            VariableCurve {
+                keyframe_timestamps: vec![0.0, 2.0],
+                keyframes: Keyframes::Translation(vec![Vec3::ZERO, Vec3::X]),
+                interpolation: Interpolation::Step,
+            },
+        );
+        clip.set_duration(3.0);
+
+        let mut bytes = Vec::new();
+        clip.save(&mut bytes).unwrap();
+        let loaded: AnimationClip = ron::de::from_bytes::<SerializedAnimationClipAsset>(&bytes)
+            .unwrap()
+            .into();
+
+        assert_eq!(loaded.duration(), 3.0);
+        let curves = loaded.curves_for_target(target).unwrap();
+        assert_eq!(curves.len(), 2);
+        assert!(matches!(
+            &curves[0].keyframes,
+            Keyframes::Rotation(rotations) if rotations[1] == Quat::from_rotation_y(1.0)
+        ));
+        assert!(matches!(curves[1].interpolation, Interpolation::Step));
+        assert_eq!(curves[1].keyframe_timestamps, vec![0.0, 2.0]);
+    }
+
+    #[test]
+    fn error_messages_include_source() {
+        let source = ron::de::from_bytes::<SerializedAnimationClipAsset>(b"(duration: ")
+            .err()
+            .unwrap();
+        let message = AnimationClipLoadError::from(source.clone()).to_string();
+        assert_eq!(
+            message,
+            format!("could not parse the animation clip RON: {source}")
+        );
+
+        let message = AnimationClipLoadError::from(io::Error::from(io::ErrorKind::UnexpectedEof));
+        assert_eq!(
+            message.to_string(),
+            format!(
+                "could not read or write the animation clip: {}",
+                io::Error::from(io::ErrorKind::UnexpectedEof)
+            )
+        );
+    }
+}
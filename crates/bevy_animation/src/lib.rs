@@ -8,6 +8,7 @@
 //! Animation for the game engine Bevy
 
 mod animatable;
+mod clip_asset;
 mod graph;
 mod transition;
 mod util;
@@ -38,7 +39,7 @@ use fixedbitset::FixedBitSet;
 use graph::{AnimationGraph, AnimationNodeIndex};
 use petgraph::graph::NodeIndex;
 use petgraph::Direction;
-use prelude::{AnimationGraphAssetLoader, AnimationTransitions};
+use prelude::{AnimationClipLoader, AnimationGraphAssetLoader, AnimationTransitions};
 use thread_local::ThreadLocal;
 use uuid::Uuid;
 
@@ -46,8 +47,8 @@ use uuid::Uuid;
 pub mod prelude {
     #[doc(hidden)]
     pub use crate::{
-        animatable::*, graph::*, transition::*, AnimationClip, AnimationPlayer, AnimationPlugin,
-        Interpolation, Keyframes, VariableCurve,
+        animatable::*, clip_asset::*, graph::*, transition::*, AnimationClip, AnimationPlayer,
+        AnimationPlugin, Interpolation, Keyframes, VariableCurve,
     };
 }
 
@@ -1155,6 +1156,7 @@ impl Plugin for AnimationPlugin {
     fn build(&self, app: &mut App) {
         app.init_asset::<AnimationClip>()
             .init_asset::<AnimationGraph>()
+            .init_asset_loader::<AnimationClipLoader>()
             .init_asset_loader::<AnimationGraphAssetLoader>()
             .register_asset_reflect::<AnimationClip>()
             .register_asset_reflect::<AnimationGraph>()
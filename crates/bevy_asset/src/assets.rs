@@ -1,6 +1,7 @@
 use crate::{self as bevy_asset};
 use crate::{
-    Asset, AssetEvent, AssetHandleProvider, AssetId, AssetServer, Handle, LoadState, UntypedHandle,
+    reload::ReloadHook, Asset, AssetEvent, AssetHandleProvider, AssetId, AssetPath,
+    AssetReloadContext, AssetServer, Handle, LoadState, UntypedHandle,
 };
 use bevy_ecs::{
     prelude::EventWriter,
@@ -297,6 +298,9 @@ pub struct Assets<A: Asset> {
     /// Assets managed by the `Assets` struct with live strong `Handle`s
     /// originating from `get_strong_handle`.
     duplicate_handles: HashMap<AssetId<A>, u16>,
+    /// Merges reloaded assets into existing ones, registered by
+    /// [`AssetApp::register_asset_reload_hook`](crate::AssetApp::register_asset_reload_hook).
+    reload_hook: Option<ReloadHook<A>>,
 }
 
 impl<A: Asset> Default for Assets<A> {
@@ -310,6 +314,7 @@ impl<A: Asset> Default for Assets<A> {
             hash_map: Default::default(),
             queued_events: Default::default(),
             duplicate_handles: Default::default(),
+            reload_hook: None,
         }
     }
 }
@@ -338,6 +343,28 @@ impl<A: Asset> Assets<A> {
         }
     }
 
+    pub(crate) fn set_reload_hook(&mut self, hook: ReloadHook<A>) {
+        self.reload_hook = Some(hook);
+    }
+
+    /// Inserts an asset produced by a loader. If an asset already exists for `id` and a reload hook is registered,
+    /// the loaded asset is merged into the existing one instead of replacing it.
+    pub(crate) fn insert_loaded(
+        &mut self,
+        id: AssetId<A>,
+        asset: A,
+        path: Option<&AssetPath<'static>>,
+    ) {
+        if let Some(hook) = self.reload_hook {
+            // `get_mut` queues the `Modified` event.
+            if let Some(existing) = self.get_mut(id) {
+                hook(existing, asset, &AssetReloadContext { id, path });
+                return;
+            }
+        }
+        self.insert(id, asset);
+    }
+
     /// Retrieves an [`Asset`] stored for the given `id` if it exists. If it does not exist, it will be inserted using `insert_fn`.
     // PERF: Optimize this or remove it
     pub fn get_or_insert_with(
@@ -3,7 +3,6 @@ use crate::io::{
     memory::Dir,
     AssetSourceEvent, AssetWatcher,
 };
-use bevy_utils::tracing::warn;
 use bevy_utils::{Duration, HashMap};
 use notify_debouncer_full::{notify::RecommendedWatcher, Debouncer, FileIdMap};
 use parking_lot::RwLock;
@@ -62,27 +61,38 @@ impl FilesystemEventHandler for EmbeddedEventHandler {
     fn get_path(&self, absolute_path: &Path) -> Option<(PathBuf, bool)> {
         let (local_path, is_meta) = get_asset_path(&self.root, absolute_path);
         let final_path = self.root_paths.read().get(local_path.as_path())?.clone();
-        if is_meta {
-            warn!("Meta file asset hot-reloading is not supported yet: {final_path:?}");
-        }
-        Some((final_path, false))
+        Some((final_path, is_meta))
     }
 
     fn handle(&mut self, absolute_paths: &[PathBuf], event: AssetSourceEvent) {
         if self.last_event.as_ref() != Some(&event) {
-            if let AssetSourceEvent::ModifiedAsset(path) = &event {
-                if let Ok(file) = File::open(&absolute_paths[0]) {
-                    let mut reader = BufReader::new(file);
-                    let mut buffer = Vec::new();
-
-                    // Read file into vector.
-                    if reader.read_to_end(&mut buffer).is_ok() {
+            match &event {
+                AssetSourceEvent::AddedAsset(path) | AssetSourceEvent::ModifiedAsset(path) => {
+                    if let Some(buffer) = read_file(&absolute_paths[0]) {
                         self.dir.insert_asset(path, buffer);
                     }
                 }
+                // Meta files are stored next to the embedded asset they describe, so that
+                // changing import or process settings reloads the asset as well.
+                AssetSourceEvent::AddedMeta(path) | AssetSourceEvent::ModifiedMeta(path) => {
+                    if let Some(buffer) = read_file(&absolute_paths[0]) {
+                        self.dir.insert_meta(path, buffer);
+                    }
+                }
+                _ => {}
             }
             self.last_event = Some(event.clone());
             self.sender.send(event).unwrap();
         }
     }
 }
+
+fn read_file(path: &Path) -> Option<Vec<u8>> {
+    let file = File::open(path).ok()?;
+    let mut reader = BufReader::new(file);
+    let mut buffer = Vec::new();
+
+    // Read file into vector.
+    reader.read_to_end(&mut buffer).ok()?;
+    Some(buffer)
+}
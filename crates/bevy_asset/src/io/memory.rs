@@ -116,6 +116,26 @@ impl Dir {
             .and_then(|f| dir.0.read().metadata.get(f.to_str().unwrap()).cloned())
     }
 
+    pub fn remove_asset(&self, path: &Path) -> Option<Data> {
+        let mut dir = self.clone();
+        if let Some(parent) = path.parent() {
+            dir = dir.get_dir(parent)?;
+        }
+
+        path.file_name()
+            .and_then(|f| dir.0.write().assets.remove(f.to_str().unwrap()))
+    }
+
+    pub fn remove_metadata(&self, path: &Path) -> Option<Data> {
+        let mut dir = self.clone();
+        if let Some(parent) = path.parent() {
+            dir = dir.get_dir(parent)?;
+        }
+
+        path.file_name()
+            .and_then(|f| dir.0.write().metadata.remove(f.to_str().unwrap()))
+    }
+
     pub fn path(&self) -> PathBuf {
         self.0.read().path.to_owned()
     }
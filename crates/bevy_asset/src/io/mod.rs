@@ -12,6 +12,7 @@ pub mod embedded;
 pub mod file;
 pub mod gated;
 pub mod memory;
+pub mod overlay;
 pub mod processor_gated;
 #[cfg(target_arch = "wasm32")]
 pub mod wasm;
@@ -23,7 +24,7 @@ pub use source::*;
 
 use bevy_utils::{BoxedFuture, ConditionalSendFuture};
 use futures_io::{AsyncRead, AsyncSeek, AsyncWrite};
-use futures_lite::{ready, Stream};
+use futures_lite::{ready, Stream, StreamExt};
 use std::io::SeekFrom;
 use std::task::Context;
 use std::{
@@ -117,6 +118,12 @@ pub trait AssetReader: Send + Sync + 'static {
             Ok(meta_bytes)
         }
     }
+    /// Returns the name of the layer the asset at `path` was last read from, for readers that
+    /// combine several layers like [`OverlayAssetReader`](overlay::OverlayAssetReader). Returns
+    /// `None` by default.
+    fn layer(&self, _path: &Path) -> Option<Arc<str>> {
+        None
+    }
 }
 
 /// Equivalent to an [`AssetReader`] but using boxed futures, necessary eg. when using a `dyn AssetReader`,
@@ -143,6 +150,9 @@ pub trait ErasedAssetReader: Send + Sync + 'static {
         &'a self,
         path: &'a Path,
     ) -> BoxedFuture<Result<Vec<u8>, AssetReaderError>>;
+    /// Returns the name of the layer the asset at `path` was last read from, for readers that
+    /// combine several layers like [`OverlayAssetReader`](overlay::OverlayAssetReader).
+    fn layer(&self, path: &Path) -> Option<Arc<str>>;
 }
 
 impl<T: AssetReader> ErasedAssetReader for T {
@@ -173,6 +183,25 @@ impl<T: AssetReader> ErasedAssetReader for T {
     ) -> BoxedFuture<Result<Vec<u8>, AssetReaderError>> {
         Box::pin(Self::read_meta_bytes(self, path))
     }
+    fn layer(&self, path: &Path) -> Option<Arc<str>> {
+        Self::layer(self, path)
+    }
+}
+
+/// Returns the paths of the files in the folder at `path`, in alphabetical order. Sub-folders are skipped.
+pub(crate) async fn read_folder_files(
+    reader: &dyn ErasedAssetReader,
+    path: &Path,
+) -> Result<Vec<PathBuf>, AssetReaderError> {
+    let mut child_paths = Vec::new();
+    let mut path_stream = reader.read_directory(path).await?;
+    while let Some(child_path) = path_stream.next().await {
+        if !reader.is_directory(&child_path).await? {
+            child_paths.push(child_path);
+        }
+    }
+    child_paths.sort();
+    Ok(child_paths)
 }
 
 pub type Writer = dyn AsyncWrite + Unpin + Send + Sync;
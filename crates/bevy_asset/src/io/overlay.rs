@@ -0,0 +1,331 @@
+use crate::io::{
+    AssetReader, AssetReaderError, AssetSource, AssetSourceBuilder, AssetSourceEvent, AssetWatcher,
+    ErasedAssetReader, PathStream, Reader,
+};
+use bevy_utils::{tracing::warn, HashMap, HashSet};
+use futures_lite::StreamExt;
+use parking_lot::RwLock;
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// A named [`AssetReader`] layer of an [`OverlayAssetReader`].
+struct OverlayLayer {
+    name: Arc<str>,
+    reader: Box<dyn ErasedAssetReader>,
+}
+
+/// An [`AssetReader`] that layers several readers on top of each other, such as a base game, its DLCs and
+/// user mods in load order.
+///
+/// Layers added later take precedence over layers added earlier:
+/// * Each path is read from the topmost layer that contains it.
+/// * Asset metadata is always read from the same layer as the asset itself, so that `.meta` files from one
+///   layer never apply to an asset provided by another layer. If that layer has no `.meta` file for the asset,
+///   the asset is loaded with default settings.
+/// * Directory listings are the union of the listings of every layer that contains the directory, which makes
+///   [`AssetServer::load_folder`](crate::AssetServer::load_folder) pick up files from all layers.
+/// * A path is a directory if it is a directory in any layer.
+///
+/// The layer each asset was last read from is returned by
+/// [`AssetServer::get_path_layer`](crate::AssetServer::get_path_layer), and recorded in the
+/// [`OverlayResolutions`] returned by [`OverlayAssetReader::resolutions`].
+#[derive(Default)]
+pub struct OverlayAssetReader {
+    layers: Vec<OverlayLayer>,
+    resolutions: OverlayResolutions,
+}
+
+impl OverlayAssetReader {
+    /// Creates a new [`OverlayAssetReader`] without any layers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a new topmost layer with the given `name`, which takes precedence over every existing layer.
+    pub fn with_layer(
+        mut self,
+        name: impl Into<Arc<str>>,
+        reader: Box<dyn ErasedAssetReader>,
+    ) -> Self {
+        self.layers.push(OverlayLayer {
+            name: name.into(),
+            reader,
+        });
+        self
+    }
+
+    /// Returns the [`OverlayResolutions`] this reader records the layer of each read asset into.
+    pub fn resolutions(&self) -> OverlayResolutions {
+        self.resolutions.clone()
+    }
+
+    /// Returns the topmost layer that contains the asset at `path`.
+    ///
+    /// The asset server reads an asset before its meta file, so the layer is usually already
+    /// resolved and only read again if the asset hasn't been read through this reader yet.
+    async fn find_layer<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<&'a OverlayLayer, AssetReaderError> {
+        if let Some(name) = self.resolutions.layer(path) {
+            if let Some(layer) = self.layers.iter().rev().find(|layer| layer.name == name) {
+                return Ok(layer);
+            }
+        }
+        self.read_layer(path).await.map(|(layer, _)| layer)
+    }
+
+    /// Reads the asset at `path` from the topmost layer that contains it, recording that layer
+    /// in the [`OverlayResolutions`].
+    async fn read_layer<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<(&'a OverlayLayer, Box<Reader<'a>>), AssetReaderError> {
+        for layer in self.layers.iter().rev() {
+            match layer.reader.read(path).await {
+                Ok(reader) => {
+                    self.resolutions.insert(path, layer.name.clone());
+                    return Ok((layer, reader));
+                }
+                Err(AssetReaderError::NotFound(_)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        Err(AssetReaderError::NotFound(path.to_owned()))
+    }
+}
+
+impl AssetReader for OverlayAssetReader {
+    async fn read<'a>(&'a self, path: &'a Path) -> Result<Box<Reader<'a>>, AssetReaderError> {
+        self.read_layer(path).await.map(|(_, reader)| reader)
+    }
+
+    async fn read_meta<'a>(&'a self, path: &'a Path) -> Result<Box<Reader<'a>>, AssetReaderError> {
+        let layer = self.find_layer(path).await?;
+        layer.reader.read_meta(path).await
+    }
+
+    async fn read_directory<'a>(
+        &'a self,
+        path: &'a Path,
+    ) -> Result<Box<PathStream>, AssetReaderError> {
+        let mut found = false;
+        let mut seen = HashSet::new();
+        let mut paths = Vec::new();
+        for layer in self.layers.iter().rev() {
+            match layer.reader.read_directory(path).await {
+                Ok(mut stream) => {
+                    found = true;
+                    while let Some(path) = stream.next().await {
+                        if seen.insert(path.clone()) {
+                            paths.push(path);
+                        }
+                    }
+                }
+                Err(AssetReaderError::NotFound(_)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        if !found {
+            return Err(AssetReaderError::NotFound(path.to_owned()));
+        }
+        let stream: Box<PathStream> = Box::new(futures_lite::stream::iter(paths));
+        Ok(stream)
+    }
+
+    async fn is_directory<'a>(&'a self, path: &'a Path) -> Result<bool, AssetReaderError> {
+        let mut found = false;
+        for layer in self.layers.iter().rev() {
+            match layer.reader.is_directory(path).await {
+                Ok(true) => return Ok(true),
+                Ok(false) => found = true,
+                Err(AssetReaderError::NotFound(_)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+        if found {
+            Ok(false)
+        } else {
+            Err(AssetReaderError::NotFound(path.to_owned()))
+        }
+    }
+
+    fn layer(&self, path: &Path) -> Option<Arc<str>> {
+        self.resolutions.layer(path)
+    }
+}
+
+/// A clone-able (internally Arc-ed) record of the layer each asset of an [`OverlayAssetReader`] was read from.
+///
+/// The layer of a loaded asset is most easily found with
+/// [`AssetServer::get_path_layer`](crate::AssetServer::get_path_layer), while this record also covers
+/// paths that were read directly through the reader.
+#[derive(Clone, Default, Debug)]
+pub struct OverlayResolutions(Arc<RwLock<HashMap<PathBuf, Arc<str>>>>);
+
+impl OverlayResolutions {
+    /// Returns the name of the layer the asset at `path` was last read from, if it has been read.
+    pub fn layer(&self, path: &Path) -> Option<Arc<str>> {
+        self.0.read().get(path).cloned()
+    }
+
+    fn insert(&self, path: &Path, layer: Arc<str>) {
+        self.0.write().insert(path.to_owned(), layer);
+    }
+}
+
+/// An [`AssetWatcher`] that keeps the watchers of every layer of an overlay source alive.
+pub struct OverlayAssetWatcher {
+    _watchers: Vec<Box<dyn AssetWatcher>>,
+}
+
+impl AssetWatcher for OverlayAssetWatcher {}
+
+/// Builds an [`AssetSourceBuilder`] for an overlay source from the [`AssetSourceBuilder`]s of its layers.
+///
+/// The reader of the built source is an [`OverlayAssetReader`] over the readers of the layers. Its watcher
+/// watches every layer that has a watcher, so that a change in any layer is hot-reloaded. The built source
+/// is read-only and unprocessed: the writers and processed readers of the layers are not used.
+///
+/// ```no_run
+/// # use bevy_asset::{AssetApp, io::{AssetSourceBuilder, AssetSourceId, overlay::OverlayAssetSourceBuilder}};
+/// # let mut app = bevy_app::App::new();
+/// let overlay = OverlayAssetSourceBuilder::default()
+///     .with_layer("base", AssetSourceBuilder::platform_default("assets", None))
+///     .with_layer("dlc", AssetSourceBuilder::platform_default("dlc", None))
+///     .with_layer("my_mod", AssetSourceBuilder::platform_default("mods/my_mod", None));
+/// let resolutions = overlay.resolutions();
+/// app.register_asset_source(AssetSourceId::Default, overlay.build());
+/// ```
+#[derive(Default)]
+pub struct OverlayAssetSourceBuilder {
+    layers: Vec<(Arc<str>, AssetSourceBuilder)>,
+    resolutions: OverlayResolutions,
+}
+
+impl OverlayAssetSourceBuilder {
+    /// Adds a new topmost layer with the given `name`, which takes precedence over every existing layer.
+    pub fn with_layer(mut self, name: impl Into<Arc<str>>, layer: AssetSourceBuilder) -> Self {
+        self.layers.push((name.into(), layer));
+        self
+    }
+
+    /// Returns the [`OverlayResolutions`] shared by every [`OverlayAssetReader`] built for this source.
+    pub fn resolutions(&self) -> OverlayResolutions {
+        self.resolutions.clone()
+    }
+
+    /// Builds the [`AssetSourceBuilder`] of the overlay source.
+    pub fn build(self) -> AssetSourceBuilder {
+        let mut readers = Vec::new();
+        let mut watchers = Vec::new();
+        for (name, layer) in self.layers {
+            match layer.reader {
+                Some(reader) => readers.push((name, reader)),
+                None => warn!("Overlay layer {name} has no reader and will be ignored"),
+            }
+            watchers.extend(layer.watcher);
+        }
+
+        let resolutions = self.resolutions;
+        AssetSourceBuilder::default()
+            .with_reader(move || {
+                Box::new(OverlayAssetReader {
+                    layers: readers
+                        .iter_mut()
+                        .map(|(name, reader)| OverlayLayer {
+                            name: name.clone(),
+                            reader: reader(),
+                        })
+                        .collect(),
+                    resolutions: resolutions.clone(),
+                })
+            })
+            .with_watcher(move |sender: crossbeam_channel::Sender<AssetSourceEvent>| {
+                let watchers = watchers
+                    .iter_mut()
+                    .filter_map(|watcher| watcher(sender.clone()))
+                    .collect::<Vec<_>>();
+                if watchers.is_empty() {
+                    return None;
+                }
+                let watcher: Box<dyn AssetWatcher> = Box::new(OverlayAssetWatcher {
+                    _watchers: watchers,
+                });
+                Some(watcher)
+            })
+            .with_watch_warning(AssetSource::get_default_watch_warning())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::io::memory::{Dir, MemoryAssetReader};
+    use futures_lite::future::block_on;
+
+    fn read_to_string(reader: &OverlayAssetReader, path: &str) -> Result<String, AssetReaderError> {
+        use futures_lite::AsyncReadExt;
+        block_on(async {
+            let mut reader = AssetReader::read(reader, Path::new(path)).await?;
+            let mut text = String::new();
+            reader.read_to_string(&mut text).await?;
+            Ok(text)
+        })
+    }
+
+    #[test]
+    fn topmost_layer_wins() {
+        let base = Dir::default();
+        base.insert_asset_text(Path::new("a.txt"), "base a");
+        base.insert_meta_text(Path::new("a.txt"), "base a meta");
+        base.insert_asset_text(Path::new("b.txt"), "base b");
+        base.insert_asset_text(Path::new("dir/c.txt"), "base c");
+
+        let mod_dir = Dir::default();
+        mod_dir.insert_asset_text(Path::new("a.txt"), "mod a");
+        mod_dir.insert_asset_text(Path::new("dir/d.txt"), "mod d");
+
+        let reader = OverlayAssetReader::new()
+            .with_layer("base", Box::new(MemoryAssetReader { root: base }))
+            .with_layer("mod", Box::new(MemoryAssetReader { root: mod_dir }));
+        let resolutions = reader.resolutions();
+
+        assert_eq!(read_to_string(&reader, "a.txt").unwrap(), "mod a");
+        assert_eq!(read_to_string(&reader, "b.txt").unwrap(), "base b");
+        assert!(matches!(
+            read_to_string(&reader, "missing.txt"),
+            Err(AssetReaderError::NotFound(_))
+        ));
+        assert_eq!(
+            resolutions.layer(Path::new("a.txt")).as_deref(),
+            Some("mod")
+        );
+        assert_eq!(
+            resolutions.layer(Path::new("b.txt")).as_deref(),
+            Some("base")
+        );
+
+        // The base layer's meta file must not apply to the mod's asset.
+        assert!(matches!(
+            block_on(AssetReader::read_meta_bytes(&reader, Path::new("a.txt"))),
+            Err(AssetReaderError::NotFound(_))
+        ));
+
+        assert!(block_on(AssetReader::is_directory(&reader, Path::new("dir"))).unwrap());
+        let mut entries = block_on(async {
+            AssetReader::read_directory(&reader, Path::new("dir"))
+                .await
+                .unwrap()
+                .collect::<Vec<_>>()
+                .await
+        });
+        entries.sort();
+        assert_eq!(
+            entries,
+            vec![PathBuf::from("dir/c.txt"), PathBuf::from("dir/d.txt")]
+        );
+    }
+}
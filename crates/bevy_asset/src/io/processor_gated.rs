@@ -115,6 +115,10 @@ impl AssetReader for ProcessorGatedReader {
         let result = self.reader.is_directory(path).await?;
         Ok(result)
     }
+
+    fn layer(&self, path: &Path) -> Option<Arc<str>> {
+        self.reader.layer(path)
+    }
 }
 
 /// An [`AsyncRead`] impl that will hold its asset's transaction lock until [`TransactionLockedReader`] is dropped.
@@ -29,6 +29,7 @@ mod id;
 mod loader;
 mod path;
 mod reflect;
+mod reload;
 mod server;
 
 pub use assets::*;
@@ -42,6 +43,7 @@ pub use id::*;
 pub use loader::*;
 pub use path::*;
 pub use reflect::*;
+pub use reload::*;
 pub use server::*;
 
 /// Rusty Object Notation, a crate used to serialize and deserialize bevy assets.
@@ -310,6 +312,11 @@ pub trait AssetApp {
     /// Preregisters a loader for the given extensions, that will block asset loads until a real loader
     /// is registered.
     fn preregister_asset_loader<L: AssetLoader>(&mut self, extensions: &[&str]) -> &mut Self;
+    /// Makes reloads of the [`Asset`] `A` merge the new version into the existing one with
+    /// [`ReloadAsset::reload`], instead of replacing it.
+    ///
+    /// The [`Asset`] must already be initialized with [`AssetApp::init_asset`].
+    fn register_asset_reload_hook<A: ReloadAsset>(&mut self) -> &mut Self;
 }
 
 impl AssetApp for App {
@@ -413,6 +420,13 @@ impl AssetApp for App {
             .preregister_loader::<L>(extensions);
         self
     }
+
+    fn register_asset_reload_hook<A: ReloadAsset>(&mut self) -> &mut Self {
+        self.world_mut()
+            .resource_mut::<Assets<A>>()
+            .set_reload_hook(|asset, reloaded, context| asset.reload(reloaded, context));
+        self
+    }
 }
 
 /// A system set that holds all "track asset" operations.
@@ -434,12 +448,13 @@ mod tests {
         io::{
             gated::{GateOpener, GatedReader},
             memory::{Dir, MemoryAssetReader},
+            overlay::OverlayAssetSourceBuilder,
             AssetReader, AssetReaderError, AssetSource, AssetSourceId, Reader,
         },
         loader::{AssetLoader, LoadContext},
         Asset, AssetApp, AssetEvent, AssetId, AssetLoadError, AssetLoadFailedEvent, AssetPath,
-        AssetPlugin, AssetServer, Assets, DependencyLoadState, LoadState,
-        RecursiveDependencyLoadState,
+        AssetPlugin, AssetReloadContext, AssetServer, Assets, DependencyLoadState, LoadState,
+        RecursiveDependencyLoadState, ReloadAsset,
     };
     use bevy_app::{App, Update};
     use bevy_core::TaskPoolPlugin;
@@ -1479,6 +1494,160 @@ mod tests {
         app.world_mut().run_schedule(Update);
     }
 
+    impl ReloadAsset for CoolText {
+        fn reload(&mut self, reloaded: Self, context: &AssetReloadContext<Self>) {
+            assert_eq!(context.path(), Some(&AssetPath::from("a.cool.ron")));
+            // `embedded` stands in for runtime state that should survive reloads.
+            self.text = reloaded.text;
+            self.dependencies = reloaded.dependencies;
+            self.sub_texts = reloaded.sub_texts;
+        }
+    }
+
+    #[test]
+    fn reload_hook_merges_reloaded_asset() {
+        let dir = Dir::default();
+        let a_path = "a.cool.ron";
+        dir.insert_asset_text(Path::new(a_path), SIMPLE_TEXT);
+
+        let (mut app, gate_opener) = test_app(dir.clone());
+        app.init_asset::<CoolText>()
+            .init_asset::<SubText>()
+            .init_resource::<StoredEvents>()
+            .register_asset_loader(CoolTextLoader)
+            .register_asset_reload_hook::<CoolText>()
+            .add_systems(Update, store_asset_events);
+
+        gate_opener.open(a_path);
+        let handle: Handle<CoolText> = app.world().resource::<AssetServer>().load(a_path);
+        let id = handle.id();
+        run_app_until(&mut app, |world| {
+            let text = world
+                .resource_mut::<Assets<CoolText>>()
+                .into_inner()
+                .get_mut(id)?;
+            text.embedded = "runtime state".to_string();
+            Some(())
+        });
+        app.update();
+        app.world_mut().resource_mut::<StoredEvents>().0.clear();
+
+        dir.insert_asset_text(
+            Path::new(a_path),
+            &SIMPLE_TEXT.replace("\"dep\"", "\"reloaded\""),
+        );
+        gate_opener.open(a_path);
+        app.world().resource::<AssetServer>().reload(a_path);
+        run_app_until(&mut app, |world| {
+            let text = get::<CoolText>(world, id)?;
+            (text.text == "reloaded").then_some(())
+        });
+
+        let text = get::<CoolText>(app.world(), id).unwrap();
+        assert_eq!(text.embedded, "runtime state");
+        app.update();
+        let events = std::mem::take(&mut app.world_mut().resource_mut::<StoredEvents>().0);
+        assert!(events.contains(&AssetEvent::Modified { id }));
+    }
+
+    #[test]
+    fn get_path_layer_of_overlay_asset() {
+        let base = Dir::default();
+        base.insert_asset_text(Path::new("a.cool.ron"), SIMPLE_TEXT);
+        base.insert_asset_text(Path::new("b.cool.ron"), SIMPLE_TEXT);
+        let mod_dir = Dir::default();
+        mod_dir.insert_asset_text(Path::new("a.cool.ron"), SIMPLE_TEXT);
+
+        let overlay = OverlayAssetSourceBuilder::default()
+            .with_layer(
+                "base",
+                AssetSource::build()
+                    .with_reader(move || Box::new(MemoryAssetReader { root: base.clone() })),
+            )
+            .with_layer(
+                "mod",
+                AssetSource::build().with_reader(move || {
+                    Box::new(MemoryAssetReader {
+                        root: mod_dir.clone(),
+                    })
+                }),
+            );
+        let mut app = App::new();
+        app.register_asset_source(AssetSourceId::Default, overlay.build())
+            .add_plugins((
+                TaskPoolPlugin::default(),
+                LogPlugin::default(),
+                AssetPlugin::default(),
+            ))
+            .init_asset::<CoolText>()
+            .init_asset::<SubText>()
+            .register_asset_loader(CoolTextLoader);
+
+        let asset_server = app.world().resource::<AssetServer>().clone();
+        let a: Handle<CoolText> = asset_server.load("a.cool.ron");
+        let b: Handle<CoolText> = asset_server.load("b.cool.ron");
+        run_app_until(&mut app, |world| {
+            get::<CoolText>(world, a.id())?;
+            get::<CoolText>(world, b.id())?;
+            Some(())
+        });
+
+        assert_eq!(asset_server.get_path_layer(&a).as_deref(), Some("mod"));
+        assert_eq!(asset_server.get_path_layer(&b).as_deref(), Some("base"));
+    }
+
+    #[test]
+    fn read_folder_registers_folder_hash() {
+        let dir = Dir::default();
+        dir.insert_asset_text(Path::new("items/b.cool.ron"), SIMPLE_TEXT);
+        dir.insert_asset_text(Path::new("items/a.cool.ron"), SIMPLE_TEXT);
+        dir.insert_asset_text(Path::new("items/nested/c.cool.ron"), SIMPLE_TEXT);
+
+        let mut app = App::new();
+        let root = dir.clone();
+        app.register_asset_source(
+            AssetSourceId::Default,
+            AssetSource::build()
+                .with_reader(move || Box::new(MemoryAssetReader { root: root.clone() })),
+        )
+        .add_plugins((TaskPoolPlugin::default(), AssetPlugin::default()));
+        let asset_server = app.world().resource::<AssetServer>().clone();
+
+        let read_folder = |populate_hashes| {
+            let mut load_context = LoadContext::new(
+                &asset_server,
+                "root.cool.ron".into(),
+                false,
+                populate_hashes,
+            );
+            let paths = bevy_tasks::block_on(load_context.read_folder("items")).unwrap();
+            let loaded = load_context.finish(TestAsset, None);
+            (paths, loaded.loader_dependencies)
+        };
+
+        let (paths, dependencies) = read_folder(false);
+        assert_eq!(
+            paths,
+            [
+                AssetPath::from("items/a.cool.ron"),
+                AssetPath::from("items/b.cool.ron")
+            ]
+        );
+        assert!(dependencies.is_empty());
+
+        let (_, dependencies) = read_folder(true);
+        let hash = dependencies[&AssetPath::from("items")];
+
+        // Changing a file doesn't change the hash of the folder, adding one does
+        dir.insert_asset_text(Path::new("items/a.cool.ron"), "changed");
+        let (_, dependencies) = read_folder(true);
+        assert_eq!(dependencies[&AssetPath::from("items")], hash);
+        dir.insert_asset_text(Path::new("items/d.cool.ron"), SIMPLE_TEXT);
+        let (paths, dependencies) = read_folder(true);
+        assert_eq!(paths.len(), 3);
+        assert_ne!(dependencies[&AssetPath::from("items")], hash);
+    }
+
     // validate the Asset derive macro for various asset types
     #[derive(Asset, TypePath)]
     pub struct TestAsset;
@@ -1,8 +1,11 @@
 use crate::{
-    io::{AssetReaderError, MissingAssetSourceError, MissingProcessedAssetReaderError, Reader},
+    io::{
+        read_folder_files, AssetReaderError, MissingAssetSourceError,
+        MissingProcessedAssetReaderError, Reader,
+    },
     meta::{
-        loader_settings_meta_transform, AssetHash, AssetMeta, AssetMetaDyn, ProcessedInfoMinimal,
-        Settings,
+        get_folder_hash, loader_settings_meta_transform, AssetHash, AssetMeta, AssetMetaDyn,
+        ProcessedInfoMinimal, Settings,
     },
     path::AssetPath,
     Asset, AssetLoadError, AssetServer, AssetServerMode, Assets, Handle, LoadedUntypedAsset,
@@ -148,6 +151,8 @@ pub struct LoadedAsset<A: Asset> {
     pub(crate) loader_dependencies: HashMap<AssetPath<'static>, AssetHash>,
     pub(crate) labeled_assets: HashMap<CowArc<'static, str>, LabeledAsset>,
     pub(crate) meta: Option<Box<dyn AssetMetaDyn>>,
+    /// The path the asset was loaded from, set by [`LoadContext::finish`].
+    pub(crate) path: Option<AssetPath<'static>>,
 }
 
 impl<A: Asset> LoadedAsset<A> {
@@ -163,6 +168,7 @@ impl<A: Asset> LoadedAsset<A> {
             loader_dependencies: HashMap::default(),
             labeled_assets: HashMap::default(),
             meta,
+            path: None,
         }
     }
 }
@@ -180,6 +186,7 @@ pub struct ErasedLoadedAsset {
     pub(crate) loader_dependencies: HashMap<AssetPath<'static>, AssetHash>,
     pub(crate) labeled_assets: HashMap<CowArc<'static, str>, LabeledAsset>,
     pub(crate) meta: Option<Box<dyn AssetMetaDyn>>,
+    pub(crate) path: Option<AssetPath<'static>>,
 }
 
 impl<A: Asset> From<LoadedAsset<A>> for ErasedLoadedAsset {
@@ -190,6 +197,7 @@ impl<A: Asset> From<LoadedAsset<A>> for ErasedLoadedAsset {
             loader_dependencies: asset.loader_dependencies,
             labeled_assets: asset.labeled_assets,
             meta: asset.meta,
+            path: asset.path,
         }
     }
 }
@@ -232,15 +240,28 @@ impl ErasedLoadedAsset {
 
 /// A type erased container for an [`Asset`] value that is capable of inserting the [`Asset`] into a [`World`]'s [`Assets`] collection.
 pub trait AssetContainer: Downcast + Any + Send + Sync + 'static {
-    fn insert(self: Box<Self>, id: UntypedAssetId, world: &mut World);
+    /// Inserts the asset for `id`, which was loaded from `path` if it has one.
+    fn insert(
+        self: Box<Self>,
+        id: UntypedAssetId,
+        path: Option<&AssetPath<'static>>,
+        world: &mut World,
+    );
     fn asset_type_name(&self) -> &'static str;
 }
 
 impl_downcast!(AssetContainer);
 
 impl<A: Asset> AssetContainer for A {
-    fn insert(self: Box<Self>, id: UntypedAssetId, world: &mut World) {
-        world.resource_mut::<Assets<A>>().insert(id.typed(), *self);
+    fn insert(
+        self: Box<Self>,
+        id: UntypedAssetId,
+        path: Option<&AssetPath<'static>>,
+        world: &mut World,
+    ) {
+        world
+            .resource_mut::<Assets<A>>()
+            .insert_loaded(id.typed(), *self, path);
     }
 
     fn asset_type_name(&self) -> &'static str {
@@ -379,8 +400,9 @@ impl<'a> LoadContext<'a> {
         loaded_asset: LoadedAsset<A>,
     ) -> Handle<A> {
         let label = label.into();
-        let loaded_asset: ErasedLoadedAsset = loaded_asset.into();
+        let mut loaded_asset: ErasedLoadedAsset = loaded_asset.into();
         let labeled_path = self.asset_path.clone().with_label(label.clone());
+        loaded_asset.path = Some(labeled_path.clone());
         let handle = self
             .asset_server
             .get_or_create_path_handle(labeled_path, None);
@@ -411,6 +433,7 @@ impl<'a> LoadContext<'a> {
             loader_dependencies: self.loader_dependencies,
             labeled_assets: self.labeled_assets,
             meta,
+            path: Some(self.asset_path),
         }
     }
 
@@ -461,6 +484,45 @@ impl<'a> LoadContext<'a> {
         Ok(bytes)
     }
 
+    /// Returns the paths of the files in the folder at the given path, in alphabetical order.
+    /// Sub-folders are skipped.
+    ///
+    /// When the current asset is being processed, the folder is registered as a "process dependency",
+    /// like with [`ProcessContext::add_process_dependency`], so adding or removing files reprocesses
+    /// the current asset. Otherwise adding or removing files doesn't trigger a reload, and only
+    /// changes to the files that are then loaded with [`LoadContext::load_direct`] or read with
+    /// [`LoadContext::read_asset_bytes`] do.
+    ///
+    /// [`ProcessContext::add_process_dependency`]: crate::processor::ProcessContext::add_process_dependency
+    pub async fn read_folder<'b>(
+        &mut self,
+        path: impl Into<AssetPath<'b>>,
+    ) -> Result<Vec<AssetPath<'static>>, ReadAssetBytesError> {
+        let path = path.into();
+        let source = self.asset_server.get_source(path.source())?;
+        // While processing, the processed folder can't be listed until every asset has been processed,
+        // including the current one, so the source folder is listed instead.
+        let asset_reader = match self.asset_server.mode() {
+            AssetServerMode::Processed { .. } if !self.populate_hashes => {
+                source.processed_reader()?
+            }
+            _ => source.reader(),
+        };
+        let child_paths = read_folder_files(asset_reader, path.path()).await?;
+        if self.populate_hashes {
+            self.loader_dependencies
+                .insert(path.clone_owned(), get_folder_hash(&child_paths));
+        }
+        Ok(child_paths
+            .iter()
+            .map(|child_path| {
+                AssetPath::from_path(child_path)
+                    .with_source(path.source().clone_owned())
+                    .into_owned()
+            })
+            .collect())
+    }
+
     /// Retrieves a handle for the asset at the given path and adds that path as a dependency of the asset.
     /// If the current context is a normal [`AssetServer::load`], an actual asset load will be kicked off immediately, which ensures the load happens
     /// as soon as possible.
@@ -4,6 +4,7 @@ use bevy_utils::tracing::error;
 use downcast_rs::{impl_downcast, Downcast};
 use ron::ser::PrettyConfig;
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
 
 pub const META_FORMAT_VERSION: &str = "1.0";
 pub type MetaTransform = Box<dyn Fn(&mut dyn AssetMetaDyn) + Send + Sync>;
@@ -75,6 +76,12 @@ pub struct ProcessedInfo {
     pub full_hash: AssetHash,
     /// Information about the "process dependencies" used to process this asset.
     pub process_dependencies: Vec<ProcessDependencyInfo>,
+    /// Paths of the additional processed assets that were written while processing this asset.
+    /// See [`ProcessContext::write_additional_output`].
+    ///
+    /// [`ProcessContext::write_additional_output`]: crate::processor::ProcessContext::write_additional_output
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub additional_outputs: Vec<AssetPath<'static>>,
 }
 
 /// Information about a dependency used to process an asset. This is used to determine whether an asset's "process dependency"
@@ -235,6 +242,20 @@ pub(crate) fn get_asset_hash(meta_bytes: &[u8], asset_bytes: &[u8]) -> AssetHash
     *hasher.finalize().as_bytes()
 }
 
+/// Returns the hash of a folder used as a "process dependency", which changes whenever a file is added to or removed
+/// from the folder. Changes to the files themselves are tracked by depending on them directly.
+///
+/// NOTE: changing the hashing logic here is a _breaking change_ that requires a [`META_FORMAT_VERSION`] bump.
+pub(crate) fn get_folder_hash(file_paths: &[PathBuf]) -> AssetHash {
+    let mut hasher = blake3::Hasher::new();
+    for path in file_paths {
+        hasher.update(path.to_string_lossy().as_bytes());
+        // Separate the paths so that different listings can't produce the same bytes
+        hasher.update(&[0]);
+    }
+    *hasher.finalize().as_bytes()
+}
+
 /// NOTE: changing the hashing logic here is a _breaking change_ that requires a [`META_FORMAT_VERSION`] bump.
 pub(crate) fn get_full_asset_hash(
     asset_hash: AssetHash,
@@ -0,0 +1,289 @@
+use crate::{io::AssetSourceId, processor::AssetProcessor};
+use bevy_app::{App, AppExit, Plugin, PluginsState};
+use bevy_utils::tracing::error;
+use thiserror::Error;
+
+/// Replaces the [`App`] runner with a headless "asset cooker", which processes assets to completion using the
+/// [`AssetProcessor`] and then exits, without ever running the app's schedules. This makes it possible to process
+/// assets as part of a build pipeline, on machines without a GPU or a window.
+///
+/// When the cooker finishes, it prints a [`ProcessReport`] of the processed, skipped, out of date and failed assets
+/// to stdout, serialized as RON. The app exits with:
+/// * [`AppExit::Success`] if every asset was processed (or was already up to date).
+/// * An exit code of `1` if any asset failed to process (or the cooker could not run at all).
+/// * An exit code of `2` if [`AssetCookPlugin::check`] is enabled and any processed asset is out of date.
+///
+/// This requires the `asset_processor` and `multi_threaded` cargo features, and the [`AssetPlugin`] must use
+/// [`AssetMode::Processed`]. All loaders and processors must be registered before the app is run.
+///
+/// ```no_run
+/// # use bevy_app::{App, AppExit};
+/// # use bevy_asset::{processor::AssetCookPlugin, AssetMode, AssetPlugin};
+/// # use bevy_core::TaskPoolPlugin;
+/// fn main() -> AppExit {
+///     let cook = AssetCookPlugin::from_args(std::env::args().skip(1)).unwrap();
+///     App::new()
+///         .add_plugins((
+///             TaskPoolPlugin::default(),
+///             AssetPlugin {
+///                 mode: AssetMode::Processed,
+///                 ..Default::default()
+///             },
+///             cook,
+///         ))
+///         // Register your asset loaders and processors here
+///         .run()
+/// }
+/// ```
+///
+/// [`ProcessReport`]: crate::processor::ProcessReport
+/// [`AssetPlugin`]: crate::AssetPlugin
+/// [`AssetMode::Processed`]: crate::AssetMode::Processed
+#[derive(Default, Clone, Debug)]
+pub struct AssetCookPlugin {
+    /// The processed asset sources to cook. If this is empty, every processed asset source is cooked.
+    pub sources: Vec<AssetSourceId<'static>>,
+    /// If enabled, the cooker only checks whether processed assets are up to date, without processing them.
+    /// See [`AssetProcessor::check_source_assets`].
+    pub check: bool,
+}
+
+impl AssetCookPlugin {
+    /// Creates an [`AssetCookPlugin`] from command-line arguments (not including the name of the binary):
+    /// * `--check`: enables [`AssetCookPlugin::check`].
+    /// * Any other argument is the name of an asset source to cook. `default` selects the default asset source.
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Result<Self, AssetCookArgsError> {
+        let mut plugin = AssetCookPlugin::default();
+        for arg in args {
+            match arg.as_str() {
+                "--check" => plugin.check = true,
+                "default" => plugin.sources.push(AssetSourceId::Default),
+                _ if arg.starts_with('-') => return Err(AssetCookArgsError::UnknownFlag(arg)),
+                _ => plugin.sources.push(AssetSourceId::Name(arg.into())),
+            }
+        }
+        Ok(plugin)
+    }
+}
+
+impl Plugin for AssetCookPlugin {
+    fn build(&self, app: &mut App) {
+        let plugin = self.clone();
+        app.set_runner(move |app| cook_runner(app, plugin));
+    }
+}
+
+/// An error that occurs when parsing the command-line arguments of an [`AssetCookPlugin`].
+#[derive(Error, Debug)]
+pub enum AssetCookArgsError {
+    #[error("Unknown asset cooker flag '{0}'. Supported flags: --check")]
+    UnknownFlag(String),
+}
+
+fn cook_runner(mut app: App, plugin: AssetCookPlugin) -> AppExit {
+    while app.plugins_state() == PluginsState::Adding {
+        #[cfg(not(target_arch = "wasm32"))]
+        bevy_tasks::tick_global_task_pools_on_main_thread();
+    }
+    app.finish();
+    app.cleanup();
+
+    let Some(processor) = app.world().get_resource::<AssetProcessor>().cloned() else {
+        error!("The asset cooker requires the `asset_processor` cargo feature and `AssetMode::Processed`.");
+        return AppExit::error();
+    };
+
+    let sources = if plugin.sources.is_empty() {
+        processor
+            .sources()
+            .iter_processed()
+            .map(|source| source.id())
+            .collect()
+    } else {
+        plugin.sources
+    };
+    for id in &sources {
+        match processor.get_source(id.clone()) {
+            Ok(source) if source.should_process() => {}
+            Ok(_) => {
+                error!("The asset source {id} is not configured to be processed.");
+                return AppExit::error();
+            }
+            Err(err) => {
+                error!("{err}");
+                return AppExit::error();
+            }
+        }
+    }
+
+    #[cfg(any(target_arch = "wasm32", not(feature = "multi_threaded")))]
+    {
+        let _ = (sources, processor);
+        error!("Cannot run the asset cooker in single threaded mode (or WASM) yet.");
+        AppExit::error()
+    }
+    #[cfg(all(not(target_arch = "wasm32"), feature = "multi_threaded"))]
+    {
+        if plugin.check {
+            processor.check_source_assets(&sources);
+        } else {
+            processor.process_source_assets(&sources);
+        }
+
+        let report = bevy_tasks::block_on(processor.report());
+        match ron::ser::to_string_pretty(&report, ron::ser::PrettyConfig::default()) {
+            Ok(report) => println!("{report}"),
+            Err(err) => error!("Failed to serialize the asset cooker report: {err}"),
+        }
+
+        if !report.failed.is_empty() {
+            AppExit::error()
+        } else if plugin.check && !report.out_of_date.is_empty() {
+            AppExit::from_code(2)
+        } else {
+            AppExit::Success
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_args() {
+        let args = ["--check", "default", "remote"].map(String::from);
+        let plugin = AssetCookPlugin::from_args(args).unwrap();
+        assert!(plugin.check);
+        assert_eq!(
+            plugin.sources,
+            vec![AssetSourceId::Default, AssetSourceId::Name("remote".into())]
+        );
+
+        let plugin = AssetCookPlugin::from_args([]).unwrap();
+        assert!(!plugin.check);
+        assert!(plugin.sources.is_empty());
+
+        assert!(matches!(
+            AssetCookPlugin::from_args(["--watch".to_string()]),
+            Err(AssetCookArgsError::UnknownFlag(flag)) if flag == "--watch"
+        ));
+    }
+
+    /// Runs the cooker end to end, on assets stored in memory.
+    #[cfg(all(not(target_arch = "wasm32"), feature = "multi_threaded"))]
+    mod runner {
+        use super::*;
+        use crate::{
+            io::{memory::Dir, AssetReader, AssetWriterError, Writer},
+            meta::AssetMeta,
+            processor::{
+                tests::{create_processor, TextLoader},
+                Process, ProcessContext, ProcessError, ProcessReport,
+            },
+            AssetPath,
+        };
+        use bevy_tasks::{IoTaskPool, TaskPool};
+        use futures_lite::{future::block_on, AsyncReadExt, AsyncWriteExt};
+        use std::path::Path;
+
+        /// Converts text assets to uppercase.
+        struct UppercaseText;
+
+        impl Process for UppercaseText {
+            type Settings = ();
+            type OutputLoader = TextLoader;
+
+            async fn process<'a>(
+                &'a self,
+                context: &'a mut ProcessContext<'_>,
+                _meta: AssetMeta<(), Self>,
+                writer: &'a mut Writer,
+            ) -> Result<(), ProcessError> {
+                let text = String::from_utf8_lossy(context.asset_bytes()).to_uppercase();
+                writer.write_all(text.as_bytes()).await.map_err(|err| {
+                    ProcessError::AssetWriterError {
+                        path: context.path().clone(),
+                        err: AssetWriterError::Io(err),
+                    }
+                })
+            }
+        }
+
+        /// Runs the cooker on a default source backed by `source` and `processed`.
+        fn cook(
+            source: &Dir,
+            processed: &Dir,
+            log_path: &Path,
+            check: bool,
+        ) -> (AppExit, ProcessReport) {
+            IoTaskPool::get_or_init(TaskPool::new);
+            let processor = create_processor(source, processed);
+            processor.set_log_path(log_path);
+            processor.register_processor(UppercaseText);
+            processor.set_default_processor::<UppercaseText>("txt");
+
+            let mut app = App::new();
+            app.insert_resource(processor.clone());
+            let plugin = AssetCookPlugin {
+                sources: Vec::new(),
+                check,
+            };
+            let exit = cook_runner(app, plugin);
+            (exit, block_on(processor.report()))
+        }
+
+        fn read_text(dir: &Dir, path: &str) -> Option<String> {
+            let reader = crate::io::memory::MemoryAssetReader { root: dir.clone() };
+            block_on(async {
+                let mut reader = AssetReader::read(&reader, Path::new(path)).await.ok()?;
+                let mut text = String::new();
+                reader.read_to_string(&mut text).await.ok()?;
+                Some(text)
+            })
+        }
+
+        #[test]
+        fn cook_and_check() {
+            let log_dir =
+                std::env::temp_dir().join(format!("bevy_asset_cook_{}", std::process::id()));
+            let log_path = log_dir.join("log");
+            let source = Dir::default();
+            source.insert_asset_text(Path::new("a.txt"), "a");
+            // Writes to the `read_only` folder fail, so this asset fails to process
+            source.insert_asset_text(Path::new("read_only/b.txt"), "b");
+            let processed = Dir::default();
+
+            let (exit, report) = cook(&source, &processed, &log_path, false);
+            assert_eq!(exit, AppExit::error());
+            assert_eq!(report.processed, vec![AssetPath::from("a.txt")]);
+            assert_eq!(report.failed.len(), 1);
+            assert_eq!(report.failed[0].path, AssetPath::from("read_only/b.txt"));
+            assert_eq!(read_text(&processed, "a.txt").as_deref(), Some("A"));
+
+            // Processed assets are up to date in the next run
+            source.remove_asset(Path::new("read_only/b.txt"));
+            let (exit, report) = cook(&source, &processed, &log_path, false);
+            assert_eq!(exit, AppExit::Success);
+            assert_eq!(report.skipped, vec![AssetPath::from("a.txt")]);
+            assert!(report.processed.is_empty() && report.failed.is_empty());
+
+            // Checking reports modified assets and orphaned processed files, without touching them
+            source.insert_asset_text(Path::new("a.txt"), "modified");
+            processed.insert_asset_text(Path::new("orphan.txt"), "orphan");
+            let (exit, report) = cook(&source, &processed, &log_path, true);
+            assert_eq!(exit, AppExit::from_code(2));
+            assert_eq!(
+                report.out_of_date,
+                vec![AssetPath::from("a.txt"), AssetPath::from("orphan.txt")]
+            );
+            assert_eq!(read_text(&processed, "a.txt").as_deref(), Some("A"));
+            assert_eq!(
+                read_text(&processed, "orphan.txt").as_deref(),
+                Some("orphan")
+            );
+
+            let _ = std::fs::remove_dir_all(log_dir);
+        }
+    }
+}
@@ -1,18 +1,20 @@
+mod cook;
 mod log;
 mod process;
 
+pub use cook::*;
 pub use log::*;
 pub use process::*;
 
 use crate::{
     io::{
-        AssetReaderError, AssetSource, AssetSourceBuilders, AssetSourceEvent, AssetSourceId,
-        AssetSources, AssetWriterError, ErasedAssetReader, ErasedAssetWriter,
+        read_folder_files, AssetReaderError, AssetSource, AssetSourceBuilders, AssetSourceEvent,
+        AssetSourceId, AssetSources, AssetWriterError, ErasedAssetReader, ErasedAssetWriter,
         MissingAssetSourceError,
     },
     meta::{
-        get_asset_hash, get_full_asset_hash, AssetAction, AssetActionMinimal, AssetHash, AssetMeta,
-        AssetMetaDyn, AssetMetaMinimal, ProcessedInfo, ProcessedInfoMinimal,
+        get_asset_hash, get_folder_hash, get_full_asset_hash, AssetAction, AssetActionMinimal,
+        AssetHash, AssetMeta, AssetMetaDyn, AssetMetaMinimal, ProcessedInfo, ProcessedInfoMinimal,
     },
     AssetLoadError, AssetMetaCheck, AssetPath, AssetServer, AssetServerMode, DeserializeMetaError,
     MissingAssetLoaderForExtensionError,
@@ -29,10 +31,14 @@ use bevy_utils::{HashMap, HashSet};
 use futures_io::ErrorKind;
 use futures_lite::{AsyncReadExt, AsyncWriteExt, StreamExt};
 use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
 use std::{
     collections::VecDeque,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
 };
 use thiserror::Error;
 
@@ -64,6 +70,8 @@ pub struct AssetProcessor {
 pub struct AssetProcessorData {
     pub(crate) asset_infos: async_lock::RwLock<ProcessorAssetInfos>,
     log: async_lock::RwLock<Option<ProcessorTransactionLog>>,
+    /// The path the [`ProcessorTransactionLog`] is written to. See [`AssetProcessor::set_log_path`].
+    log_path: RwLock<PathBuf>,
     processors: RwLock<HashMap<&'static str, Arc<dyn ErasedProcessor>>>,
     /// Default processors for file extensions
     default_processors: RwLock<HashMap<Box<str>, &'static str>>,
@@ -73,6 +81,9 @@ pub struct AssetProcessorData {
     initialized_receiver: async_broadcast::Receiver<()>,
     finished_sender: async_broadcast::Sender<()>,
     finished_receiver: async_broadcast::Receiver<()>,
+    /// When set, assets are only checked against their processed versions instead of being processed.
+    /// See [`AssetProcessor::check_source_assets`].
+    check_only: AtomicBool,
 }
 
 impl AssetProcessor {
@@ -108,6 +119,12 @@ impl AssetProcessor {
         }
     }
 
+    /// Sets the path the [`ProcessorTransactionLog`] is written to, which defaults to `imported_assets/log` in the base
+    /// asset path. This must be set before processing starts.
+    pub fn set_log_path(&self, path: impl Into<PathBuf>) {
+        *self.data.log_path.write() = path.into();
+    }
+
     /// Retrieves the current [`ProcessorState`]
     pub async fn get_state(&self) -> ProcessorState {
         *self.data.state.read().await
@@ -127,6 +144,17 @@ impl AssetProcessor {
         &self.data.sources
     }
 
+    /// Returns the hash of the folder at `path` used when it is a "process dependency", or `None` if it isn't a
+    /// folder of its source.
+    pub(crate) async fn get_folder_hash(&self, path: &AssetPath<'_>) -> Option<AssetHash> {
+        let reader = self.get_source(path.source()).ok()?.reader();
+        if !reader.is_directory(path.path()).await.ok()? {
+            return None;
+        }
+        let file_paths = read_folder_files(reader, path.path()).await.ok()?;
+        Some(get_folder_hash(&file_paths))
+    }
+
     /// Logs an unrecoverable error. On the next run of the processor, all assets will be regenerated. This should only be used as a last resort.
     /// Every call to this should be considered with scrutiny and ideally replaced with something more granular.
     async fn log_unrecoverable(&self) {
@@ -173,12 +201,38 @@ impl AssetProcessor {
     /// (if the latest version of the asset has not been processed).
     #[cfg(all(not(target_arch = "wasm32"), feature = "multi_threaded"))]
     pub fn process_assets(&self) {
+        self.process_assets_filtered(|_| true);
+    }
+
+    /// Processes all assets in the given processed `sources`. Processed assets of other sources are still scanned
+    /// (and cleaned up if necessary), but their assets are not processed. See [`AssetProcessor::process_assets`].
+    #[cfg(all(not(target_arch = "wasm32"), feature = "multi_threaded"))]
+    pub fn process_source_assets(&self, sources: &[AssetSourceId<'_>]) {
+        self.process_assets_filtered(|source| sources.contains(&source.id()));
+    }
+
+    /// Checks whether the processed assets in the given `sources` are up to date, without processing them or writing
+    /// new `.meta` files. Assets that would have been processed are reported as [`ProcessResult::OutOfDate`] instead.
+    /// Use [`AssetProcessor::report`] to retrieve the results.
+    ///
+    /// Processed assets whose source asset no longer exists are reported as out of date instead of being removed. Note that
+    /// failed transactions are still recovered, just like in [`AssetProcessor::process_assets`].
+    #[cfg(all(not(target_arch = "wasm32"), feature = "multi_threaded"))]
+    pub fn check_source_assets(&self, sources: &[AssetSourceId<'_>]) {
+        self.data.check_only.store(true, Ordering::Release);
+        self.process_assets_filtered(|source| sources.contains(&source.id()));
+        self.data.check_only.store(false, Ordering::Release);
+    }
+
+    #[cfg(all(not(target_arch = "wasm32"), feature = "multi_threaded"))]
+    fn process_assets_filtered(&self, filter: impl Fn(&AssetSource) -> bool + Sync) {
         let start_time = std::time::Instant::now();
         debug!("Processing Assets");
+        let filter = &filter;
         IoTaskPool::get().scope(|scope| {
             scope.spawn(async move {
                 self.initialize().await.unwrap();
-                for source in self.sources().iter_processed() {
+                for source in self.sources().iter_processed().filter(|s| filter(s)) {
                     self.process_assets_internal(scope, source, PathBuf::from(""))
                         .await
                         .unwrap();
@@ -404,6 +458,12 @@ impl AssetProcessor {
             let _write_lock = info.file_transaction_lock.write();
             self.remove_processed_asset_and_meta(source, asset_path.path())
                 .await;
+            if let Some(processed_info) = &info.processed_info {
+                for output in &processed_info.additional_outputs {
+                    self.remove_processed_asset_and_meta(source, output.path())
+                        .await;
+                }
+            }
         }
         infos.remove(&asset_path).await;
     }
@@ -481,6 +541,33 @@ impl AssetProcessor {
         }
     }
 
+    /// Returns a [`ProcessReport`], which groups assets by the outcome of the most recent attempt to process them.
+    pub async fn report(&self) -> ProcessReport {
+        let mut report = ProcessReport::default();
+        let infos = self.data.asset_infos.read().await;
+        for (path, info) in &infos.infos {
+            let path = path.clone();
+            match &info.outcome {
+                Some(ProcessOutcome::Processed) => report.processed.push(path),
+                Some(ProcessOutcome::Skipped) => report.skipped.push(path),
+                Some(ProcessOutcome::OutOfDate) => report.out_of_date.push(path),
+                Some(ProcessOutcome::Failed(error)) => report.failed.push(ProcessFailure {
+                    path,
+                    error: error.clone(),
+                }),
+                None => {}
+            }
+        }
+        report.out_of_date.extend(infos.orphaned.iter().cloned());
+        report.processed.sort_by_cached_key(ToString::to_string);
+        report.skipped.sort_by_cached_key(ToString::to_string);
+        report.out_of_date.sort_by_cached_key(ToString::to_string);
+        report
+            .failed
+            .sort_by_cached_key(|failure| failure.path.to_string());
+        report
+    }
+
     /// Register a new asset processor.
     pub fn register_processor<P: Process>(&self, processor: P) {
         let mut process_plans = self.data.processors.write();
@@ -515,7 +602,9 @@ impl AssetProcessor {
     #[allow(unused)]
     async fn initialize(&self) -> Result<(), InitializeError> {
         self.validate_transaction_log_and_recover().await;
+        let check_only = self.data.check_only.load(Ordering::Acquire);
         let mut asset_infos = self.data.asset_infos.write().await;
+        asset_infos.orphaned.clear();
 
         /// Retrieves asset paths recursively. If `clean_empty_folders_writer` is Some, it will be used to clean up empty
         /// folders when they are discovered.
@@ -571,7 +660,7 @@ impl AssetProcessor {
             let mut processed_paths = Vec::new();
             get_asset_paths(
                 processed_reader,
-                Some(processed_writer),
+                (!check_only).then_some(processed_writer),
                 PathBuf::from(""),
                 &mut processed_paths,
             )
@@ -582,10 +671,14 @@ impl AssetProcessor {
                 asset_infos.get_or_insert(AssetPath::from(path).with_source(source.id()));
             }
 
+            // Paths that don't correspond to a source asset might be additional outputs of other assets. These are
+            // resolved after the processed info of every source asset has been populated.
+            let mut unowned_paths = Vec::new();
             for path in processed_paths {
                 let mut dependencies = Vec::new();
+                let mut additional_outputs = Vec::new();
                 let asset_path = AssetPath::from(path).with_source(source.id());
-                if let Some(info) = asset_infos.get_mut(&asset_path) {
+                if let Some(info) = asset_infos.infos.get_mut(&asset_path) {
                     match processed_reader.read_meta_bytes(asset_path.path()).await {
                         Ok(meta_bytes) => {
                             match ron::de::from_bytes::<ProcessedInfoMinimal>(&meta_bytes) {
@@ -601,31 +694,52 @@ impl AssetProcessor {
                                         {
                                             dependencies.push(process_dependency_info.path.clone());
                                         }
+                                        additional_outputs.extend(
+                                            processed_info.additional_outputs.iter().cloned(),
+                                        );
                                     }
                                     info.processed_info = minimal.processed_info;
                                 }
-                                Err(err) => {
+                                Err(err) if !check_only => {
                                     trace!("Removing processed data for {asset_path} because meta could not be parsed: {err}");
                                     self.remove_processed_asset_and_meta(source, asset_path.path())
                                         .await;
                                 }
+                                Err(_) => {}
                             }
                         }
-                        Err(err) => {
+                        Err(err) if !check_only => {
                             trace!("Removing processed data for {asset_path} because meta failed to load: {err}");
                             self.remove_processed_asset_and_meta(source, asset_path.path())
                                 .await;
                         }
+                        // Without processed info, the asset is reported as out of date
+                        Err(_) => {}
                     }
                 } else {
-                    trace!("Removing processed data for non-existent asset {asset_path}");
-                    self.remove_processed_asset_and_meta(source, asset_path.path())
-                        .await;
+                    unowned_paths.push(asset_path);
+                    continue;
                 }
 
                 for dependency in dependencies {
                     asset_infos.add_dependant(&dependency, asset_path.clone());
                 }
+                for output in additional_outputs {
+                    asset_infos.output_owners.insert(output, asset_path.clone());
+                }
+            }
+
+            for asset_path in unowned_paths {
+                if asset_infos.output_owners.contains_key(&asset_path) {
+                    continue;
+                }
+                if check_only {
+                    asset_infos.orphaned.push(asset_path);
+                } else {
+                    trace!("Removing processed data for non-existent asset {asset_path}");
+                    self.remove_processed_asset_and_meta(source, asset_path.path())
+                        .await;
+                }
             }
         }
 
@@ -649,6 +763,35 @@ impl AssetProcessor {
             .await;
     }
 
+    /// Removes the additional output `output` of `owner`, which was written while processing `owner` failed. The path of
+    /// the output is released if `release` is true, which is the case when `owner` didn't own it before it was processed.
+    async fn discard_additional_output(
+        &self,
+        source: &AssetSource,
+        owner: &AssetPath<'static>,
+        output: &AssetPath<'static>,
+        release: bool,
+    ) {
+        if let Ok(processed_writer) = source.processed_writer() {
+            // The output might have failed before it was created, and its meta is only written once processing succeeds,
+            // so either of them may not exist.
+            if let Err(err) = processed_writer.remove(output.path()).await {
+                trace!("Failed to remove discarded output {output}: {err}");
+            }
+            if let Err(err) = processed_writer.remove_meta(output.path()).await {
+                trace!("Failed to remove the meta of discarded output {output}: {err}");
+            }
+            self.clean_empty_processed_ancestor_folders(source, output.path())
+                .await;
+        }
+        if release {
+            let mut infos = self.data.asset_infos.write().await;
+            if infos.output_owners.get(output) == Some(owner) {
+                infos.output_owners.remove(output);
+            }
+        }
+    }
+
     async fn clean_empty_processed_ancestor_folders(&self, source: &AssetSource, path: &Path) {
         // As a safety precaution don't delete absolute paths to avoid deleting folders outside of the destination folder
         if path.is_absolute() {
@@ -753,11 +896,13 @@ impl AssetProcessor {
                 };
                 let meta_bytes = meta.serialize();
                 // write meta to source location if it doesn't already exist
-                source
-                    .writer()?
-                    .write_meta_bytes(path, &meta_bytes)
-                    .await
-                    .map_err(writer_err)?;
+                if !self.data.check_only.load(Ordering::Acquire) {
+                    source
+                        .writer()?
+                        .write_meta_bytes(path, &meta_bytes)
+                        .await
+                        .map_err(writer_err)?;
+                }
                 (meta, meta_bytes, processor)
             }
             Err(err) => {
@@ -787,21 +932,23 @@ impl AssetProcessor {
             hash: new_hash,
             full_hash: new_hash,
             process_dependencies: Vec::new(),
+            additional_outputs: Vec::new(),
         };
 
-        {
+        let old_outputs = {
             let infos = self.data.asset_infos.read().await;
-            if let Some(current_processed_info) = infos
+            let current_processed_info = infos
                 .get(asset_path)
-                .and_then(|i| i.processed_info.as_ref())
-            {
+                .and_then(|i| i.processed_info.as_ref());
+            if let Some(current_processed_info) = current_processed_info {
                 if current_processed_info.hash == new_hash {
                     let mut dependency_changed = false;
                     for current_dep_info in &current_processed_info.process_dependencies {
-                        let live_hash = infos
-                            .get(&current_dep_info.path)
-                            .and_then(|i| i.processed_info.as_ref())
-                            .map(|i| i.full_hash);
+                        let live_hash = match infos.get(&current_dep_info.path) {
+                            Some(info) => info.processed_info.as_ref().map(|i| i.full_hash),
+                            // Folders aren't processed, so their hash is computed from their contents
+                            None => self.get_folder_hash(&current_dep_info.path).await,
+                        };
                         if live_hash != Some(current_dep_info.full_hash) {
                             dependency_changed = true;
                             break;
@@ -812,7 +959,13 @@ impl AssetProcessor {
                     }
                 }
             }
-        }
+            if self.data.check_only.load(Ordering::Acquire) {
+                return Ok(ProcessResult::OutOfDate);
+            }
+            current_processed_info
+                .map(|i| i.additional_outputs.clone())
+                .unwrap_or_default()
+        };
         // Note: this lock must remain alive until all processed asset asset and meta writes have finished (or failed)
         // See ProcessedAssetInfo::file_transaction_lock docs for more info
         let _transaction_lock = {
@@ -827,36 +980,76 @@ impl AssetProcessor {
         self.log_begin_processing(asset_path).await;
         if let Some(processor) = processor {
             let mut writer = processed_writer.write(path).await.map_err(writer_err)?;
-            let mut processed_meta = {
-                let mut context =
-                    ProcessContext::new(self, asset_path, &asset_bytes, &mut new_processed_info);
-                processor
-                    .process(&mut context, source_meta, &mut *writer)
-                    .await?
-            };
-
-            writer
-                .flush()
-                .await
-                .map_err(|e| ProcessError::AssetWriterError {
-                    path: asset_path.clone(),
-                    err: AssetWriterError::Io(e),
-                })?;
-
-            let full_hash = get_full_asset_hash(
-                new_hash,
-                new_processed_info
-                    .process_dependencies
-                    .iter()
-                    .map(|i| i.full_hash),
+            let mut context = ProcessContext::new(
+                self,
+                source,
+                asset_path,
+                &asset_bytes,
+                &mut new_processed_info,
             );
-            new_processed_info.full_hash = full_hash;
-            *processed_meta.processed_info_mut() = Some(new_processed_info.clone());
-            let meta_bytes = processed_meta.serialize();
-            processed_writer
-                .write_meta_bytes(path, &meta_bytes)
-                .await
-                .map_err(writer_err)?;
+            let processed_meta = processor
+                .process(&mut context, source_meta, &mut *writer)
+                .await;
+            let additional_outputs = context.additional_outputs;
+            let output_paths: Vec<_> = additional_outputs
+                .iter()
+                .map(|(output_path, _)| output_path.clone())
+                .collect();
+
+            let result = async {
+                let mut processed_meta = processed_meta?;
+                writer
+                    .flush()
+                    .await
+                    .map_err(|e| ProcessError::AssetWriterError {
+                        path: asset_path.clone(),
+                        err: AssetWriterError::Io(e),
+                    })?;
+
+                let full_hash = get_full_asset_hash(
+                    new_hash,
+                    new_processed_info
+                        .process_dependencies
+                        .iter()
+                        .map(|i| i.full_hash),
+                );
+                new_processed_info.full_hash = full_hash;
+                for (output_path, mut output_meta) in additional_outputs {
+                    // Additional outputs share the hashes of the asset that produced them, which ensures that assets
+                    // depending on them are reprocessed whenever that asset is reprocessed.
+                    *output_meta.processed_info_mut() = Some(ProcessedInfo {
+                        hash: new_hash,
+                        full_hash,
+                        process_dependencies: Vec::new(),
+                        additional_outputs: Vec::new(),
+                    });
+                    processed_writer
+                        .write_meta_bytes(output_path.path(), &output_meta.serialize())
+                        .await
+                        .map_err(|err| ProcessError::AssetWriterError {
+                            path: output_path.clone(),
+                            err,
+                        })?;
+                    new_processed_info.additional_outputs.push(output_path);
+                }
+                *processed_meta.processed_info_mut() = Some(new_processed_info.clone());
+                let meta_bytes = processed_meta.serialize();
+                processed_writer
+                    .write_meta_bytes(path, &meta_bytes)
+                    .await
+                    .map_err(writer_err)
+            }
+            .await;
+
+            // Outputs written for a failed attempt are removed, so that they don't linger or keep their paths claimed.
+            if let Err(err) = result {
+                for output_path in &output_paths {
+                    let release = !old_outputs.contains(output_path);
+                    self.discard_additional_output(source, asset_path, output_path, release)
+                        .await;
+                }
+                return Err(err);
+            }
         } else {
             processed_writer
                 .write_bytes(path, &asset_bytes)
@@ -869,13 +1062,20 @@ impl AssetProcessor {
                 .await
                 .map_err(writer_err)?;
         }
+        for old_output in old_outputs {
+            if !new_processed_info.additional_outputs.contains(&old_output) {
+                self.remove_processed_asset_and_meta(source, old_output.path())
+                    .await;
+            }
+        }
         self.log_end_processing(asset_path).await;
 
         Ok(ProcessResult::Processed(new_processed_info))
     }
 
     async fn validate_transaction_log_and_recover(&self) {
-        if let Err(err) = ProcessorTransactionLog::validate().await {
+        let log_path = self.data.log_path.read().clone();
+        if let Err(err) = ProcessorTransactionLog::validate(&log_path).await {
             let state_is_valid = match err {
                 ValidateLogError::ReadLogError(err) => {
                     error!("Failed to read processor log file. Processed assets cannot be validated so they must be re-generated {err}");
@@ -953,7 +1153,7 @@ impl AssetProcessor {
             }
         }
         let mut log = self.data.log.write().await;
-        *log = match ProcessorTransactionLog::new().await {
+        *log = match ProcessorTransactionLog::new(&log_path).await {
             Ok(log) => Some(log),
             Err(err) => panic!("Failed to initialize asset processor log. This cannot be recovered. Try restarting. If that doesn't work, try deleting processed asset folder. {}", err),
         };
@@ -977,9 +1177,11 @@ impl AssetProcessorData {
             initialized_receiver,
             state: async_lock::RwLock::new(ProcessorState::Initializing),
             log: Default::default(),
+            log_path: RwLock::new(ProcessorTransactionLog::full_log_path()),
             processors: Default::default(),
             asset_infos: Default::default(),
             default_processors: Default::default(),
+            check_only: AtomicBool::new(false),
         }
     }
 
@@ -1075,6 +1277,44 @@ pub enum ProcessResult {
     Processed(ProcessedInfo),
     SkippedNotChanged,
     Ignored,
+    /// The processed asset is out of date, but was not processed because the processor was only checking assets.
+    /// See [`AssetProcessor::check_source_assets`].
+    OutOfDate,
+}
+
+/// The outcome of the most recent attempt to process an asset, which is summarized by [`ProcessReport`].
+#[derive(Debug, Clone)]
+enum ProcessOutcome {
+    Processed,
+    Skipped,
+    OutOfDate,
+    Failed(String),
+}
+
+/// A summary of the assets known to the [`AssetProcessor`], grouped by the outcome of the most recent attempt to process them.
+/// Ignored assets and assets that cannot be processed (ex: because there is no loader for them) are not included.
+///
+/// See [`AssetProcessor::report`].
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct ProcessReport {
+    /// Assets that were processed.
+    pub processed: Vec<AssetPath<'static>>,
+    /// Assets that were not processed because their processed version was already up to date.
+    pub skipped: Vec<AssetPath<'static>>,
+    /// Assets whose processed version is out of date, and processed files whose source asset no longer exists. These are
+    /// only produced by [`AssetProcessor::check_source_assets`].
+    pub out_of_date: Vec<AssetPath<'static>>,
+    /// Assets that failed to process.
+    pub failed: Vec<ProcessFailure>,
+}
+
+/// An asset that failed to process. See [`ProcessReport`].
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProcessFailure {
+    /// The path of the asset that failed to process.
+    pub path: AssetPath<'static>,
+    /// The [`ProcessError`] that was encountered, formatted as a string.
+    pub error: String,
 }
 
 /// The final status of processing an asset
@@ -1092,6 +1332,7 @@ pub(crate) struct ProcessorAssetInfo {
     /// Paths of assets that depend on this asset when they are being processed.
     dependants: HashSet<AssetPath<'static>>,
     status: Option<ProcessStatus>,
+    outcome: Option<ProcessOutcome>,
     /// A lock that controls read/write access to processed asset files. The lock is shared for both the asset bytes and the meta bytes.
     /// _This lock must be locked whenever a read or write to processed assets occurs_
     /// There are scenarios where processed assets (and their metadata) are being read and written in multiple places at once:
@@ -1116,6 +1357,7 @@ impl Default for ProcessorAssetInfo {
             dependants: Default::default(),
             file_transaction_lock: Default::default(),
             status: None,
+            outcome: None,
             status_sender,
             status_receiver,
         }
@@ -1146,11 +1388,20 @@ pub struct ProcessorAssetInfos {
     /// Therefore this _must_ always be consistent with the `infos` data. If a new asset is added to `infos`, it should
     /// check this maps for dependencies and add them. If an asset is removed, it should update the dependants here.
     non_existent_dependants: HashMap<AssetPath<'static>, HashSet<AssetPath<'static>>>,
+    /// Maps the paths of additional processed outputs (see [`ProcessContext::write_additional_output`]) to the path of the
+    /// asset that produced them. Lookups of an additional output resolve to the info of the asset that owns it.
+    output_owners: HashMap<AssetPath<'static>, AssetPath<'static>>,
+    /// Processed files without a source asset or an owner, found by [`AssetProcessor::check_source_assets`]. Instead of
+    /// being removed, they are reported as out of date.
+    orphaned: Vec<AssetPath<'static>>,
     check_reprocess_queue: VecDeque<AssetPath<'static>>,
 }
 
 impl ProcessorAssetInfos {
     fn get_or_insert(&mut self, asset_path: AssetPath<'static>) -> &mut ProcessorAssetInfo {
+        if !self.infos.contains_key(&asset_path) {
+            self.queue_folder_dependants(&asset_path);
+        }
         self.infos.entry(asset_path.clone()).or_insert_with(|| {
             let mut info = ProcessorAssetInfo::default();
             // track existing dependants by resolving existing "hanging" dependants.
@@ -1161,16 +1412,33 @@ impl ProcessorAssetInfos {
         })
     }
 
+    /// Retrieves the info for the given path. If the path is an additional processed output, this will return the info of
+    /// the asset that produced it.
     pub(crate) fn get(&self, asset_path: &AssetPath<'static>) -> Option<&ProcessorAssetInfo> {
+        let asset_path = self.output_owners.get(asset_path).unwrap_or(asset_path);
         self.infos.get(asset_path)
     }
 
     fn get_mut(&mut self, asset_path: &AssetPath<'static>) -> Option<&mut ProcessorAssetInfo> {
+        let asset_path = self.output_owners.get(asset_path).unwrap_or(asset_path);
         self.infos.get_mut(asset_path)
     }
 
+    /// Queues the assets that depend on the folder containing `asset_path` for a reprocess check, because adding or
+    /// removing an asset changes the hash of its folder. Folders have no info, so their dependants are "hanging".
+    fn queue_folder_dependants(&mut self, asset_path: &AssetPath<'static>) {
+        let Some(folder) = asset_path.parent() else {
+            return;
+        };
+        if let Some(dependants) = self.non_existent_dependants.get(&folder) {
+            self.check_reprocess_queue
+                .extend(dependants.iter().cloned());
+        }
+    }
+
     fn add_dependant(&mut self, asset_path: &AssetPath<'static>, dependant: AssetPath<'static>) {
-        if let Some(info) = self.get_mut(asset_path) {
+        let asset_path = self.output_owners.get(asset_path).unwrap_or(asset_path);
+        if let Some(info) = self.infos.get_mut(asset_path) {
             info.dependants.insert(dependant);
         } else {
             let dependants = self
@@ -1196,6 +1464,9 @@ impl ProcessorAssetInfos {
                     .get_mut(&asset_path)
                     .and_then(|i| i.processed_info.take());
                 if let Some(old_processed_info) = old_processed_info {
+                    for output in &old_processed_info.additional_outputs {
+                        self.output_owners.remove(output);
+                    }
                     self.clear_dependencies(&asset_path, old_processed_info);
                 }
 
@@ -1203,8 +1474,13 @@ impl ProcessorAssetInfos {
                 for process_dependency_info in &processed_info.process_dependencies {
                     self.add_dependant(&process_dependency_info.path, asset_path.to_owned());
                 }
+                for output in &processed_info.additional_outputs {
+                    self.output_owners
+                        .insert(output.clone(), asset_path.to_owned());
+                }
                 let info = self.get_or_insert(asset_path);
                 info.processed_info = Some(processed_info);
+                info.outcome = Some(ProcessOutcome::Processed);
                 info.update_status(ProcessStatus::Processed).await;
                 let dependants = info.dependants.iter().cloned().collect::<Vec<_>>();
                 for path in dependants {
@@ -1220,6 +1496,13 @@ impl ProcessorAssetInfos {
                 // Therefore this relies on hot-reloading in the app to pickup the "latest" version of the asset
                 // If "block until latest state is reflected" is required, we can easily add a less granular
                 // "block until first pass finished" mode
+                info.outcome = Some(ProcessOutcome::Skipped);
+                info.update_status(ProcessStatus::Processed).await;
+            }
+            Ok(ProcessResult::OutOfDate) => {
+                debug!("Processed asset is out of date \"{:?}\"", asset_path);
+                let info = self.get_mut(&asset_path).expect("info should exist");
+                info.outcome = Some(ProcessOutcome::OutOfDate);
                 info.update_status(ProcessStatus::Processed).await;
             }
             Ok(ProcessResult::Ignored) => {
@@ -1240,20 +1523,28 @@ impl ProcessorAssetInfos {
             }
             Err(err) => {
                 error!("Failed to process asset {asset_path}: {err}");
+                let outcome = ProcessOutcome::Failed(err.to_string());
                 // if this failed because a dependency could not be loaded, make sure it is reprocessed if that dependency is reprocessed
-                if let ProcessError::AssetLoadError(AssetLoadError::AssetLoaderError(dependency)) =
-                    err
-                {
+                let failed_dependency = match err {
+                    ProcessError::AssetLoadError(AssetLoadError::AssetLoaderError(dependency)) => {
+                        Some(dependency.path().clone())
+                    }
+                    ProcessError::ProcessDependencyFailed(dependency) => Some(dependency),
+                    _ => None,
+                };
+                if let Some(dependency) = failed_dependency {
                     let info = self.get_mut(&asset_path).expect("info should exist");
                     info.processed_info = Some(ProcessedInfo {
                         hash: AssetHash::default(),
                         full_hash: AssetHash::default(),
                         process_dependencies: vec![],
+                        additional_outputs: vec![],
                     });
-                    self.add_dependant(dependency.path(), asset_path.to_owned());
+                    self.add_dependant(&dependency, asset_path.to_owned());
                 }
 
                 let info = self.get_mut(&asset_path).expect("info should exist");
+                info.outcome = Some(outcome);
                 info.update_status(ProcessStatus::Failed).await;
             }
         }
@@ -1261,9 +1552,14 @@ impl ProcessorAssetInfos {
 
     /// Remove the info for the given path. This should only happen if an asset's source is removed / non-existent
     async fn remove(&mut self, asset_path: &AssetPath<'static>) {
+        self.output_owners.remove(asset_path);
         let info = self.infos.remove(asset_path);
         if let Some(info) = info {
+            self.queue_folder_dependants(asset_path);
             if let Some(processed_info) = info.processed_info {
+                for output in &processed_info.additional_outputs {
+                    self.output_owners.remove(output);
+                }
                 self.clear_dependencies(asset_path, processed_info);
             }
             // Tell all listeners this asset does not exist
@@ -1286,6 +1582,7 @@ impl ProcessorAssetInfos {
     async fn rename(&mut self, old: &AssetPath<'static>, new: &AssetPath<'static>) {
         let info = self.infos.remove(old);
         if let Some(mut info) = info {
+            self.queue_folder_dependants(old);
             if !info.dependants.is_empty() {
                 // TODO: We can't currently ensure "moved" folders with relative paths aren't broken because AssetPath
                 // doesn't distinguish between absolute and relative paths. We have "erased" relativeness. In the short term,
@@ -1302,9 +1599,14 @@ impl ProcessorAssetInfos {
                     .insert(old.clone(), std::mem::take(&mut info.dependants));
             }
             if let Some(processed_info) = &info.processed_info {
+                // Additional outputs keep their paths, but are now owned by the new path.
+                for output in &processed_info.additional_outputs {
+                    self.output_owners.insert(output.clone(), new.clone());
+                }
                 // Update "dependant" lists for this asset's "process dependencies" to use new path.
                 for dep in &processed_info.process_dependencies {
-                    if let Some(info) = self.infos.get_mut(&dep.path) {
+                    let dep_path = self.output_owners.get(&dep.path).unwrap_or(&dep.path);
+                    if let Some(info) = self.infos.get_mut(dep_path) {
                         info.dependants.remove(old);
                         info.dependants.insert(new.clone());
                     } else if let Some(dependants) = self.non_existent_dependants.get_mut(&dep.path)
@@ -1323,6 +1625,7 @@ impl ProcessorAssetInfos {
                 let new_info = self.get_or_insert(new.clone());
                 new_info.processed_info = info.processed_info;
                 new_info.status = info.status;
+                new_info.outcome = info.outcome;
                 // Ensure things waiting on the new path are informed of the status of this asset
                 if let Some(status) = new_info.status {
                     new_info.status_sender.broadcast(status).await.unwrap();
@@ -1340,7 +1643,11 @@ impl ProcessorAssetInfos {
 
     fn clear_dependencies(&mut self, asset_path: &AssetPath<'static>, removed_info: ProcessedInfo) {
         for old_load_dep in removed_info.process_dependencies {
-            if let Some(info) = self.infos.get_mut(&old_load_dep.path) {
+            let dep_path = self
+                .output_owners
+                .get(&old_load_dep.path)
+                .unwrap_or(&old_load_dep.path);
+            if let Some(info) = self.infos.get_mut(dep_path) {
                 info.dependants.remove(asset_path);
             } else if let Some(dependants) =
                 self.non_existent_dependants.get_mut(&old_load_dep.path)
@@ -1374,3 +1681,441 @@ pub enum InitializeError {
     #[error("Failed to validate asset log: {0}")]
     ValidateLogError(ValidateLogError),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        self as bevy_asset,
+        io::{
+            memory::{Data, Dir, MemoryAssetReader},
+            AssetSource, AssetWriterError, Reader, Writer,
+        },
+        saver::{AssetSaver, SavedAsset},
+        Asset, AssetLoader, ErasedLoadedAsset, LoadContext, LoadedAsset,
+    };
+    use bevy_reflect::TypePath;
+    use futures_io::AsyncWrite;
+    use futures_lite::{future::block_on, AsyncReadExt};
+    use std::{pin::Pin, task::Poll};
+
+    #[derive(Asset, TypePath)]
+    pub(super) struct Text(String);
+
+    pub(super) struct TextLoader;
+
+    impl AssetLoader for TextLoader {
+        type Asset = Text;
+        type Settings = ();
+        type Error = std::io::Error;
+
+        async fn load<'a>(
+            &'a self,
+            reader: &'a mut Reader<'_>,
+            _settings: &'a (),
+            _load_context: &'a mut LoadContext<'_>,
+        ) -> Result<Text, Self::Error> {
+            let mut text = String::new();
+            reader.read_to_string(&mut text).await?;
+            Ok(Text(text))
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["txt"]
+        }
+    }
+
+    struct TextSaver;
+
+    impl AssetSaver for TextSaver {
+        type Asset = Text;
+        type Settings = ();
+        type OutputLoader = TextLoader;
+        type Error = std::io::Error;
+
+        async fn save<'a>(
+            &'a self,
+            writer: &'a mut Writer,
+            asset: SavedAsset<'a, Text>,
+            _settings: &'a (),
+        ) -> Result<(), Self::Error> {
+            writer.write_all(asset.0.as_bytes()).await
+        }
+    }
+
+    /// Writes assets to a [`Dir`]. Writes to the `read_only` folder fail.
+    struct DirAssetWriter(Dir);
+
+    struct DirWriter {
+        dir: Dir,
+        path: PathBuf,
+        is_meta: bool,
+        bytes: Vec<u8>,
+    }
+
+    impl AsyncWrite for DirWriter {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+            buf: &[u8],
+        ) -> Poll<std::io::Result<usize>> {
+            self.get_mut().bytes.extend_from_slice(buf);
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(
+            self: Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            if self.is_meta {
+                self.dir.insert_meta(&self.path, self.bytes.clone());
+            } else {
+                self.dir.insert_asset(&self.path, self.bytes.clone());
+            }
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_close(
+            self: Pin<&mut Self>,
+            cx: &mut std::task::Context<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            self.poll_flush(cx)
+        }
+    }
+
+    impl DirAssetWriter {
+        fn writer(&self, path: &Path, is_meta: bool) -> Result<Box<Writer>, AssetWriterError> {
+            if path.starts_with("read_only") {
+                return Err(AssetWriterError::Io(ErrorKind::PermissionDenied.into()));
+            }
+            Ok(Box::new(DirWriter {
+                dir: self.0.clone(),
+                path: path.to_owned(),
+                is_meta,
+                bytes: Vec::new(),
+            }))
+        }
+    }
+
+    fn not_found(removed: Option<Data>) -> Result<(), AssetWriterError> {
+        removed
+            .map(|_| ())
+            .ok_or_else(|| AssetWriterError::Io(ErrorKind::NotFound.into()))
+    }
+
+    impl AssetWriter for DirAssetWriter {
+        async fn write<'a>(&'a self, path: &'a Path) -> Result<Box<Writer>, AssetWriterError> {
+            self.writer(path, false)
+        }
+
+        async fn write_meta<'a>(&'a self, path: &'a Path) -> Result<Box<Writer>, AssetWriterError> {
+            self.writer(path, true)
+        }
+
+        async fn remove<'a>(&'a self, path: &'a Path) -> Result<(), AssetWriterError> {
+            not_found(self.0.remove_asset(path))
+        }
+
+        async fn remove_meta<'a>(&'a self, path: &'a Path) -> Result<(), AssetWriterError> {
+            not_found(self.0.remove_metadata(path))
+        }
+
+        async fn rename<'a>(
+            &'a self,
+            _old_path: &'a Path,
+            _new_path: &'a Path,
+        ) -> Result<(), AssetWriterError> {
+            unimplemented!()
+        }
+
+        async fn rename_meta<'a>(
+            &'a self,
+            _old_path: &'a Path,
+            _new_path: &'a Path,
+        ) -> Result<(), AssetWriterError> {
+            unimplemented!()
+        }
+
+        async fn remove_directory<'a>(&'a self, _path: &'a Path) -> Result<(), AssetWriterError> {
+            unimplemented!()
+        }
+
+        async fn remove_empty_directory<'a>(
+            &'a self,
+            _path: &'a Path,
+        ) -> Result<(), AssetWriterError> {
+            // Folders are left in place
+            Err(AssetWriterError::Io(ErrorKind::Unsupported.into()))
+        }
+
+        async fn remove_assets_in_directory<'a>(
+            &'a self,
+            _path: &'a Path,
+        ) -> Result<(), AssetWriterError> {
+            unimplemented!()
+        }
+    }
+
+    /// Creates a processor reading from `source` and writing to `processed`. It is not started, so asset infos and
+    /// statuses are set up by the tests themselves.
+    /// Creates an [`AssetProcessor`] with a default source that reads and writes its assets in `source`, and its
+    /// processed assets in `processed`.
+    pub(super) fn create_processor(source: &Dir, processed: &Dir) -> AssetProcessor {
+        let (source_reader, source_writer) = (source.clone(), source.clone());
+        let (processed_reader, processed_writer) = (processed.clone(), processed.clone());
+        let mut builders = AssetSourceBuilders::default();
+        builders.insert(
+            AssetSourceId::Default,
+            AssetSource::build()
+                .with_reader(move || {
+                    Box::new(MemoryAssetReader {
+                        root: source_reader.clone(),
+                    })
+                })
+                .with_writer(move |_| Some(Box::new(DirAssetWriter(source_writer.clone()))))
+                .with_processed_reader(move || {
+                    Box::new(MemoryAssetReader {
+                        root: processed_reader.clone(),
+                    })
+                })
+                .with_processed_writer(move |_| {
+                    Some(Box::new(DirAssetWriter(processed_writer.clone())))
+                }),
+        );
+        AssetProcessor::new(&mut builders)
+    }
+
+    fn processor(source: &Dir, processed: &Dir) -> AssetProcessor {
+        let processor = create_processor(source, processed);
+        block_on(processor.set_state(ProcessorState::Processing));
+        processor
+    }
+
+    fn processed(
+        full_hash: AssetHash,
+        additional_outputs: Vec<AssetPath<'static>>,
+    ) -> ProcessedInfo {
+        ProcessedInfo {
+            hash: full_hash,
+            full_hash,
+            process_dependencies: Vec::new(),
+            additional_outputs,
+        }
+    }
+
+    #[test]
+    fn process_dependencies() {
+        let dir = Dir::default();
+        dir.insert_asset_text(Path::new("a.txt"), "a");
+        dir.insert_asset_text(Path::new("folder/b.txt"), "b");
+        dir.insert_asset_text(Path::new("failed.txt"), "failed");
+        let processor = processor(&dir, &Dir::default());
+        block_on(async {
+            let mut infos = processor.data.asset_infos.write().await;
+            infos.get_or_insert("a.txt".into());
+            infos
+                .finish_processing(
+                    "a.txt".into(),
+                    Ok(ProcessResult::Processed(processed([1; 32], vec![]))),
+                )
+                .await;
+            infos.get_or_insert("failed.txt".into());
+            infos
+                .finish_processing(
+                    "failed.txt".into(),
+                    Err(ProcessError::MissingProcessor("Nope".to_string())),
+                )
+                .await;
+        });
+
+        let source = processor.get_source(AssetSourceId::Default).unwrap();
+        let path = AssetPath::from("c.txt");
+        let mut info = processed(AssetHash::default(), vec![]);
+        let mut context = ProcessContext::new(&processor, source, &path, &[], &mut info);
+        block_on(async {
+            context.add_process_dependency("a.txt#Label").await.unwrap();
+            context.add_process_dependency("folder").await.unwrap();
+            assert!(matches!(
+                context.add_process_dependency("missing.txt").await,
+                Err(ProcessError::ProcessDependencyFailed(path)) if path == "missing.txt".into()
+            ));
+            assert!(matches!(
+                context.add_process_dependency("failed.txt").await,
+                Err(ProcessError::ProcessDependencyFailed(path)) if path == "failed.txt".into()
+            ));
+        });
+
+        let folder_hash = get_folder_hash(&[PathBuf::from("folder/b.txt")]);
+        let dependencies: Vec<_> = info
+            .process_dependencies
+            .iter()
+            .map(|dependency| (dependency.path.clone(), dependency.full_hash))
+            .collect();
+        assert_eq!(
+            dependencies,
+            vec![("a.txt".into(), [1; 32]), ("folder".into(), folder_hash)]
+        );
+    }
+
+    #[test]
+    fn additional_outputs() {
+        let dir = Dir::default();
+        dir.insert_asset_text(Path::new("scene.txt"), "scene");
+        dir.insert_asset_text(Path::new("other.txt"), "other");
+        let processed_dir = Dir::default();
+        let processor = processor(&dir, &processed_dir);
+        block_on(async {
+            let mut infos = processor.data.asset_infos.write().await;
+            infos.get_or_insert("scene.txt".into());
+            infos.get_or_insert("other.txt".into());
+        });
+
+        let source = processor.get_source(AssetSourceId::Default).unwrap();
+        let path = AssetPath::from("scene.txt");
+        let mut info = processed(AssetHash::default(), vec![]);
+        let mut context = ProcessContext::new(&processor, source, &path, &[], &mut info);
+        let mesh: ErasedLoadedAsset = LoadedAsset::from(Text("mesh".to_string())).into();
+        let mesh = || SavedAsset::from_loaded(&mesh).unwrap();
+        block_on(async {
+            let output = context
+                .write_additional_output("scene/mesh.txt", &TextSaver, mesh(), &())
+                .await
+                .unwrap();
+            assert_eq!(output, "scene/mesh.txt".into());
+
+            // Paths used by source assets or by other outputs of the same asset can't be written
+            for claimed in ["other.txt", "scene/mesh.txt"] {
+                assert!(matches!(
+                    context.write_additional_output(claimed, &TextSaver, mesh(), &()).await,
+                    Err(ProcessError::InvalidAdditionalOutputPath(path)) if path == claimed.into()
+                ));
+            }
+            // Failed writes don't leave an owner behind
+            assert!(matches!(
+                context
+                    .write_additional_output("read_only/mesh.txt", &TextSaver, mesh(), &())
+                    .await,
+                Err(ProcessError::AssetWriterError { .. })
+            ));
+        });
+        assert!(processed_dir
+            .get_asset(Path::new("read_only/mesh.txt"))
+            .is_none());
+        assert!(processed_dir
+            .get_asset(Path::new("scene/mesh.txt"))
+            .is_some());
+        assert_eq!(context.additional_outputs.len(), 1);
+        assert_eq!(context.additional_outputs[0].0, "scene/mesh.txt".into());
+
+        block_on(async {
+            let mut infos = processor.data.asset_infos.write().await;
+            let output = AssetPath::from("scene/mesh.txt");
+            assert_eq!(infos.output_owners.get(&output), Some(&path));
+            assert!(!infos
+                .output_owners
+                .contains_key(&AssetPath::from("read_only/mesh.txt")));
+
+            // Lookups of an output resolve to its owner, until the owner is removed
+            infos
+                .finish_processing(
+                    path.clone(),
+                    Ok(ProcessResult::Processed(processed(
+                        [2; 32],
+                        vec![output.clone()],
+                    ))),
+                )
+                .await;
+            let full_hash = infos
+                .get(&output)
+                .and_then(|info| info.processed_info.as_ref())
+                .map(|info| info.full_hash);
+            assert_eq!(full_hash, Some([2; 32]));
+
+            infos.remove(&path).await;
+            assert!(infos.output_owners.is_empty());
+            assert!(infos.get(&output).is_none());
+        });
+    }
+
+    #[test]
+    fn additional_output_owners() {
+        let dir = Dir::default();
+        dir.insert_asset_text(Path::new("a.txt"), "a");
+        dir.insert_asset_text(Path::new("b.txt"), "b");
+        let processed_dir = Dir::default();
+        let processor = processor(&dir, &processed_dir);
+        block_on(async {
+            let mut infos = processor.data.asset_infos.write().await;
+            infos.get_or_insert("a.txt".into());
+            infos.get_or_insert("b.txt".into());
+        });
+
+        let source = processor.get_source(AssetSourceId::Default).unwrap();
+        let a = AssetPath::from("a.txt");
+        let b = AssetPath::from("b.txt");
+        let shared = AssetPath::from("shared.txt");
+        let text =
+            |text: &str| -> ErasedLoadedAsset { LoadedAsset::from(Text(text.to_string())).into() };
+        let (from_a, from_b) = (text("from a"), text("from b"));
+        let mut a_info = processed(AssetHash::default(), vec![]);
+        let mut b_info = processed(AssetHash::default(), vec![]);
+        let mut a_context = ProcessContext::new(&processor, source, &a, &[], &mut a_info);
+        let mut b_context = ProcessContext::new(&processor, source, &b, &[], &mut b_info);
+        block_on(async {
+            let from_a = SavedAsset::from_loaded(&from_a).unwrap();
+            a_context
+                .write_additional_output("shared.txt", &TextSaver, from_a, &())
+                .await
+                .unwrap();
+
+            // The first asset to claim an output keeps it
+            let from_b = SavedAsset::from_loaded(&from_b).unwrap();
+            assert!(matches!(
+                b_context
+                    .write_additional_output("shared.txt", &TextSaver, from_b, &())
+                    .await,
+                Err(ProcessError::InvalidAdditionalOutputPath(path)) if path == shared
+            ));
+            assert_eq!(
+                processor
+                    .data
+                    .asset_infos
+                    .read()
+                    .await
+                    .output_owners
+                    .get(&shared),
+                Some(&a)
+            );
+        });
+        assert!(b_context.additional_outputs.is_empty());
+        block_on(async {
+            let mut text = String::new();
+            let reader = MemoryAssetReader {
+                root: processed_dir.clone(),
+            };
+            let mut reader = AssetReader::read(&reader, Path::new("shared.txt"))
+                .await
+                .unwrap();
+            reader.read_to_string(&mut text).await.unwrap();
+            assert_eq!(text, "from a");
+        });
+
+        // Discarding an output removes it and releases it for other assets
+        block_on(async {
+            processor
+                .discard_additional_output(source, &a, &shared, true)
+                .await;
+            assert!(!processor
+                .data
+                .asset_infos
+                .read()
+                .await
+                .output_owners
+                .contains_key(&shared));
+        });
+        assert!(processed_dir.get_asset(Path::new("shared.txt")).is_none());
+        assert!(processed_dir
+            .get_metadata(Path::new("shared.txt"))
+            .is_none());
+    }
+}
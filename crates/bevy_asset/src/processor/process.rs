@@ -1,19 +1,20 @@
 use crate::io::SliceReader;
 use crate::{
     io::{
-        AssetReaderError, AssetWriterError, MissingAssetWriterError,
+        AssetReaderError, AssetSource, AssetWriterError, MissingAssetWriterError,
         MissingProcessedAssetReaderError, MissingProcessedAssetWriterError, Writer,
     },
     meta::{AssetAction, AssetMeta, AssetMetaDyn, ProcessDependencyInfo, ProcessedInfo, Settings},
-    processor::AssetProcessor,
+    processor::{AssetProcessor, ProcessStatus},
     saver::{AssetSaver, SavedAsset},
     transformer::{AssetTransformer, TransformedAsset},
     AssetLoadError, AssetLoader, AssetPath, DeserializeMetaError, ErasedLoadedAsset,
     MissingAssetLoaderForExtensionError, MissingAssetLoaderForTypeNameError,
 };
 use bevy_utils::{BoxedFuture, ConditionalSendFuture};
+use futures_lite::AsyncWriteExt;
 use serde::{Deserialize, Serialize};
-use std::marker::PhantomData;
+use std::{marker::PhantomData, path::Path};
 use thiserror::Error;
 
 /// Asset "processor" logic that reads input asset bytes (stored on [`ProcessContext`]), processes the value in some way,
@@ -165,6 +166,10 @@ pub enum ProcessError {
     AssetTransformError(Box<dyn std::error::Error + Send + Sync + 'static>),
     #[error("Assets without extensions are not supported.")]
     ExtensionRequired,
+    #[error("The process dependency '{0}' does not exist or failed to process")]
+    ProcessDependencyFailed(AssetPath<'static>),
+    #[error("'{0}' cannot be used as an additional processed output because it is already in use")]
+    InvalidAdditionalOutputPath(AssetPath<'static>),
 }
 
 impl<Loader, Transformer, Saver> Process for LoadTransformAndSave<Loader, Transformer, Saver>
@@ -301,7 +306,8 @@ pub struct ProcessContext<'a> {
     /// job to populate `process_dependencies` with any asset dependencies used to process
     /// this asset (ex: loading an asset value from the [`AssetServer`] of the [`AssetProcessor`])
     ///
-    /// DO NOT CHANGE ANY VALUES HERE OTHER THAN APPENDING TO `process_dependencies`
+    /// DO NOT CHANGE ANY VALUES HERE OTHER THAN APPENDING TO `process_dependencies`.
+    /// `additional_outputs` is populated by the [`AssetProcessor`] from `additional_outputs` below.
     ///
     /// Do not expose this publicly as it would be too easily to invalidate state.
     ///
@@ -316,22 +322,29 @@ pub struct ProcessContext<'a> {
     ///
     /// [`AssetServer`]: crate::server::AssetServer
     processor: &'a AssetProcessor,
+    source: &'a AssetSource,
     path: &'a AssetPath<'static>,
     asset_bytes: &'a [u8],
+    /// The additional processed assets written by [`ProcessContext::write_additional_output`], alongside their meta.
+    /// The [`AssetProcessor`] writes the meta once the final `full_hash` of the processed asset is known.
+    pub(crate) additional_outputs: Vec<(AssetPath<'static>, Box<dyn AssetMetaDyn>)>,
 }
 
 impl<'a> ProcessContext<'a> {
     pub(crate) fn new(
         processor: &'a AssetProcessor,
+        source: &'a AssetSource,
         path: &'a AssetPath<'static>,
         asset_bytes: &'a [u8],
         new_processed_info: &'a mut ProcessedInfo,
     ) -> Self {
         Self {
             processor,
+            source,
             path,
             asset_bytes,
             new_processed_info,
+            additional_outputs: Vec::new(),
         }
     }
 
@@ -368,6 +381,135 @@ impl<'a> ProcessContext<'a> {
         Ok(loaded_asset)
     }
 
+    /// Registers the asset at `path` as a "process dependency" of the asset being processed, without loading it.
+    /// This will wait until the asset at `path` has been processed. Whenever it is reprocessed in the future,
+    /// the current asset will be reprocessed as well.
+    ///
+    /// Use this when the processed output of the current asset depends on another asset in a way that isn't captured
+    /// by loading it (ex: when the current asset references a processed output of another asset by path).
+    ///
+    /// If `path` is a folder, the current asset is reprocessed whenever an asset is added to or removed from it,
+    /// which is how [`LoadContext::read_folder`](crate::LoadContext::read_folder) registers the folders it reads.
+    pub async fn add_process_dependency<'b>(
+        &mut self,
+        path: impl Into<AssetPath<'b>>,
+    ) -> Result<(), ProcessError> {
+        let path = path.into().without_label().into_owned();
+        if let Some(full_hash) = self.processor.get_folder_hash(&path).await {
+            self.new_processed_info
+                .process_dependencies
+                .push(ProcessDependencyInfo { full_hash, path });
+            return Ok(());
+        }
+        let status = self.processor.data.wait_until_processed(path.clone()).await;
+        let full_hash = if status == ProcessStatus::Processed {
+            let infos = self.processor.data.asset_infos.read().await;
+            infos
+                .get(&path)
+                .and_then(|info| info.processed_info.as_ref())
+                .map(|info| info.full_hash)
+        } else {
+            None
+        };
+        let Some(full_hash) = full_hash else {
+            return Err(ProcessError::ProcessDependencyFailed(path));
+        };
+        self.new_processed_info
+            .process_dependencies
+            .push(ProcessDependencyInfo { full_hash, path });
+        Ok(())
+    }
+
+    /// Saves `asset` using `saver` as an additional processed asset at `path` in the [`AssetSource`] of the asset being processed.
+    /// A `.meta` file is written next to it, which will load it using [`AssetSaver::OutputLoader`] with the settings returned by `saver`.
+    /// This makes it possible to split a single source asset into multiple separately loadable processed assets (ex: one file per mesh
+    /// of a scene).
+    ///
+    /// Additional outputs are owned by the asset being processed. Reads of them wait until that asset has been processed, they
+    /// are removed when it is removed, and outputs that are not written again when it is reprocessed are cleaned up.
+    /// If writing the output or processing the asset fails, the output is removed again.
+    /// `path` must not be used by a source asset, by another output of the asset being processed or by an output of another asset.
+    pub async fn write_additional_output<S: AssetSaver>(
+        &mut self,
+        path: impl AsRef<Path>,
+        saver: &S,
+        asset: SavedAsset<'_, S::Asset>,
+        settings: &S::Settings,
+    ) -> Result<AssetPath<'static>, ProcessError> {
+        let output_path = AssetPath::from_path(path.as_ref())
+            .with_source(self.path.source().clone_owned())
+            .into_owned();
+        // The path is checked and reserved under the same lock, so that two assets processed at the same time can't
+        // both claim it. Reserving it before writing also gates reads of the output on the asset being processed.
+        let is_new_output = {
+            let mut infos = self.processor.data.asset_infos.write().await;
+            let is_claimed = infos.infos.contains_key(&output_path)
+                || self
+                    .additional_outputs
+                    .iter()
+                    .any(|(path, _)| *path == output_path)
+                || infos
+                    .output_owners
+                    .get(&output_path)
+                    .is_some_and(|owner| owner != self.path);
+            if is_claimed {
+                return Err(ProcessError::InvalidAdditionalOutputPath(output_path));
+            }
+            infos
+                .output_owners
+                .insert(output_path.clone(), self.path.clone())
+                .is_none()
+        };
+
+        let loader_settings = match self
+            .write_output(&output_path, saver, asset, settings)
+            .await
+        {
+            Ok(loader_settings) => loader_settings,
+            Err(err) => {
+                self.processor
+                    .discard_additional_output(self.source, self.path, &output_path, is_new_output)
+                    .await;
+                return Err(err);
+            }
+        };
+
+        let meta: Box<dyn AssetMetaDyn> =
+            Box::new(AssetMeta::<S::OutputLoader, ()>::new(AssetAction::Load {
+                loader: std::any::type_name::<S::OutputLoader>().to_string(),
+                settings: loader_settings,
+            }));
+        self.additional_outputs.push((output_path.clone(), meta));
+        Ok(output_path)
+    }
+
+    async fn write_output<S: AssetSaver>(
+        &self,
+        output_path: &AssetPath<'static>,
+        saver: &S,
+        asset: SavedAsset<'_, S::Asset>,
+        settings: &S::Settings,
+    ) -> Result<<S::OutputLoader as AssetLoader>::Settings, ProcessError> {
+        let writer_err = |err| ProcessError::AssetWriterError {
+            path: output_path.clone(),
+            err,
+        };
+        let processed_writer = self.source.processed_writer()?;
+        let mut writer = processed_writer
+            .write(output_path.path())
+            .await
+            .map_err(writer_err)?;
+        let loader_settings = saver
+            .save(&mut *writer, asset, settings)
+            .await
+            .map_err(|error| ProcessError::AssetSaveError(error.into()))?;
+        writer
+            .flush()
+            .await
+            .map_err(|e| writer_err(AssetWriterError::Io(e)))?;
+        Ok(loader_settings)
+    }
+
     /// The path of the asset being processed.
     #[inline]
     pub fn path(&self) -> &AssetPath<'static> {
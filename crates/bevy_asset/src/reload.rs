@@ -0,0 +1,54 @@
+use crate::{Asset, AssetId, AssetPath};
+
+/// An [`Asset`] that can merge a freshly loaded version of itself into the version that is already
+/// stored in [`Assets`](crate::Assets), instead of being replaced wholesale.
+///
+/// By default, when an asset is reloaded (for example because its file changed on disk and hot reloading
+/// is enabled), the new value simply replaces the old one. Assets that accumulate runtime state, such as
+/// caches, counters or user edits, can implement this trait to decide which parts of the old value to keep.
+///
+/// The hook must be enabled with [`AssetApp::register_asset_reload_hook`](crate::AssetApp::register_asset_reload_hook).
+/// An [`AssetEvent::Modified`](crate::AssetEvent::Modified) event is sent after the merge, just like when the
+/// asset is replaced.
+///
+/// ```
+/// # use bevy_asset::{Asset, AssetReloadContext, ReloadAsset};
+/// # use bevy_reflect::TypePath;
+/// #[derive(Asset, TypePath)]
+/// struct Dialogue {
+///     lines: Vec<String>,
+///     // Runtime state that should survive hot reloads.
+///     times_read: u32,
+/// }
+///
+/// impl ReloadAsset for Dialogue {
+///     fn reload(&mut self, reloaded: Self, _context: &AssetReloadContext<Self>) {
+///         self.lines = reloaded.lines;
+///     }
+/// }
+/// ```
+pub trait ReloadAsset: Asset + Sized {
+    /// Merges `reloaded`, the newly loaded version of the asset, into `self`.
+    fn reload(&mut self, reloaded: Self, context: &AssetReloadContext<Self>);
+}
+
+/// Information about an asset being merged by [`ReloadAsset::reload`].
+pub struct AssetReloadContext<'a, A: Asset> {
+    pub(crate) id: AssetId<A>,
+    pub(crate) path: Option<&'a AssetPath<'static>>,
+}
+
+impl<'a, A: Asset> AssetReloadContext<'a, A> {
+    /// The id of the asset being reloaded.
+    pub fn id(&self) -> AssetId<A> {
+        self.id
+    }
+
+    /// The path the asset was loaded from, if it has one.
+    pub fn path(&self) -> Option<&'a AssetPath<'static>> {
+        self.path
+    }
+}
+
+/// The function stored in [`Assets`](crate::Assets) that merges a reloaded asset into the existing one.
+pub(crate) type ReloadHook<A> = fn(&mut A, A, &AssetReloadContext<A>);
@@ -377,7 +377,9 @@ impl AssetInfos {
         world: &mut World,
         sender: &Sender<InternalAssetEvent>,
     ) {
-        loaded_asset.value.insert(loaded_asset_id, world);
+        loaded_asset
+            .value
+            .insert(loaded_asset_id, loaded_asset.path.as_ref(), world);
         let mut loading_deps = loaded_asset.dependencies;
         let mut failed_deps = HashSet::new();
         let mut loading_rec_deps = loading_deps.clone();
@@ -895,6 +895,19 @@ impl AssetServer {
         Some(info.path.as_ref()?.clone())
     }
 
+    /// Returns the name of the layer the asset with the given `id` was read from, if its path's
+    /// source combines several layers, like sources built with
+    /// [`OverlayAssetSourceBuilder`](crate::io::overlay::OverlayAssetSourceBuilder).
+    pub fn get_path_layer(&self, id: impl Into<UntypedAssetId>) -> Option<Arc<str>> {
+        let path = self.get_path(id)?;
+        let source = self.get_source(path.source()).ok()?;
+        let reader = match self.data.mode {
+            AssetServerMode::Unprocessed { .. } => source.reader(),
+            AssetServerMode::Processed { .. } => source.processed_reader().ok()?,
+        };
+        reader.layer(path.path())
+    }
+
     /// Returns the [`AssetServerMode`] this server is currently in.
     pub fn mode(&self) -> AssetServerMode {
         self.data.mode
@@ -17,6 +17,7 @@ pub mod graph {
         StartMainPass,
         MainTransparentPass,
         EndMainPass,
+        Lighting,
         Bloom,
         Tonemapping,
         Fxaa,
@@ -24,6 +24,7 @@ pub mod graph {
         MainOpaquePass,
         MainTransmissivePass,
         MainTransparentPass,
+        OitResolve,
         EndMainPass,
         Taa,
         MotionBlur,
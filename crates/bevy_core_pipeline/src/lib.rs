@@ -19,6 +19,7 @@ pub mod fullscreen_vertex_shader;
 pub mod fxaa;
 pub mod motion_blur;
 pub mod msaa_writeback;
+pub mod oit;
 pub mod prepass;
 mod skybox;
 mod taa;
@@ -59,6 +60,7 @@ use crate::{
     fxaa::FxaaPlugin,
     motion_blur::MotionBlurPlugin,
     msaa_writeback::MsaaWritebackPlugin,
+    oit::OrderIndependentTransparencyPlugin,
     prepass::{DeferredPrepass, DepthPrepass, MotionVectorPrepass, NormalPrepass},
     tonemapping::TonemappingPlugin,
     upscaling::UpscalingPlugin,
@@ -96,6 +98,7 @@ impl Plugin for CorePipelinePlugin {
                 CASPlugin,
                 MotionBlurPlugin,
                 DepthOfFieldPlugin,
+                OrderIndependentTransparencyPlugin,
             ));
     }
 }
@@ -0,0 +1,468 @@
+//! Order-independent transparency.
+//!
+//! Add the [`OrderIndependentTransparencySettings`] component to a 3d camera to draw the
+//! [`Transparent3d`](crate::core_3d::Transparent3d) items that support it without sorting them.
+
+use bevy_app::{App, Plugin};
+use bevy_asset::{load_internal_asset, Handle};
+use bevy_derive::{Deref, DerefMut};
+use bevy_ecs::{
+    prelude::{Component, Entity},
+    query::{QueryItem, With},
+    reflect::ReflectComponent,
+    schedule::IntoSystemConfigs,
+    system::{lifetimeless::Read, Commands, Query, Res, ResMut, Resource},
+    world::{FromWorld, World},
+};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use bevy_render::{
+    camera::ExtractedCamera,
+    extract_component::{ExtractComponent, ExtractComponentPlugin},
+    render_graph::{NodeRunError, RenderGraphApp, RenderGraphContext, ViewNode, ViewNodeRunner},
+    render_resource::{
+        binding_types::{storage_buffer_sized, uniform_buffer},
+        *,
+    },
+    renderer::{RenderContext, RenderDevice, RenderQueue},
+    texture::BevyDefault,
+    view::{ExtractedView, Msaa, ViewTarget},
+    Render, RenderApp, RenderSet,
+};
+use bevy_utils::{prelude::default, tracing::warn, warn_once};
+
+use crate::{
+    core_3d::{
+        graph::{Core3d, Node3d},
+        Camera3d,
+    },
+    fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+};
+
+const OIT_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(4042527984320512);
+const OIT_RESOLVE_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(7698420424769536);
+
+/// The size in bytes of a transparent fragment in the layer buffer: its packed color, and its
+/// depth packed with its alpha.
+const OIT_FRAGMENT_SIZE: u64 = 8;
+
+/// The number of storage buffers the fragment shaders of transparent meshes bind.
+const OIT_STORAGE_BUFFER_COUNT: u32 = 2;
+
+/// Plugin for order-independent transparency.
+pub struct OrderIndependentTransparencyPlugin;
+
+impl Plugin for OrderIndependentTransparencyPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(app, OIT_SHADER_HANDLE, "oit.wgsl", Shader::from_wgsl);
+        load_internal_asset!(
+            app,
+            OIT_RESOLVE_SHADER_HANDLE,
+            "oit_resolve.wgsl",
+            Shader::from_wgsl
+        );
+
+        app.register_type::<OrderIndependentTransparencySettings>()
+            .add_plugins(ExtractComponentPlugin::<OrderIndependentTransparencySettings>::default());
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .add_render_graph_node::<ViewNodeRunner<OitResolveNode>>(Core3d, Node3d::OitResolve)
+            .add_render_graph_edges(
+                Core3d,
+                (
+                    Node3d::MainTransparentPass,
+                    Node3d::OitResolve,
+                    Node3d::EndMainPass,
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        if !OitBuffers::is_supported(render_app.world().resource::<RenderDevice>()) {
+            warn!(
+                "OrderIndependentTransparencyPlugin not loaded. GPU lacks support: \
+                Limits::max_storage_buffers_per_shader_stage is less than {OIT_STORAGE_BUFFER_COUNT}. \
+                Transparent objects will be sorted instead."
+            );
+            return;
+        }
+
+        render_app
+            .init_resource::<OitBuffers>()
+            .init_resource::<OitResolvePipeline>()
+            .init_resource::<SpecializedRenderPipelines<OitResolvePipeline>>()
+            .add_systems(
+                Render,
+                (
+                    prepare_oit_resolve_pipelines.in_set(RenderSet::Prepare),
+                    prepare_oit_buffers.in_set(RenderSet::PrepareResources),
+                    prepare_oit_bind_group.in_set(RenderSet::PrepareBindGroups),
+                ),
+            );
+    }
+}
+
+/// Component to draw the transparent objects of a 3d camera with order-independent transparency.
+///
+/// By default, [`Transparent3d`](crate::core_3d::Transparent3d) items are sorted back to front by
+/// the distance of their origin to the camera, and blended in that order. This gives wrong
+/// results when transparent meshes intersect each other, or when a large mesh is both in front of
+/// and behind another one.
+///
+/// With this component, the transparent fragments of every pixel are instead stored in a buffer
+/// during the main transparent pass, then sorted by depth and blended in a resolve pass, which is
+/// correct regardless of the shape and order of the meshes.
+///
+/// # Usage Notes
+///
+/// Only the materials that support it write to the buffer: with `bevy_pbr`, those are the
+/// [`StandardMaterial`]s and extended materials using the `Blend`, `Premultiplied` and `Add`
+/// alpha modes. Other transparent items are still sorted and blended in the main transparent
+/// pass, under the resolved fragments.
+///
+/// Up to [`Self::layer_count`] fragments are kept for each pixel, and the following ones are
+/// dropped. The buffer holds 8 bytes per layer of every pixel of the render target, and its layer
+/// count is lowered to fit the maximum storage buffer binding size of the device.
+///
+/// Order-independent transparency requires storage buffers in fragment shaders, so it isn't
+/// supported on WebGL 2 and other downlevel devices. The transparent objects of the camera are
+/// sorted and blended as usual on those.
+///
+/// [`StandardMaterial`]: https://docs.rs/bevy/latest/bevy/pbr/struct.StandardMaterial.html
+#[derive(Component, Reflect, ExtractComponent, Clone, Copy, Debug, PartialEq)]
+#[extract_component_filter(With<Camera3d>)]
+#[reflect(Component, Default)]
+pub struct OrderIndependentTransparencySettings {
+    /// The maximum number of transparent fragments stored for each pixel.
+    pub layer_count: u32,
+}
+
+impl Default for OrderIndependentTransparencySettings {
+    fn default() -> Self {
+        Self { layer_count: 8 }
+    }
+}
+
+/// The GPU-side representation of [`OrderIndependentTransparencySettings`] for a view.
+#[derive(Clone, Copy, ShaderType, Default)]
+pub struct OitSettingsUniform {
+    /// The number of layers of the view, after fitting them in the buffer.
+    pub layer_count: u32,
+    /// The number of pixels of the render target of the view.
+    pub layer_stride: u32,
+    /// The width of the render target of the view.
+    pub target_width: u32,
+}
+
+/// The buffers holding the transparent fragments of the views with order-independent
+/// transparency.
+///
+/// They're shared by all views, which are drawn one after the other.
+#[derive(Resource)]
+pub struct OitBuffers {
+    /// The transparent fragments, stored layer after layer.
+    pub layers: Buffer,
+    /// The number of transparent fragments drawn to each pixel.
+    ///
+    /// The resolve pass resets them to zero.
+    pub layer_ids: Buffer,
+    /// The [`OitSettingsUniform`] of every view.
+    pub settings: DynamicUniformBuffer<OitSettingsUniform>,
+    /// The layout of the bind group holding the buffers, which is also used to draw the
+    /// transparent fragments.
+    pub bind_group_layout: BindGroupLayout,
+    /// The bind group holding the buffers, with a dynamic offset into [`Self::settings`].
+    pub bind_group: Option<BindGroup>,
+}
+
+impl OitBuffers {
+    /// Returns whether the device can bind the buffers in fragment shaders.
+    ///
+    /// When it can't, the [`OrderIndependentTransparencyPlugin`] doesn't insert the [`OitBuffers`],
+    /// and views with [`OrderIndependentTransparencySettings`] fall back to sorted blending.
+    pub fn is_supported(render_device: &RenderDevice) -> bool {
+        render_device.limits().max_storage_buffers_per_shader_stage >= OIT_STORAGE_BUFFER_COUNT
+    }
+}
+
+impl FromWorld for OitBuffers {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let bind_group_layout = render_device.create_bind_group_layout(
+            "oit_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    storage_buffer_sized(false, None),
+                    storage_buffer_sized(false, None),
+                    uniform_buffer::<OitSettingsUniform>(true),
+                ),
+            ),
+        );
+
+        Self {
+            layers: create_oit_buffer(render_device, "oit_layers", OIT_FRAGMENT_SIZE),
+            layer_ids: create_oit_buffer(render_device, "oit_layer_ids", 4),
+            settings: DynamicUniformBuffer::default(),
+            bind_group_layout,
+            bind_group: None,
+        }
+    }
+}
+
+fn create_oit_buffer(render_device: &RenderDevice, label: &str, size: u64) -> Buffer {
+    // Buffers are zeroed when they're created, so every layer counter starts at zero.
+    render_device.create_buffer(&BufferDescriptor {
+        label: Some(label),
+        size,
+        usage: BufferUsages::STORAGE,
+        mapped_at_creation: false,
+    })
+}
+
+/// A component attached to each view in the render world that stores the offset of its
+/// [`OitSettingsUniform`] in [`OitBuffers::settings`].
+#[derive(Component, Default, Deref, DerefMut)]
+pub struct ViewOitSettingsOffset(u32);
+
+/// The render pipeline used by a view to resolve its transparent fragments.
+#[derive(Component, Deref, DerefMut)]
+pub struct ViewOitResolvePipeline(CachedRenderPipelineId);
+
+#[derive(Default)]
+struct OitResolveNode;
+
+impl ViewNode for OitResolveNode {
+    type ViewQuery = (
+        Read<ExtractedCamera>,
+        Read<ViewTarget>,
+        Read<ViewOitResolvePipeline>,
+        Read<ViewOitSettingsOffset>,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (camera, view_target, pipeline_id, settings_offset): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let oit_buffers = world.resource::<OitBuffers>();
+        let (Some(pipeline), Some(bind_group)) = (
+            pipeline_cache.get_render_pipeline(**pipeline_id),
+            &oit_buffers.bind_group,
+        ) else {
+            return Ok(());
+        };
+
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("oit_resolve_pass"),
+            color_attachments: &[Some(view_target.get_color_attachment())],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        if let Some(viewport) = camera.viewport.as_ref() {
+            render_pass.set_camera_viewport(viewport);
+        }
+
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, bind_group, &[**settings_offset]);
+        render_pass.draw(0..3, 0..1);
+
+        Ok(())
+    }
+}
+
+#[derive(Resource)]
+pub struct OitResolvePipeline {
+    bind_group_layout: BindGroupLayout,
+}
+
+impl FromWorld for OitResolvePipeline {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            bind_group_layout: world.resource::<OitBuffers>().bind_group_layout.clone(),
+        }
+    }
+}
+
+/// Identifies a specialization of the [`OitResolvePipeline`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct OitResolvePipelineKey {
+    pub hdr: bool,
+    pub msaa_samples: u32,
+    pub layer_count: u32,
+}
+
+impl SpecializedRenderPipeline for OitResolvePipeline {
+    type Key = OitResolvePipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        RenderPipelineDescriptor {
+            label: Some("oit_resolve_pipeline".into()),
+            layout: vec![self.bind_group_layout.clone()],
+            push_constant_ranges: vec![],
+            vertex: fullscreen_shader_vertex_state(),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState {
+                count: key.msaa_samples,
+                ..default()
+            },
+            fragment: Some(FragmentState {
+                shader: OIT_RESOLVE_SHADER_HANDLE,
+                shader_defs: vec![ShaderDefVal::UInt("LAYER_COUNT".into(), key.layer_count)],
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format: if key.hdr {
+                        ViewTarget::TEXTURE_FORMAT_HDR
+                    } else {
+                        TextureFormat::bevy_default()
+                    },
+                    // The shader outputs the premultiplied color of all the transparent fragments
+                    // of the pixel, blended together.
+                    blend: Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+        }
+    }
+}
+
+/// Specializes the [`OitResolvePipeline`] of every view with order-independent transparency.
+pub fn prepare_oit_resolve_pipelines(
+    mut commands: Commands,
+    pipeline_cache: Res<PipelineCache>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<OitResolvePipeline>>,
+    resolve_pipeline: Res<OitResolvePipeline>,
+    msaa: Res<Msaa>,
+    views: Query<(
+        Entity,
+        &ExtractedView,
+        &OrderIndependentTransparencySettings,
+    )>,
+) {
+    for (entity, view, settings) in &views {
+        let key = OitResolvePipelineKey {
+            hdr: view.hdr,
+            msaa_samples: msaa.samples(),
+            layer_count: settings.layer_count.max(1),
+        };
+        let pipeline_id = pipelines.specialize(&pipeline_cache, &resolve_pipeline, key);
+
+        commands
+            .entity(entity)
+            .insert(ViewOitResolvePipeline(pipeline_id));
+    }
+}
+
+/// Grows the [`OitBuffers`] to fit every view with order-independent transparency, and writes
+/// their [`OitSettingsUniform`]s.
+pub fn prepare_oit_buffers(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    mut oit_buffers: ResMut<OitBuffers>,
+    views: Query<(
+        Entity,
+        &ExtractedCamera,
+        &OrderIndependentTransparencySettings,
+    )>,
+) {
+    let max_binding_size = render_device.limits().max_storage_buffer_binding_size as u64;
+
+    let mut settings = Vec::with_capacity(views.iter().len());
+    let (mut layers_size, mut layer_ids_size) = (0, 0);
+    for (entity, camera, view_settings) in &views {
+        let Some(target_size) = camera.physical_target_size else {
+            continue;
+        };
+        let pixel_count = target_size.x as u64 * target_size.y as u64;
+        if pixel_count == 0 {
+            continue;
+        }
+
+        let max_layer_count = max_binding_size / (pixel_count * OIT_FRAGMENT_SIZE);
+        let layer_count = view_settings
+            .layer_count
+            .clamp(1, max_layer_count.max(1) as u32);
+        if layer_count < view_settings.layer_count {
+            warn_once!(
+                "The order-independent transparency buffer of a {}x{} view can hold at most {} \
+                layers on this device, but {} were requested",
+                target_size.x,
+                target_size.y,
+                layer_count,
+                view_settings.layer_count
+            );
+        }
+
+        layers_size = layers_size.max(pixel_count * layer_count as u64 * OIT_FRAGMENT_SIZE);
+        layer_ids_size = layer_ids_size.max(pixel_count * 4);
+        settings.push((
+            entity,
+            OitSettingsUniform {
+                layer_count,
+                layer_stride: pixel_count as u32,
+                target_width: target_size.x,
+            },
+        ));
+    }
+
+    if settings.is_empty() {
+        return;
+    }
+
+    if oit_buffers.layers.size() < layers_size {
+        oit_buffers.layers = create_oit_buffer(&render_device, "oit_layers", layers_size);
+    }
+    if oit_buffers.layer_ids.size() < layer_ids_size {
+        oit_buffers.layer_ids = create_oit_buffer(&render_device, "oit_layer_ids", layer_ids_size);
+    }
+
+    let Some(mut writer) =
+        oit_buffers
+            .settings
+            .get_writer(settings.len(), &render_device, &render_queue)
+    else {
+        return;
+    };
+
+    for (entity, uniform) in settings {
+        commands
+            .entity(entity)
+            .insert(ViewOitSettingsOffset(writer.write(&uniform)));
+    }
+}
+
+/// Prepares the bind group holding the [`OitBuffers`].
+pub fn prepare_oit_bind_group(
+    render_device: Res<RenderDevice>,
+    mut oit_buffers: ResMut<OitBuffers>,
+) {
+    let Some(settings_binding) = oit_buffers.settings.binding() else {
+        return;
+    };
+
+    let bind_group = render_device.create_bind_group(
+        "oit_bind_group",
+        &oit_buffers.bind_group_layout,
+        &BindGroupEntries::sequential((
+            oit_buffers.layers.as_entire_binding(),
+            oit_buffers.layer_ids.as_entire_binding(),
+            settings_binding,
+        )),
+    );
+    oit_buffers.bind_group = Some(bind_group);
+}
@@ -0,0 +1,58 @@
+#define_import_path bevy_core_pipeline::oit
+
+struct OrderIndependentTransparencySettings {
+    // The maximum number of fragments stored for each pixel.
+    layer_count: u32,
+    // The number of pixels of the render target, which is the distance between two layers of the
+    // same pixel in the layer buffer.
+    layer_stride: u32,
+    // The width of the render target, in pixels.
+    target_width: u32,
+}
+
+// Returns the index of the layer counter of the pixel at the given fragment coordinates.
+fn oit_pixel_index(frag_coord: vec2<f32>, settings: OrderIndependentTransparencySettings) -> u32 {
+    let pixel = vec2<u32>(frag_coord);
+    return pixel.y * settings.target_width + pixel.x;
+}
+
+// Packs a premultiplied color and its depth into a fragment of the layer buffer.
+//
+// The color is stored in the shared exponent RGB9E5 format, to keep its high dynamic range. The
+// depth is stored in the upper 24 bits of the second word, above the alpha, so that fragments are
+// sorted by depth when their packed values are compared as integers.
+fn pack_oit_fragment(color: vec4<f32>, depth: f32) -> vec2<u32> {
+    let alpha = u32(saturate(color.a) * 255.0 + 0.5);
+    return vec2(vec3_to_rgb9e5(color.rgb), (bitcast<u32>(depth) & 0xFFFFFF00u) | alpha);
+}
+
+fn unpack_oit_fragment_color(fragment: vec2<u32>) -> vec4<f32> {
+    return vec4(rgb9e5_to_vec3(fragment.x), f32(fragment.y & 0xFFu) / 255.0);
+}
+
+const RGB9E5_MANTISSA_BITS: i32 = 9;
+const RGB9E5_EXP_BIAS: i32 = 15;
+const RGB9E5_MAX: f32 = 65408.0;
+
+// https://registry.khronos.org/OpenGL/extensions/EXT/EXT_texture_shared_exponent.txt
+fn vec3_to_rgb9e5(rgb_in: vec3<f32>) -> u32 {
+    let rgb = clamp(rgb_in, vec3(0.0), vec3(RGB9E5_MAX));
+    let max_component = max(rgb.r, max(rgb.g, rgb.b));
+
+    let floor_log2 = i32((bitcast<u32>(max_component) >> 23u) & 0xFFu) - 127;
+    var shared_exponent = max(-RGB9E5_EXP_BIAS - 1, floor_log2) + 1 + RGB9E5_EXP_BIAS;
+    var denominator = exp2(f32(shared_exponent - RGB9E5_EXP_BIAS - RGB9E5_MANTISSA_BITS));
+    if (u32(floor(max_component / denominator + 0.5)) == 512u) {
+        denominator *= 2.0;
+        shared_exponent += 1;
+    }
+
+    let mantissas = vec3<u32>(floor(rgb / denominator + 0.5));
+    return (u32(shared_exponent) << 27u) | (mantissas.b << 18u) | (mantissas.g << 9u) | mantissas.r;
+}
+
+fn rgb9e5_to_vec3(packed: u32) -> vec3<f32> {
+    let exponent = i32(packed >> 27u) - RGB9E5_EXP_BIAS - RGB9E5_MANTISSA_BITS;
+    let mantissas = vec3(packed, packed >> 9u, packed >> 18u) & vec3(0x1FFu);
+    return vec3<f32>(mantissas) * exp2(f32(exponent));
+}
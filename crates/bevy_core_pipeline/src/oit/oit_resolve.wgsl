@@ -0,0 +1,46 @@
+// Composites the transparent fragments stored by the main transparent pass onto the view target.
+//
+// The fragments of each pixel are sorted back to front and blended with the "over" operator, and
+// the layer counter of the pixel is reset for the next view that uses the layer buffer.
+
+#import bevy_core_pipeline::{
+    fullscreen_vertex_shader::FullscreenVertexOutput,
+    oit::{OrderIndependentTransparencySettings, oit_pixel_index, unpack_oit_fragment_color},
+}
+
+@group(0) @binding(0) var<storage, read_write> oit_layers: array<vec2<u32>>;
+@group(0) @binding(1) var<storage, read_write> oit_layer_ids: array<atomic<u32>>;
+@group(0) @binding(2) var<uniform> oit_settings: OrderIndependentTransparencySettings;
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let pixel_index = oit_pixel_index(in.position.xy, oit_settings);
+    let count = min(atomicExchange(&oit_layer_ids[pixel_index], 0u), oit_settings.layer_count);
+    if (count == 0u) {
+        discard;
+    }
+
+    var fragments: array<vec2<u32>, #{LAYER_COUNT}u>;
+    for (var i = 0u; i < count; i += 1u) {
+        fragments[i] = oit_layers[pixel_index + i * oit_settings.layer_stride];
+    }
+
+    // Insertion sort, by increasing depth. The depth is reversed, so this sorts the fragments
+    // from back to front.
+    for (var i = 1u; i < count; i += 1u) {
+        let fragment = fragments[i];
+        var j = i;
+        while (j > 0u && fragments[j - 1u].y > fragment.y) {
+            fragments[j] = fragments[j - 1u];
+            j -= 1u;
+        }
+        fragments[j] = fragment;
+    }
+
+    var color = vec4(0.0);
+    for (var i = 0u; i < count; i += 1u) {
+        let fragment_color = unpack_oit_fragment_color(fragments[i]);
+        color = fragment_color + color * (1.0 - fragment_color.a);
+    }
+    return color;
+}
@@ -19,7 +19,10 @@ use bevy_ecs::{
     system::{Query, Res, ResMut, Resource},
     world::{FromWorld, World},
 };
-use bevy_pbr::{MeshPipeline, MeshPipelineKey, SetMeshViewBindGroup};
+use bevy_pbr::{
+    MeshPipeline, MeshPipelineKey, ScreenSpaceReflectionsSettings, SetMeshViewBindGroup,
+    ViewDecals, ViewLightCookies,
+};
 use bevy_render::{
     render_asset::{prepare_assets, RenderAssets},
     render_phase::{
@@ -292,6 +295,11 @@ fn queue_line_gizmos_3d(
             Has<MotionVectorPrepass>,
             Has<DeferredPrepass>,
         ),
+        (
+            Has<ScreenSpaceReflectionsSettings>,
+            Has<ViewDecals>,
+            Has<ViewLightCookies>,
+        ),
     )>,
 ) {
     let draw_function = draw_functions.read().get_id::<DrawLineGizmo3d>().unwrap();
@@ -301,6 +309,7 @@ fn queue_line_gizmos_3d(
         mut transparent_phase,
         render_layers,
         (normal_prepass, depth_prepass, motion_vector_prepass, deferred_prepass),
+        (ssr, decals, light_cookies),
     ) in &mut views
     {
         let render_layers = render_layers.copied().unwrap_or_default();
@@ -324,6 +333,17 @@ fn queue_line_gizmos_3d(
             view_key |= MeshPipelineKey::DEFERRED_PREPASS;
         }
 
+        // Only needed so that the layout matches the mesh view bind group of the view.
+        if ssr {
+            view_key |= MeshPipelineKey::SCREEN_SPACE_REFLECTIONS;
+        }
+        if decals {
+            view_key |= MeshPipelineKey::DECALS;
+        }
+        if light_cookies {
+            view_key |= MeshPipelineKey::LIGHT_COOKIES;
+        }
+
         for (entity, handle, config) in &line_gizmos {
             if !config.render_layers.intersects(&render_layers) {
                 continue;
@@ -375,6 +395,11 @@ fn queue_line_joint_gizmos_3d(
             Has<MotionVectorPrepass>,
             Has<DeferredPrepass>,
         ),
+        (
+            Has<ScreenSpaceReflectionsSettings>,
+            Has<ViewDecals>,
+            Has<ViewLightCookies>,
+        ),
     )>,
 ) {
     let draw_function = draw_functions
@@ -387,6 +412,7 @@ fn queue_line_joint_gizmos_3d(
         mut transparent_phase,
         render_layers,
         (normal_prepass, depth_prepass, motion_vector_prepass, deferred_prepass),
+        (ssr, decals, light_cookies),
     ) in &mut views
     {
         let render_layers = render_layers.copied().unwrap_or_default();
@@ -410,6 +436,17 @@ fn queue_line_joint_gizmos_3d(
             view_key |= MeshPipelineKey::DEFERRED_PREPASS;
         }
 
+        // Only needed so that the layout matches the mesh view bind group of the view.
+        if ssr {
+            view_key |= MeshPipelineKey::SCREEN_SPACE_REFLECTIONS;
+        }
+        if decals {
+            view_key |= MeshPipelineKey::DECALS;
+        }
+        if light_cookies {
+            view_key |= MeshPipelineKey::LIGHT_COOKIES;
+        }
+
         for (entity, handle, config) in &line_gizmos {
             if !config.render_layers.intersects(&render_layers) {
                 continue;
@@ -15,8 +15,10 @@ use bevy_animation::AnimationClip;
 use bevy_utils::HashMap;
 
 mod loader;
+mod saver;
 mod vertex_attributes;
 pub use loader::*;
+pub use saver::*;
 
 use bevy_app::prelude::*;
 use bevy_asset::{Asset, AssetApp, Handle};
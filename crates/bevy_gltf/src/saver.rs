@@ -0,0 +1,651 @@
+use crate::{Gltf, GltfLoader, GltfLoaderSettings};
+use bevy_asset::{
+    io::Writer,
+    saver::{AssetSaver, SavedAsset},
+    Asset, AssetId, Assets, AsyncWriteExt, Handle,
+};
+use bevy_color::LinearRgba;
+use bevy_core::Name;
+use bevy_ecs::world::{EntityRef, World};
+use bevy_hierarchy::{Children, Parent};
+use bevy_pbr::StandardMaterial;
+use bevy_render::{
+    alpha::AlphaMode,
+    mesh::{Indices, Mesh, VertexAttributeValues},
+    render_resource::PrimitiveTopology,
+};
+use bevy_scene::Scene;
+use bevy_transform::components::Transform;
+use bevy_utils::{tracing::warn, HashMap};
+use serde_json::{json, Value};
+use thiserror::Error;
+
+/// Saves a [`Gltf`] asset as a binary glTF (`.glb`) file that can be read back by the [`GltfLoader`].
+///
+/// The scenes of the [`Gltf`] are exported with their hierarchy, transforms and names. Every entity
+/// of a scene holding a `Handle<Mesh>` is exported as a node with a single primitive, using the
+/// entity's `Handle<StandardMaterial>` as material if there is one.
+///
+/// Meshes, materials and scenes are resolved through the labeled assets of the [`Gltf`]: handles
+/// pointing to a labeled path are looked up by their label, which is also used as the name of the
+/// exported object, while other handles are matched by id and exported with a generated name
+/// (`Scene0`, `Mesh0`, `Material0`, ...). Material textures, skins, cameras, lights and animations
+/// are not exported, a warning is logged for every material whose textures are dropped.
+///
+/// Scenes and meshes that aren't part of a [`Gltf`] asset can be exported with
+/// [`GltfSaver::save_scene`] and [`GltfSaver::save_mesh`].
+#[derive(Clone, Default)]
+pub struct GltfSaver;
+
+/// An error that occurs when saving a glTF file.
+#[derive(Error, Debug)]
+pub enum GltfSaverError {
+    /// An IO error.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    /// The glTF JSON couldn't be serialized.
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    /// A handle doesn't point to a labeled asset of the saved [`Gltf`], neither by label nor by id.
+    #[error("the {0} asset isn't a labeled asset of the saved glTF")]
+    MissingLabeledAsset(&'static str),
+    /// A handle doesn't point to an asset of the [`Assets`] collection it is looked up in.
+    #[error("the {0} asset doesn't exist")]
+    MissingAsset(&'static str),
+}
+
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const GLB_CHUNK_JSON: u32 = 0x4E4F_534A;
+const GLB_CHUNK_BIN: u32 = 0x004E_4942;
+
+const COMPONENT_UNSIGNED_SHORT: u32 = 5123;
+const COMPONENT_UNSIGNED_INT: u32 = 5125;
+const COMPONENT_FLOAT: u32 = 5126;
+
+const TARGET_ARRAY_BUFFER: u32 = 34962;
+const TARGET_ELEMENT_ARRAY_BUFFER: u32 = 34963;
+
+impl AssetSaver for GltfSaver {
+    type Asset = Gltf;
+    type Settings = ();
+    type OutputLoader = GltfLoader;
+    type Error = GltfSaverError;
+
+    async fn save<'a>(
+        &'a self,
+        writer: &'a mut Writer,
+        gltf: SavedAsset<'a, Self::Asset>,
+        _settings: &'a Self::Settings,
+    ) -> Result<GltfLoaderSettings, Self::Error> {
+        let mut exporter = GltfExporter::new(ExportSource::Gltf(&gltf));
+        for handle in &gltf.scenes {
+            let (scene, name) =
+                exporter.resolve(handle, "scene", "Scene", exporter.scenes.len())?;
+            let is_default = gltf.default_scene.as_ref() == Some(handle);
+            exporter.export_scene(scene, name, is_default)?;
+        }
+        writer.write_all(&exporter.finish()?).await?;
+        Ok(GltfLoaderSettings::default())
+    }
+}
+
+impl GltfSaver {
+    /// Exports `scene` as the default scene of a binary glTF file, returning the bytes of the file.
+    ///
+    /// The meshes and materials of the scene's entities are looked up in `meshes` and `materials`, and
+    /// exported with generated names.
+    pub fn save_scene(
+        scene: &Scene,
+        meshes: &Assets<Mesh>,
+        materials: &Assets<StandardMaterial>,
+    ) -> Result<Vec<u8>, GltfSaverError> {
+        let mut exporter = GltfExporter::new(ExportSource::Assets { meshes, materials });
+        exporter.export_scene(scene, "Scene0".to_string(), true)?;
+        exporter.finish()
+    }
+
+    /// Exports a mesh, using `material` if there is one, as a binary glTF file holding a scene with
+    /// a single node, returning the bytes of the file.
+    ///
+    /// The handles are looked up in `meshes` and `materials`.
+    pub fn save_mesh(
+        mesh: &Handle<Mesh>,
+        material: Option<&Handle<StandardMaterial>>,
+        meshes: &Assets<Mesh>,
+        materials: &Assets<StandardMaterial>,
+    ) -> Result<Vec<u8>, GltfSaverError> {
+        let mut exporter = GltfExporter::new(ExportSource::Assets { meshes, materials });
+        let mesh = exporter.export_mesh(mesh, material)?;
+        exporter.nodes.push(json!({ "mesh": mesh }));
+        exporter
+            .scenes
+            .push(json!({ "name": "Scene0", "nodes": [0] }));
+        exporter.default_scene = Some(0);
+        exporter.finish()
+    }
+}
+
+/// Where the [`GltfExporter`] looks up the assets that handles point to.
+#[derive(Clone, Copy)]
+enum ExportSource<'a, 'b> {
+    /// The labeled assets of a saved [`Gltf`].
+    Gltf(&'b SavedAsset<'a, Gltf>),
+    /// The asset collections of an app.
+    Assets {
+        meshes: &'b Assets<Mesh>,
+        materials: &'b Assets<StandardMaterial>,
+    },
+}
+
+/// Accumulates the glTF JSON objects and the binary buffer of a GLB file.
+struct GltfExporter<'a, 'b> {
+    source: ExportSource<'a, 'b>,
+    buffer: Vec<u8>,
+    buffer_views: Vec<Value>,
+    accessors: Vec<Value>,
+    meshes: Vec<Value>,
+    materials: Vec<Value>,
+    nodes: Vec<Value>,
+    scenes: Vec<Value>,
+    default_scene: Option<usize>,
+    uses_unlit: bool,
+    mesh_indices: HashMap<(AssetId<Mesh>, Option<AssetId<StandardMaterial>>), usize>,
+    material_indices: HashMap<AssetId<StandardMaterial>, usize>,
+}
+
+impl<'a, 'b> GltfExporter<'a, 'b> {
+    fn new(source: ExportSource<'a, 'b>) -> Self {
+        Self {
+            source,
+            buffer: Vec::new(),
+            buffer_views: Vec::new(),
+            accessors: Vec::new(),
+            meshes: Vec::new(),
+            materials: Vec::new(),
+            nodes: Vec::new(),
+            scenes: Vec::new(),
+            default_scene: None,
+            uses_unlit: false,
+            mesh_indices: HashMap::default(),
+            material_indices: HashMap::default(),
+        }
+    }
+
+    fn export_scene(
+        &mut self,
+        scene: &Scene,
+        name: String,
+        is_default: bool,
+    ) -> Result<(), GltfSaverError> {
+        let mut nodes = Vec::new();
+        for entity in scene.world.iter_entities() {
+            if !entity.contains::<Parent>() {
+                nodes.push(self.export_node(&scene.world, entity)?);
+            }
+        }
+
+        if is_default {
+            self.default_scene = Some(self.scenes.len());
+        }
+        self.scenes.push(json!({ "name": name, "nodes": nodes }));
+        Ok(())
+    }
+
+    fn export_node(&mut self, world: &World, entity: EntityRef) -> Result<usize, GltfSaverError> {
+        let mut node = json!({});
+        if let Some(name) = entity.get::<Name>() {
+            node["name"] = json!(name.as_str());
+        }
+        if let Some(transform) = entity.get::<Transform>() {
+            node["translation"] = json!(transform.translation.to_array());
+            node["rotation"] = json!(transform.rotation.to_array());
+            node["scale"] = json!(transform.scale.to_array());
+        }
+        if let Some(mesh) = entity.get::<Handle<Mesh>>() {
+            let material = entity.get::<Handle<StandardMaterial>>();
+            node["mesh"] = json!(self.export_mesh(mesh, material)?);
+        }
+        if let Some(children) = entity.get::<Children>() {
+            let mut child_nodes = Vec::new();
+            for child in children.iter() {
+                if let Some(child) = world.get_entity(*child) {
+                    child_nodes.push(self.export_node(world, child)?);
+                }
+            }
+            node["children"] = json!(child_nodes);
+        }
+        self.nodes.push(node);
+        Ok(self.nodes.len() - 1)
+    }
+
+    fn export_mesh(
+        &mut self,
+        mesh_handle: &Handle<Mesh>,
+        material_handle: Option<&Handle<StandardMaterial>>,
+    ) -> Result<usize, GltfSaverError> {
+        let key = (mesh_handle.id(), material_handle.map(Handle::id));
+        if let Some(index) = self.mesh_indices.get(&key) {
+            return Ok(*index);
+        }
+
+        let (mesh, name) = match self.source {
+            ExportSource::Gltf(_) => {
+                self.resolve(mesh_handle, "mesh", "Mesh", self.meshes.len())?
+            }
+            ExportSource::Assets { meshes, .. } => (
+                meshes
+                    .get(mesh_handle)
+                    .ok_or(GltfSaverError::MissingAsset("mesh"))?,
+                format!("Mesh{}", self.meshes.len()),
+            ),
+        };
+
+        let mut attributes = json!({});
+        for (semantic, attribute) in [
+            ("POSITION", Mesh::ATTRIBUTE_POSITION),
+            ("NORMAL", Mesh::ATTRIBUTE_NORMAL),
+            ("TANGENT", Mesh::ATTRIBUTE_TANGENT),
+            ("TEXCOORD_0", Mesh::ATTRIBUTE_UV_0),
+            ("TEXCOORD_1", Mesh::ATTRIBUTE_UV_1),
+            ("COLOR_0", Mesh::ATTRIBUTE_COLOR),
+        ] {
+            if let Some(values) = mesh.attribute(attribute) {
+                if let Some(accessor) = self.export_vertex_attribute(values) {
+                    attributes[semantic] = json!(accessor);
+                }
+            }
+        }
+
+        let mode = match mesh.primitive_topology() {
+            PrimitiveTopology::PointList => 0,
+            PrimitiveTopology::LineList => 1,
+            PrimitiveTopology::LineStrip => 3,
+            PrimitiveTopology::TriangleList => 4,
+            PrimitiveTopology::TriangleStrip => 5,
+        };
+        let mut primitive = json!({ "attributes": attributes, "mode": mode });
+        if let Some(indices) = mesh.indices() {
+            primitive["indices"] = json!(self.export_indices(indices));
+        }
+        if let Some(material_handle) = material_handle {
+            primitive["material"] = json!(self.export_material(material_handle)?);
+        }
+
+        self.meshes.push(json!({
+            "name": name,
+            "primitives": [primitive],
+        }));
+        let index = self.meshes.len() - 1;
+        self.mesh_indices.insert(key, index);
+        Ok(index)
+    }
+
+    fn export_material(
+        &mut self,
+        handle: &Handle<StandardMaterial>,
+    ) -> Result<usize, GltfSaverError> {
+        if let Some(index) = self.material_indices.get(&handle.id()) {
+            return Ok(*index);
+        }
+        let (material, name) = match self.source {
+            ExportSource::Gltf(_) => {
+                self.resolve(handle, "material", "Material", self.materials.len())?
+            }
+            ExportSource::Assets { materials, .. } => (
+                materials
+                    .get(handle)
+                    .ok_or(GltfSaverError::MissingAsset("material"))?,
+                format!("Material{}", self.materials.len()),
+            ),
+        };
+        let has_textures = material.base_color_texture.is_some()
+            || material.emissive_texture.is_some()
+            || material.metallic_roughness_texture.is_some()
+            || material.normal_map_texture.is_some()
+            || material.occlusion_texture.is_some();
+        if has_textures {
+            warn!("The textures of material {name} aren't supported by the glTF saver and are dropped");
+        }
+
+        let emissive = LinearRgba::from(material.emissive);
+        let mut json_material = json!({
+            "name": name,
+            "pbrMetallicRoughness": {
+                "baseColorFactor": LinearRgba::from(material.base_color).to_f32_array(),
+                "metallicFactor": material.metallic,
+                "roughnessFactor": material.perceptual_roughness,
+            },
+            "emissiveFactor": [emissive.red, emissive.green, emissive.blue],
+            "doubleSided": material.double_sided,
+        });
+        match material.alpha_mode {
+            AlphaMode::Opaque => {}
+            AlphaMode::Mask(cutoff) => {
+                json_material["alphaMode"] = json!("MASK");
+                json_material["alphaCutoff"] = json!(cutoff);
+            }
+            _ => json_material["alphaMode"] = json!("BLEND"),
+        }
+        if material.unlit {
+            json_material["extensions"] = json!({ "KHR_materials_unlit": {} });
+            self.uses_unlit = true;
+        }
+
+        self.materials.push(json_material);
+        let index = self.materials.len() - 1;
+        self.material_indices.insert(handle.id(), index);
+        Ok(index)
+    }
+
+    /// Finds the labeled asset of the saved [`Gltf`] a handle points to, along with the name it is
+    /// exported with.
+    ///
+    /// Handles without a labeled path are matched against the handles of the labeled assets, and
+    /// get a name generated from `prefix` and the `index` of the exported object.
+    fn resolve<A: Asset>(
+        &self,
+        handle: &Handle<A>,
+        kind: &'static str,
+        prefix: &str,
+        index: usize,
+    ) -> Result<(&'b A, String), GltfSaverError> {
+        let ExportSource::Gltf(gltf) = self.source else {
+            return Err(GltfSaverError::MissingLabeledAsset(kind));
+        };
+        let label = match handle.path().and_then(|path| path.label()) {
+            Some(label) => gltf.iter_labels().find(|other| *other == label),
+            None => gltf.iter_labels().find(|label| {
+                gltf.get_untyped_handle(*label)
+                    .is_some_and(|other| other.id() == handle.id().untyped())
+            }),
+        };
+        let (label, asset) = label
+            .and_then(|label| Some((label, gltf.get_labeled::<A, _>(label)?)))
+            .ok_or(GltfSaverError::MissingLabeledAsset(kind))?;
+        let name = match handle.path() {
+            Some(path) if path.label().is_some() => label.to_string(),
+            _ => format!("{prefix}{index}"),
+        };
+        Ok((asset.get(), name))
+    }
+
+    /// Exports the attribute if glTF supports its format, returning the index of its accessor.
+    fn export_vertex_attribute(&mut self, values: &VertexAttributeValues) -> Option<usize> {
+        let accessor_type = match values {
+            VertexAttributeValues::Float32x2(_) => "VEC2",
+            VertexAttributeValues::Float32x3(_) => "VEC3",
+            VertexAttributeValues::Float32x4(_) => "VEC4",
+            _ => return None,
+        };
+        let buffer_view = self.push_buffer_view(values.get_bytes(), TARGET_ARRAY_BUFFER);
+        let mut accessor = json!({
+            "bufferView": buffer_view,
+            "componentType": COMPONENT_FLOAT,
+            "count": values.len(),
+            "type": accessor_type,
+        });
+        // Positions are required to have bounds
+        if let VertexAttributeValues::Float32x3(positions) = values {
+            let (min, max) = positions.iter().fold(
+                ([f32::MAX; 3], [f32::MIN; 3]),
+                |(mut min, mut max), position| {
+                    for axis in 0..3 {
+                        min[axis] = min[axis].min(position[axis]);
+                        max[axis] = max[axis].max(position[axis]);
+                    }
+                    (min, max)
+                },
+            );
+            if !positions.is_empty() {
+                accessor["min"] = json!(min);
+                accessor["max"] = json!(max);
+            }
+        }
+        self.accessors.push(accessor);
+        Some(self.accessors.len() - 1)
+    }
+
+    fn export_indices(&mut self, indices: &Indices) -> usize {
+        let (bytes, component_type) = match indices {
+            Indices::U16(indices) => (
+                indices
+                    .iter()
+                    .flat_map(|index| index.to_le_bytes())
+                    .collect::<Vec<_>>(),
+                COMPONENT_UNSIGNED_SHORT,
+            ),
+            Indices::U32(indices) => (
+                indices
+                    .iter()
+                    .flat_map(|index| index.to_le_bytes())
+                    .collect(),
+                COMPONENT_UNSIGNED_INT,
+            ),
+        };
+        let buffer_view = self.push_buffer_view(&bytes, TARGET_ELEMENT_ARRAY_BUFFER);
+        self.accessors.push(json!({
+            "bufferView": buffer_view,
+            "componentType": component_type,
+            "count": indices.len(),
+            "type": "SCALAR",
+        }));
+        self.accessors.len() - 1
+    }
+
+    fn push_buffer_view(&mut self, bytes: &[u8], target: u32) -> usize {
+        // Accessors must be aligned to the size of their components
+        self.buffer.resize(self.buffer.len().next_multiple_of(4), 0);
+        self.buffer_views.push(json!({
+            "buffer": 0,
+            "byteOffset": self.buffer.len(),
+            "byteLength": bytes.len(),
+            "target": target,
+        }));
+        self.buffer.extend_from_slice(bytes);
+        self.buffer_views.len() - 1
+    }
+
+    /// Writes the GLB container holding the JSON document and the binary buffer.
+    fn finish(mut self) -> Result<Vec<u8>, GltfSaverError> {
+        self.buffer.resize(self.buffer.len().next_multiple_of(4), 0);
+
+        let mut document = json!({
+            "asset": { "version": "2.0", "generator": "Bevy" },
+            "scenes": self.scenes,
+            "nodes": self.nodes,
+            "meshes": self.meshes,
+            "materials": self.materials,
+            "accessors": self.accessors,
+            "bufferViews": self.buffer_views,
+        });
+        if !self.buffer.is_empty() {
+            document["buffers"] = json!([{ "byteLength": self.buffer.len() }]);
+        }
+        if let Some(default_scene) = self.default_scene {
+            document["scene"] = json!(default_scene);
+        }
+        if self.uses_unlit {
+            document["extensionsUsed"] = json!(["KHR_materials_unlit"]);
+        }
+        let mut json = serde_json::to_vec(&document)?;
+        json.resize(json.len().next_multiple_of(4), b' ');
+
+        let mut chunks = vec![(GLB_CHUNK_JSON, json)];
+        if !self.buffer.is_empty() {
+            chunks.push((GLB_CHUNK_BIN, self.buffer));
+        }
+        let length = 12 + chunks.iter().map(|(_, data)| 8 + data.len()).sum::<usize>();
+
+        let mut bytes = Vec::with_capacity(length);
+        bytes.extend_from_slice(&GLB_MAGIC.to_le_bytes());
+        bytes.extend_from_slice(&2u32.to_le_bytes());
+        bytes.extend_from_slice(&(length as u32).to_le_bytes());
+        for (chunk_type, data) in chunks {
+            bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            bytes.extend_from_slice(&chunk_type.to_le_bytes());
+            bytes.extend_from_slice(&data);
+        }
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_asset::{transformer::TransformedAsset, LoadedAsset};
+    use bevy_color::{palettes::basic::RED, Color};
+    use bevy_hierarchy::BuildWorldChildren;
+    use bevy_render::render_asset::RenderAssetUsages;
+
+    const SCENE: Handle<Scene> = Handle::weak_from_u128(188416240452532396457718066585430165051);
+    const MESH: Handle<Mesh> = Handle::weak_from_u128(331009262540153717366372898811498386779);
+    const MATERIAL: Handle<StandardMaterial> =
+        Handle::weak_from_u128(267303391592417312548113409281925412212);
+
+    fn gltf_asset(mesh: Handle<Mesh>) -> TransformedAsset<Gltf> {
+        let mut world = World::new();
+        world
+            .spawn((Name::new("Root"), Transform::from_xyz(1., 2., 3.)))
+            .with_children(|parent| {
+                parent.spawn((Transform::default(), mesh, MATERIAL));
+            });
+
+        let gltf = Gltf {
+            scenes: vec![SCENE],
+            named_scenes: HashMap::default(),
+            meshes: Vec::new(),
+            named_meshes: HashMap::default(),
+            materials: vec![MATERIAL],
+            named_materials: HashMap::default(),
+            nodes: Vec::new(),
+            named_nodes: HashMap::default(),
+            default_scene: Some(SCENE),
+            #[cfg(feature = "bevy_animation")]
+            animations: Vec::new(),
+            #[cfg(feature = "bevy_animation")]
+            named_animations: HashMap::default(),
+            source: None,
+        };
+        let mut asset =
+            TransformedAsset::<Gltf>::from_loaded(LoadedAsset::from(gltf).into()).unwrap();
+        asset.insert_labeled("Scene0", SCENE, LoadedAsset::from(Scene::new(world)));
+        asset.insert_labeled(
+            "Mesh0/Primitive0",
+            MESH,
+            LoadedAsset::from(
+                Mesh::new(
+                    PrimitiveTopology::TriangleList,
+                    RenderAssetUsages::default(),
+                )
+                .with_inserted_attribute(
+                    Mesh::ATTRIBUTE_POSITION,
+                    vec![[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]],
+                )
+                .with_inserted_indices(Indices::U16(vec![0, 1, 2])),
+            ),
+        );
+        asset.insert_labeled(
+            "Material0",
+            MATERIAL,
+            LoadedAsset::from(StandardMaterial {
+                base_color: RED.into(),
+                unlit: true,
+                ..Default::default()
+            }),
+        );
+        asset
+    }
+
+    fn save(asset: &TransformedAsset<Gltf>) -> Result<Vec<u8>, GltfSaverError> {
+        let mut bytes = Vec::new();
+        bevy_tasks::block_on(GltfSaver.save(&mut bytes, SavedAsset::from_transformed(asset), &()))?;
+        Ok(bytes)
+    }
+
+    #[test]
+    fn unlabeled_handles_get_generated_names() {
+        let bytes = save(&gltf_asset(MESH)).unwrap();
+        let gltf::Gltf { document, blob } = gltf::Gltf::from_slice(&bytes).unwrap();
+
+        let scene = document.default_scene().unwrap();
+        assert_eq!(scene.name(), Some("Scene0"));
+        let roots = scene.nodes().collect::<Vec<_>>();
+        assert_eq!(roots.len(), 1);
+        assert_eq!(roots[0].name(), Some("Root"));
+        assert_eq!(roots[0].transform().decomposed().0, [1., 2., 3.]);
+
+        let child = roots[0].children().next().unwrap();
+        let mesh = child.mesh().unwrap();
+        assert_eq!(mesh.name(), Some("Mesh0"));
+        let primitive = mesh.primitives().next().unwrap();
+        let material = primitive.material();
+        assert_eq!(material.name(), Some("Material0"));
+        assert!(material.unlit());
+        assert_eq!(
+            material.pbr_metallic_roughness().base_color_factor(),
+            [1., 0., 0., 1.]
+        );
+
+        let reader = primitive.reader(|_| blob.as_deref());
+        assert_eq!(
+            reader.read_positions().unwrap().collect::<Vec<_>>(),
+            vec![[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]]
+        );
+        assert_eq!(
+            reader
+                .read_indices()
+                .unwrap()
+                .into_u32()
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn missing_assets_are_an_error() {
+        let mesh = Handle::weak_from_u128(87348910283746501238475610293847561029);
+        assert!(matches!(
+            save(&gltf_asset(mesh)),
+            Err(GltfSaverError::MissingLabeledAsset("mesh"))
+        ));
+    }
+
+    #[test]
+    fn save_scene_from_assets() {
+        let mut meshes = Assets::<Mesh>::default();
+        let mut materials = Assets::<StandardMaterial>::default();
+        let mesh = meshes.add(
+            Mesh::new(
+                PrimitiveTopology::TriangleList,
+                RenderAssetUsages::default(),
+            )
+            .with_inserted_attribute(
+                Mesh::ATTRIBUTE_POSITION,
+                vec![[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]],
+            ),
+        );
+        let material = materials.add(StandardMaterial::from(Color::from(RED)));
+
+        let mut world = World::new();
+        world.spawn((Name::new("Cube"), mesh.clone(), material.clone()));
+        let bytes = GltfSaver::save_scene(&Scene::new(world), &meshes, &materials).unwrap();
+        let gltf::Gltf { document, blob } = gltf::Gltf::from_slice(&bytes).unwrap();
+
+        let node = document.default_scene().unwrap().nodes().next().unwrap();
+        assert_eq!(node.name(), Some("Cube"));
+        let primitive = node.mesh().unwrap().primitives().next().unwrap();
+        assert_eq!(primitive.material().name(), Some("Material0"));
+        let reader = primitive.reader(|_| blob.as_deref());
+        assert_eq!(reader.read_positions().unwrap().count(), 3);
+
+        let bytes = GltfSaver::save_mesh(&mesh, None, &meshes, &materials).unwrap();
+        let gltf::Gltf { document, .. } = gltf::Gltf::from_slice(&bytes).unwrap();
+        let node = document.default_scene().unwrap().nodes().next().unwrap();
+        assert_eq!(node.mesh().unwrap().name(), Some("Mesh0"));
+
+        assert!(matches!(
+            GltfSaver::save_mesh(&MESH, None, &meshes, &materials),
+            Err(GltfSaverError::MissingAsset("mesh"))
+        ));
+    }
+}
@@ -24,6 +24,8 @@ use bevy_app::{Plugin, PluginGroup, PluginGroupBuilder};
 /// * [`TextPlugin`](crate::text::TextPlugin) - with feature `bevy_text`
 /// * [`UiPlugin`](crate::ui::UiPlugin) - with feature `bevy_ui`
 /// * [`PbrPlugin`](crate::pbr::PbrPlugin) - with feature `bevy_pbr`
+/// * [`PickingPlugin`](crate::picking::PickingPlugin) - with feature `bevy_picking`
+/// * [`PointerInputPlugin`](crate::picking::input::PointerInputPlugin) - with feature `bevy_picking`
 /// * [`GltfPlugin`](crate::gltf::GltfPlugin) - with feature `bevy_gltf`
 /// * [`AudioPlugin`](crate::audio::AudioPlugin) - with feature `bevy_audio`
 /// * [`GilrsPlugin`](crate::gilrs::GilrsPlugin) - with feature `bevy_gilrs`
@@ -110,6 +112,13 @@ impl PluginGroup for DefaultPlugins {
             group = group.add(bevy_pbr::PbrPlugin::default());
         }
 
+        #[cfg(feature = "bevy_picking")]
+        {
+            group = group
+                .add(bevy_picking::PickingPlugin)
+                .add(bevy_picking::input::PointerInputPlugin::default());
+        }
+
         // NOTE: Load this after renderer initialization so that it knows about the supported
         // compressed texture formats
         #[cfg(feature = "bevy_gltf")]
@@ -35,6 +35,10 @@ pub use crate::pbr::prelude::*;
 #[cfg(feature = "bevy_render")]
 pub use crate::render::prelude::*;
 
+#[doc(hidden)]
+#[cfg(feature = "bevy_picking")]
+pub use crate::picking::prelude::*;
+
 #[doc(hidden)]
 #[cfg(feature = "bevy_scene")]
 pub use crate::scene::prelude::*;
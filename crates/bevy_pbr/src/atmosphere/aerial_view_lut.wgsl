@@ -0,0 +1,57 @@
+#import bevy_pbr::atmosphere::{
+    bindings::settings,
+    functions::{raymarch_atmosphere, distance_to_atmosphere_boundary, uv_to_ray_dir, view_position},
+}
+
+@group(1) @binding(0) var transmittance_lut: texture_2d<f32>;
+@group(1) @binding(1) var multiscattering_lut: texture_2d<f32>;
+@group(1) @binding(2) var aerial_view_lut_out: texture_storage_3d<rgba16float, write>;
+
+// Computes the light scattered towards the camera and the transmittance between the camera and
+// points at increasing distances along the view frustum. Slice `i` of the LUT stores the
+// scattering up to `(i + 0.5) / N` of the maximum distance.
+@compute
+@workgroup_size(16, 16, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    if any(global_id.xy >= settings.aerial_view_lut_size.xy) {
+        return;
+    }
+
+    let uv = (vec2<f32>(global_id.xy) + 0.5) / vec2<f32>(settings.aerial_view_lut_size.xy);
+    let ray_dir = uv_to_ray_dir(uv);
+    let position = view_position();
+    let t_max = distance_to_atmosphere_boundary(position.y, ray_dir.y);
+    let slice_count = settings.aerial_view_lut_size.z;
+
+    var inscattering = vec3(0.0);
+    var transmittance = vec3(1.0);
+    var t_start = 0.0;
+    for (var slice = 0u; slice < slice_count; slice += 1u) {
+        let t_end = min(
+            (f32(slice) + 0.5) / f32(slice_count) * settings.aerial_view_lut_max_distance,
+            t_max
+        );
+
+        if t_end > t_start {
+            let result = raymarch_atmosphere(
+                transmittance_lut,
+                multiscattering_lut,
+                position,
+                ray_dir,
+                t_start,
+                t_end,
+                settings.aerial_view_lut_samples
+            );
+            inscattering += transmittance * result.inscattering;
+            transmittance *= result.transmittance;
+            t_start = t_end;
+        }
+
+        let mean_transmittance = dot(transmittance, vec3(1.0 / 3.0));
+        textureStore(
+            aerial_view_lut_out,
+            vec3(global_id.xy, slice),
+            vec4(inscattering, mean_transmittance)
+        );
+    }
+}
@@ -0,0 +1,13 @@
+#define_import_path bevy_pbr::atmosphere::bindings
+
+#import bevy_render::view::View
+#import bevy_pbr::{
+    mesh_view_types::Lights,
+    atmosphere::types::{Atmosphere, AtmosphereSettings},
+}
+
+@group(0) @binding(0) var<uniform> atmosphere: Atmosphere;
+@group(0) @binding(1) var<uniform> settings: AtmosphereSettings;
+@group(0) @binding(2) var<uniform> view: View;
+@group(0) @binding(3) var<uniform> lights: Lights;
+@group(0) @binding(4) var lut_sampler: sampler;
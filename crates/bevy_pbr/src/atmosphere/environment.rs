@@ -0,0 +1,228 @@
+use bevy_asset::Assets;
+use bevy_derive::{Deref, DerefMut};
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    query::Changed,
+    system::{Commands, Query, Res, ResMut, Resource},
+};
+use bevy_render::{
+    render_asset::{RenderAssetUsages, RenderAssets},
+    render_resource::*,
+    renderer::{RenderDevice, RenderQueue},
+    texture::{GpuImage, Image, ImageSampler, TextureFormatPixelInfo},
+};
+use bevy_utils::prelude::default;
+
+use crate::environment_map::EnvironmentMapLight;
+
+use super::{
+    resources::{AtmospherePipelines, AtmosphereTextures, ATMOSPHERE_TEXTURE_FORMAT},
+    AtmosphereEnvironmentMap, AtmosphereEnvironmentMapLight,
+};
+
+/// The size of each face of the diffuse cubemap, in texels. Diffuse light varies slowly, so it
+/// doesn't need more.
+const DIFFUSE_MAP_SIZE: u32 = 32;
+
+/// The number of directions of the sky averaged by each texel of the environment maps.
+const ENVIRONMENT_MAP_SAMPLE_COUNT: u32 = 64;
+
+/// Creates the cubemaps that the sky is rendered into, and lights the camera with them, whenever
+/// an [`AtmosphereEnvironmentMapLight`] is added or changed.
+pub(super) fn add_atmosphere_environment_maps(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    cameras: Query<
+        (Entity, &AtmosphereEnvironmentMapLight),
+        Changed<AtmosphereEnvironmentMapLight>,
+    >,
+) {
+    for (entity, environment_map_light) in &cameras {
+        let size = environment_map_light.size.max(1).next_power_of_two();
+        let diffuse_map = images.add(create_environment_cubemap(DIFFUSE_MAP_SIZE, 1));
+        let specular_map = images.add(create_environment_cubemap(size, size.ilog2() + 1));
+
+        commands.entity(entity).insert((
+            EnvironmentMapLight {
+                diffuse_map: diffuse_map.clone(),
+                specular_map: specular_map.clone(),
+                intensity: environment_map_light.intensity,
+            },
+            AtmosphereEnvironmentMap {
+                diffuse_map,
+                specular_map,
+            },
+        ));
+    }
+}
+
+/// Creates an empty cubemap that compute shaders can write to.
+fn create_environment_cubemap(size: u32, mip_level_count: u32) -> Image {
+    let pixel_size = ATMOSPHERE_TEXTURE_FORMAT.pixel_size();
+    let data_size: usize = (0..mip_level_count)
+        .map(|mip_level| {
+            let mip_size = (size >> mip_level).max(1) as usize;
+            mip_size * mip_size * 6 * pixel_size
+        })
+        .sum();
+
+    Image {
+        data: vec![0; data_size],
+        texture_descriptor: TextureDescriptor {
+            label: Some("atmosphere_environment_map"),
+            size: Extent3d {
+                width: size,
+                height: size,
+                depth_or_array_layers: 6,
+            },
+            mip_level_count,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: ATMOSPHERE_TEXTURE_FORMAT,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::STORAGE_BINDING
+                | TextureUsages::COPY_DST,
+            view_formats: &[],
+        },
+        sampler: ImageSampler::linear(),
+        texture_view_descriptor: Some(TextureViewDescriptor {
+            dimension: Some(TextureViewDimension::Cube),
+            ..default()
+        }),
+        asset_usage: RenderAssetUsages::RENDER_WORLD,
+        gpu_mipmaps: false,
+    }
+}
+
+/// The parameters of the filter that renders the sky into a mip level of an environment map.
+#[derive(Clone, Copy, ShaderType)]
+pub(super) struct AtmosphereEnvironmentFilterUniform {
+    /// The perceptual roughness that the mip level is prefiltered for.
+    roughness: f32,
+    sample_count: u32,
+}
+
+/// The buffer holding the [`AtmosphereEnvironmentFilterUniform`] of every mip level of every
+/// environment map rendered from an atmosphere.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub(super) struct AtmosphereEnvironmentFilterUniforms(
+    DynamicUniformBuffer<AtmosphereEnvironmentFilterUniform>,
+);
+
+/// A dispatch that renders the sky into one mip level of an environment map.
+pub(super) struct EnvironmentMapPass {
+    pub bind_group: BindGroup,
+    pub filter_offset: u32,
+    pub size: u32,
+}
+
+/// The bind groups used to render the sky into the environment maps of a view.
+#[derive(Component)]
+pub(super) struct AtmosphereEnvironmentMapBindGroups {
+    pub diffuse: EnvironmentMapPass,
+    pub specular: Vec<EnvironmentMapPass>,
+}
+
+/// A mip level of an environment map, before its bind group is created.
+struct PendingEnvironmentMapPass {
+    view: TextureView,
+    filter_offset: u32,
+    size: u32,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(super) fn prepare_atmosphere_environment_map_bind_groups(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    pipelines: Res<AtmospherePipelines>,
+    images: Res<RenderAssets<GpuImage>>,
+    mut filter_uniforms: ResMut<AtmosphereEnvironmentFilterUniforms>,
+    views: Query<(Entity, &AtmosphereEnvironmentMap, &AtmosphereTextures)>,
+) {
+    let environment_maps: Vec<_> = views
+        .iter()
+        .filter_map(|(entity, environment_map, textures)| {
+            Some((
+                entity,
+                images.get(&environment_map.diffuse_map)?,
+                images.get(&environment_map.specular_map)?,
+                textures,
+            ))
+        })
+        .collect();
+
+    let filter_count = environment_maps
+        .iter()
+        .map(|(_, _, specular_map, _)| 1 + specular_map.mip_level_count as usize)
+        .sum();
+    let Some(mut writer) = filter_uniforms.get_writer(filter_count, &render_device, &render_queue)
+    else {
+        return;
+    };
+
+    let mut pending_passes = Vec::with_capacity(environment_maps.len());
+    for (entity, diffuse_map, specular_map, textures) in environment_maps {
+        let mut mip_pass =
+            |gpu_image: &GpuImage, mip_level: u32, roughness: f32| PendingEnvironmentMapPass {
+                view: gpu_image.texture.create_view(&TextureViewDescriptor {
+                    label: Some("atmosphere_environment_map_mip_view"),
+                    dimension: Some(TextureViewDimension::D2Array),
+                    base_mip_level: mip_level,
+                    mip_level_count: Some(1),
+                    ..default()
+                }),
+                filter_offset: writer.write(&AtmosphereEnvironmentFilterUniform {
+                    roughness,
+                    sample_count: ENVIRONMENT_MAP_SAMPLE_COUNT,
+                }),
+                size: (gpu_image.size.x >> mip_level).max(1),
+            };
+
+        let diffuse = mip_pass(diffuse_map, 0, 1.0);
+
+        // The roughness grows linearly with the mip level, which is how the environment map
+        // shader picks the mip level to sample.
+        let max_mip_level = specular_map.mip_level_count.saturating_sub(1).max(1);
+        let specular: Vec<_> = (0..specular_map.mip_level_count)
+            .map(|mip_level| {
+                mip_pass(
+                    specular_map,
+                    mip_level,
+                    mip_level as f32 / max_mip_level as f32,
+                )
+            })
+            .collect();
+
+        pending_passes.push((entity, textures, diffuse, specular));
+    }
+    drop(writer);
+
+    let Some(filter_binding) = filter_uniforms.binding() else {
+        return;
+    };
+
+    for (entity, textures, diffuse, specular) in pending_passes {
+        let create_pass = |pending_pass: PendingEnvironmentMapPass| EnvironmentMapPass {
+            bind_group: render_device.create_bind_group(
+                "atmosphere_environment_bind_group",
+                &pipelines.environment_bind_group_layout,
+                &BindGroupEntries::sequential((
+                    &textures.sky_view_lut.default_view,
+                    &pending_pass.view,
+                    filter_binding.clone(),
+                )),
+            ),
+            filter_offset: pending_pass.filter_offset,
+            size: pending_pass.size,
+        };
+
+        commands
+            .entity(entity)
+            .insert(AtmosphereEnvironmentMapBindGroups {
+                diffuse: create_pass(diffuse),
+                specular: specular.into_iter().map(create_pass).collect(),
+            });
+    }
+}
@@ -0,0 +1,126 @@
+// Renders the sky into the cubemaps of an `EnvironmentMapLight`, so that the atmosphere lights
+// the scene.
+//
+// Each mip of the specular map is prefiltered for a roughness that grows linearly with the mip
+// level, by importance sampling the GGX distribution. The diffuse map holds the cosine-weighted
+// average of the sky over each hemisphere.
+
+#import bevy_render::maths::PI_2
+#import bevy_pbr::atmosphere::functions::sample_sky_view_lut
+
+struct EnvironmentFilter {
+    roughness: f32,
+    sample_count: u32,
+}
+
+@group(1) @binding(0) var sky_view_lut: texture_2d<f32>;
+@group(1) @binding(1) var environment_out: texture_storage_2d_array<rgba16float, write>;
+@group(1) @binding(2) var<uniform> environment_filter: EnvironmentFilter;
+
+// Returns the direction through the given UV coordinate of a cube face, in the cubemap's space.
+fn cube_face_direction(face: u32, uv: vec2<f32>) -> vec3<f32> {
+    let c = uv * 2.0 - 1.0;
+    switch face {
+        case 0u: { return normalize(vec3(1.0, -c.y, -c.x)); }
+        case 1u: { return normalize(vec3(-1.0, -c.y, c.x)); }
+        case 2u: { return normalize(vec3(c.x, 1.0, c.y)); }
+        case 3u: { return normalize(vec3(c.x, -1.0, -c.y)); }
+        case 4u: { return normalize(vec3(c.x, -c.y, 1.0)); }
+        default: { return normalize(vec3(-c.x, -c.y, -1.0)); }
+    }
+}
+
+fn hammersley(index: u32, count: u32) -> vec2<f32> {
+    return vec2(f32(index) / f32(count), f32(reverseBits(index)) * 2.3283064365386963e-10);
+}
+
+// Returns an orthonormal basis whose Z axis is `normal`.
+fn tangent_frame(normal: vec3<f32>) -> mat3x3<f32> {
+    var up = vec3(0.0, 0.0, 1.0);
+    if abs(normal.z) > 0.999 {
+        up = vec3(1.0, 0.0, 0.0);
+    }
+    let tangent = normalize(cross(up, normal));
+    let bitangent = cross(normal, tangent);
+    return mat3x3(tangent, bitangent, normal);
+}
+
+// Samples the sky along a direction in the cubemap's space. Environment maps are sampled with a
+// flipped Z axis.
+fn sample_sky(direction: vec3<f32>) -> vec3<f32> {
+    return sample_sky_view_lut(sky_view_lut, vec3(direction.xy, -direction.z));
+}
+
+fn texel_direction(global_id: vec3<u32>) -> vec3<f32> {
+    let size = textureDimensions(environment_out);
+    let uv = (vec2<f32>(global_id.xy) + 0.5) / vec2<f32>(size);
+    return cube_face_direction(global_id.z, uv);
+}
+
+@compute
+@workgroup_size(8, 8, 1)
+fn diffuse(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    if any(global_id.xy >= textureDimensions(environment_out)) {
+        return;
+    }
+
+    let normal = texel_direction(global_id);
+    let frame = tangent_frame(normal);
+
+    var irradiance = vec3(0.0);
+    for (var sample_index = 0u; sample_index < environment_filter.sample_count; sample_index += 1u) {
+        // Cosine-weighted hemisphere sampling, whose probability density cancels out the cosine
+        // term.
+        let xi = hammersley(sample_index, environment_filter.sample_count);
+        let phi = PI_2 * xi.x;
+        let sin_theta = sqrt(xi.y);
+        let local_dir = vec3(cos(phi) * sin_theta, sin(phi) * sin_theta, sqrt(1.0 - xi.y));
+        irradiance += sample_sky(frame * local_dir);
+    }
+    irradiance /= f32(environment_filter.sample_count);
+
+    textureStore(environment_out, global_id.xy, global_id.z, vec4(irradiance, 1.0));
+}
+
+@compute
+@workgroup_size(8, 8, 1)
+fn specular(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    if any(global_id.xy >= textureDimensions(environment_out)) {
+        return;
+    }
+
+    let normal = texel_direction(global_id);
+    if environment_filter.roughness == 0.0 {
+        textureStore(environment_out, global_id.xy, global_id.z, vec4(sample_sky(normal), 1.0));
+        return;
+    }
+
+    let frame = tangent_frame(normal);
+    let alpha = environment_filter.roughness * environment_filter.roughness;
+
+    // Like most prefiltered environment maps, this assumes that the view direction is the
+    // same as the normal.
+    var radiance = vec3(0.0);
+    var total_weight = 0.0;
+    for (var sample_index = 0u; sample_index < environment_filter.sample_count; sample_index += 1u) {
+        let xi = hammersley(sample_index, environment_filter.sample_count);
+        let phi = PI_2 * xi.x;
+        let cos_theta = sqrt((1.0 - xi.y) / (1.0 + (alpha * alpha - 1.0) * xi.y));
+        let sin_theta = sqrt(1.0 - cos_theta * cos_theta);
+        let half_vector = frame * vec3(cos(phi) * sin_theta, sin(phi) * sin_theta, cos_theta);
+        let light_dir = reflect(-normal, half_vector);
+
+        let n_dot_l = dot(normal, light_dir);
+        if n_dot_l > 0.0 {
+            radiance += sample_sky(light_dir) * n_dot_l;
+            total_weight += n_dot_l;
+        }
+    }
+
+    textureStore(
+        environment_out,
+        global_id.xy,
+        global_id.z,
+        vec4(radiance / max(total_weight, 1.0e-4), 1.0)
+    );
+}
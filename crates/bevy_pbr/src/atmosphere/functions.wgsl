@@ -0,0 +1,283 @@
+#define_import_path bevy_pbr::atmosphere::functions
+
+#import bevy_render::maths::{PI, PI_2, HALF_PI}
+#import bevy_pbr::atmosphere::bindings::{atmosphere, settings, view, lights, lut_sampler}
+
+// Based on "A Scalable and Production Ready Sky and Atmosphere Rendering Technique" by
+// Sébastien Hillaire (2020), with the transmittance parameterization of "Precomputed Atmospheric
+// Scattering" by Eric Bruneton and Fabrice Neyret (2008).
+//
+// All positions are relative to the center of the planet and all distances are in meters. The
+// planet's up axis is the world's +Y axis, and the camera always sits directly above the center.
+
+const FRAC_3_16_PI: f32 = 0.0596831036594607509;
+const FRAC_4_PI: f32 = 0.07957747154594767;
+
+// The angular radius of the sun disk drawn in the sky, in radians.
+const SUN_ANGULAR_RADIUS: f32 = 0.00465;
+
+struct MediumSample {
+    rayleigh_scattering: vec3<f32>,
+    mie_scattering: vec3<f32>,
+    extinction: vec3<f32>,
+}
+
+// Returns the scattering and extinction coefficients of the atmosphere at the given altitude.
+fn sample_medium(altitude: f32) -> MediumSample {
+    let rayleigh_density = exp(-altitude / atmosphere.rayleigh_scale_height);
+    let mie_density = exp(-altitude / atmosphere.mie_scale_height);
+    // The ozone layer is a tent function centered on its altitude.
+    let ozone_density = max(
+        0.0,
+        1.0 - abs(altitude - atmosphere.ozone_layer_altitude) / (atmosphere.ozone_layer_width * 0.5)
+    );
+
+    var medium: MediumSample;
+    medium.rayleigh_scattering = atmosphere.rayleigh_scattering * rayleigh_density;
+    medium.mie_scattering = vec3(atmosphere.mie_scattering * mie_density);
+    let mie_absorption = atmosphere.mie_absorption * mie_density;
+    let ozone_absorption = atmosphere.ozone_absorption * ozone_density;
+    medium.extinction = medium.rayleigh_scattering + medium.mie_scattering + mie_absorption +
+        ozone_absorption;
+    return medium;
+}
+
+fn rayleigh_phase(cos_theta: f32) -> f32 {
+    return FRAC_3_16_PI * (1.0 + cos_theta * cos_theta);
+}
+
+fn henyey_greenstein_phase(cos_theta: f32, g: f32) -> f32 {
+    let denominator = 1.0 + g * g - 2.0 * g * cos_theta;
+    return FRAC_4_PI * (1.0 - g * g) / (denominator * sqrt(denominator));
+}
+
+// The distance from a point at radius `r` to the top of the atmosphere, along a ray whose angle
+// with the local up axis has the cosine `mu`.
+fn distance_to_top_atmosphere_boundary(r: f32, mu: f32) -> f32 {
+    let discriminant = r * r * (mu * mu - 1.0) + atmosphere.top_radius * atmosphere.top_radius;
+    return max(-r * mu + sqrt(max(discriminant, 0.0)), 0.0);
+}
+
+// The distance from a point at radius `r` to the ground, along a ray whose angle with the local up
+// axis has the cosine `mu`.
+fn distance_to_bottom_atmosphere_boundary(r: f32, mu: f32) -> f32 {
+    let discriminant = r * r * (mu * mu - 1.0) +
+        atmosphere.bottom_radius * atmosphere.bottom_radius;
+    return max(-r * mu - sqrt(max(discriminant, 0.0)), 0.0);
+}
+
+fn ray_intersects_ground(r: f32, mu: f32) -> bool {
+    return mu < 0.0 &&
+        r * r * (mu * mu - 1.0) + atmosphere.bottom_radius * atmosphere.bottom_radius >= 0.0;
+}
+
+// The distance along a ray to either the ground or the top of the atmosphere, whichever comes
+// first.
+fn distance_to_atmosphere_boundary(r: f32, mu: f32) -> f32 {
+    if ray_intersects_ground(r, mu) {
+        return distance_to_bottom_atmosphere_boundary(r, mu);
+    }
+    return distance_to_top_atmosphere_boundary(r, mu);
+}
+
+// The radius of the camera, clamped to lie within the atmosphere.
+fn view_radius() -> f32 {
+    return clamp(
+        view.world_position.y * settings.scene_units_to_m + atmosphere.bottom_radius,
+        atmosphere.bottom_radius + 1.0,
+        atmosphere.top_radius
+    );
+}
+
+fn transmittance_lut_r_mu_to_uv(r: f32, mu: f32) -> vec2<f32> {
+    let bottom_radius_squared = atmosphere.bottom_radius * atmosphere.bottom_radius;
+    // The distance to the top of the atmosphere along a ray tangent to the ground.
+    let h = sqrt(atmosphere.top_radius * atmosphere.top_radius - bottom_radius_squared);
+    // The distance to the horizon.
+    let rho = sqrt(max(r * r - bottom_radius_squared, 0.0));
+    let d = distance_to_top_atmosphere_boundary(r, mu);
+    let d_min = atmosphere.top_radius - r;
+    let d_max = rho + h;
+    return vec2((d - d_min) / (d_max - d_min), rho / h);
+}
+
+fn transmittance_lut_uv_to_r_mu(uv: vec2<f32>) -> vec2<f32> {
+    let bottom_radius_squared = atmosphere.bottom_radius * atmosphere.bottom_radius;
+    let h = sqrt(atmosphere.top_radius * atmosphere.top_radius - bottom_radius_squared);
+    let rho = h * uv.y;
+    let r = sqrt(rho * rho + bottom_radius_squared);
+    let d_min = atmosphere.top_radius - r;
+    let d_max = rho + h;
+    let d = d_min + uv.x * (d_max - d_min);
+    var mu = 1.0;
+    if d > 0.0 {
+        mu = (h * h - rho * rho - d * d) / (2.0 * r * d);
+    }
+    return vec2(r, clamp(mu, -1.0, 1.0));
+}
+
+fn multiscattering_lut_r_mu_to_uv(r: f32, mu: f32) -> vec2<f32> {
+    return vec2(
+        mu * 0.5 + 0.5,
+        (r - atmosphere.bottom_radius) / (atmosphere.top_radius - atmosphere.bottom_radius)
+    );
+}
+
+fn multiscattering_lut_uv_to_r_mu(uv: vec2<f32>) -> vec2<f32> {
+    return vec2(
+        mix(atmosphere.bottom_radius, atmosphere.top_radius, uv.y),
+        uv.x * 2.0 - 1.0
+    );
+}
+
+// The sky view LUT covers every direction around the camera, with more texels close to the
+// horizon where the sky changes the most.
+fn sky_view_lut_uv_to_ray_dir(uv: vec2<f32>) -> vec3<f32> {
+    let azimuth = (uv.x - 0.5) * PI_2;
+    let v = uv.y * 2.0 - 1.0;
+    let altitude = sign(v) * v * v * HALF_PI;
+    let cos_altitude = cos(altitude);
+    return vec3(cos_altitude * sin(azimuth), sin(altitude), -cos_altitude * cos(azimuth));
+}
+
+fn ray_dir_to_sky_view_lut_uv(ray_dir: vec3<f32>) -> vec2<f32> {
+    let altitude = asin(clamp(ray_dir.y, -1.0, 1.0));
+    let azimuth = atan2(ray_dir.x, -ray_dir.z);
+    let v = sign(altitude) * sqrt(abs(altitude) / HALF_PI);
+    return vec2(azimuth / PI_2 + 0.5, v * 0.5 + 0.5);
+}
+
+fn sample_transmittance_lut(transmittance_lut: texture_2d<f32>, r: f32, mu: f32) -> vec3<f32> {
+    let uv = transmittance_lut_r_mu_to_uv(r, mu);
+    return textureSampleLevel(transmittance_lut, lut_sampler, uv, 0.0).rgb;
+}
+
+fn sample_multiscattering_lut(multiscattering_lut: texture_2d<f32>, r: f32, mu: f32) -> vec3<f32> {
+    let uv = multiscattering_lut_r_mu_to_uv(r, mu);
+    return textureSampleLevel(multiscattering_lut, lut_sampler, uv, 0.0).rgb;
+}
+
+fn sample_sky_view_lut(sky_view_lut: texture_2d<f32>, ray_dir: vec3<f32>) -> vec3<f32> {
+    let uv = ray_dir_to_sky_view_lut_uv(ray_dir);
+    return textureSampleLevel(sky_view_lut, lut_sampler, uv, 0.0).rgb;
+}
+
+// The light of every directional light scattered towards `-ray_dir` at `position`, per meter,
+// including the light that has been scattered more than once.
+fn sample_local_inscattering(
+    transmittance_lut: texture_2d<f32>,
+    multiscattering_lut: texture_2d<f32>,
+    medium: MediumSample,
+    ray_dir: vec3<f32>,
+    position: vec3<f32>,
+) -> vec3<f32> {
+    let r = length(position);
+    let up = position / r;
+    let scattering = medium.rayleigh_scattering + medium.mie_scattering;
+
+    var inscattering = vec3(0.0);
+    for (var light_index = 0u; light_index < lights.n_directional_lights; light_index += 1u) {
+        let light = &lights.directional_lights[light_index];
+        let dir_to_light = (*light).direction_to_light;
+        let mu_light = dot(up, dir_to_light);
+        let cos_theta = dot(ray_dir, dir_to_light);
+
+        // The planet casts a shadow on the atmosphere behind it.
+        var transmittance_to_light = vec3(0.0);
+        if !ray_intersects_ground(r, mu_light) {
+            transmittance_to_light = sample_transmittance_lut(transmittance_lut, r, mu_light);
+        }
+
+        let phase_scattering = medium.rayleigh_scattering * rayleigh_phase(cos_theta) +
+            medium.mie_scattering * henyey_greenstein_phase(cos_theta, atmosphere.mie_asymmetry);
+        let psi_ms = sample_multiscattering_lut(multiscattering_lut, r, mu_light);
+
+        inscattering += (transmittance_to_light * phase_scattering + psi_ms * scattering) *
+            (*light).color.rgb;
+    }
+    return inscattering;
+}
+
+struct RaymarchResult {
+    inscattering: vec3<f32>,
+    transmittance: vec3<f32>,
+}
+
+// Integrates the light scattered towards `position` along `ray_dir`, from `t_start` to `t_end`
+// meters away, using the energy-conserving integration of Hillaire (2015).
+fn raymarch_atmosphere(
+    transmittance_lut: texture_2d<f32>,
+    multiscattering_lut: texture_2d<f32>,
+    position: vec3<f32>,
+    ray_dir: vec3<f32>,
+    t_start: f32,
+    t_end: f32,
+    sample_count: u32,
+) -> RaymarchResult {
+    var result: RaymarchResult;
+    result.inscattering = vec3(0.0);
+    result.transmittance = vec3(1.0);
+
+    let dt = (t_end - t_start) / f32(sample_count);
+    for (var sample_index = 0u; sample_index < sample_count; sample_index += 1u) {
+        let t = t_start + (f32(sample_index) + 0.5) * dt;
+        let sample_position = position + t * ray_dir;
+        let medium = sample_medium(length(sample_position) - atmosphere.bottom_radius);
+        let sample_transmittance = exp(-medium.extinction * dt);
+
+        let inscattering = sample_local_inscattering(
+            transmittance_lut,
+            multiscattering_lut,
+            medium,
+            ray_dir,
+            sample_position
+        );
+        let integrated_inscattering = (inscattering - inscattering * sample_transmittance) /
+            max(medium.extinction, vec3(1.0e-12));
+
+        result.inscattering += result.transmittance * integrated_inscattering;
+        result.transmittance *= sample_transmittance;
+    }
+    return result;
+}
+
+// The radiance of the sun disk of every directional light seen along `ray_dir` from the camera.
+// The disks aren't part of the sky view LUT, since they are much smaller than its texels.
+fn sun_radiance(transmittance_lut: texture_2d<f32>, ray_dir: vec3<f32>) -> vec3<f32> {
+    let r = view_radius();
+    if ray_intersects_ground(r, ray_dir.y) {
+        return vec3(0.0);
+    }
+
+    let cos_sun_angular_radius = cos(SUN_ANGULAR_RADIUS);
+    let sun_solid_angle = PI_2 * (1.0 - cos_sun_angular_radius);
+
+    var radiance = vec3(0.0);
+    for (var light_index = 0u; light_index < lights.n_directional_lights; light_index += 1u) {
+        let light = &lights.directional_lights[light_index];
+        if dot(ray_dir, (*light).direction_to_light) > cos_sun_angular_radius {
+            radiance += (*light).color.rgb / sun_solid_angle;
+        }
+    }
+    return radiance * sample_transmittance_lut(transmittance_lut, r, ray_dir.y);
+}
+
+// Returns the direction from the camera through the given UV coordinate of the view.
+fn uv_to_ray_dir(uv: vec2<f32>) -> vec3<f32> {
+    let ndc = vec2(uv.x * 2.0 - 1.0, 1.0 - uv.y * 2.0);
+    let near_position = view.inverse_view_proj * vec4(ndc, 1.0, 1.0);
+    return normalize(near_position.xyz / near_position.w - view.world_position);
+}
+
+// Returns the camera's position relative to the center of the planet.
+fn view_position() -> vec3<f32> {
+    return vec3(0.0, view_radius(), 0.0);
+}
+
+fn fibonacci_sphere(index: u32, count: u32) -> vec3<f32> {
+    let golden_angle = PI * (3.0 - sqrt(5.0));
+    let y = 1.0 - (2.0 * f32(index) + 1.0) / f32(count);
+    let radius = sqrt(max(1.0 - y * y, 0.0));
+    let phi = golden_angle * f32(index);
+    return vec3(cos(phi) * radius, y, sin(phi) * radius);
+}
@@ -0,0 +1,517 @@
+//! Procedural sky and aerial perspective, rendered from a physically based model of a planet's
+//! atmosphere.
+//!
+//! The [`Atmosphere`] component describes how the air of a planet scatters and absorbs light.
+//! When it's added to a 3d camera, the sky is drawn behind all opaque geometry, lit by every
+//! [`DirectionalLight`](crate::DirectionalLight) in the scene, and distant objects are faded
+//! into the atmosphere. Adding [`AtmosphereEnvironmentMapLight`] as well renders the sky into an
+//! [`EnvironmentMapLight`](crate::EnvironmentMapLight), so that it lights the scene too.
+//!
+//! The implementation follows "A Scalable and Production Ready Sky and Atmosphere Rendering
+//! Technique" by Sébastien Hillaire (2020). Every frame, a few compute passes fill look-up tables
+//! (LUTs) that the sky and aerial perspective are then read from:
+//!
+//! * The *transmittance LUT* stores how much light reaches the top of the atmosphere from any
+//!   point within it, in any direction.
+//!
+//! * The *multiscattering LUT* approximates the contribution of light scattered more than once,
+//!   which brightens the sky and keeps it from turning black after sunset.
+//!
+//! * The *sky view LUT* stores the light scattered towards the camera from every direction around
+//!   it.
+//!
+//! * The *aerial view LUT* is a froxel volume aligned with the view frustum that stores the light
+//!   scattered towards the camera and the transmittance between the camera and the points inside
+//!   it.
+//!
+//! The planet's up axis is the world's +Y axis, and the camera always sits directly above its
+//! center, at the altitude given by its translation.
+
+mod environment;
+mod node;
+mod resources;
+
+use bevy_app::{App, Plugin, PostUpdate};
+use bevy_asset::{load_internal_asset, Handle};
+use bevy_core_pipeline::core_3d::{
+    graph::{Core3d, Node3d},
+    Camera3d,
+};
+use bevy_ecs::{
+    component::Component,
+    query::{QueryItem, With},
+    reflect::ReflectComponent,
+    schedule::IntoSystemConfigs,
+    system::lifetimeless::Read,
+};
+use bevy_math::{UVec2, UVec3, Vec3};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use bevy_render::{
+    extract_component::{ExtractComponent, ExtractComponentPlugin, UniformComponentPlugin},
+    render_graph::{RenderGraphApp, ViewNodeRunner},
+    render_resource::{
+        Shader, ShaderType, SpecializedRenderPipelines, TextureFormat, TextureUsages,
+    },
+    renderer::RenderAdapter,
+    texture::Image,
+    Render, RenderApp, RenderSet,
+};
+use bevy_utils::tracing::warn;
+
+use crate::graph::NodePbr;
+
+use self::{
+    environment::{
+        add_atmosphere_environment_maps, prepare_atmosphere_environment_map_bind_groups,
+        AtmosphereEnvironmentFilterUniforms,
+    },
+    node::{AtmosphereLutsNode, RenderSkyNode},
+    resources::{
+        configure_atmosphere_cameras, prepare_atmosphere_bind_groups, prepare_atmosphere_textures,
+        prepare_render_sky_pipelines, AtmospherePipelines, RenderSkyPipeline,
+    },
+};
+
+const TYPES_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(293651479853204);
+const BINDINGS_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(874125630985216);
+const FUNCTIONS_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(507392164870153);
+const TRANSMITTANCE_LUT_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(158204937651082);
+const MULTISCATTERING_LUT_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(639218705742391);
+const SKY_VIEW_LUT_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(417560928314675);
+const AERIAL_VIEW_LUT_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(962874153026548);
+const RENDER_SKY_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(734051298667213);
+const ENVIRONMENT_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(281937465107934);
+
+/// Plugin for the procedural sky and aerial perspective of an [`Atmosphere`].
+pub struct AtmospherePlugin;
+
+impl Plugin for AtmospherePlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(app, TYPES_SHADER_HANDLE, "types.wgsl", Shader::from_wgsl);
+        load_internal_asset!(
+            app,
+            BINDINGS_SHADER_HANDLE,
+            "bindings.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            FUNCTIONS_SHADER_HANDLE,
+            "functions.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            TRANSMITTANCE_LUT_SHADER_HANDLE,
+            "transmittance_lut.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            MULTISCATTERING_LUT_SHADER_HANDLE,
+            "multiscattering_lut.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            SKY_VIEW_LUT_SHADER_HANDLE,
+            "sky_view_lut.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            AERIAL_VIEW_LUT_SHADER_HANDLE,
+            "aerial_view_lut.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            RENDER_SKY_SHADER_HANDLE,
+            "render_sky.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            ENVIRONMENT_SHADER_HANDLE,
+            "environment.wgsl",
+            Shader::from_wgsl
+        );
+
+        app.register_type::<Atmosphere>()
+            .register_type::<AtmosphereSettings>()
+            .register_type::<AtmosphereEnvironmentMapLight>()
+            .add_plugins((
+                ExtractComponentPlugin::<Atmosphere>::default(),
+                ExtractComponentPlugin::<AtmosphereSettings>::default(),
+                ExtractComponentPlugin::<AtmosphereEnvironmentMap>::default(),
+                UniformComponentPlugin::<Atmosphere>::default(),
+                UniformComponentPlugin::<AtmosphereSettings>::default(),
+            ))
+            .add_systems(
+                PostUpdate,
+                (
+                    configure_atmosphere_cameras,
+                    add_atmosphere_environment_maps,
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        if !render_app
+            .world()
+            .resource::<RenderAdapter>()
+            .get_texture_format_features(TextureFormat::Rgba16Float)
+            .allowed_usages
+            .contains(TextureUsages::STORAGE_BINDING)
+        {
+            warn!("AtmospherePlugin not loaded. GPU lacks support: TextureFormat::Rgba16Float does not support TextureUsages::STORAGE_BINDING.");
+            return;
+        }
+
+        render_app
+            .init_resource::<AtmospherePipelines>()
+            .init_resource::<RenderSkyPipeline>()
+            .init_resource::<SpecializedRenderPipelines<RenderSkyPipeline>>()
+            .init_resource::<AtmosphereEnvironmentFilterUniforms>()
+            .add_systems(
+                Render,
+                (
+                    prepare_render_sky_pipelines.in_set(RenderSet::Prepare),
+                    prepare_atmosphere_textures.in_set(RenderSet::PrepareResources),
+                    (
+                        prepare_atmosphere_bind_groups,
+                        prepare_atmosphere_environment_map_bind_groups,
+                    )
+                        .in_set(RenderSet::PrepareBindGroups),
+                ),
+            )
+            .add_render_graph_node::<ViewNodeRunner<AtmosphereLutsNode>>(
+                Core3d,
+                NodePbr::AtmosphereLuts,
+            )
+            .add_render_graph_node::<ViewNodeRunner<RenderSkyNode>>(Core3d, NodePbr::RenderSky)
+            .add_render_graph_edges(
+                Core3d,
+                (
+                    // END_PRE_PASSES -> ATMOSPHERE_LUTS -> MAIN_PASS
+                    Node3d::EndPrepasses,
+                    NodePbr::AtmosphereLuts,
+                    Node3d::StartMainPass,
+                ),
+            )
+            .add_render_graph_edges(
+                Core3d,
+                (
+                    // MAIN_OPAQUE_PASS -> RENDER_SKY -> MAIN_TRANSMISSIVE_PASS
+                    Node3d::MainOpaquePass,
+                    NodePbr::RenderSky,
+                    Node3d::MainTransmissivePass,
+                ),
+            );
+    }
+}
+
+/// Component to draw a physically based sky and aerial perspective for a 3d camera.
+///
+/// The sky is lit by every [`DirectionalLight`](crate::DirectionalLight), whose illuminance
+/// should be that of the sun above the atmosphere (around
+/// [`light_consts::lux::DIRECT_SUNLIGHT`]). The camera should use an HDR target and a physically
+/// based [`Exposure`](bevy_render::camera::Exposure), since the sky is much brighter than most
+/// artificial lights.
+///
+/// All distances are in meters, and the scattering and absorption coefficients are per meter.
+/// [`AtmosphereSettings::scene_units_to_m`] converts world units to meters.
+///
+/// # Usage Notes
+///
+/// The sky replaces the clear color of the camera wherever there's no opaque geometry, and
+/// transparent objects don't receive aerial perspective.
+///
+/// The atmosphere isn't supported on `WebGL2`.
+///
+/// [`light_consts::lux::DIRECT_SUNLIGHT`]: crate::light_consts::lux::DIRECT_SUNLIGHT
+#[derive(Component, Reflect, ExtractComponent, ShaderType, Clone, Copy, Debug, PartialEq)]
+#[extract_component_filter(With<Camera3d>)]
+#[reflect(Component, Default)]
+pub struct Atmosphere {
+    /// The radius of the planet's surface.
+    pub bottom_radius: f32,
+    /// The radius of the top of the atmosphere, above which there's no air.
+    pub top_radius: f32,
+    /// The albedo of the ground, which reflects light back into the atmosphere.
+    pub ground_albedo: Vec3,
+    /// The altitude over which the density of the molecules that cause Rayleigh scattering
+    /// decreases by a factor of e.
+    pub rayleigh_scale_height: f32,
+    /// The Rayleigh scattering coefficient at sea level, for the red, green and blue wavelengths.
+    ///
+    /// Rayleigh scattering affects short wavelengths the most, which makes the sky blue.
+    pub rayleigh_scattering: Vec3,
+    /// The altitude over which the density of the aerosols that cause Mie scattering decreases
+    /// by a factor of e.
+    pub mie_scale_height: f32,
+    /// The Mie scattering coefficient at sea level.
+    ///
+    /// Mie scattering affects all wavelengths equally, and produces haze and the glow around the
+    /// sun.
+    pub mie_scattering: f32,
+    /// The Mie absorption coefficient at sea level.
+    pub mie_absorption: f32,
+    /// The asymmetry of the Mie phase function, between -1.0 and 1.0.
+    ///
+    /// Positive values scatter light forwards, which brightens the sky around the sun.
+    pub mie_asymmetry: f32,
+    /// The altitude at which the ozone layer is the densest.
+    pub ozone_layer_altitude: f32,
+    /// The thickness of the ozone layer, whose density decreases linearly away from its center.
+    pub ozone_layer_width: f32,
+    /// The absorption coefficient of the ozone layer at its densest, for the red, green and blue
+    /// wavelengths.
+    ///
+    /// Ozone absorbs orange light, which keeps the sky blue around sunset.
+    pub ozone_absorption: Vec3,
+}
+
+impl Atmosphere {
+    /// The atmosphere of the Earth.
+    pub const EARTH: Atmosphere = Atmosphere {
+        bottom_radius: 6_360_000.0,
+        top_radius: 6_460_000.0,
+        ground_albedo: Vec3::splat(0.3),
+        rayleigh_scale_height: 8_000.0,
+        rayleigh_scattering: Vec3::new(5.802e-6, 13.558e-6, 33.1e-6),
+        mie_scale_height: 1_200.0,
+        mie_scattering: 3.996e-6,
+        mie_absorption: 0.444e-6,
+        mie_asymmetry: 0.8,
+        ozone_layer_altitude: 25_000.0,
+        ozone_layer_width: 30_000.0,
+        ozone_absorption: Vec3::new(0.650e-6, 1.881e-6, 0.085e-6),
+    };
+}
+
+impl Default for Atmosphere {
+    fn default() -> Self {
+        Self::EARTH
+    }
+}
+
+/// Controls the resolution and quality of the look-up tables used to render an [`Atmosphere`].
+///
+/// Add this component next to an [`Atmosphere`] to override the defaults.
+#[derive(Component, Reflect, ShaderType, Clone, Copy, Debug, PartialEq)]
+#[reflect(Component, Default)]
+pub struct AtmosphereSettings {
+    /// The size of the transmittance LUT.
+    pub transmittance_lut_size: UVec2,
+    /// The size of the multiscattering LUT.
+    pub multiscattering_lut_size: UVec2,
+    /// The size of the sky view LUT.
+    pub sky_view_lut_size: UVec2,
+    /// The size of the aerial view LUT. The Z axis is the number of depth slices.
+    pub aerial_view_lut_size: UVec3,
+    /// The number of samples taken along each ray of the transmittance LUT.
+    pub transmittance_lut_samples: u32,
+    /// The number of directions averaged by each texel of the multiscattering LUT.
+    pub multiscattering_lut_dirs: u32,
+    /// The number of samples taken along each ray of the multiscattering LUT.
+    pub multiscattering_lut_samples: u32,
+    /// The number of samples taken along each ray of the sky view LUT.
+    pub sky_view_lut_samples: u32,
+    /// The number of samples taken between two depth slices of the aerial view LUT.
+    pub aerial_view_lut_samples: u32,
+    /// The distance covered by the aerial view LUT, in meters. Beyond it, objects receive the
+    /// aerial perspective of the last slice.
+    pub aerial_view_lut_max_distance: f32,
+    /// The number of meters in one world unit.
+    pub scene_units_to_m: f32,
+}
+
+impl Default for AtmosphereSettings {
+    fn default() -> Self {
+        Self {
+            transmittance_lut_size: UVec2::new(256, 128),
+            multiscattering_lut_size: UVec2::new(32, 32),
+            sky_view_lut_size: UVec2::new(400, 200),
+            aerial_view_lut_size: UVec3::new(32, 32, 32),
+            transmittance_lut_samples: 40,
+            multiscattering_lut_dirs: 64,
+            multiscattering_lut_samples: 20,
+            sky_view_lut_samples: 16,
+            aerial_view_lut_samples: 10,
+            aerial_view_lut_max_distance: 32_000.0,
+            scene_units_to_m: 1.0,
+        }
+    }
+}
+
+impl ExtractComponent for AtmosphereSettings {
+    type QueryData = Option<Read<AtmosphereSettings>>;
+    type QueryFilter = (With<Camera3d>, With<Atmosphere>);
+    type Out = AtmosphereSettings;
+
+    fn extract_component(item: QueryItem<'_, Self::QueryData>) -> Option<Self::Out> {
+        Some(item.copied().unwrap_or_default())
+    }
+}
+
+/// Add this component next to an [`Atmosphere`] to light the scene with the sky.
+///
+/// Every frame, the sky is rendered into the cubemaps of an
+/// [`EnvironmentMapLight`](crate::EnvironmentMapLight) that's inserted on the camera, so don't
+/// add one yourself. The sun disks aren't part of the environment map, since directional lights
+/// already light the scene directly.
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+#[reflect(Component, Default)]
+pub struct AtmosphereEnvironmentMapLight {
+    /// Scale factor applied to the light of the environment map.
+    pub intensity: f32,
+    /// The size of each face of the specular cubemap, in texels. It must be a power of two.
+    pub size: u32,
+}
+
+impl Default for AtmosphereEnvironmentMapLight {
+    fn default() -> Self {
+        Self {
+            intensity: 1.0,
+            size: 256,
+        }
+    }
+}
+
+/// The cubemaps that the sky of a camera with an [`AtmosphereEnvironmentMapLight`] is rendered
+/// into.
+#[derive(Component, ExtractComponent, Clone)]
+#[extract_component_filter(With<Atmosphere>)]
+pub struct AtmosphereEnvironmentMap {
+    pub diffuse_map: Handle<Image>,
+    pub specular_map: Handle<Image>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        environment_map::EnvironmentMapLight,
+        test_utils::{assert_shader_composes, extract_app, shaders_app},
+    };
+    use bevy_asset::Assets;
+    use bevy_ecs::{system::RunSystemOnce, world::World};
+    use resources::RenderSkyPipelineKey;
+
+    #[test]
+    fn atmospheres_are_extracted_for_3d_cameras() {
+        let mut app = extract_app();
+        app.add_plugins((
+            ExtractComponentPlugin::<Atmosphere>::default(),
+            ExtractComponentPlugin::<AtmosphereSettings>::default(),
+        ));
+        let settings = AtmosphereSettings {
+            sky_view_lut_samples: 8,
+            ..Default::default()
+        };
+        let default_settings = app
+            .world_mut()
+            .spawn((Camera3d::default(), Atmosphere::EARTH))
+            .id();
+        let custom_settings = app
+            .world_mut()
+            .spawn((Camera3d::default(), Atmosphere::EARTH, settings))
+            .id();
+        let no_atmosphere = app.world_mut().spawn((Camera3d::default(), settings)).id();
+        let not_a_camera = app.world_mut().spawn(Atmosphere::EARTH).id();
+        app.update();
+
+        // Cameras with an atmosphere always get settings, which default when they're missing
+        let render_world = app.sub_app(RenderApp).world();
+        let extracted = |entity| {
+            render_world.get_entity(entity).map(|entity| {
+                (
+                    entity.get::<Atmosphere>().copied(),
+                    entity.get::<AtmosphereSettings>().copied(),
+                )
+            })
+        };
+        assert_eq!(
+            extracted(default_settings),
+            Some((Some(Atmosphere::EARTH), Some(AtmosphereSettings::default())))
+        );
+        assert_eq!(
+            extracted(custom_settings),
+            Some((Some(Atmosphere::EARTH), Some(settings)))
+        );
+        assert!(matches!(
+            extracted(no_atmosphere),
+            None | Some((None, None))
+        ));
+        assert!(matches!(extracted(not_a_camera), None | Some((None, None))));
+    }
+
+    #[test]
+    fn environment_maps_are_created_with_power_of_two_sizes() {
+        let mut world = World::new();
+        world.init_resource::<Assets<Image>>();
+        let camera = world
+            .spawn(AtmosphereEnvironmentMapLight {
+                intensity: 0.5,
+                size: 200,
+            })
+            .id();
+        world.run_system_once(environment::add_atmosphere_environment_maps);
+
+        let environment_map_light = world.get::<EnvironmentMapLight>(camera).unwrap();
+        assert_eq!(environment_map_light.intensity, 0.5);
+        let images = world.resource::<Assets<Image>>();
+        let specular_map = &images
+            .get(&environment_map_light.specular_map)
+            .unwrap()
+            .texture_descriptor;
+        assert_eq!(specular_map.size.width, 256);
+        assert_eq!(specular_map.size.depth_or_array_layers, 6);
+        assert_eq!(specular_map.mip_level_count, 9);
+        let environment_map = world.get::<AtmosphereEnvironmentMap>(camera).unwrap();
+        assert_eq!(
+            environment_map.specular_map,
+            environment_map_light.specular_map
+        );
+        assert_eq!(
+            environment_map.diffuse_map,
+            environment_map_light.diffuse_map
+        );
+    }
+
+    #[test]
+    fn render_sky_is_multisampled_with_msaa() {
+        let key = |msaa_samples| RenderSkyPipelineKey {
+            msaa_samples,
+            hdr: true,
+        };
+        assert!(key(1).shader_defs().is_empty());
+        assert_eq!(key(4).shader_defs(), ["MULTISAMPLED".into()]);
+    }
+
+    #[test]
+    fn shaders_compose() {
+        let app = shaders_app();
+        for shader in [
+            TRANSMITTANCE_LUT_SHADER_HANDLE,
+            MULTISCATTERING_LUT_SHADER_HANDLE,
+            SKY_VIEW_LUT_SHADER_HANDLE,
+            AERIAL_VIEW_LUT_SHADER_HANDLE,
+            ENVIRONMENT_SHADER_HANDLE,
+        ] {
+            assert_shader_composes(&app, &shader, &[]);
+        }
+        for msaa_samples in [1, 4] {
+            let key = RenderSkyPipelineKey {
+                msaa_samples,
+                hdr: true,
+            };
+            assert_shader_composes(&app, &RENDER_SKY_SHADER_HANDLE, &key.shader_defs());
+        }
+    }
+}
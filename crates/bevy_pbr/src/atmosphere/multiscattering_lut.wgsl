@@ -0,0 +1,83 @@
+#import bevy_render::maths::PI
+#import bevy_pbr::atmosphere::{
+    bindings::{atmosphere, settings},
+    functions::{
+        sample_medium, sample_transmittance_lut, distance_to_atmosphere_boundary,
+        ray_intersects_ground, multiscattering_lut_uv_to_r_mu, fibonacci_sphere, FRAC_4_PI,
+    },
+}
+
+@group(1) @binding(0) var transmittance_lut: texture_2d<f32>;
+@group(1) @binding(1) var multiscattering_lut_out: texture_storage_2d<rgba16float, write>;
+
+// Computes the contribution of light scattered any number of times, for a light of unit
+// illuminance, assuming that it arrives uniformly from every direction after the second bounce.
+@compute
+@workgroup_size(16, 16, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    if any(global_id.xy >= settings.multiscattering_lut_size) {
+        return;
+    }
+
+    let uv = (vec2<f32>(global_id.xy) + 0.5) / vec2<f32>(settings.multiscattering_lut_size);
+    let r_mu = multiscattering_lut_uv_to_r_mu(uv);
+    let position = vec3(0.0, r_mu.x, 0.0);
+    let dir_to_light = vec3(sqrt(1.0 - r_mu.y * r_mu.y), r_mu.y, 0.0);
+
+    // The light scattered twice towards the origin, and the fraction of light that is scattered
+    // again, both averaged over the sphere of directions.
+    var second_order_inscattering = vec3(0.0);
+    var multiscattering_transfer = vec3(0.0);
+
+    for (var dir_index = 0u; dir_index < settings.multiscattering_lut_dirs; dir_index += 1u) {
+        let ray_dir = fibonacci_sphere(dir_index, settings.multiscattering_lut_dirs);
+        let t_max = distance_to_atmosphere_boundary(r_mu.x, ray_dir.y);
+        let dt = t_max / f32(settings.multiscattering_lut_samples);
+
+        var throughput = vec3(1.0);
+        for (var sample_index = 0u; sample_index < settings.multiscattering_lut_samples; sample_index += 1u) {
+            let t = (f32(sample_index) + 0.5) * dt;
+            let sample_position = position + t * ray_dir;
+            let r = length(sample_position);
+            let mu_light = dot(sample_position / r, dir_to_light);
+            let medium = sample_medium(r - atmosphere.bottom_radius);
+            let scattering = medium.rayleigh_scattering + medium.mie_scattering;
+            let sample_transmittance = exp(-medium.extinction * dt);
+            let extinction = max(medium.extinction, vec3(1.0e-12));
+
+            var transmittance_to_light = vec3(0.0);
+            if !ray_intersects_ground(r, mu_light) {
+                transmittance_to_light = sample_transmittance_lut(transmittance_lut, r, mu_light);
+            }
+
+            let inscattering = transmittance_to_light * scattering * FRAC_4_PI;
+            second_order_inscattering += throughput *
+                (inscattering - inscattering * sample_transmittance) / extinction;
+            multiscattering_transfer += throughput *
+                (scattering - scattering * sample_transmittance) / extinction;
+            throughput *= sample_transmittance;
+        }
+
+        // Light reflected by the ground towards the origin.
+        if ray_intersects_ground(r_mu.x, ray_dir.y) {
+            let ground_position = position + t_max * ray_dir;
+            let ground_normal = normalize(ground_position);
+            let mu_light = dot(ground_normal, dir_to_light);
+            let transmittance_to_light = sample_transmittance_lut(
+                transmittance_lut,
+                atmosphere.bottom_radius,
+                mu_light
+            );
+            second_order_inscattering += throughput * transmittance_to_light *
+                saturate(mu_light) * atmosphere.ground_albedo / PI;
+        }
+    }
+
+    let dir_count = f32(settings.multiscattering_lut_dirs);
+    second_order_inscattering /= dir_count;
+    multiscattering_transfer /= dir_count;
+
+    // The sum of the geometric series of every order of scattering.
+    let psi_ms = second_order_inscattering / (1.0 - multiscattering_transfer);
+    textureStore(multiscattering_lut_out, global_id.xy, vec4(psi_ms, 1.0));
+}
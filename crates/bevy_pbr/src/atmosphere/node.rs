@@ -0,0 +1,209 @@
+use bevy_ecs::{query::QueryItem, system::lifetimeless::Read, world::World};
+use bevy_render::{
+    extract_component::DynamicUniformIndex,
+    render_graph::{NodeRunError, RenderGraphContext, ViewNode},
+    render_resource::{ComputePassDescriptor, PipelineCache, RenderPassDescriptor},
+    renderer::RenderContext,
+    view::{ViewTarget, ViewUniformOffset},
+};
+
+use crate::ViewLightsUniformOffset;
+
+use super::{
+    environment::AtmosphereEnvironmentMapBindGroups,
+    resources::{AtmosphereBindGroups, AtmospherePipelines, ViewRenderSkyPipeline},
+    Atmosphere, AtmosphereSettings,
+};
+
+/// Fills the LUTs of every view with an [`Atmosphere`], and renders its sky into its environment
+/// maps if it has an [`AtmosphereEnvironmentMapLight`](super::AtmosphereEnvironmentMapLight).
+#[derive(Default)]
+pub(super) struct AtmosphereLutsNode;
+
+impl ViewNode for AtmosphereLutsNode {
+    type ViewQuery = (
+        Read<AtmosphereSettings>,
+        Read<AtmosphereBindGroups>,
+        Read<DynamicUniformIndex<Atmosphere>>,
+        Read<DynamicUniformIndex<AtmosphereSettings>>,
+        Read<ViewUniformOffset>,
+        Read<ViewLightsUniformOffset>,
+        Option<Read<AtmosphereEnvironmentMapBindGroups>>,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (
+            settings,
+            bind_groups,
+            atmosphere_uniform_index,
+            settings_uniform_index,
+            view_uniform_offset,
+            view_lights_offset,
+            environment_map_bind_groups,
+        ): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let pipelines = world.resource::<AtmospherePipelines>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let (
+            Some(transmittance_lut_pipeline),
+            Some(multiscattering_lut_pipeline),
+            Some(sky_view_lut_pipeline),
+            Some(aerial_view_lut_pipeline),
+        ) = (
+            pipeline_cache.get_compute_pipeline(pipelines.transmittance_lut_pipeline),
+            pipeline_cache.get_compute_pipeline(pipelines.multiscattering_lut_pipeline),
+            pipeline_cache.get_compute_pipeline(pipelines.sky_view_lut_pipeline),
+            pipeline_cache.get_compute_pipeline(pipelines.aerial_view_lut_pipeline),
+        )
+        else {
+            return Ok(());
+        };
+
+        let common_offsets = [
+            atmosphere_uniform_index.index(),
+            settings_uniform_index.index(),
+            view_uniform_offset.offset,
+            view_lights_offset.offset,
+        ];
+
+        let mut luts_pass =
+            render_context
+                .command_encoder()
+                .begin_compute_pass(&ComputePassDescriptor {
+                    label: Some("atmosphere_luts_pass"),
+                    timestamp_writes: None,
+                });
+        luts_pass.set_bind_group(0, &bind_groups.common, &common_offsets);
+
+        luts_pass.set_pipeline(transmittance_lut_pipeline);
+        luts_pass.set_bind_group(1, &bind_groups.transmittance_lut, &[]);
+        luts_pass.dispatch_workgroups(
+            settings.transmittance_lut_size.x.div_ceil(16),
+            settings.transmittance_lut_size.y.div_ceil(16),
+            1,
+        );
+
+        luts_pass.set_pipeline(multiscattering_lut_pipeline);
+        luts_pass.set_bind_group(1, &bind_groups.multiscattering_lut, &[]);
+        luts_pass.dispatch_workgroups(
+            settings.multiscattering_lut_size.x.div_ceil(16),
+            settings.multiscattering_lut_size.y.div_ceil(16),
+            1,
+        );
+
+        luts_pass.set_pipeline(sky_view_lut_pipeline);
+        luts_pass.set_bind_group(1, &bind_groups.sky_view_lut, &[]);
+        luts_pass.dispatch_workgroups(
+            settings.sky_view_lut_size.x.div_ceil(16),
+            settings.sky_view_lut_size.y.div_ceil(16),
+            1,
+        );
+
+        luts_pass.set_pipeline(aerial_view_lut_pipeline);
+        luts_pass.set_bind_group(1, &bind_groups.aerial_view_lut, &[]);
+        luts_pass.dispatch_workgroups(
+            settings.aerial_view_lut_size.x.div_ceil(16),
+            settings.aerial_view_lut_size.y.div_ceil(16),
+            1,
+        );
+
+        let (
+            Some(environment_map_bind_groups),
+            Some(environment_diffuse_pipeline),
+            Some(environment_specular_pipeline),
+        ) = (
+            environment_map_bind_groups,
+            pipeline_cache.get_compute_pipeline(pipelines.environment_diffuse_pipeline),
+            pipeline_cache.get_compute_pipeline(pipelines.environment_specular_pipeline),
+        )
+        else {
+            return Ok(());
+        };
+
+        let environment_passes = std::iter::once((
+            environment_diffuse_pipeline,
+            &environment_map_bind_groups.diffuse,
+        ))
+        .chain(
+            environment_map_bind_groups
+                .specular
+                .iter()
+                .map(|pass| (environment_specular_pipeline, pass)),
+        );
+
+        // Each workgroup covers a tile of one face of the cubemap.
+        for (pipeline, pass) in environment_passes {
+            luts_pass.set_pipeline(pipeline);
+            luts_pass.set_bind_group(1, &pass.bind_group, &[pass.filter_offset]);
+            luts_pass.dispatch_workgroups(pass.size.div_ceil(8), pass.size.div_ceil(8), 6);
+        }
+
+        Ok(())
+    }
+}
+
+/// Draws the sky and aerial perspective of every view with an [`Atmosphere`] over its opaque
+/// geometry.
+#[derive(Default)]
+pub(super) struct RenderSkyNode;
+
+impl ViewNode for RenderSkyNode {
+    type ViewQuery = (
+        Read<ViewTarget>,
+        Read<ViewRenderSkyPipeline>,
+        Read<AtmosphereBindGroups>,
+        Read<DynamicUniformIndex<Atmosphere>>,
+        Read<DynamicUniformIndex<AtmosphereSettings>>,
+        Read<ViewUniformOffset>,
+        Read<ViewLightsUniformOffset>,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (
+            view_target,
+            pipeline_id,
+            bind_groups,
+            atmosphere_uniform_index,
+            settings_uniform_index,
+            view_uniform_offset,
+            view_lights_offset,
+        ): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let Some(pipeline) = pipeline_cache.get_render_pipeline(pipeline_id.0) else {
+            return Ok(());
+        };
+
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("atmosphere_render_sky_pass"),
+            color_attachments: &[Some(view_target.get_color_attachment())],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(
+            0,
+            &bind_groups.common,
+            &[
+                atmosphere_uniform_index.index(),
+                settings_uniform_index.index(),
+                view_uniform_offset.offset,
+                view_lights_offset.offset,
+            ],
+        );
+        render_pass.set_bind_group(1, &bind_groups.render_sky, &[]);
+        render_pass.draw(0..3, 0..1);
+
+        Ok(())
+    }
+}
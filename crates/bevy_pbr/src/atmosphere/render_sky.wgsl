@@ -0,0 +1,46 @@
+// Draws the sky behind opaque geometry and applies aerial perspective in front of it.
+//
+// The output holds the light scattered towards the camera in `rgb` and the transmittance of the
+// atmosphere in `a`, which the blend state uses to attenuate the color that's already in the main
+// texture. The sky itself has no transmittance, so it replaces the clear color.
+
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+#import bevy_pbr::atmosphere::{
+    bindings::{settings, view, lut_sampler},
+    functions::{sample_sky_view_lut, sun_radiance, uv_to_ray_dir},
+}
+
+@group(1) @binding(0) var transmittance_lut: texture_2d<f32>;
+@group(1) @binding(1) var sky_view_lut: texture_2d<f32>;
+@group(1) @binding(2) var aerial_view_lut: texture_3d<f32>;
+#ifdef MULTISAMPLED
+@group(1) @binding(3) var depth_texture: texture_depth_multisampled_2d;
+#else
+@group(1) @binding(3) var depth_texture: texture_depth_2d;
+#endif
+
+@fragment
+fn main(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let depth = textureLoad(depth_texture, vec2<i32>(in.position.xy), 0);
+    let ray_dir = uv_to_ray_dir(in.uv);
+
+    if depth == 0.0 {
+        let radiance = sample_sky_view_lut(sky_view_lut, ray_dir) +
+            sun_radiance(transmittance_lut, ray_dir);
+        return vec4(radiance * view.exposure, 0.0);
+    }
+
+    let ndc = vec2(in.uv.x * 2.0 - 1.0, 1.0 - in.uv.y * 2.0);
+    let world_position = view.inverse_view_proj * vec4(ndc, depth, 1.0);
+    let view_distance = length(world_position.xyz / world_position.w - view.world_position) *
+        settings.scene_units_to_m;
+    let w = view_distance / settings.aerial_view_lut_max_distance;
+    let aerial_view = textureSampleLevel(aerial_view_lut, lut_sampler, vec3(in.uv, w), 0.0);
+
+    // The first slice of the LUT lies half a slice away from the camera, so fade the scattering
+    // out in front of it.
+    let fade = saturate(2.0 * w * f32(settings.aerial_view_lut_size.z));
+    let inscattering = aerial_view.rgb * fade;
+    let transmittance = mix(1.0, aerial_view.a, fade);
+    return vec4(inscattering * view.exposure, transmittance);
+}
@@ -0,0 +1,553 @@
+use bevy_asset::Handle;
+use bevy_core_pipeline::{
+    core_3d::Camera3d, fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+};
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    query::With,
+    system::{Commands, Query, Res, ResMut, Resource},
+    world::{FromWorld, World},
+};
+use bevy_render::{
+    extract_component::ComponentUniforms,
+    render_resource::{
+        binding_types::{
+            sampler, texture_2d, texture_3d, texture_depth_2d, texture_depth_2d_multisampled,
+            texture_storage_2d, texture_storage_2d_array, uniform_buffer,
+        },
+        *,
+    },
+    renderer::RenderDevice,
+    texture::{BevyDefault, CachedTexture, TextureCache},
+    view::{ExtractedView, Msaa, ViewDepthTexture, ViewTarget, ViewUniform, ViewUniforms},
+};
+use bevy_utils::prelude::default;
+
+use crate::{GpuLights, LightMeta};
+
+use super::{
+    environment::AtmosphereEnvironmentFilterUniform, Atmosphere, AtmosphereSettings,
+    AERIAL_VIEW_LUT_SHADER_HANDLE, ENVIRONMENT_SHADER_HANDLE, MULTISCATTERING_LUT_SHADER_HANDLE,
+    RENDER_SKY_SHADER_HANDLE, SKY_VIEW_LUT_SHADER_HANDLE, TRANSMITTANCE_LUT_SHADER_HANDLE,
+};
+
+/// The format of every LUT, and of the environment maps generated from the sky.
+pub(super) const ATMOSPHERE_TEXTURE_FORMAT: TextureFormat = TextureFormat::Rgba16Float;
+
+/// Makes the depth texture of every camera with an [`Atmosphere`] readable from shaders, so that
+/// the sky can find the opaque geometry in front of it.
+pub(super) fn configure_atmosphere_cameras(mut cameras: Query<&mut Camera3d, With<Atmosphere>>) {
+    for mut camera_3d in &mut cameras {
+        let usages = TextureUsages::from(camera_3d.depth_texture_usages);
+        if !usages.contains(TextureUsages::TEXTURE_BINDING) {
+            camera_3d.depth_texture_usages = (usages | TextureUsages::TEXTURE_BINDING).into();
+        }
+    }
+}
+
+#[derive(Resource)]
+pub(super) struct AtmospherePipelines {
+    pub common_bind_group_layout: BindGroupLayout,
+    pub transmittance_lut_bind_group_layout: BindGroupLayout,
+    pub multiscattering_lut_bind_group_layout: BindGroupLayout,
+    pub sky_view_lut_bind_group_layout: BindGroupLayout,
+    pub aerial_view_lut_bind_group_layout: BindGroupLayout,
+    pub render_sky_bind_group_layout: BindGroupLayout,
+    pub render_sky_bind_group_layout_msaa: BindGroupLayout,
+    pub environment_bind_group_layout: BindGroupLayout,
+
+    pub lut_sampler: Sampler,
+
+    pub transmittance_lut_pipeline: CachedComputePipelineId,
+    pub multiscattering_lut_pipeline: CachedComputePipelineId,
+    pub sky_view_lut_pipeline: CachedComputePipelineId,
+    pub aerial_view_lut_pipeline: CachedComputePipelineId,
+    pub environment_diffuse_pipeline: CachedComputePipelineId,
+    pub environment_specular_pipeline: CachedComputePipelineId,
+}
+
+impl FromWorld for AtmospherePipelines {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+
+        let lut_sampler = render_device.create_sampler(&SamplerDescriptor {
+            label: Some("atmosphere_lut_sampler"),
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..default()
+        });
+
+        let common_bind_group_layout = render_device.create_bind_group_layout(
+            "atmosphere_common_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::COMPUTE | ShaderStages::FRAGMENT,
+                (
+                    uniform_buffer::<Atmosphere>(true),
+                    uniform_buffer::<AtmosphereSettings>(true),
+                    uniform_buffer::<ViewUniform>(true),
+                    uniform_buffer::<GpuLights>(true),
+                    sampler(SamplerBindingType::Filtering),
+                ),
+            ),
+        );
+
+        let lut = || texture_2d(TextureSampleType::Float { filterable: true });
+        let lut_out =
+            || texture_storage_2d(ATMOSPHERE_TEXTURE_FORMAT, StorageTextureAccess::WriteOnly);
+
+        let transmittance_lut_bind_group_layout = render_device.create_bind_group_layout(
+            "atmosphere_transmittance_lut_bind_group_layout",
+            &BindGroupLayoutEntries::single(ShaderStages::COMPUTE, lut_out()),
+        );
+
+        let multiscattering_lut_bind_group_layout = render_device.create_bind_group_layout(
+            "atmosphere_multiscattering_lut_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(ShaderStages::COMPUTE, (lut(), lut_out())),
+        );
+
+        let sky_view_lut_bind_group_layout = render_device.create_bind_group_layout(
+            "atmosphere_sky_view_lut_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(ShaderStages::COMPUTE, (lut(), lut(), lut_out())),
+        );
+
+        let aerial_view_lut_bind_group_layout = render_device.create_bind_group_layout(
+            "atmosphere_aerial_view_lut_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::COMPUTE,
+                (
+                    lut(),
+                    lut(),
+                    BindingType::StorageTexture {
+                        access: StorageTextureAccess::WriteOnly,
+                        format: ATMOSPHERE_TEXTURE_FORMAT,
+                        view_dimension: TextureViewDimension::D3,
+                    },
+                ),
+            ),
+        );
+
+        let render_sky_bind_group_layout = render_device.create_bind_group_layout(
+            "atmosphere_render_sky_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    lut(),
+                    lut(),
+                    texture_3d(TextureSampleType::Float { filterable: true }),
+                    texture_depth_2d(),
+                ),
+            ),
+        );
+
+        let render_sky_bind_group_layout_msaa = render_device.create_bind_group_layout(
+            "atmosphere_render_sky_bind_group_layout_msaa",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    lut(),
+                    lut(),
+                    texture_3d(TextureSampleType::Float { filterable: true }),
+                    texture_depth_2d_multisampled(),
+                ),
+            ),
+        );
+
+        let environment_bind_group_layout = render_device.create_bind_group_layout(
+            "atmosphere_environment_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::COMPUTE,
+                (
+                    lut(),
+                    texture_storage_2d_array(
+                        ATMOSPHERE_TEXTURE_FORMAT,
+                        StorageTextureAccess::WriteOnly,
+                    ),
+                    uniform_buffer::<AtmosphereEnvironmentFilterUniform>(true),
+                ),
+            ),
+        );
+
+        let queue_pipeline = |label: &'static str,
+                              layout: &BindGroupLayout,
+                              shader: Handle<Shader>,
+                              entry_point: &'static str| {
+            pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+                label: Some(label.into()),
+                layout: vec![common_bind_group_layout.clone(), layout.clone()],
+                push_constant_ranges: vec![],
+                shader,
+                shader_defs: vec![],
+                entry_point: entry_point.into(),
+            })
+        };
+
+        let transmittance_lut_pipeline = queue_pipeline(
+            "atmosphere_transmittance_lut_pipeline",
+            &transmittance_lut_bind_group_layout,
+            TRANSMITTANCE_LUT_SHADER_HANDLE,
+            "main",
+        );
+        let multiscattering_lut_pipeline = queue_pipeline(
+            "atmosphere_multiscattering_lut_pipeline",
+            &multiscattering_lut_bind_group_layout,
+            MULTISCATTERING_LUT_SHADER_HANDLE,
+            "main",
+        );
+        let sky_view_lut_pipeline = queue_pipeline(
+            "atmosphere_sky_view_lut_pipeline",
+            &sky_view_lut_bind_group_layout,
+            SKY_VIEW_LUT_SHADER_HANDLE,
+            "main",
+        );
+        let aerial_view_lut_pipeline = queue_pipeline(
+            "atmosphere_aerial_view_lut_pipeline",
+            &aerial_view_lut_bind_group_layout,
+            AERIAL_VIEW_LUT_SHADER_HANDLE,
+            "main",
+        );
+        let environment_diffuse_pipeline = queue_pipeline(
+            "atmosphere_environment_diffuse_pipeline",
+            &environment_bind_group_layout,
+            ENVIRONMENT_SHADER_HANDLE,
+            "diffuse",
+        );
+        let environment_specular_pipeline = queue_pipeline(
+            "atmosphere_environment_specular_pipeline",
+            &environment_bind_group_layout,
+            ENVIRONMENT_SHADER_HANDLE,
+            "specular",
+        );
+
+        Self {
+            common_bind_group_layout,
+            transmittance_lut_bind_group_layout,
+            multiscattering_lut_bind_group_layout,
+            sky_view_lut_bind_group_layout,
+            aerial_view_lut_bind_group_layout,
+            render_sky_bind_group_layout,
+            render_sky_bind_group_layout_msaa,
+            environment_bind_group_layout,
+            lut_sampler,
+            transmittance_lut_pipeline,
+            multiscattering_lut_pipeline,
+            sky_view_lut_pipeline,
+            aerial_view_lut_pipeline,
+            environment_diffuse_pipeline,
+            environment_specular_pipeline,
+        }
+    }
+}
+
+#[derive(Resource)]
+pub(super) struct RenderSkyPipeline {
+    common_bind_group_layout: BindGroupLayout,
+    render_sky_bind_group_layout: BindGroupLayout,
+    render_sky_bind_group_layout_msaa: BindGroupLayout,
+}
+
+impl FromWorld for RenderSkyPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let pipelines = world.resource::<AtmospherePipelines>();
+        Self {
+            common_bind_group_layout: pipelines.common_bind_group_layout.clone(),
+            render_sky_bind_group_layout: pipelines.render_sky_bind_group_layout.clone(),
+            render_sky_bind_group_layout_msaa: pipelines.render_sky_bind_group_layout_msaa.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) struct RenderSkyPipelineKey {
+    pub msaa_samples: u32,
+    pub hdr: bool,
+}
+
+impl RenderSkyPipelineKey {
+    /// The shader defs of the [`RenderSkyPipeline`] specialized with this key.
+    pub fn shader_defs(&self) -> Vec<ShaderDefVal> {
+        let mut shader_defs = Vec::new();
+        if self.msaa_samples > 1 {
+            shader_defs.push("MULTISAMPLED".into());
+        }
+        shader_defs
+    }
+}
+
+impl SpecializedRenderPipeline for RenderSkyPipeline {
+    type Key = RenderSkyPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        let render_sky_bind_group_layout = if key.msaa_samples > 1 {
+            self.render_sky_bind_group_layout_msaa.clone()
+        } else {
+            self.render_sky_bind_group_layout.clone()
+        };
+
+        RenderPipelineDescriptor {
+            label: Some("atmosphere_render_sky_pipeline".into()),
+            layout: vec![
+                self.common_bind_group_layout.clone(),
+                render_sky_bind_group_layout,
+            ],
+            push_constant_ranges: vec![],
+            vertex: fullscreen_shader_vertex_state(),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState {
+                count: key.msaa_samples,
+                ..default()
+            },
+            fragment: Some(FragmentState {
+                shader: RENDER_SKY_SHADER_HANDLE,
+                shader_defs: key.shader_defs(),
+                entry_point: "main".into(),
+                targets: vec![Some(ColorTargetState {
+                    format: if key.hdr {
+                        ViewTarget::TEXTURE_FORMAT_HDR
+                    } else {
+                        TextureFormat::bevy_default()
+                    },
+                    // The shader outputs the light scattered towards the camera in `rgb` and the
+                    // transmittance of the atmosphere in `a`, which attenuates what's behind it.
+                    blend: Some(BlendState {
+                        color: BlendComponent {
+                            src_factor: BlendFactor::One,
+                            dst_factor: BlendFactor::SrcAlpha,
+                            operation: BlendOperation::Add,
+                        },
+                        alpha: BlendComponent {
+                            src_factor: BlendFactor::Zero,
+                            dst_factor: BlendFactor::One,
+                            operation: BlendOperation::Add,
+                        },
+                    }),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+        }
+    }
+}
+
+/// The render pipeline used by a view to draw its sky and aerial perspective.
+#[derive(Component)]
+pub(super) struct ViewRenderSkyPipeline(pub CachedRenderPipelineId);
+
+/// Specializes the [`RenderSkyPipeline`] of every view with an [`Atmosphere`].
+pub(super) fn prepare_render_sky_pipelines(
+    mut commands: Commands,
+    pipeline_cache: Res<PipelineCache>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<RenderSkyPipeline>>,
+    render_sky_pipeline: Res<RenderSkyPipeline>,
+    msaa: Res<Msaa>,
+    views: Query<(Entity, &ExtractedView), With<Atmosphere>>,
+) {
+    for (entity, view) in &views {
+        let pipeline_id = pipelines.specialize(
+            &pipeline_cache,
+            &render_sky_pipeline,
+            RenderSkyPipelineKey {
+                msaa_samples: msaa.samples(),
+                hdr: view.hdr,
+            },
+        );
+
+        commands
+            .entity(entity)
+            .insert(ViewRenderSkyPipeline(pipeline_id));
+    }
+}
+
+/// The LUTs of a view with an [`Atmosphere`].
+#[derive(Component)]
+pub(super) struct AtmosphereTextures {
+    pub transmittance_lut: CachedTexture,
+    pub multiscattering_lut: CachedTexture,
+    pub sky_view_lut: CachedTexture,
+    pub aerial_view_lut: CachedTexture,
+}
+
+pub(super) fn prepare_atmosphere_textures(
+    mut commands: Commands,
+    mut texture_cache: ResMut<TextureCache>,
+    render_device: Res<RenderDevice>,
+    views: Query<(Entity, &AtmosphereSettings), With<Atmosphere>>,
+) {
+    for (entity, settings) in &views {
+        let mut lut = |label: &'static str,
+                       width: u32,
+                       height: u32,
+                       depth_or_array_layers: u32,
+                       dimension: TextureDimension| {
+            texture_cache.get(
+                &render_device,
+                TextureDescriptor {
+                    label: Some(label),
+                    size: Extent3d {
+                        width,
+                        height,
+                        depth_or_array_layers,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension,
+                    format: ATMOSPHERE_TEXTURE_FORMAT,
+                    usage: TextureUsages::STORAGE_BINDING | TextureUsages::TEXTURE_BINDING,
+                    view_formats: &[],
+                },
+            )
+        };
+
+        let transmittance_lut = lut(
+            "atmosphere_transmittance_lut",
+            settings.transmittance_lut_size.x,
+            settings.transmittance_lut_size.y,
+            1,
+            TextureDimension::D2,
+        );
+        let multiscattering_lut = lut(
+            "atmosphere_multiscattering_lut",
+            settings.multiscattering_lut_size.x,
+            settings.multiscattering_lut_size.y,
+            1,
+            TextureDimension::D2,
+        );
+        let sky_view_lut = lut(
+            "atmosphere_sky_view_lut",
+            settings.sky_view_lut_size.x,
+            settings.sky_view_lut_size.y,
+            1,
+            TextureDimension::D2,
+        );
+        let aerial_view_lut = lut(
+            "atmosphere_aerial_view_lut",
+            settings.aerial_view_lut_size.x,
+            settings.aerial_view_lut_size.y,
+            settings.aerial_view_lut_size.z,
+            TextureDimension::D3,
+        );
+
+        commands.entity(entity).insert(AtmosphereTextures {
+            transmittance_lut,
+            multiscattering_lut,
+            sky_view_lut,
+            aerial_view_lut,
+        });
+    }
+}
+
+#[derive(Component)]
+pub(super) struct AtmosphereBindGroups {
+    pub common: BindGroup,
+    pub transmittance_lut: BindGroup,
+    pub multiscattering_lut: BindGroup,
+    pub sky_view_lut: BindGroup,
+    pub aerial_view_lut: BindGroup,
+    pub render_sky: BindGroup,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(super) fn prepare_atmosphere_bind_groups(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    pipelines: Res<AtmospherePipelines>,
+    atmosphere_uniforms: Res<ComponentUniforms<Atmosphere>>,
+    settings_uniforms: Res<ComponentUniforms<AtmosphereSettings>>,
+    view_uniforms: Res<ViewUniforms>,
+    light_meta: Res<LightMeta>,
+    msaa: Res<Msaa>,
+    views: Query<(Entity, &AtmosphereTextures, &ViewDepthTexture), With<Atmosphere>>,
+) {
+    let (
+        Some(atmosphere_binding),
+        Some(settings_binding),
+        Some(view_binding),
+        Some(lights_binding),
+    ) = (
+        atmosphere_uniforms.binding(),
+        settings_uniforms.binding(),
+        view_uniforms.uniforms.binding(),
+        light_meta.view_gpu_lights.binding(),
+    )
+    else {
+        return;
+    };
+
+    // The uniforms of every view live in the same buffers, so the views only differ by their
+    // dynamic offsets.
+    let common = render_device.create_bind_group(
+        "atmosphere_common_bind_group",
+        &pipelines.common_bind_group_layout,
+        &BindGroupEntries::sequential((
+            atmosphere_binding,
+            settings_binding,
+            view_binding,
+            lights_binding,
+            &pipelines.lut_sampler,
+        )),
+    );
+
+    let render_sky_bind_group_layout = if msaa.samples() > 1 {
+        &pipelines.render_sky_bind_group_layout_msaa
+    } else {
+        &pipelines.render_sky_bind_group_layout
+    };
+
+    for (entity, textures, depth_texture) in &views {
+        let transmittance_lut = render_device.create_bind_group(
+            "atmosphere_transmittance_lut_bind_group",
+            &pipelines.transmittance_lut_bind_group_layout,
+            &BindGroupEntries::single(&textures.transmittance_lut.default_view),
+        );
+
+        let multiscattering_lut = render_device.create_bind_group(
+            "atmosphere_multiscattering_lut_bind_group",
+            &pipelines.multiscattering_lut_bind_group_layout,
+            &BindGroupEntries::sequential((
+                &textures.transmittance_lut.default_view,
+                &textures.multiscattering_lut.default_view,
+            )),
+        );
+
+        let sky_view_lut = render_device.create_bind_group(
+            "atmosphere_sky_view_lut_bind_group",
+            &pipelines.sky_view_lut_bind_group_layout,
+            &BindGroupEntries::sequential((
+                &textures.transmittance_lut.default_view,
+                &textures.multiscattering_lut.default_view,
+                &textures.sky_view_lut.default_view,
+            )),
+        );
+
+        let aerial_view_lut = render_device.create_bind_group(
+            "atmosphere_aerial_view_lut_bind_group",
+            &pipelines.aerial_view_lut_bind_group_layout,
+            &BindGroupEntries::sequential((
+                &textures.transmittance_lut.default_view,
+                &textures.multiscattering_lut.default_view,
+                &textures.aerial_view_lut.default_view,
+            )),
+        );
+
+        let render_sky = render_device.create_bind_group(
+            "atmosphere_render_sky_bind_group",
+            render_sky_bind_group_layout,
+            &BindGroupEntries::sequential((
+                &textures.transmittance_lut.default_view,
+                &textures.sky_view_lut.default_view,
+                &textures.aerial_view_lut.default_view,
+                depth_texture.view(),
+            )),
+        );
+
+        commands.entity(entity).insert(AtmosphereBindGroups {
+            common: common.clone(),
+            transmittance_lut,
+            multiscattering_lut,
+            sky_view_lut,
+            aerial_view_lut,
+            render_sky,
+        });
+    }
+}
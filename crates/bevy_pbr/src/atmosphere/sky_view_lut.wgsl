@@ -0,0 +1,37 @@
+#import bevy_pbr::atmosphere::{
+    bindings::settings,
+    functions::{
+        raymarch_atmosphere, distance_to_atmosphere_boundary, sky_view_lut_uv_to_ray_dir,
+        view_position,
+    },
+}
+
+@group(1) @binding(0) var transmittance_lut: texture_2d<f32>;
+@group(1) @binding(1) var multiscattering_lut: texture_2d<f32>;
+@group(1) @binding(2) var sky_view_lut_out: texture_storage_2d<rgba16float, write>;
+
+// Computes the light scattered towards the camera from every direction around it.
+@compute
+@workgroup_size(16, 16, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    if any(global_id.xy >= settings.sky_view_lut_size) {
+        return;
+    }
+
+    let uv = (vec2<f32>(global_id.xy) + 0.5) / vec2<f32>(settings.sky_view_lut_size);
+    let ray_dir = sky_view_lut_uv_to_ray_dir(uv);
+    let position = view_position();
+    let t_max = distance_to_atmosphere_boundary(position.y, ray_dir.y);
+
+    let result = raymarch_atmosphere(
+        transmittance_lut,
+        multiscattering_lut,
+        position,
+        ray_dir,
+        0.0,
+        t_max,
+        settings.sky_view_lut_samples
+    );
+
+    textureStore(sky_view_lut_out, global_id.xy, vec4(result.inscattering, 1.0));
+}
@@ -0,0 +1,35 @@
+#import bevy_pbr::atmosphere::{
+    bindings::{atmosphere, settings},
+    functions::{sample_medium, distance_to_top_atmosphere_boundary, transmittance_lut_uv_to_r_mu},
+}
+
+@group(1) @binding(0) var transmittance_lut_out: texture_storage_2d<rgba16float, write>;
+
+// Integrates the optical depth between every point of the atmosphere and its top boundary.
+@compute
+@workgroup_size(16, 16, 1)
+fn main(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    if any(global_id.xy >= settings.transmittance_lut_size) {
+        return;
+    }
+
+    let uv = (vec2<f32>(global_id.xy) + 0.5) / vec2<f32>(settings.transmittance_lut_size);
+    let r_mu = transmittance_lut_uv_to_r_mu(uv);
+    let r = r_mu.x;
+    let mu = r_mu.y;
+
+    let position = vec3(0.0, r, 0.0);
+    let ray_dir = vec3(sqrt(1.0 - mu * mu), mu, 0.0);
+    let t_max = distance_to_top_atmosphere_boundary(r, mu);
+    let dt = t_max / f32(settings.transmittance_lut_samples);
+
+    var optical_depth = vec3(0.0);
+    for (var sample_index = 0u; sample_index < settings.transmittance_lut_samples; sample_index += 1u) {
+        let t = (f32(sample_index) + 0.5) * dt;
+        let sample_position = position + t * ray_dir;
+        let medium = sample_medium(length(sample_position) - atmosphere.bottom_radius);
+        optical_depth += medium.extinction * dt;
+    }
+
+    textureStore(transmittance_lut_out, global_id.xy, vec4(exp(-optical_depth), 1.0));
+}
@@ -0,0 +1,32 @@
+#define_import_path bevy_pbr::atmosphere::types
+
+// The GPU-side representation of `Atmosphere`. All distances are in meters.
+struct Atmosphere {
+    bottom_radius: f32,
+    top_radius: f32,
+    ground_albedo: vec3<f32>,
+    rayleigh_scale_height: f32,
+    rayleigh_scattering: vec3<f32>,
+    mie_scale_height: f32,
+    mie_scattering: f32,
+    mie_absorption: f32,
+    mie_asymmetry: f32,
+    ozone_layer_altitude: f32,
+    ozone_layer_width: f32,
+    ozone_absorption: vec3<f32>,
+}
+
+// The GPU-side representation of `AtmosphereSettings`.
+struct AtmosphereSettings {
+    transmittance_lut_size: vec2<u32>,
+    multiscattering_lut_size: vec2<u32>,
+    sky_view_lut_size: vec2<u32>,
+    aerial_view_lut_size: vec3<u32>,
+    transmittance_lut_samples: u32,
+    multiscattering_lut_dirs: u32,
+    multiscattering_lut_samples: u32,
+    sky_view_lut_samples: u32,
+    aerial_view_lut_samples: u32,
+    aerial_view_lut_max_distance: f32,
+    scene_units_to_m: f32,
+}
@@ -0,0 +1,138 @@
+// Draws box-projected decals into the decal buffer.
+//
+// Each decal is rasterized as the back faces of its box, so that it still covers the screen when
+// the camera is inside of it. Every fragment reconstructs the position of the opaque surface
+// behind it from the depth prepass, and projects the decal onto it along the local Y axis of the
+// box. The decal buffer accumulates premultiplied values, so that decals drawn later are layered
+// on top of earlier ones.
+
+#import bevy_render::view::View
+
+struct Decal {
+    world_from_decal: mat4x4<f32>,
+    decal_from_world: mat4x4<f32>,
+    base_color: vec4<f32>,
+    perceptual_roughness: f32,
+    angle_fade: f32,
+    flags: u32,
+}
+
+// NOTE: These must match the bit flags in bevy_pbr/src/decal/mod.rs!
+const DECAL_FLAGS_BASE_COLOR_TEXTURE_BIT: u32 = 1u;
+const DECAL_FLAGS_NORMAL_MAP_TEXTURE_BIT: u32 = 2u;
+const DECAL_FLAGS_WRITES_BASE_COLOR_BIT: u32  = 4u;
+const DECAL_FLAGS_WRITES_ROUGHNESS_BIT: u32   = 8u;
+
+@group(0) @binding(0) var<uniform> view: View;
+#ifdef MULTISAMPLED
+@group(0) @binding(1) var depth_texture: texture_depth_multisampled_2d;
+#else
+@group(0) @binding(1) var depth_texture: texture_depth_2d;
+#endif
+
+@group(1) @binding(0) var<uniform> decal: Decal;
+@group(1) @binding(1) var base_color_texture: texture_2d<f32>;
+@group(1) @binding(2) var base_color_sampler: sampler;
+@group(1) @binding(3) var normal_map_texture: texture_2d<f32>;
+@group(1) @binding(4) var normal_map_sampler: sampler;
+
+// The corners of the 12 triangles of a box, wound counter-clockwise when seen from the outside.
+// Bit 0 of each corner is its X coordinate, bit 1 its Y coordinate and bit 2 its Z coordinate.
+const BOX_CORNERS: array<u32, 36> = array<u32, 36>(
+    0u, 2u, 3u, 0u, 3u, 1u, // -Z
+    4u, 5u, 7u, 4u, 7u, 6u, // +Z
+    0u, 4u, 6u, 0u, 6u, 2u, // -X
+    1u, 3u, 7u, 1u, 7u, 5u, // +X
+    0u, 1u, 5u, 0u, 5u, 4u, // -Y
+    2u, 6u, 7u, 2u, 7u, 3u, // +Y
+);
+
+struct FragmentOutput {
+    @location(0) base_color: vec4<f32>,
+    @location(1) normal: vec4<f32>,
+    @location(2) roughness: vec4<f32>,
+}
+
+@vertex
+fn vertex(@builtin(vertex_index) vertex_index: u32) -> @builtin(position) vec4<f32> {
+    var corners = BOX_CORNERS;
+    let corner = corners[vertex_index];
+    let decal_position = vec3(
+        f32(corner & 1u),
+        f32((corner >> 1u) & 1u),
+        f32((corner >> 2u) & 1u)
+    ) - 0.5;
+    return view.view_proj * decal.world_from_decal * vec4(decal_position, 1.0);
+}
+
+@fragment
+fn fragment(@builtin(position) frag_coord: vec4<f32>) -> FragmentOutput {
+    let depth = textureLoad(depth_texture, vec2<i32>(frag_coord.xy), 0);
+
+    let uv = (frag_coord.xy - view.viewport.xy) / view.viewport.zw;
+    let ndc = vec3(uv * vec2(2.0, -2.0) + vec2(-1.0, 1.0), depth);
+    let world_position_w = view.inverse_view_proj * vec4(ndc, 1.0);
+    let world_position = world_position_w.xyz / world_position_w.w;
+    let decal_position = (decal.decal_from_world * vec4(world_position, 1.0)).xyz;
+    let decal_uv = decal_position.xz + 0.5;
+
+    // Derivatives have to be taken before any fragment is discarded.
+    let surface_normal = normalize(cross(dpdy(world_position), dpdx(world_position)));
+    let decal_uv_dx = dpdx(decal_uv);
+    let decal_uv_dy = dpdy(decal_uv);
+
+    // Nothing to project onto where the depth prepass holds the far plane, or outside of the box.
+    if (depth == 0.0 || any(abs(decal_position) > vec3(0.5))) {
+        discard;
+    }
+
+    let decal_x = normalize(decal.world_from_decal[0].xyz);
+    let decal_y = normalize(decal.world_from_decal[1].xyz);
+
+    // Fade the decal out on surfaces that don't face its projection direction, so that it doesn't
+    // stretch over them.
+    let facing = dot(surface_normal, decal_y);
+    let fade = saturate(facing / max(decal.angle_fade, 1e-4));
+    if (fade <= 0.0) {
+        discard;
+    }
+
+    var base_color = decal.base_color;
+    if ((decal.flags & DECAL_FLAGS_BASE_COLOR_TEXTURE_BIT) != 0u) {
+        base_color *= textureSampleGrad(
+            base_color_texture,
+            base_color_sampler,
+            decal_uv,
+            decal_uv_dx,
+            decal_uv_dy
+        );
+    }
+    let opacity = base_color.a * fade;
+
+    var output: FragmentOutput;
+
+    if ((decal.flags & DECAL_FLAGS_WRITES_BASE_COLOR_BIT) != 0u) {
+        output.base_color = vec4(base_color.rgb * opacity, opacity);
+    }
+
+    if ((decal.flags & DECAL_FLAGS_NORMAL_MAP_TEXTURE_BIT) != 0u) {
+        let Nt = textureSampleGrad(
+            normal_map_texture,
+            normal_map_sampler,
+            decal_uv,
+            decal_uv_dx,
+            decal_uv_dy
+        ).rgb * 2.0 - 1.0;
+        // The box spans the tangent space of the decal: U runs along its X axis, V along its Z
+        // axis and the normal points up its Y axis.
+        let bitangent = cross(decal_y, decal_x);
+        let normal = normalize(Nt.x * decal_x + Nt.y * bitangent + Nt.z * decal_y);
+        output.normal = vec4(normal * opacity, opacity);
+    }
+
+    if ((decal.flags & DECAL_FLAGS_WRITES_ROUGHNESS_BIT) != 0u) {
+        output.roughness = vec4(decal.perceptual_roughness * opacity, 0.0, 0.0, opacity);
+    }
+
+    return output;
+}
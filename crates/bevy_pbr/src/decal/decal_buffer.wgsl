@@ -0,0 +1,56 @@
+// Reads the decal buffer in the main pass, and layers the decals it holds on top of the material
+// of the surface being shaded.
+
+#define_import_path bevy_pbr::decal_buffer
+
+#import bevy_pbr::{
+    mesh_types::MESH_FLAGS_DECAL_RECEIVER_BIT,
+    mesh_view_bindings::{decal_base_color_texture, decal_normal_texture, decal_roughness_texture},
+    pbr_types::{
+        PbrInput, STANDARD_MATERIAL_FLAGS_ALPHA_MODE_ALPHA_TO_COVERAGE,
+        STANDARD_MATERIAL_FLAGS_ALPHA_MODE_MASK, STANDARD_MATERIAL_FLAGS_ALPHA_MODE_OPAQUE,
+        STANDARD_MATERIAL_FLAGS_ALPHA_MODE_RESERVED_BITS,
+    },
+}
+
+// Returns true if the decals were projected onto the surface of this fragment.
+//
+// Decals are projected onto the depth prepass, which only holds the surfaces of opaque and
+// alpha-masked meshes.
+fn receives_decals(pbr_input: PbrInput) -> bool {
+    if ((pbr_input.flags & MESH_FLAGS_DECAL_RECEIVER_BIT) == 0u) {
+        return false;
+    }
+
+    let alpha_mode = pbr_input.material.flags & STANDARD_MATERIAL_FLAGS_ALPHA_MODE_RESERVED_BITS;
+    return alpha_mode == STANDARD_MATERIAL_FLAGS_ALPHA_MODE_OPAQUE ||
+        alpha_mode == STANDARD_MATERIAL_FLAGS_ALPHA_MODE_MASK ||
+        alpha_mode == STANDARD_MATERIAL_FLAGS_ALPHA_MODE_ALPHA_TO_COVERAGE;
+}
+
+// Composites the decal buffer over the base color, normal and roughness of the surface.
+//
+// Every channel of the decal buffer holds the premultiplied value of all the decals layered on
+// top of each other, along with their combined coverage in alpha.
+fn apply_decals(pbr_input: ptr<function, PbrInput>) {
+    if (!receives_decals(*pbr_input)) {
+        return;
+    }
+
+    let pixel = vec2<i32>((*pbr_input).frag_coord.xy);
+
+    let base_color = textureLoad(decal_base_color_texture, pixel, 0);
+    (*pbr_input).material.base_color = vec4(
+        (*pbr_input).material.base_color.rgb * (1.0 - base_color.a) + base_color.rgb,
+        (*pbr_input).material.base_color.a
+    );
+
+    let normal = textureLoad(decal_normal_texture, pixel, 0);
+    if (normal.a > 0.0) {
+        (*pbr_input).N = normalize((*pbr_input).N * (1.0 - normal.a) + normal.rgb);
+    }
+
+    let roughness = textureLoad(decal_roughness_texture, pixel, 0);
+    (*pbr_input).material.perceptual_roughness =
+        (*pbr_input).material.perceptual_roughness * (1.0 - roughness.a) + roughness.r;
+}
@@ -0,0 +1,670 @@
+use crate::graph::NodePbr;
+use bevy_app::{App, Plugin};
+use bevy_asset::{load_internal_asset, AssetId, Handle};
+use bevy_color::{Color, ColorToComponents, LinearRgba};
+use bevy_core_pipeline::{
+    core_3d::{
+        graph::{Core3d, Node3d},
+        Camera3d,
+    },
+    prepass::{DepthPrepass, ViewPrepassTextures},
+};
+use bevy_derive::{Deref, DerefMut};
+use bevy_ecs::{
+    prelude::{Bundle, Component, Entity},
+    query::{QueryItem, With},
+    reflect::ReflectComponent,
+    schedule::IntoSystemConfigs,
+    system::{lifetimeless::Read, Commands, Query, Res, ResMut, Resource},
+    world::{FromWorld, World},
+};
+use bevy_math::{Mat4, Vec4};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use bevy_render::{
+    camera::{Camera, ExtractedCamera},
+    render_asset::RenderAssets,
+    render_graph::{NodeRunError, RenderGraphApp, RenderGraphContext, ViewNode, ViewNodeRunner},
+    render_resource::{
+        binding_types::{
+            sampler, texture_2d, texture_depth_2d, texture_depth_2d_multisampled, uniform_buffer,
+        },
+        *,
+    },
+    renderer::{RenderContext, RenderDevice, RenderQueue},
+    texture::{CachedTexture, FallbackImage, GpuImage, Image, TextureCache},
+    view::{InheritedVisibility, Msaa, ViewUniform, ViewUniformOffset, ViewUniforms, Visibility},
+    Extract, ExtractSchedule, Render, RenderApp, RenderSet,
+};
+use bevy_transform::components::{GlobalTransform, Transform};
+use bevy_utils::prelude::default;
+
+const DECAL_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(630197825443516);
+const DECAL_BUFFER_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(281649037251893);
+
+/// Plugin for box-projected decals.
+pub struct DecalPlugin;
+
+impl Plugin for DecalPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(app, DECAL_SHADER_HANDLE, "decal.wgsl", Shader::from_wgsl);
+        load_internal_asset!(
+            app,
+            DECAL_BUFFER_SHADER_HANDLE,
+            "decal_buffer.wgsl",
+            Shader::from_wgsl
+        );
+
+        app.register_type::<Decal>()
+            .register_type::<NotDecalReceiver>();
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .init_resource::<ExtractedDecals>()
+            .init_resource::<DecalUniformBuffer>()
+            .init_resource::<DecalBindGroups>()
+            .init_resource::<SpecializedRenderPipelines<DecalPipeline>>()
+            .add_systems(ExtractSchedule, extract_decals)
+            .add_systems(
+                Render,
+                (
+                    prepare_decal_pipelines.in_set(RenderSet::Prepare),
+                    (prepare_decal_textures, prepare_decal_uniforms)
+                        .in_set(RenderSet::PrepareResources),
+                    prepare_decal_bind_groups.in_set(RenderSet::PrepareBindGroups),
+                ),
+            )
+            .add_render_graph_node::<ViewNodeRunner<DecalNode>>(Core3d, NodePbr::Decals)
+            .add_render_graph_edges(
+                Core3d,
+                (
+                    // END_PRE_PASSES -> DECALS -> MAIN_PASS
+                    Node3d::EndPrepasses,
+                    NodePbr::Decals,
+                    Node3d::StartMainPass,
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app.init_resource::<DecalPipeline>();
+    }
+}
+
+/// A decal that's projected onto the opaque surfaces inside of its box.
+///
+/// The box of the decal is the unit cube centered on the origin of its [`Transform`], so its
+/// scale sets the size of the decal. The decal is projected downwards along the local Y axis of
+/// the box: the U texture coordinate runs along its X axis, and the V texture coordinate runs
+/// along its Z axis.
+///
+/// Decals are drawn into a decal buffer after the prepasses, which the main pass then layers on
+/// top of the [`StandardMaterial`](crate::StandardMaterial) inputs of every surface, with both
+/// [`OpaqueRendererMethod::Forward`](crate::OpaqueRendererMethod::Forward) and
+/// [`OpaqueRendererMethod::Deferred`](crate::OpaqueRendererMethod::Deferred).
+///
+/// # Usage Notes
+///
+/// Decals are only drawn by 3d cameras with the [`DepthPrepass`] component. They only apply to
+/// opaque and alpha-masked meshes, which you can opt out with [`NotDecalReceiver`].
+#[derive(Component, Reflect, Clone, Debug)]
+#[reflect(Component, Default)]
+pub struct Decal {
+    /// The color of the decal, which multiplies [`Self::base_color_texture`].
+    ///
+    /// Its alpha sets the opacity of the whole decal.
+    pub base_color: Color,
+    /// The texture projected onto the surfaces inside of the box.
+    ///
+    /// Its alpha masks where the decal covers those surfaces, including for its normal map and
+    /// roughness.
+    pub base_color_texture: Option<Handle<Image>>,
+    /// A tangent space normal map, which replaces the normals of the surfaces where the decal
+    /// covers them.
+    ///
+    /// Like in [`StandardMaterial::normal_map_texture`](crate::StandardMaterial::normal_map_texture),
+    /// this must be a linear texture with a Y+ ("OpenGL") green channel.
+    pub normal_map_texture: Option<Handle<Image>>,
+    /// The perceptual roughness that replaces the one of the surfaces where the decal covers them,
+    /// if any.
+    pub perceptual_roughness: Option<f32>,
+    /// Whether the decal replaces the base color of the surfaces it covers.
+    ///
+    /// Turn this off for decals that only change the normals or the roughness of a surface, like
+    /// bullet holes or wet patches.
+    pub writes_base_color: bool,
+    /// How far away from the projection direction, from 0.0 to 1.0, the normal of a surface
+    /// has to tilt before the decal starts fading out on it.
+    ///
+    /// Surfaces facing the projection direction get the whole decal, and surfaces perpendicular to
+    /// it get none, which avoids stretching the decal over the sides of objects.
+    pub angle_fade: f32,
+    /// The layer of the decal.
+    ///
+    /// Decals with a higher order are drawn on top of decals with a lower order.
+    pub order: i32,
+}
+
+impl Default for Decal {
+    fn default() -> Self {
+        Self {
+            base_color: Color::WHITE,
+            base_color_texture: None,
+            normal_map_texture: None,
+            perceptual_roughness: None,
+            writes_base_color: true,
+            angle_fade: 0.25,
+            order: 0,
+        }
+    }
+}
+
+/// A component bundle for [`Decal`] entities.
+#[derive(Bundle, Clone, Default)]
+pub struct DecalBundle {
+    pub decal: Decal,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    /// User indication of whether the decal is visible
+    pub visibility: Visibility,
+    /// Inherited visibility of the decal.
+    pub inherited_visibility: InheritedVisibility,
+}
+
+/// Add this component to make a mesh not receive [`Decal`]s.
+#[derive(Component, Reflect, Clone, Copy, Default, Debug)]
+#[reflect(Component, Default)]
+pub struct NotDecalReceiver;
+
+/// A marker component added to the views that draw decals, in the render world.
+#[derive(Component, Clone, Copy, Default)]
+pub struct ViewDecals;
+
+/// A decal extracted to the render world.
+pub struct ExtractedDecal {
+    pub entity: Entity,
+    pub world_from_decal: Mat4,
+    pub base_color: LinearRgba,
+    pub base_color_texture: Option<AssetId<Image>>,
+    pub normal_map_texture: Option<AssetId<Image>>,
+    pub perceptual_roughness: Option<f32>,
+    pub writes_base_color: bool,
+    pub angle_fade: f32,
+    pub order: i32,
+}
+
+/// The visible decals, in the order in which they're drawn.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct ExtractedDecals(Vec<ExtractedDecal>);
+
+// NOTE: These must match the bit flags in bevy_pbr/src/decal/decal.wgsl!
+bitflags::bitflags! {
+    #[repr(transparent)]
+    pub struct DecalFlags: u32 {
+        const BASE_COLOR_TEXTURE = 1 << 0;
+        const NORMAL_MAP_TEXTURE = 1 << 1;
+        const WRITES_BASE_COLOR  = 1 << 2;
+        const WRITES_ROUGHNESS   = 1 << 3;
+    }
+}
+
+/// The GPU-side representation of a [`Decal`].
+#[derive(Clone, Copy, ShaderType, Default)]
+pub struct DecalUniform {
+    world_from_decal: Mat4,
+    decal_from_world: Mat4,
+    base_color: Vec4,
+    perceptual_roughness: f32,
+    angle_fade: f32,
+    flags: u32,
+}
+
+/// The buffer holding the [`DecalUniform`] of every visible decal.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct DecalUniformBuffer(DynamicUniformBuffer<DecalUniform>);
+
+/// The bind group of every decal whose textures are loaded, along with the offset of its
+/// [`DecalUniform`], in the order in which they're drawn.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct DecalBindGroups(Vec<(BindGroup, u32)>);
+
+/// The decal buffer of a view, which the decals are drawn into.
+///
+/// Every texture holds the premultiplied values of the decals layered on top of each other, along
+/// with their combined coverage in alpha.
+#[derive(Component)]
+pub struct ViewDecalTextures {
+    /// The base color of the decals.
+    pub base_color: CachedTexture,
+    /// The world space normal of the decals.
+    pub normal: CachedTexture,
+    /// The perceptual roughness of the decals, in the red channel.
+    pub roughness: CachedTexture,
+}
+
+impl ViewDecalTextures {
+    pub const BASE_COLOR_FORMAT: TextureFormat = TextureFormat::Rgba8UnormSrgb;
+    pub const NORMAL_FORMAT: TextureFormat = TextureFormat::Rgba16Float;
+    pub const ROUGHNESS_FORMAT: TextureFormat = TextureFormat::Rgba8Unorm;
+}
+
+/// The render pipeline used by a view to draw its decals.
+#[derive(Component, Deref, DerefMut)]
+pub struct ViewDecalPipeline(CachedRenderPipelineId);
+
+/// The bind group holding the view uniform and the depth prepass of a view, for the decal pass.
+#[derive(Component)]
+pub struct ViewDecalBindGroup(BindGroup);
+
+#[derive(Default)]
+struct DecalNode;
+
+impl ViewNode for DecalNode {
+    type ViewQuery = (
+        Read<ExtractedCamera>,
+        Read<ViewDecalTextures>,
+        Read<ViewDecalPipeline>,
+        Read<ViewDecalBindGroup>,
+        Read<ViewUniformOffset>,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (camera, decal_textures, pipeline_id, view_bind_group, view_uniform_offset): QueryItem<
+            Self::ViewQuery,
+        >,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let decal_bind_groups = world.resource::<DecalBindGroups>();
+        let Some(pipeline) = pipeline_cache.get_render_pipeline(**pipeline_id) else {
+            return Ok(());
+        };
+
+        // The pass always runs, so that the decal buffer is cleared even without any decal.
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("decal_pass"),
+            color_attachments: &[
+                color_attachment(&decal_textures.base_color),
+                color_attachment(&decal_textures.normal),
+                color_attachment(&decal_textures.roughness),
+            ],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        if let Some(viewport) = camera.viewport.as_ref() {
+            render_pass.set_camera_viewport(viewport);
+        }
+
+        render_pass.set_render_pipeline(pipeline);
+        render_pass.set_bind_group(0, &view_bind_group.0, &[view_uniform_offset.offset]);
+        for (bind_group, decal_offset) in decal_bind_groups.iter() {
+            render_pass.set_bind_group(1, bind_group, &[*decal_offset]);
+            render_pass.draw(0..36, 0..1);
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns an attachment clearing `texture` and storing what's drawn to it.
+fn color_attachment(texture: &CachedTexture) -> Option<RenderPassColorAttachment<'_>> {
+    Some(RenderPassColorAttachment {
+        view: &texture.default_view,
+        resolve_target: None,
+        ops: Operations {
+            load: LoadOp::Clear(Default::default()),
+            store: StoreOp::Store,
+        },
+    })
+}
+
+#[derive(Resource)]
+pub struct DecalPipeline {
+    view_bind_group_layout: BindGroupLayout,
+    view_bind_group_layout_multisampled: BindGroupLayout,
+    decal_bind_group_layout: BindGroupLayout,
+}
+
+impl FromWorld for DecalPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let view_bind_group_layout = render_device.create_bind_group_layout(
+            "decal_view_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::VERTEX_FRAGMENT,
+                (uniform_buffer::<ViewUniform>(true), texture_depth_2d()),
+            ),
+        );
+
+        let view_bind_group_layout_multisampled = render_device.create_bind_group_layout(
+            "decal_view_bind_group_layout_multisampled",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::VERTEX_FRAGMENT,
+                (
+                    uniform_buffer::<ViewUniform>(true),
+                    texture_depth_2d_multisampled(),
+                ),
+            ),
+        );
+
+        let decal_bind_group_layout = render_device.create_bind_group_layout(
+            "decal_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::VERTEX_FRAGMENT,
+                (
+                    uniform_buffer::<DecalUniform>(true),
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                ),
+            ),
+        );
+
+        Self {
+            view_bind_group_layout,
+            view_bind_group_layout_multisampled,
+            decal_bind_group_layout,
+        }
+    }
+}
+
+/// Identifies a specialization of the [`DecalPipeline`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DecalPipelineKey {
+    /// Whether the depth prepass of the view is multisampled.
+    pub multisampled: bool,
+}
+
+impl SpecializedRenderPipeline for DecalPipeline {
+    type Key = DecalPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        let mut shader_defs = Vec::new();
+
+        let view_bind_group_layout = if key.multisampled {
+            shader_defs.push("MULTISAMPLED".into());
+            self.view_bind_group_layout_multisampled.clone()
+        } else {
+            self.view_bind_group_layout.clone()
+        };
+
+        // Decals are composited with the "over" operator, on top of the ones drawn before them.
+        let target = |format| {
+            Some(ColorTargetState {
+                format,
+                blend: Some(BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                write_mask: ColorWrites::ALL,
+            })
+        };
+
+        RenderPipelineDescriptor {
+            label: Some("decal_pipeline".into()),
+            layout: vec![view_bind_group_layout, self.decal_bind_group_layout.clone()],
+            push_constant_ranges: vec![],
+            vertex: VertexState {
+                shader: DECAL_SHADER_HANDLE,
+                shader_defs: shader_defs.clone(),
+                entry_point: "vertex".into(),
+                buffers: vec![],
+            },
+            // Only the back faces of the box are drawn, so that each surface inside of it is
+            // covered exactly once, even when the camera is inside of the box.
+            primitive: PrimitiveState {
+                cull_mode: Some(Face::Front),
+                ..default()
+            },
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            fragment: Some(FragmentState {
+                shader: DECAL_SHADER_HANDLE,
+                shader_defs,
+                entry_point: "fragment".into(),
+                targets: vec![
+                    target(ViewDecalTextures::BASE_COLOR_FORMAT),
+                    target(ViewDecalTextures::NORMAL_FORMAT),
+                    target(ViewDecalTextures::ROUGHNESS_FORMAT),
+                ],
+            }),
+        }
+    }
+}
+
+/// Extracts the visible [`Decal`]s, and marks the views that draw them with [`ViewDecals`].
+pub fn extract_decals(
+    mut commands: Commands,
+    mut extracted_decals: ResMut<ExtractedDecals>,
+    decals: Extract<Query<(Entity, &Decal, &GlobalTransform, &InheritedVisibility)>>,
+    cameras: Extract<Query<(Entity, &Camera), (With<Camera3d>, With<DepthPrepass>)>>,
+) {
+    extracted_decals.clear();
+    extracted_decals.extend(
+        decals
+            .iter()
+            .filter(|(_, _, _, inherited_visibility)| inherited_visibility.get())
+            .map(|(entity, decal, transform, _)| ExtractedDecal {
+                entity,
+                world_from_decal: transform.compute_matrix(),
+                base_color: decal.base_color.into(),
+                base_color_texture: decal.base_color_texture.as_ref().map(Handle::id),
+                normal_map_texture: decal.normal_map_texture.as_ref().map(Handle::id),
+                perceptual_roughness: decal.perceptual_roughness,
+                writes_base_color: decal.writes_base_color,
+                angle_fade: decal.angle_fade,
+                order: decal.order,
+            }),
+    );
+    extracted_decals.sort_by_key(|decal| (decal.order, decal.entity));
+
+    if extracted_decals.is_empty() {
+        return;
+    }
+
+    for (entity, camera) in &cameras {
+        if camera.is_active {
+            commands.get_or_spawn(entity).insert(ViewDecals);
+        }
+    }
+}
+
+/// Specializes the [`DecalPipeline`] of every view that draws decals.
+pub fn prepare_decal_pipelines(
+    mut commands: Commands,
+    pipeline_cache: Res<PipelineCache>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<DecalPipeline>>,
+    decal_pipeline: Res<DecalPipeline>,
+    msaa: Res<Msaa>,
+    views: Query<Entity, With<ViewDecals>>,
+) {
+    for entity in &views {
+        let key = DecalPipelineKey {
+            multisampled: msaa.samples() > 1,
+        };
+        let pipeline_id = pipelines.specialize(&pipeline_cache, &decal_pipeline, key);
+
+        commands
+            .entity(entity)
+            .insert(ViewDecalPipeline(pipeline_id));
+    }
+}
+
+/// Allocates the [`ViewDecalTextures`] of every view that draws decals.
+pub fn prepare_decal_textures(
+    mut commands: Commands,
+    mut texture_cache: ResMut<TextureCache>,
+    render_device: Res<RenderDevice>,
+    views: Query<(Entity, &ExtractedCamera), With<ViewDecals>>,
+) {
+    for (entity, camera) in &views {
+        // The main pass reads the decal buffer with the coordinates of its fragments, which are
+        // relative to the render target rather than to the viewport.
+        let Some(physical_target_size) = camera.physical_target_size else {
+            continue;
+        };
+        let size = Extent3d {
+            width: physical_target_size.x,
+            height: physical_target_size.y,
+            depth_or_array_layers: 1,
+        };
+
+        let mut get_texture = |label, format| {
+            texture_cache.get(
+                &render_device,
+                TextureDescriptor {
+                    label: Some(label),
+                    size,
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: TextureDimension::D2,
+                    format,
+                    usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+                    view_formats: &[],
+                },
+            )
+        };
+
+        commands.entity(entity).insert(ViewDecalTextures {
+            base_color: get_texture(
+                "decal_base_color_texture",
+                ViewDecalTextures::BASE_COLOR_FORMAT,
+            ),
+            normal: get_texture("decal_normal_texture", ViewDecalTextures::NORMAL_FORMAT),
+            roughness: get_texture(
+                "decal_roughness_texture",
+                ViewDecalTextures::ROUGHNESS_FORMAT,
+            ),
+        });
+    }
+}
+
+/// Writes the [`DecalUniform`] of every visible decal, and prepares the bind groups to draw
+/// them with.
+#[allow(clippy::too_many_arguments)]
+pub fn prepare_decal_uniforms(
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    extracted_decals: Res<ExtractedDecals>,
+    images: Res<RenderAssets<GpuImage>>,
+    fallback_image: Res<FallbackImage>,
+    decal_pipeline: Res<DecalPipeline>,
+    mut decal_uniform_buffer: ResMut<DecalUniformBuffer>,
+    mut decal_bind_groups: ResMut<DecalBindGroups>,
+) {
+    decal_bind_groups.clear();
+
+    // Decals whose textures aren't loaded yet are skipped until they are.
+    let get_image = |id: Option<AssetId<Image>>| match id {
+        Some(id) => images.get(id).map(Some),
+        None => Some(None),
+    };
+    let decals: Vec<_> = extracted_decals
+        .iter()
+        .filter_map(|decal| {
+            let base_color_image = get_image(decal.base_color_texture)?;
+            let normal_map_image = get_image(decal.normal_map_texture)?;
+            Some((decal, base_color_image, normal_map_image))
+        })
+        .collect();
+
+    let Some(mut writer) =
+        decal_uniform_buffer.get_writer(decals.len(), &render_device, &render_queue)
+    else {
+        return;
+    };
+
+    let mut offsets = Vec::with_capacity(decals.len());
+    for (decal, base_color_image, normal_map_image) in &decals {
+        let mut flags = DecalFlags::empty();
+        if base_color_image.is_some() {
+            flags |= DecalFlags::BASE_COLOR_TEXTURE;
+        }
+        if normal_map_image.is_some() {
+            flags |= DecalFlags::NORMAL_MAP_TEXTURE;
+        }
+        if decal.writes_base_color {
+            flags |= DecalFlags::WRITES_BASE_COLOR;
+        }
+        if decal.perceptual_roughness.is_some() {
+            flags |= DecalFlags::WRITES_ROUGHNESS;
+        }
+
+        offsets.push(writer.write(&DecalUniform {
+            world_from_decal: decal.world_from_decal,
+            decal_from_world: decal.world_from_decal.inverse(),
+            base_color: decal.base_color.to_vec4(),
+            perceptual_roughness: decal.perceptual_roughness.unwrap_or_default(),
+            angle_fade: decal.angle_fade,
+            flags: flags.bits(),
+        }));
+    }
+    drop(writer);
+
+    let Some(decal_binding) = decal_uniform_buffer.binding() else {
+        return;
+    };
+
+    for ((_, base_color_image, normal_map_image), offset) in decals.iter().zip(offsets) {
+        let base_color_image = base_color_image.unwrap_or(&fallback_image.d2);
+        let normal_map_image = normal_map_image.unwrap_or(&fallback_image.d2);
+        let bind_group = render_device.create_bind_group(
+            "decal_bind_group",
+            &decal_pipeline.decal_bind_group_layout,
+            &BindGroupEntries::sequential((
+                decal_binding.clone(),
+                &base_color_image.texture_view,
+                &base_color_image.sampler,
+                &normal_map_image.texture_view,
+                &normal_map_image.sampler,
+            )),
+        );
+        decal_bind_groups.push((bind_group, offset));
+    }
+}
+
+/// Prepares the [`ViewDecalBindGroup`] of every view that draws decals.
+pub fn prepare_decal_bind_groups(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    view_uniforms: Res<ViewUniforms>,
+    decal_pipeline: Res<DecalPipeline>,
+    msaa: Res<Msaa>,
+    views: Query<(Entity, &ViewPrepassTextures), With<ViewDecals>>,
+) {
+    let Some(view_binding) = view_uniforms.uniforms.binding() else {
+        return;
+    };
+
+    let layout = if msaa.samples() > 1 {
+        &decal_pipeline.view_bind_group_layout_multisampled
+    } else {
+        &decal_pipeline.view_bind_group_layout
+    };
+
+    for (entity, prepass_textures) in &views {
+        let Some(depth_view) = prepass_textures.depth_view() else {
+            continue;
+        };
+
+        let bind_group = render_device.create_bind_group(
+            "decal_view_bind_group",
+            layout,
+            &BindGroupEntries::sequential((view_binding.clone(), depth_view)),
+        );
+
+        commands
+            .entity(entity)
+            .insert(ViewDecalBindGroup(bind_group));
+    }
+}
@@ -21,7 +21,9 @@ pub mod experimental {
     }
 }
 
+mod atmosphere;
 mod bundle;
+mod decal;
 pub mod deferred;
 mod extended_material;
 mod fog;
@@ -34,11 +36,18 @@ mod pbr_material;
 mod prepass;
 mod render;
 mod ssao;
+mod ssr;
+mod volumetric_fog;
+
+#[cfg(test)]
+mod test_utils;
 
 use bevy_color::{Color, LinearRgba};
 use std::marker::PhantomData;
 
+pub use atmosphere::*;
 pub use bundle::*;
+pub use decal::*;
 pub use extended_material::*;
 pub use fog::*;
 pub use light::*;
@@ -50,6 +59,8 @@ pub use pbr_material::*;
 pub use prepass::*;
 pub use render::*;
 pub use ssao::*;
+pub use ssr::*;
+pub use volumetric_fog::*;
 
 pub mod prelude {
     #[doc(hidden)]
@@ -80,9 +91,23 @@ pub mod graph {
         ShadowPass,
         /// Label for the screen space ambient occlusion render node.
         ScreenSpaceAmbientOcclusion,
+        /// Label for the screen space reflections render node.
+        ScreenSpaceReflections,
+        /// Label for the node that keeps the color history of screen space reflections.
+        ScreenSpaceReflectionsHistory,
+        /// Label for the volumetric fog render node.
+        VolumetricFog,
+        /// Label for the node that draws decals into the decal buffer.
+        Decals,
+        /// Label for the compute node that fills the look-up tables of the atmosphere.
+        AtmosphereLuts,
+        /// Label for the node that draws the sky and aerial perspective of the atmosphere.
+        RenderSky,
         DeferredLightingPass,
         /// Label for the compute shader instance data building pass.
         GpuPreprocess,
+        /// Label for the compute node that builds the depth pyramids used for occlusion culling.
+        DepthPyramid,
     }
 }
 
@@ -263,6 +288,12 @@ impl Plugin for PbrPlugin {
             "render/view_transformations.wgsl",
             Shader::from_wgsl
         );
+        load_internal_asset!(
+            app,
+            DEPTH_PYRAMID_SHADER_HANDLE,
+            "render/depth_pyramid.wgsl",
+            Shader::from_wgsl
+        );
         // Setup dummy shaders for when MeshletPlugin is not used to prevent shader import errors.
         load_internal_asset!(
             app,
@@ -280,10 +311,13 @@ impl Plugin for PbrPlugin {
             .register_type::<CubemapVisibleEntities>()
             .register_type::<DirectionalLight>()
             .register_type::<DirectionalLightShadowMap>()
+            .register_type::<LightCookie>()
             .register_type::<NotShadowCaster>()
             .register_type::<NotShadowReceiver>()
             .register_type::<PointLight>()
             .register_type::<PointLightShadowMap>()
+            .register_type::<ShadowAtlasSettings>()
+            .register_type::<ShadowPriority>()
             .register_type::<SpotLight>()
             .register_type::<FogSettings>()
             .register_type::<ShadowFilteringMethod>()
@@ -291,6 +325,7 @@ impl Plugin for PbrPlugin {
             .init_resource::<GlobalVisiblePointLights>()
             .init_resource::<DirectionalLightShadowMap>()
             .init_resource::<PointLightShadowMap>()
+            .init_resource::<ShadowAtlasSettings>()
             .register_type::<DefaultOpaqueRendererMethod>()
             .init_resource::<DefaultOpaqueRendererMethod>()
             .add_plugins((
@@ -302,6 +337,8 @@ impl Plugin for PbrPlugin {
                     ..Default::default()
                 },
                 ScreenSpaceAmbientOcclusionPlugin,
+                ScreenSpaceReflectionsPlugin,
+                VolumetricFogPlugin,
                 ExtractResourcePlugin::<AmbientLight>::default(),
                 FogPlugin,
                 ExtractResourcePlugin::<DefaultOpaqueRendererMethod>::default(),
@@ -315,6 +352,8 @@ impl Plugin for PbrPlugin {
                     use_gpu_instance_buffer_builder: self.use_gpu_instance_buffer_builder,
                 },
             ))
+            .add_plugins(DecalPlugin)
+            .add_plugins(AtmospherePlugin)
             .configure_sets(
                 PostUpdate,
                 (
@@ -388,7 +427,14 @@ impl Plugin for PbrPlugin {
 
         // Extract the required data from the main world
         render_app
-            .add_systems(ExtractSchedule, (extract_clusters, extract_lights))
+            .add_systems(
+                ExtractSchedule,
+                (
+                    extract_clusters,
+                    extract_lights,
+                    extract_shadow_caster_changes,
+                ),
+            )
             .add_systems(
                 Render,
                 (
@@ -398,7 +444,9 @@ impl Plugin for PbrPlugin {
                     prepare_clusters.in_set(RenderSet::PrepareResources),
                 ),
             )
-            .init_resource::<LightMeta>();
+            .init_resource::<LightMeta>()
+            .init_resource::<ShadowAtlas>()
+            .init_resource::<ShadowCasterChanges>();
 
         let shadow_pass_node = ShadowPassNode::new(render_app.world_mut());
         let mut graph = render_app.world_mut().resource_mut::<RenderGraph>();
@@ -415,6 +463,7 @@ impl Plugin for PbrPlugin {
         // Extract the required data from the main world
         render_app
             .init_resource::<ShadowSamplers>()
+            .init_resource::<ShadowAtlasClearPipeline>()
             .init_resource::<GlobalLightMeta>();
     }
 }
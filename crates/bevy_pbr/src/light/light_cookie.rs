@@ -0,0 +1,34 @@
+use super::*;
+
+/// A texture, known as a light cookie, that is projected by the light on the same entity and
+/// multiplies its color.
+///
+/// How the texture is projected depends on the kind of light:
+///
+/// - A [`SpotLight`] projects it across its cone, covering the square enclosing the cone at
+///   `outer_angle` and oriented the same way as the light's shadow map. This can be used for
+///   flashlights, stained glass or projectors.
+/// - A [`PointLight`] projects a cubemap outward, sampled along the world-space direction from the
+///   light to the lit surface. The image must have a
+///   [`TextureViewDimension::Cube`](bevy_render::render_resource::TextureViewDimension::Cube)
+///   texture view; other images are ignored.
+/// - A [`DirectionalLight`] projects it along its direction and tiles it across its local X/Y
+///   plane, with one tile covering one world unit scaled by the light's [`Transform`]. This can
+///   be used to project the shadows of clouds or foliage.
+///
+/// Cookies are bound as binding arrays, so the number of distinct cookie images that can be used at
+/// once depends on the platform:
+///
+/// - Where binding arrays are supported, up to [`MAX_LIGHT_COOKIES`] cookie textures, used by spot
+///   and directional lights, and up to [`MAX_LIGHT_COOKIES`] cookie cubemaps, used by point
+///   lights, can be used at once.
+/// - Where they aren't, only a single cookie texture and a single cookie cubemap can be used at
+///   once. Lights can still share them.
+/// - On WebGL 2 and WebGPU, light cookies aren't supported at all.
+///
+/// Cookies past the limit are ignored, and a warning is logged.
+///
+/// [`MAX_LIGHT_COOKIES`]: crate::MAX_LIGHT_COOKIES
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component)]
+pub struct LightCookie(pub Handle<Image>);
@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 
+use bevy_asset::Handle;
 use bevy_ecs::entity::EntityHashMap;
 use bevy_ecs::prelude::*;
 use bevy_math::{
@@ -14,6 +15,7 @@ use bevy_render::{
     primitives::{Aabb, CascadesFrusta, CubemapFrusta, Frustum, HalfSpace, Sphere},
     render_resource::BufferBindingType,
     renderer::RenderDevice,
+    texture::Image,
     view::{
         InheritedVisibility, RenderLayers, ViewVisibility, VisibilityRange, VisibleEntities,
         VisibleEntityRanges, WithMesh,
@@ -32,6 +34,8 @@ mod spot_light;
 pub use spot_light::SpotLight;
 mod directional_light;
 pub use directional_light::DirectionalLight;
+mod light_cookie;
+pub use light_cookie::LightCookie;
 
 /// Constants for operating with the light units: lumens, and lux.
 pub mod light_consts {
@@ -90,6 +94,8 @@ pub mod light_consts {
     }
 }
 
+/// Controls the maximum resolution of each face of a [`PointLight`]'s shadow map in the
+/// [`ShadowAtlasSettings`] atlas.
 #[derive(Resource, Clone, Debug, Reflect)]
 #[reflect(Resource)]
 pub struct PointLightShadowMap {
@@ -106,7 +112,8 @@ impl Default for PointLightShadowMap {
 /// With<DirectionalLight>)>`, for use with [`VisibleEntities`].
 pub type WithLight = Or<(With<PointLight>, With<SpotLight>, With<DirectionalLight>)>;
 
-/// Controls the resolution of [`DirectionalLight`] shadow maps.
+/// Controls the resolution of [`DirectionalLight`] shadow maps, and the maximum resolution of
+/// [`SpotLight`] shadow maps in the [`ShadowAtlasSettings`] atlas.
 #[derive(Resource, Clone, Debug, Reflect)]
 #[reflect(Resource)]
 pub struct DirectionalLightShadowMap {
@@ -119,6 +126,59 @@ impl Default for DirectionalLightShadowMap {
     }
 }
 
+/// Controls the shadow atlas that [`PointLight`] and [`SpotLight`] shadow maps are allocated from.
+///
+/// Each shadow casting point or spot light is given a square tile of the atlas, or a 3x2 block of
+/// tiles for the six faces of a point light. The resolution of a tile follows the light's coverage
+/// of the screen, scaled by its [`ShadowPriority`], and is limited by [`PointLightShadowMap`] and
+/// [`DirectionalLightShadowMap`] respectively. When the atlas is full, the resolution of every tile
+/// is halved until the lights fit.
+///
+/// Tiles are cached across frames, and a light's tile is only rendered again when the light moves
+/// or changes, or when a shadow caster within its range moves, changes or is added or removed.
+/// Changing a mesh asset re-renders every tile.
+#[derive(Resource, Clone, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct ShadowAtlasSettings {
+    /// The width and height of the atlas texture, in texels.
+    ///
+    /// This is clamped to the maximum texture size supported by the device, and to 16384.
+    pub size: u32,
+    /// The lowest resolution a light's tile can have, in texels.
+    pub min_tile_size: u32,
+    /// Whether tiles are kept across frames. If `false`, every tile is rendered every frame.
+    ///
+    /// Disable this if shadow casters change in ways that aren't detected, for example through
+    /// custom vertex shaders.
+    pub cache_shadows: bool,
+}
+
+impl Default for ShadowAtlasSettings {
+    fn default() -> Self {
+        Self {
+            size: 4096,
+            min_tile_size: 64,
+            cache_shadows: true,
+        }
+    }
+}
+
+/// Scales the resolution a [`PointLight`] or [`SpotLight`] is given in the shadow atlas, relative
+/// to the resolution its coverage of the screen would give it.
+///
+/// Lights without this component have a priority of `1.0`.
+///
+/// See [`ShadowAtlasSettings`] for more information.
+#[derive(Component, Clone, Copy, Debug, Reflect)]
+#[reflect(Component, Default)]
+pub struct ShadowPriority(pub f32);
+
+impl Default for ShadowPriority {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
 /// Controls how cascaded shadow mapping works.
 /// Prefer using [`CascadeShadowConfigBuilder`] to construct an instance.
 ///
@@ -4,12 +4,13 @@ use crate::meshlet::{
     MeshletGpuScene,
 };
 use crate::*;
-use bevy_asset::{Asset, AssetId, AssetServer};
+use bevy_asset::{Asset, AssetEvent, AssetId, AssetServer};
 use bevy_core_pipeline::{
     core_3d::{
         AlphaMask3d, Camera3d, Opaque3d, Opaque3dBinKey, ScreenSpaceTransmissionQuality,
         Transmissive3d, Transparent3d,
     },
+    oit::OrderIndependentTransparencySettings,
     prepass::{
         DeferredPrepass, DepthPrepass, MotionVectorPrepass, NormalPrepass, OpaqueNoLightmap3dBinKey,
     },
@@ -33,7 +34,7 @@ use bevy_render::{
     texture::FallbackImage,
     view::{ExtractedView, Msaa, RenderVisibilityRanges, VisibleEntities, WithMesh},
 };
-use bevy_utils::tracing::error;
+use bevy_utils::{tracing::error, HashMap, HashSet};
 use std::marker::PhantomData;
 use std::sync::atomic::{AtomicU32, Ordering};
 use std::{hash::Hash, num::NonZeroU32};
@@ -249,17 +250,19 @@ where
     M::Data: PartialEq + Eq + Hash + Clone,
 {
     fn build(&self, app: &mut App) {
-        app.init_asset::<M>().add_plugins((
-            ExtractInstancesPlugin::<AssetId<M>>::extract_visible(),
-            RenderAssetPlugin::<PreparedMaterial<M>>::default(),
-        ));
+        app.init_asset::<M>()
+            .add_plugins((
+                ExtractInstancesPlugin::<AssetId<M>>::extract_visible(),
+                RenderAssetPlugin::<PreparedMaterial<M>>::default(),
+            ))
+            .add_systems(PostUpdate, mark_materials_with_modified_images::<M>);
 
         if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
             render_app
                 .init_resource::<DrawFunctions<Shadow>>()
                 .add_render_command::<Shadow, DrawPrepass<M>>()
                 .add_render_command::<Transmissive3d, DrawMaterial<M>>()
-                .add_render_command::<Transparent3d, DrawMaterial<M>>()
+                .add_render_command::<Transparent3d, DrawTransparentMaterial<M>>()
                 .add_render_command::<Opaque3d, DrawMaterial<M>>()
                 .add_render_command::<AlphaMask3d, DrawMaterial<M>>()
                 .init_resource::<SpecializedMeshPipelines<MaterialPipeline<M>>>()
@@ -428,6 +431,17 @@ type DrawMaterial<M> = (
     DrawMesh,
 );
 
+/// Like [`DrawMaterial`], with the bind group of the order-independent transparency buffers of
+/// the view, when it has [`OrderIndependentTransparencySettings`].
+type DrawTransparentMaterial<M> = (
+    SetItemPipeline,
+    SetMeshViewBindGroup<0>,
+    SetMeshBindGroup<1>,
+    SetMaterialBindGroup<M, 2>,
+    SetOitBindGroup<3>,
+    DrawMesh,
+);
+
 /// Sets the bind group for a given [`Material`] at the configured `I` index.
 pub struct SetMaterialBindGroup<M: Material, const I: usize>(PhantomData<M>);
 impl<P: PhaseItem, M: Material, const I: usize> RenderCommand<P> for SetMaterialBindGroup<M, I> {
@@ -512,6 +526,84 @@ pub const fn screen_space_specular_transmission_pipeline_key(
     }
 }
 
+/// The materials using each [`Image`], kept up to date by [`mark_materials_with_modified_images`].
+pub struct MaterialsByImage<M: Material> {
+    materials: HashMap<AssetId<Image>, HashSet<AssetId<M>>>,
+    images: HashMap<AssetId<M>, Vec<AssetId<Image>>>,
+}
+
+impl<M: Material> Default for MaterialsByImage<M> {
+    fn default() -> Self {
+        Self {
+            materials: HashMap::default(),
+            images: HashMap::default(),
+        }
+    }
+}
+
+impl<M: Material> MaterialsByImage<M> {
+    fn insert(&mut self, id: AssetId<M>, material: &M) {
+        let mut images = Vec::new();
+        material.visit_dependencies(&mut |dependency| {
+            if let Ok(image) = dependency.try_typed::<Image>() {
+                images.push(image);
+            }
+        });
+        for image in &images {
+            self.materials.entry(*image).or_default().insert(id);
+        }
+        self.images.insert(id, images);
+    }
+
+    fn remove(&mut self, id: AssetId<M>) {
+        for image in self.images.remove(&id).into_iter().flatten() {
+            if let Some(materials) = self.materials.get_mut(&image) {
+                materials.remove(&id);
+                if materials.is_empty() {
+                    self.materials.remove(&image);
+                }
+            }
+        }
+    }
+}
+
+/// Marks every material that depends on a modified [`Image`] as modified too, so that its bind
+/// group is rebuilt with the new texture, for example when the image is hot-reloaded.
+pub fn mark_materials_with_modified_images<M: Material>(
+    mut material_events: EventReader<AssetEvent<M>>,
+    mut image_events: EventReader<AssetEvent<Image>>,
+    mut materials: ResMut<Assets<M>>,
+    mut materials_by_image: Local<MaterialsByImage<M>>,
+) {
+    for event in material_events.read() {
+        match *event {
+            AssetEvent::Added { id } | AssetEvent::Modified { id } => {
+                materials_by_image.remove(id);
+                if let Some(material) = materials.get(id) {
+                    materials_by_image.insert(id, material);
+                }
+            }
+            AssetEvent::Removed { id } => materials_by_image.remove(id),
+            AssetEvent::Unused { .. } | AssetEvent::LoadedWithDependencies { .. } => {}
+        }
+    }
+
+    let modified_materials = image_events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::Modified { id } => materials_by_image.materials.get(id),
+            _ => None,
+        })
+        .flatten()
+        .copied()
+        .collect::<HashSet<_>>();
+    for id in modified_materials {
+        // `get_mut` queues an `AssetEvent::Modified` for the material, which updates its images
+        // on the next run.
+        materials.get_mut(id);
+    }
+}
+
 /// For each view, iterates over all the meshes visible from that view and adds
 /// them to [`BinnedRenderPhase`]s or [`SortedRenderPhase`]s as appropriate.
 #[allow(clippy::too_many_arguments)]
@@ -536,7 +628,12 @@ pub fn queue_material_meshes<M: Material>(
         Option<&Tonemapping>,
         Option<&DebandDither>,
         Option<&ShadowFilteringMethod>,
-        Has<ScreenSpaceAmbientOcclusionSettings>,
+        (
+            Has<ScreenSpaceAmbientOcclusionSettings>,
+            Has<ScreenSpaceReflectionsSettings>,
+            Has<ViewDecals>,
+            Has<ViewLightCookies>,
+        ),
         (
             Has<NormalPrepass>,
             Has<DepthPrepass>,
@@ -544,7 +641,10 @@ pub fn queue_material_meshes<M: Material>(
             Has<DeferredPrepass>,
         ),
         Option<&Camera3d>,
-        Has<TemporalJitter>,
+        (
+            Has<TemporalJitter>,
+            Has<OrderIndependentTransparencySettings>,
+        ),
         Option<&Projection>,
         &mut BinnedRenderPhase<Opaque3d>,
         &mut BinnedRenderPhase<AlphaMask3d>,
@@ -564,10 +664,10 @@ pub fn queue_material_meshes<M: Material>(
         tonemapping,
         dither,
         shadow_filter_method,
-        ssao,
+        (ssao, ssr, decals, light_cookies),
         (normal_prepass, depth_prepass, motion_vector_prepass, deferred_prepass),
         camera_3d,
-        temporal_jitter,
+        (temporal_jitter, order_independent_transparency),
         projection,
         mut opaque_phase,
         mut alpha_mask_phase,
@@ -579,7 +679,9 @@ pub fn queue_material_meshes<M: Material>(
         let draw_opaque_pbr = opaque_draw_functions.read().id::<DrawMaterial<M>>();
         let draw_alpha_mask_pbr = alpha_mask_draw_functions.read().id::<DrawMaterial<M>>();
         let draw_transmissive_pbr = transmissive_draw_functions.read().id::<DrawMaterial<M>>();
-        let draw_transparent_pbr = transparent_draw_functions.read().id::<DrawMaterial<M>>();
+        let draw_transparent_pbr = transparent_draw_functions
+            .read()
+            .id::<DrawTransparentMaterial<M>>();
 
         let mut view_key = MeshPipelineKey::from_msaa_samples(msaa.samples())
             | MeshPipelineKey::from_hdr(view.hdr);
@@ -643,6 +745,15 @@ pub fn queue_material_meshes<M: Material>(
         if ssao {
             view_key |= MeshPipelineKey::SCREEN_SPACE_AMBIENT_OCCLUSION;
         }
+        if ssr {
+            view_key |= MeshPipelineKey::SCREEN_SPACE_REFLECTIONS;
+        }
+        if decals {
+            view_key |= MeshPipelineKey::DECALS;
+        }
+        if light_cookies {
+            view_key |= MeshPipelineKey::LIGHT_COOKIES;
+        }
         if let Some(camera_3d) = camera_3d {
             view_key |= screen_space_specular_transmission_pipeline_key(
                 camera_3d.screen_space_specular_transmission_quality,
@@ -681,6 +792,18 @@ pub fn queue_material_meshes<M: Material>(
                 mesh_key |= MeshPipelineKey::VISIBILITY_RANGE_DITHER;
             }
 
+            // Without support for order-independent transparency, the items are sorted as usual
+            if order_independent_transparency
+                && material_pipeline.mesh_pipeline.oit_layout.is_some()
+            {
+                let blend = mesh_key.intersection(MeshPipelineKey::BLEND_RESERVED_BITS);
+                if blend == MeshPipelineKey::BLEND_ALPHA
+                    || blend == MeshPipelineKey::BLEND_PREMULTIPLIED_ALPHA
+                {
+                    mesh_key |= MeshPipelineKey::OIT_ENABLED;
+                }
+            }
+
             let pipeline_id = pipelines.specialize(
                 &pipeline_cache,
                 &material_pipeline,
@@ -0,0 +1,99 @@
+#import bevy_pbr::meshlet_bindings::{
+    meshlet_animated_instance_ids,
+    meshlet_instance_animations,
+    meshlet_vertex_data,
+    meshlet_vertex_joints,
+    meshlet_joint_matrices,
+    meshlet_morph_displacements,
+    meshlet_morph_weights,
+    meshlet_animated_vertex_data,
+    meshlet_animated_displacements,
+    unpack_meshlet_vertex,
+    MeshletInstanceAnimation,
+    PackedMeshletVertex,
+    MESHLET_NOT_ANIMATED,
+}
+#import bevy_render::maths::inverse_mat3x3
+
+/// Applies morph targets and then skinning to the vertices of animated instances (1 instance per workgroup row, 1 vertex per thread),
+/// writing the results to the animated vertex data buffer in the instance's local space.
+/// Also records how far the instance's vertices moved from their rest pose, which the culling passes expand the bounding spheres by.
+
+// The furthest any vertex of the workgroup moved, as f32 bits, which compare like u32s for non-negative floats
+var<workgroup> workgroup_max_displacement: atomic<u32>;
+
+@compute
+@workgroup_size(64, 1, 1) // 64 threads per workgroup, 1 vertex per thread
+fn animate_meshlet_vertices(
+    @builtin(global_invocation_id) global_invocation_id: vec3<u32>,
+    @builtin(local_invocation_index) local_invocation_index: u32,
+) {
+    let instance_id = meshlet_animated_instance_ids[global_invocation_id.y];
+    let animation = meshlet_instance_animations[instance_id];
+    let vertex_id_local = global_invocation_id.x;
+    if vertex_id_local < animation.vertex_count {
+        let displacement = animate_vertex(animation, vertex_id_local);
+        atomicMax(&workgroup_max_displacement, bitcast<u32>(displacement));
+    }
+
+    workgroupBarrier();
+    if local_invocation_index == 0u {
+        atomicMax(&meshlet_animated_displacements[instance_id], atomicLoad(&workgroup_max_displacement));
+    }
+}
+
+// Animates a single vertex, returning how far it moved from its rest position
+fn animate_vertex(animation: MeshletInstanceAnimation, vertex_id_local: u32) -> f32 {
+    let packed_vertex = meshlet_vertex_data[animation.vertex_start + vertex_id_local];
+    let vertex = unpack_meshlet_vertex(packed_vertex);
+    var position = vertex.position;
+    var normal = vertex.normal;
+    var tangent = vertex.tangent.xyz;
+
+    // Morph targets, stored target by target
+    for (var target_id = 0u; target_id < animation.morph_target_count; target_id++) {
+        let weight = meshlet_morph_weights[animation.morph_weights_start + target_id];
+        if weight == 0.0 { continue; }
+        let displacement_id = animation.morph_displacements_start + target_id * animation.vertex_count + vertex_id_local;
+        let i = displacement_id * 9u;
+        position += weight * vec3(meshlet_morph_displacements[i], meshlet_morph_displacements[i + 1u], meshlet_morph_displacements[i + 2u]);
+        normal += weight * vec3(meshlet_morph_displacements[i + 3u], meshlet_morph_displacements[i + 4u], meshlet_morph_displacements[i + 5u]);
+        tangent += weight * vec3(meshlet_morph_displacements[i + 6u], meshlet_morph_displacements[i + 7u], meshlet_morph_displacements[i + 8u]);
+    }
+
+    // Skinning, with the joint matrices already relative to the instance's transform
+    if animation.joints_start != MESHLET_NOT_ANIMATED {
+        let i = (animation.joints_start + vertex_id_local) * 6u;
+        let weights = bitcast<vec4<f32>>(vec4(
+            meshlet_vertex_joints[i],
+            meshlet_vertex_joints[i + 1u],
+            meshlet_vertex_joints[i + 2u],
+            meshlet_vertex_joints[i + 3u],
+        ));
+        let packed_indices = vec2(meshlet_vertex_joints[i + 4u], meshlet_vertex_joints[i + 5u]);
+        let indices = animation.joint_matrices_start + vec4(
+            extractBits(packed_indices.x, 0u, 16u),
+            extractBits(packed_indices.x, 16u, 16u),
+            extractBits(packed_indices.y, 0u, 16u),
+            extractBits(packed_indices.y, 16u, 16u),
+        );
+        let skin = weights.x * meshlet_joint_matrices[indices.x]
+            + weights.y * meshlet_joint_matrices[indices.y]
+            + weights.z * meshlet_joint_matrices[indices.z]
+            + weights.w * meshlet_joint_matrices[indices.w];
+        let skin_3x3 = mat3x3(skin[0].xyz, skin[1].xyz, skin[2].xyz);
+
+        position = (skin * vec4(position, 1.0)).xyz;
+        normal = transpose(inverse_mat3x3(skin_3x3)) * normal;
+        tangent = skin_3x3 * tangent;
+    }
+
+    normal = normalize(normal);
+    var animated_vertex: PackedMeshletVertex;
+    animated_vertex.a = vec4(position, normal.x);
+    animated_vertex.b = vec4(normal.yz, vertex.uv);
+    animated_vertex.tangent = vec4(normalize(tangent), vertex.tangent.w);
+    meshlet_animated_vertex_data[animation.animated_vertex_start + vertex_id_local] = animated_vertex;
+
+    return distance(position, vertex.position);
+}
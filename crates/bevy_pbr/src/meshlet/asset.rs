@@ -11,7 +11,7 @@ use serde::{Deserialize, Serialize};
 use std::{io::Cursor, sync::Arc};
 
 /// The current version of the [`MeshletMesh`] asset format.
-pub const MESHLET_MESH_ASSET_VERSION: u64 = 0;
+pub const MESHLET_MESH_ASSET_VERSION: u64 = 2;
 
 /// A mesh that has been pre-processed into multiple small clusters of triangles called meshlets.
 ///
@@ -19,6 +19,12 @@ pub const MESHLET_MESH_ASSET_VERSION: u64 = 0;
 /// The conversion step is very slow, and is meant to be ran once ahead of time, and not during runtime. This type of mesh is not suitable for
 /// dynamically generated geometry.
 ///
+/// Skinned meshes and meshes with morph targets are supported: add a [`bevy_render::mesh::skinning::SkinnedMesh`]
+/// and/or [`bevy_render::mesh::morph::MeshMorphWeights`] component to the entity, as with a regular mesh. Animated vertices are
+/// computed on the GPU every frame, before rasterization. Meshlets of animated meshes are culled using their rest pose bounds
+/// expanded by how far the mesh's vertices moved, their level of detail is chosen using their rest pose bounds, and their
+/// motion vectors only account for the entity's transform.
+///
 /// There are restrictions on the [`crate::Material`] functionality that can be used with this type of mesh.
 /// * Materials have no control over the vertex shader or vertex attributes.
 /// * Materials must be opaque. Transparent, alpha masked, and transmissive materials are not supported.
@@ -41,6 +47,20 @@ pub struct MeshletMesh {
     pub meshlets: Arc<[Meshlet]>,
     /// Spherical bounding volumes.
     pub bounding_spheres: Arc<[MeshletBoundingSpheres]>,
+    /// The joints influencing each vertex in `vertex_data`. Empty if the mesh is not skinned.
+    pub vertex_joints: Arc<[MeshletVertexJoints]>,
+    /// The number of morph targets of the mesh.
+    pub morph_target_count: u32,
+    /// The displacement of each vertex in `vertex_data` for each morph target, stored target by target.
+    /// Empty if the mesh has no morph targets.
+    pub morph_displacements: Arc<[MeshletMorphDisplacement]>,
+}
+
+impl MeshletMesh {
+    /// Returns true if the mesh is skinned or has morph targets, and so must be animated on the GPU.
+    pub fn is_animated(&self) -> bool {
+        !self.vertex_joints.is_empty() || self.morph_target_count != 0
+    }
 }
 
 /// A single meshlet within a [`MeshletMesh`].
@@ -56,6 +76,9 @@ pub struct Meshlet {
 }
 
 /// Bounding spheres used for culling and choosing level of detail for a [`Meshlet`].
+///
+/// The radius of the LOD bounding spheres is the object-space simplification error of the meshlet (or its parent),
+/// accumulated from LOD 0. A parent's LOD bounding sphere encloses those of all of its children.
 #[derive(Serialize, Deserialize, Copy, Clone, Pod, Zeroable)]
 #[repr(C)]
 pub struct MeshletBoundingSpheres {
@@ -75,6 +98,25 @@ pub struct MeshletBoundingSphere {
     pub radius: f32,
 }
 
+/// The joints influencing a single vertex of a skinned [`MeshletMesh`].
+#[derive(Serialize, Deserialize, Copy, Clone, Pod, Zeroable)]
+#[repr(C)]
+pub struct MeshletVertexJoints {
+    /// The weight of each joint.
+    pub weights: [f32; 4],
+    /// The indices of the joints within the [`bevy_render::mesh::skinning::SkinnedMesh`], two per `u32`.
+    pub indices: [u32; 2],
+}
+
+/// The displacement of a single vertex of a [`MeshletMesh`] by a morph target at full weight.
+#[derive(Serialize, Deserialize, Copy, Clone, Pod, Zeroable)]
+#[repr(C)]
+pub struct MeshletMorphDisplacement {
+    pub position: Vec3,
+    pub normal: Vec3,
+    pub tangent: Vec3,
+}
+
 /// An [`AssetLoader`] and [`AssetSaver`] for `.meshlet_mesh` [`MeshletMesh`] assets.
 pub struct MeshletMeshSaverLoad;
 
@@ -3,6 +3,7 @@
     meshlet_bounding_spheres,
     meshlet_cluster_instance_ids,
     meshlet_instance_uniforms,
+    meshlet_animated_displacements,
     meshlet_second_pass_candidates,
     depth_pyramid,
     view,
@@ -41,6 +42,11 @@ fn cull_meshlets(
     if should_cull_instance(instance_id) { return; }
 #endif
 
+    // Animated instances move away from their rest pose bounding spheres, so the culling bounding spheres are expanded by the
+    // furthest any of their vertices moved this frame (0 if not animated), while the rest pose LOD bounding spheres are still
+    // used to choose a consistent LOD cut
+    let animated_displacement = meshlet_animated_displacements[instance_id];
+
     // Calculate world-space culling bounding sphere for the cluster
     let instance_uniform = meshlet_instance_uniforms[instance_id];
     let meshlet_id = meshlet_cluster_meshlet_ids[cluster_id];
@@ -48,7 +54,7 @@ fn cull_meshlets(
     let model_scale = max(length(model[0]), max(length(model[1]), length(model[2])));
     let bounding_spheres = meshlet_bounding_spheres[meshlet_id];
     var culling_bounding_sphere_center = model * vec4(bounding_spheres.self_culling.center, 1.0);
-    var culling_bounding_sphere_radius = model_scale * bounding_spheres.self_culling.radius;
+    var culling_bounding_sphere_radius = model_scale * (bounding_spheres.self_culling.radius + animated_displacement);
 
 #ifdef MESHLET_FIRST_CULLING_PASS
     // Frustum culling
@@ -80,7 +86,7 @@ fn cull_meshlets(
     let previous_model = affine3_to_square(instance_uniform.previous_model);
     let previous_model_scale = max(length(previous_model[0]), max(length(previous_model[1]), length(previous_model[2])));
     culling_bounding_sphere_center = previous_model * vec4(bounding_spheres.self_culling.center, 1.0);
-    culling_bounding_sphere_radius = previous_model_scale * bounding_spheres.self_culling.radius;
+    culling_bounding_sphere_radius = previous_model_scale * (bounding_spheres.self_culling.radius + animated_displacement);
 #endif
     let culling_bounding_sphere_center_view_space = (view.inverse_view * vec4(culling_bounding_sphere_center.xyz, 1.0)).xyz;
 
@@ -129,10 +135,19 @@ fn cull_meshlets(
 }
 
 // https://stackoverflow.com/questions/21648630/radius-of-projected-sphere-in-screen-space/21649403#21649403
+// The LOD bounding sphere's radius is the simplification error, so this checks if the error projects to less than a pixel
 fn lod_error_is_imperceptible(cp: vec3<f32>, r: f32) -> bool {
-    let d2 = dot(cp, cp);
-    let r2 = r * r;
-    let sphere_diameter_uv = view.projection[0][0] * r / sqrt(d2 - r2);
+    var sphere_diameter_uv: f32;
+    if view.projection[3][3] == 1.0 {
+        // Orthographic
+        sphere_diameter_uv = view.projection[0][0] * r;
+    } else {
+        // Perspective, where the error is always perceptible from inside the sphere
+        let d2 = dot(cp, cp);
+        let r2 = r * r;
+        if d2 <= r2 { return false; }
+        sphere_diameter_uv = view.projection[0][0] * r / sqrt(d2 - r2);
+    }
     let view_size = f32(max(view.viewport.z, view.viewport.w));
     let sphere_diameter_pixels = sphere_diameter_uv * view_size;
     return sphere_diameter_pixels < 1.0;
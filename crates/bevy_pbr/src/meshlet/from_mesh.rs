@@ -1,7 +1,11 @@
-use super::asset::{Meshlet, MeshletBoundingSphere, MeshletBoundingSpheres, MeshletMesh};
+use super::asset::{
+    Meshlet, MeshletBoundingSphere, MeshletBoundingSpheres, MeshletMesh, MeshletMorphDisplacement,
+    MeshletVertexJoints,
+};
 use bevy_render::{
-    mesh::{Indices, Mesh},
+    mesh::{morph::MorphAttributes, Indices, Mesh, VertexAttributeValues},
     render_resource::PrimitiveTopology,
+    texture::Image,
 };
 use bevy_utils::{HashMap, HashSet};
 use itertools::Itertools;
@@ -11,7 +15,7 @@ use meshopt::{
     simplify, simplify_scale, Meshlets, SimplifyOptions, VertexDataAdapter,
 };
 use metis::Graph;
-use std::{borrow::Cow, ops::Range};
+use std::{borrow::Cow, mem::size_of, ops::Range};
 
 impl MeshletMesh {
     /// Process a [`Mesh`] to generate a [`MeshletMesh`].
@@ -23,10 +27,46 @@ impl MeshletMesh {
     /// The input mesh must:
     /// 1. Use [`PrimitiveTopology::TriangleList`]
     /// 2. Use indices
-    /// 3. Have the exact following set of vertex attributes: `{POSITION, NORMAL, UV_0, TANGENT}`
+    /// 3. Have the exact following set of vertex attributes: `{POSITION, NORMAL, UV_0, TANGENT}`,
+    ///    optionally followed by `{JOINT_WEIGHT, JOINT_INDEX}` for skinned meshes
+    /// 4. Have no morph targets. Use [`MeshletMesh::from_morphed_mesh`] for meshes with morph targets.
     pub fn from_mesh(mesh: &Mesh) -> Result<Self, MeshToMeshletMeshConversionError> {
+        if mesh.has_morph_targets() {
+            return Err(MeshToMeshletMeshConversionError::MeshHasMorphTargets);
+        }
+
+        Self::from_mesh_and_morph_targets(mesh, None)
+    }
+
+    /// Process a [`Mesh`] with morph targets to generate a [`MeshletMesh`].
+    ///
+    /// `morph_targets` is the image the mesh's morph targets are stored in, as built by
+    /// [`MorphTargetImage`](bevy_render::mesh::morph::MorphTargetImage).
+    ///
+    /// See [`MeshletMesh::from_mesh`] for the other requirements on the input mesh.
+    pub fn from_morphed_mesh(
+        mesh: &Mesh,
+        morph_targets: &Image,
+    ) -> Result<Self, MeshToMeshletMeshConversionError> {
+        Self::from_mesh_and_morph_targets(mesh, Some(morph_targets))
+    }
+
+    fn from_mesh_and_morph_targets(
+        mesh: &Mesh,
+        morph_targets: Option<&Image>,
+    ) -> Result<Self, MeshToMeshletMeshConversionError> {
         // Validate mesh format
         let indices = validate_input_mesh(mesh)?;
+        let vertex_joints = read_vertex_joints(mesh)?;
+        let (morph_target_count, morph_displacements) = match morph_targets {
+            Some(morph_targets) => read_morph_targets(morph_targets, mesh.count_vertices())?,
+            None => (0, Vec::new()),
+        };
+
+        // Joints are stored separately from the rest of the vertex data, which has a fixed layout
+        let mut mesh = mesh.clone();
+        mesh.remove_attribute(Mesh::ATTRIBUTE_JOINT_WEIGHT);
+        mesh.remove_attribute(Mesh::ATTRIBUTE_JOINT_INDEX);
 
         // Split the mesh into an initial list of meshlets (LOD 0)
         let vertex_buffer = mesh.get_vertex_buffer_data();
@@ -86,17 +126,26 @@ impl MeshletMesh {
 
                 // Add the maximum child error to the parent error to make parent error cumulative from LOD 0
                 // (we're currently building the parent from its children)
-                group_error += group_meshlets.iter().fold(group_error, |acc, meshlet_id| {
-                    acc.max(bounding_spheres[*meshlet_id].self_lod.radius)
-                });
+                group_error += group_meshlets
+                    .iter()
+                    .map(|meshlet_id| bounding_spheres[*meshlet_id].self_lod.radius)
+                    .fold(0.0, f32::max);
 
-                // Build a new LOD bounding sphere for the simplified group as a whole
+                // Build a new LOD bounding sphere for the simplified group as a whole, with the error as its radius
                 let mut group_bounding_sphere = convert_meshlet_bounds(compute_cluster_bounds(
                     &simplified_group_indices,
                     &vertices,
                 ));
                 group_bounding_sphere.radius = group_error;
 
+                // Grow the group's LOD bounding sphere to enclose the LOD bounding spheres of its children.
+                // The projected error of the group can then never be smaller than that of one of its children from
+                // any viewpoint, so that exactly one LOD is chosen for every part of the mesh.
+                let group_bounding_sphere = group_meshlets
+                    .iter()
+                    .map(|meshlet_id| bounding_spheres[*meshlet_id].self_lod)
+                    .fold(group_bounding_sphere, merge_bounding_spheres);
+
                 // For each meshlet in the group set their parent LOD bounding sphere to that of the simplified group
                 for meshlet_id in group_meshlets {
                     bounding_spheres[*meshlet_id].parent_lod = group_bounding_sphere;
@@ -150,6 +199,9 @@ impl MeshletMesh {
             indices: meshlets.triangles.into(),
             meshlets: bevy_meshlets,
             bounding_spheres: bounding_spheres.into(),
+            vertex_joints: vertex_joints.into(),
+            morph_target_count,
+            morph_displacements: morph_displacements.into(),
         })
     }
 }
@@ -159,12 +211,19 @@ fn validate_input_mesh(mesh: &Mesh) -> Result<Cow<'_, [u32]>, MeshToMeshletMeshC
         return Err(MeshToMeshletMeshConversionError::WrongMeshPrimitiveTopology);
     }
 
-    if mesh.attributes().map(|(id, _)| id).ne([
+    let static_attributes = [
         Mesh::ATTRIBUTE_POSITION.id,
         Mesh::ATTRIBUTE_NORMAL.id,
         Mesh::ATTRIBUTE_UV_0.id,
         Mesh::ATTRIBUTE_TANGENT.id,
-    ]) {
+    ];
+    let skinned_attributes = static_attributes.into_iter().chain([
+        Mesh::ATTRIBUTE_JOINT_WEIGHT.id,
+        Mesh::ATTRIBUTE_JOINT_INDEX.id,
+    ]);
+    if mesh.attributes().map(|(id, _)| id).ne(static_attributes)
+        && mesh.attributes().map(|(id, _)| id).ne(skinned_attributes)
+    {
         return Err(MeshToMeshletMeshConversionError::WrongMeshVertexAttributes);
     }
 
@@ -175,6 +234,64 @@ fn validate_input_mesh(mesh: &Mesh) -> Result<Cow<'_, [u32]>, MeshToMeshletMeshC
     }
 }
 
+fn read_vertex_joints(
+    mesh: &Mesh,
+) -> Result<Vec<MeshletVertexJoints>, MeshToMeshletMeshConversionError> {
+    match (
+        mesh.attribute(Mesh::ATTRIBUTE_JOINT_WEIGHT),
+        mesh.attribute(Mesh::ATTRIBUTE_JOINT_INDEX),
+    ) {
+        (None, None) => Ok(Vec::new()),
+        (
+            Some(VertexAttributeValues::Float32x4(weights)),
+            Some(VertexAttributeValues::Uint16x4(indices)),
+        ) => Ok(weights
+            .iter()
+            .zip(indices)
+            .map(|(weights, indices)| MeshletVertexJoints {
+                weights: *weights,
+                indices: [
+                    indices[0] as u32 | ((indices[1] as u32) << 16),
+                    indices[2] as u32 | ((indices[3] as u32) << 16),
+                ],
+            })
+            .collect()),
+        _ => Err(MeshToMeshletMeshConversionError::WrongMeshVertexAttributes),
+    }
+}
+
+/// Reads the displacements of every vertex for every target out of a morph target image.
+fn read_morph_targets(
+    morph_targets: &Image,
+    vertex_count: usize,
+) -> Result<(u32, Vec<MeshletMorphDisplacement>), MeshToMeshletMeshConversionError> {
+    let size = morph_targets.texture_descriptor.size;
+    let target_count = size.depth_or_array_layers;
+
+    // Each layer of the image holds one target, padded to fill the layer
+    let layer_size = (size.width * size.height) as usize * size_of::<f32>();
+    let target_size = vertex_count * size_of::<MorphAttributes>();
+    if target_size > layer_size || morph_targets.data.len() < layer_size * target_count as usize {
+        return Err(MeshToMeshletMeshConversionError::WrongMorphTargets);
+    }
+
+    let displacements = morph_targets
+        .data
+        .chunks_exact(layer_size)
+        .take(target_count as usize)
+        .flat_map(|layer| {
+            bytemuck::pod_collect_to_vec::<u8, MorphAttributes>(&layer[..target_size])
+        })
+        .map(|attributes| MeshletMorphDisplacement {
+            position: attributes.position,
+            normal: attributes.normal,
+            tangent: attributes.tangent,
+        })
+        .collect();
+
+    Ok((target_count, displacements))
+}
+
 fn compute_meshlets(indices: &[u32], vertices: &VertexDataAdapter) -> Meshlets {
     let mut meshlets = build_meshlets(indices, vertices, 64, 64, 0.0);
 
@@ -355,13 +472,119 @@ fn convert_meshlet_bounds(bounds: meshopt_Bounds) -> MeshletBoundingSphere {
     }
 }
 
-/// An error produced by [`MeshletMesh::from_mesh`].
+/// Returns the smallest sphere enclosing both spheres.
+fn merge_bounding_spheres(
+    a: MeshletBoundingSphere,
+    b: MeshletBoundingSphere,
+) -> MeshletBoundingSphere {
+    let distance = a.center.distance(b.center);
+    if distance + b.radius <= a.radius {
+        return a;
+    }
+    if distance + a.radius <= b.radius {
+        return b;
+    }
+
+    let radius = (distance + a.radius + b.radius) * 0.5;
+    let center = a.center + (b.center - a.center) * ((radius - a.radius) / distance);
+    MeshletBoundingSphere { center, radius }
+}
+
+/// An error produced by [`MeshletMesh::from_mesh`] and [`MeshletMesh::from_morphed_mesh`].
 #[derive(thiserror::Error, Debug)]
 pub enum MeshToMeshletMeshConversionError {
     #[error("Mesh primitive topology is not TriangleList")]
     WrongMeshPrimitiveTopology,
-    #[error("Mesh attributes are not {{POSITION, NORMAL, UV_0, TANGENT}}")]
+    #[error("Mesh attributes are not {{POSITION, NORMAL, UV_0, TANGENT}}, optionally followed by {{JOINT_WEIGHT, JOINT_INDEX}}")]
     WrongMeshVertexAttributes,
     #[error("Mesh has no indices")]
     MeshMissingIndices,
+    #[error("Mesh has morph targets, but no morph target image was provided")]
+    MeshHasMorphTargets,
+    #[error("Morph target image does not match the mesh's vertex count")]
+    WrongMorphTargets,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_math::{primitives::Sphere, Vec3};
+    use bevy_render::{
+        mesh::{morph::MorphTargetImage, Meshable},
+        render_asset::RenderAssetUsages,
+    };
+
+    fn sphere() -> Mesh {
+        Sphere::default()
+            .mesh()
+            .uv(32, 18)
+            .with_generated_tangents()
+            .unwrap()
+    }
+
+    #[test]
+    fn skinned_mesh_keeps_its_joints() {
+        let mut mesh = sphere();
+        let vertex_count = mesh.count_vertices();
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_JOINT_WEIGHT,
+            vec![[0.25, 0.25, 0.5, 0.0]; vertex_count],
+        );
+        mesh.insert_attribute(
+            Mesh::ATTRIBUTE_JOINT_INDEX,
+            VertexAttributeValues::Uint16x4(
+                (0..vertex_count as u16).map(|i| [i, 1, 2, 3]).collect(),
+            ),
+        );
+
+        let meshlet_mesh = MeshletMesh::from_mesh(&mesh).unwrap();
+        assert!(meshlet_mesh.is_animated());
+        assert_eq!(meshlet_mesh.morph_target_count, 0);
+        assert_eq!(meshlet_mesh.vertex_joints.len(), vertex_count);
+        // The joints are stored apart from the vertex data, which keeps its static layout.
+        assert_eq!(
+            meshlet_mesh.vertex_data.len(),
+            sphere().get_vertex_buffer_data().len()
+        );
+        for (i, joints) in meshlet_mesh.vertex_joints.iter().enumerate() {
+            assert_eq!(joints.weights, [0.25, 0.25, 0.5, 0.0]);
+            assert_eq!(joints.indices, [i as u32 | (1 << 16), 2 | (3 << 16)]);
+        }
+    }
+
+    #[test]
+    fn morphed_mesh_keeps_its_displacements() {
+        let mut mesh = sphere();
+        let vertex_count = mesh.count_vertices();
+        let targets = (0..2).map(|target| {
+            (0..vertex_count).map(move |i| MorphAttributes {
+                position: Vec3::new(i as f32, target as f32, 0.0),
+                normal: Vec3::Y,
+                tangent: Vec3::X,
+            })
+        });
+        let morph_targets =
+            MorphTargetImage::new(targets, vertex_count, RenderAssetUsages::default()).unwrap();
+        mesh.set_morph_targets(Default::default());
+
+        assert!(matches!(
+            MeshletMesh::from_mesh(&mesh),
+            Err(MeshToMeshletMeshConversionError::MeshHasMorphTargets)
+        ));
+
+        let meshlet_mesh = MeshletMesh::from_morphed_mesh(&mesh, &morph_targets.0).unwrap();
+        assert!(meshlet_mesh.is_animated());
+        assert!(meshlet_mesh.vertex_joints.is_empty());
+        assert_eq!(meshlet_mesh.morph_target_count, 2);
+        assert_eq!(meshlet_mesh.morph_displacements.len(), 2 * vertex_count);
+        for (i, displacement) in meshlet_mesh.morph_displacements.iter().enumerate() {
+            let (target, vertex) = (i / vertex_count, i % vertex_count);
+            assert_eq!(
+                displacement.position,
+                Vec3::new(vertex as f32, target as f32, 0.0)
+            );
+            assert_eq!(displacement.normal, Vec3::Y);
+            assert_eq!(displacement.tangent, Vec3::X);
+        }
+    }
 }
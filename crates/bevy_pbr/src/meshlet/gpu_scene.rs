@@ -1,9 +1,14 @@
 use super::{
-    asset::{Meshlet, MeshletBoundingSpheres, MeshletMesh},
+    asset::{
+        Meshlet, MeshletBoundingSpheres, MeshletMesh, MeshletMorphDisplacement, MeshletVertexJoints,
+    },
     persistent_buffer::PersistentGpuBuffer,
+    persistent_buffer_impls::MESHLET_VERTEX_SIZE_IN_BYTES,
 };
 use crate::{
-    Material, MeshFlags, MeshTransforms, MeshUniform, NotShadowCaster, NotShadowReceiver,
+    depth_pyramid_size, depth_pyramid_texture_descriptor, DepthPyramidBindGroups,
+    DepthPyramidPipelineKey, DepthPyramidPipelines, DepthPyramidViews, Material, MeshFlags,
+    MeshTransforms, MeshUniform, NotDecalReceiver, NotShadowCaster, NotShadowReceiver,
     PreviousGlobalTransform, PreviousViewData, PreviousViewUniforms, RenderMaterialInstances,
     ShadowView,
 };
@@ -17,7 +22,12 @@ use bevy_ecs::{
     system::{Commands, Local, Query, Res, ResMut, Resource, SystemState},
     world::{FromWorld, World},
 };
+use bevy_math::{Mat4, Vec4Swizzles};
 use bevy_render::{
+    mesh::{
+        morph::MeshMorphWeights,
+        skinning::{SkinnedMesh, SkinnedMeshInverseBindposes},
+    },
     render_resource::{binding_types::*, *},
     renderer::{RenderDevice, RenderQueue},
     texture::{CachedTexture, TextureCache},
@@ -26,6 +36,7 @@ use bevy_render::{
 };
 use bevy_transform::components::GlobalTransform;
 use bevy_utils::{default, HashMap, HashSet};
+use bytemuck::{Pod, Zeroable};
 use encase::internal::WriteInto;
 use std::{
     iter,
@@ -51,22 +62,34 @@ pub fn extract_meshlet_meshes(
                     Option<&PreviousGlobalTransform>,
                     Option<&RenderLayers>,
                     Has<NotShadowReceiver>,
+                    Has<NotDecalReceiver>,
                     Has<NotShadowCaster>,
+                    Option<&SkinnedMesh>,
+                    Option<&MeshMorphWeights>,
                 )>,
+                Query<&GlobalTransform>,
                 Res<AssetServer>,
                 ResMut<Assets<MeshletMesh>>,
+                Res<Assets<SkinnedMeshInverseBindposes>>,
                 EventReader<AssetEvent<MeshletMesh>>,
             )>,
         >,
     >,
+    mut joint_matrices: Local<Vec<Mat4>>,
 ) {
     if system_state.is_none() {
         *system_state = Some(SystemState::new(&mut main_world));
     }
     let system_state = system_state.as_mut().unwrap();
 
-    let (instances_query, asset_server, mut assets, mut asset_events) =
-        system_state.get_mut(&mut main_world);
+    let (
+        instances_query,
+        joints_query,
+        asset_server,
+        mut assets,
+        inverse_bindposes,
+        mut asset_events,
+    ) = system_state.get_mut(&mut main_world);
 
     // Reset all temporary data for MeshletGpuScene
     gpu_scene.reset();
@@ -75,10 +98,21 @@ pub fn extract_meshlet_meshes(
     for asset_event in asset_events.read() {
         if let AssetEvent::Unused { id } | AssetEvent::Modified { id } = asset_event {
             if let Some((
-                [vertex_data_slice, vertex_ids_slice, indices_slice, meshlets_slice, meshlet_bounding_spheres_slice],
+                [vertex_data_slice, vertex_ids_slice, indices_slice, meshlets_slice, meshlet_bounding_spheres_slice, vertex_joints_slice, morph_displacements_slice],
                 _,
             )) = gpu_scene.meshlet_mesh_slices.remove(id)
             {
+                // Static meshes have no joints or morph targets, and so were never allocated space for them
+                if !vertex_joints_slice.is_empty() {
+                    gpu_scene
+                        .vertex_joints
+                        .mark_slice_unused(vertex_joints_slice);
+                }
+                if !morph_displacements_slice.is_empty() {
+                    gpu_scene
+                        .morph_displacements
+                        .mark_slice_unused(morph_displacements_slice);
+                }
                 gpu_scene.vertex_data.mark_slice_unused(vertex_data_slice);
                 gpu_scene.vertex_ids.mark_slice_unused(vertex_ids_slice);
                 gpu_scene.indices.mark_slice_unused(indices_slice);
@@ -97,7 +131,10 @@ pub fn extract_meshlet_meshes(
         previous_transform,
         render_layers,
         not_shadow_receiver,
+        not_decal_receiver,
         not_shadow_caster,
+        skin,
+        morph_weights,
     ) in &instances_query
     {
         // Skip instances with an unloaded MeshletMesh asset
@@ -107,6 +144,28 @@ pub fn extract_meshlet_meshes(
             continue;
         }
 
+        // Skinned vertices are computed in the instance's local space, so that the instance's transform still applies to them
+        let transform = transform.affine();
+        joint_matrices.clear();
+        if let Some((skin, inverse_bindposes)) =
+            skin.and_then(|skin| Some((skin, inverse_bindposes.get(&skin.inverse_bindposes)?)))
+        {
+            let inverse_transform = transform.inverse();
+            joint_matrices.extend(
+                joints_query
+                    .iter_many(&skin.joints)
+                    .zip(inverse_bindposes.iter())
+                    .map(|(joint, bindpose)| {
+                        Mat4::from(inverse_transform * joint.affine()) * *bindpose
+                    }),
+            );
+            // iter_many will skip any failed fetches, which would assign the wrong joints to vertices,
+            // so just leave the instance unskinned.
+            if joint_matrices.len() != skin.joints.len() {
+                joint_matrices.clear();
+            }
+        }
+
         // Upload the instance's MeshletMesh asset data, if not done already, along with other per-frame per-instance data.
         gpu_scene.queue_meshlet_mesh_upload(
             instance,
@@ -114,16 +173,20 @@ pub fn extract_meshlet_meshes(
             not_shadow_caster,
             handle,
             &mut assets,
+            (!joint_matrices.is_empty()).then_some(joint_matrices.as_slice()),
+            morph_weights.map(MeshMorphWeights::weights),
         );
 
         // Build a MeshUniform for each instance
-        let transform = transform.affine();
         let previous_transform = previous_transform.map(|t| t.0).unwrap_or(transform);
         let mut flags = if not_shadow_receiver {
             MeshFlags::empty()
         } else {
             MeshFlags::SHADOW_RECEIVER
         };
+        if !not_decal_receiver {
+            flags |= MeshFlags::DECAL_RECEIVER;
+        }
         if transform.matrix3.determinant().is_sign_positive() {
             flags |= MeshFlags::SIGN_DETERMINANT_MODEL_3X3;
         }
@@ -160,6 +223,12 @@ pub fn perform_pending_meshlet_mesh_writes(
     gpu_scene
         .meshlet_bounding_spheres
         .perform_writes(&render_queue, &render_device);
+    gpu_scene
+        .vertex_joints
+        .perform_writes(&render_queue, &render_device);
+    gpu_scene
+        .morph_displacements
+        .perform_writes(&render_queue, &render_device);
 }
 
 /// For each entity in the scene, record what material ID (for use with depth testing during the meshlet mesh material draw nodes)
@@ -240,6 +309,30 @@ pub fn prepare_meshlet_per_frame_resources(
         &render_device,
         &render_queue,
     );
+    upload_storage_buffer(
+        &mut gpu_scene.instance_animations,
+        &render_device,
+        &render_queue,
+    );
+
+    // Empty buffers can't be bound, so pad the animation inputs if nothing in the scene is animated
+    let animated_instance_count = gpu_scene.animated_instance_ids.get().len() as u32;
+    if animated_instance_count == 0 {
+        gpu_scene.animated_instance_ids.get_mut().push(0);
+    }
+    if gpu_scene.joint_matrices.get().is_empty() {
+        gpu_scene.joint_matrices.get_mut().push(Mat4::IDENTITY);
+    }
+    if gpu_scene.morph_weights.get().is_empty() {
+        gpu_scene.morph_weights.get_mut().push(0.0);
+    }
+    upload_storage_buffer(
+        &mut gpu_scene.animated_instance_ids,
+        &render_device,
+        &render_queue,
+    );
+    upload_storage_buffer(&mut gpu_scene.joint_matrices, &render_device, &render_queue);
+    upload_storage_buffer(&mut gpu_scene.morph_weights, &render_device, &render_queue);
 
     // Early submission for GPU data uploads to start while the render graph records commands
     render_queue.submit([]);
@@ -272,6 +365,37 @@ pub fn prepare_meshlet_per_frame_resources(
         }
     };
 
+    let needed_buffer_size =
+        MESHLET_VERTEX_SIZE_IN_BYTES as u64 * gpu_scene.scene_animated_vertex_count.max(1) as u64;
+    match &mut gpu_scene.animated_vertex_data {
+        Some(buffer) if buffer.size() >= needed_buffer_size => buffer.clone(),
+        slot => {
+            let buffer = render_device.create_buffer(&BufferDescriptor {
+                label: Some("meshlet_animated_vertex_data"),
+                size: needed_buffer_size,
+                usage: BufferUsages::STORAGE,
+                mapped_at_creation: false,
+            });
+            *slot = Some(buffer.clone());
+            buffer
+        }
+    };
+
+    let needed_buffer_size = 4 * gpu_scene.instance_animations.get().len().max(1) as u64;
+    let animated_displacements = match &mut gpu_scene.animated_displacements {
+        Some(buffer) if buffer.size() >= needed_buffer_size => buffer.clone(),
+        slot => {
+            let buffer = render_device.create_buffer(&BufferDescriptor {
+                label: Some("meshlet_animated_displacements"),
+                size: needed_buffer_size,
+                usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+            *slot = Some(buffer.clone());
+            buffer
+        }
+    };
+
     let needed_buffer_size = 4 * gpu_scene.scene_triangle_count;
     let visibility_buffer_draw_triangle_buffer =
         match &mut gpu_scene.visibility_buffer_draw_triangle_buffer {
@@ -373,49 +497,31 @@ pub fn prepare_meshlet_per_frame_resources(
                 usage: BufferUsages::STORAGE | BufferUsages::INDIRECT,
             });
 
-        let depth_pyramid_size = Extent3d {
-            // Round down to the nearest power of 2 to ensure depth is conservative
-            width: previous_power_of_2(view.viewport.z),
-            height: previous_power_of_2(view.viewport.w),
-            depth_or_array_layers: 1,
-        };
-        let depth_pyramid_mip_count = depth_pyramid_size.max_mips(TextureDimension::D2);
-        let depth_pyramid = texture_cache.get(
-            &render_device,
-            TextureDescriptor {
-                label: Some("meshlet_depth_pyramid"),
-                size: depth_pyramid_size,
-                mip_level_count: depth_pyramid_mip_count,
-                sample_count: 1,
-                dimension: TextureDimension::D2,
-                format: TextureFormat::R32Float,
-                usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
-                view_formats: &[],
-            },
+        // Depth pyramids of shadow atlas tiles are cleared with render passes
+        let mut depth_pyramid_descriptor = depth_pyramid_texture_descriptor(
+            "meshlet_depth_pyramid",
+            depth_pyramid_size(view.viewport.zw()),
+        );
+        depth_pyramid_descriptor.usage |= TextureUsages::RENDER_ATTACHMENT;
+        let depth_pyramid = DepthPyramidViews::new(
+            &texture_cache
+                .get(&render_device, depth_pyramid_descriptor)
+                .texture,
         );
-        let depth_pyramid_mips = (0..depth_pyramid_mip_count)
-            .map(|i| {
-                depth_pyramid.texture.create_view(&TextureViewDescriptor {
-                    label: Some("meshlet_depth_pyramid_texture_view"),
-                    format: Some(TextureFormat::R32Float),
-                    dimension: Some(TextureViewDimension::D2),
-                    aspect: TextureAspect::All,
-                    base_mip_level: i,
-                    mip_level_count: Some(1),
-                    base_array_layer: 0,
-                    array_layer_count: Some(1),
-                })
-            })
-            .collect::<Box<[TextureView]>>();
-        let depth_pyramid_all_mips = depth_pyramid.default_view.clone();
 
+        // Shadow atlas tiles are only rendered when out of date, so they have no previous depth
+        // pyramid, and their depth pyramid is cleared instead of built to disable occlusion culling.
+        let renders_to_shadow_atlas =
+            matches!(shadow_view, Some(shadow_view) if shadow_view.viewport.is_some());
         let previous_depth_pyramid = match gpu_scene.previous_depth_pyramids.get(&view_entity) {
-            Some(texture_view) => texture_view.clone(),
-            None => depth_pyramid_all_mips.clone(),
+            Some(texture_view) if !renders_to_shadow_atlas => texture_view.clone(),
+            _ => depth_pyramid.all_mips.clone(),
         };
-        gpu_scene
-            .previous_depth_pyramids
-            .insert(view_entity, depth_pyramid_all_mips.clone());
+        if !renders_to_shadow_atlas {
+            gpu_scene
+                .previous_depth_pyramids
+                .insert(view_entity, depth_pyramid.all_mips.clone());
+        }
 
         let material_depth_color = TextureDescriptor {
             label: Some("meshlet_material_depth_color"),
@@ -450,6 +556,9 @@ pub fn prepare_meshlet_per_frame_resources(
         let not_shadow_view = shadow_view.is_none();
         commands.entity(view_entity).insert(MeshletViewResources {
             scene_meshlet_count: gpu_scene.scene_meshlet_count,
+            animated_instance_count,
+            max_animated_vertex_count: gpu_scene.max_animated_vertex_count,
+            animated_displacements: animated_displacements.clone(),
             second_pass_candidates_buffer,
             instance_visibility,
             visibility_buffer: not_shadow_view
@@ -457,8 +566,7 @@ pub fn prepare_meshlet_per_frame_resources(
             visibility_buffer_draw_indirect_args_first,
             visibility_buffer_draw_indirect_args_second,
             visibility_buffer_draw_triangle_buffer: visibility_buffer_draw_triangle_buffer.clone(),
-            depth_pyramid_all_mips,
-            depth_pyramid_mips,
+            depth_pyramid,
             previous_depth_pyramid,
             material_depth_color: not_shadow_view
                 .then(|| texture_cache.get(&render_device, material_depth_color)),
@@ -478,16 +586,19 @@ pub fn prepare_meshlet_view_bind_groups(
     view_uniforms: Res<ViewUniforms>,
     previous_view_uniforms: Res<PreviousViewUniforms>,
     render_device: Res<RenderDevice>,
+    mut depth_pyramid_pipelines: DepthPyramidPipelines,
     mut commands: Commands,
 ) {
     let (
         Some(cluster_instance_ids),
         Some(cluster_meshlet_ids),
+        Some(animated_vertex_data),
         Some(view_uniforms),
         Some(previous_view_uniforms),
     ) = (
         gpu_scene.cluster_instance_ids.as_ref(),
         gpu_scene.cluster_meshlet_ids.as_ref(),
+        gpu_scene.animated_vertex_data.as_ref(),
         view_uniforms.uniforms.binding(),
         previous_view_uniforms.uniforms.binding(),
     )
@@ -514,6 +625,23 @@ pub fn prepare_meshlet_view_bind_groups(
             &entries,
         );
 
+        let entries = BindGroupEntries::sequential((
+            gpu_scene.animated_instance_ids.binding().unwrap(),
+            gpu_scene.instance_animations.binding().unwrap(),
+            gpu_scene.vertex_data.binding(),
+            gpu_scene.vertex_joints.binding(),
+            gpu_scene.joint_matrices.binding().unwrap(),
+            gpu_scene.morph_displacements.binding(),
+            gpu_scene.morph_weights.binding().unwrap(),
+            animated_vertex_data.as_entire_binding(),
+            view_resources.animated_displacements.as_entire_binding(),
+        ));
+        let animate_vertices = render_device.create_bind_group(
+            "meshlet_animate_vertices_bind_group",
+            &gpu_scene.animate_vertices_bind_group_layout,
+            &entries,
+        );
+
         let entries = BindGroupEntries::sequential((
             cluster_meshlet_ids.as_entire_binding(),
             gpu_scene.meshlet_bounding_spheres.binding(),
@@ -533,6 +661,7 @@ pub fn prepare_meshlet_view_bind_groups(
             &view_resources.previous_depth_pyramid,
             view_uniforms.clone(),
             previous_view_uniforms.clone(),
+            view_resources.animated_displacements.as_entire_binding(),
         ));
         let culling_first = render_device.create_bind_group(
             "meshlet_culling_first_bind_group",
@@ -556,9 +685,10 @@ pub fn prepare_meshlet_view_bind_groups(
             view_resources
                 .visibility_buffer_draw_triangle_buffer
                 .as_entire_binding(),
-            &view_resources.depth_pyramid_all_mips,
+            &view_resources.depth_pyramid.all_mips,
             view_uniforms.clone(),
             previous_view_uniforms.clone(),
+            view_resources.animated_displacements.as_entire_binding(),
         ));
         let culling_second = render_device.create_bind_group(
             "meshlet_culling_second_bind_group",
@@ -571,22 +701,12 @@ pub fn prepare_meshlet_view_bind_groups(
             (None, Some(shadow_view)) => &shadow_view.depth_attachment.view,
             _ => unreachable!(),
         };
-        let downsample_depth = (0..view_resources.depth_pyramid_mips.len())
-            .map(|i| {
-                render_device.create_bind_group(
-                    "meshlet_downsample_depth_bind_group",
-                    &gpu_scene.downsample_depth_bind_group_layout,
-                    &BindGroupEntries::sequential((
-                        if i == 0 {
-                            view_depth_texture
-                        } else {
-                            &view_resources.depth_pyramid_mips[i - 1]
-                        },
-                        &gpu_scene.depth_pyramid_sampler,
-                    )),
-                )
-            })
-            .collect();
+        let depth_pyramid = depth_pyramid_pipelines.create_bind_groups(
+            DepthPyramidPipelineKey::empty(),
+            view_depth_texture,
+            view_uniforms.clone(),
+            &view_resources.depth_pyramid,
+        );
 
         let entries = BindGroupEntries::sequential((
             cluster_meshlet_ids.as_entire_binding(),
@@ -601,6 +721,8 @@ pub fn prepare_meshlet_view_bind_groups(
                 .visibility_buffer_draw_triangle_buffer
                 .as_entire_binding(),
             view_uniforms.clone(),
+            gpu_scene.instance_animations.binding().unwrap(),
+            animated_vertex_data.as_entire_binding(),
         ));
         let visibility_buffer_raster = render_device.create_bind_group(
             "meshlet_visibility_raster_buffer_bind_group",
@@ -638,6 +760,8 @@ pub fn prepare_meshlet_view_bind_groups(
                     gpu_scene.vertex_data.binding(),
                     cluster_instance_ids.as_entire_binding(),
                     gpu_scene.instance_uniforms.binding().unwrap(),
+                    gpu_scene.instance_animations.binding().unwrap(),
+                    animated_vertex_data.as_entire_binding(),
                 ));
                 render_device.create_bind_group(
                     "meshlet_mesh_material_draw_bind_group",
@@ -649,9 +773,10 @@ pub fn prepare_meshlet_view_bind_groups(
         commands.entity(view_entity).insert(MeshletViewBindGroups {
             first_node: Arc::clone(&first_node),
             fill_cluster_buffers,
+            animate_vertices,
             culling_first,
             culling_second,
-            downsample_depth,
+            depth_pyramid,
             visibility_buffer_raster,
             copy_material_depth,
             material_draw,
@@ -667,9 +792,13 @@ pub struct MeshletGpuScene {
     indices: PersistentGpuBuffer<Arc<[u8]>>,
     meshlets: PersistentGpuBuffer<Arc<[Meshlet]>>,
     meshlet_bounding_spheres: PersistentGpuBuffer<Arc<[MeshletBoundingSpheres]>>,
-    meshlet_mesh_slices: HashMap<AssetId<MeshletMesh>, ([Range<BufferAddress>; 5], u64)>,
+    vertex_joints: PersistentGpuBuffer<Arc<[MeshletVertexJoints]>>,
+    morph_displacements: PersistentGpuBuffer<Arc<[MeshletMorphDisplacement]>>,
+    meshlet_mesh_slices: HashMap<AssetId<MeshletMesh>, ([Range<BufferAddress>; 7], u64)>,
 
     scene_meshlet_count: u32,
+    scene_animated_vertex_count: u32,
+    max_animated_vertex_count: u32,
     scene_triangle_count: u64,
     next_material_id: u32,
     material_id_lookup: HashMap<UntypedAssetId, u32>,
@@ -683,6 +812,16 @@ pub struct MeshletGpuScene {
     instance_material_ids: StorageBuffer<Vec<u32>>,
     instance_meshlet_counts_prefix_sum: StorageBuffer<Vec<u32>>,
     instance_meshlet_slice_starts: StorageBuffer<Vec<u32>>,
+    /// Per-instance skinning and morph target data, and where to read the instance's vertices from
+    instance_animations: StorageBuffer<Vec<MeshletInstanceAnimation>>,
+    /// IDs of the instances that are skinned or morphed
+    animated_instance_ids: StorageBuffer<Vec<u32>>,
+    joint_matrices: StorageBuffer<Vec<Mat4>>,
+    morph_weights: StorageBuffer<Vec<f32>>,
+    /// Vertices of animated instances, written each frame by the animation pass
+    animated_vertex_data: Option<Buffer>,
+    /// How far the vertices of each instance moved from their rest pose, written each frame by the animation pass
+    animated_displacements: Option<Buffer>,
     cluster_instance_ids: Option<Buffer>,
     cluster_meshlet_ids: Option<Buffer>,
     second_pass_candidates_buffer: Option<Buffer>,
@@ -690,12 +829,11 @@ pub struct MeshletGpuScene {
     visibility_buffer_draw_triangle_buffer: Option<Buffer>,
 
     fill_cluster_buffers_bind_group_layout: BindGroupLayout,
+    animate_vertices_bind_group_layout: BindGroupLayout,
     culling_bind_group_layout: BindGroupLayout,
     visibility_buffer_raster_bind_group_layout: BindGroupLayout,
-    downsample_depth_bind_group_layout: BindGroupLayout,
     copy_material_depth_bind_group_layout: BindGroupLayout,
     material_draw_bind_group_layout: BindGroupLayout,
-    depth_pyramid_sampler: Sampler,
 }
 
 impl FromWorld for MeshletGpuScene {
@@ -711,9 +849,16 @@ impl FromWorld for MeshletGpuScene {
                 "meshlet_bounding_spheres",
                 render_device,
             ),
+            vertex_joints: PersistentGpuBuffer::new("meshlet_vertex_joints", render_device),
+            morph_displacements: PersistentGpuBuffer::new(
+                "meshlet_morph_displacements",
+                render_device,
+            ),
             meshlet_mesh_slices: HashMap::new(),
 
             scene_meshlet_count: 0,
+            scene_animated_vertex_count: 0,
+            max_animated_vertex_count: 0,
             scene_triangle_count: 0,
             next_material_id: 0,
             material_id_lookup: HashMap::new(),
@@ -740,6 +885,28 @@ impl FromWorld for MeshletGpuScene {
                 buffer.set_label(Some("meshlet_instance_meshlet_slice_starts"));
                 buffer
             },
+            instance_animations: {
+                let mut buffer = StorageBuffer::default();
+                buffer.set_label(Some("meshlet_instance_animations"));
+                buffer
+            },
+            animated_instance_ids: {
+                let mut buffer = StorageBuffer::default();
+                buffer.set_label(Some("meshlet_animated_instance_ids"));
+                buffer
+            },
+            joint_matrices: {
+                let mut buffer = StorageBuffer::default();
+                buffer.set_label(Some("meshlet_joint_matrices"));
+                buffer
+            },
+            morph_weights: {
+                let mut buffer = StorageBuffer::default();
+                buffer.set_label(Some("meshlet_morph_weights"));
+                buffer
+            },
+            animated_vertex_data: None,
+            animated_displacements: None,
             cluster_instance_ids: None,
             cluster_meshlet_ids: None,
             second_pass_candidates_buffer: None,
@@ -759,8 +926,8 @@ impl FromWorld for MeshletGpuScene {
                     ),
                 ),
             ),
-            culling_bind_group_layout: render_device.create_bind_group_layout(
-                "meshlet_culling_bind_group_layout",
+            animate_vertices_bind_group_layout: render_device.create_bind_group_layout(
+                "meshlet_animate_vertices_bind_group_layout",
                 &BindGroupLayoutEntries::sequential(
                     ShaderStages::COMPUTE,
                     (
@@ -769,23 +936,31 @@ impl FromWorld for MeshletGpuScene {
                         storage_buffer_read_only_sized(false, None),
                         storage_buffer_read_only_sized(false, None),
                         storage_buffer_read_only_sized(false, None),
-                        storage_buffer_sized(false, None),
+                        storage_buffer_read_only_sized(false, None),
                         storage_buffer_read_only_sized(false, None),
                         storage_buffer_sized(false, None),
                         storage_buffer_sized(false, None),
-                        texture_2d(TextureSampleType::Float { filterable: false }),
-                        uniform_buffer::<ViewUniform>(true),
-                        uniform_buffer::<PreviousViewData>(true),
                     ),
                 ),
             ),
-            downsample_depth_bind_group_layout: render_device.create_bind_group_layout(
-                "meshlet_downsample_depth_bind_group_layout",
+            culling_bind_group_layout: render_device.create_bind_group_layout(
+                "meshlet_culling_bind_group_layout",
                 &BindGroupLayoutEntries::sequential(
-                    ShaderStages::FRAGMENT,
+                    ShaderStages::COMPUTE,
                     (
+                        storage_buffer_read_only_sized(false, None),
+                        storage_buffer_read_only_sized(false, None),
+                        storage_buffer_read_only_sized(false, None),
+                        storage_buffer_read_only_sized(false, None),
+                        storage_buffer_read_only_sized(false, None),
+                        storage_buffer_sized(false, None),
+                        storage_buffer_read_only_sized(false, None),
+                        storage_buffer_sized(false, None),
+                        storage_buffer_sized(false, None),
                         texture_2d(TextureSampleType::Float { filterable: false }),
-                        sampler(SamplerBindingType::NonFiltering),
+                        uniform_buffer::<ViewUniform>(true),
+                        uniform_buffer::<PreviousViewData>(true),
+                        storage_buffer_read_only_sized(false, None),
                     ),
                 ),
             ),
@@ -804,6 +979,8 @@ impl FromWorld for MeshletGpuScene {
                         storage_buffer_read_only_sized(false, None),
                         storage_buffer_read_only_sized(false, None),
                         uniform_buffer::<ViewUniform>(true),
+                        storage_buffer_read_only_sized(false, None),
+                        storage_buffer_read_only_sized(false, None),
                     ),
                 ),
             ),
@@ -827,13 +1004,11 @@ impl FromWorld for MeshletGpuScene {
                         storage_buffer_read_only_sized(false, None),
                         storage_buffer_read_only_sized(false, None),
                         storage_buffer_read_only_sized(false, None),
+                        storage_buffer_read_only_sized(false, None),
+                        storage_buffer_read_only_sized(false, None),
                     ),
                 ),
             ),
-            depth_pyramid_sampler: render_device.create_sampler(&SamplerDescriptor {
-                label: Some("meshlet_depth_pyramid_sampler"),
-                ..default()
-            }),
         }
     }
 }
@@ -843,6 +1018,8 @@ impl MeshletGpuScene {
     fn reset(&mut self) {
         // TODO: Shrink capacity if saturation is low
         self.scene_meshlet_count = 0;
+        self.scene_animated_vertex_count = 0;
+        self.max_animated_vertex_count = 0;
         self.scene_triangle_count = 0;
         self.next_material_id = 0;
         self.material_id_lookup.clear();
@@ -855,6 +1032,10 @@ impl MeshletGpuScene {
         self.instance_material_ids.get_mut().clear();
         self.instance_meshlet_counts_prefix_sum.get_mut().clear();
         self.instance_meshlet_slice_starts.get_mut().clear();
+        self.instance_animations.get_mut().clear();
+        self.animated_instance_ids.get_mut().clear();
+        self.joint_matrices.get_mut().clear();
+        self.morph_weights.get_mut().clear();
         // TODO: Remove unused entries for view_instance_visibility and previous_depth_pyramids
     }
 
@@ -865,6 +1046,8 @@ impl MeshletGpuScene {
         not_shadow_caster: bool,
         handle: &Handle<MeshletMesh>,
         assets: &mut Assets<MeshletMesh>,
+        joint_matrices: Option<&[Mat4]>,
+        morph_weights: Option<&[f32]>,
     ) {
         let queue_meshlet_mesh = |asset_id: &AssetId<MeshletMesh>| {
             let meshlet_mesh = assets.remove_untracked(*asset_id).expect(
@@ -889,6 +1072,20 @@ impl MeshletGpuScene {
                 .meshlet_bounding_spheres
                 .queue_write(Arc::clone(&meshlet_mesh.bounding_spheres), ());
 
+            // Leave the slices empty for meshes without joints or morph targets, as empty writes can't be uploaded
+            let vertex_joints_slice = if meshlet_mesh.vertex_joints.is_empty() {
+                0..0
+            } else {
+                self.vertex_joints
+                    .queue_write(Arc::clone(&meshlet_mesh.vertex_joints), ())
+            };
+            let morph_displacements_slice = if meshlet_mesh.morph_displacements.is_empty() {
+                0..0
+            } else {
+                self.morph_displacements
+                    .queue_write(Arc::clone(&meshlet_mesh.morph_displacements), ())
+            };
+
             (
                 [
                     vertex_data_slice,
@@ -896,13 +1093,18 @@ impl MeshletGpuScene {
                     indices_slice,
                     meshlets_slice,
                     meshlet_bounding_spheres_slice,
+                    vertex_joints_slice,
+                    morph_displacements_slice,
                 ],
                 meshlet_mesh.worst_case_meshlet_triangles,
             )
         };
 
         // If the MeshletMesh asset has not been uploaded to the GPU yet, queue it for uploading
-        let ([_, _, _, meshlets_slice, _], triangle_count) = self
+        let (
+            [vertex_data_slice, _, _, meshlets_slice, _, vertex_joints_slice, morph_displacements_slice],
+            triangle_count,
+        ) = self
             .meshlet_mesh_slices
             .entry(handle.id())
             .or_insert_with_key(queue_meshlet_mesh)
@@ -911,7 +1113,54 @@ impl MeshletGpuScene {
         let meshlets_slice = (meshlets_slice.start as u32 / size_of::<Meshlet>() as u32)
             ..(meshlets_slice.end as u32 / size_of::<Meshlet>() as u32);
 
+        // Find where the instance's vertices, joints, and morph targets are, and queue its joint matrices and morph weights
+        let vertex_start = vertex_data_slice.start as u32 / MESHLET_VERTEX_SIZE_IN_BYTES;
+        let vertex_count =
+            (vertex_data_slice.end - vertex_data_slice.start) as u32 / MESHLET_VERTEX_SIZE_IN_BYTES;
+        let mut animation = MeshletInstanceAnimation {
+            vertex_start,
+            vertex_count,
+            animated_vertex_start: u32::MAX,
+            joints_start: u32::MAX,
+            ..default()
+        };
+        if let (false, Some(joint_matrices)) = (vertex_joints_slice.is_empty(), joint_matrices) {
+            animation.joints_start =
+                (vertex_joints_slice.start / size_of::<MeshletVertexJoints>() as u64) as u32;
+            animation.joint_matrices_start = self.joint_matrices.get().len() as u32;
+            self.joint_matrices
+                .get_mut()
+                .extend_from_slice(joint_matrices);
+        }
+        if let (false, Some(morph_weights)) = (morph_displacements_slice.is_empty(), morph_weights)
+        {
+            let displacement_size = size_of::<MeshletMorphDisplacement>() as u64;
+            let displacement_count = (morph_displacements_slice.end
+                - morph_displacements_slice.start)
+                / displacement_size;
+            animation.morph_displacements_start =
+                (morph_displacements_slice.start / displacement_size) as u32;
+            animation.morph_weights_start = self.morph_weights.get().len() as u32;
+            animation.morph_target_count = displacement_count as u32 / vertex_count;
+            self.morph_weights.get_mut().extend(
+                morph_weights
+                    .iter()
+                    .copied()
+                    .chain(iter::repeat(0.0))
+                    .take(animation.morph_target_count as usize),
+            );
+        }
+        if animation.joints_start != u32::MAX || animation.morph_target_count != 0 {
+            animation.animated_vertex_start = self.scene_animated_vertex_count;
+            self.scene_animated_vertex_count += vertex_count;
+            self.max_animated_vertex_count = self.max_animated_vertex_count.max(vertex_count);
+            self.animated_instance_ids
+                .get_mut()
+                .push(self.instances.len() as u32);
+        }
+
         // Append instance data for this frame
+        self.instance_animations.get_mut().push(animation);
         self.instances
             .push((instance, render_layers, not_shadow_caster));
         self.instance_material_ids.get_mut().push(0);
@@ -945,12 +1194,12 @@ impl MeshletGpuScene {
         self.fill_cluster_buffers_bind_group_layout.clone()
     }
 
-    pub fn culling_bind_group_layout(&self) -> BindGroupLayout {
-        self.culling_bind_group_layout.clone()
+    pub fn animate_vertices_bind_group_layout(&self) -> BindGroupLayout {
+        self.animate_vertices_bind_group_layout.clone()
     }
 
-    pub fn downsample_depth_bind_group_layout(&self) -> BindGroupLayout {
-        self.downsample_depth_bind_group_layout.clone()
+    pub fn culling_bind_group_layout(&self) -> BindGroupLayout {
+        self.culling_bind_group_layout.clone()
     }
 
     pub fn visibility_buffer_raster_bind_group_layout(&self) -> BindGroupLayout {
@@ -966,17 +1215,36 @@ impl MeshletGpuScene {
     }
 }
 
+/// Where to read an instance's vertices from, and how to skin and morph them if it is animated.
+#[derive(ShaderType, Pod, Zeroable, Clone, Copy, Default)]
+#[repr(C)]
+pub struct MeshletInstanceAnimation {
+    /// Index of the instance's first vertex within the vertex data buffer.
+    vertex_start: u32,
+    vertex_count: u32,
+    /// Index of the instance's first vertex within the animated vertex data buffer, or `u32::MAX` if not animated.
+    animated_vertex_start: u32,
+    /// Index of the joints of the instance's first vertex, or `u32::MAX` if not skinned.
+    joints_start: u32,
+    joint_matrices_start: u32,
+    morph_displacements_start: u32,
+    morph_weights_start: u32,
+    morph_target_count: u32,
+}
+
 #[derive(Component)]
 pub struct MeshletViewResources {
     pub scene_meshlet_count: u32,
+    pub animated_instance_count: u32,
+    pub max_animated_vertex_count: u32,
+    pub animated_displacements: Buffer,
     pub second_pass_candidates_buffer: Buffer,
     instance_visibility: Buffer,
     pub visibility_buffer: Option<CachedTexture>,
     pub visibility_buffer_draw_indirect_args_first: Buffer,
     pub visibility_buffer_draw_indirect_args_second: Buffer,
     visibility_buffer_draw_triangle_buffer: Buffer,
-    depth_pyramid_all_mips: TextureView,
-    pub depth_pyramid_mips: Box<[TextureView]>,
+    pub depth_pyramid: DepthPyramidViews,
     previous_depth_pyramid: TextureView,
     pub material_depth_color: Option<CachedTexture>,
     pub material_depth: Option<CachedTexture>,
@@ -986,20 +1254,11 @@ pub struct MeshletViewResources {
 pub struct MeshletViewBindGroups {
     pub first_node: Arc<AtomicBool>,
     pub fill_cluster_buffers: BindGroup,
+    pub animate_vertices: BindGroup,
     pub culling_first: BindGroup,
     pub culling_second: BindGroup,
-    pub downsample_depth: Box<[BindGroup]>,
+    pub depth_pyramid: DepthPyramidBindGroups,
     pub visibility_buffer_raster: BindGroup,
     pub copy_material_depth: Option<BindGroup>,
     pub material_draw: Option<BindGroup>,
 }
-
-fn previous_power_of_2(x: u32) -> u32 {
-    // If x is a power of 2, halve it
-    if x.count_ones() == 1 {
-        x / 2
-    } else {
-        // Else calculate the largest power of 2 that is less than x
-        1 << (31 - x.leading_zeros())
-    }
-}
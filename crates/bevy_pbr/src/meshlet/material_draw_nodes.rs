@@ -8,7 +8,7 @@ use super::{
 };
 use crate::{
     MeshViewBindGroup, PrepassViewBindGroup, PreviousViewUniformOffset, ViewFogUniformOffset,
-    ViewLightProbesUniformOffset, ViewLightsUniformOffset,
+    ViewLightProbesUniformOffset, ViewLightsUniformOffset, ViewScreenSpaceReflectionsUniformOffset,
 };
 use bevy_core_pipeline::prepass::ViewPrepassTextures;
 use bevy_ecs::{query::QueryItem, world::World};
@@ -22,6 +22,7 @@ use bevy_render::{
     renderer::RenderContext,
     view::{ViewTarget, ViewUniformOffset},
 };
+use smallvec::{smallvec, SmallVec};
 
 /// Fullscreen shading pass based on the visibility buffer generated from rasterizing meshlets.
 #[derive(Default)]
@@ -35,6 +36,7 @@ impl ViewNode for MeshletMainOpaquePass3dNode {
         &'static ViewLightsUniformOffset,
         &'static ViewFogUniformOffset,
         &'static ViewLightProbesUniformOffset,
+        Option<&'static ViewScreenSpaceReflectionsUniformOffset>,
         &'static MeshletViewMaterialsMainOpaquePass,
         &'static MeshletViewBindGroups,
         &'static MeshletViewResources,
@@ -52,6 +54,7 @@ impl ViewNode for MeshletMainOpaquePass3dNode {
             view_lights_offset,
             view_fog_offset,
             view_light_probes_offset,
+            view_ssr_offset,
             meshlet_view_materials,
             meshlet_view_bind_groups,
             meshlet_view_resources,
@@ -95,16 +98,16 @@ impl ViewNode for MeshletMainOpaquePass3dNode {
             render_pass.set_camera_viewport(viewport);
         }
 
-        render_pass.set_bind_group(
-            0,
-            &mesh_view_bind_group.value,
-            &[
-                view_uniform_offset.offset,
-                view_lights_offset.offset,
-                view_fog_offset.offset,
-                **view_light_probes_offset,
-            ],
-        );
+        let mut offsets: SmallVec<[u32; 5]> = smallvec![
+            view_uniform_offset.offset,
+            view_lights_offset.offset,
+            view_fog_offset.offset,
+            **view_light_probes_offset,
+        ];
+        if let Some(view_ssr_offset) = view_ssr_offset {
+            offsets.push(**view_ssr_offset);
+        }
+        render_pass.set_bind_group(0, &mesh_view_bind_group.value, &offsets);
         render_pass.set_bind_group(1, meshlet_material_draw_bind_group, &[]);
 
         // 1 fullscreen triangle draw per material
@@ -44,6 +44,19 @@ struct MeshletBoundingSphere {
     radius: f32,
 }
 
+struct MeshletInstanceAnimation {
+    vertex_start: u32,
+    vertex_count: u32,
+    animated_vertex_start: u32,
+    joints_start: u32,
+    joint_matrices_start: u32,
+    morph_displacements_start: u32,
+    morph_weights_start: u32,
+    morph_target_count: u32,
+}
+
+const MESHLET_NOT_ANIMATED: u32 = 0xFFFFFFFFu;
+
 struct DrawIndirectArgs {
     vertex_count: atomic<u32>,
     instance_count: u32,
@@ -59,6 +72,18 @@ var<push_constant> cluster_count: u32;
 @group(0) @binding(3) var<storage, read_write> meshlet_cluster_meshlet_ids: array<u32>; // Per cluster
 #endif
 
+#ifdef MESHLET_ANIMATION_PASS
+@group(0) @binding(0) var<storage, read> meshlet_animated_instance_ids: array<u32>; // Per animated entity instance
+@group(0) @binding(1) var<storage, read> meshlet_instance_animations: array<MeshletInstanceAnimation>; // Per entity instance
+@group(0) @binding(2) var<storage, read> meshlet_vertex_data: array<PackedMeshletVertex>; // Many per meshlet
+@group(0) @binding(3) var<storage, read> meshlet_vertex_joints: array<u32>; // 4 f32 weights and 4 u16 joint indices per skinned vertex
+@group(0) @binding(4) var<storage, read> meshlet_joint_matrices: array<mat4x4<f32>>; // Many per skinned entity instance
+@group(0) @binding(5) var<storage, read> meshlet_morph_displacements: array<f32>; // 9 f32s per vertex per morph target
+@group(0) @binding(6) var<storage, read> meshlet_morph_weights: array<f32>; // Per morph target per morphed entity instance
+@group(0) @binding(7) var<storage, read_write> meshlet_animated_vertex_data: array<PackedMeshletVertex>; // Per animated entity instance vertex
+@group(0) @binding(8) var<storage, read_write> meshlet_animated_displacements: array<atomic<u32>>; // Per entity instance, f32 bits
+#endif
+
 #ifdef MESHLET_CULLING_PASS
 @group(0) @binding(0) var<storage, read> meshlet_cluster_meshlet_ids: array<u32>; // Per cluster
 @group(0) @binding(1) var<storage, read> meshlet_bounding_spheres: array<MeshletBoundingSpheres>; // Per meshlet
@@ -72,6 +97,7 @@ var<push_constant> cluster_count: u32;
 @group(0) @binding(9) var depth_pyramid: texture_2d<f32>; // From the end of the last frame for the first culling pass, and from the first raster pass for the second culling pass
 @group(0) @binding(10) var<uniform> view: View;
 @group(0) @binding(11) var<uniform> previous_view: PreviousViewUniforms;
+@group(0) @binding(12) var<storage, read> meshlet_animated_displacements: array<f32>; // Per entity instance, how far its vertices moved from their rest pose this frame
 
 fn should_cull_instance(instance_id: u32) -> bool {
     let bit_offset = instance_id % 32u;
@@ -97,12 +123,24 @@ fn cluster_is_second_pass_candidate(cluster_id: u32) -> bool {
 @group(0) @binding(7) var<storage, read> meshlet_instance_material_ids: array<u32>; // Per entity instance
 @group(0) @binding(8) var<storage, read> draw_triangle_buffer: array<u32>; // Single object shared between all workgroups/meshlets/triangles
 @group(0) @binding(9) var<uniform> view: View;
+@group(0) @binding(10) var<storage, read> meshlet_instance_animations: array<MeshletInstanceAnimation>; // Per entity instance
+@group(0) @binding(11) var<storage, read> meshlet_animated_vertex_data: array<PackedMeshletVertex>; // Per animated entity instance vertex
 
 fn get_meshlet_index(index_id: u32) -> u32 {
     let packed_index = meshlet_indices[index_id / 4u];
     let bit_offset = (index_id % 4u) * 8u;
     return extractBits(packed_index, bit_offset, 8u);
 }
+
+// Animated instances read the vertices written for them this frame by the animation pass
+fn get_meshlet_vertex(instance_id: u32, vertex_id: u32) -> MeshletVertex {
+    let animation = meshlet_instance_animations[instance_id];
+    if animation.animated_vertex_start == MESHLET_NOT_ANIMATED {
+        return unpack_meshlet_vertex(meshlet_vertex_data[vertex_id]);
+    }
+    let animated_vertex_id = animation.animated_vertex_start + vertex_id - animation.vertex_start;
+    return unpack_meshlet_vertex(meshlet_animated_vertex_data[animated_vertex_id]);
+}
 #endif
 
 #ifdef MESHLET_MESH_MATERIAL_PASS
@@ -114,10 +152,22 @@ fn get_meshlet_index(index_id: u32) -> u32 {
 @group(1) @binding(5) var<storage, read> meshlet_vertex_data: array<PackedMeshletVertex>; // Many per meshlet
 @group(1) @binding(6) var<storage, read> meshlet_cluster_instance_ids: array<u32>; // Per cluster
 @group(1) @binding(7) var<storage, read> meshlet_instance_uniforms: array<Mesh>; // Per entity instance
+@group(1) @binding(8) var<storage, read> meshlet_instance_animations: array<MeshletInstanceAnimation>; // Per entity instance
+@group(1) @binding(9) var<storage, read> meshlet_animated_vertex_data: array<PackedMeshletVertex>; // Per animated entity instance vertex
 
 fn get_meshlet_index(index_id: u32) -> u32 {
     let packed_index = meshlet_indices[index_id / 4u];
     let bit_offset = (index_id % 4u) * 8u;
     return extractBits(packed_index, bit_offset, 8u);
 }
+
+// Animated instances read the vertices written for them this frame by the animation pass
+fn get_meshlet_vertex(instance_id: u32, vertex_id: u32) -> MeshletVertex {
+    let animation = meshlet_instance_animations[instance_id];
+    if animation.animated_vertex_start == MESHLET_NOT_ANIMATED {
+        return unpack_meshlet_vertex(meshlet_vertex_data[vertex_id]);
+    }
+    let animated_vertex_id = animation.animated_vertex_start + vertex_id - animation.vertex_start;
+    return unpack_meshlet_vertex(meshlet_animated_vertex_data[animated_vertex_id]);
+}
 #endif
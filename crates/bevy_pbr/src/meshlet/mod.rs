@@ -48,13 +48,13 @@ use self::{
         MeshletViewMaterialsPrepass,
     },
     pipelines::{
-        MeshletPipelines, MESHLET_COPY_MATERIAL_DEPTH_SHADER_HANDLE, MESHLET_CULLING_SHADER_HANDLE,
-        MESHLET_DOWNSAMPLE_DEPTH_SHADER_HANDLE, MESHLET_FILL_CLUSTER_BUFFERS_SHADER_HANDLE,
-        MESHLET_VISIBILITY_BUFFER_RASTER_SHADER_HANDLE,
+        MeshletPipelines, MESHLET_ANIMATE_VERTICES_SHADER_HANDLE,
+        MESHLET_COPY_MATERIAL_DEPTH_SHADER_HANDLE, MESHLET_CULLING_SHADER_HANDLE,
+        MESHLET_FILL_CLUSTER_BUFFERS_SHADER_HANDLE, MESHLET_VISIBILITY_BUFFER_RASTER_SHADER_HANDLE,
     },
     visibility_buffer_raster_node::MeshletVisibilityBufferRasterPassNode,
 };
-use crate::{graph::NodePbr, Material};
+use crate::{graph::NodePbr, DepthPyramidPipeline, Material};
 use bevy_app::{App, Plugin, PostUpdate};
 use bevy_asset::{load_internal_asset, AssetApp, Handle};
 use bevy_core_pipeline::{
@@ -74,7 +74,7 @@ use bevy_ecs::{
 };
 use bevy_render::{
     render_graph::{RenderGraphApp, ViewNodeRunner},
-    render_resource::{Shader, TextureUsages},
+    render_resource::{Shader, SpecializedComputePipelines, TextureUsages},
     renderer::RenderDevice,
     settings::WgpuFeatures,
     view::{
@@ -135,14 +135,14 @@ impl Plugin for MeshletPlugin {
         );
         load_internal_asset!(
             app,
-            MESHLET_CULLING_SHADER_HANDLE,
-            "cull_meshlets.wgsl",
+            MESHLET_ANIMATE_VERTICES_SHADER_HANDLE,
+            "animate_meshlet_vertices.wgsl",
             Shader::from_wgsl
         );
         load_internal_asset!(
             app,
-            MESHLET_DOWNSAMPLE_DEPTH_SHADER_HANDLE,
-            "downsample_depth.wgsl",
+            MESHLET_CULLING_SHADER_HANDLE,
+            "cull_meshlets.wgsl",
             Shader::from_wgsl
         );
         load_internal_asset!(
@@ -224,6 +224,8 @@ impl Plugin for MeshletPlugin {
             )
             .init_resource::<MeshletGpuScene>()
             .init_resource::<MeshletPipelines>()
+            .init_resource::<DepthPyramidPipeline>()
+            .init_resource::<SpecializedComputePipelines<DepthPyramidPipeline>>()
             .add_systems(ExtractSchedule, extract_meshlet_meshes)
             .add_systems(
                 Render,
@@ -1,10 +1,10 @@
 use super::{
-    asset::{Meshlet, MeshletBoundingSpheres},
+    asset::{Meshlet, MeshletBoundingSpheres, MeshletMorphDisplacement, MeshletVertexJoints},
     persistent_buffer::PersistentGpuBufferable,
 };
 use std::{mem::size_of, sync::Arc};
 
-const MESHLET_VERTEX_SIZE_IN_BYTES: u32 = 48;
+pub(super) const MESHLET_VERTEX_SIZE_IN_BYTES: u32 = 48;
 
 impl PersistentGpuBufferable for Arc<[u8]> {
     type Metadata = ();
@@ -76,3 +76,27 @@ impl PersistentGpuBufferable for Arc<[MeshletBoundingSpheres]> {
         buffer_slice.clone_from_slice(bytemuck::cast_slice(self));
     }
 }
+
+impl PersistentGpuBufferable for Arc<[MeshletVertexJoints]> {
+    type Metadata = ();
+
+    fn size_in_bytes(&self) -> usize {
+        self.len() * size_of::<MeshletVertexJoints>()
+    }
+
+    fn write_bytes_le(&self, _: Self::Metadata, buffer_slice: &mut [u8]) {
+        buffer_slice.clone_from_slice(bytemuck::cast_slice(self));
+    }
+}
+
+impl PersistentGpuBufferable for Arc<[MeshletMorphDisplacement]> {
+    type Metadata = ();
+
+    fn size_in_bytes(&self) -> usize {
+        self.len() * size_of::<MeshletMorphDisplacement>()
+    }
+
+    fn write_bytes_le(&self, _: Self::Metadata, buffer_slice: &mut [u8]) {
+        buffer_slice.clone_from_slice(bytemuck::cast_slice(self));
+    }
+}
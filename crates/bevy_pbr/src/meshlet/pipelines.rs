@@ -12,19 +12,19 @@ use bevy_render::render_resource::*;
 pub const MESHLET_FILL_CLUSTER_BUFFERS_SHADER_HANDLE: Handle<Shader> =
     Handle::weak_from_u128(4325134235233421);
 pub const MESHLET_CULLING_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(5325134235233421);
-pub const MESHLET_DOWNSAMPLE_DEPTH_SHADER_HANDLE: Handle<Shader> =
-    Handle::weak_from_u128(6325134235233421);
 pub const MESHLET_VISIBILITY_BUFFER_RASTER_SHADER_HANDLE: Handle<Shader> =
     Handle::weak_from_u128(7325134235233421);
 pub const MESHLET_COPY_MATERIAL_DEPTH_SHADER_HANDLE: Handle<Shader> =
     Handle::weak_from_u128(8325134235233421);
+pub const MESHLET_ANIMATE_VERTICES_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(9325134235233421);
 
 #[derive(Resource)]
 pub struct MeshletPipelines {
     fill_cluster_buffers: CachedComputePipelineId,
+    animate_vertices: CachedComputePipelineId,
     cull_first: CachedComputePipelineId,
     cull_second: CachedComputePipelineId,
-    downsample_depth: CachedRenderPipelineId,
     visibility_buffer_raster: CachedRenderPipelineId,
     visibility_buffer_raster_depth_only: CachedRenderPipelineId,
     visibility_buffer_raster_depth_only_clamp_ortho: CachedRenderPipelineId,
@@ -36,8 +36,8 @@ impl FromWorld for MeshletPipelines {
         let gpu_scene = world.resource::<MeshletGpuScene>();
         let fill_cluster_buffers_bind_group_layout =
             gpu_scene.fill_cluster_buffers_bind_group_layout();
+        let animate_vertices_layout = gpu_scene.animate_vertices_bind_group_layout();
         let cull_layout = gpu_scene.culling_bind_group_layout();
-        let downsample_depth_layout = gpu_scene.downsample_depth_bind_group_layout();
         let visibility_buffer_layout = gpu_scene.visibility_buffer_raster_bind_group_layout();
         let copy_material_depth_layout = gpu_scene.copy_material_depth_bind_group_layout();
         let pipeline_cache = world.resource_mut::<PipelineCache>();
@@ -57,6 +57,15 @@ impl FromWorld for MeshletPipelines {
                 },
             ),
 
+            animate_vertices: pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+                label: Some("meshlet_animate_vertices_pipeline".into()),
+                layout: vec![animate_vertices_layout],
+                push_constant_ranges: vec![],
+                shader: MESHLET_ANIMATE_VERTICES_SHADER_HANDLE,
+                shader_defs: vec!["MESHLET_ANIMATION_PASS".into()],
+                entry_point: "animate_meshlet_vertices".into(),
+            }),
+
             cull_first: pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
                 label: Some("meshlet_culling_first_pipeline".into()),
                 layout: vec![cull_layout.clone()],
@@ -81,26 +90,6 @@ impl FromWorld for MeshletPipelines {
                 entry_point: "cull_meshlets".into(),
             }),
 
-            downsample_depth: pipeline_cache.queue_render_pipeline(RenderPipelineDescriptor {
-                label: Some("meshlet_downsample_depth".into()),
-                layout: vec![downsample_depth_layout],
-                push_constant_ranges: vec![],
-                vertex: fullscreen_shader_vertex_state(),
-                primitive: PrimitiveState::default(),
-                depth_stencil: None,
-                multisample: MultisampleState::default(),
-                fragment: Some(FragmentState {
-                    shader: MESHLET_DOWNSAMPLE_DEPTH_SHADER_HANDLE,
-                    shader_defs: vec![],
-                    entry_point: "downsample_depth".into(),
-                    targets: vec![Some(ColorTargetState {
-                        format: TextureFormat::R32Float,
-                        blend: None,
-                        write_mask: ColorWrites::ALL,
-                    })],
-                }),
-            }),
-
             visibility_buffer_raster: pipeline_cache.queue_render_pipeline(
                 RenderPipelineDescriptor {
                     label: Some("meshlet_visibility_buffer_raster_pipeline".into()),
@@ -264,7 +253,7 @@ impl MeshletPipelines {
         &ComputePipeline,
         &ComputePipeline,
         &ComputePipeline,
-        &RenderPipeline,
+        &ComputePipeline,
         &RenderPipeline,
         &RenderPipeline,
         &RenderPipeline,
@@ -274,9 +263,9 @@ impl MeshletPipelines {
         let pipeline = world.get_resource::<Self>()?;
         Some((
             pipeline_cache.get_compute_pipeline(pipeline.fill_cluster_buffers)?,
+            pipeline_cache.get_compute_pipeline(pipeline.animate_vertices)?,
             pipeline_cache.get_compute_pipeline(pipeline.cull_first)?,
             pipeline_cache.get_compute_pipeline(pipeline.cull_second)?,
-            pipeline_cache.get_render_pipeline(pipeline.downsample_depth)?,
             pipeline_cache.get_render_pipeline(pipeline.visibility_buffer_raster)?,
             pipeline_cache.get_render_pipeline(pipeline.visibility_buffer_raster_depth_only)?,
             pipeline_cache
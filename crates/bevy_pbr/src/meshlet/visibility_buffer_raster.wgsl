@@ -3,14 +3,13 @@
         meshlet_cluster_meshlet_ids,
         meshlets,
         meshlet_vertex_ids,
-        meshlet_vertex_data,
         meshlet_cluster_instance_ids,
         meshlet_instance_uniforms,
         meshlet_instance_material_ids,
         draw_triangle_buffer,
         view,
         get_meshlet_index,
-        unpack_meshlet_vertex,
+        get_meshlet_vertex,
     },
     mesh_functions::mesh_position_local_to_world,
 }
@@ -46,8 +45,8 @@ fn vertex(@builtin(vertex_index) vertex_index: u32) -> VertexOutput {
     let meshlet = meshlets[meshlet_id];
     let index = get_meshlet_index(meshlet.start_index_id + index_id);
     let vertex_id = meshlet_vertex_ids[meshlet.start_vertex_id + index];
-    let vertex = unpack_meshlet_vertex(meshlet_vertex_data[vertex_id]);
     let instance_id = meshlet_cluster_instance_ids[cluster_id];
+    let vertex = get_meshlet_vertex(instance_id, vertex_id);
     let instance_uniform = meshlet_instance_uniforms[instance_id];
 
     let model = affine3_to_square(instance_uniform.model);
@@ -8,8 +8,9 @@ use bevy_ecs::{
     query::QueryState,
     world::{FromWorld, World},
 };
+use bevy_math::Vec4Swizzles;
 use bevy_render::{
-    camera::ExtractedCamera,
+    camera::{ExtractedCamera, Viewport},
     render_graph::{Node, NodeRunError, RenderGraphContext},
     render_resource::*,
     renderer::RenderContext,
@@ -74,9 +75,9 @@ impl Node for MeshletVisibilityBufferRasterPassNode {
 
         let Some((
             fill_cluster_buffers_pipeline,
+            animate_vertices_pipeline,
             culling_first_pipeline,
             culling_second_pipeline,
-            downsample_depth_pipeline,
             visibility_buffer_raster_pipeline,
             visibility_buffer_raster_depth_only_pipeline,
             visibility_buffer_raster_depth_only_clamp_ortho,
@@ -85,6 +86,7 @@ impl Node for MeshletVisibilityBufferRasterPassNode {
         else {
             return Ok(());
         };
+        let pipeline_cache = world.resource::<PipelineCache>();
 
         let first_node = meshlet_view_bind_groups
             .first_node
@@ -111,6 +113,18 @@ impl Node for MeshletVisibilityBufferRasterPassNode {
                 thread_per_cluster_workgroups,
                 meshlet_view_resources.scene_meshlet_count,
             );
+            render_context.command_encoder().clear_buffer(
+                &meshlet_view_resources.animated_displacements,
+                0,
+                None,
+            );
+            animate_vertices_pass(
+                render_context,
+                &meshlet_view_bind_groups.animate_vertices,
+                animate_vertices_pipeline,
+                meshlet_view_resources.max_animated_vertex_count,
+                meshlet_view_resources.animated_instance_count,
+            );
         }
         cull_pass(
             "culling_first",
@@ -130,13 +144,14 @@ impl Node for MeshletVisibilityBufferRasterPassNode {
             meshlet_view_bind_groups,
             view_offset,
             visibility_buffer_raster_pipeline,
-            Some(camera),
+            camera.viewport.as_ref(),
         );
         downsample_depth(
             render_context,
+            pipeline_cache,
             meshlet_view_resources,
             meshlet_view_bind_groups,
-            downsample_depth_pipeline,
+            view_offset,
         );
         cull_pass(
             "culling_second",
@@ -156,7 +171,7 @@ impl Node for MeshletVisibilityBufferRasterPassNode {
             meshlet_view_bind_groups,
             view_offset,
             visibility_buffer_raster_pipeline,
-            Some(camera),
+            camera.viewport.as_ref(),
         );
         copy_material_depth_pass(
             render_context,
@@ -167,9 +182,10 @@ impl Node for MeshletVisibilityBufferRasterPassNode {
         );
         downsample_depth(
             render_context,
+            pipeline_cache,
             meshlet_view_resources,
             meshlet_view_bind_groups,
-            downsample_depth_pipeline,
+            view_offset,
         );
         render_context.command_encoder().pop_debug_group();
 
@@ -200,6 +216,16 @@ impl Node for MeshletVisibilityBufferRasterPassNode {
                 0,
                 None,
             );
+            // Shadow atlas tiles are rendered to a region of the atlas, and don't keep a depth
+            // pyramid across frames, so occlusion culling is disabled by clearing it instead.
+            let atlas_viewport = shadow_view.viewport.map(|viewport| Viewport {
+                physical_position: viewport.xy(),
+                physical_size: viewport.zw(),
+                ..Default::default()
+            });
+            if atlas_viewport.is_some() {
+                clear_depth_pyramid(render_context, meshlet_view_resources);
+            }
             cull_pass(
                 "culling_first",
                 render_context,
@@ -218,14 +244,17 @@ impl Node for MeshletVisibilityBufferRasterPassNode {
                 meshlet_view_bind_groups,
                 view_offset,
                 shadow_visibility_buffer_pipeline,
-                None,
-            );
-            downsample_depth(
-                render_context,
-                meshlet_view_resources,
-                meshlet_view_bind_groups,
-                downsample_depth_pipeline,
+                atlas_viewport.as_ref(),
             );
+            if atlas_viewport.is_none() {
+                downsample_depth(
+                    render_context,
+                    pipeline_cache,
+                    meshlet_view_resources,
+                    meshlet_view_bind_groups,
+                    view_offset,
+                );
+            }
             cull_pass(
                 "culling_second",
                 render_context,
@@ -244,14 +273,17 @@ impl Node for MeshletVisibilityBufferRasterPassNode {
                 meshlet_view_bind_groups,
                 view_offset,
                 shadow_visibility_buffer_pipeline,
-                None,
-            );
-            downsample_depth(
-                render_context,
-                meshlet_view_resources,
-                meshlet_view_bind_groups,
-                downsample_depth_pipeline,
+                atlas_viewport.as_ref(),
             );
+            if atlas_viewport.is_none() {
+                downsample_depth(
+                    render_context,
+                    pipeline_cache,
+                    meshlet_view_resources,
+                    meshlet_view_bind_groups,
+                    view_offset,
+                );
+            }
             render_context.command_encoder().pop_debug_group();
         }
 
@@ -282,6 +314,31 @@ fn fill_cluster_buffers_pass(
     );
 }
 
+fn animate_vertices_pass(
+    render_context: &mut RenderContext,
+    animate_vertices_bind_group: &BindGroup,
+    animate_vertices_pipeline: &ComputePipeline,
+    max_animated_vertex_count: u32,
+    animated_instance_count: u32,
+) {
+    if animated_instance_count == 0 {
+        return;
+    }
+
+    let command_encoder = render_context.command_encoder();
+    let mut animate_pass = command_encoder.begin_compute_pass(&ComputePassDescriptor {
+        label: Some("animate_vertices"),
+        timestamp_writes: None,
+    });
+    animate_pass.set_pipeline(animate_vertices_pipeline);
+    animate_pass.set_bind_group(0, animate_vertices_bind_group, &[]);
+    animate_pass.dispatch_workgroups(
+        max_animated_vertex_count.div_ceil(64),
+        animated_instance_count,
+        1,
+    );
+}
+
 fn cull_pass(
     label: &'static str,
     render_context: &mut RenderContext,
@@ -315,7 +372,7 @@ fn raster_pass(
     meshlet_view_bind_groups: &MeshletViewBindGroups,
     view_offset: &ViewUniformOffset,
     visibility_buffer_raster_pipeline: &RenderPipeline,
-    camera: Option<&ExtractedCamera>,
+    viewport: Option<&Viewport>,
 ) {
     let mut color_attachments_filled = [None, None];
     if let (Some(visibility_buffer), Some(material_depth_color)) = (
@@ -362,7 +419,7 @@ fn raster_pass(
         timestamp_writes: None,
         occlusion_query_set: None,
     });
-    if let Some(viewport) = camera.and_then(|camera| camera.viewport.as_ref()) {
+    if let Some(viewport) = viewport {
         draw_pass.set_camera_viewport(viewport);
     }
 
@@ -375,21 +432,15 @@ fn raster_pass(
     draw_pass.draw_indirect(visibility_buffer_draw_indirect_args, 0);
 }
 
-fn downsample_depth(
+fn clear_depth_pyramid(
     render_context: &mut RenderContext,
     meshlet_view_resources: &MeshletViewResources,
-    meshlet_view_bind_groups: &MeshletViewBindGroups,
-    downsample_depth_pipeline: &RenderPipeline,
 ) {
-    render_context
-        .command_encoder()
-        .push_debug_group("meshlet_downsample_depth");
-
-    for i in 0..meshlet_view_resources.depth_pyramid_mips.len() {
-        let downsample_pass = RenderPassDescriptor {
-            label: Some("downsample_depth"),
+    for depth_pyramid_mip in meshlet_view_resources.depth_pyramid.mips.iter() {
+        render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("clear_depth_pyramid"),
             color_attachments: &[Some(RenderPassColorAttachment {
-                view: &meshlet_view_resources.depth_pyramid_mips[i],
+                view: depth_pyramid_mip,
                 resolve_target: None,
                 ops: Operations {
                     load: LoadOp::Clear(LinearRgba::BLACK.into()),
@@ -399,15 +450,35 @@ fn downsample_depth(
             depth_stencil_attachment: None,
             timestamp_writes: None,
             occlusion_query_set: None,
-        };
-
-        let mut downsample_pass = render_context.begin_tracked_render_pass(downsample_pass);
-        downsample_pass.set_render_pipeline(downsample_depth_pipeline);
-        downsample_pass.set_bind_group(0, &meshlet_view_bind_groups.downsample_depth[i], &[]);
-        downsample_pass.draw(0..3, 0..1);
+        });
     }
+}
 
-    render_context.command_encoder().pop_debug_group();
+fn downsample_depth(
+    render_context: &mut RenderContext,
+    pipeline_cache: &PipelineCache,
+    meshlet_view_resources: &MeshletViewResources,
+    meshlet_view_bind_groups: &MeshletViewBindGroups,
+    view_offset: &ViewUniformOffset,
+) {
+    let depth_pyramid_built = {
+        let command_encoder = render_context.command_encoder();
+        let mut downsample_pass = command_encoder.begin_compute_pass(&ComputePassDescriptor {
+            label: Some("downsample_depth"),
+            timestamp_writes: None,
+        });
+        meshlet_view_bind_groups.depth_pyramid.dispatch(
+            &mut downsample_pass,
+            pipeline_cache,
+            view_offset.offset,
+        )
+    };
+
+    // Until the pipelines are compiled, disable occlusion culling rather than cull against a
+    // stale depth pyramid
+    if !depth_pyramid_built {
+        clear_depth_pyramid(render_context, meshlet_view_resources);
+    }
 }
 
 fn copy_material_depth_pass(
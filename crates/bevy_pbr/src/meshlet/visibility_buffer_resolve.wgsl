@@ -6,11 +6,10 @@
         meshlet_cluster_meshlet_ids,
         meshlets,
         meshlet_vertex_ids,
-        meshlet_vertex_data,
         meshlet_cluster_instance_ids,
         meshlet_instance_uniforms,
         get_meshlet_index,
-        unpack_meshlet_vertex,
+        get_meshlet_vertex,
     },
     mesh_view_bindings::view,
     mesh_functions::mesh_position_local_to_world,
@@ -103,11 +102,11 @@ fn resolve_vertex_output(frag_coord: vec4<f32>) -> VertexOutput {
     let index_ids = meshlet.start_index_id + vec3(triangle_id * 3u) + vec3(0u, 1u, 2u);
     let indices = meshlet.start_vertex_id + vec3(get_meshlet_index(index_ids.x), get_meshlet_index(index_ids.y), get_meshlet_index(index_ids.z));
     let vertex_ids = vec3(meshlet_vertex_ids[indices.x], meshlet_vertex_ids[indices.y], meshlet_vertex_ids[indices.z]);
-    let vertex_1 = unpack_meshlet_vertex(meshlet_vertex_data[vertex_ids.x]);
-    let vertex_2 = unpack_meshlet_vertex(meshlet_vertex_data[vertex_ids.y]);
-    let vertex_3 = unpack_meshlet_vertex(meshlet_vertex_data[vertex_ids.z]);
-
     let instance_id = meshlet_cluster_instance_ids[cluster_id];
+    let vertex_1 = get_meshlet_vertex(instance_id, vertex_ids.x);
+    let vertex_2 = get_meshlet_vertex(instance_id, vertex_ids.y);
+    let vertex_3 = get_meshlet_vertex(instance_id, vertex_ids.z);
+
     let instance_uniform = meshlet_instance_uniforms[instance_id];
     let model = affine3_to_square(instance_uniform.model);
 
@@ -0,0 +1,535 @@
+//! Hierarchical depth buffers.
+//!
+//! A *depth pyramid* is a mip chain built from the depth buffer of a view, in which every texel
+//! holds either the farthest or the closest depth of the texels it covers. The same shader and
+//! bind groups build the depth pyramids of GPU occlusion culling, meshlet culling and screen space
+//! reflections.
+//!
+//! After the opaque geometry of a view with [`OcclusionCulling`] has been drawn, its depth buffer
+//! is downsampled into a pyramid of farthest depths. On the next frame, the mesh preprocessing
+//! shader projects the bounding box of each mesh with the view-projection matrix that the pyramid
+//! was built with, and skips the mesh if the box lies entirely behind the depth stored in the
+//! pyramid.
+
+use bevy_asset::Handle;
+use bevy_core_pipeline::core_3d::Camera3d;
+use bevy_derive::{Deref, DerefMut};
+use bevy_ecs::{
+    component::Component,
+    entity::{Entity, EntityHashMap},
+    query::{QueryItem, With},
+    system::{lifetimeless::Read, Commands, Query, Res, ResMut, Resource, SystemParam},
+    world::{FromWorld, World},
+};
+use bevy_math::{Mat4, UVec2, Vec4Swizzles};
+use bevy_render::{
+    render_graph::{NodeRunError, RenderGraphContext, ViewNode},
+    render_resource::{
+        binding_types::{
+            texture_2d, texture_depth_2d, texture_depth_2d_multisampled, texture_storage_2d,
+            uniform_buffer,
+        },
+        BindGroup, BindGroupEntries, BindGroupLayout, BindGroupLayoutEntries, BindingResource,
+        CachedComputePipelineId, ComputePass, ComputePassDescriptor, ComputePipelineDescriptor,
+        DynamicUniformBuffer, Extent3d, PipelineCache, Shader, ShaderStages, ShaderType,
+        SpecializedComputePipeline, SpecializedComputePipelines, StorageTextureAccess, Texture,
+        TextureDescriptor, TextureDimension, TextureFormat, TextureSampleType, TextureUsages,
+        TextureView, TextureViewDescriptor,
+    },
+    renderer::{RenderContext, RenderDevice, RenderQueue},
+    view::{
+        ExtractedView, OcclusionCulling, ViewDepthTexture, ViewUniform, ViewUniformOffset,
+        ViewUniforms,
+    },
+};
+
+/// The handle to the `depth_pyramid.wgsl` compute shader.
+pub const DEPTH_PYRAMID_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(11497052312698140651);
+
+/// The format of depth pyramids.
+const DEPTH_PYRAMID_FORMAT: TextureFormat = TextureFormat::R32Float;
+
+/// Returns the size of the most detailed mip level of a depth pyramid built from a viewport of
+/// the given size.
+///
+/// The size is rounded down to powers of two, so that each texel covers at least one texel of the
+/// viewport and the mip levels halve exactly.
+pub fn depth_pyramid_size(viewport_size: UVec2) -> UVec2 {
+    UVec2::new(
+        1 << viewport_size.x.max(1).ilog2(),
+        1 << viewport_size.y.max(1).ilog2(),
+    )
+}
+
+/// Returns the descriptor of a depth pyramid texture whose most detailed mip level has the given
+/// size, with a full mip chain.
+pub fn depth_pyramid_texture_descriptor(
+    label: &'static str,
+    size: UVec2,
+) -> TextureDescriptor<'static> {
+    let size = Extent3d {
+        width: size.x,
+        height: size.y,
+        depth_or_array_layers: 1,
+    };
+    TextureDescriptor {
+        label: Some(label),
+        size,
+        mip_level_count: size.max_mips(TextureDimension::D2),
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format: DEPTH_PYRAMID_FORMAT,
+        usage: TextureUsages::STORAGE_BINDING | TextureUsages::TEXTURE_BINDING,
+        view_formats: &[],
+    }
+}
+
+/// The texture views of a depth pyramid.
+#[derive(Clone)]
+pub struct DepthPyramidViews {
+    /// A view of every mip level, sampled by the shaders that use the pyramid.
+    pub all_mips: TextureView,
+    /// A view of each individual mip level, written to when building the pyramid.
+    pub mips: Box<[TextureView]>,
+    /// The size of the most detailed mip level.
+    pub size: UVec2,
+}
+
+impl DepthPyramidViews {
+    /// Creates the views of a depth pyramid texture.
+    pub fn new(texture: &Texture) -> Self {
+        let mips = (0..texture.mip_level_count())
+            .map(|mip_level| {
+                texture.create_view(&TextureViewDescriptor {
+                    label: Some("depth_pyramid_mip_view"),
+                    base_mip_level: mip_level,
+                    mip_level_count: Some(1),
+                    ..Default::default()
+                })
+            })
+            .collect();
+
+        Self {
+            all_mips: texture.create_view(&TextureViewDescriptor::default()),
+            mips,
+            size: UVec2::new(texture.width(), texture.height()),
+        }
+    }
+}
+
+bitflags::bitflags! {
+    /// Specifies one of the pipelines that build depth pyramids.
+    #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct DepthPyramidPipelineKey: u8 {
+        /// Whether the pipeline downsamples the depth buffer into the first mip level, rather
+        /// than a mip level into the next one.
+        ///
+        /// This `#define`'s `FIRST_MIP` in the shader.
+        const FIRST_MIP = 1 << 0;
+        /// Whether the depth buffer is multisampled.
+        ///
+        /// This `#define`'s `MULTISAMPLED` in the shader.
+        const MULTISAMPLED = 1 << 1;
+        /// Whether the pyramid keeps the closest depth of the texels it covers instead of the
+        /// farthest one.
+        ///
+        /// This `#define`'s `CLOSEST_DEPTH` in the shader.
+        const CLOSEST_DEPTH = 1 << 2;
+    }
+}
+
+/// The bind group layouts of the pipelines that build depth pyramids.
+#[derive(Resource)]
+pub struct DepthPyramidPipeline {
+    first_mip_bind_group_layout: BindGroupLayout,
+    first_mip_multisampled_bind_group_layout: BindGroupLayout,
+    downsample_bind_group_layout: BindGroupLayout,
+}
+
+impl FromWorld for DepthPyramidPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let mip_output = texture_storage_2d(DEPTH_PYRAMID_FORMAT, StorageTextureAccess::WriteOnly);
+
+        Self {
+            first_mip_bind_group_layout: render_device.create_bind_group_layout(
+                "depth_pyramid_first_mip_bind_group_layout",
+                &BindGroupLayoutEntries::sequential(
+                    ShaderStages::COMPUTE,
+                    (
+                        texture_depth_2d(),
+                        mip_output,
+                        uniform_buffer::<ViewUniform>(true),
+                    ),
+                ),
+            ),
+            first_mip_multisampled_bind_group_layout: render_device.create_bind_group_layout(
+                "depth_pyramid_first_mip_multisampled_bind_group_layout",
+                &BindGroupLayoutEntries::sequential(
+                    ShaderStages::COMPUTE,
+                    (
+                        texture_depth_2d_multisampled(),
+                        mip_output,
+                        uniform_buffer::<ViewUniform>(true),
+                    ),
+                ),
+            ),
+            downsample_bind_group_layout: render_device.create_bind_group_layout(
+                "depth_pyramid_downsample_bind_group_layout",
+                &BindGroupLayoutEntries::sequential(
+                    ShaderStages::COMPUTE,
+                    (
+                        texture_2d(TextureSampleType::Float { filterable: false }),
+                        mip_output,
+                    ),
+                ),
+            ),
+        }
+    }
+}
+
+impl DepthPyramidPipeline {
+    fn bind_group_layout(&self, key: DepthPyramidPipelineKey) -> &BindGroupLayout {
+        if !key.contains(DepthPyramidPipelineKey::FIRST_MIP) {
+            &self.downsample_bind_group_layout
+        } else if key.contains(DepthPyramidPipelineKey::MULTISAMPLED) {
+            &self.first_mip_multisampled_bind_group_layout
+        } else {
+            &self.first_mip_bind_group_layout
+        }
+    }
+}
+
+impl SpecializedComputePipeline for DepthPyramidPipeline {
+    type Key = DepthPyramidPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> ComputePipelineDescriptor {
+        let mut shader_defs = vec![];
+        if key.contains(DepthPyramidPipelineKey::FIRST_MIP) {
+            shader_defs.push("FIRST_MIP".into());
+        }
+        if key.contains(DepthPyramidPipelineKey::MULTISAMPLED) {
+            shader_defs.push("MULTISAMPLED".into());
+        }
+        if key.contains(DepthPyramidPipelineKey::CLOSEST_DEPTH) {
+            shader_defs.push("CLOSEST_DEPTH".into());
+        }
+
+        ComputePipelineDescriptor {
+            label: Some("depth_pyramid_pipeline".into()),
+            layout: vec![self.bind_group_layout(key).clone()],
+            push_constant_ranges: vec![],
+            shader: DEPTH_PYRAMID_SHADER_HANDLE,
+            shader_defs,
+            entry_point: "downsample_depth".into(),
+        }
+    }
+}
+
+/// The resources needed to create the [`DepthPyramidBindGroups`] of views.
+#[derive(SystemParam)]
+pub struct DepthPyramidPipelines<'w> {
+    render_device: Res<'w, RenderDevice>,
+    pipeline_cache: Res<'w, PipelineCache>,
+    pipeline: Res<'w, DepthPyramidPipeline>,
+    specialized_pipelines: ResMut<'w, SpecializedComputePipelines<DepthPyramidPipeline>>,
+}
+
+impl DepthPyramidPipelines<'_> {
+    /// Creates the bind groups that downsample the viewport of `depth` into the depth pyramid
+    /// `views`.
+    ///
+    /// `key` selects whether the depth buffer is multisampled and which depth the pyramid keeps;
+    /// [`DepthPyramidPipelineKey::FIRST_MIP`] is ignored.
+    pub fn create_bind_groups(
+        &mut self,
+        key: DepthPyramidPipelineKey,
+        depth: &TextureView,
+        view_uniforms: BindingResource,
+        views: &DepthPyramidViews,
+    ) -> DepthPyramidBindGroups {
+        let first_mip_key = key | DepthPyramidPipelineKey::FIRST_MIP;
+        let downsample_key =
+            key & !(DepthPyramidPipelineKey::FIRST_MIP | DepthPyramidPipelineKey::MULTISAMPLED);
+
+        let first_mip = self.render_device.create_bind_group(
+            "depth_pyramid_first_mip_bind_group",
+            self.pipeline.bind_group_layout(first_mip_key),
+            &BindGroupEntries::sequential((depth, &views.mips[0], view_uniforms)),
+        );
+
+        let downsample = views
+            .mips
+            .windows(2)
+            .map(|mips| {
+                self.render_device.create_bind_group(
+                    "depth_pyramid_downsample_bind_group",
+                    self.pipeline.bind_group_layout(downsample_key),
+                    &BindGroupEntries::sequential((&mips[0], &mips[1])),
+                )
+            })
+            .collect();
+
+        DepthPyramidBindGroups {
+            first_mip,
+            first_mip_pipeline: self.specialized_pipelines.specialize(
+                &self.pipeline_cache,
+                &self.pipeline,
+                first_mip_key,
+            ),
+            downsample,
+            downsample_pipeline: self.specialized_pipelines.specialize(
+                &self.pipeline_cache,
+                &self.pipeline,
+                downsample_key,
+            ),
+            size: views.size,
+        }
+    }
+}
+
+/// The bind groups and pipelines that build the depth pyramid of a view.
+#[derive(Component)]
+pub struct DepthPyramidBindGroups {
+    first_mip: BindGroup,
+    first_mip_pipeline: CachedComputePipelineId,
+    /// The bind group that downsamples each mip level into the next one.
+    downsample: Box<[BindGroup]>,
+    downsample_pipeline: CachedComputePipelineId,
+    size: UVec2,
+}
+
+impl DepthPyramidBindGroups {
+    /// Builds the depth pyramid in `compute_pass`, using the view uniform at
+    /// `view_uniform_offset` to find the viewport of the depth buffer.
+    ///
+    /// Returns `false` without building anything if the pipelines haven't been compiled yet.
+    pub fn dispatch<'a>(
+        &'a self,
+        compute_pass: &mut ComputePass<'a>,
+        pipeline_cache: &'a PipelineCache,
+        view_uniform_offset: u32,
+    ) -> bool {
+        let (Some(first_mip_pipeline), Some(downsample_pipeline)) = (
+            pipeline_cache.get_compute_pipeline(self.first_mip_pipeline),
+            pipeline_cache.get_compute_pipeline(self.downsample_pipeline),
+        ) else {
+            return false;
+        };
+
+        compute_pass.set_pipeline(first_mip_pipeline);
+        compute_pass.set_bind_group(0, &self.first_mip, &[view_uniform_offset]);
+        compute_pass.dispatch_workgroups(self.size.x.div_ceil(8), self.size.y.div_ceil(8), 1);
+
+        compute_pass.set_pipeline(downsample_pipeline);
+        for (mip_level, bind_group) in (1u32..).zip(self.downsample.iter()) {
+            let mip_size = (self.size >> mip_level).max(UVec2::ONE);
+            compute_pass.set_bind_group(0, bind_group, &[]);
+            compute_pass.dispatch_workgroups(mip_size.x.div_ceil(8), mip_size.y.div_ceil(8), 1);
+        }
+
+        true
+    }
+}
+
+/// Makes the depth texture of cameras with [`OcclusionCulling`] readable from shaders, so that it
+/// can be downsampled into a depth pyramid.
+pub fn configure_occlusion_culling_cameras(
+    mut cameras: Query<&mut Camera3d, With<OcclusionCulling>>,
+) {
+    for mut camera_3d in &mut cameras {
+        let usages = TextureUsages::from(camera_3d.depth_texture_usages);
+        if !usages.contains(TextureUsages::TEXTURE_BINDING) {
+            camera_3d.depth_texture_usages = (usages | TextureUsages::TEXTURE_BINDING).into();
+        }
+    }
+}
+
+/// The depth pyramid of every view with [`OcclusionCulling`].
+///
+/// Unlike most view textures, these survive from one frame to the next, since each frame culls
+/// against the pyramid built on the previous one.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct DepthPyramids(EntityHashMap<DepthPyramid>);
+
+/// A depth pyramid that outlives the frame it was built on.
+pub struct DepthPyramid {
+    views: DepthPyramidViews,
+    /// The view-projection matrix of the view when the pyramid was last built.
+    view_proj: Mat4,
+}
+
+/// The depth pyramid of a view on this frame.
+///
+/// The view is culled against its contents, and then its depth buffer is downsampled into it.
+#[derive(Component)]
+pub struct ViewDepthPyramid {
+    /// The views of the pyramid's texture.
+    pub views: DepthPyramidViews,
+    /// The offset of the view's [`DepthPyramidUniform`] in [`DepthPyramidUniforms`].
+    pub uniform_offset: u32,
+}
+
+/// The matrix that the bounding boxes of meshes are projected with to test them against a depth
+/// pyramid.
+#[derive(Clone, Copy, ShaderType)]
+pub struct DepthPyramidUniform {
+    /// The view-projection matrix that the contents of the depth pyramid were rendered with.
+    pub view_proj: Mat4,
+}
+
+/// The buffer holding the [`DepthPyramidUniform`] of every view with [`OcclusionCulling`].
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct DepthPyramidUniforms(DynamicUniformBuffer<DepthPyramidUniform>);
+
+/// Creates, resizes and releases the depth pyramids of views, and writes the matrices they are
+/// tested against.
+pub fn prepare_depth_pyramids(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    mut depth_pyramids: ResMut<DepthPyramids>,
+    mut depth_pyramid_uniforms: ResMut<DepthPyramidUniforms>,
+    views: Query<(Entity, &ExtractedView), With<OcclusionCulling>>,
+) {
+    depth_pyramids.retain(|entity, _| views.contains(*entity));
+
+    let Some(mut writer) =
+        depth_pyramid_uniforms.get_writer(views.iter().len(), &render_device, &render_queue)
+    else {
+        return;
+    };
+
+    for (entity, view) in &views {
+        let view_proj = view
+            .view_projection
+            .unwrap_or_else(|| view.projection * view.transform.compute_matrix().inverse());
+        let size = depth_pyramid_size(view.viewport.zw());
+
+        // A new pyramid starts out cleared to the far plane, so that nothing is culled against it
+        // until it has been built.
+        let depth_pyramid = depth_pyramids
+            .entry(entity)
+            .and_modify(|depth_pyramid| {
+                if depth_pyramid.views.size != size {
+                    *depth_pyramid = create_depth_pyramid(&render_device, size, view_proj);
+                }
+            })
+            .or_insert_with(|| create_depth_pyramid(&render_device, size, view_proj));
+
+        let uniform_offset = writer.write(&DepthPyramidUniform {
+            view_proj: depth_pyramid.view_proj,
+        });
+        depth_pyramid.view_proj = view_proj;
+
+        commands.entity(entity).insert(ViewDepthPyramid {
+            views: depth_pyramid.views.clone(),
+            uniform_offset,
+        });
+    }
+}
+
+fn create_depth_pyramid(
+    render_device: &RenderDevice,
+    size: UVec2,
+    view_proj: Mat4,
+) -> DepthPyramid {
+    let texture =
+        render_device.create_texture(&depth_pyramid_texture_descriptor("depth_pyramid", size));
+
+    DepthPyramid {
+        views: DepthPyramidViews::new(&texture),
+        view_proj,
+    }
+}
+
+/// Creates the bind groups that downsample the depth buffer of each view with
+/// [`OcclusionCulling`] into its depth pyramid.
+pub fn prepare_depth_pyramid_bind_groups(
+    mut commands: Commands,
+    mut pipelines: DepthPyramidPipelines,
+    view_uniforms: Res<ViewUniforms>,
+    views: Query<(Entity, &ViewDepthPyramid, &ViewDepthTexture)>,
+) {
+    let Some(view_uniforms) = view_uniforms.uniforms.binding() else {
+        return;
+    };
+
+    for (entity, depth_pyramid, depth_texture) in &views {
+        let mut key = DepthPyramidPipelineKey::empty();
+        if depth_texture.texture.sample_count() > 1 {
+            key |= DepthPyramidPipelineKey::MULTISAMPLED;
+        }
+
+        commands.entity(entity).insert(pipelines.create_bind_groups(
+            key,
+            depth_texture.view(),
+            view_uniforms.clone(),
+            &depth_pyramid.views,
+        ));
+    }
+}
+
+/// Downsamples the depth buffer of every view with [`OcclusionCulling`] into its depth pyramid.
+#[derive(Default)]
+pub struct DepthPyramidNode;
+
+impl ViewNode for DepthPyramidNode {
+    type ViewQuery = (Read<DepthPyramidBindGroups>, Read<ViewUniformOffset>);
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (bind_groups, view_uniform_offset): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let pipeline_cache = world.resource::<PipelineCache>();
+
+        let mut compute_pass =
+            render_context
+                .command_encoder()
+                .begin_compute_pass(&ComputePassDescriptor {
+                    label: Some("depth_pyramid_pass"),
+                    timestamp_writes: None,
+                });
+        bind_groups.dispatch(
+            &mut compute_pass,
+            pipeline_cache,
+            view_uniform_offset.offset,
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn depth_pyramid_size_rounds_down_to_powers_of_two() {
+        assert_eq!(
+            depth_pyramid_size(UVec2::new(1920, 1080)),
+            UVec2::new(1024, 1024)
+        );
+        assert_eq!(depth_pyramid_size(UVec2::new(512, 3)), UVec2::new(512, 2));
+        assert_eq!(depth_pyramid_size(UVec2::new(1, 0)), UVec2::ONE);
+    }
+
+    #[test]
+    fn depth_pyramid_covers_the_viewport_with_a_full_mip_chain() {
+        let descriptor = depth_pyramid_texture_descriptor("depth_pyramid", UVec2::new(1024, 512));
+        assert_eq!(descriptor.mip_level_count, 11);
+
+        // Every texel of the first mip level covers at least one texel of the viewport, and at
+        // most two along each axis.
+        for viewport_size in [UVec2::new(1920, 1080), UVec2::new(4, 4), UVec2::new(7, 1)] {
+            let size = depth_pyramid_size(viewport_size);
+            assert!(size.cmple(viewport_size).all());
+            assert!((size * 2).cmpgt(viewport_size).all());
+        }
+    }
+}
@@ -0,0 +1,95 @@
+// Depth pyramid building, shared by occlusion culling, meshlet culling and
+// screen space reflections.
+//
+// Each dispatch fills one mip level of the pyramid. The first mip level is
+// downsampled from the viewport of the view's depth buffer, and each later one
+// from the mip level before it. Every texel stores either the farthest depth of
+// the texels it covers, which is the minimum since depth is reversed, or the
+// closest one, the maximum, if `CLOSEST_DEPTH` is defined.
+
+#import bevy_render::view::View
+
+#ifdef FIRST_MIP
+#ifdef MULTISAMPLED
+@group(0) @binding(0) var input_depth: texture_depth_multisampled_2d;
+#else
+@group(0) @binding(0) var input_depth: texture_depth_2d;
+#endif
+#else
+@group(0) @binding(0) var input_depth: texture_2d<f32>;
+#endif
+@group(0) @binding(1) var output_depth: texture_storage_2d<r32float, write>;
+#ifdef FIRST_MIP
+@group(0) @binding(2) var<uniform> view: View;
+#endif
+
+#ifdef CLOSEST_DEPTH
+const EMPTY_DEPTH: f32 = 0.0;
+#else
+const EMPTY_DEPTH: f32 = 1.0;
+#endif
+
+fn reduce_depth(a: f32, b: f32) -> f32 {
+#ifdef CLOSEST_DEPTH
+    return max(a, b);
+#else
+    return min(a, b);
+#endif
+}
+
+@compute
+@workgroup_size(8, 8, 1)
+fn downsample_depth(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let output_size = textureDimensions(output_depth);
+    if (any(global_id.xy >= output_size)) {
+        return;
+    }
+
+    var depth = EMPTY_DEPTH;
+
+#ifdef FIRST_MIP
+    // Find the texels of the viewport that this texel overlaps, rounding
+    // outwards so that none of them are missed.
+    let viewport_origin = vec2<u32>(view.viewport.xy);
+    let viewport_size = vec2<u32>(view.viewport.zw);
+    let texel_min = viewport_origin + global_id.xy * viewport_size / output_size;
+    let texel_max = viewport_origin +
+        ((global_id.xy + 1u) * viewport_size + output_size - 1u) / output_size;
+
+    for (var y = texel_min.y; y < texel_max.y; y += 1u) {
+        for (var x = texel_min.x; x < texel_max.x; x += 1u) {
+#ifdef MULTISAMPLED
+            for (var sample_index = 0u; sample_index < textureNumSamples(input_depth);
+                    sample_index += 1u) {
+                depth = reduce_depth(depth, textureLoad(input_depth, vec2(x, y), sample_index));
+            }
+#else
+            depth = reduce_depth(depth, textureLoad(input_depth, vec2(x, y), 0));
+#endif
+        }
+    }
+#else
+    // Each texel covers a 2x2 block of the previous mip level. If that level
+    // has an odd size, the last texel along that axis also covers the extra
+    // row or column, and along an axis that is already one texel wide, the
+    // block is clipped to the edge.
+    let input_size = textureDimensions(input_depth);
+    let texel_min = global_id.xy * 2u;
+    var texel_max = texel_min + 2u;
+    if (global_id.x == output_size.x - 1u && (input_size.x & 1u) != 0u) {
+        texel_max.x += 1u;
+    }
+    if (global_id.y == output_size.y - 1u && (input_size.y & 1u) != 0u) {
+        texel_max.y += 1u;
+    }
+    texel_max = min(texel_max, input_size);
+
+    for (var y = texel_min.y; y < texel_max.y; y += 1u) {
+        for (var x = texel_min.x; x < texel_max.x; x += 1u) {
+            depth = reduce_depth(depth, textureLoad(input_depth, vec2(x, y), 0).r);
+        }
+    }
+#endif
+
+    textureStore(output_depth, global_id.xy, vec4(depth));
+}
@@ -5,10 +5,15 @@
 //! instead of transferring [`MeshUniform`]s to the GPU, we transfer the smaller
 //! [`MeshInputUniform`]s instead and use the GPU to calculate the remaining
 //! derived fields in [`MeshUniform`].
+//!
+//! When GPU culling is in use, this pass also culls meshes against the view
+//! frustum and, for views with
+//! [`OcclusionCulling`](bevy_render::view::OcclusionCulling), against the depth pyramid
+//! built on the previous frame (see [`depth_pyramid`](super::depth_pyramid)).
 
 use std::num::NonZeroU64;
 
-use bevy_app::{App, Plugin};
+use bevy_app::{App, Plugin, PostUpdate};
 use bevy_asset::{load_internal_asset, Handle};
 use bevy_core_pipeline::core_3d::graph::{Core3d, Node3d};
 use bevy_ecs::{
@@ -16,7 +21,7 @@ use bevy_ecs::{
     entity::Entity,
     query::{Has, QueryState},
     schedule::{common_conditions::resource_exists, IntoSystemConfigs as _},
-    system::{lifetimeless::Read, Commands, Res, ResMut, Resource},
+    system::{lifetimeless::Read, Commands, Query, Res, ResMut, Resource},
     world::{FromWorld, World},
 };
 use bevy_render::{
@@ -24,13 +29,13 @@ use bevy_render::{
         BatchedInstanceBuffers, GpuPreprocessingSupport, IndirectParameters,
         IndirectParametersBuffer, PreprocessWorkItem,
     },
-    render_graph::{Node, NodeRunError, RenderGraphApp, RenderGraphContext},
+    render_graph::{Node, NodeRunError, RenderGraphApp, RenderGraphContext, ViewNodeRunner},
     render_resource::{
-        binding_types::{storage_buffer, storage_buffer_read_only, uniform_buffer},
+        binding_types::{storage_buffer, storage_buffer_read_only, texture_2d, uniform_buffer},
         BindGroup, BindGroupEntries, BindGroupLayout, BindingResource, BufferBinding,
         CachedComputePipelineId, ComputePassDescriptor, ComputePipelineDescriptor,
         DynamicBindGroupLayoutEntries, PipelineCache, Shader, ShaderStages, ShaderType,
-        SpecializedComputePipeline, SpecializedComputePipelines,
+        SpecializedComputePipeline, SpecializedComputePipelines, TextureSampleType,
     },
     renderer::{RenderContext, RenderDevice, RenderQueue},
     view::{GpuCulling, ViewUniform, ViewUniformOffset, ViewUniforms},
@@ -41,7 +46,10 @@ use bitflags::bitflags;
 use smallvec::{smallvec, SmallVec};
 
 use crate::{
-    graph::NodePbr, MeshCullingData, MeshCullingDataBuffer, MeshInputUniform, MeshUniform,
+    configure_occlusion_culling_cameras, graph::NodePbr, prepare_depth_pyramid_bind_groups,
+    prepare_depth_pyramids, DepthPyramidNode, DepthPyramidPipeline, DepthPyramidUniform,
+    DepthPyramidUniforms, DepthPyramids, MeshCullingData, MeshCullingDataBuffer, MeshInputUniform,
+    MeshUniform, ViewDepthPyramid,
 };
 
 /// The handle to the `mesh_preprocess.wgsl` compute shader.
@@ -70,6 +78,7 @@ pub struct GpuPreprocessNode {
         Read<PreprocessBindGroup>,
         Read<ViewUniformOffset>,
         Has<GpuCulling>,
+        Option<Read<ViewDepthPyramid>>,
     )>,
 }
 
@@ -82,6 +91,11 @@ pub struct PreprocessPipelines {
     /// The pipeline used for GPU culling. This pipeline populates indirect
     /// parameters.
     pub gpu_culling: PreprocessPipeline,
+    /// The pipeline used for GPU culling in views with
+    /// [`OcclusionCulling`](bevy_render::view::OcclusionCulling).
+    /// Besides frustum culling, this pipeline culls meshes hidden behind the
+    /// depth pyramid of the view.
+    pub occlusion_culling: PreprocessPipeline,
 }
 
 /// The pipeline for the GPU mesh preprocessing shader.
@@ -102,6 +116,10 @@ bitflags! {
         ///
         /// This `#define`'s `GPU_CULLING` in the shader.
         const GPU_CULLING = 1;
+        /// Whether occlusion culling is in use. This requires GPU culling.
+        ///
+        /// This `#define`'s `OCCLUSION_CULLING` in the shader.
+        const OCCLUSION_CULLING = 2;
     }
 }
 
@@ -119,6 +137,8 @@ impl Plugin for GpuMeshPreprocessPlugin {
             "mesh_preprocess.wgsl",
             Shader::from_wgsl
         );
+
+        app.add_systems(PostUpdate, configure_occlusion_culling_cameras);
     }
 
     fn finish(&self, app: &mut App) {
@@ -140,12 +160,30 @@ impl Plugin for GpuMeshPreprocessPlugin {
             .add_render_graph_node::<GpuPreprocessNode>(Core3d, NodePbr::GpuPreprocess)
             .add_render_graph_edges(Core3d, (NodePbr::GpuPreprocess, Node3d::Prepass))
             .add_render_graph_edges(Core3d, (NodePbr::GpuPreprocess, NodePbr::ShadowPass))
+            .add_render_graph_node::<ViewNodeRunner<DepthPyramidNode>>(
+                Core3d,
+                NodePbr::DepthPyramid,
+            )
+            .add_render_graph_edges(
+                Core3d,
+                (
+                    Node3d::MainOpaquePass,
+                    NodePbr::DepthPyramid,
+                    Node3d::MainTransmissivePass,
+                ),
+            )
             .init_resource::<PreprocessPipelines>()
             .init_resource::<SpecializedComputePipelines<PreprocessPipeline>>()
+            .init_resource::<DepthPyramidPipeline>()
+            .init_resource::<SpecializedComputePipelines<DepthPyramidPipeline>>()
+            .init_resource::<DepthPyramids>()
+            .init_resource::<DepthPyramidUniforms>()
             .add_systems(
                 Render,
                 (
                     prepare_preprocess_pipelines.in_set(RenderSet::Prepare),
+                    prepare_depth_pyramids.in_set(RenderSet::PrepareResources),
+                    prepare_depth_pyramid_bind_groups.in_set(RenderSet::PrepareBindGroups),
                     prepare_preprocess_bind_groups
                         .run_if(
                             resource_exists::<BatchedInstanceBuffers<MeshUniform, MeshInputUniform>>,
@@ -194,7 +232,7 @@ impl Node for GpuPreprocessNode {
                 });
 
         // Run the compute passes.
-        for (view, bind_group, view_uniform_offset, gpu_culling) in
+        for (view, bind_group, view_uniform_offset, gpu_culling, depth_pyramid) in
             self.view_query.iter_manual(world)
         {
             // Grab the index buffer for this view.
@@ -203,9 +241,11 @@ impl Node for GpuPreprocessNode {
                 return Ok(());
             };
 
-            // Select the right pipeline, depending on whether GPU culling is in
-            // use.
-            let maybe_pipeline_id = if gpu_culling {
+            // Select the right pipeline, depending on whether GPU culling and
+            // occlusion culling are in use.
+            let maybe_pipeline_id = if gpu_culling && depth_pyramid.is_some() {
+                preprocess_pipelines.occlusion_culling.pipeline_id
+            } else if gpu_culling {
                 preprocess_pipelines.gpu_culling.pipeline_id
             } else {
                 preprocess_pipelines.direct.pipeline_id
@@ -226,9 +266,12 @@ impl Node for GpuPreprocessNode {
 
             compute_pass.set_pipeline(preprocess_pipeline);
 
-            let mut dynamic_offsets: SmallVec<[u32; 1]> = smallvec![];
+            let mut dynamic_offsets: SmallVec<[u32; 2]> = smallvec![];
             if gpu_culling {
                 dynamic_offsets.push(view_uniform_offset.offset);
+                if let Some(depth_pyramid) = depth_pyramid {
+                    dynamic_offsets.push(depth_pyramid.uniform_offset);
+                }
             }
             compute_pass.set_bind_group(0, &bind_group.0, &dynamic_offsets);
 
@@ -242,7 +285,9 @@ impl Node for GpuPreprocessNode {
 
 impl PreprocessPipelines {
     pub(crate) fn pipelines_are_loaded(&self, pipeline_cache: &PipelineCache) -> bool {
-        self.direct.is_loaded(pipeline_cache) && self.gpu_culling.is_loaded(pipeline_cache)
+        self.direct.is_loaded(pipeline_cache)
+            && self.gpu_culling.is_loaded(pipeline_cache)
+            && self.occlusion_culling.is_loaded(pipeline_cache)
     }
 }
 
@@ -262,12 +307,17 @@ impl SpecializedComputePipeline for PreprocessPipeline {
             shader_defs.push("INDIRECT".into());
             shader_defs.push("FRUSTUM_CULLING".into());
         }
+        if key.contains(PreprocessPipelineKey::OCCLUSION_CULLING) {
+            shader_defs.push("OCCLUSION_CULLING".into());
+        }
 
         ComputePipelineDescriptor {
             label: Some(
                 format!(
                     "mesh preprocessing ({})",
-                    if key.contains(PreprocessPipelineKey::GPU_CULLING) {
+                    if key.contains(PreprocessPipelineKey::OCCLUSION_CULLING) {
+                        "occlusion culling"
+                    } else if key.contains(PreprocessPipelineKey::GPU_CULLING) {
                         "GPU culling"
                     } else {
                         "direct"
@@ -290,15 +340,17 @@ impl FromWorld for PreprocessPipelines {
 
         // GPU culling bind group parameters are a superset of those in the CPU
         // culling (direct) shader.
+        // Likewise, occlusion culling bind group parameters are a superset of
+        // those in the GPU culling shader.
         let direct_bind_group_layout_entries = preprocess_direct_bind_group_layout_entries();
-        let gpu_culling_bind_group_layout_entries = preprocess_direct_bind_group_layout_entries()
-            .extend_sequential((
-                // `indirect_parameters`
-                storage_buffer::<IndirectParameters>(/*has_dynamic_offset=*/ false),
-                // `mesh_culling_data`
-                storage_buffer_read_only::<MeshCullingData>(/*has_dynamic_offset=*/ false),
-                // `view`
-                uniform_buffer::<ViewUniform>(/*has_dynamic_offset=*/ true),
+        let gpu_culling_bind_group_layout_entries =
+            preprocess_gpu_culling_bind_group_layout_entries();
+        let occlusion_culling_bind_group_layout_entries =
+            preprocess_gpu_culling_bind_group_layout_entries().extend_sequential((
+                // `depth_pyramid`
+                texture_2d(TextureSampleType::Float { filterable: false }),
+                // `depth_pyramid_view`
+                uniform_buffer::<DepthPyramidUniform>(/*has_dynamic_offset=*/ true),
             ));
 
         let direct_bind_group_layout = render_device.create_bind_group_layout(
@@ -309,6 +361,10 @@ impl FromWorld for PreprocessPipelines {
             "build mesh uniforms GPU culling bind group layout",
             &gpu_culling_bind_group_layout_entries,
         );
+        let occlusion_culling_bind_group_layout = render_device.create_bind_group_layout(
+            "build mesh uniforms occlusion culling bind group layout",
+            &occlusion_culling_bind_group_layout_entries,
+        );
 
         PreprocessPipelines {
             direct: PreprocessPipeline {
@@ -319,6 +375,10 @@ impl FromWorld for PreprocessPipelines {
                 bind_group_layout: gpu_culling_bind_group_layout,
                 pipeline_id: None,
             },
+            occlusion_culling: PreprocessPipeline {
+                bind_group_layout: occlusion_culling_bind_group_layout,
+                pipeline_id: None,
+            },
         }
     }
 }
@@ -339,6 +399,17 @@ fn preprocess_direct_bind_group_layout_entries() -> DynamicBindGroupLayoutEntrie
     )
 }
 
+fn preprocess_gpu_culling_bind_group_layout_entries() -> DynamicBindGroupLayoutEntries {
+    preprocess_direct_bind_group_layout_entries().extend_sequential((
+        // `indirect_parameters`
+        storage_buffer::<IndirectParameters>(/*has_dynamic_offset=*/ false),
+        // `mesh_culling_data`
+        storage_buffer_read_only::<MeshCullingData>(/*has_dynamic_offset=*/ false),
+        // `view`
+        uniform_buffer::<ViewUniform>(/*has_dynamic_offset=*/ true),
+    ))
+}
+
 /// A system that specializes the `mesh_preprocess.wgsl` pipelines if necessary.
 pub fn prepare_preprocess_pipelines(
     pipeline_cache: Res<PipelineCache>,
@@ -355,6 +426,11 @@ pub fn prepare_preprocess_pipelines(
         &mut pipelines,
         PreprocessPipelineKey::GPU_CULLING,
     );
+    preprocess_pipelines.occlusion_culling.prepare(
+        &pipeline_cache,
+        &mut pipelines,
+        PreprocessPipelineKey::GPU_CULLING | PreprocessPipelineKey::OCCLUSION_CULLING,
+    );
 }
 
 impl PreprocessPipeline {
@@ -375,6 +451,7 @@ impl PreprocessPipeline {
 
 /// A system that attaches the mesh uniform buffers to the bind groups for the
 /// variants of the mesh preprocessing compute shader.
+#[allow(clippy::too_many_arguments)]
 pub fn prepare_preprocess_bind_groups(
     mut commands: Commands,
     render_device: Res<RenderDevice>,
@@ -382,7 +459,9 @@ pub fn prepare_preprocess_bind_groups(
     indirect_parameters_buffer: Res<IndirectParametersBuffer>,
     mesh_culling_data_buffer: Res<MeshCullingDataBuffer>,
     view_uniforms: Res<ViewUniforms>,
+    depth_pyramid_uniforms: Res<DepthPyramidUniforms>,
     pipelines: Res<PreprocessPipelines>,
+    depth_pyramids: Query<&ViewDepthPyramid>,
 ) {
     // Grab the `BatchedInstanceBuffers`.
     let BatchedInstanceBuffers {
@@ -427,23 +506,49 @@ pub fn prepare_preprocess_bind_groups(
                 continue;
             };
 
-            PreprocessBindGroup(render_device.create_bind_group(
-                "preprocess_gpu_culling_bind_group",
-                &pipelines.gpu_culling.bind_group_layout,
-                &BindGroupEntries::sequential((
-                    current_input_buffer.as_entire_binding(),
-                    previous_input_buffer.as_entire_binding(),
-                    BindingResource::Buffer(BufferBinding {
-                        buffer: index_buffer,
-                        offset: 0,
-                        size: index_buffer_size,
-                    }),
-                    data_buffer.as_entire_binding(),
-                    indirect_parameters_buffer.as_entire_binding(),
-                    mesh_culling_data_buffer.as_entire_binding(),
-                    view_uniforms_binding,
-                )),
-            ))
+            // Views with a depth pyramid are occlusion culled against it as
+            // well.
+            if let (Ok(depth_pyramid), Some(depth_pyramid_uniforms_binding)) =
+                (depth_pyramids.get(*view), depth_pyramid_uniforms.binding())
+            {
+                PreprocessBindGroup(render_device.create_bind_group(
+                    "preprocess_occlusion_culling_bind_group",
+                    &pipelines.occlusion_culling.bind_group_layout,
+                    &BindGroupEntries::sequential((
+                        current_input_buffer.as_entire_binding(),
+                        previous_input_buffer.as_entire_binding(),
+                        BindingResource::Buffer(BufferBinding {
+                            buffer: index_buffer,
+                            offset: 0,
+                            size: index_buffer_size,
+                        }),
+                        data_buffer.as_entire_binding(),
+                        indirect_parameters_buffer.as_entire_binding(),
+                        mesh_culling_data_buffer.as_entire_binding(),
+                        view_uniforms_binding,
+                        &depth_pyramid.views.all_mips,
+                        depth_pyramid_uniforms_binding,
+                    )),
+                ))
+            } else {
+                PreprocessBindGroup(render_device.create_bind_group(
+                    "preprocess_gpu_culling_bind_group",
+                    &pipelines.gpu_culling.bind_group_layout,
+                    &BindGroupEntries::sequential((
+                        current_input_buffer.as_entire_binding(),
+                        previous_input_buffer.as_entire_binding(),
+                        BindingResource::Buffer(BufferBinding {
+                            buffer: index_buffer,
+                            offset: 0,
+                            size: index_buffer_size,
+                        }),
+                        data_buffer.as_entire_binding(),
+                        indirect_parameters_buffer.as_entire_binding(),
+                        mesh_culling_data_buffer.as_entire_binding(),
+                        view_uniforms_binding,
+                    )),
+                ))
+            }
         } else {
             PreprocessBindGroup(render_device.create_bind_group(
                 "preprocess_direct_bind_group",
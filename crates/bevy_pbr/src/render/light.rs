@@ -5,7 +5,7 @@ use bevy_ecs::{entity::EntityHashMap, system::lifetimeless::Read};
 use bevy_math::{Mat4, UVec3, UVec4, Vec2, Vec3, Vec3Swizzles, Vec4, Vec4Swizzles};
 use bevy_render::mesh::Mesh;
 use bevy_render::{
-    camera::Camera,
+    camera::{Camera, ExtractedCamera},
     diagnostic::RecordDiagnostics,
     mesh::GpuMesh,
     primitives::{CascadesFrusta, CubemapFrusta, Frustum, HalfSpace},
@@ -22,7 +22,12 @@ use bevy_transform::{components::GlobalTransform, prelude::Transform};
 #[cfg(feature = "trace")]
 use bevy_utils::tracing::info_span;
 use bevy_utils::tracing::{error, warn};
-use std::{hash::Hash, num::NonZeroU64, ops::Range};
+use bevy_utils::warn_once;
+use std::{
+    hash::Hash,
+    num::{NonZeroU32, NonZeroU64},
+    ops::{Deref, Range},
+};
 
 use crate::*;
 
@@ -38,6 +43,11 @@ pub struct ExtractedPointLight {
     pub shadow_depth_bias: f32,
     pub shadow_normal_bias: f32,
     pub spot_light_angles: Option<(f32, f32)>,
+    pub volumetric: bool,
+    /// The cubemap cookie of a point light, or the texture cookie of a spot light.
+    pub cookie: Option<AssetId<Image>>,
+    /// Scales the resolution of the light's tile in the [`ShadowAtlas`].
+    pub shadow_priority: f32,
 }
 
 #[derive(Component, Debug)]
@@ -52,6 +62,8 @@ pub struct ExtractedDirectionalLight {
     pub cascades: EntityHashMap<Vec<Cascade>>,
     pub frusta: EntityHashMap<Vec<Frustum>>,
     pub render_layers: RenderLayers,
+    pub volumetric: bool,
+    pub cookie: Option<AssetId<Image>>,
 }
 
 #[derive(Copy, Clone, ShaderType, Default, Debug)]
@@ -150,11 +162,31 @@ bitflags::bitflags! {
     struct PointLightFlags: u32 {
         const SHADOWS_ENABLED            = 1 << 0;
         const SPOT_LIGHT_Y_NEGATIVE      = 1 << 1;
+        const VOLUMETRIC                 = 1 << 2;
+        const COOKIE_CUBEMAP             = 1 << 3;
+        const COOKIE_TEXTURE             = 1 << 4;
+        const SPOT_LIGHT                 = 1 << 5;
         const NONE                       = 0;
         const UNINITIALIZED              = 0xFFFF;
     }
 }
 
+/// The number of bits that a light's cookie index is shifted by when it's packed into the light's
+/// flags.
+///
+/// NOTE: This must match `LIGHT_COOKIE_INDEX_SHIFT` in `bevy_pbr/src/render/mesh_view_types.wgsl`!
+pub(crate) const LIGHT_COOKIE_INDEX_SHIFT: u32 = 16;
+
+/// The maximum number of cookie textures, and separately of cookie cubemaps, that can be bound at
+/// once when binding arrays are available.
+///
+/// Otherwise, only a single cookie texture and a single cookie cubemap can be used.
+pub const MAX_LIGHT_COOKIES: usize = 8;
+
+/// On WebGL and WebGPU, we must disable light cookies, as otherwise we can overflow the number of
+/// texture bindings.
+pub(crate) const LIGHT_COOKIES_ARE_USABLE: bool = cfg!(not(target_arch = "wasm32"));
+
 #[derive(Copy, Clone, ShaderType, Default, Debug)]
 pub struct GpuDirectionalCascade {
     view_projection: Mat4,
@@ -174,6 +206,8 @@ pub struct GpuDirectionalLight {
     cascades_overlap_proportion: f32,
     depth_texture_base_index: u32,
     render_layers: u32,
+    // Maps world space positions to the light's local space, in which the cookie is tiled.
+    cookie_from_world: Mat4,
 }
 
 // NOTE: These must match the bit flags in bevy_pbr/src/render/mesh_view_types.wgsl!
@@ -181,6 +215,8 @@ bitflags::bitflags! {
     #[repr(transparent)]
     struct DirectionalLightFlags: u32 {
         const SHADOWS_ENABLED            = 1 << 0;
+        const VOLUMETRIC                 = 1 << 1;
+        const COOKIE_TEXTURE             = 1 << 2;
         const NONE                       = 0;
         const UNINITIALIZED              = 0xFFFF;
     }
@@ -197,8 +233,11 @@ pub struct GpuLights {
     // w is cluster_dimensions.z * log(near) / log(far / near)
     cluster_factors: Vec4,
     n_directional_lights: u32,
-    // offset from spot light's light index to spot light's shadow map index
+    // offset from spot light's light index to spot light's shadow atlas tile index
     spot_light_shadowmap_offset: i32,
+    // the shadow atlas tiles of point and spot lights, packed by `ShadowAtlasTile::pack`, four
+    // per element
+    shadow_atlas_tiles: [UVec4; MAX_SHADOW_ATLAS_TILES / 4],
 }
 
 // NOTE: this must be kept in sync with the same constants in pbr.frag
@@ -225,7 +264,7 @@ pub const MAX_CASCADES_PER_LIGHT: usize = 1;
 
 #[derive(Resource, Clone)]
 pub struct ShadowSamplers {
-    pub point_light_sampler: Sampler,
+    pub shadow_atlas_sampler: Sampler,
     pub directional_light_sampler: Sampler,
 }
 
@@ -235,7 +274,7 @@ impl FromWorld for ShadowSamplers {
         let render_device = world.resource::<RenderDevice>();
 
         ShadowSamplers {
-            point_light_sampler: render_device.create_sampler(&SamplerDescriptor {
+            shadow_atlas_sampler: render_device.create_sampler(&SamplerDescriptor {
                 address_mode_u: AddressMode::ClampToEdge,
                 address_mode_v: AddressMode::ClampToEdge,
                 address_mode_w: AddressMode::ClampToEdge,
@@ -315,6 +354,7 @@ pub fn extract_lights(
     mut commands: Commands,
     point_light_shadow_map: Extract<Res<PointLightShadowMap>>,
     directional_light_shadow_map: Extract<Res<DirectionalLightShadowMap>>,
+    shadow_atlas_settings: Extract<Res<ShadowAtlasSettings>>,
     global_point_lights: Extract<Res<GlobalVisiblePointLights>>,
     point_lights: Extract<
         Query<(
@@ -323,6 +363,9 @@ pub fn extract_lights(
             &GlobalTransform,
             &ViewVisibility,
             &CubemapFrusta,
+            Has<VolumetricLight>,
+            Option<&ShadowPriority>,
+            Option<&LightCookie>,
         )>,
     >,
     spot_lights: Extract<
@@ -332,6 +375,9 @@ pub fn extract_lights(
             &GlobalTransform,
             &ViewVisibility,
             &Frustum,
+            Has<VolumetricLight>,
+            Option<&ShadowPriority>,
+            Option<&LightCookie>,
         )>,
     >,
     directional_lights: Extract<
@@ -346,6 +392,8 @@ pub fn extract_lights(
                 &GlobalTransform,
                 &ViewVisibility,
                 Option<&RenderLayers>,
+                Has<VolumetricLight>,
+                Option<&LightCookie>,
             ),
             Without<SpotLight>,
         >,
@@ -361,8 +409,12 @@ pub fn extract_lights(
     if directional_light_shadow_map.is_changed() {
         commands.insert_resource(directional_light_shadow_map.clone());
     }
+    if shadow_atlas_settings.is_changed() {
+        commands.insert_resource(shadow_atlas_settings.clone());
+    }
     // This is the point light shadow map texel size for one face of the cube as a distance of 1.0
-    // world unit from the light.
+    // world unit from the light, at the maximum resolution. `prepare_lights` scales it to the
+    // resolution of the light's tile in the shadow atlas.
     // point_light_texel_size = 2.0 * 1.0 * tan(PI / 4.0) / cube face width in texels
     // PI / 4.0 is half the cube face fov, tan(PI / 4.0) = 1.0, so this simplifies to:
     // point_light_texel_size = 2.0 / cube face width in texels
@@ -372,8 +424,16 @@ pub fn extract_lights(
 
     let mut point_lights_values = Vec::with_capacity(*previous_point_lights_len);
     for entity in global_point_lights.iter().copied() {
-        let Ok((point_light, cubemap_visible_entities, transform, view_visibility, frusta)) =
-            point_lights.get(entity)
+        let Ok((
+            point_light,
+            cubemap_visible_entities,
+            transform,
+            view_visibility,
+            frusta,
+            volumetric,
+            shadow_priority,
+            cookie,
+        )) = point_lights.get(entity)
         else {
             continue;
         };
@@ -399,6 +459,9 @@ pub fn extract_lights(
                 * point_light_texel_size
                 * std::f32::consts::SQRT_2,
             spot_light_angles: None,
+            volumetric,
+            cookie: cookie.map(|cookie| cookie.0.id()),
+            shadow_priority: shadow_priority.map_or(1.0, |priority| priority.0),
         };
         point_lights_values.push((
             entity,
@@ -414,8 +477,16 @@ pub fn extract_lights(
 
     let mut spot_lights_values = Vec::with_capacity(*previous_spot_lights_len);
     for entity in global_point_lights.iter().copied() {
-        if let Ok((spot_light, visible_entities, transform, view_visibility, frustum)) =
-            spot_lights.get(entity)
+        if let Ok((
+            spot_light,
+            visible_entities,
+            transform,
+            view_visibility,
+            frustum,
+            volumetric,
+            shadow_priority,
+            cookie,
+        )) = spot_lights.get(entity)
         {
             if !view_visibility.get() {
                 continue;
@@ -448,6 +519,9 @@ pub fn extract_lights(
                             * texel_size
                             * std::f32::consts::SQRT_2,
                         spot_light_angles: Some((spot_light.inner_angle, spot_light.outer_angle)),
+                        volumetric,
+                        cookie: cookie.map(|cookie| cookie.0.id()),
+                        shadow_priority: shadow_priority.map_or(1.0, |priority| priority.0),
                     },
                     render_visible_entities,
                     *frustum,
@@ -468,6 +542,8 @@ pub fn extract_lights(
         transform,
         view_visibility,
         maybe_layers,
+        volumetric,
+        cookie,
     ) in &directional_lights
     {
         if !view_visibility.get() {
@@ -489,6 +565,8 @@ pub fn extract_lights(
                 cascades: cascades.cascades.clone(),
                 frusta: frusta.frusta.clone(),
                 render_layers: maybe_layers.copied().unwrap_or_default(),
+                volumetric,
+                cookie: cookie.map(|cookie| cookie.0.id()),
             },
             render_visible_entities,
         ));
@@ -558,12 +636,17 @@ fn face_index_to_name(face_index: usize) -> &'static str {
 pub struct ShadowView {
     pub depth_attachment: DepthAttachment,
     pub pass_name: String,
+    /// The region of the depth attachment the view renders to, as x, y, width and height, or
+    /// `None` if it renders to the whole attachment.
+    ///
+    /// The region is cleared before rendering, and the rest of the attachment is kept.
+    pub viewport: Option<UVec4>,
 }
 
 #[derive(Component)]
 pub struct ViewShadowBindings {
-    pub point_light_depth_texture: Texture,
-    pub point_light_depth_texture_view: TextureView,
+    pub shadow_atlas_texture: Texture,
+    pub shadow_atlas_texture_view: TextureView,
     pub directional_light_depth_texture: Texture,
     pub directional_light_depth_texture_view: TextureView,
 }
@@ -573,6 +656,13 @@ pub struct ViewLightEntities {
     pub lights: Vec<Entity>,
 }
 
+/// A marker component added to the views that bind the light cookies in [`GlobalLightMeta`],
+/// because at least one light has a [`LightCookie`](crate::LightCookie) that's ready to be used.
+///
+/// Views without it leave the light cookie bindings out of their mesh view bind group.
+#[derive(Component, Clone, Copy, Default)]
+pub struct ViewLightCookies;
+
 #[derive(Component)]
 pub struct ViewLightsUniformOffset {
     pub offset: u32,
@@ -586,6 +676,10 @@ pub const CLUSTERED_FORWARD_STORAGE_BUFFER_COUNT: u32 = 3;
 pub struct GlobalLightMeta {
     pub gpu_point_lights: GpuPointLights,
     pub entity_to_index: EntityHashMap<usize>,
+    /// The cookie textures of spot and directional lights, in binding array order.
+    pub cookie_textures: Vec<AssetId<Image>>,
+    /// The cookie cubemaps of point lights, in binding array order.
+    pub cookie_cubemaps: Vec<AssetId<Image>>,
 }
 
 impl FromWorld for GlobalLightMeta {
@@ -603,6 +697,114 @@ impl GlobalLightMeta {
         Self {
             gpu_point_lights: GpuPointLights::new(buffer_binding_type),
             entity_to_index: EntityHashMap::default(),
+            cookie_textures: Vec::new(),
+            cookie_cubemaps: Vec::new(),
+        }
+    }
+}
+
+/// All the bind group entries necessary for the light cookies of a view.
+pub(crate) enum RenderLightCookieBindGroupEntries<'a> {
+    /// The version used when binding arrays aren't available on the current
+    /// platform.
+    Single {
+        /// The cookie texture of the first spot or directional light with one.
+        texture_view: &'a TextureView,
+        /// The cookie cubemap of the first point light with one.
+        cubemap_view: &'a TextureView,
+        /// The sampler used to sample both cookies.
+        sampler: &'a Sampler,
+    },
+
+    /// The version used when binding arrays are available on the current
+    /// platform.
+    Multiple {
+        /// The cookie textures, in the order of
+        /// [`GlobalLightMeta::cookie_textures`], padded with fallback textures.
+        texture_views: Vec<&'a <TextureView as Deref>::Target>,
+        /// As above, but for the cookie cubemaps.
+        cubemap_views: Vec<&'a <TextureView as Deref>::Target>,
+        /// The sampler used to sample all cookies.
+        sampler: &'a Sampler,
+    },
+}
+
+/// Returns the bind group layout entries for the light cookie texture and
+/// cubemap binding arrays respectively, in addition to the sampler.
+pub(crate) fn get_light_cookie_bind_group_layout_entries(
+    render_device: &RenderDevice,
+) -> [BindGroupLayoutEntryBuilder; 3] {
+    let mut texture_binding =
+        binding_types::texture_2d(TextureSampleType::Float { filterable: true });
+    let mut cubemap_binding =
+        binding_types::texture_cube(TextureSampleType::Float { filterable: true });
+    if binding_arrays_are_usable(render_device) {
+        let count = NonZeroU32::new(MAX_LIGHT_COOKIES as _).unwrap();
+        texture_binding = texture_binding.count(count);
+        cubemap_binding = cubemap_binding.count(count);
+    }
+
+    [
+        texture_binding,
+        cubemap_binding,
+        binding_types::sampler(SamplerBindingType::Filtering),
+    ]
+}
+
+impl<'a> RenderLightCookieBindGroupEntries<'a> {
+    /// Looks up and returns the bindings for the light cookies prepared in
+    /// [`prepare_lights`].
+    pub(crate) fn get(
+        global_light_meta: &GlobalLightMeta,
+        images: &'a RenderAssets<GpuImage>,
+        fallback_image: &'a FallbackImage,
+        render_device: &RenderDevice,
+    ) -> RenderLightCookieBindGroupEntries<'a> {
+        let texture_images = global_light_meta
+            .cookie_textures
+            .iter()
+            .filter_map(|id| images.get(*id));
+        let cubemap_images = global_light_meta
+            .cookie_cubemaps
+            .iter()
+            .filter_map(|id| images.get(*id));
+
+        // The sampler of the first cookie is used for all of them.
+        let sampler = texture_images
+            .clone()
+            .chain(cubemap_images.clone())
+            .map(|image| &image.sampler)
+            .next()
+            .unwrap_or(&fallback_image.d2.sampler);
+
+        if binding_arrays_are_usable(render_device) {
+            let mut texture_views: Vec<_> =
+                texture_images.map(|image| &*image.texture_view).collect();
+            let mut cubemap_views: Vec<_> =
+                cubemap_images.map(|image| &*image.texture_view).collect();
+
+            // Pad out the bindings to the size of the binding array using fallback
+            // textures. This is necessary on D3D12 and Metal.
+            texture_views.resize(MAX_LIGHT_COOKIES, &*fallback_image.d2.texture_view);
+            cubemap_views.resize(MAX_LIGHT_COOKIES, &*fallback_image.cube.texture_view);
+
+            return RenderLightCookieBindGroupEntries::Multiple {
+                texture_views,
+                cubemap_views,
+                sampler,
+            };
+        }
+
+        RenderLightCookieBindGroupEntries::Single {
+            texture_view: texture_images
+                .map(|image| &image.texture_view)
+                .next()
+                .unwrap_or(&fallback_image.d2.texture_view),
+            cubemap_view: cubemap_images
+                .map(|image| &image.texture_view)
+                .next()
+                .unwrap_or(&fallback_image.cube.texture_view),
+            sampler,
         }
     }
 }
@@ -675,6 +877,40 @@ pub(crate) fn spot_light_projection_matrix(angle: f32) -> Mat4 {
     Mat4::perspective_infinite_reverse_rh(angle * 2.0, 1.0, POINT_LIGHT_NEAR_Z)
 }
 
+/// Returns the binding array index of the light cookie `cookie`, adding it to `cookies` if it
+/// isn't there yet.
+///
+/// Returns [`None`] if the image isn't loaded, doesn't have `array_layers` layers, or if there's
+/// no room left for it.
+fn add_light_cookie(
+    cookies: &mut Vec<AssetId<Image>>,
+    cookie: AssetId<Image>,
+    array_layers: u32,
+    images: &RenderAssets<GpuImage>,
+    max_light_cookies: usize,
+) -> Option<u32> {
+    if let Some(index) = cookies.iter().position(|id| *id == cookie) {
+        return Some(index as u32);
+    }
+
+    let image = images.get(cookie)?;
+    if image.texture.depth_or_array_layers() != array_layers || max_light_cookies == 0 {
+        return None;
+    }
+
+    if cookies.len() >= max_light_cookies {
+        warn_once!(
+            "The number of light cookies of the same kind exceeds the supported limit of {}. \
+            Some light cookies will be ignored.",
+            max_light_cookies
+        );
+        return None;
+    }
+
+    cookies.push(cookie);
+    Some(cookies.len() as u32 - 1)
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn prepare_lights(
     mut commands: Commands,
@@ -684,12 +920,22 @@ pub fn prepare_lights(
     mut global_light_meta: ResMut<GlobalLightMeta>,
     mut light_meta: ResMut<LightMeta>,
     views: Query<
-        (Entity, &ExtractedView, &ExtractedClusterConfig),
+        (
+            Entity,
+            &ExtractedView,
+            &ExtractedClusterConfig,
+            Option<&ExtractedCamera>,
+        ),
         With<SortedRenderPhase<Transparent3d>>,
     >,
     ambient_light: Res<AmbientLight>,
     point_light_shadow_map: Res<PointLightShadowMap>,
     directional_light_shadow_map: Res<DirectionalLightShadowMap>,
+    (mut shadow_atlas, shadow_atlas_settings, shadow_caster_changes): (
+        ResMut<ShadowAtlas>,
+        Res<ShadowAtlasSettings>,
+        Res<ShadowCasterChanges>,
+    ),
     mut max_directional_lights_warning_emitted: Local<bool>,
     mut max_cascades_per_light_warning_emitted: Local<bool>,
     point_lights: Query<(
@@ -698,6 +944,7 @@ pub fn prepare_lights(
         AnyOf<(&CubemapFrusta, &Frustum)>,
     )>,
     directional_lights: Query<(Entity, &ExtractedDirectionalLight)>,
+    images: Res<RenderAssets<GpuImage>>,
 ) {
     let views_iter = views.iter();
     let views_count = views_iter.len();
@@ -718,6 +965,16 @@ pub fn prepare_lights(
         .collect::<Vec<_>>();
 
     global_light_meta.entity_to_index.clear();
+    global_light_meta.cookie_textures.clear();
+    global_light_meta.cookie_cubemaps.clear();
+
+    let max_light_cookies = if !LIGHT_COOKIES_ARE_USABLE {
+        0
+    } else if binding_arrays_are_usable(&render_device) {
+        MAX_LIGHT_COOKIES
+    } else {
+        1
+    };
 
     let mut point_lights: Vec<_> = point_lights.iter().collect::<Vec<_>>();
     let mut directional_lights: Vec<_> = directional_lights.iter().collect::<Vec<_>>();
@@ -728,16 +985,8 @@ pub fn prepare_lights(
         feature = "webgpu"
     ))]
     let max_texture_array_layers = render_device.limits().max_texture_array_layers as usize;
-    #[cfg(any(
-        not(feature = "webgl"),
-        not(target_arch = "wasm32"),
-        feature = "webgpu"
-    ))]
-    let max_texture_cubes = max_texture_array_layers / 6;
     #[cfg(all(feature = "webgl", target_arch = "wasm32", not(feature = "webgpu")))]
     let max_texture_array_layers = 1;
-    #[cfg(all(feature = "webgl", target_arch = "wasm32", not(feature = "webgpu")))]
-    let max_texture_cubes = 1;
 
     if !*max_directional_lights_warning_emitted && directional_lights.len() > MAX_DIRECTIONAL_LIGHTS
     {
@@ -766,11 +1015,13 @@ pub fn prepare_lights(
         .filter(|light| light.1.spot_light_angles.is_none())
         .count();
 
+    // Point and spot light shadow maps are tiles of the shadow atlas, which the shaders find by
+    // the light's index, so only `MAX_SHADOW_ATLAS_TILES` of them can have one.
     let point_light_shadow_maps_count = point_lights
         .iter()
         .filter(|light| light.1.shadows_enabled && light.1.spot_light_angles.is_none())
         .count()
-        .min(max_texture_cubes);
+        .min(MAX_SHADOW_ATLAS_TILES);
 
     let directional_shadow_enabled_count = directional_lights
         .iter()
@@ -783,7 +1034,7 @@ pub fn prepare_lights(
         .iter()
         .filter(|(_, light, _)| light.shadows_enabled && light.spot_light_angles.is_some())
         .count()
-        .min(max_texture_array_layers - directional_shadow_enabled_count * MAX_CASCADES_PER_LIGHT);
+        .min(MAX_SHADOW_ATLAS_TILES - point_light_shadow_maps_count);
 
     // Sort lights by
     // - point-light vs spot-light, so that we can iterate point lights and spot lights in contiguous blocks in the fragment shader,
@@ -822,21 +1073,123 @@ pub fn prepare_lights(
             .reserve(point_lights.len());
     }
 
+    // Allocate the shadow atlas tiles of point and spot lights, with a resolution that follows
+    // their largest coverage of any view.
+    let shadow_atlas_size = shadow_atlas_size(&shadow_atlas_settings, &render_device);
+    let mut shadow_atlas_requests: Vec<_> = point_lights
+        .iter()
+        // Lights are sorted, shadow enabled lights are first
+        .take(point_light_shadow_maps_count)
+        .chain(
+            point_lights
+                .iter()
+                .skip(point_light_count)
+                .take(spot_light_shadow_maps_count),
+        )
+        .map(|&(light_entity, light, _)| {
+            let (max_tile_size, key) = match light.spot_light_angles {
+                Some((_, outer)) => (
+                    (directional_light_shadow_map.size as u32).min(shadow_atlas_size),
+                    ShadowMapKey {
+                        transform: spot_light_view_matrix(&light.transform),
+                        range: light.range,
+                        angle: outer,
+                    },
+                ),
+                None => (
+                    (point_light_shadow_map.size as u32).min(shadow_atlas_size / 3),
+                    ShadowMapKey {
+                        transform: Mat4::from_translation(light.transform.translation()),
+                        range: light.range,
+                        angle: 0.0,
+                    },
+                ),
+            };
+            let coverage = views
+                .iter()
+                .map(|(_, view, _, _)| {
+                    shadow_map_coverage(view, light.transform.translation(), light.range)
+                })
+                .fold(0.0, f32::max);
+
+            ShadowAtlasRequest {
+                light_entity,
+                is_point_light: light.spot_light_angles.is_none(),
+                tile_size: shadow_atlas_tile_size(
+                    coverage * light.shadow_priority,
+                    shadow_atlas_settings.min_tile_size,
+                    max_tile_size,
+                ),
+                key,
+            }
+        })
+        .collect();
+    let shadow_atlas_lights_to_render = shadow_atlas.allocate(
+        &render_device,
+        &shadow_atlas_settings,
+        &shadow_caster_changes,
+        &mut shadow_atlas_requests,
+    );
+    let (Some(shadow_atlas_texture), Some(shadow_atlas_texture_view)) = (
+        shadow_atlas.texture().cloned(),
+        shadow_atlas.texture_view().cloned(),
+    ) else {
+        return;
+    };
+
+    let mut shadow_atlas_tiles = [UVec4::ZERO; MAX_SHADOW_ATLAS_TILES / 4];
     let mut gpu_point_lights = Vec::new();
     for (index, &(entity, light, _)) in point_lights.iter().enumerate() {
         let mut flags = PointLightFlags::NONE;
 
-        // Lights are sorted, shadow enabled lights are first
-        if light.shadows_enabled
-            && (index < point_light_shadow_maps_count
-                || (light.spot_light_angles.is_some()
-                    && index - point_light_count < spot_light_shadow_maps_count))
-        {
+        // Only lights with shadows enabled are given a tile
+        let mut shadow_normal_bias = light.shadow_normal_bias;
+        if let Some(tile) = shadow_atlas.tile(entity) {
             flags |= PointLightFlags::SHADOWS_ENABLED;
+
+            // Spot light tiles follow the point light tiles
+            let (tile_index, max_tile_size) = match light.spot_light_angles {
+                Some(_) => (
+                    index - point_light_count + point_light_shadow_maps_count,
+                    directional_light_shadow_map.size,
+                ),
+                None => (index, point_light_shadow_map.size),
+            };
+            shadow_atlas_tiles[tile_index / 4][tile_index % 4] = tile.pack();
+
+            // The normal bias was scaled to the texel size at the maximum resolution
+            shadow_normal_bias *= max_tile_size as f32 / tile.size as f32;
+        }
+
+        if light.volumetric {
+            flags |= PointLightFlags::VOLUMETRIC;
+        }
+
+        let mut cookie_index = 0;
+        if let Some(cookie) = light.cookie {
+            let (cookies, array_layers, cookie_flag) = match light.spot_light_angles {
+                Some(_) => (
+                    &mut global_light_meta.cookie_textures,
+                    1,
+                    PointLightFlags::COOKIE_TEXTURE,
+                ),
+                None => (
+                    &mut global_light_meta.cookie_cubemaps,
+                    6,
+                    PointLightFlags::COOKIE_CUBEMAP,
+                ),
+            };
+            if let Some(index) =
+                add_light_cookie(cookies, cookie, array_layers, &images, max_light_cookies)
+            {
+                flags |= cookie_flag;
+                cookie_index = index;
+            }
         }
 
         let (light_custom_data, spot_light_tan_angle) = match light.spot_light_angles {
             Some((inner, outer)) => {
+                flags |= PointLightFlags::SPOT_LIGHT;
                 let light_direction = light.transform.forward();
                 if light_direction.y.is_sign_negative() {
                     flags |= PointLightFlags::SPOT_LIGHT_Y_NEGATIVE;
@@ -876,9 +1229,9 @@ pub fn prepare_lights(
                 .xyz()
                 .extend(1.0 / (light.range * light.range)),
             position_radius: light.transform.translation().extend(light.radius),
-            flags: flags.bits(),
+            flags: flags.bits() | (cookie_index << LIGHT_COOKIE_INDEX_SHIFT),
             shadow_depth_bias: light.shadow_depth_bias,
-            shadow_normal_bias: light.shadow_normal_bias,
+            shadow_normal_bias,
             spot_light_tan_angle,
         });
         global_light_meta.entity_to_index.insert(entity, index);
@@ -898,6 +1251,24 @@ pub fn prepare_lights(
             flags |= DirectionalLightFlags::SHADOWS_ENABLED;
         }
 
+        if light.volumetric {
+            flags |= DirectionalLightFlags::VOLUMETRIC;
+        }
+
+        let mut cookie_index = 0;
+        if let Some(cookie) = light.cookie {
+            if let Some(index) = add_light_cookie(
+                &mut global_light_meta.cookie_textures,
+                cookie,
+                1,
+                &images,
+                max_light_cookies,
+            ) {
+                flags |= DirectionalLightFlags::COOKIE_TEXTURE;
+                cookie_index = index;
+            }
+        }
+
         let num_cascades = light
             .cascade_shadow_config
             .bounds
@@ -911,13 +1282,14 @@ pub fn prepare_lights(
             color: Vec4::from_slice(&light.color.to_f32_array()) * light.illuminance,
             // direction is negated to be ready for N.L
             dir_to_light: light.transform.back().into(),
-            flags: flags.bits(),
+            flags: flags.bits() | (cookie_index << LIGHT_COOKIE_INDEX_SHIFT),
             shadow_depth_bias: light.shadow_depth_bias,
             shadow_normal_bias: light.shadow_normal_bias,
             num_cascades: num_cascades as u32,
             cascades_overlap_proportion: light.cascade_shadow_config.overlap_proportion,
             depth_texture_base_index: num_directional_cascades_enabled as u32,
             render_layers: light.render_layers.bits(),
+            cookie_from_world: light.transform.compute_matrix().inverse(),
         };
         if index < directional_shadow_enabled_count {
             num_directional_cascades_enabled += num_cascades;
@@ -929,25 +1301,15 @@ pub fn prepare_lights(
         .gpu_point_lights
         .write_buffer(&render_device, &render_queue);
 
+    // Point and spot light shadow maps are shared by all views, so they are rendered to the
+    // atlas once, by the view that renders first.
+    let shadow_atlas_owner_view = views
+        .iter()
+        .min_by_key(|(entity, _, _, camera)| (camera.map(|camera| camera.order), *entity))
+        .map(|(entity, ..)| entity);
+
     // set up light data for each view
-    for (entity, extracted_view, clusters) in &views {
-        let point_light_depth_texture = texture_cache.get(
-            &render_device,
-            TextureDescriptor {
-                size: Extent3d {
-                    width: point_light_shadow_map.size as u32,
-                    height: point_light_shadow_map.size as u32,
-                    depth_or_array_layers: point_light_shadow_maps_count.max(1) as u32 * 6,
-                },
-                mip_level_count: 1,
-                sample_count: 1,
-                dimension: TextureDimension::D2,
-                format: CORE_3D_DEPTH_FORMAT,
-                label: Some("point_light_shadow_map_texture"),
-                usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
-                view_formats: &[],
-            },
-        );
+    for (entity, extracted_view, clusters, _) in &views {
         let directional_light_depth_texture = texture_cache.get(
             &render_device,
             TextureDescriptor {
@@ -956,9 +1318,7 @@ pub fn prepare_lights(
                         .min(render_device.limits().max_texture_dimension_2d),
                     height: (directional_light_shadow_map.size as u32)
                         .min(render_device.limits().max_texture_dimension_2d),
-                    depth_or_array_layers: (num_directional_cascades_enabled
-                        + spot_light_shadow_maps_count)
-                        .max(1) as u32,
+                    depth_or_array_layers: num_directional_cascades_enabled.max(1) as u32,
                 },
                 mip_level_count: 1,
                 sample_count: 1,
@@ -993,24 +1353,70 @@ pub fn prepare_lights(
             cluster_dimensions: clusters.dimensions.extend(n_clusters),
             n_directional_lights: directional_lights.iter().len().min(MAX_DIRECTIONAL_LIGHTS)
                 as u32,
-            // spotlight shadow maps are stored in the directional light array, starting at num_directional_cascades_enabled.
-            // the spot lights themselves start in the light array at point_light_count. so to go from light
-            // index to shadow map index, we need to subtract point light count and add directional shadowmap count.
-            spot_light_shadowmap_offset: num_directional_cascades_enabled as i32
+            // spotlight shadow maps are stored in the shadow atlas after the point light ones, starting at
+            // point_light_shadow_maps_count. the spot lights themselves start in the light array at
+            // point_light_count. so to go from light index to atlas tile index, we need to subtract point
+            // light count and add point light shadow map count.
+            spot_light_shadowmap_offset: point_light_shadow_maps_count as i32
                 - point_light_count as i32,
+            shadow_atlas_tiles,
         };
 
-        // TODO: this should select lights based on relevance to the view instead of the first ones that show up in a query
-        for &(light_entity, light, (point_light_frusta, _)) in point_lights
+        // Only the lights whose shadow atlas tile is out of date are rendered
+        let atlas_lights_to_render = point_lights
             .iter()
             // Lights are sorted, shadow enabled lights are first
             .take(point_light_shadow_maps_count)
-            .filter(|(_, light, _)| light.shadows_enabled)
+            .chain(
+                point_lights
+                    .iter()
+                    .skip(point_light_count)
+                    .take(spot_light_shadow_maps_count),
+            )
+            .filter(|(light_entity, ..)| {
+                shadow_atlas_owner_view == Some(entity)
+                    && shadow_atlas_lights_to_render.contains(light_entity)
+            });
+        for &(light_entity, light, (point_light_frusta, spot_light_frustum)) in
+            atlas_lights_to_render
         {
+            let Some(tile) = shadow_atlas.tile(light_entity) else {
+                continue;
+            };
             let light_index = *global_light_meta
                 .entity_to_index
                 .get(&light_entity)
                 .unwrap();
+
+            if let Some((_, angle)) = light.spot_light_angles {
+                let viewport = tile.face_viewport(0);
+                let view_light_entity = commands
+                    .spawn((
+                        ShadowView {
+                            depth_attachment: DepthAttachment::new(
+                                shadow_atlas_texture_view.clone(),
+                                None,
+                            ),
+                            pass_name: format!("shadow pass spot light {light_index}"),
+                            viewport: Some(viewport),
+                        },
+                        ExtractedView {
+                            viewport,
+                            transform: spot_light_view_matrix(&light.transform).into(),
+                            projection: spot_light_projection_matrix(angle),
+                            view_projection: None,
+                            hdr: false,
+                            color_grading: Default::default(),
+                        },
+                        *spot_light_frustum.unwrap(),
+                        BinnedRenderPhase::<Shadow>::default(),
+                        LightEntity::Spot { light_entity },
+                    ))
+                    .id();
+                view_lights.push(view_light_entity);
+                continue;
+            }
+
             // ignore scale because we don't want to effectively scale light radius and range
             // by applying those as a view transform to shadow map rendering of objects
             // and ignore rotation because we want the shadow map projections to align with the axes
@@ -1021,37 +1427,23 @@ pub fn prepare_lights(
                 .zip(&point_light_frusta.unwrap().frusta)
                 .enumerate()
             {
-                let depth_texture_view =
-                    point_light_depth_texture
-                        .texture
-                        .create_view(&TextureViewDescriptor {
-                            label: Some("point_light_shadow_map_texture_view"),
-                            format: None,
-                            dimension: Some(TextureViewDimension::D2),
-                            aspect: TextureAspect::All,
-                            base_mip_level: 0,
-                            mip_level_count: None,
-                            base_array_layer: (light_index * 6 + face_index) as u32,
-                            array_layer_count: Some(1u32),
-                        });
-
+                let viewport = tile.face_viewport(face_index);
                 let view_light_entity = commands
                     .spawn((
                         ShadowView {
-                            depth_attachment: DepthAttachment::new(depth_texture_view, Some(0.0)),
+                            depth_attachment: DepthAttachment::new(
+                                shadow_atlas_texture_view.clone(),
+                                None,
+                            ),
                             pass_name: format!(
                                 "shadow pass point light {} {}",
                                 light_index,
                                 face_index_to_name(face_index)
                             ),
+                            viewport: Some(viewport),
                         },
                         ExtractedView {
-                            viewport: UVec4::new(
-                                0,
-                                0,
-                                point_light_shadow_map.size as u32,
-                                point_light_shadow_map.size as u32,
-                            ),
+                            viewport,
                             transform: view_translation * *view_rotation,
                             view_projection: None,
                             projection: cube_face_projection,
@@ -1070,62 +1462,6 @@ pub fn prepare_lights(
             }
         }
 
-        // spot lights
-        for (light_index, &(light_entity, light, (_, spot_light_frustum))) in point_lights
-            .iter()
-            .skip(point_light_count)
-            .take(spot_light_shadow_maps_count)
-            .enumerate()
-        {
-            let spot_view_matrix = spot_light_view_matrix(&light.transform);
-            let spot_view_transform = spot_view_matrix.into();
-
-            let angle = light.spot_light_angles.expect("lights should be sorted so that \
-                [point_light_count..point_light_count + spot_light_shadow_maps_count] are spot lights").1;
-            let spot_projection = spot_light_projection_matrix(angle);
-
-            let depth_texture_view =
-                directional_light_depth_texture
-                    .texture
-                    .create_view(&TextureViewDescriptor {
-                        label: Some("spot_light_shadow_map_texture_view"),
-                        format: None,
-                        dimension: Some(TextureViewDimension::D2),
-                        aspect: TextureAspect::All,
-                        base_mip_level: 0,
-                        mip_level_count: None,
-                        base_array_layer: (num_directional_cascades_enabled + light_index) as u32,
-                        array_layer_count: Some(1u32),
-                    });
-
-            let view_light_entity = commands
-                .spawn((
-                    ShadowView {
-                        depth_attachment: DepthAttachment::new(depth_texture_view, Some(0.0)),
-                        pass_name: format!("shadow pass spot light {light_index}"),
-                    },
-                    ExtractedView {
-                        viewport: UVec4::new(
-                            0,
-                            0,
-                            directional_light_shadow_map.size as u32,
-                            directional_light_shadow_map.size as u32,
-                        ),
-                        transform: spot_view_transform,
-                        projection: spot_projection,
-                        view_projection: None,
-                        hdr: false,
-                        color_grading: Default::default(),
-                    },
-                    *spot_light_frustum.unwrap(),
-                    BinnedRenderPhase::<Shadow>::default(),
-                    LightEntity::Spot { light_entity },
-                ))
-                .id();
-
-            view_lights.push(view_light_entity);
-        }
-
         // directional lights
         let mut directional_depth_texture_array_index = 0u32;
         for (light_index, &(light_entity, light)) in directional_lights
@@ -1183,6 +1519,7 @@ pub fn prepare_lights(
                             depth_attachment: DepthAttachment::new(depth_texture_view, Some(0.0)),
                             pass_name: format!(
                                 "shadow pass directional light {light_index} cascade {cascade_index}"),
+                            viewport: None,
                         },
                         ExtractedView {
                             viewport: UVec4::new(
@@ -1209,34 +1546,6 @@ pub fn prepare_lights(
             }
         }
 
-        let point_light_depth_texture_view =
-            point_light_depth_texture
-                .texture
-                .create_view(&TextureViewDescriptor {
-                    label: Some("point_light_shadow_map_array_texture_view"),
-                    format: None,
-                    // NOTE: iOS Simulator is missing CubeArray support so we use Cube instead.
-                    // See https://github.com/bevyengine/bevy/pull/12052 - remove if support is added.
-                    #[cfg(all(
-                        not(feature = "ios_simulator"),
-                        any(
-                            not(feature = "webgl"),
-                            not(target_arch = "wasm32"),
-                            feature = "webgpu"
-                        )
-                    ))]
-                    dimension: Some(TextureViewDimension::CubeArray),
-                    #[cfg(any(
-                        feature = "ios_simulator",
-                        all(feature = "webgl", target_arch = "wasm32", not(feature = "webgpu"))
-                    ))]
-                    dimension: Some(TextureViewDimension::Cube),
-                    aspect: TextureAspect::DepthOnly,
-                    base_mip_level: 0,
-                    mip_level_count: None,
-                    base_array_layer: 0,
-                    array_layer_count: None,
-                });
         let directional_light_depth_texture_view = directional_light_depth_texture
             .texture
             .create_view(&TextureViewDescriptor {
@@ -1259,8 +1568,8 @@ pub fn prepare_lights(
 
         commands.entity(entity).insert((
             ViewShadowBindings {
-                point_light_depth_texture: point_light_depth_texture.texture,
-                point_light_depth_texture_view,
+                shadow_atlas_texture: shadow_atlas_texture.clone(),
+                shadow_atlas_texture_view: shadow_atlas_texture_view.clone(),
                 directional_light_depth_texture: directional_light_depth_texture.texture,
                 directional_light_depth_texture_view,
             },
@@ -1271,6 +1580,12 @@ pub fn prepare_lights(
                 offset: view_gpu_lights_writer.write(&gpu_lights),
             },
         ));
+
+        if !global_light_meta.cookie_textures.is_empty()
+            || !global_light_meta.cookie_cubemaps.is_empty()
+        {
+            commands.entity(entity).insert(ViewLightCookies);
+        }
     }
 }
 
@@ -1609,6 +1924,7 @@ pub fn queue_shadows<M: Material>(
     mut pipelines: ResMut<SpecializedMeshPipelines<PrepassPipeline<M>>>,
     pipeline_cache: Res<PipelineCache>,
     render_lightmaps: Res<RenderLightmaps>,
+    shadow_atlas: Res<ShadowAtlas>,
     view_lights: Query<(Entity, &ViewLightEntities)>,
     mut view_light_shadow_phases: Query<(&LightEntity, &mut BinnedRenderPhase<Shadow>)>,
     point_light_entities: Query<&CubemapVisibleEntities, With<ExtractedPointLight>>,
@@ -1646,6 +1962,19 @@ pub fn queue_shadows<M: Material>(
                     .get(*light_entity)
                     .expect("Failed to get spot light visible entities"),
             };
+            // Shadow atlas tiles are kept across frames, so a tile missing a shadow caster that
+            // isn't ready yet must be rendered again.
+            let atlas_light_entity = match light_entity {
+                LightEntity::Point { light_entity, .. } | LightEntity::Spot { light_entity } => {
+                    Some(*light_entity)
+                }
+                LightEntity::Directional { .. } => None,
+            };
+            let invalidate_atlas_tile = || {
+                if let Some(atlas_light_entity) = atlas_light_entity {
+                    shadow_atlas.invalidate(atlas_light_entity);
+                }
+            };
             let mut light_key = MeshPipelineKey::DEPTH_PREPASS;
             light_key.set(MeshPipelineKey::DEPTH_CLAMP_ORTHO, is_directional_light);
 
@@ -1667,9 +1996,11 @@ pub fn queue_shadows<M: Material>(
                     continue;
                 };
                 let Some(material) = render_materials.get(*material_asset_id) else {
+                    invalidate_atlas_tile();
                     continue;
                 };
                 let Some(mesh) = render_meshes.get(mesh_instance.mesh_asset_id) else {
+                    invalidate_atlas_tile();
                     continue;
                 };
 
@@ -1808,7 +2139,11 @@ impl CachedRenderPipelinePhaseItem for Shadow {
 
 pub struct ShadowPassNode {
     main_view_query: QueryState<Read<ViewLightEntities>>,
-    view_light_query: QueryState<(Read<ShadowView>, Read<BinnedRenderPhase<Shadow>>)>,
+    view_light_query: QueryState<(
+        Read<ShadowView>,
+        Read<LightEntity>,
+        Read<BinnedRenderPhase<Shadow>>,
+    )>,
 }
 
 impl ShadowPassNode {
@@ -1835,14 +2170,39 @@ impl Node for ShadowPassNode {
         let diagnostics = render_context.diagnostic_recorder();
         let time_span = diagnostics.time_span(render_context.command_encoder(), "shadows");
 
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let shadow_atlas = world.resource::<ShadowAtlas>();
+        let clear_pipeline = pipeline_cache
+            .get_render_pipeline(world.resource::<ShadowAtlasClearPipeline>().pipeline_id);
+
         let view_entity = graph.view_entity();
         if let Ok(view_lights) = self.main_view_query.get_manual(world, view_entity) {
             for view_light_entity in view_lights.lights.iter().copied() {
-                let (view_light, shadow_phase) = self
+                let (view_light, light_entity, shadow_phase) = self
                     .view_light_query
                     .get_manual(world, view_light_entity)
                     .unwrap();
 
+                // Views rendering to a shadow atlas tile clear it first, and render it again
+                // next frame if any pipeline isn't ready yet.
+                let atlas_tile = view_light.viewport.inspect(|_| {
+                    let pipelines_ready = clear_pipeline.is_some()
+                        && shadow_phase
+                            .batchable_keys
+                            .iter()
+                            .chain(&shadow_phase.unbatchable_keys)
+                            .all(|key| pipeline_cache.get_render_pipeline(key.pipeline).is_some());
+                    if !pipelines_ready {
+                        match light_entity {
+                            LightEntity::Point { light_entity, .. }
+                            | LightEntity::Spot { light_entity } => {
+                                shadow_atlas.invalidate(*light_entity);
+                            }
+                            LightEntity::Directional { .. } => {}
+                        }
+                    }
+                });
+
                 let depth_stencil_attachment =
                     Some(view_light.depth_attachment.get_attachment(StoreOp::Store));
 
@@ -1867,6 +2227,23 @@ impl Node for ShadowPassNode {
                     let pass_span =
                         diagnostics.pass_span(&mut render_pass, view_light.pass_name.clone());
 
+                    if let Some(viewport) = atlas_tile {
+                        render_pass.set_viewport(
+                            viewport.x as f32,
+                            viewport.y as f32,
+                            viewport.z as f32,
+                            viewport.w as f32,
+                            0.0,
+                            1.0,
+                        );
+                        render_pass
+                            .set_scissor_rect(viewport.x, viewport.y, viewport.z, viewport.w);
+                        if let Some(clear_pipeline) = clear_pipeline {
+                            render_pass.set_render_pipeline(clear_pipeline);
+                            render_pass.draw(0..3, 0..1);
+                        }
+                    }
+
                     shadow_phase.render(&mut render_pass, world, view_light_entity);
 
                     pass_span.end(&mut render_pass);
@@ -4,6 +4,7 @@ use bevy_asset::{load_internal_asset, AssetId};
 use bevy_core_pipeline::{
     core_3d::{AlphaMask3d, Opaque3d, Transmissive3d, Transparent3d, CORE_3D_DEPTH_FORMAT},
     deferred::{AlphaMask3dDeferred, Opaque3dDeferred},
+    oit::{OitBuffers, ViewOitSettingsOffset},
 };
 use bevy_derive::{Deref, DerefMut};
 use bevy_ecs::entity::EntityHashMap;
@@ -42,6 +43,7 @@ use bevy_utils::{tracing::error, tracing::warn, Entry, HashMap, Parallel};
 
 use bytemuck::{Pod, Zeroable};
 use nonmax::{NonMaxU16, NonMaxU32};
+use smallvec::{smallvec, SmallVec};
 use static_assertions::const_assert_eq;
 
 use crate::render::{
@@ -75,6 +77,7 @@ pub const MESH_FUNCTIONS_HANDLE: Handle<Shader> = Handle::weak_from_u128(6300874
 pub const MESH_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(3252377289100772450);
 pub const SKINNING_HANDLE: Handle<Shader> = Handle::weak_from_u128(13215291596265391738);
 pub const MORPH_HANDLE: Handle<Shader> = Handle::weak_from_u128(970982813587607345);
+pub const OIT_DRAW_HANDLE: Handle<Shader> = Handle::weak_from_u128(5376241894532785410);
 
 /// How many textures are allowed in the view bind group layout (`@group(0)`) before
 /// broader compatibility with WebGL and WebGPU is at risk, due to the minimum guaranteed
@@ -122,6 +125,7 @@ impl Plugin for MeshRenderPlugin {
         load_internal_asset!(app, MESH_SHADER_HANDLE, "mesh.wgsl", Shader::from_wgsl);
         load_internal_asset!(app, SKINNING_HANDLE, "skinning.wgsl", Shader::from_wgsl);
         load_internal_asset!(app, MORPH_HANDLE, "morph.wgsl", Shader::from_wgsl);
+        load_internal_asset!(app, OIT_DRAW_HANDLE, "oit_draw.wgsl", Shader::from_wgsl);
 
         app.add_systems(
             PostUpdate,
@@ -359,6 +363,7 @@ bitflags::bitflags! {
         ///
         /// This will be `u16::MAX` if this mesh has no LOD.
         const LOD_INDEX_MASK              = (1 << 16) - 1;
+        const DECAL_RECEIVER              = 1 << 28;
         const SHADOW_RECEIVER             = 1 << 29;
         const TRANSMITTED_SHADOW_RECEIVER = 1 << 30;
         // Indicates the sign of the determinant of the 3x3 model matrix. If the sign is positive,
@@ -375,6 +380,7 @@ impl MeshFlags {
         lod_index: Option<NonMaxU16>,
         not_shadow_receiver: bool,
         transmitted_receiver: bool,
+        not_decal_receiver: bool,
     ) -> MeshFlags {
         let mut mesh_flags = if not_shadow_receiver {
             MeshFlags::empty()
@@ -384,6 +390,9 @@ impl MeshFlags {
         if transmitted_receiver {
             mesh_flags |= MeshFlags::TRANSMITTED_SHADOW_RECEIVER;
         }
+        if !not_decal_receiver {
+            mesh_flags |= MeshFlags::DECAL_RECEIVER;
+        }
         if transform.affine().matrix3.determinant().is_sign_positive() {
             mesh_flags |= MeshFlags::SIGN_DETERMINANT_MODEL_3X3;
         }
@@ -779,6 +788,7 @@ pub fn extract_meshes_for_cpu_building(
             &Handle<Mesh>,
             Has<NotShadowReceiver>,
             Has<TransmittedShadowReceiver>,
+            Has<NotDecalReceiver>,
             Has<NotShadowCaster>,
             Has<NoAutomaticBatching>,
             Has<VisibilityRange>,
@@ -796,6 +806,7 @@ pub fn extract_meshes_for_cpu_building(
             handle,
             not_shadow_receiver,
             transmitted_receiver,
+            not_decal_receiver,
             not_shadow_caster,
             no_automatic_batching,
             visibility_range,
@@ -814,6 +825,7 @@ pub fn extract_meshes_for_cpu_building(
                 lod_index,
                 not_shadow_receiver,
                 transmitted_receiver,
+                not_decal_receiver,
             );
 
             let shared = RenderMeshInstanceShared::from_components(
@@ -880,6 +892,7 @@ pub fn extract_meshes_for_gpu_building(
             &Handle<Mesh>,
             Has<NotShadowReceiver>,
             Has<TransmittedShadowReceiver>,
+            Has<NotDecalReceiver>,
             Has<NotShadowCaster>,
             Has<NoAutomaticBatching>,
             Has<VisibilityRange>,
@@ -914,6 +927,7 @@ pub fn extract_meshes_for_gpu_building(
             handle,
             not_shadow_receiver,
             transmitted_receiver,
+            not_decal_receiver,
             not_shadow_caster,
             no_automatic_batching,
             visibility_range,
@@ -932,6 +946,7 @@ pub fn extract_meshes_for_gpu_building(
                 lod_index,
                 not_shadow_receiver,
                 transmitted_receiver,
+                not_decal_receiver,
             );
 
             let shared = RenderMeshInstanceShared::from_components(
@@ -1057,6 +1072,13 @@ pub struct MeshPipeline {
     ///
     /// This affects whether reflection probes can be used.
     pub binding_arrays_are_usable: bool,
+
+    /// The layout of the bind group holding the order-independent transparency buffers, which
+    /// transparent pipelines with [`MeshPipelineKey::OIT_ENABLED`] use after the material bind
+    /// group.
+    ///
+    /// `None` when the device doesn't support order-independent transparency.
+    pub oit_layout: Option<BindGroupLayout>,
 }
 
 impl FromWorld for MeshPipeline {
@@ -1066,8 +1088,9 @@ impl FromWorld for MeshPipeline {
             Res<DefaultImageSampler>,
             Res<RenderQueue>,
             Res<MeshPipelineViewLayouts>,
+            Option<Res<OitBuffers>>,
         )> = SystemState::new(world);
-        let (render_device, default_sampler, render_queue, view_layouts) =
+        let (render_device, default_sampler, render_queue, view_layouts, oit_buffers) =
             system_state.get_mut(world);
         let clustered_forward_buffer_binding_type = render_device
             .get_supported_read_only_binding_type(CLUSTERED_FORWARD_STORAGE_BUFFER_COUNT);
@@ -1113,6 +1136,7 @@ impl FromWorld for MeshPipeline {
             mesh_layouts: MeshLayouts::new(&render_device),
             per_object_buffer_batch_size: GpuArrayBuffer::<MeshUniform>::batch_size(&render_device),
             binding_arrays_are_usable: binding_arrays_are_usable(&render_device),
+            oit_layout: oit_buffers.map(|oit_buffers| oit_buffers.bind_group_layout.clone()),
         }
     }
 }
@@ -1340,7 +1364,11 @@ bitflags::bitflags! {
         const LIGHTMAPPED                       = 1 << 13;
         const IRRADIANCE_VOLUME                 = 1 << 14;
         const VISIBILITY_RANGE_DITHER           = 1 << 15;
-        const LAST_FLAG                         = Self::VISIBILITY_RANGE_DITHER.bits();
+        const SCREEN_SPACE_REFLECTIONS          = 1 << 16;
+        const DECALS                            = 1 << 17;
+        const OIT_ENABLED                       = 1 << 18;
+        const LIGHT_COOKIES                     = 1 << 19;
+        const LAST_FLAG                         = Self::LIGHT_COOKIES.bits();
 
         // Bitfields
         const MSAA_RESERVED_BITS                = Self::MSAA_MASK_BITS << Self::MSAA_SHIFT_BITS;
@@ -1574,6 +1602,14 @@ impl SpecializedMeshPipeline for MeshPipeline {
             shader_defs.push("SCREEN_SPACE_AMBIENT_OCCLUSION".into());
         }
 
+        if key.contains(MeshPipelineKey::DECALS) {
+            shader_defs.push("DECALS".into());
+        }
+
+        if key.contains(MeshPipelineKey::LIGHT_COOKIES) {
+            shader_defs.push("LIGHT_COOKIES".into());
+        }
+
         let vertex_buffer_layout = layout.0.get_layout(&vertex_attributes)?;
 
         let (label, blend, depth_write_enabled);
@@ -1630,6 +1666,21 @@ impl SpecializedMeshPipeline for MeshPipeline {
             is_opaque = !key.contains(MeshPipelineKey::READS_VIEW_TRANSMISSION_TEXTURE);
         }
 
+        // Blended fragments are stored in the order-independent transparency buffers instead of
+        // the view target. The material bind group is inserted before theirs, at index 2.
+        if let Some(oit_layout) = self
+            .oit_layout
+            .as_ref()
+            .filter(|_| key.contains(MeshPipelineKey::OIT_ENABLED))
+            .filter(|_| {
+                pass == MeshPipelineKey::BLEND_ALPHA
+                    || pass == MeshPipelineKey::BLEND_PREMULTIPLIED_ALPHA
+            })
+        {
+            shader_defs.push("OIT_ENABLED".into());
+            bind_group_layout.push(oit_layout.clone());
+        }
+
         if key.contains(MeshPipelineKey::NORMAL_PREPASS) {
             shader_defs.push("NORMAL_PREPASS".into());
         }
@@ -1650,6 +1701,12 @@ impl SpecializedMeshPipeline for MeshPipeline {
             shader_defs.push("LOAD_PREPASS_NORMALS".into());
         }
 
+        // Screen space reflections are traced against the opaque depth and normal prepasses, so
+        // only opaque and alpha mask surfaces can use them.
+        if key.contains(MeshPipelineKey::SCREEN_SPACE_REFLECTIONS) && is_opaque {
+            shader_defs.push("SCREEN_SPACE_REFLECTIONS".into());
+        }
+
         let view_projection = key.intersection(MeshPipelineKey::VIEW_PROJECTION_RESERVED_BITS);
         if view_projection == MeshPipelineKey::VIEW_PROJECTION_NONSTANDARD {
             shader_defs.push("VIEW_PROJECTION_NONSTANDARD".into());
@@ -1921,6 +1978,7 @@ impl<P: PhaseItem, const I: usize> RenderCommand<P> for SetMeshViewBindGroup<I>
         Read<ViewLightsUniformOffset>,
         Read<ViewFogUniformOffset>,
         Read<ViewLightProbesUniformOffset>,
+        Option<Read<ViewScreenSpaceReflectionsUniformOffset>>,
         Read<MeshViewBindGroup>,
     );
     type ItemQuery = ();
@@ -1928,24 +1986,59 @@ impl<P: PhaseItem, const I: usize> RenderCommand<P> for SetMeshViewBindGroup<I>
     #[inline]
     fn render<'w>(
         _item: &P,
-        (view_uniform, view_lights, view_fog, view_light_probes, mesh_view_bind_group): ROQueryItem<
-            'w,
-            Self::ViewQuery,
-        >,
+        (
+            view_uniform,
+            view_lights,
+            view_fog,
+            view_light_probes,
+            view_ssr,
+            mesh_view_bind_group,
+        ): ROQueryItem<'w, Self::ViewQuery>,
         _entity: Option<()>,
         _: SystemParamItem<'w, '_, Self::Param>,
         pass: &mut TrackedRenderPass<'w>,
     ) -> RenderCommandResult {
-        pass.set_bind_group(
-            I,
-            &mesh_view_bind_group.value,
-            &[
-                view_uniform.offset,
-                view_lights.offset,
-                view_fog.offset,
-                **view_light_probes,
-            ],
-        );
+        let mut offsets: SmallVec<[u32; 5]> = smallvec![
+            view_uniform.offset,
+            view_lights.offset,
+            view_fog.offset,
+            **view_light_probes,
+        ];
+        if let Some(view_ssr) = view_ssr {
+            offsets.push(**view_ssr);
+        }
+        pass.set_bind_group(I, &mesh_view_bind_group.value, &offsets);
+
+        RenderCommandResult::Success
+    }
+}
+
+/// Sets the bind group holding the order-independent transparency buffers, for views with
+/// [`OrderIndependentTransparencySettings`](bevy_core_pipeline::oit::OrderIndependentTransparencySettings).
+pub struct SetOitBindGroup<const I: usize>;
+impl<P: PhaseItem, const I: usize> RenderCommand<P> for SetOitBindGroup<I> {
+    type Param = Option<SRes<OitBuffers>>;
+    type ViewQuery = Option<Read<ViewOitSettingsOffset>>;
+    type ItemQuery = ();
+
+    #[inline]
+    fn render<'w>(
+        _item: &P,
+        oit_settings_offset: ROQueryItem<'w, Self::ViewQuery>,
+        _entity: Option<()>,
+        oit_buffers: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let Some(oit_settings_offset) = oit_settings_offset else {
+            return RenderCommandResult::Success;
+        };
+        let Some(bind_group) =
+            oit_buffers.and_then(|oit_buffers| oit_buffers.into_inner().bind_group.as_ref())
+        else {
+            return RenderCommandResult::Failure;
+        };
+
+        pass.set_bind_group(I, bind_group, &[**oit_settings_offset]);
 
         RenderCommandResult::Success
     }
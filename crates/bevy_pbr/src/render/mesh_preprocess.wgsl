@@ -6,6 +6,10 @@
 // meshes for all views. As part of this process, the shader gathers each
 // mesh's transform on the previous frame and writes it into the `MeshUniform`
 // so that TAA works.
+//
+// With GPU culling, meshes outside the view frustum are skipped. With occlusion
+// culling, so are meshes whose bounding box, as it was on the previous frame,
+// lies entirely behind the depth pyramid built on the previous frame.
 
 #import bevy_pbr::mesh_types::Mesh
 #import bevy_render::maths
@@ -122,6 +126,83 @@ fn view_frustum_intersects_obb(
 }
 #endif
 
+#ifdef OCCLUSION_CULLING
+// The view-projection matrix that the depth pyramid was rendered with.
+struct DepthPyramidView {
+    view_proj: mat4x4<f32>,
+}
+
+// The hierarchical depth buffer built from the previous frame. Each texel holds
+// the farthest depth of the texels it covers.
+@group(0) @binding(7) var depth_pyramid: texture_2d<f32>;
+
+@group(0) @binding(8) var<uniform> depth_pyramid_view: DepthPyramidView;
+
+// Returns true if an axis-aligned bounding box (AABB), transformed by `model`,
+// is entirely hidden behind the contents of the depth pyramid.
+fn aabb_is_occluded(
+    model: mat4x4<f32>,
+    aabb_center: vec3<f32>,
+    aabb_half_extents: vec3<f32>,
+) -> bool {
+    // Meshes without an AABB have infinite extents and are never culled.
+    if (any(aabb_half_extents >= vec3(3.40282347e38))) {
+        return false;
+    }
+
+    // Project the corners of the box, and find the screen-space rectangle and
+    // the nearest depth that bound it.
+    var ndc_min = vec2(1.0);
+    var ndc_max = vec2(-1.0);
+    var nearest_depth = 0.0;
+    for (var i = 0u; i < 8u; i += 1u) {
+        let corner_sign = vec3<f32>(vec3(i & 1u, (i >> 1u) & 1u, (i >> 2u) & 1u)) * 2.0 - 1.0;
+        let corner = model * vec4(aabb_center + corner_sign * aabb_half_extents, 1.0);
+        let clip_position = depth_pyramid_view.view_proj * corner;
+
+        // A box that crosses the near plane can cover the whole screen, so
+        // treat it as visible.
+        if (clip_position.w <= 0.0) {
+            return false;
+        }
+
+        let ndc_position = clip_position.xyz / clip_position.w;
+        ndc_min = min(ndc_min, ndc_position.xy);
+        ndc_max = max(ndc_max, ndc_position.xy);
+        // Depth is reversed, so the nearest depth is the greatest.
+        nearest_depth = max(nearest_depth, ndc_position.z);
+    }
+
+    // Convert the rectangle to UVs, flipping Y, and clip it to the screen.
+    let uv_min = saturate(vec2(ndc_min.x, -ndc_max.y) * 0.5 + 0.5);
+    let uv_max = saturate(vec2(ndc_max.x, -ndc_min.y) * 0.5 + 0.5);
+
+    // Pick the mip level at which the rectangle spans at most two texels along
+    // each axis, so that four loads cover it.
+    let extent = (uv_max - uv_min) * vec2<f32>(textureDimensions(depth_pyramid, 0));
+    let mip_level = min(
+        u32(ceil(log2(max(max(extent.x, extent.y), 1.0)))),
+        textureNumLevels(depth_pyramid) - 1u,
+    );
+    let mip_size = textureDimensions(depth_pyramid, mip_level);
+    let texel_min = min(vec2<u32>(uv_min * vec2<f32>(mip_size)), mip_size - 1u);
+    let texel_max = min(vec2<u32>(uv_max * vec2<f32>(mip_size)), mip_size - 1u);
+
+    let occluder_depth = min(
+        min(
+            textureLoad(depth_pyramid, texel_min, mip_level).r,
+            textureLoad(depth_pyramid, vec2(texel_max.x, texel_min.y), mip_level).r,
+        ),
+        min(
+            textureLoad(depth_pyramid, vec2(texel_min.x, texel_max.y), mip_level).r,
+            textureLoad(depth_pyramid, texel_max, mip_level).r,
+        ),
+    );
+
+    return nearest_depth < occluder_depth;
+}
+#endif
+
 @compute
 @workgroup_size(64)
 fn main(@builtin(global_invocation_id) global_invocation_id: vec3<u32>) {
@@ -150,6 +231,24 @@ fn main(@builtin(global_invocation_id) global_invocation_id: vec3<u32>) {
     }
 #endif
 
+    // Look up the previous model matrix.
+    let previous_input_index = current_input[input_index].previous_input_index;
+    var previous_model: mat3x4<f32>;
+    if (previous_input_index == 0xffffffff) {
+        previous_model = model_affine_transpose;
+    } else {
+        previous_model = previous_input[previous_input_index].model;
+    }
+
+#ifdef OCCLUSION_CULLING
+    // The depth pyramid was built on the previous frame, so test the mesh where
+    // it was then.
+    if (aabb_is_occluded(
+            maths::affine3_to_square(previous_model), aabb_center, aabb_half_extents)) {
+        return;
+    }
+#endif
+
     // Calculate inverse transpose.
     let inverse_transpose_model = transpose(maths::inverse_affine3(transpose(
         model_affine_transpose)));
@@ -160,15 +259,6 @@ fn main(@builtin(global_invocation_id) global_invocation_id: vec3<u32>) {
         vec4<f32>(inverse_transpose_model[1].yz, inverse_transpose_model[2].xy));
     let inverse_transpose_model_b = inverse_transpose_model[2].z;
 
-    // Look up the previous model matrix.
-    let previous_input_index = current_input[input_index].previous_input_index;
-    var previous_model: mat3x4<f32>;
-    if (previous_input_index == 0xffffffff) {
-        previous_model = model_affine_transpose;
-    } else {
-        previous_model = previous_input[previous_input_index].model;
-    }
-
     // Figure out the output index. In indirect mode, this involves bumping the
     // instance index in the indirect parameters structure. Otherwise, this
     // index was directly supplied to us.
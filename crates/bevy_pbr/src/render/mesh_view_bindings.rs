@@ -11,6 +11,7 @@ use bevy_derive::{Deref, DerefMut};
 use bevy_ecs::{
     component::Component,
     entity::Entity,
+    query::Has,
     system::{Commands, Query, Res, Resource},
     world::{FromWorld, World},
 };
@@ -27,8 +28,6 @@ use bevy_render::{
     },
 };
 
-#[cfg(all(feature = "webgl", target_arch = "wasm32", not(feature = "webgpu")))]
-use bevy_render::render_resource::binding_types::texture_cube;
 #[cfg(debug_assertions)]
 use bevy_utils::warn_once;
 use environment_map::EnvironmentMapLight;
@@ -37,14 +36,17 @@ use environment_map::EnvironmentMapLight;
 use crate::MESH_PIPELINE_VIEW_LAYOUT_SAFE_MAX_TEXTURES;
 use crate::{
     environment_map::{self, RenderViewEnvironmentMapBindGroupEntries},
+    get_light_cookie_bind_group_layout_entries,
     irradiance_volume::{
         self, IrradianceVolume, RenderViewIrradianceVolumeBindGroupEntries,
         IRRADIANCE_VOLUMES_ARE_USABLE,
     },
     prepass, FogMeta, GlobalLightMeta, GpuFog, GpuLights, GpuPointLights, LightMeta,
-    LightProbesBuffer, LightProbesUniform, MeshPipeline, MeshPipelineKey, RenderViewLightProbes,
-    ScreenSpaceAmbientOcclusionTextures, ShadowSamplers, ViewClusterBindings, ViewShadowBindings,
-    CLUSTERED_FORWARD_STORAGE_BUFFER_COUNT,
+    LightProbesBuffer, LightProbesUniform, MeshPipeline, MeshPipelineKey,
+    RenderLightCookieBindGroupEntries, RenderViewLightProbes, ScreenSpaceAmbientOcclusionTextures,
+    ScreenSpaceReflectionsBuffer, ScreenSpaceReflectionsSettings, ScreenSpaceReflectionsTextures,
+    ScreenSpaceReflectionsUniform, ShadowSamplers, ViewClusterBindings, ViewDecalTextures,
+    ViewDecals, ViewLightCookies, ViewShadowBindings, CLUSTERED_FORWARD_STORAGE_BUFFER_COUNT,
 };
 
 #[derive(Clone)]
@@ -69,6 +71,9 @@ bitflags::bitflags! {
         const NORMAL_PREPASS              = 1 << 2;
         const MOTION_VECTOR_PREPASS       = 1 << 3;
         const DEFERRED_PREPASS            = 1 << 4;
+        const SCREEN_SPACE_REFLECTIONS    = 1 << 5;
+        const DECALS                      = 1 << 6;
+        const LIGHT_COOKIES               = 1 << 7;
     }
 }
 
@@ -81,7 +86,7 @@ impl MeshPipelineViewLayoutKey {
         use MeshPipelineViewLayoutKey as Key;
 
         format!(
-            "mesh_view_layout{}{}{}{}{}",
+            "mesh_view_layout{}{}{}{}{}{}{}{}",
             self.contains(Key::MULTISAMPLED)
                 .then_some("_multisampled")
                 .unwrap_or_default(),
@@ -97,6 +102,15 @@ impl MeshPipelineViewLayoutKey {
             self.contains(Key::DEFERRED_PREPASS)
                 .then_some("_deferred")
                 .unwrap_or_default(),
+            self.contains(Key::SCREEN_SPACE_REFLECTIONS)
+                .then_some("_ssr")
+                .unwrap_or_default(),
+            self.contains(Key::DECALS)
+                .then_some("_decals")
+                .unwrap_or_default(),
+            self.contains(Key::LIGHT_COOKIES)
+                .then_some("_cookies")
+                .unwrap_or_default(),
         )
     }
 }
@@ -120,6 +134,15 @@ impl From<MeshPipelineKey> for MeshPipelineViewLayoutKey {
         if value.contains(MeshPipelineKey::DEFERRED_PREPASS) {
             result |= MeshPipelineViewLayoutKey::DEFERRED_PREPASS;
         }
+        if value.contains(MeshPipelineKey::SCREEN_SPACE_REFLECTIONS) {
+            result |= MeshPipelineViewLayoutKey::SCREEN_SPACE_REFLECTIONS;
+        }
+        if value.contains(MeshPipelineKey::DECALS) {
+            result |= MeshPipelineViewLayoutKey::DECALS;
+        }
+        if value.contains(MeshPipelineKey::LIGHT_COOKIES) {
+            result |= MeshPipelineViewLayoutKey::LIGHT_COOKIES;
+        }
 
         result
     }
@@ -194,25 +217,9 @@ fn layout_entries(
             ),
             // Lights
             (1, uniform_buffer::<GpuLights>(true)),
-            // Point Shadow Texture Cube Array
-            (
-                2,
-                #[cfg(all(
-                    not(feature = "ios_simulator"),
-                    any(
-                        not(feature = "webgl"),
-                        not(target_arch = "wasm32"),
-                        feature = "webgpu"
-                    )
-                ))]
-                texture_cube_array(TextureSampleType::Depth),
-                #[cfg(any(
-                    feature = "ios_simulator",
-                    all(feature = "webgl", target_arch = "wasm32", not(feature = "webgpu"))
-                ))]
-                texture_cube(TextureSampleType::Depth),
-            ),
-            // Point Shadow Texture Array Sampler
+            // Point and Spot Shadow Atlas
+            (2, texture_depth_2d()),
+            // Shadow Atlas Sampler
             (3, sampler(SamplerBindingType::Comparison)),
             // Directional Shadow Texture Array
             (
@@ -337,6 +344,45 @@ fn layout_entries(
         (26, sampler(SamplerBindingType::Filtering)),
     ));
 
+    // Screen space reflections
+    if layout_key.contains(MeshPipelineViewLayoutKey::SCREEN_SPACE_REFLECTIONS) {
+        entries = entries.extend_with_indices((
+            (
+                27,
+                texture_2d(TextureSampleType::Float { filterable: false }),
+            ),
+            (28, uniform_buffer::<ScreenSpaceReflectionsUniform>(true)),
+        ));
+    }
+
+    // Decal buffer
+    if layout_key.contains(MeshPipelineViewLayoutKey::DECALS) {
+        entries = entries.extend_with_indices((
+            (
+                29,
+                texture_2d(TextureSampleType::Float { filterable: false }),
+            ),
+            (
+                30,
+                texture_2d(TextureSampleType::Float { filterable: false }),
+            ),
+            (
+                31,
+                texture_2d(TextureSampleType::Float { filterable: false }),
+            ),
+        ));
+    }
+
+    // Light cookies
+    if layout_key.contains(MeshPipelineViewLayoutKey::LIGHT_COOKIES) {
+        let light_cookie_entries = get_light_cookie_bind_group_layout_entries(render_device);
+        entries = entries.extend_with_indices((
+            (32, light_cookie_entries[0]),
+            (33, light_cookie_entries[1]),
+            (34, light_cookie_entries[2]),
+        ));
+    }
+
     entries.to_vec()
 }
 
@@ -445,12 +491,17 @@ pub fn prepare_mesh_view_bind_groups(
     light_meta: Res<LightMeta>,
     global_light_meta: Res<GlobalLightMeta>,
     fog_meta: Res<FogMeta>,
+    ssr_buffer: Option<Res<ScreenSpaceReflectionsBuffer>>,
     view_uniforms: Res<ViewUniforms>,
     views: Query<(
         Entity,
         &ViewShadowBindings,
         &ViewClusterBindings,
         Option<&ScreenSpaceAmbientOcclusionTextures>,
+        Option<&ScreenSpaceReflectionsTextures>,
+        Has<ScreenSpaceReflectionsSettings>,
+        (Has<ViewDecals>, Option<&ViewDecalTextures>),
+        Has<ViewLightCookies>,
         Option<&ViewPrepassTextures>,
         Option<&ViewTransmissionTexture>,
         &Tonemapping,
@@ -486,11 +537,19 @@ pub fn prepare_mesh_view_bind_groups(
         light_probes_buffer.binding(),
         visibility_ranges.buffer().buffer(),
     ) {
+        let ssr_binding = ssr_buffer
+            .as_ref()
+            .and_then(|ssr_buffer| ssr_buffer.binding());
+
         for (
             entity,
             shadow_bindings,
             cluster_bindings,
             ssao_textures,
+            ssr_textures,
+            ssr,
+            (decals, decal_textures),
+            light_cookies,
             prepass_textures,
             transmission_texture,
             tonemapping,
@@ -506,16 +565,25 @@ pub fn prepare_mesh_view_bind_groups(
                 .map(|t| &t.screen_space_ambient_occlusion_texture.default_view)
                 .unwrap_or(&fallback_ssao);
 
-            let layout = &mesh_pipeline.get_view_layout(
-                MeshPipelineViewLayoutKey::from(*msaa)
-                    | MeshPipelineViewLayoutKey::from(prepass_textures),
-            );
+            let mut layout_key = MeshPipelineViewLayoutKey::from(*msaa)
+                | MeshPipelineViewLayoutKey::from(prepass_textures);
+            let ssr_binding = ssr_binding.as_ref().filter(|_| ssr);
+            if ssr_binding.is_some() {
+                layout_key |= MeshPipelineViewLayoutKey::SCREEN_SPACE_REFLECTIONS;
+            }
+            if decals {
+                layout_key |= MeshPipelineViewLayoutKey::DECALS;
+            }
+            if light_cookies {
+                layout_key |= MeshPipelineViewLayoutKey::LIGHT_COOKIES;
+            }
+            let layout = &mesh_pipeline.get_view_layout(layout_key);
 
             let mut entries = DynamicBindGroupEntries::new_with_indices((
                 (0, view_binding.clone()),
                 (1, light_binding.clone()),
-                (2, &shadow_bindings.point_light_depth_texture_view),
-                (3, &shadow_samplers.point_light_sampler),
+                (2, &shadow_bindings.shadow_atlas_texture_view),
+                (3, &shadow_samplers.shadow_atlas_sampler),
                 (4, &shadow_bindings.directional_light_depth_texture_view),
                 (5, &shadow_samplers.directional_light_sampler),
                 (6, point_light_binding.clone()),
@@ -618,6 +686,75 @@ pub fn prepare_mesh_view_bind_groups(
             entries =
                 entries.extend_with_indices(((25, transmission_view), (26, transmission_sampler)));
 
+            if let Some(ssr_binding) = ssr_binding {
+                // The zero fallback image has an alpha of zero, which means that no reflection was found.
+                let ssr_view = ssr_textures
+                    .map(|t| &t.screen_space_reflections_texture.default_view)
+                    .unwrap_or(&fallback_image_zero.texture_view);
+
+                entries = entries.extend_with_indices(((27, ssr_view), (28, ssr_binding.clone())));
+            }
+
+            if decals {
+                // Likewise, a decal buffer with an alpha of zero leaves the surface untouched.
+                let (decal_base_color_view, decal_normal_view, decal_roughness_view) =
+                    match decal_textures {
+                        Some(decal_textures) => (
+                            &decal_textures.base_color.default_view,
+                            &decal_textures.normal.default_view,
+                            &decal_textures.roughness.default_view,
+                        ),
+                        None => (
+                            &fallback_image_zero.texture_view,
+                            &fallback_image_zero.texture_view,
+                            &fallback_image_zero.texture_view,
+                        ),
+                    };
+
+                entries = entries.extend_with_indices((
+                    (29, decal_base_color_view),
+                    (30, decal_normal_view),
+                    (31, decal_roughness_view),
+                ));
+            }
+
+            let light_cookie_bind_group_entries = if light_cookies {
+                Some(RenderLightCookieBindGroupEntries::get(
+                    &global_light_meta,
+                    &images,
+                    &fallback_image,
+                    &render_device,
+                ))
+            } else {
+                None
+            };
+
+            match light_cookie_bind_group_entries {
+                Some(RenderLightCookieBindGroupEntries::Single {
+                    texture_view,
+                    cubemap_view,
+                    sampler,
+                }) => {
+                    entries = entries.extend_with_indices((
+                        (32, texture_view),
+                        (33, cubemap_view),
+                        (34, sampler),
+                    ));
+                }
+                Some(RenderLightCookieBindGroupEntries::Multiple {
+                    ref texture_views,
+                    ref cubemap_views,
+                    sampler,
+                }) => {
+                    entries = entries.extend_with_indices((
+                        (32, texture_views.as_slice()),
+                        (33, cubemap_views.as_slice()),
+                        (34, sampler),
+                    ));
+                }
+                None => {}
+            }
+
             commands.entity(entity).insert(MeshViewBindGroup {
                 value: render_device.create_bind_group("mesh_view_bind_group", layout, &entries),
             });
@@ -8,12 +8,8 @@
 
 @group(0) @binding(0) var<uniform> view: View;
 @group(0) @binding(1) var<uniform> lights: types::Lights;
-#ifdef NO_CUBE_ARRAY_TEXTURES_SUPPORT
-@group(0) @binding(2) var point_shadow_textures: texture_depth_cube;
-#else
-@group(0) @binding(2) var point_shadow_textures: texture_depth_cube_array;
-#endif
-@group(0) @binding(3) var point_shadow_textures_sampler: sampler_comparison;
+@group(0) @binding(2) var shadow_atlas_texture: texture_depth_2d;
+@group(0) @binding(3) var shadow_atlas_sampler: sampler_comparison;
 #ifdef NO_ARRAY_TEXTURES_SUPPORT
 @group(0) @binding(4) var directional_shadow_textures: texture_depth_2d;
 #else
@@ -97,3 +93,25 @@ const VISIBILITY_RANGE_UNIFORM_BUFFER_SIZE: u32 = 64u;
 
 @group(0) @binding(25) var view_transmission_texture: texture_2d<f32>;
 @group(0) @binding(26) var view_transmission_sampler: sampler;
+
+#ifdef SCREEN_SPACE_REFLECTIONS
+@group(0) @binding(27) var screen_space_reflections_texture: texture_2d<f32>;
+@group(0) @binding(28) var<uniform> screen_space_reflections_settings: types::ScreenSpaceReflectionsSettings;
+#endif // SCREEN_SPACE_REFLECTIONS
+
+#ifdef DECALS
+@group(0) @binding(29) var decal_base_color_texture: texture_2d<f32>;
+@group(0) @binding(30) var decal_normal_texture: texture_2d<f32>;
+@group(0) @binding(31) var decal_roughness_texture: texture_2d<f32>;
+#endif // DECALS
+
+#ifdef LIGHT_COOKIES
+#ifdef MULTIPLE_LIGHT_PROBES_IN_ARRAY
+@group(0) @binding(32) var light_cookie_textures: binding_array<texture_2d<f32>, 8u>;
+@group(0) @binding(33) var light_cookie_cubemaps: binding_array<texture_cube<f32>, 8u>;
+#else
+@group(0) @binding(32) var light_cookie_texture: texture_2d<f32>;
+@group(0) @binding(33) var light_cookie_cubemap: texture_cube<f32>;
+#endif
+@group(0) @binding(34) var light_cookie_sampler: sampler;
+#endif
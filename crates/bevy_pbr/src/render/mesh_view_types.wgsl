@@ -15,6 +15,10 @@ struct PointLight {
 
 const POINT_LIGHT_FLAGS_SHADOWS_ENABLED_BIT: u32   = 1u;
 const POINT_LIGHT_FLAGS_SPOT_LIGHT_Y_NEGATIVE: u32 = 2u;
+const POINT_LIGHT_FLAGS_VOLUMETRIC_BIT: u32        = 4u;
+const POINT_LIGHT_FLAGS_COOKIE_CUBEMAP_BIT: u32    = 8u;
+const POINT_LIGHT_FLAGS_COOKIE_TEXTURE_BIT: u32    = 16u;
+const POINT_LIGHT_FLAGS_SPOT_LIGHT_BIT: u32         = 32u;
 
 struct DirectionalCascade {
     view_projection: mat4x4<f32>,
@@ -34,9 +38,17 @@ struct DirectionalLight {
     cascades_overlap_proportion: f32,
     depth_texture_base_index: u32,
     render_layers: u32,
+    // Maps world space positions to the light's local space, in which the cookie is tiled.
+    cookie_from_world: mat4x4<f32>,
 };
 
 const DIRECTIONAL_LIGHT_FLAGS_SHADOWS_ENABLED_BIT: u32 = 1u;
+const DIRECTIONAL_LIGHT_FLAGS_VOLUMETRIC_BIT: u32      = 2u;
+const DIRECTIONAL_LIGHT_FLAGS_COOKIE_TEXTURE_BIT: u32  = 4u;
+
+// The index of a light's cookie in the cookie binding arrays is stored in the
+// upper bits of its flags.
+const LIGHT_COOKIE_INDEX_SHIFT: u32 = 16u;
 
 struct Lights {
     // NOTE: this array size must be kept in sync with the constants defined in bevy_pbr/src/render/light.rs
@@ -59,6 +71,9 @@ struct Lights {
     spot_light_shadowmap_offset: i32,
     environment_map_smallest_specular_mip_level: u32,
     environment_map_intensity: f32,
+    // The shadow atlas tiles of point and spot lights, four per element. Each is packed as x in
+    // bits 0-13, y in bits 14-27 and log2 of the size in bits 28-31.
+    shadow_atlas_tiles: array<vec4<u32>, 64u>,
 };
 
 struct Fog {
@@ -134,3 +149,12 @@ struct LightProbes {
     // The intensity of the environment map associated with the view.
     intensity_for_view: f32,
 };
+
+struct ScreenSpaceReflectionsSettings {
+    // Transforms world space positions into the clip space of the previous frame.
+    previous_view_proj: mat4x4<f32>,
+    perceptual_roughness_threshold: f32,
+    thickness: f32,
+    max_steps: u32,
+    edge_fade: f32,
+};
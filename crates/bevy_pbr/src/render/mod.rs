@@ -1,3 +1,4 @@
+mod depth_pyramid;
 mod fog;
 mod gpu_preprocess;
 mod light;
@@ -5,12 +6,15 @@ pub(crate) mod mesh;
 mod mesh_bindings;
 mod mesh_view_bindings;
 mod morph;
+mod shadow_atlas;
 mod skin;
 
+pub use depth_pyramid::*;
 pub use fog::*;
 pub use gpu_preprocess::*;
 pub use light::*;
 pub use mesh::*;
 pub use mesh_bindings::MeshLayouts;
 pub use mesh_view_bindings::*;
+pub use shadow_atlas::*;
 pub use skin::{extract_skins, prepare_skins, SkinIndex, SkinUniform, MAX_JOINTS};
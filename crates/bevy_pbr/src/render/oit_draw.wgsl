@@ -0,0 +1,24 @@
+#define_import_path bevy_pbr::oit_draw
+
+#import bevy_core_pipeline::oit::{
+    OrderIndependentTransparencySettings, oit_pixel_index, pack_oit_fragment,
+}
+
+@group(3) @binding(0) var<storage, read_write> oit_layers: array<vec2<u32>>;
+@group(3) @binding(1) var<storage, read_write> oit_layer_ids: array<atomic<u32>>;
+@group(3) @binding(2) var<uniform> oit_settings: OrderIndependentTransparencySettings;
+
+// Stores a transparent fragment in the order-independent transparency buffers, so that the
+// resolve pass can blend it in depth order with the other fragments of its pixel.
+//
+// `color` must be premultiplied by its alpha. Fragments past the layer count of the view are
+// dropped.
+fn oit_draw(position: vec4<f32>, color: vec4<f32>) {
+    let pixel_index = oit_pixel_index(position.xy, oit_settings);
+    let layer = atomicAdd(&oit_layer_ids[pixel_index], 1u);
+    if (layer >= oit_settings.layer_count) {
+        return;
+    }
+
+    oit_layers[pixel_index + layer * oit_settings.layer_stride] = pack_oit_fragment(color, position.z);
+}
@@ -21,6 +21,10 @@
 #import bevy_pbr::meshlet_visibility_buffer_resolve::resolve_vertex_output
 #endif
 
+#ifdef OIT_ENABLED
+#import bevy_pbr::oit_draw::oit_draw
+#endif
+
 @fragment
 fn fragment(
 #ifdef MESHLET_MESH_MATERIAL_PASS
@@ -63,6 +67,17 @@ fn fragment(
     // apply in-shader post processing (fog, alpha-premultiply, and also tonemapping, debanding if the camera is non-hdr)
     // note this does not include fullscreen postprocessing effects like bloom.
     out.color = main_pass_post_lighting_processing(pbr_input, out.color);
+
+#ifdef OIT_ENABLED
+    // The fragment is only blended with the view target in the resolve pass, once all the
+    // transparent fragments of its pixel are known.
+#ifdef BLEND_PREMULTIPLIED_ALPHA
+    oit_draw(in.position, out.color);
+#else
+    oit_draw(in.position, vec4(out.color.rgb * out.color.a, out.color.a));
+#endif
+    discard;
+#endif
 #endif
 
     return out;
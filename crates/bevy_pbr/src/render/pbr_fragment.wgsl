@@ -18,6 +18,10 @@
 #import bevy_pbr::gtao_utils::gtao_multibounce
 #endif
 
+#ifdef DECALS
+#import bevy_pbr::decal_buffer::apply_decals
+#endif
+
 #ifdef MESHLET_MESH_MATERIAL_PASS
 #import bevy_pbr::meshlet_visibility_buffer_resolve::VertexOutput
 #else ifdef PREPASS_PIPELINE
@@ -430,5 +434,9 @@ fn pbr_input_from_standard_material(
 #endif
     }
 
+#ifdef DECALS
+    apply_decals(&pbr_input);
+#endif
+
     return pbr_input;
 }
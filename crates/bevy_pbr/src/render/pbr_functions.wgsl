@@ -28,6 +28,10 @@
 #import bevy_pbr::environment_map
 #endif
 
+#ifdef SCREEN_SPACE_REFLECTIONS
+#import bevy_pbr::ssr
+#endif
+
 #import bevy_core_pipeline::tonemapping::{screen_space_dither, powsafe, tone_mapping}
 
 // Biasing info needed to sample from a texture when calling `sample_texture`.
@@ -485,9 +489,21 @@ fn apply_pbr_lighting(
     // Note that up until this point, we have only accumulated diffuse light.
     // This call is the first call that can accumulate specular light.
 #ifdef ENVIRONMENT_MAP
-    let environment_light =
+    var environment_light =
         environment_map::environment_map_light(&lighting_input, any(indirect_light != vec3(0.0f)));
 
+#ifdef SCREEN_SPACE_REFLECTIONS
+    // Where the screen space reflections found a surface on screen, use it instead of the light
+    // probe, which remains as the fallback for rays that missed.
+    let screen_space_reflection =
+        ssr::screen_space_reflections_light(&lighting_input, in.frag_coord.xy);
+    environment_light.specular = mix(
+        environment_light.specular,
+        screen_space_reflection.rgb,
+        screen_space_reflection.a
+    );
+#endif  // SCREEN_SPACE_REFLECTIONS
+
     indirect_light += environment_light.diffuse * diffuse_occlusion +
         environment_light.specular * specular_occlusion;
 
@@ -549,6 +565,13 @@ fn apply_pbr_lighting(
     // If there's no environment map light, there's no transmitted environment
     // light specular component, so we can just hardcode it to zero.
     let specular_transmitted_environment_light = vec3<f32>(0.0);
+
+#ifdef SCREEN_SPACE_REFLECTIONS
+    // Without a light probe to fall back to, rays that missed simply don't reflect anything.
+    let screen_space_reflection =
+        ssr::screen_space_reflections_light(&lighting_input, in.frag_coord.xy);
+    indirect_light += screen_space_reflection.rgb * screen_space_reflection.a * specular_occlusion;
+#endif  // SCREEN_SPACE_REFLECTIONS
 #endif
 
     // Ambient light (indirect)
@@ -1,7 +1,11 @@
 #define_import_path bevy_pbr::lighting
 
 #import bevy_pbr::{
-    mesh_view_types::POINT_LIGHT_FLAGS_SPOT_LIGHT_Y_NEGATIVE,
+    mesh_view_types::{
+        POINT_LIGHT_FLAGS_SPOT_LIGHT_Y_NEGATIVE, POINT_LIGHT_FLAGS_COOKIE_CUBEMAP_BIT,
+        POINT_LIGHT_FLAGS_COOKIE_TEXTURE_BIT, DIRECTIONAL_LIGHT_FLAGS_COOKIE_TEXTURE_BIT,
+        LIGHT_COOKIE_INDEX_SHIFT,
+    },
     mesh_view_bindings as view_bindings,
 }
 #import bevy_render::maths::PI
@@ -334,6 +338,103 @@ fn perceptualRoughnessToRoughness(perceptualRoughness: f32) -> f32 {
     return clampedPerceptualRoughness * clampedPerceptualRoughness;
 }
 
+#ifdef LIGHT_COOKIES
+// Cookies are sampled in non-uniform control flow, so only the base mip level
+// can be used.
+fn sample_light_cookie_texture(cookie_index: u32, uv: vec2<f32>) -> vec3<f32> {
+#ifdef MULTIPLE_LIGHT_PROBES_IN_ARRAY
+    return textureSampleLevel(
+        view_bindings::light_cookie_textures[cookie_index],
+        view_bindings::light_cookie_sampler,
+        uv,
+        0.0
+    ).rgb;
+#else   // MULTIPLE_LIGHT_PROBES_IN_ARRAY
+    return textureSampleLevel(
+        view_bindings::light_cookie_texture,
+        view_bindings::light_cookie_sampler,
+        uv,
+        0.0
+    ).rgb;
+#endif  // MULTIPLE_LIGHT_PROBES_IN_ARRAY
+}
+
+fn sample_light_cookie_cubemap(cookie_index: u32, direction: vec3<f32>) -> vec3<f32> {
+#ifdef MULTIPLE_LIGHT_PROBES_IN_ARRAY
+    return textureSampleLevel(
+        view_bindings::light_cookie_cubemaps[cookie_index],
+        view_bindings::light_cookie_sampler,
+        direction,
+        0.0
+    ).rgb;
+#else   // MULTIPLE_LIGHT_PROBES_IN_ARRAY
+    return textureSampleLevel(
+        view_bindings::light_cookie_cubemap,
+        view_bindings::light_cookie_sampler,
+        direction,
+        0.0
+    ).rgb;
+#endif  // MULTIPLE_LIGHT_PROBES_IN_ARRAY
+}
+#endif  // LIGHT_COOKIES
+
+// Returns the color of the point light's cookie cubemap in the direction of
+// the fragment, or white if the light has none.
+fn point_light_cookie(light_id: u32, light_to_frag: vec3<f32>) -> vec3<f32> {
+#ifdef LIGHT_COOKIES
+    let flags = view_bindings::point_lights.data[light_id].flags;
+    if ((flags & POINT_LIGHT_FLAGS_COOKIE_CUBEMAP_BIT) != 0u) {
+        return sample_light_cookie_cubemap(flags >> LIGHT_COOKIE_INDEX_SHIFT, -light_to_frag);
+    }
+#endif  // LIGHT_COOKIES
+    return vec3(1.0);
+}
+
+// Returns the color of the spot light's cookie texture at the fragment, or
+// white if the light has none.
+fn spot_light_cookie(light_id: u32, spot_dir: vec3<f32>, light_to_frag: vec3<f32>) -> vec3<f32> {
+#ifdef LIGHT_COOKIES
+    let light = &view_bindings::point_lights.data[light_id];
+    if (((*light).flags & POINT_LIGHT_FLAGS_COOKIE_TEXTURE_BIT) == 0u) {
+        return vec3(1.0);
+    }
+
+    // Project the fragment the same way as `fetch_spot_shadow` does, so that
+    // the cookie lines up with the shadow map.
+    let fwd = -spot_dir;
+    var sign = -1.0;
+    if (fwd.z >= 0.0) {
+        sign = 1.0;
+    }
+    let a = -1.0 / (fwd.z + sign);
+    let b = fwd.x * fwd.y * a;
+    let up_dir = vec3<f32>(1.0 + sign * fwd.x * fwd.x * a, sign * b, -sign * fwd.x);
+    let right_dir = vec3<f32>(-b, -sign - fwd.y * fwd.y * a, fwd.y);
+    let light_inv_rot = mat3x3<f32>(right_dir, up_dir, fwd);
+
+    let projected_position = -light_to_frag * light_inv_rot;
+    let xy_ndc = projected_position.xy / ((*light).spot_light_tan_angle * -projected_position.z);
+    let uv = xy_ndc * vec2<f32>(0.5, -0.5) + vec2<f32>(0.5, 0.5);
+    return sample_light_cookie_texture((*light).flags >> LIGHT_COOKIE_INDEX_SHIFT, uv);
+#else   // LIGHT_COOKIES
+    return vec3(1.0);
+#endif  // LIGHT_COOKIES
+}
+
+// Returns the color of the directional light's cookie texture, tiled across
+// the light's local X/Y plane, or white if the light has none.
+fn directional_light_cookie(light_id: u32, world_position: vec3<f32>) -> vec3<f32> {
+#ifdef LIGHT_COOKIES
+    let light = &view_bindings::lights.directional_lights[light_id];
+    if (((*light).flags & DIRECTIONAL_LIGHT_FLAGS_COOKIE_TEXTURE_BIT) != 0u) {
+        let light_position = (*light).cookie_from_world * vec4(world_position, 1.0);
+        let uv = fract(light_position.xy * vec2(1.0, -1.0) + 0.5);
+        return sample_light_cookie_texture((*light).flags >> LIGHT_COOKIE_INDEX_SHIFT, uv);
+    }
+#endif  // LIGHT_COOKIES
+    return vec3(1.0);
+}
+
 fn point_light(light_id: u32, input: ptr<function, LightingInput>) -> vec3<f32> {
     // Unpack.
     let diffuse_color = (*input).diffuse_color;
@@ -422,7 +523,7 @@ fn point_light(light_id: u32, input: ptr<function, LightingInput>) -> vec3<f32>
 #endif  // STANDARD_MATERIAL_CLEARCOAT
 
     return color * (*light).color_inverse_square_range.rgb *
-        (rangeAttenuation * derived_input.NdotL);
+        point_light_cookie(light_id, light_to_frag) * (rangeAttenuation * derived_input.NdotL);
 }
 
 fn spot_light(light_id: u32, input: ptr<function, LightingInput>) -> vec3<f32> {
@@ -446,7 +547,7 @@ fn spot_light(light_id: u32, input: ptr<function, LightingInput>) -> vec3<f32> {
     let attenuation = saturate(cd * (*light).light_custom_data.z + (*light).light_custom_data.w);
     let spot_attenuation = attenuation * attenuation;
 
-    return point_light * spot_attenuation;
+    return point_light * spot_attenuation * spot_light_cookie(light_id, spot_dir, light_to_frag);
 }
 
 fn directional_light(light_id: u32, input: ptr<function, LightingInput>) -> vec3<f32> {
@@ -492,5 +593,5 @@ fn directional_light(light_id: u32, input: ptr<function, LightingInput>) -> vec3
     color = (diffuse + specular_light) * derived_input.NdotL;
 #endif  // STANDARD_MATERIAL_CLEARCOAT
 
-    return color * (*light).color.rgb;
+    return color * (*light).color.rgb * directional_light_cookie(light_id, (*input).P);
 }
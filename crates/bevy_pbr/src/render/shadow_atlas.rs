@@ -0,0 +1,660 @@
+use std::sync::Mutex;
+
+use bevy_asset::{AssetEvent, Handle};
+use bevy_core_pipeline::{
+    core_3d::CORE_3D_DEPTH_FORMAT, fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+};
+use bevy_ecs::{
+    entity::{EntityHashMap, EntityHashSet},
+    prelude::*,
+};
+use bevy_math::{Mat4, UVec2, UVec4, Vec3, Vec3A, Vec4Swizzles};
+use bevy_render::{
+    mesh::{morph::MeshMorphWeights, skinning::SkinnedMesh, Mesh},
+    primitives::{Aabb, Sphere},
+    render_resource::*,
+    renderer::RenderDevice,
+    view::{ExtractedView, InheritedVisibility},
+    Extract,
+};
+use bevy_transform::components::GlobalTransform;
+use bevy_utils::warn_once;
+
+#[cfg(feature = "meshlet")]
+use crate::meshlet::MeshletMesh;
+use crate::{NotShadowCaster, ShadowAtlasSettings};
+
+/// The maximum number of point and spot lights that can have a tile in the [`ShadowAtlas`].
+///
+/// NOTE: This must match the size of `shadow_atlas_tiles` in `bevy_pbr/src/render/mesh_view_types.wgsl`!
+pub const MAX_SHADOW_ATLAS_TILES: usize = 256;
+
+/// The largest supported [`ShadowAtlas`] size, as tile coordinates are packed into 14 bits.
+const MAX_SHADOW_ATLAS_SIZE: u32 = 1 << 14;
+
+/// A tile of the [`ShadowAtlas`] allocated to a point or spot light.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ShadowAtlasTile {
+    /// The top left corner of the tile, in texels.
+    pub origin: UVec2,
+    /// The width and height of the tile, in texels.
+    ///
+    /// The six faces of a point light each have a tile of this size, laid out in a 3x2 block
+    /// starting at `origin`, in the order +X, -X, +Y on the first row and -Y, +Z, -Z on the second.
+    pub size: u32,
+}
+
+impl ShadowAtlasTile {
+    /// Returns the size of the block of texels taken up by a light with tiles of `size` texels.
+    fn extent(size: u32, is_point_light: bool) -> UVec2 {
+        if is_point_light {
+            UVec2::new(size * 3, size * 2)
+        } else {
+            UVec2::splat(size)
+        }
+    }
+
+    /// Returns the viewport of the tile as x, y, width and height, in texels. Spot lights only
+    /// have the face with index 0.
+    pub fn face_viewport(&self, face_index: usize) -> UVec4 {
+        let face = UVec2::new(face_index as u32 % 3, face_index as u32 / 3);
+        (self.origin + face * self.size)
+            .extend(self.size)
+            .extend(self.size)
+    }
+
+    /// Packs the tile into the format read by `shadow_atlas_tile` in `shadow_sampling.wgsl`.
+    pub(crate) fn pack(&self) -> u32 {
+        self.origin.x | (self.origin.y << 14) | (self.size.trailing_zeros() << 28)
+    }
+}
+
+/// The state of a light that its shadow map depends on.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShadowMapKey {
+    /// The transform of the shadow map's views. Point lights only use the translation.
+    pub transform: Mat4,
+    pub range: f32,
+    /// The outer angle of a spot light, or 0 for point lights.
+    pub angle: f32,
+}
+
+/// A request for a tile of the [`ShadowAtlas`], made by each shadow casting point and spot light
+/// every frame.
+#[derive(Clone, Copy, Debug)]
+pub struct ShadowAtlasRequest {
+    pub light_entity: Entity,
+    pub is_point_light: bool,
+    /// The size of tile the light should have, in texels. This must be a power of two.
+    pub tile_size: u32,
+    pub key: ShadowMapKey,
+}
+
+struct ShadowAtlasAllocation {
+    tile: ShadowAtlasTile,
+    is_point_light: bool,
+    /// The state of the light when its tile was rendered, or `None` if the tile must be rendered.
+    rendered_key: Option<ShadowMapKey>,
+}
+
+/// Packs blocks of texels into rows of the atlas, called shelves, each as high as the first
+/// block placed in it.
+#[derive(Default)]
+struct ShelfAllocator {
+    size: u32,
+    /// The y coordinate, height and used width of each shelf.
+    shelves: Vec<(u32, u32, u32)>,
+}
+
+impl ShelfAllocator {
+    fn new(size: u32) -> Self {
+        Self {
+            size,
+            shelves: Vec::new(),
+        }
+    }
+
+    /// Returns the top left corner of a free block of `extent` texels, or `None` if there's no
+    /// room left for it.
+    fn allocate(&mut self, extent: UVec2) -> Option<UVec2> {
+        // Use the lowest shelf that's high enough and has room left, to waste as little as possible.
+        let shelf = self
+            .shelves
+            .iter_mut()
+            .filter(|(_, height, used_width)| {
+                *height >= extent.y && self.size - *used_width >= extent.x
+            })
+            .min_by_key(|(_, height, _)| *height);
+        if let Some((y, _, used_width)) = shelf {
+            let origin = UVec2::new(*used_width, *y);
+            *used_width += extent.x;
+            return Some(origin);
+        }
+
+        let y = self.shelves.last().map_or(0, |(y, height, _)| *y + *height);
+        if extent.x > self.size || self.size - y < extent.y {
+            return None;
+        }
+        self.shelves.push((y, extent.y, extent.x));
+        Some(UVec2::new(0, y))
+    }
+}
+
+/// The shadow atlas that [`PointLight`](crate::PointLight) and [`SpotLight`](crate::SpotLight)
+/// shadow maps are rendered to, shared by all views.
+///
+/// Tiles are kept across frames, and are only rendered again when their light or the shadow
+/// casters within its range change. See [`ShadowAtlasSettings`].
+#[derive(Resource, Default)]
+pub struct ShadowAtlas {
+    texture: Option<Texture>,
+    texture_view: Option<TextureView>,
+    allocator: ShelfAllocator,
+    allocations: EntityHashMap<ShadowAtlasAllocation>,
+    /// How many times the requested tile sizes were halved to fit them the last time the atlas
+    /// was packed. This is applied to the requests of every frame, so that a full atlas isn't
+    /// packed, and all of its tiles rendered, again each frame.
+    shrink: u32,
+    /// Lights whose tiles couldn't be completely rendered, and must be rendered again.
+    invalidated_lights: Mutex<EntityHashSet>,
+}
+
+impl ShadowAtlas {
+    pub fn texture(&self) -> Option<&Texture> {
+        self.texture.as_ref()
+    }
+
+    pub fn texture_view(&self) -> Option<&TextureView> {
+        self.texture_view.as_ref()
+    }
+
+    /// Returns the tile allocated to the light `light_entity`, if it has one.
+    pub fn tile(&self, light_entity: Entity) -> Option<ShadowAtlasTile> {
+        self.allocations
+            .get(&light_entity)
+            .map(|allocation| allocation.tile)
+    }
+
+    /// Makes the tile of the light `light_entity` be rendered again next frame.
+    ///
+    /// This is used when some of the light's shadow casters couldn't be drawn, for example because
+    /// their pipelines are still being compiled.
+    pub fn invalidate(&self, light_entity: Entity) {
+        self.invalidated_lights.lock().unwrap().insert(light_entity);
+    }
+
+    /// Allocates tiles for `requests`, and returns the lights whose tiles must be rendered this
+    /// frame.
+    ///
+    /// If the atlas is full, the sizes of the requested tiles are halved until they fit, and the
+    /// lights that still don't fit at the minimum tile size get no tile.
+    pub(crate) fn allocate(
+        &mut self,
+        render_device: &RenderDevice,
+        settings: &ShadowAtlasSettings,
+        caster_changes: &ShadowCasterChanges,
+        requests: &mut [ShadowAtlasRequest],
+    ) -> EntityHashSet {
+        let size = shadow_atlas_size(settings, render_device);
+        if self.texture.as_ref().map(|texture| texture.width()) != Some(size) {
+            let texture = render_device.create_texture(&TextureDescriptor {
+                label: Some("shadow_atlas_texture"),
+                size: Extent3d {
+                    width: size,
+                    height: size,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: CORE_3D_DEPTH_FORMAT,
+                usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            });
+            self.texture_view = Some(texture.create_view(&TextureViewDescriptor {
+                label: Some("shadow_atlas_texture_view"),
+                ..Default::default()
+            }));
+            self.texture = Some(texture);
+            self.allocator = ShelfAllocator::new(size);
+            self.allocations.clear();
+            self.shrink = 0;
+        }
+
+        for light_entity in self.invalidated_lights.get_mut().unwrap().drain() {
+            if let Some(allocation) = self.allocations.get_mut(&light_entity) {
+                allocation.rendered_key = None;
+            }
+        }
+
+        // Tiles only grow back once they fit in the atlas at the larger size.
+        let min_tile_size = settings.min_tile_size.max(1).next_power_of_two();
+        if self.shrink > 0 && fits(size, requests, self.shrink - 1, min_tile_size) {
+            self.shrink -= 1;
+        }
+        for request in requests.iter_mut() {
+            request.tile_size = shrink_tile_size(request.tile_size, self.shrink, min_tile_size);
+        }
+
+        // Don't shrink tiles by a single step, so that lights near the threshold don't flip
+        // between two sizes, each time rendering their shadow maps again.
+        for request in requests.iter_mut() {
+            if let Some(allocation) = self.allocations.get(&request.light_entity) {
+                if allocation.is_point_light == request.is_point_light
+                    && request.tile_size * 2 == allocation.tile.size
+                {
+                    request.tile_size = allocation.tile.size;
+                }
+            }
+        }
+
+        // Release the tiles of lights that are gone or need a different tile. Their space is only
+        // reused once the atlas is packed again.
+        let mut previous_allocations = std::mem::take(&mut self.allocations);
+        let mut fits = true;
+        for request in requests.iter() {
+            match previous_allocations.remove(&request.light_entity) {
+                Some(allocation)
+                    if allocation.is_point_light == request.is_point_light
+                        && allocation.tile.size == request.tile_size =>
+                {
+                    self.allocations.insert(request.light_entity, allocation);
+                }
+                _ => {
+                    let extent = ShadowAtlasTile::extent(request.tile_size, request.is_point_light);
+                    let Some(origin) = self.allocator.allocate(extent) else {
+                        fits = false;
+                        break;
+                    };
+                    self.allocations.insert(
+                        request.light_entity,
+                        ShadowAtlasAllocation {
+                            tile: ShadowAtlasTile {
+                                origin,
+                                size: request.tile_size,
+                            },
+                            is_point_light: request.is_point_light,
+                            rendered_key: None,
+                        },
+                    );
+                }
+            }
+        }
+
+        if !fits {
+            self.repack(min_tile_size, requests);
+        }
+
+        let mut lights_to_render = EntityHashSet::default();
+        for request in requests.iter() {
+            let Some(allocation) = self.allocations.get_mut(&request.light_entity) else {
+                continue;
+            };
+            let light_position = Vec3A::from(request.key.transform.w_axis.xyz());
+            let casters_changed = caster_changes.invalidate_all
+                || caster_changes.spheres.iter().any(|sphere| {
+                    (sphere.center - light_position).length() < sphere.radius + request.key.range
+                });
+            if !settings.cache_shadows
+                || casters_changed
+                || allocation.rendered_key != Some(request.key)
+            {
+                allocation.rendered_key = Some(request.key);
+                lights_to_render.insert(request.light_entity);
+            }
+        }
+        lights_to_render
+    }
+
+    /// Packs every requested tile into the atlas from scratch, halving tile sizes until they fit.
+    fn repack(&mut self, min_tile_size: u32, requests: &mut [ShadowAtlasRequest]) {
+        let order = packing_order(requests);
+
+        loop {
+            self.allocator = ShelfAllocator::new(self.allocator.size);
+            self.allocations.clear();
+
+            let mut fits = true;
+            for &index in &order {
+                let request = &requests[index];
+                let extent = ShadowAtlasTile::extent(request.tile_size, request.is_point_light);
+                let Some(origin) = self.allocator.allocate(extent) else {
+                    fits = false;
+                    continue;
+                };
+                self.allocations.insert(
+                    request.light_entity,
+                    ShadowAtlasAllocation {
+                        tile: ShadowAtlasTile {
+                            origin,
+                            size: request.tile_size,
+                        },
+                        is_point_light: request.is_point_light,
+                        rendered_key: None,
+                    },
+                );
+            }
+
+            if fits {
+                return;
+            }
+
+            let mut shrunk = false;
+            for request in requests.iter_mut() {
+                if request.tile_size > min_tile_size {
+                    request.tile_size /= 2;
+                    shrunk = true;
+                }
+            }
+            if shrunk {
+                self.shrink += 1;
+            } else {
+                warn_once!(
+                    "The shadow atlas is too small to fit the shadow maps of every point and spot \
+                    light, even at the minimum tile size. Some lights won't cast shadows."
+                );
+                return;
+            }
+        }
+    }
+}
+
+/// Returns the order to pack `requests` in. Packing the highest blocks first fills the shelves best.
+fn packing_order(requests: &[ShadowAtlasRequest]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..requests.len()).collect();
+    order.sort_by_key(|&index| {
+        let request = &requests[index];
+        let extent = ShadowAtlasTile::extent(request.tile_size, request.is_point_light);
+        std::cmp::Reverse((extent.y, extent.x))
+    });
+    order
+}
+
+/// Halves `tile_size` `shrink` times, without going below `min_tile_size`.
+fn shrink_tile_size(tile_size: u32, shrink: u32, min_tile_size: u32) -> u32 {
+    if tile_size > min_tile_size {
+        (tile_size >> shrink).max(min_tile_size)
+    } else {
+        tile_size
+    }
+}
+
+/// Returns whether `requests` all fit in an empty atlas of `size` texels once their tile sizes are
+/// halved `shrink` times.
+fn fits(size: u32, requests: &[ShadowAtlasRequest], shrink: u32, min_tile_size: u32) -> bool {
+    let mut allocator = ShelfAllocator::new(size);
+    let mut requests = requests.to_vec();
+    for request in &mut requests {
+        request.tile_size = shrink_tile_size(request.tile_size, shrink, min_tile_size);
+    }
+    packing_order(&requests).into_iter().all(|index| {
+        let request = &requests[index];
+        let extent = ShadowAtlasTile::extent(request.tile_size, request.is_point_light);
+        allocator.allocate(extent).is_some()
+    })
+}
+
+/// Returns the width and height of the shadow atlas texture, in texels.
+pub(crate) fn shadow_atlas_size(
+    settings: &ShadowAtlasSettings,
+    render_device: &RenderDevice,
+) -> u32 {
+    settings
+        .size
+        .min(render_device.limits().max_texture_dimension_2d)
+        .clamp(1, MAX_SHADOW_ATLAS_SIZE)
+}
+
+/// Returns the resolution that a point or spot light's shadow map needs to match its coverage of
+/// `view`, in texels.
+pub(crate) fn shadow_map_coverage(view: &ExtractedView, light_position: Vec3, range: f32) -> f32 {
+    let is_orthographic = view.projection.w_axis.w == 1.0;
+    let scale = if is_orthographic {
+        view.projection.y_axis.y
+    } else {
+        let distance = view.transform.translation().distance(light_position);
+        if distance <= range {
+            // The view is within the light's range, so the light may cover the whole screen.
+            return f32::INFINITY;
+        }
+        view.projection.y_axis.y / distance
+    };
+    // The diameter of the light's range on screen, in pixels.
+    range * scale * view.viewport.w as f32
+}
+
+/// Rounds `texels` up to a power of two tile size between `min_tile_size` and `max_tile_size`.
+pub(crate) fn shadow_atlas_tile_size(texels: f32, min_tile_size: u32, max_tile_size: u32) -> u32 {
+    let max_tile_size = 1 << max_tile_size.max(1).ilog2();
+    let min_tile_size = min_tile_size.max(1).next_power_of_two().min(max_tile_size);
+    // `as` saturates, so infinite coverage gives the maximum size.
+    (texels as u32)
+        .clamp(min_tile_size, max_tile_size)
+        .next_power_of_two()
+}
+
+/// The shadow casters that changed since the last frame, used to work out which tiles of the
+/// [`ShadowAtlas`] must be rendered again.
+#[derive(Resource, Default)]
+pub struct ShadowCasterChanges {
+    /// The world space bounding spheres of the casters that changed, both where they were and
+    /// where they are now.
+    pub spheres: Vec<Sphere>,
+    /// Whether every tile must be rendered again.
+    pub invalidate_all: bool,
+}
+
+fn shadow_caster_sphere(transform: &GlobalTransform, aabb: &Aabb) -> Sphere {
+    Sphere {
+        center: transform.transform_point(aabb.center.into()).into(),
+        radius: transform.radius_vec3a(aabb.half_extents),
+    }
+}
+
+/// Extracts the [`ShadowCasterChanges`] since the last frame.
+///
+/// Skinned and morphed meshes are assumed to change every frame. Changes to meshlet meshes
+/// render every tile again, as they have no [`Aabb`].
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn extract_shadow_caster_changes(
+    mut caster_changes: ResMut<ShadowCasterChanges>,
+    changed_casters: Extract<
+        Query<
+            (
+                Entity,
+                &GlobalTransform,
+                &Aabb,
+                &InheritedVisibility,
+                Has<NotShadowCaster>,
+            ),
+            (
+                With<Handle<Mesh>>,
+                Or<(
+                    Changed<GlobalTransform>,
+                    Changed<Aabb>,
+                    Changed<Handle<Mesh>>,
+                    Changed<InheritedVisibility>,
+                    Added<NotShadowCaster>,
+                )>,
+            ),
+        >,
+    >,
+    animated_casters: Extract<
+        Query<
+            (&GlobalTransform, &Aabb),
+            (
+                With<Handle<Mesh>>,
+                Without<NotShadowCaster>,
+                Or<(With<SkinnedMesh>, With<MeshMorphWeights>)>,
+            ),
+        >,
+    >,
+    casters: Extract<Query<(&GlobalTransform, &Aabb, &InheritedVisibility), With<Handle<Mesh>>>>,
+    mut removed_meshes: Extract<RemovedComponents<Handle<Mesh>>>,
+    mut removed_not_shadow_casters: Extract<RemovedComponents<NotShadowCaster>>,
+    mut mesh_events: Extract<EventReader<AssetEvent<Mesh>>>,
+    #[cfg(feature = "meshlet")] changed_meshlet_casters: Extract<
+        Query<
+            (),
+            (
+                With<Handle<MeshletMesh>>,
+                Or<(
+                    Changed<GlobalTransform>,
+                    Changed<Handle<MeshletMesh>>,
+                    Changed<InheritedVisibility>,
+                    With<SkinnedMesh>,
+                    With<MeshMorphWeights>,
+                )>,
+            ),
+        >,
+    >,
+    #[cfg(feature = "meshlet")] mut removed_meshlet_meshes: Extract<
+        RemovedComponents<Handle<MeshletMesh>>,
+    >,
+    mut previous_spheres: Local<EntityHashMap<Sphere>>,
+) {
+    let caster_changes = caster_changes.as_mut();
+    caster_changes.spheres.clear();
+    caster_changes.invalidate_all = mesh_events.read().count() > 0;
+
+    #[cfg(feature = "meshlet")]
+    {
+        caster_changes.invalidate_all |= !changed_meshlet_casters.is_empty();
+        caster_changes.invalidate_all |= removed_meshlet_meshes.read().count() > 0;
+    }
+
+    for (entity, transform, aabb, visibility, not_shadow_caster) in &changed_casters {
+        caster_changes
+            .spheres
+            .extend(previous_spheres.remove(&entity));
+        if visibility.get() && !not_shadow_caster {
+            let sphere = shadow_caster_sphere(transform, aabb);
+            caster_changes.spheres.push(sphere.clone());
+            previous_spheres.insert(entity, sphere);
+        }
+    }
+
+    for entity in removed_meshes.read() {
+        caster_changes
+            .spheres
+            .extend(previous_spheres.remove(&entity));
+    }
+
+    for entity in removed_not_shadow_casters.read() {
+        let Ok((transform, aabb, visibility)) = casters.get(entity) else {
+            continue;
+        };
+        if visibility.get() {
+            let sphere = shadow_caster_sphere(transform, aabb);
+            caster_changes.spheres.push(sphere.clone());
+            previous_spheres.insert(entity, sphere);
+        }
+    }
+
+    for (transform, aabb) in &animated_casters {
+        caster_changes
+            .spheres
+            .push(shadow_caster_sphere(transform, aabb));
+    }
+}
+
+/// The pipeline that clears a tile of the [`ShadowAtlas`] before it's rendered, as a render pass
+/// can only clear the whole atlas.
+#[derive(Resource)]
+pub struct ShadowAtlasClearPipeline {
+    pub pipeline_id: CachedRenderPipelineId,
+}
+
+impl FromWorld for ShadowAtlasClearPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let pipeline_id =
+            world
+                .resource_mut::<PipelineCache>()
+                .queue_render_pipeline(RenderPipelineDescriptor {
+                    label: Some("shadow_atlas_clear_pipeline".into()),
+                    layout: vec![],
+                    push_constant_ranges: vec![],
+                    // The fullscreen triangle is at a depth of 0, the far plane with reversed Z
+                    vertex: fullscreen_shader_vertex_state(),
+                    primitive: PrimitiveState::default(),
+                    depth_stencil: Some(DepthStencilState {
+                        format: CORE_3D_DEPTH_FORMAT,
+                        depth_write_enabled: true,
+                        depth_compare: CompareFunction::Always,
+                        stencil: StencilState::default(),
+                        bias: DepthBiasState::default(),
+                    }),
+                    multisample: MultisampleState::default(),
+                    fragment: None,
+                });
+
+        Self { pipeline_id }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::entity::Entity;
+    use bevy_math::{Mat4, UVec2};
+
+    use super::{fits, shrink_tile_size, ShadowAtlasRequest, ShadowMapKey, ShelfAllocator};
+
+    #[test]
+    fn shelf_allocator_reuses_shelves() {
+        let mut allocator = ShelfAllocator::new(1024);
+        assert_eq!(
+            allocator.allocate(UVec2::new(768, 512)),
+            Some(UVec2::new(0, 0))
+        );
+        assert_eq!(
+            allocator.allocate(UVec2::new(256, 256)),
+            Some(UVec2::new(768, 0))
+        );
+        assert_eq!(
+            allocator.allocate(UVec2::new(256, 256)),
+            Some(UVec2::new(0, 512))
+        );
+        assert_eq!(
+            allocator.allocate(UVec2::new(512, 256)),
+            Some(UVec2::new(256, 512))
+        );
+    }
+
+    #[test]
+    fn shelf_allocator_runs_out_of_space() {
+        let mut allocator = ShelfAllocator::new(512);
+        assert!(allocator.allocate(UVec2::new(384, 256)).is_some());
+        assert!(allocator.allocate(UVec2::new(384, 256)).is_some());
+        assert!(allocator.allocate(UVec2::new(384, 256)).is_none());
+        assert!(allocator.allocate(UVec2::new(128, 128)).is_some());
+        assert!(allocator.allocate(UVec2::new(1024, 1)).is_none());
+    }
+
+    #[test]
+    fn shrunk_requests_fit() {
+        let requests: Vec<_> = (0..4)
+            .map(|index| ShadowAtlasRequest {
+                light_entity: Entity::from_raw(index),
+                is_point_light: false,
+                tile_size: 512,
+                key: ShadowMapKey {
+                    transform: Mat4::IDENTITY,
+                    range: 1.0,
+                    angle: 0.0,
+                },
+            })
+            .collect();
+
+        // Four 512 tiles need twice the area of a 512 atlas, so they only fit at 256.
+        assert!(!fits(512, &requests, 0, 64));
+        assert!(fits(512, &requests, 1, 64));
+        assert!(fits(512, &requests, 2, 64));
+    }
+
+    #[test]
+    fn shrink_tile_size_stops_at_the_minimum() {
+        assert_eq!(shrink_tile_size(1024, 2, 64), 256);
+        assert_eq!(shrink_tile_size(1024, 8, 64), 64);
+        assert_eq!(shrink_tile_size(32, 1, 64), 32);
+    }
+}
@@ -2,6 +2,7 @@
 
 #import bevy_pbr::{
     mesh_view_bindings as view_bindings,
+    mesh_view_types::POINT_LIGHT_FLAGS_SPOT_LIGHT_BIT,
     utils::interleaved_gradient_noise,
     utils,
 }
@@ -28,7 +29,6 @@ fn sample_shadow_map_hardware(light_local: vec2<f32>, depth: f32, array_index: i
 }
 
 // Numbers determined by trial and error that gave nice results.
-const SPOT_SHADOW_TEXEL_SIZE: f32 = 0.0134277345;
 const POINT_SHADOW_SCALE: f32 = 0.003;
 const POINT_SHADOW_TEMPORAL_OFFSET_SCALE: f32 = 0.5;
 
@@ -169,20 +169,93 @@ fn sample_shadow_map(light_local: vec2<f32>, depth: f32, array_index: i32, texel
 #endif
 }
 
+// Returns the origin of the light's tile in the shadow atlas in xy, and its size in z.
+fn shadow_atlas_tile(light_id: u32) -> vec3<u32> {
+    var tile_index = light_id;
+    if ((view_bindings::point_lights.data[light_id].flags & POINT_LIGHT_FLAGS_SPOT_LIGHT_BIT) != 0u) {
+        tile_index = u32(i32(light_id) + view_bindings::lights.spot_light_shadowmap_offset);
+    }
+    // Must match `ShadowAtlasTile::pack`
+    let packed = view_bindings::lights.shadow_atlas_tiles[tile_index / 4u][tile_index % 4u];
+    return vec3(packed & 0x3fffu, (packed >> 14u) & 0x3fffu, 1u << (packed >> 28u));
+}
+
+// Selects the cube face a direction points to, returning the uv within the face in xy and the
+// face index in z, in the +X, -X, +Y, -Y, +Z, -Z order of the point light shadow views.
+fn cube_face_uv(direction: vec3<f32>) -> vec3<f32> {
+    let direction_abs = abs(direction);
+    var major_axis: f32;
+    var st: vec2<f32>;
+    var face: f32;
+    if (direction_abs.x >= direction_abs.y && direction_abs.x >= direction_abs.z) {
+        major_axis = direction_abs.x;
+        if (direction.x > 0.0) {
+            st = vec2(-direction.z, -direction.y);
+            face = 0.0;
+        } else {
+            st = vec2(direction.z, -direction.y);
+            face = 1.0;
+        }
+    } else if (direction_abs.y >= direction_abs.z) {
+        major_axis = direction_abs.y;
+        if (direction.y > 0.0) {
+            st = vec2(direction.x, direction.z);
+            face = 2.0;
+        } else {
+            st = vec2(direction.x, -direction.z);
+            face = 3.0;
+        }
+    } else {
+        major_axis = direction_abs.z;
+        if (direction.z > 0.0) {
+            st = vec2(direction.x, -direction.y);
+            face = 4.0;
+        } else {
+            st = vec2(-direction.x, -direction.y);
+            face = 5.0;
+        }
+    }
+    return vec3(st / major_axis * 0.5 + 0.5, face);
+}
+
 // NOTE: Due to the non-uniform control flow in `shadows::fetch_point_shadow`,
 // we must use the Level variant of textureSampleCompare to avoid undefined
 // behavior due to some of the fragments in a quad (2x2 fragments) being
 // processed not being sampled, and this messing with mip-mapping functionality.
 // The shadow maps have no mipmaps so Level just samples from LOD 0.
-fn sample_shadow_cubemap_hardware(light_local: vec3<f32>, depth: f32, light_id: u32) -> f32 {
-#ifdef NO_CUBE_ARRAY_TEXTURES_SUPPORT
-    return textureSampleCompare(view_bindings::point_shadow_textures, view_bindings::point_shadow_textures_sampler, light_local, depth);
-#else
-    return textureSampleCompareLevel(view_bindings::point_shadow_textures, view_bindings::point_shadow_textures_sampler, light_local, i32(light_id), depth);
-#endif
+//
+// For point lights, `light_local` is the cubemap direction from the light. For spot lights, it's
+// the position in the light's view space.
+fn sample_shadow_atlas_hardware(light_local: vec3<f32>, depth: f32, light_id: u32) -> f32 {
+    let light = &view_bindings::point_lights.data[light_id];
+    let tile = shadow_atlas_tile(light_id);
+    let tile_size = f32(tile.z);
+
+    var uv: vec2<f32>;
+    var face_offset = vec2<f32>(0.0);
+    if (((*light).flags & POINT_LIGHT_FLAGS_SPOT_LIGHT_BIT) != 0u) {
+        let ndc = light_local.xy / ((*light).spot_light_tan_angle * -light_local.z);
+        uv = ndc * vec2(0.5, -0.5) + vec2(0.5);
+    } else {
+        // Faces are laid out in a 3x2 block in the tile
+        let face_uv = cube_face_uv(light_local);
+        uv = face_uv.xy;
+        face_offset = vec2(face_uv.z % 3.0, floor(face_uv.z / 3.0)) * tile_size;
+    }
+
+    // Keep the filtered texels inside the tile, so that neighbouring tiles don't bleed in
+    let texel = clamp(uv * tile_size, vec2(0.5), vec2(tile_size - 0.5)) + face_offset
+        + vec2<f32>(tile.xy);
+    let atlas_size = vec2<f32>(textureDimensions(view_bindings::shadow_atlas_texture));
+    return textureSampleCompareLevel(
+        view_bindings::shadow_atlas_texture,
+        view_bindings::shadow_atlas_sampler,
+        texel / atlas_size,
+        depth,
+    );
 }
 
-fn sample_shadow_cubemap_at_offset(
+fn sample_shadow_atlas_at_offset(
     position: vec2<f32>,
     coeff: f32,
     x_basis: vec3<f32>,
@@ -191,7 +264,7 @@ fn sample_shadow_cubemap_at_offset(
     depth: f32,
     light_id: u32,
 ) -> f32 {
-    return sample_shadow_cubemap_hardware(
+    return sample_shadow_atlas_hardware(
         light_local + position.x * x_basis + position.y * y_basis,
         depth,
         light_id
@@ -203,7 +276,7 @@ fn sample_shadow_cubemap_at_offset(
 // bilinear filtering hardware to reduce the number of samples needed. This
 // trick doesn't apply to cubemaps, so we manually apply a Gaussian filter over
 // the standard 8xMSAA pattern instead.
-fn sample_shadow_cubemap_gaussian(
+fn sample_shadow_atlas_gaussian(
     light_local: vec3<f32>,
     depth: f32,
     scale: f32,
@@ -219,28 +292,28 @@ fn sample_shadow_cubemap_gaussian(
     let basis = orthonormalize(light_local, up) * scale * distance_to_light;
 
     var sum: f32 = 0.0;
-    sum += sample_shadow_cubemap_at_offset(
+    sum += sample_shadow_atlas_at_offset(
         D3D_SAMPLE_POINT_POSITIONS[0], D3D_SAMPLE_POINT_COEFFS[0],
         basis[0], basis[1], light_local, depth, light_id);
-    sum += sample_shadow_cubemap_at_offset(
+    sum += sample_shadow_atlas_at_offset(
         D3D_SAMPLE_POINT_POSITIONS[1], D3D_SAMPLE_POINT_COEFFS[1],
         basis[0], basis[1], light_local, depth, light_id);
-    sum += sample_shadow_cubemap_at_offset(
+    sum += sample_shadow_atlas_at_offset(
         D3D_SAMPLE_POINT_POSITIONS[2], D3D_SAMPLE_POINT_COEFFS[2],
         basis[0], basis[1], light_local, depth, light_id);
-    sum += sample_shadow_cubemap_at_offset(
+    sum += sample_shadow_atlas_at_offset(
         D3D_SAMPLE_POINT_POSITIONS[3], D3D_SAMPLE_POINT_COEFFS[3],
         basis[0], basis[1], light_local, depth, light_id);
-    sum += sample_shadow_cubemap_at_offset(
+    sum += sample_shadow_atlas_at_offset(
         D3D_SAMPLE_POINT_POSITIONS[4], D3D_SAMPLE_POINT_COEFFS[4],
         basis[0], basis[1], light_local, depth, light_id);
-    sum += sample_shadow_cubemap_at_offset(
+    sum += sample_shadow_atlas_at_offset(
         D3D_SAMPLE_POINT_POSITIONS[5], D3D_SAMPLE_POINT_COEFFS[5],
         basis[0], basis[1], light_local, depth, light_id);
-    sum += sample_shadow_cubemap_at_offset(
+    sum += sample_shadow_atlas_at_offset(
         D3D_SAMPLE_POINT_POSITIONS[6], D3D_SAMPLE_POINT_COEFFS[6],
         basis[0], basis[1], light_local, depth, light_id);
-    sum += sample_shadow_cubemap_at_offset(
+    sum += sample_shadow_atlas_at_offset(
         D3D_SAMPLE_POINT_POSITIONS[7], D3D_SAMPLE_POINT_COEFFS[7],
         basis[0], basis[1], light_local, depth, light_id);
     return sum;
@@ -249,7 +322,7 @@ fn sample_shadow_cubemap_gaussian(
 // This is a port of the Jimenez14 filter above to the 3D space. It jitters the
 // points in the spiral pattern after first creating a 2D orthonormal basis
 // along the principal light direction.
-fn sample_shadow_cubemap_temporal(
+fn sample_shadow_atlas_temporal(
     light_local: vec3<f32>,
     depth: f32,
     scale: f32,
@@ -284,39 +357,39 @@ fn sample_shadow_cubemap_temporal(
         POINT_SHADOW_TEMPORAL_OFFSET_SCALE;
 
     var sum: f32 = 0.0;
-    sum += sample_shadow_cubemap_at_offset(
+    sum += sample_shadow_atlas_at_offset(
         sample_offset0, 0.125, basis[0], basis[1], light_local, depth, light_id);
-    sum += sample_shadow_cubemap_at_offset(
+    sum += sample_shadow_atlas_at_offset(
         sample_offset1, 0.125, basis[0], basis[1], light_local, depth, light_id);
-    sum += sample_shadow_cubemap_at_offset(
+    sum += sample_shadow_atlas_at_offset(
         sample_offset2, 0.125, basis[0], basis[1], light_local, depth, light_id);
-    sum += sample_shadow_cubemap_at_offset(
+    sum += sample_shadow_atlas_at_offset(
         sample_offset3, 0.125, basis[0], basis[1], light_local, depth, light_id);
-    sum += sample_shadow_cubemap_at_offset(
+    sum += sample_shadow_atlas_at_offset(
         sample_offset4, 0.125, basis[0], basis[1], light_local, depth, light_id);
-    sum += sample_shadow_cubemap_at_offset(
+    sum += sample_shadow_atlas_at_offset(
         sample_offset5, 0.125, basis[0], basis[1], light_local, depth, light_id);
-    sum += sample_shadow_cubemap_at_offset(
+    sum += sample_shadow_atlas_at_offset(
         sample_offset6, 0.125, basis[0], basis[1], light_local, depth, light_id);
-    sum += sample_shadow_cubemap_at_offset(
+    sum += sample_shadow_atlas_at_offset(
         sample_offset7, 0.125, basis[0], basis[1], light_local, depth, light_id);
     return sum;
 }
 
-fn sample_shadow_cubemap(
+fn sample_shadow_atlas(
     light_local: vec3<f32>,
     distance_to_light: f32,
     depth: f32,
     light_id: u32,
 ) -> f32 {
 #ifdef SHADOW_FILTER_METHOD_GAUSSIAN
-    return sample_shadow_cubemap_gaussian(
+    return sample_shadow_atlas_gaussian(
         light_local, depth, POINT_SHADOW_SCALE, distance_to_light, light_id);
 #else ifdef SHADOW_FILTER_METHOD_TEMPORAL
-    return sample_shadow_cubemap_temporal(
+    return sample_shadow_atlas_temporal(
         light_local, depth, POINT_SHADOW_SCALE, distance_to_light, light_id);
 #else ifdef SHADOW_FILTER_METHOD_HARDWARE_2X2
-    return sample_shadow_cubemap_hardware(light_local, depth, light_id);
+    return sample_shadow_atlas_hardware(light_local, depth, light_id);
 #else
     // This needs a default return value to avoid shader compilation errors if it's compiled with no SHADOW_FILTER_METHOD_* defined.
     // (eg. if the normal prepass is enabled it ends up compiling this due to the normal prepass depending on pbr_functions, which depends on shadows)
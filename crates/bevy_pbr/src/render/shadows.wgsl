@@ -3,7 +3,7 @@
 #import bevy_pbr::{
     mesh_view_types::POINT_LIGHT_FLAGS_SPOT_LIGHT_Y_NEGATIVE,
     mesh_view_bindings as view_bindings,
-    shadow_sampling::{SPOT_SHADOW_TEXEL_SIZE, sample_shadow_cubemap, sample_shadow_map}
+    shadow_sampling::{sample_shadow_atlas, sample_shadow_map}
 }
 
 #import bevy_render::{
@@ -43,7 +43,7 @@ fn fetch_point_shadow(light_id: u32, frag_position: vec4<f32>, surface_normal: v
 
     // Do the lookup, using HW PCF and comparison. Cubemaps assume a left-handed coordinate space,
     // so we have to flip the z-axis when sampling.
-    return sample_shadow_cubemap(frag_ls * flip_z, distance_to_light, depth, light_id);
+    return sample_shadow_atlas(frag_ls * flip_z, distance_to_light, depth, light_id);
 }
 
 fn fetch_spot_shadow(light_id: u32, frag_position: vec4<f32>, surface_normal: vec3<f32>) -> f32 {
@@ -84,22 +84,11 @@ fn fetch_spot_shadow(light_id: u32, frag_position: vec4<f32>, surface_normal: ve
     // this allows us to keep the matrix construction code identical between CPU and GPU.
     let projected_position = offset_position * light_inv_rot;
 
-    // divide xy by perspective matrix "f" and by -projected.z (projected.z is -projection matrix's w)
-    // to get ndc coordinates
-    let f_div_minus_z = 1.0 / ((*light).spot_light_tan_angle * -projected_position.z);
-    let shadow_xy_ndc = projected_position.xy * f_div_minus_z;
-    // convert to uv coordinates
-    let shadow_uv = shadow_xy_ndc * vec2<f32>(0.5, -0.5) + vec2<f32>(0.5, 0.5);
-
     // 0.1 must match POINT_LIGHT_NEAR_Z
     let depth = 0.1 / -projected_position.z;
 
-    return sample_shadow_map(
-        shadow_uv,
-        depth,
-        i32(light_id) + view_bindings::lights.spot_light_shadowmap_offset,
-        SPOT_SHADOW_TEXEL_SIZE
-    );
+    // The projection to the light's tile in the shadow atlas happens in `sample_shadow_atlas`
+    return sample_shadow_atlas(projected_position, distance_to_light, depth, light_id);
 }
 
 fn get_cascade_index(light_id: u32, view_z: f32) -> u32 {
@@ -0,0 +1,600 @@
+use crate::{
+    depth_pyramid_texture_descriptor, DepthPyramidBindGroups, DepthPyramidPipeline,
+    DepthPyramidPipelineKey, DepthPyramidPipelines, DepthPyramidViews, NodePbr,
+};
+use bevy_app::{App, Plugin};
+use bevy_asset::{load_internal_asset, Handle};
+use bevy_core_pipeline::{
+    core_3d::graph::{Core3d, Node3d},
+    prelude::Camera3d,
+    prepass::{DepthPrepass, NormalPrepass, ViewPrepassTextures},
+};
+use bevy_derive::{Deref, DerefMut};
+use bevy_ecs::{
+    entity::EntityHashMap,
+    prelude::{Bundle, Component, Entity},
+    query::{QueryItem, With},
+    reflect::ReflectComponent,
+    schedule::IntoSystemConfigs,
+    system::{Commands, Local, Query, Res, ResMut, Resource},
+    world::{FromWorld, World},
+};
+use bevy_math::Mat4;
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use bevy_render::{
+    camera::ExtractedCamera,
+    prelude::Camera,
+    render_graph::{NodeRunError, RenderGraphApp, RenderGraphContext, ViewNode, ViewNodeRunner},
+    render_resource::{
+        binding_types::{sampler, texture_2d, texture_storage_2d, uniform_buffer},
+        *,
+    },
+    renderer::{RenderAdapter, RenderContext, RenderDevice, RenderQueue},
+    texture::{CachedTexture, TextureCache},
+    view::{ExtractedView, Msaa, ViewTarget, ViewUniform, ViewUniformOffset, ViewUniforms},
+    Extract, ExtractSchedule, Render, RenderApp, RenderSet,
+};
+use bevy_utils::tracing::{error, warn};
+
+const RAYMARCH_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(807359312044617);
+const SSR_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(592841375021956);
+
+/// Plugin for screen space reflections.
+pub struct ScreenSpaceReflectionsPlugin;
+
+impl Plugin for ScreenSpaceReflectionsPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            RAYMARCH_SHADER_HANDLE,
+            "raymarch.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(app, SSR_SHADER_HANDLE, "ssr.wgsl", Shader::from_wgsl);
+
+        app.register_type::<ScreenSpaceReflectionsSettings>();
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        let render_adapter = render_app.world().resource::<RenderAdapter>();
+        for format in [TextureFormat::R32Float, TextureFormat::Rgba16Float] {
+            if !render_adapter
+                .get_texture_format_features(format)
+                .allowed_usages
+                .contains(TextureUsages::STORAGE_BINDING)
+            {
+                warn!("ScreenSpaceReflectionsPlugin not loaded. GPU lacks support: TextureFormat::{format:?} does not support TextureUsages::STORAGE_BINDING.");
+                return;
+            }
+        }
+
+        render_app
+            .init_resource::<SsrPipelines>()
+            .init_resource::<DepthPyramidPipeline>()
+            .init_resource::<SpecializedComputePipelines<DepthPyramidPipeline>>()
+            .init_resource::<ScreenSpaceReflectionsBuffer>()
+            .add_systems(ExtractSchedule, extract_ssr_settings)
+            .add_systems(
+                Render,
+                (
+                    prepare_ssr_settings.in_set(RenderSet::PrepareResources),
+                    prepare_ssr_textures.in_set(RenderSet::PrepareResources),
+                    prepare_ssr_bind_groups.in_set(RenderSet::PrepareBindGroups),
+                ),
+            )
+            .add_render_graph_node::<ViewNodeRunner<SsrNode>>(
+                Core3d,
+                NodePbr::ScreenSpaceReflections,
+            )
+            .add_render_graph_node::<ViewNodeRunner<SsrHistoryNode>>(
+                Core3d,
+                NodePbr::ScreenSpaceReflectionsHistory,
+            )
+            .add_render_graph_edges(
+                Core3d,
+                (
+                    // END_PRE_PASSES -> SCREEN_SPACE_REFLECTIONS -> MAIN_PASS
+                    Node3d::EndPrepasses,
+                    NodePbr::ScreenSpaceReflections,
+                    Node3d::StartMainPass,
+                ),
+            )
+            .add_render_graph_edges(
+                Core3d,
+                (
+                    // OIT_RESOLVE -> SCREEN_SPACE_REFLECTIONS_HISTORY -> VOLUMETRIC_FOG -> END_MAIN_PASS
+                    //
+                    // The history is captured before the fog, which is applied again on top of
+                    // the reflecting surface.
+                    Node3d::OitResolve,
+                    NodePbr::ScreenSpaceReflectionsHistory,
+                    NodePbr::VolumetricFog,
+                    Node3d::EndMainPass,
+                ),
+            );
+    }
+}
+
+/// Bundle to apply screen space reflections.
+#[derive(Bundle, Default, Clone)]
+pub struct ScreenSpaceReflectionsBundle {
+    pub settings: ScreenSpaceReflectionsSettings,
+    pub depth_prepass: DepthPrepass,
+    pub normal_prepass: NormalPrepass,
+}
+
+/// Component to apply screen space reflections to a 3d camera.
+///
+/// Screen space reflections (SSR) add sharp reflections of what's visible on-screen to smooth
+/// surfaces, such as wet streets and polished floors. For each pixel, a mirror reflection ray is
+/// marched through a hierarchical depth buffer built from the depth prepass, using the normals from
+/// the normal prepass. The color of the surface the ray hits is taken from the previous frame.
+///
+/// Reflections that can't be found on screen, such as those of objects behind the camera or
+/// hidden behind other objects, fall back to the light probes of the camera: its
+/// [`EnvironmentMapLight`](crate::environment_map::EnvironmentMapLight) and any reflection probes.
+/// The same happens on rough surfaces, whose blurry reflections are better approximated by
+/// the prefiltered light probes.
+///
+/// SSR works with both forward and deferred rendering.
+///
+/// # Usage Notes
+///
+/// Requires that you add the [`DepthPrepass`] and [`NormalPrepass`] components to your camera,
+/// and that [`Msaa`] is set to [`Msaa::Off`]. The main texture of the camera must be copyable,
+/// which it is unless [`CameraMainTextureUsages`](bevy_render::camera::CameraMainTextureUsages)
+/// were changed.
+///
+/// It's recommended to use SSR with an HDR camera, as the reflected colors of an LDR camera have
+/// already been tonemapped.
+///
+/// SSR is not supported on `WebGL2`.
+#[derive(Component, Reflect, Clone, Copy, Debug, PartialEq)]
+#[reflect(Component, Default)]
+pub struct ScreenSpaceReflectionsSettings {
+    /// Surfaces with a perceptual roughness at or above this value don't receive screen space
+    /// reflections. Reflections fade out smoothly from half of this value up to it.
+    pub perceptual_roughness_threshold: f32,
+    /// How deep surfaces are assumed to be, in world units. A ray that passes further behind a
+    /// surface than this is considered to pass behind it instead of hitting it.
+    pub thickness: f32,
+    /// The maximum number of steps a ray takes through the depth pyramid before giving up.
+    pub max_steps: u32,
+    /// The fraction of the screen over which reflections fade out towards the edges of the screen.
+    pub edge_fade: f32,
+}
+
+impl Default for ScreenSpaceReflectionsSettings {
+    fn default() -> Self {
+        Self {
+            perceptual_roughness_threshold: 0.3,
+            thickness: 0.25,
+            max_steps: 64,
+            edge_fade: 0.1,
+        }
+    }
+}
+
+/// The GPU-side representation of [`ScreenSpaceReflectionsSettings`].
+#[derive(Clone, Copy, ShaderType, Default)]
+pub struct ScreenSpaceReflectionsUniform {
+    /// The view projection matrix of the previous frame, used to reproject hits into the color
+    /// of the previous frame.
+    previous_view_proj: Mat4,
+    perceptual_roughness_threshold: f32,
+    thickness: f32,
+    max_steps: u32,
+    edge_fade: f32,
+}
+
+/// The buffer holding the [`ScreenSpaceReflectionsUniform`] of every view with screen space
+/// reflections.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct ScreenSpaceReflectionsBuffer(DynamicUniformBuffer<ScreenSpaceReflectionsUniform>);
+
+/// A component attached to each view in the render world that stores the offset of its
+/// [`ScreenSpaceReflectionsUniform`] in the [`ScreenSpaceReflectionsBuffer`].
+#[derive(Component, Default, Deref, DerefMut)]
+pub struct ViewScreenSpaceReflectionsUniformOffset(u32);
+
+#[derive(Default)]
+struct SsrNode {}
+
+impl ViewNode for SsrNode {
+    type ViewQuery = (
+        &'static ExtractedCamera,
+        &'static SsrBindGroups,
+        &'static ViewUniformOffset,
+        &'static ViewScreenSpaceReflectionsUniformOffset,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (camera, bind_groups, view_uniform_offset, ssr_uniform_offset): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let pipelines = world.resource::<SsrPipelines>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let (Some(camera_size), Some(raymarch_pipeline)) = (
+            camera.physical_viewport_size,
+            pipeline_cache.get_compute_pipeline(pipelines.raymarch_pipeline),
+        ) else {
+            return Ok(());
+        };
+
+        render_context.command_encoder().push_debug_group("ssr");
+
+        let depth_pyramid_built = {
+            let mut depth_pyramid_pass =
+                render_context
+                    .command_encoder()
+                    .begin_compute_pass(&ComputePassDescriptor {
+                        label: Some("ssr_depth_pyramid_pass"),
+                        timestamp_writes: None,
+                    });
+            bind_groups.depth_pyramid.dispatch(
+                &mut depth_pyramid_pass,
+                pipeline_cache,
+                view_uniform_offset.offset,
+            )
+        };
+        if !depth_pyramid_built {
+            render_context.command_encoder().pop_debug_group();
+            return Ok(());
+        }
+
+        {
+            let mut raymarch_pass =
+                render_context
+                    .command_encoder()
+                    .begin_compute_pass(&ComputePassDescriptor {
+                        label: Some("ssr_raymarch_pass"),
+                        timestamp_writes: None,
+                    });
+            raymarch_pass.set_pipeline(raymarch_pipeline);
+            raymarch_pass.set_bind_group(0, &bind_groups.raymarch_bind_group, &[]);
+            raymarch_pass.set_bind_group(
+                1,
+                &bind_groups.common_bind_group,
+                &[view_uniform_offset.offset, **ssr_uniform_offset],
+            );
+            raymarch_pass.dispatch_workgroups(
+                div_ceil(camera_size.x, 8),
+                div_ceil(camera_size.y, 8),
+                1,
+            );
+        }
+
+        render_context.command_encoder().pop_debug_group();
+        Ok(())
+    }
+}
+
+/// Copies the lit color of the view into its color history, for the reflections of the next frame.
+#[derive(Default)]
+struct SsrHistoryNode {}
+
+impl ViewNode for SsrHistoryNode {
+    type ViewQuery = (
+        &'static ExtractedCamera,
+        &'static ViewTarget,
+        &'static ScreenSpaceReflectionsTextures,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (camera, view_target, ssr_textures): QueryItem<Self::ViewQuery>,
+        _world: &World,
+    ) -> Result<(), NodeRunError> {
+        let origin = camera
+            .viewport
+            .as_ref()
+            .map(|viewport| viewport.physical_position)
+            .unwrap_or_default();
+
+        render_context.command_encoder().copy_texture_to_texture(
+            ImageCopyTexture {
+                origin: Origin3d {
+                    x: origin.x,
+                    y: origin.y,
+                    z: 0,
+                },
+                ..view_target.main_texture().as_image_copy()
+            },
+            ssr_textures.color_history.texture.as_image_copy(),
+            ssr_textures.color_history.texture.size(),
+        );
+
+        Ok(())
+    }
+}
+
+#[derive(Resource)]
+struct SsrPipelines {
+    raymarch_pipeline: CachedComputePipelineId,
+
+    common_bind_group_layout: BindGroupLayout,
+    raymarch_bind_group_layout: BindGroupLayout,
+
+    linear_clamp_sampler: Sampler,
+}
+
+impl FromWorld for SsrPipelines {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+
+        let linear_clamp_sampler = render_device.create_sampler(&SamplerDescriptor {
+            min_filter: FilterMode::Linear,
+            mag_filter: FilterMode::Linear,
+            mipmap_filter: FilterMode::Nearest,
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            ..Default::default()
+        });
+
+        let common_bind_group_layout = render_device.create_bind_group_layout(
+            "ssr_common_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::COMPUTE,
+                (
+                    uniform_buffer::<ViewUniform>(true),
+                    uniform_buffer::<ScreenSpaceReflectionsUniform>(true),
+                ),
+            ),
+        );
+
+        let raymarch_bind_group_layout = render_device.create_bind_group_layout(
+            "ssr_raymarch_bind_group_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::COMPUTE,
+                (
+                    texture_2d(TextureSampleType::Float { filterable: false }),
+                    texture_2d(TextureSampleType::Float { filterable: false }),
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    texture_storage_2d(TextureFormat::Rgba16Float, StorageTextureAccess::WriteOnly),
+                ),
+            ),
+        );
+
+        let raymarch_pipeline = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            label: Some("ssr_raymarch_pipeline".into()),
+            layout: vec![
+                raymarch_bind_group_layout.clone(),
+                common_bind_group_layout.clone(),
+            ],
+            push_constant_ranges: vec![],
+            shader: RAYMARCH_SHADER_HANDLE,
+            shader_defs: Vec::new(),
+            entry_point: "raymarch".into(),
+        });
+
+        Self {
+            raymarch_pipeline,
+
+            common_bind_group_layout,
+            raymarch_bind_group_layout,
+
+            linear_clamp_sampler,
+        }
+    }
+}
+
+fn extract_ssr_settings(
+    mut commands: Commands,
+    cameras: Extract<
+        Query<
+            (Entity, &Camera, &ScreenSpaceReflectionsSettings),
+            (With<Camera3d>, With<DepthPrepass>, With<NormalPrepass>),
+        >,
+    >,
+    msaa: Extract<Res<Msaa>>,
+) {
+    for (entity, camera, ssr_settings) in &cameras {
+        if **msaa != Msaa::Off {
+            error!(
+                "SSR is being used which requires Msaa::Off, but Msaa is currently set to Msaa::{:?}",
+                **msaa
+            );
+            return;
+        }
+
+        if camera.is_active {
+            commands.get_or_spawn(entity).insert(*ssr_settings);
+        }
+    }
+}
+
+/// Writes the [`ScreenSpaceReflectionsUniform`] of every view with screen space reflections.
+pub fn prepare_ssr_settings(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    mut ssr_buffer: ResMut<ScreenSpaceReflectionsBuffer>,
+    mut previous_view_projections: Local<EntityHashMap<Mat4>>,
+    views: Query<(Entity, &ExtractedView, &ScreenSpaceReflectionsSettings)>,
+) {
+    let Some(mut writer) = ssr_buffer.get_writer(views.iter().len(), &render_device, &render_queue)
+    else {
+        return;
+    };
+
+    let mut view_projections = EntityHashMap::default();
+    for (entity, view, ssr_settings) in &views {
+        let view_proj = view
+            .view_projection
+            .unwrap_or_else(|| view.projection * view.transform.compute_matrix().inverse());
+        let previous_view_proj = previous_view_projections
+            .get(&entity)
+            .copied()
+            .unwrap_or(view_proj);
+        view_projections.insert(entity, view_proj);
+
+        let uniform = ScreenSpaceReflectionsUniform {
+            previous_view_proj,
+            perceptual_roughness_threshold: ssr_settings.perceptual_roughness_threshold,
+            thickness: ssr_settings.thickness,
+            max_steps: ssr_settings.max_steps,
+            edge_fade: ssr_settings.edge_fade,
+        };
+
+        // This is later read by `SetMeshViewBindGroup<I>`
+        commands
+            .entity(entity)
+            .insert(ViewScreenSpaceReflectionsUniformOffset(
+                writer.write(&uniform),
+            ));
+    }
+
+    *previous_view_projections = view_projections;
+}
+
+#[derive(Component)]
+pub struct ScreenSpaceReflectionsTextures {
+    depth_pyramid_texture: CachedTexture,
+    color_history: CachedTexture,
+    pub screen_space_reflections_texture: CachedTexture,
+}
+
+fn prepare_ssr_textures(
+    mut commands: Commands,
+    mut texture_cache: ResMut<TextureCache>,
+    render_device: Res<RenderDevice>,
+    views: Query<(Entity, &ExtractedCamera, &ViewTarget), With<ScreenSpaceReflectionsSettings>>,
+) {
+    for (entity, camera, view_target) in &views {
+        let Some(physical_viewport_size) = camera.physical_viewport_size else {
+            continue;
+        };
+        let size = Extent3d {
+            width: physical_viewport_size.x,
+            height: physical_viewport_size.y,
+            depth_or_array_layers: 1,
+        };
+
+        // Unlike the culling pyramids, the first mip level matches the viewport exactly, so that
+        // the raymarch can step through it one pixel at a time.
+        let depth_pyramid_texture = texture_cache.get(
+            &render_device,
+            depth_pyramid_texture_descriptor("ssr_depth_pyramid_texture", physical_viewport_size),
+        );
+
+        let color_history = texture_cache.get(
+            &render_device,
+            TextureDescriptor {
+                label: Some("ssr_color_history_texture"),
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: view_target.main_texture_format(),
+                usage: TextureUsages::COPY_DST | TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            },
+        );
+
+        let ssr_texture = texture_cache.get(
+            &render_device,
+            TextureDescriptor {
+                label: Some("ssr_texture"),
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Rgba16Float,
+                usage: TextureUsages::STORAGE_BINDING | TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            },
+        );
+
+        commands
+            .entity(entity)
+            .insert(ScreenSpaceReflectionsTextures {
+                depth_pyramid_texture,
+                color_history,
+                screen_space_reflections_texture: ssr_texture,
+            });
+    }
+}
+
+#[derive(Component)]
+struct SsrBindGroups {
+    common_bind_group: BindGroup,
+    depth_pyramid: DepthPyramidBindGroups,
+    raymarch_bind_group: BindGroup,
+}
+
+fn prepare_ssr_bind_groups(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    pipelines: Res<SsrPipelines>,
+    mut depth_pyramid_pipelines: DepthPyramidPipelines,
+    view_uniforms: Res<ViewUniforms>,
+    ssr_buffer: Res<ScreenSpaceReflectionsBuffer>,
+    views: Query<(
+        Entity,
+        &ScreenSpaceReflectionsTextures,
+        &ViewPrepassTextures,
+    )>,
+) {
+    let (Some(view_uniforms), Some(ssr_uniforms)) =
+        (view_uniforms.uniforms.binding(), ssr_buffer.binding())
+    else {
+        return;
+    };
+
+    for (entity, ssr_textures, prepass_textures) in &views {
+        let (Some(depth_view), Some(normal_view)) = (
+            prepass_textures.depth_view(),
+            prepass_textures.normal_view(),
+        ) else {
+            continue;
+        };
+
+        let common_bind_group = render_device.create_bind_group(
+            "ssr_common_bind_group",
+            &pipelines.common_bind_group_layout,
+            &BindGroupEntries::sequential((view_uniforms.clone(), ssr_uniforms.clone())),
+        );
+
+        // The raymarch looks for the closest surface that a ray may hit.
+        let depth_pyramid = depth_pyramid_pipelines.create_bind_groups(
+            DepthPyramidPipelineKey::CLOSEST_DEPTH,
+            depth_view,
+            view_uniforms.clone(),
+            &DepthPyramidViews::new(&ssr_textures.depth_pyramid_texture.texture),
+        );
+
+        let raymarch_bind_group = render_device.create_bind_group(
+            "ssr_raymarch_bind_group",
+            &pipelines.raymarch_bind_group_layout,
+            &BindGroupEntries::sequential((
+                &ssr_textures.depth_pyramid_texture.default_view,
+                normal_view,
+                &ssr_textures.color_history.default_view,
+                &pipelines.linear_clamp_sampler,
+                &ssr_textures.screen_space_reflections_texture.default_view,
+            )),
+        );
+
+        commands.entity(entity).insert(SsrBindGroups {
+            common_bind_group,
+            depth_pyramid,
+            raymarch_bind_group,
+        });
+    }
+}
+
+/// Divide `numerator` by `denominator`, rounded up to the nearest multiple of `denominator`.
+fn div_ceil(numerator: u32, denominator: u32) -> u32 {
+    (numerator + denominator - 1) / denominator
+}
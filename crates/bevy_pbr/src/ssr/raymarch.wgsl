@@ -0,0 +1,221 @@
+// Traces a mirror reflection ray for every pixel through the hierarchical depth pyramid and
+// fetches the color of the surface it hits from the color of the previous frame.
+//
+// The output stores the reflected radiance in `rgb` and how confident the trace is in `a`, with 0.0
+// meaning that the ray missed and the light probe should be used instead.
+//
+// Reference: Yasin Uludag, "Hi-Z Screen-Space Cone-Traced Reflections", GPU Pro 5
+
+#import bevy_render::view::View
+#import bevy_pbr::mesh_view_types::ScreenSpaceReflectionsSettings
+
+@group(0) @binding(0) var depth_pyramid: texture_2d<f32>;
+@group(0) @binding(1) var normal_prepass_texture: texture_2d<f32>;
+@group(0) @binding(2) var color_history: texture_2d<f32>;
+@group(0) @binding(3) var linear_clamp_sampler: sampler;
+@group(0) @binding(4) var reflections: texture_storage_2d<rgba16float, write>;
+@group(1) @binding(0) var<uniform> view: View;
+@group(1) @binding(1) var<uniform> settings: ScreenSpaceReflectionsSettings;
+
+// The view space length of a reflected ray before it's clipped against the screen.
+const MAX_RAY_LENGTH: f32 = 1000.0;
+
+// Used in place of the distance to a boundary along an axis that the ray doesn't move along.
+const FAR_AWAY: f32 = 1e30;
+
+fn ndc_to_uv(ndc: vec2<f32>) -> vec2<f32> {
+    return ndc * vec2(0.5, -0.5) + vec2(0.5);
+}
+
+fn uv_to_ndc(uv: vec2<f32>) -> vec2<f32> {
+    return uv * vec2(2.0, -2.0) + vec2(-1.0, 1.0);
+}
+
+fn is_orthographic() -> bool {
+    return view.projection[3].w == 1.0;
+}
+
+fn depth_ndc_to_view_z(ndc_depth: f32) -> f32 {
+    let view_position = view.inverse_projection * vec4(0.0, 0.0, ndc_depth, 1.0);
+    return view_position.z / view_position.w;
+}
+
+// Projects a view space position to texels of the first MIP of the depth pyramid in `xy`,
+// with the NDC depth in `z`.
+fn view_to_screen(position: vec3<f32>, size: vec2<f32>) -> vec3<f32> {
+    let clip = view.projection * vec4(position, 1.0);
+    let ndc = clip.xyz / clip.w;
+    return vec3(ndc_to_uv(ndc.xy) * size, ndc.z);
+}
+
+// Returns the fraction of `delta` after which a ray starting at `origin` reaches `bounds`, along each axis.
+fn distance_to_bounds(origin: vec2<f32>, delta: vec2<f32>, bounds: vec2<f32>) -> vec2<f32> {
+    return select((bounds - origin) / delta, vec2(FAR_AWAY), abs(delta) < vec2(1e-6));
+}
+
+fn miss(pixel: vec2<u32>) {
+    textureStore(reflections, pixel, vec4(0.0));
+}
+
+@compute
+@workgroup_size(8, 8, 1)
+fn raymarch(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    let size = textureDimensions(reflections);
+    if (any(global_id.xy >= size)) {
+        return;
+    }
+    let screen_size = vec2<f32>(size);
+    let pixel = vec2<i32>(global_id.xy);
+
+    // Nothing was rendered to this pixel, so there's nothing to reflect off.
+    let depth = textureLoad(depth_pyramid, pixel, 0).r;
+    if (depth == 0.0) {
+        miss(global_id.xy);
+        return;
+    }
+
+    let uv = (vec2<f32>(global_id.xy) + 0.5) / screen_size;
+    let view_position_w = view.inverse_projection * vec4(uv_to_ndc(uv), depth, 1.0);
+    let view_position = view_position_w.xyz / view_position_w.w;
+
+    let world_normal = normalize(textureLoad(normal_prepass_texture, pixel, 0).xyz * 2.0 - 1.0);
+    let view_normal = normalize((view.inverse_view * vec4(world_normal, 0.0)).xyz);
+
+    var incident = normalize(view_position);
+    if (is_orthographic()) {
+        incident = vec3(0.0, 0.0, -1.0);
+    }
+    let direction = reflect(incident, view_normal);
+
+    // Rays pointing towards a perspective camera have to stop in front of its near plane, behind
+    // which they can't be projected onto the screen.
+    var ray_length = MAX_RAY_LENGTH;
+    if (!is_orthographic() && direction.z > 0.0) {
+        let near = view.projection[3][2];
+        ray_length = min(ray_length, (-near * 1.01 - view_position.z) / direction.z);
+    }
+    if (ray_length <= 0.0) {
+        miss(global_id.xy);
+        return;
+    }
+
+    let start = view_to_screen(view_position, screen_size);
+    let end = view_to_screen(view_position + direction * ray_length, screen_size);
+    let delta = end - start;
+
+    // Rays that don't leave the pixel can't hit anything else.
+    let texels = max(abs(delta.x), abs(delta.y));
+    if (texels < 1.0) {
+        miss(global_id.xy);
+        return;
+    }
+
+    // Clip the ray against the screen and the depth range.
+    let screen_bounds = select(vec2(0.0), screen_size, delta.xy > vec2(0.0));
+    let t_screen = distance_to_bounds(start.xy, delta.xy, screen_bounds);
+    let t_depth = distance_to_bounds(
+        vec2(start.z),
+        vec2(delta.z),
+        vec2(select(0.0, 1.0, delta.z > 0.0)),
+    ).x;
+    let t_max = min(min(1.0, t_depth), min(t_screen.x, t_screen.y));
+
+    // Start one texel away from the pixel to avoid hitting the surface the ray starts on.
+    var t = 1.0 / texels;
+    var level = 0;
+    let max_level = i32(textureNumLevels(depth_pyramid)) - 1;
+    let towards_positive = delta.xy > vec2(0.0);
+    var hit = false;
+
+    for (var i = 0u; i < settings.max_steps && t < t_max; i += 1u) {
+        let position = start + delta * t;
+        let cell_size = exp2(f32(level));
+        let cell = floor(position.xy / cell_size);
+        let level_size = vec2<i32>(textureDimensions(depth_pyramid, level));
+        let closest_depth = textureLoad(
+            depth_pyramid,
+            clamp(vec2<i32>(cell), vec2(0), level_size - 1),
+            level
+        ).r;
+
+        // The point at which the ray leaves the current cell, nudged slightly into the next one.
+        let boundary = (cell + select(vec2(0.0), vec2(1.0), towards_positive)) * cell_size +
+            select(vec2(-0.01), vec2(0.01), towards_positive);
+        let t_cell = distance_to_bounds(start.xy, delta.xy, boundary);
+        let t_exit = min(t_cell.x, t_cell.y);
+
+        if (position.z > closest_depth) {
+            // The ray is in front of every surface in the cell. If it reaches the closest one
+            // before leaving the cell, refine the search there, otherwise skip the whole cell.
+            var t_surface = t_exit;
+            if (delta.z < 0.0) {
+                t_surface = (closest_depth - start.z) / delta.z;
+            }
+
+            if (t_surface < t_exit) {
+                t = max(t, t_surface);
+                if (level == 0) {
+                    hit = true;
+                    break;
+                }
+                level -= 1;
+            } else {
+                t = t_exit;
+                level = min(level + 1, max_level);
+            }
+        } else if (level > 0) {
+            // The ray is behind the closest surface in the cell, but possibly not behind the others.
+            level -= 1;
+        } else {
+            // The ray is behind the surface in this pixel. Surfaces are assumed to be `thickness`
+            // deep, so the ray hit it unless it's further behind than that.
+            let distance_behind =
+                depth_ndc_to_view_z(closest_depth) - depth_ndc_to_view_z(position.z);
+            if (distance_behind < settings.thickness) {
+                hit = true;
+                break;
+            }
+            t = t_exit;
+        }
+    }
+
+    if (!hit || t > t_max) {
+        miss(global_id.xy);
+        return;
+    }
+
+    let hit_pixel = clamp(vec2<i32>((start + delta * t).xy), vec2(0), vec2<i32>(size) - 1);
+
+    // The back of a surface isn't visible on screen, so its color is unknown.
+    let world_direction = (view.view * vec4(direction, 0.0)).xyz;
+    let hit_normal = textureLoad(normal_prepass_texture, hit_pixel, 0).xyz * 2.0 - 1.0;
+    if (dot(hit_normal, world_direction) > 0.0) {
+        miss(global_id.xy);
+        return;
+    }
+
+    // Reproject the hit into the previous frame, whose color is the only one available before the
+    // main pass.
+    let hit_uv = (vec2<f32>(hit_pixel) + 0.5) / screen_size;
+    let hit_depth = textureLoad(depth_pyramid, hit_pixel, 0).r;
+    let hit_world_position = view.inverse_view_proj * vec4(uv_to_ndc(hit_uv), hit_depth, 1.0);
+    let previous_clip_position =
+        settings.previous_view_proj * vec4(hit_world_position.xyz / hit_world_position.w, 1.0);
+    let previous_uv = ndc_to_uv(previous_clip_position.xy / previous_clip_position.w);
+    if (previous_clip_position.w <= 0.0 ||
+            any(previous_uv < vec2(0.0)) || any(previous_uv > vec2(1.0))) {
+        miss(global_id.xy);
+        return;
+    }
+
+    // The main pass applies the exposure to the indirect light, so undo it here.
+    let color = textureSampleLevel(color_history, linear_clamp_sampler, previous_uv, 0.0).rgb /
+        view.exposure;
+
+    // Fade out hits close to the edges of the screen, where the ray would have soon left the screen
+    // if it had been slightly different.
+    let edge_distance = min(min(hit_uv.x, 1.0 - hit_uv.x), min(hit_uv.y, 1.0 - hit_uv.y));
+    let confidence = saturate(edge_distance / max(settings.edge_fade, 1e-4));
+
+    textureStore(reflections, global_id.xy, vec4(color, confidence));
+}
@@ -0,0 +1,45 @@
+#define_import_path bevy_pbr::ssr
+
+#import bevy_pbr::{
+    lighting::{LightingInput, LAYER_BASE},
+    mesh_view_bindings as view_bindings,
+}
+
+// Returns the specular light of the reflection found by the screen space reflections raymarch
+// in `rgb`, and how much of the light probe's specular light it should replace in `a`.
+fn screen_space_reflections_light(
+    input: ptr<function, LightingInput>,
+    frag_coord: vec2<f32>,
+) -> vec4<f32> {
+    // Unpack.
+    let perceptual_roughness = (*input).layers[LAYER_BASE].perceptual_roughness;
+    let roughness = (*input).layers[LAYER_BASE].roughness;
+    let NdotV = (*input).layers[LAYER_BASE].NdotV;
+    let F0 = (*input).F0_;
+    let F_ab = (*input).F_ab;
+
+    let threshold = view_bindings::screen_space_reflections_settings.perceptual_roughness_threshold;
+    if (perceptual_roughness >= threshold) {
+        return vec4(0.0);
+    }
+
+    let reflection = textureLoad(
+        view_bindings::screen_space_reflections_texture,
+        vec2<i32>(frag_coord),
+        0
+    );
+
+    // The raymarch only traces mirror reflections, so they're faded out on rougher surfaces in
+    // favor of the prefiltered light probe.
+    let roughness_fade = 1.0 - smoothstep(threshold * 0.5, threshold, perceptual_roughness);
+
+    // Use the same split-sum specular term as `environment_map_light`, so that the reflections
+    // blend seamlessly with the light probe.
+    let specular_occlusion = saturate(dot(F0, vec3(50.0 * 0.33)));
+    let Fr = max(vec3(1.0 - roughness), F0) - F0;
+    let kS = F0 + Fr * pow(1.0 - NdotV, 5.0);
+    let Ess = F_ab.x + F_ab.y;
+    let FssEss = kS * Ess * specular_occlusion;
+
+    return vec4(FssEss * reflection.rgb, reflection.a * roughness_fade);
+}
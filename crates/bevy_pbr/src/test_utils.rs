@@ -0,0 +1,123 @@
+//! Helpers to test the extraction and the shaders of the render features of this crate without a
+//! GPU.
+
+use bevy_app::{App, SubApp};
+use bevy_asset::{AssetId, AssetPlugin, Assets, Handle};
+use bevy_core::TaskPoolPlugin;
+use bevy_core_pipeline::CorePipelinePlugin;
+use bevy_render::{
+    render_resource::{Shader, ShaderDefVal, ShaderImport},
+    settings::WgpuSettings,
+    texture::ImagePlugin,
+    ExtractSchedule, MainWorld, RenderApp, RenderPlugin,
+};
+use bevy_utils::HashMap;
+use naga_oil::compose::{Composer, NagaModuleDescriptor, ShaderDefValue};
+
+use crate::PbrPlugin;
+
+/// An app with a render sub-app that only runs the [`ExtractSchedule`], for plugins that only
+/// extract components when added to it.
+pub(crate) fn extract_app() -> App {
+    let mut render_app = SubApp::new();
+    render_app
+        .init_resource::<MainWorld>()
+        .init_schedule(ExtractSchedule)
+        .set_extract(|main_world, render_world| {
+            std::mem::swap(main_world, &mut render_world.resource_mut::<MainWorld>());
+            render_world.run_schedule(ExtractSchedule);
+            std::mem::swap(main_world, &mut render_world.resource_mut::<MainWorld>());
+        });
+
+    let mut app = App::new();
+    app.insert_sub_app(RenderApp, render_app);
+    app
+}
+
+/// An app with the shaders of the [`PbrPlugin`], without a renderer.
+pub(crate) fn shaders_app() -> App {
+    let mut app = App::new();
+    app.add_plugins((
+        TaskPoolPlugin::default(),
+        AssetPlugin::default(),
+        RenderPlugin {
+            render_creation: WgpuSettings {
+                backends: None,
+                ..Default::default()
+            }
+            .into(),
+            synchronous_pipeline_compilation: true,
+        },
+        ImagePlugin::default(),
+        CorePipelinePlugin,
+        PbrPlugin::default(),
+    ));
+    // Some shaders are only loaded once the plugins are finished
+    app.finish();
+    app.cleanup();
+    app
+}
+
+fn shader_def_value(shader_def: &ShaderDefVal) -> (String, ShaderDefValue) {
+    match shader_def {
+        ShaderDefVal::Bool(name, value) => (name.clone(), ShaderDefValue::Bool(*value)),
+        ShaderDefVal::Int(name, value) => (name.clone(), ShaderDefValue::Int(*value)),
+        ShaderDefVal::UInt(name, value) => (name.clone(), ShaderDefValue::UInt(*value)),
+    }
+}
+
+fn add_import(
+    composer: &mut Composer,
+    shaders: &HashMap<&ShaderImport, &Shader>,
+    import: &ShaderImport,
+) {
+    if composer.contains_module(&import.module_name()) {
+        return;
+    }
+    let Some(&shader) = shaders.get(import) else {
+        panic!("shader import {import:?} isn't loaded");
+    };
+    for import in shader.imports() {
+        add_import(composer, shaders, import);
+    }
+    if let Err(error) = composer.add_composable_module(shader.into()) {
+        panic!("{}", error.emit_to_string(composer));
+    }
+}
+
+/// Composes and validates `shader` with `naga_oil` the same way the pipeline cache does, with
+/// `shader_defs` and the definitions that the pipeline cache adds, and panics if it's invalid.
+pub(crate) fn assert_shader_composes(
+    app: &App,
+    shader: &Handle<Shader>,
+    shader_defs: &[ShaderDefVal],
+) {
+    let assets = app.world().resource::<Assets<Shader>>();
+    let shaders: HashMap<_, _> = assets
+        .iter()
+        .map(|(_, shader)| (shader.import_path(), shader))
+        .collect();
+    let shader = assets
+        .get(AssetId::from(shader))
+        .expect("the shader isn't loaded");
+
+    let mut composer = Composer::default();
+    for import in shader.imports() {
+        add_import(&mut composer, &shaders, import);
+    }
+    let result = composer.make_naga_module(NagaModuleDescriptor {
+        shader_defs: shader_defs
+            .iter()
+            .chain(&shader.shader_defs)
+            .chain(&[ShaderDefVal::UInt(
+                "AVAILABLE_STORAGE_BUFFER_BINDINGS".into(),
+                8,
+            )])
+            .map(shader_def_value)
+            .collect(),
+        ..shader.into()
+    });
+    if let Err(error) = result {
+        panic!("{}", error.emit_to_string(&composer));
+    }
+}
@@ -0,0 +1,660 @@
+use crate::{
+    graph::NodePbr, irradiance_volume::IRRADIANCE_VOLUMES_ARE_USABLE, MeshPipeline,
+    MeshPipelineKey, MeshViewBindGroup, ScreenSpaceReflectionsSettings, ShadowFilteringMethod,
+    ViewDecals, ViewFogUniformOffset, ViewLightCookies, ViewLightProbesUniformOffset,
+    ViewLightsUniformOffset, ViewScreenSpaceReflectionsUniformOffset,
+};
+use bevy_app::{App, Plugin};
+use bevy_asset::{load_internal_asset, Handle};
+use bevy_color::{Color, ColorToComponents, LinearRgba};
+use bevy_core_pipeline::{
+    core_3d::{
+        graph::{Core3d, Node3d},
+        Camera3d,
+    },
+    fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    prepass::{DeferredPrepass, DepthPrepass, MotionVectorPrepass, NormalPrepass},
+};
+use bevy_derive::{Deref, DerefMut};
+use bevy_ecs::{
+    prelude::{Bundle, Component, Entity},
+    query::{Has, QueryItem, With},
+    reflect::ReflectComponent,
+    schedule::IntoSystemConfigs,
+    system::{lifetimeless::Read, Commands, Query, Res, ResMut, Resource},
+    world::{FromWorld, World},
+};
+use bevy_math::Vec3;
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use bevy_render::{
+    extract_component::{ExtractComponent, ExtractComponentPlugin},
+    render_graph::{NodeRunError, RenderGraphApp, RenderGraphContext, ViewNode, ViewNodeRunner},
+    render_resource::{binding_types::uniform_buffer, *},
+    renderer::{RenderContext, RenderDevice, RenderQueue},
+    texture::BevyDefault,
+    view::{ExtractedView, Msaa, ViewTarget, ViewUniformOffset},
+    Render, RenderApp, RenderSet,
+};
+use bevy_utils::prelude::default;
+use smallvec::{smallvec, SmallVec};
+
+const VOLUMETRIC_FOG_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(174982360452618);
+
+/// Plugin for volumetric fog and light shafts.
+pub struct VolumetricFogPlugin;
+
+impl Plugin for VolumetricFogPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            VOLUMETRIC_FOG_SHADER_HANDLE,
+            "volumetric_fog.wgsl",
+            Shader::from_wgsl
+        );
+
+        app.register_type::<VolumetricFogSettings>()
+            .register_type::<VolumetricLight>()
+            .add_plugins(ExtractComponentPlugin::<VolumetricFogSettings>::default());
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .init_resource::<SpecializedRenderPipelines<VolumetricFogPipeline>>()
+            .init_resource::<VolumetricFogUniformBuffer>()
+            .add_systems(
+                Render,
+                (
+                    prepare_volumetric_fog_pipelines.in_set(RenderSet::Prepare),
+                    prepare_volumetric_fog_uniforms.in_set(RenderSet::PrepareResources),
+                ),
+            )
+            .add_render_graph_node::<ViewNodeRunner<VolumetricFogNode>>(
+                Core3d,
+                NodePbr::VolumetricFog,
+            )
+            .add_render_graph_edges(
+                Core3d,
+                (
+                    // OIT_RESOLVE -> VOLUMETRIC_FOG -> END_MAIN_PASS
+                    Node3d::OitResolve,
+                    NodePbr::VolumetricFog,
+                    Node3d::EndMainPass,
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app.init_resource::<VolumetricFogPipeline>();
+    }
+}
+
+/// Bundle to apply volumetric fog.
+#[derive(Bundle, Default, Clone)]
+pub struct VolumetricFogBundle {
+    pub settings: VolumetricFogSettings,
+    pub depth_prepass: DepthPrepass,
+}
+
+/// Add this component to a [`DirectionalLight`](crate::DirectionalLight),
+/// [`PointLight`](crate::PointLight) or [`SpotLight`](crate::SpotLight) to make it scatter light
+/// in [`VolumetricFogSettings`], producing light shafts where its shadows cut through the fog.
+///
+/// Lights without this component only contribute to the ambient part of the fog.
+#[derive(Component, Reflect, Clone, Copy, Default, Debug)]
+#[reflect(Component, Default)]
+pub struct VolumetricLight;
+
+/// Component to apply volumetric fog to a 3d camera.
+///
+/// Unlike [`FogSettings`](crate::FogSettings), which only darkens distant objects analytically,
+/// volumetric fog marches each view ray through a participating medium and samples the shadow
+/// maps of every [`VolumetricLight`] along the way. Light is scattered towards the camera only
+/// where it isn't shadowed, which produces light shafts ("god rays") around occluders.
+///
+/// The fog is uniform in density and fills the space between the camera and the closest opaque
+/// surface, up to [`VolumetricFogSettings::max_depth`].
+///
+/// # Usage Notes
+///
+/// Requires that you add the [`DepthPrepass`] component to your camera. Lights only cast light
+/// shafts if they have shadows enabled.
+///
+/// Transparent objects aren't part of the depth prepass, so they're covered by the fog as if they
+/// weren't there.
+#[derive(Component, Reflect, ExtractComponent, Clone, Copy, Debug, PartialEq)]
+#[extract_component_filter(With<Camera3d>)]
+#[reflect(Component, Default)]
+pub struct VolumetricFogSettings {
+    /// The color of the fog, which tints the light scattered by it.
+    pub fog_color: Color,
+    /// The color of the ambient light that the fog scatters uniformly in all directions.
+    pub ambient_color: Color,
+    /// The brightness of the ambient light that the fog scatters.
+    pub ambient_intensity: f32,
+    /// The number of samples taken along each view ray.
+    ///
+    /// More steps reduce banding in the light shafts, at a higher cost.
+    pub step_count: u32,
+    /// The distance from the camera, in world units, after which the fog stops being marched.
+    pub max_depth: f32,
+    /// How much light the fog absorbs per world unit, relative to its [`Self::density`].
+    pub absorption: f32,
+    /// How much light the fog scatters per world unit, relative to its [`Self::density`].
+    pub scattering: f32,
+    /// The density of the fog, which scales both its [`Self::absorption`] and
+    /// [`Self::scattering`].
+    pub density: f32,
+    /// The asymmetry of the Henyey-Greenstein phase function, between -1.0 and 1.0.
+    ///
+    /// Positive values scatter light forwards, making the fog brighter when looking towards a
+    /// light, while negative values scatter it back towards the light. 0.0 scatters light equally
+    /// in all directions.
+    pub scattering_asymmetry: f32,
+    /// The color that tints the light of every [`VolumetricLight`] scattered by the fog.
+    pub light_tint: Color,
+    /// A multiplier for the light of every [`VolumetricLight`] scattered by the fog.
+    pub light_intensity: f32,
+    /// How much the starting point of each ray is randomly offset, from 0.0 to 1.0 of a step.
+    ///
+    /// Jittering trades banding for noise, which temporal anti-aliasing can then smooth out.
+    pub jitter: f32,
+}
+
+impl Default for VolumetricFogSettings {
+    fn default() -> Self {
+        Self {
+            fog_color: Color::WHITE,
+            ambient_color: Color::WHITE,
+            ambient_intensity: 0.1,
+            step_count: 64,
+            max_depth: 25.0,
+            absorption: 0.3,
+            scattering: 0.3,
+            density: 0.1,
+            scattering_asymmetry: 0.5,
+            light_tint: Color::WHITE,
+            light_intensity: 1.0,
+            jitter: 0.0,
+        }
+    }
+}
+
+/// The GPU-side representation of [`VolumetricFogSettings`].
+#[derive(Clone, Copy, ShaderType, Default)]
+pub struct VolumetricFogUniform {
+    fog_color: Vec3,
+    ambient_intensity: f32,
+    ambient_color: Vec3,
+    step_count: u32,
+    light_tint: Vec3,
+    light_intensity: f32,
+    max_depth: f32,
+    absorption: f32,
+    scattering: f32,
+    density: f32,
+    scattering_asymmetry: f32,
+    jitter: f32,
+}
+
+impl From<&VolumetricFogSettings> for VolumetricFogUniform {
+    fn from(settings: &VolumetricFogSettings) -> Self {
+        Self {
+            fog_color: LinearRgba::from(settings.fog_color).to_vec3(),
+            ambient_intensity: settings.ambient_intensity,
+            ambient_color: LinearRgba::from(settings.ambient_color).to_vec3(),
+            step_count: settings.step_count,
+            light_tint: LinearRgba::from(settings.light_tint).to_vec3(),
+            light_intensity: settings.light_intensity,
+            max_depth: settings.max_depth,
+            absorption: settings.absorption,
+            scattering: settings.scattering,
+            density: settings.density,
+            scattering_asymmetry: settings.scattering_asymmetry.clamp(-0.999, 0.999),
+            jitter: settings.jitter,
+        }
+    }
+}
+
+/// The buffer holding the [`VolumetricFogUniform`] of every view with volumetric fog.
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct VolumetricFogUniformBuffer(DynamicUniformBuffer<VolumetricFogUniform>);
+
+/// A component attached to each view in the render world that stores the offset of its
+/// [`VolumetricFogUniform`] in the [`VolumetricFogUniformBuffer`].
+#[derive(Component, Default, Deref, DerefMut)]
+pub struct ViewVolumetricFogUniformOffset(u32);
+
+/// The render pipeline used by a view to draw its volumetric fog.
+#[derive(Component, Deref, DerefMut)]
+pub struct ViewVolumetricFogPipeline(CachedRenderPipelineId);
+
+#[derive(Default)]
+struct VolumetricFogNode;
+
+impl ViewNode for VolumetricFogNode {
+    type ViewQuery = (
+        Read<ViewTarget>,
+        Read<ViewVolumetricFogPipeline>,
+        Read<ViewUniformOffset>,
+        Read<ViewLightsUniformOffset>,
+        Read<ViewFogUniformOffset>,
+        Read<ViewLightProbesUniformOffset>,
+        Option<Read<ViewScreenSpaceReflectionsUniformOffset>>,
+        Read<ViewVolumetricFogUniformOffset>,
+        Read<MeshViewBindGroup>,
+    );
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        (
+            view_target,
+            pipeline_id,
+            view_uniform_offset,
+            view_lights_offset,
+            view_fog_offset,
+            view_light_probes_offset,
+            view_ssr_offset,
+            view_volumetric_fog_offset,
+            mesh_view_bind_group,
+        ): QueryItem<Self::ViewQuery>,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let volumetric_fog_pipeline = world.resource::<VolumetricFogPipeline>();
+        let volumetric_fog_buffer = world.resource::<VolumetricFogUniformBuffer>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let (Some(pipeline), Some(settings_binding)) = (
+            pipeline_cache.get_render_pipeline(**pipeline_id),
+            volumetric_fog_buffer.binding(),
+        ) else {
+            return Ok(());
+        };
+
+        let settings_bind_group = render_context.render_device().create_bind_group(
+            "volumetric_fog_settings_bind_group",
+            &volumetric_fog_pipeline.settings_bind_group_layout,
+            &BindGroupEntries::single(settings_binding),
+        );
+
+        let mut render_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+            label: Some("volumetric_fog_pass"),
+            color_attachments: &[Some(view_target.get_color_attachment())],
+            depth_stencil_attachment: None,
+            timestamp_writes: None,
+            occlusion_query_set: None,
+        });
+
+        render_pass.set_render_pipeline(pipeline);
+        let mut offsets: SmallVec<[u32; 5]> = smallvec![
+            view_uniform_offset.offset,
+            view_lights_offset.offset,
+            view_fog_offset.offset,
+            **view_light_probes_offset,
+        ];
+        if let Some(view_ssr_offset) = view_ssr_offset {
+            offsets.push(**view_ssr_offset);
+        }
+        render_pass.set_bind_group(0, &mesh_view_bind_group.value, &offsets);
+        render_pass.set_bind_group(1, &settings_bind_group, &[**view_volumetric_fog_offset]);
+        render_pass.draw(0..3, 0..1);
+
+        Ok(())
+    }
+}
+
+#[derive(Resource)]
+pub struct VolumetricFogPipeline {
+    mesh_pipeline: MeshPipeline,
+    settings_bind_group_layout: BindGroupLayout,
+}
+
+impl FromWorld for VolumetricFogPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let settings_bind_group_layout = render_device.create_bind_group_layout(
+            "volumetric_fog_settings_bind_group_layout",
+            &BindGroupLayoutEntries::single(
+                ShaderStages::FRAGMENT,
+                uniform_buffer::<VolumetricFogUniform>(true),
+            ),
+        );
+
+        Self {
+            mesh_pipeline: world.resource::<MeshPipeline>().clone(),
+            settings_bind_group_layout,
+        }
+    }
+}
+
+impl SpecializedRenderPipeline for VolumetricFogPipeline {
+    type Key = MeshPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        let shader_defs =
+            volumetric_fog_shader_defs(key, self.mesh_pipeline.binding_arrays_are_usable);
+
+        RenderPipelineDescriptor {
+            label: Some("volumetric_fog_pipeline".into()),
+            layout: vec![
+                self.mesh_pipeline.get_view_layout(key.into()).clone(),
+                self.settings_bind_group_layout.clone(),
+            ],
+            push_constant_ranges: vec![],
+            vertex: fullscreen_shader_vertex_state(),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState {
+                count: key.msaa_samples(),
+                ..default()
+            },
+            fragment: Some(FragmentState {
+                shader: VOLUMETRIC_FOG_SHADER_HANDLE,
+                shader_defs,
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format: if key.contains(MeshPipelineKey::HDR) {
+                        ViewTarget::TEXTURE_FORMAT_HDR
+                    } else {
+                        TextureFormat::bevy_default()
+                    },
+                    // The shader outputs the light scattered towards the camera in `rgb` and the
+                    // transmittance of the fog in `a`, which attenuates what's behind it.
+                    blend: Some(BlendState {
+                        color: BlendComponent {
+                            src_factor: BlendFactor::One,
+                            dst_factor: BlendFactor::SrcAlpha,
+                            operation: BlendOperation::Add,
+                        },
+                        alpha: BlendComponent {
+                            src_factor: BlendFactor::Zero,
+                            dst_factor: BlendFactor::One,
+                            operation: BlendOperation::Add,
+                        },
+                    }),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+        }
+    }
+}
+
+/// The shader defs of the [`VolumetricFogPipeline`] specialized with `key`.
+fn volumetric_fog_shader_defs(
+    key: MeshPipelineKey,
+    binding_arrays_are_usable: bool,
+) -> Vec<ShaderDefVal> {
+    let mut shader_defs = Vec::new();
+
+    if key.msaa_samples() > 1 {
+        shader_defs.push("MULTISAMPLED".into());
+    }
+
+    if key.contains(MeshPipelineKey::DEPTH_PREPASS) {
+        shader_defs.push("DEPTH_PREPASS".into());
+    }
+
+    if key.contains(MeshPipelineKey::NORMAL_PREPASS) {
+        shader_defs.push("NORMAL_PREPASS".into());
+    }
+
+    if key.contains(MeshPipelineKey::MOTION_VECTOR_PREPASS) {
+        shader_defs.push("MOTION_VECTOR_PREPASS".into());
+    }
+
+    if key.contains(MeshPipelineKey::DEFERRED_PREPASS) {
+        shader_defs.push("DEFERRED_PREPASS".into());
+    }
+
+    let shadow_filter_method =
+        key.intersection(MeshPipelineKey::SHADOW_FILTER_METHOD_RESERVED_BITS);
+    if shadow_filter_method == MeshPipelineKey::SHADOW_FILTER_METHOD_HARDWARE_2X2 {
+        shader_defs.push("SHADOW_FILTER_METHOD_HARDWARE_2X2".into());
+    } else if shadow_filter_method == MeshPipelineKey::SHADOW_FILTER_METHOD_GAUSSIAN {
+        shader_defs.push("SHADOW_FILTER_METHOD_GAUSSIAN".into());
+    } else if shadow_filter_method == MeshPipelineKey::SHADOW_FILTER_METHOD_TEMPORAL {
+        shader_defs.push("SHADOW_FILTER_METHOD_TEMPORAL".into());
+    }
+
+    if binding_arrays_are_usable {
+        shader_defs.push("MULTIPLE_LIGHT_PROBES_IN_ARRAY".into());
+    }
+
+    if IRRADIANCE_VOLUMES_ARE_USABLE {
+        shader_defs.push("IRRADIANCE_VOLUMES_ARE_USABLE".into());
+    }
+
+    if key.contains(MeshPipelineKey::LIGHT_COOKIES) {
+        shader_defs.push("LIGHT_COOKIES".into());
+    }
+
+    #[cfg(all(feature = "webgl", target_arch = "wasm32", not(feature = "webgpu")))]
+    shader_defs.push("WEBGL2".into());
+
+    #[cfg(all(feature = "webgl", target_arch = "wasm32", not(feature = "webgpu")))]
+    shader_defs.push("SIXTEEN_BYTE_ALIGNMENT".into());
+
+    shader_defs
+}
+
+/// Specializes the [`VolumetricFogPipeline`] of every view with volumetric fog.
+pub fn prepare_volumetric_fog_pipelines(
+    mut commands: Commands,
+    pipeline_cache: Res<PipelineCache>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<VolumetricFogPipeline>>,
+    volumetric_fog_pipeline: Res<VolumetricFogPipeline>,
+    msaa: Res<Msaa>,
+    views: Query<
+        (
+            Entity,
+            &ExtractedView,
+            Option<&ShadowFilteringMethod>,
+            (
+                Has<NormalPrepass>,
+                Has<DepthPrepass>,
+                Has<MotionVectorPrepass>,
+                Has<DeferredPrepass>,
+            ),
+            (
+                Has<ScreenSpaceReflectionsSettings>,
+                Has<ViewDecals>,
+                Has<ViewLightCookies>,
+            ),
+        ),
+        With<VolumetricFogSettings>,
+    >,
+) {
+    for (
+        entity,
+        view,
+        shadow_filter_method,
+        (normal_prepass, depth_prepass, motion_vector_prepass, deferred_prepass),
+        (ssr, decals, light_cookies),
+    ) in &views
+    {
+        // The fog needs the depth of the opaque surfaces to know where to stop.
+        if !depth_prepass {
+            continue;
+        }
+
+        let mut key = MeshPipelineKey::from_msaa_samples(msaa.samples())
+            | MeshPipelineKey::from_hdr(view.hdr)
+            | MeshPipelineKey::DEPTH_PREPASS;
+
+        if normal_prepass {
+            key |= MeshPipelineKey::NORMAL_PREPASS;
+        }
+
+        if motion_vector_prepass {
+            key |= MeshPipelineKey::MOTION_VECTOR_PREPASS;
+        }
+
+        if deferred_prepass {
+            key |= MeshPipelineKey::DEFERRED_PREPASS;
+        }
+
+        // Only needed so that the layout matches the mesh view bind group of the view.
+        if ssr {
+            key |= MeshPipelineKey::SCREEN_SPACE_REFLECTIONS;
+        }
+        if decals {
+            key |= MeshPipelineKey::DECALS;
+        }
+        if light_cookies {
+            key |= MeshPipelineKey::LIGHT_COOKIES;
+        }
+
+        key |= match shadow_filter_method.unwrap_or(&ShadowFilteringMethod::default()) {
+            ShadowFilteringMethod::Hardware2x2 => {
+                MeshPipelineKey::SHADOW_FILTER_METHOD_HARDWARE_2X2
+            }
+            ShadowFilteringMethod::Gaussian => MeshPipelineKey::SHADOW_FILTER_METHOD_GAUSSIAN,
+            ShadowFilteringMethod::Temporal => MeshPipelineKey::SHADOW_FILTER_METHOD_TEMPORAL,
+        };
+
+        let pipeline_id = pipelines.specialize(&pipeline_cache, &volumetric_fog_pipeline, key);
+
+        commands
+            .entity(entity)
+            .insert(ViewVolumetricFogPipeline(pipeline_id));
+    }
+}
+
+/// Writes the [`VolumetricFogUniform`] of every view with volumetric fog.
+pub fn prepare_volumetric_fog_uniforms(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    mut volumetric_fog_buffer: ResMut<VolumetricFogUniformBuffer>,
+    views: Query<(Entity, &VolumetricFogSettings)>,
+) {
+    let Some(mut writer) =
+        volumetric_fog_buffer.get_writer(views.iter().len(), &render_device, &render_queue)
+    else {
+        return;
+    };
+
+    for (entity, settings) in &views {
+        let uniform = VolumetricFogUniform::from(settings);
+
+        commands
+            .entity(entity)
+            .insert(ViewVolumetricFogUniformOffset(writer.write(&uniform)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{assert_shader_composes, extract_app, shaders_app};
+    use bevy_color::palettes::css::RED;
+    use bevy_render::render_resource::ShaderDefVal;
+
+    fn names(shader_defs: &[ShaderDefVal]) -> Vec<&str> {
+        shader_defs
+            .iter()
+            .map(|shader_def| match shader_def {
+                ShaderDefVal::Bool(name, _)
+                | ShaderDefVal::Int(name, _)
+                | ShaderDefVal::UInt(name, _) => name.as_str(),
+            })
+            .filter(|name| *name != "IRRADIANCE_VOLUMES_ARE_USABLE")
+            .collect()
+    }
+
+    #[test]
+    fn settings_are_extracted_for_3d_cameras() {
+        let mut app = extract_app();
+        app.add_plugins(ExtractComponentPlugin::<VolumetricFogSettings>::default());
+        let settings = VolumetricFogSettings {
+            step_count: 16,
+            ..default()
+        };
+        let camera = app.world_mut().spawn((Camera3d::default(), settings)).id();
+        let not_a_camera = app.world_mut().spawn(settings).id();
+        app.update();
+
+        let render_world = app.sub_app(RenderApp).world();
+        assert_eq!(
+            render_world.get::<VolumetricFogSettings>(camera),
+            Some(&settings)
+        );
+        assert!(render_world
+            .get_entity(not_a_camera)
+            .and_then(|entity| entity.get::<VolumetricFogSettings>())
+            .is_none());
+    }
+
+    #[test]
+    fn uniform_is_linear_with_a_clamped_asymmetry() {
+        let uniform = VolumetricFogUniform::from(&VolumetricFogSettings {
+            fog_color: RED.into(),
+            scattering_asymmetry: 1.0,
+            ..default()
+        });
+        assert_eq!(uniform.fog_color, Vec3::X);
+        assert_eq!(uniform.ambient_color, Vec3::ONE);
+        assert_eq!(uniform.scattering_asymmetry, 0.999);
+    }
+
+    #[test]
+    fn shader_defs_follow_the_pipeline_key() {
+        let key = MeshPipelineKey::DEPTH_PREPASS | MeshPipelineKey::from_msaa_samples(1);
+        assert_eq!(
+            names(&volumetric_fog_shader_defs(key, false)),
+            ["DEPTH_PREPASS", "SHADOW_FILTER_METHOD_HARDWARE_2X2"]
+        );
+
+        let key = MeshPipelineKey::DEPTH_PREPASS
+            | MeshPipelineKey::NORMAL_PREPASS
+            | MeshPipelineKey::MOTION_VECTOR_PREPASS
+            | MeshPipelineKey::DEFERRED_PREPASS
+            | MeshPipelineKey::LIGHT_COOKIES
+            | MeshPipelineKey::SHADOW_FILTER_METHOD_TEMPORAL
+            | MeshPipelineKey::from_msaa_samples(4);
+        assert_eq!(
+            names(&volumetric_fog_shader_defs(key, true)),
+            [
+                "MULTISAMPLED",
+                "DEPTH_PREPASS",
+                "NORMAL_PREPASS",
+                "MOTION_VECTOR_PREPASS",
+                "DEFERRED_PREPASS",
+                "SHADOW_FILTER_METHOD_TEMPORAL",
+                "MULTIPLE_LIGHT_PROBES_IN_ARRAY",
+                "LIGHT_COOKIES",
+            ]
+        );
+
+        let key = MeshPipelineKey::DEPTH_PREPASS | MeshPipelineKey::SHADOW_FILTER_METHOD_GAUSSIAN;
+        assert!(names(&volumetric_fog_shader_defs(key, false))
+            .contains(&"SHADOW_FILTER_METHOD_GAUSSIAN"));
+    }
+
+    #[test]
+    fn shader_composes_for_every_setting() {
+        let app = shaders_app();
+        let base = MeshPipelineKey::DEPTH_PREPASS | MeshPipelineKey::HDR;
+        for key in [
+            base | MeshPipelineKey::from_msaa_samples(1),
+            base | MeshPipelineKey::from_msaa_samples(4),
+            base | MeshPipelineKey::NORMAL_PREPASS | MeshPipelineKey::MOTION_VECTOR_PREPASS,
+            base | MeshPipelineKey::DEFERRED_PREPASS,
+            base | MeshPipelineKey::SHADOW_FILTER_METHOD_GAUSSIAN,
+            base | MeshPipelineKey::SHADOW_FILTER_METHOD_TEMPORAL,
+            base | MeshPipelineKey::LIGHT_COOKIES,
+        ] {
+            assert_shader_composes(
+                &app,
+                &VOLUMETRIC_FOG_SHADER_HANDLE,
+                &volumetric_fog_shader_defs(key, false),
+            );
+        }
+    }
+}
@@ -0,0 +1,187 @@
+// Volumetric fog and light shafts.
+//
+// Each view ray is marched from the camera to the closest opaque surface in the depth prepass,
+// through a homogeneous participating medium. At every step, the light of each volumetric light
+// is attenuated by its shadow map and scattered towards the camera according to the
+// Henyey-Greenstein phase function, while the medium absorbs and scatters away the light behind it.
+//
+// The output holds the in-scattered light in `rgb` and the transmittance of the fog in `a`, which
+// the blend state uses to attenuate the color that's already in the main texture.
+
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+#import bevy_pbr::{
+    clustered_forward as clustering,
+    lighting::getDistanceAttenuation,
+    mesh_view_bindings::{globals, lights, point_lights, view},
+    mesh_view_types::{
+        DIRECTIONAL_LIGHT_FLAGS_SHADOWS_ENABLED_BIT, DIRECTIONAL_LIGHT_FLAGS_VOLUMETRIC_BIT,
+        POINT_LIGHT_FLAGS_SHADOWS_ENABLED_BIT, POINT_LIGHT_FLAGS_SPOT_LIGHT_Y_NEGATIVE,
+        POINT_LIGHT_FLAGS_VOLUMETRIC_BIT,
+    },
+    prepass_utils::prepass_depth,
+    shadows,
+    utils::interleaved_gradient_noise,
+    view_transformations::{frag_coord_to_ndc, position_ndc_to_view, position_view_to_world},
+}
+#import bevy_render::maths::PI
+
+struct VolumetricFog {
+    fog_color: vec3<f32>,
+    ambient_intensity: f32,
+    ambient_color: vec3<f32>,
+    step_count: u32,
+    light_tint: vec3<f32>,
+    light_intensity: f32,
+    max_depth: f32,
+    absorption: f32,
+    scattering: f32,
+    density: f32,
+    scattering_asymmetry: f32,
+    jitter: f32,
+}
+
+@group(1) @binding(0) var<uniform> volumetric_fog: VolumetricFog;
+
+// https://www.pbr-book.org/4ed/Volume_Scattering/Phase_Functions#TheHenyeyndashGreensteinPhaseFunction
+fn henyey_greenstein(cos_theta: f32, g: f32) -> f32 {
+    let denom = 1.0 + g * g - 2.0 * g * cos_theta;
+    return (1.0 - g * g) / (4.0 * PI * denom * sqrt(max(denom, 1e-4)));
+}
+
+fn is_orthographic() -> bool {
+    return view.projection[3].w == 1.0;
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let frag_coord = in.position;
+    let depth = prepass_depth(frag_coord, 0u);
+
+    // Find where the ray enters the view frustum, and in which direction it travels.
+    let near_position = position_ndc_to_view(frag_coord_to_ndc(vec4(frag_coord.xy, 1.0, 1.0)));
+    var ray_origin = vec3(0.0);
+    var ray_direction = normalize(near_position);
+    if (is_orthographic()) {
+        ray_origin = near_position;
+        ray_direction = vec3(0.0, 0.0, -1.0);
+    }
+
+    // Stop at the closest opaque surface, if there is one, or at the maximum depth.
+    var ray_length = volumetric_fog.max_depth;
+    if (depth != 0.0) {
+        let surface_position = position_ndc_to_view(frag_coord_to_ndc(vec4(frag_coord.xy, depth, 1.0)));
+        ray_length = min(ray_length, distance(ray_origin, surface_position));
+    }
+
+    let step_count = max(volumetric_fog.step_count, 1u);
+    let step_size = ray_length / f32(step_count);
+    let jitter = interleaved_gradient_noise(frag_coord.xy, globals.frame_count) *
+        volumetric_fog.jitter;
+
+    let scattering_coefficient = volumetric_fog.density * volumetric_fog.scattering;
+    let extinction_coefficient = volumetric_fog.density *
+        (volumetric_fog.absorption + volumetric_fog.scattering);
+    let step_transmittance = exp(-extinction_coefficient * step_size);
+
+    let world_ray_direction = normalize((view.inverse_view * vec4(ray_direction, 0.0)).xyz);
+    let light_color_scale = volumetric_fog.light_tint * volumetric_fog.light_intensity;
+
+    var transmittance = 1.0;
+    var inscattered_light = vec3(0.0);
+
+    for (var step_index = 0u; step_index < step_count; step_index += 1u) {
+        let view_position = ray_origin + ray_direction * (f32(step_index) + 0.5 + jitter) * step_size;
+        let world_position = vec4(position_view_to_world(view_position), 1.0);
+        let view_z = view_position.z;
+
+        // Ambient light is scattered equally in all directions, so the phase function integrates
+        // to one over the sphere.
+        var light = volumetric_fog.ambient_color * volumetric_fog.ambient_intensity;
+
+        // Directional lights
+        for (var light_id = 0u; light_id < lights.n_directional_lights; light_id += 1u) {
+            let directional_light = &lights.directional_lights[light_id];
+            if (((*directional_light).flags & DIRECTIONAL_LIGHT_FLAGS_VOLUMETRIC_BIT) == 0u ||
+                    ((*directional_light).render_layers & view.render_layers) == 0u) {
+                continue;
+            }
+
+            var shadow = 1.0;
+            if (((*directional_light).flags & DIRECTIONAL_LIGHT_FLAGS_SHADOWS_ENABLED_BIT) != 0u) {
+                shadow = shadows::fetch_directional_shadow(light_id, world_position, vec3(0.0), view_z);
+            }
+
+            let phase = henyey_greenstein(
+                dot(world_ray_direction, (*directional_light).direction_to_light),
+                volumetric_fog.scattering_asymmetry
+            );
+            light += (*directional_light).color.rgb * light_color_scale * shadow * phase;
+        }
+
+        // Point and spot lights, from the cluster the step is in.
+        let cluster_index = clustering::fragment_cluster_index(frag_coord.xy, view_z, is_orthographic());
+        let offset_and_counts = clustering::unpack_offset_and_counts(cluster_index);
+        let point_lights_end = offset_and_counts[0] + offset_and_counts[1];
+        let spot_lights_end = point_lights_end + offset_and_counts[2];
+        for (var i = offset_and_counts[0]; i < spot_lights_end; i += 1u) {
+            let light_id = clustering::get_light_id(i);
+            let point_light = &point_lights.data[light_id];
+            if (((*point_light).flags & POINT_LIGHT_FLAGS_VOLUMETRIC_BIT) == 0u) {
+                continue;
+            }
+
+            let to_light = (*point_light).position_radius.xyz - world_position.xyz;
+            let distance_attenuation = getDistanceAttenuation(
+                dot(to_light, to_light),
+                (*point_light).color_inverse_square_range.w
+            );
+            if (distance_attenuation <= 0.0) {
+                continue;
+            }
+            let direction_to_light = normalize(to_light);
+
+            var attenuation = distance_attenuation;
+            var shadow = 1.0;
+            if (i < point_lights_end) {
+                if (((*point_light).flags & POINT_LIGHT_FLAGS_SHADOWS_ENABLED_BIT) != 0u) {
+                    shadow = shadows::fetch_point_shadow(light_id, world_position, vec3(0.0));
+                }
+            } else {
+                // Reconstruct the direction of the spot light, like `lighting::spot_light` does.
+                var spot_direction = vec3((*point_light).light_custom_data.x, 0.0, (*point_light).light_custom_data.y);
+                spot_direction.y = sqrt(max(0.0, 1.0 - dot(spot_direction.xz, spot_direction.xz)));
+                if (((*point_light).flags & POINT_LIGHT_FLAGS_SPOT_LIGHT_Y_NEGATIVE) != 0u) {
+                    spot_direction.y = -spot_direction.y;
+                }
+                let cone = saturate(dot(-spot_direction, direction_to_light) *
+                    (*point_light).light_custom_data.z + (*point_light).light_custom_data.w);
+                attenuation *= cone * cone;
+
+                if (attenuation > 0.0 &&
+                        ((*point_light).flags & POINT_LIGHT_FLAGS_SHADOWS_ENABLED_BIT) != 0u) {
+                    shadow = shadows::fetch_spot_shadow(light_id, world_position, vec3(0.0));
+                }
+            }
+
+            let phase = henyey_greenstein(
+                dot(world_ray_direction, direction_to_light),
+                volumetric_fog.scattering_asymmetry
+            );
+            light += (*point_light).color_inverse_square_range.rgb * light_color_scale *
+                attenuation * shadow * phase;
+        }
+
+        // Integrate the light scattered within the step analytically, which stays energy
+        // conserving regardless of the step size.
+        // https://www.ea.com/frostbite/news/physically-based-unified-volumetric-rendering-in-frostbite
+        let scattered = volumetric_fog.fog_color * scattering_coefficient * light;
+        var integrated = scattered * step_size;
+        if (extinction_coefficient > 0.0) {
+            integrated = (scattered - scattered * step_transmittance) / extinction_coefficient;
+        }
+        inscattered_light += transmittance * integrated;
+        transmittance *= step_transmittance;
+    }
+
+    return vec4(inscattered_light * view.exposure, transmittance);
+}
@@ -0,0 +1,157 @@
+//! The interface between picking backends and the rest of the picking pipeline.
+//!
+//! A backend finds the entities under each pointer and reports them with [`PointerHits`] events
+//! sent in [`PickSet::Backend`](crate::PickSet::Backend). Backends only decide what is hit, and
+//! [`update_focus`](crate::focus::update_focus) merges the hits of all the backends to find which
+//! entities are hovered.
+//!
+//! Backends that cast rays into the world can use the [`RayMap`](ray::RayMap), which holds a ray
+//! for each pair of camera and pointer over its viewport.
+
+use bevy_ecs::prelude::*;
+use bevy_math::Vec3;
+use bevy_reflect::prelude::*;
+
+use crate::pointer::PointerId;
+
+/// The entities a backend found under a pointer.
+#[derive(Event, Debug, Clone, Reflect)]
+pub struct PointerHits {
+    /// The pointer the entities are under.
+    pub pointer: PointerId,
+    /// The entities that were hit, with where they were hit. Entities that block the ones behind
+    /// them should be the last entities reported, see
+    /// [`PickingBehavior::should_block_lower`](crate::PickingBehavior::should_block_lower).
+    pub picks: Vec<(Entity, HitData)>,
+    /// The order of these hits relative to the hits of other cameras and backends, higher
+    /// orders being in front. Backends usually use the [`Camera::order`] of the camera the hits
+    /// were found with, and UI backends add a small offset to be in front of the world rendered
+    /// by the same camera.
+    ///
+    /// [`Camera::order`]: bevy_render::camera::Camera::order
+    pub order: f32,
+}
+
+impl PointerHits {
+    /// Creates the hits of a pointer.
+    pub fn new(pointer: PointerId, picks: Vec<(Entity, HitData)>, order: f32) -> Self {
+        Self {
+            pointer,
+            picks,
+            order,
+        }
+    }
+}
+
+/// Where an entity was hit by a pointer.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(PartialEq)]
+pub struct HitData {
+    /// The camera the entity was hit through.
+    pub camera: Entity,
+    /// The distance from the camera to the hit, used to sort the hits of the same order. It
+    /// doesn't need to be a world space distance, but must be consistent within a backend.
+    pub depth: f32,
+    /// The position of the hit in world space, if the backend knows it.
+    pub position: Option<Vec3>,
+    /// The normal of the surface at the hit in world space, if the backend knows it.
+    pub normal: Option<Vec3>,
+}
+
+impl HitData {
+    /// Creates the data of a hit.
+    pub fn new(camera: Entity, depth: f32, position: Option<Vec3>, normal: Option<Vec3>) -> Self {
+        Self {
+            camera,
+            depth,
+            position,
+            normal,
+        }
+    }
+}
+
+pub mod ray {
+    //! Rays cast from the pointers through the cameras, shared by the backends picking in world
+    //! space.
+
+    use bevy_ecs::prelude::*;
+    use bevy_math::Ray3d;
+    use bevy_reflect::Reflect;
+    use bevy_render::camera::Camera;
+    use bevy_transform::components::GlobalTransform;
+    use bevy_utils::HashMap;
+    use bevy_window::PrimaryWindow;
+
+    use crate::pointer::{PointerId, PointerLocation};
+
+    /// Identifies the ray cast from a pointer through a camera.
+    #[derive(Clone, Copy, Debug, Eq, PartialEq, Hash, Reflect)]
+    pub struct RayId {
+        /// The camera the ray is cast through.
+        pub camera: Entity,
+        /// The pointer the ray is cast from.
+        pub pointer: PointerId,
+    }
+
+    impl RayId {
+        /// Creates the id of a ray.
+        pub fn new(camera: Entity, pointer: PointerId) -> Self {
+            Self { camera, pointer }
+        }
+    }
+
+    /// The rays cast from every pointer through every active camera whose viewport the pointer
+    /// is over, updated in [`PickSet::ProcessInput`](crate::PickSet::ProcessInput).
+    #[derive(Clone, Debug, Default, Resource)]
+    pub struct RayMap {
+        map: HashMap<RayId, Ray3d>,
+    }
+
+    impl RayMap {
+        /// Iterates over the rays.
+        pub fn iter(&self) -> impl Iterator<Item = (&RayId, &Ray3d)> {
+            self.map.iter()
+        }
+
+        /// Returns the map of the rays.
+        pub fn map(&self) -> &HashMap<RayId, Ray3d> {
+            &self.map
+        }
+
+        /// Casts a ray from each pointer through each active camera whose viewport it is over.
+        pub fn repopulate(
+            mut ray_map: ResMut<Self>,
+            primary_window: Query<Entity, With<PrimaryWindow>>,
+            cameras: Query<(Entity, &Camera, &GlobalTransform)>,
+            pointers: Query<(&PointerId, &PointerLocation)>,
+        ) {
+            ray_map.map.clear();
+            let primary_window = primary_window.get_single().ok();
+            for (camera_entity, camera, camera_transform) in &cameras {
+                if !camera.is_active {
+                    continue;
+                }
+                for (&pointer_id, location) in &pointers {
+                    let Some(location) = location
+                        .location()
+                        .filter(|location| location.is_in_viewport(camera, primary_window))
+                    else {
+                        continue;
+                    };
+                    // `viewport_to_world` expects a position relative to the viewport
+                    let viewport_min = camera
+                        .logical_viewport_rect()
+                        .map(|viewport| viewport.min)
+                        .unwrap_or_default();
+                    if let Some(ray) =
+                        camera.viewport_to_world(camera_transform, location.position - viewport_min)
+                    {
+                        ray_map
+                            .map
+                            .insert(RayId::new(camera_entity, pointer_id), ray);
+                    }
+                }
+            }
+        }
+    }
+}
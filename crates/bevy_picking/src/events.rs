@@ -0,0 +1,777 @@
+//! The events sent when pointers interact with entities, see [`Pointer`].
+//!
+//! Events are sent in [`PickSet::Events`](crate::PickSet::Events), in this order for each frame:
+//! - [`Out`] and [`DragLeave`] for the entities the pointers stopped hovering,
+//! - [`Over`] and [`DragEnter`] for the entities the pointers started hovering,
+//! - the events of each [`PointerInput`] in the order they were sent: [`Down`], then [`Up`],
+//!   [`Click`], [`Drop`], [`DragEnd`] and [`DragLeave`] when a button is released, and [`Move`],
+//!   [`DragStart`], [`Drag`] and [`DragOver`] when a pointer moves.
+//!
+//! Each event bubbles up the hierarchy: it is sent once for its target, then once for each of
+//! the target's ancestors with [`Pointer::listener`] set to that ancestor.
+
+use std::{fmt::Debug, time::Duration};
+
+use bevy_ecs::{prelude::*, system::SystemParam};
+use bevy_hierarchy::Parent;
+use bevy_math::Vec2;
+use bevy_reflect::prelude::*;
+use bevy_time::Time;
+use bevy_utils::HashMap;
+
+use crate::{
+    backend::HitData,
+    focus::{HoverMap, PreviousHoverMap},
+    pointer::{
+        Location, PointerAction, PointerButton, PointerId, PointerInput, PointerLocation,
+        PressDirection,
+    },
+};
+
+/// An event sent when a pointer interacts with an entity, wrapping one of the event payloads
+/// of this module such as [`Click`], which it dereferences to.
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct Pointer<E: Debug + Clone + Reflect> {
+    /// The entity the pointer interacted with.
+    pub target: Entity,
+    /// The entity this event is sent for: the target, or one of its ancestors the event bubbled
+    /// up to.
+    pub listener: Entity,
+    /// The pointer that interacted with the target.
+    pub pointer_id: PointerId,
+    /// Where the pointer was.
+    pub pointer_location: Location,
+    /// The payload of the event.
+    pub event: E,
+}
+
+impl<E: Debug + Clone + Reflect> std::ops::Deref for Pointer<E> {
+    type Target = E;
+
+    fn deref(&self) -> &Self::Target {
+        &self.event
+    }
+}
+
+/// A pointer started hovering an entity.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct Over {
+    /// Where the entity was hit.
+    pub hit: HitData,
+}
+
+/// A pointer stopped hovering an entity.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct Out {
+    /// Where the entity was last hit.
+    pub hit: HitData,
+}
+
+/// A button was pressed over an entity.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct Down {
+    /// The pressed button.
+    pub button: PointerButton,
+    /// Where the entity was hit.
+    pub hit: HitData,
+}
+
+/// A button was released over an entity.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct Up {
+    /// The released button.
+    pub button: PointerButton,
+    /// Where the entity was hit.
+    pub hit: HitData,
+}
+
+/// A button was pressed then released over the same entity.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct Click {
+    /// The clicked button.
+    pub button: PointerButton,
+    /// Where the entity was hit when the button was released.
+    pub hit: HitData,
+    /// How long the button was held down.
+    pub duration: Duration,
+}
+
+/// A pointer moved over an entity.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct Move {
+    /// Where the entity was hit.
+    pub hit: HitData,
+    /// How far the pointer moved.
+    pub delta: Vec2,
+}
+
+/// A pointer started dragging an entity, by moving while a button pressed over it is held down.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct DragStart {
+    /// The held button.
+    pub button: PointerButton,
+    /// Where the entity was hit when the button was pressed.
+    pub hit: HitData,
+}
+
+/// A pointer dragging an entity moved.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct Drag {
+    /// The held button.
+    pub button: PointerButton,
+    /// How far the pointer moved since the drag started.
+    pub distance: Vec2,
+    /// How far the pointer moved since the last [`Drag`] event.
+    pub delta: Vec2,
+}
+
+/// A pointer stopped dragging an entity.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct DragEnd {
+    /// The released button.
+    pub button: PointerButton,
+    /// How far the pointer moved since the drag started.
+    pub distance: Vec2,
+}
+
+/// A pointer dragging an entity started hovering another entity, the target of this event.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct DragEnter {
+    /// The held button.
+    pub button: PointerButton,
+    /// The dragged entity.
+    pub dragged: Entity,
+    /// Where the target was hit.
+    pub hit: HitData,
+}
+
+/// A pointer dragging an entity moved over another entity, the target of this event.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct DragOver {
+    /// The held button.
+    pub button: PointerButton,
+    /// The dragged entity.
+    pub dragged: Entity,
+    /// Where the target was hit.
+    pub hit: HitData,
+}
+
+/// A pointer dragging an entity stopped hovering another entity, the target of this event, or
+/// stopped dragging over it.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct DragLeave {
+    /// The held button.
+    pub button: PointerButton,
+    /// The dragged entity.
+    pub dragged: Entity,
+    /// Where the target was last hit.
+    pub hit: HitData,
+}
+
+/// A pointer dragging an entity released its button over another entity, the target of this
+/// event.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+pub struct Drop {
+    /// The released button.
+    pub button: PointerButton,
+    /// The dropped entity.
+    pub dropped: Entity,
+    /// Where the target was hit.
+    pub hit: HitData,
+}
+
+/// The writers of all the [`Pointer`] events.
+#[derive(SystemParam)]
+pub struct PointerEventWriters<'w> {
+    over: EventWriter<'w, Pointer<Over>>,
+    out: EventWriter<'w, Pointer<Out>>,
+    down: EventWriter<'w, Pointer<Down>>,
+    up: EventWriter<'w, Pointer<Up>>,
+    click: EventWriter<'w, Pointer<Click>>,
+    moved: EventWriter<'w, Pointer<Move>>,
+    drag_start: EventWriter<'w, Pointer<DragStart>>,
+    drag: EventWriter<'w, Pointer<Drag>>,
+    drag_end: EventWriter<'w, Pointer<DragEnd>>,
+    drag_enter: EventWriter<'w, Pointer<DragEnter>>,
+    drag_over: EventWriter<'w, Pointer<DragOver>>,
+    drag_leave: EventWriter<'w, Pointer<DragLeave>>,
+    drop: EventWriter<'w, Pointer<Drop>>,
+}
+
+/// The state [`pointer_events`] keeps between frames.
+#[derive(Debug, Default)]
+pub struct PointerState {
+    /// The last known location of each pointer.
+    locations: HashMap<PointerId, Location>,
+    /// The entities each button was pressed over, with when they were pressed.
+    pressed: HashMap<(PointerId, PointerButton), HashMap<Entity, (Duration, HitData)>>,
+    /// The entities each button is dragging.
+    dragging: HashMap<(PointerId, PointerButton), HashMap<Entity, DragEntry>>,
+    /// The entities each button is dragging over.
+    dragging_over: HashMap<(PointerId, PointerButton), HashMap<Entity, HitData>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct DragEntry {
+    start: Vec2,
+    latest: Vec2,
+}
+
+/// Sends an event for its target, then for each of the target's ancestors.
+fn send_bubbled<E: Debug + Clone + Reflect>(
+    writer: &mut EventWriter<Pointer<E>>,
+    parents: &Query<&Parent>,
+    target: Entity,
+    pointer_id: PointerId,
+    location: &Location,
+    event: E,
+) {
+    let mut listener = Some(target);
+    while let Some(entity) = listener {
+        writer.send(Pointer {
+            target,
+            listener: entity,
+            pointer_id,
+            pointer_location: location.clone(),
+            event: event.clone(),
+        });
+        listener = parents.get(entity).ok().map(Parent::get);
+    }
+}
+
+/// Sends the [`Pointer`] events from the changes of the [`HoverMap`] and the [`PointerInput`]s
+/// of this frame.
+#[allow(clippy::too_many_arguments)]
+pub fn pointer_events(
+    mut input_events: EventReader<PointerInput>,
+    pointers: Query<(&PointerId, &PointerLocation)>,
+    parents: Query<&Parent>,
+    hover_map: Res<HoverMap>,
+    previous_hover_map: Res<PreviousHoverMap>,
+    time: Res<Time>,
+    mut state: Local<PointerState>,
+    mut writers: PointerEventWriters,
+) {
+    let now = time.elapsed();
+    let state = &mut *state;
+    for (&pointer_id, location) in &pointers {
+        if let Some(location) = location.location() {
+            state.locations.insert(pointer_id, location.clone());
+        }
+    }
+
+    // Entities the pointers stopped hovering
+    for (&pointer_id, previous) in previous_hover_map.iter() {
+        let Some(location) = state.locations.get(&pointer_id) else {
+            continue;
+        };
+        let hovered = hover_map.get(&pointer_id);
+        for (&target, &hit) in previous {
+            if hovered.is_some_and(|hovered| hovered.contains_key(&target)) {
+                continue;
+            }
+            let event = Out { hit };
+            send_bubbled(
+                &mut writers.out,
+                &parents,
+                target,
+                pointer_id,
+                location,
+                event,
+            );
+            for button in PointerButton::iter() {
+                let key = (pointer_id, button);
+                let Some(dragged) = state.dragging.get(&key) else {
+                    continue;
+                };
+                let Some(dragging_over) = state.dragging_over.get_mut(&key) else {
+                    continue;
+                };
+                if dragging_over.remove(&target).is_none() {
+                    continue;
+                }
+                for &dragged in dragged.keys() {
+                    let event = DragLeave {
+                        button,
+                        dragged,
+                        hit,
+                    };
+                    let writer = &mut writers.drag_leave;
+                    send_bubbled(writer, &parents, target, pointer_id, location, event);
+                }
+            }
+        }
+    }
+
+    // Entities the pointers started hovering
+    for (&pointer_id, hovered) in hover_map.iter() {
+        let Some(location) = state.locations.get(&pointer_id) else {
+            continue;
+        };
+        let previous = previous_hover_map.get(&pointer_id);
+        for (&target, &hit) in hovered {
+            if previous.is_some_and(|previous| previous.contains_key(&target)) {
+                continue;
+            }
+            let event = Over { hit };
+            send_bubbled(
+                &mut writers.over,
+                &parents,
+                target,
+                pointer_id,
+                location,
+                event,
+            );
+            for button in PointerButton::iter() {
+                let key = (pointer_id, button);
+                let Some(dragged) = state.dragging.get(&key) else {
+                    continue;
+                };
+                if dragged.is_empty() || dragged.contains_key(&target) {
+                    continue;
+                }
+                state
+                    .dragging_over
+                    .entry(key)
+                    .or_default()
+                    .insert(target, hit);
+                for &dragged in dragged.keys() {
+                    let event = DragEnter {
+                        button,
+                        dragged,
+                        hit,
+                    };
+                    let writer = &mut writers.drag_enter;
+                    send_bubbled(writer, &parents, target, pointer_id, location, event);
+                }
+            }
+        }
+    }
+
+    for input in input_events.read() {
+        let pointer_id = input.pointer_id;
+        let location = &input.location;
+        let hovered = || {
+            hover_map
+                .get(&pointer_id)
+                .into_iter()
+                .flatten()
+                .map(|(&entity, &hit)| (entity, hit))
+        };
+
+        match input.action {
+            PointerAction::Pressed {
+                direction: PressDirection::Down,
+                button,
+            } => {
+                let pressed = state.pressed.entry((pointer_id, button)).or_default();
+                pressed.clear();
+                for (target, hit) in hovered() {
+                    let event = Down { button, hit };
+                    send_bubbled(
+                        &mut writers.down,
+                        &parents,
+                        target,
+                        pointer_id,
+                        location,
+                        event,
+                    );
+                    pressed.insert(target, (now, hit));
+                }
+            }
+            PointerAction::Pressed {
+                direction: PressDirection::Up,
+                button,
+            } => {
+                let key = (pointer_id, button);
+                let pressed = state.pressed.remove(&key).unwrap_or_default();
+                let dragged = state.dragging.remove(&key).unwrap_or_default();
+                let dragging_over = state.dragging_over.remove(&key).unwrap_or_default();
+                for (target, hit) in hovered() {
+                    let event = Up { button, hit };
+                    send_bubbled(
+                        &mut writers.up,
+                        &parents,
+                        target,
+                        pointer_id,
+                        location,
+                        event,
+                    );
+                    if let Some(&(pressed_at, _)) = pressed.get(&target) {
+                        let event = Click {
+                            button,
+                            hit,
+                            duration: now.saturating_sub(pressed_at),
+                        };
+                        let writer = &mut writers.click;
+                        send_bubbled(writer, &parents, target, pointer_id, location, event);
+                    }
+                    for &dropped in dragged.keys().filter(|&&dropped| dropped != target) {
+                        let event = Drop {
+                            button,
+                            dropped,
+                            hit,
+                        };
+                        send_bubbled(
+                            &mut writers.drop,
+                            &parents,
+                            target,
+                            pointer_id,
+                            location,
+                            event,
+                        );
+                    }
+                }
+                end_drags(
+                    &mut writers,
+                    &parents,
+                    pointer_id,
+                    location,
+                    button,
+                    &dragged,
+                    &dragging_over,
+                );
+            }
+            PointerAction::Moved { delta } => {
+                for (target, hit) in hovered() {
+                    let event = Move { hit, delta };
+                    send_bubbled(
+                        &mut writers.moved,
+                        &parents,
+                        target,
+                        pointer_id,
+                        location,
+                        event,
+                    );
+                }
+
+                for button in PointerButton::iter() {
+                    let key = (pointer_id, button);
+                    let Some(pressed) = state.pressed.get(&key) else {
+                        continue;
+                    };
+                    let dragged = state.dragging.entry(key).or_default();
+                    if dragged.is_empty() && !pressed.is_empty() {
+                        // The drag starts where the pointer was before this move
+                        let start = location.position - delta;
+                        for (&target, &(_, hit)) in pressed {
+                            let event = DragStart { button, hit };
+                            let writer = &mut writers.drag_start;
+                            send_bubbled(writer, &parents, target, pointer_id, location, event);
+                            let latest = start;
+                            dragged.insert(target, DragEntry { start, latest });
+                        }
+                        let dragging_over = state.dragging_over.entry(key).or_default();
+                        for (target, hit) in hovered().filter(|(e, _)| !dragged.contains_key(e)) {
+                            dragging_over.insert(target, hit);
+                            for &dragged in dragged.keys() {
+                                let event = DragEnter {
+                                    button,
+                                    dragged,
+                                    hit,
+                                };
+                                let writer = &mut writers.drag_enter;
+                                send_bubbled(writer, &parents, target, pointer_id, location, event);
+                            }
+                        }
+                    }
+
+                    for (&target, drag) in dragged.iter_mut() {
+                        let event = Drag {
+                            button,
+                            distance: location.position - drag.start,
+                            delta: location.position - drag.latest,
+                        };
+                        drag.latest = location.position;
+                        send_bubbled(
+                            &mut writers.drag,
+                            &parents,
+                            target,
+                            pointer_id,
+                            location,
+                            event,
+                        );
+                    }
+                    for (target, hit) in hovered().filter(|(e, _)| !dragged.contains_key(e)) {
+                        for &dragged in dragged.keys() {
+                            let event = DragOver {
+                                button,
+                                dragged,
+                                hit,
+                            };
+                            let writer = &mut writers.drag_over;
+                            send_bubbled(writer, &parents, target, pointer_id, location, event);
+                        }
+                    }
+                }
+            }
+            PointerAction::Canceled => {
+                for button in PointerButton::iter() {
+                    let key = (pointer_id, button);
+                    state.pressed.remove(&key);
+                    let dragged = state.dragging.remove(&key).unwrap_or_default();
+                    let dragging_over = state.dragging_over.remove(&key).unwrap_or_default();
+                    end_drags(
+                        &mut writers,
+                        &parents,
+                        pointer_id,
+                        location,
+                        button,
+                        &dragged,
+                        &dragging_over,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Sends the [`DragEnd`] and [`DragLeave`] events of a drag that ended.
+fn end_drags(
+    writers: &mut PointerEventWriters,
+    parents: &Query<&Parent>,
+    pointer_id: PointerId,
+    location: &Location,
+    button: PointerButton,
+    dragged: &HashMap<Entity, DragEntry>,
+    dragging_over: &HashMap<Entity, HitData>,
+) {
+    for (&target, drag) in dragged {
+        let event = DragEnd {
+            button,
+            distance: drag.latest - drag.start,
+        };
+        send_bubbled(
+            &mut writers.drag_end,
+            parents,
+            target,
+            pointer_id,
+            location,
+            event,
+        );
+    }
+    for (&target, &hit) in dragging_over {
+        for &dragged in dragged.keys() {
+            let event = DragLeave {
+                button,
+                dragged,
+                hit,
+            };
+            send_bubbled(
+                &mut writers.drag_leave,
+                parents,
+                target,
+                pointer_id,
+                location,
+                event,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_app::{App, PreUpdate};
+    use bevy_ecs::event::Events;
+    use bevy_hierarchy::BuildWorldChildren;
+    use bevy_render::camera::{ManualTextureViewHandle, NormalizedRenderTarget};
+
+    use super::*;
+    use crate::{
+        backend::PointerHits,
+        pointer::{PointerBundle, PointerPress},
+        PickSet, PickingPlugin,
+    };
+
+    const POINTER: PointerId = PointerId::Custom(0);
+
+    #[derive(Resource)]
+    struct Target(Entity);
+
+    /// Hits the target whenever the pointer is over the render target.
+    fn test_backend(
+        pointers: Query<(&PointerId, &PointerLocation)>,
+        target: Res<Target>,
+        mut hits: EventWriter<PointerHits>,
+    ) {
+        for (&pointer_id, location) in &pointers {
+            if location.location().is_some() {
+                let hit = HitData::new(target.0, 0.0, None, None);
+                hits.send(PointerHits::new(pointer_id, vec![(target.0, hit)], 0.0));
+            }
+        }
+    }
+
+    fn input(action: PointerAction) -> PointerInput {
+        input_at(Vec2::new(10.0, 20.0), action)
+    }
+
+    fn input_at(position: Vec2, action: PointerAction) -> PointerInput {
+        let location = Location {
+            target: NormalizedRenderTarget::TextureView(ManualTextureViewHandle(0)),
+            position,
+        };
+        PointerInput::new(POINTER, location, action)
+    }
+
+    /// Drains the events of a type, since events aren't updated without the `TimePlugin`.
+    fn drain<E: Debug + Clone + Reflect>(app: &mut App) -> Vec<Pointer<E>> {
+        app.world_mut()
+            .resource_mut::<Events<Pointer<E>>>()
+            .drain()
+            .collect()
+    }
+
+    fn listeners<E: Debug + Clone + Reflect>(app: &mut App) -> Vec<(Entity, Entity)> {
+        drain::<E>(app)
+            .into_iter()
+            .map(|event| (event.target, event.listener))
+            .collect()
+    }
+
+    #[test]
+    fn synthetic_pointer_input() {
+        let mut app = App::new();
+        app.add_plugins(PickingPlugin)
+            .init_resource::<Time>()
+            .add_systems(PreUpdate, test_backend.in_set(PickSet::Backend));
+
+        let parent = app.world_mut().spawn_empty().id();
+        let child = app.world_mut().spawn_empty().set_parent(parent).id();
+        app.insert_resource(Target(child));
+        app.world_mut().spawn(PointerBundle::new(POINTER));
+
+        app.world_mut()
+            .send_event(input(PointerAction::Moved { delta: Vec2::ZERO }));
+        app.update();
+        assert_eq!(
+            listeners::<Over>(&mut app),
+            [(child, child), (child, parent)]
+        );
+        assert_eq!(
+            listeners::<Move>(&mut app),
+            [(child, child), (child, parent)]
+        );
+
+        app.world_mut().send_event(input(PointerAction::Pressed {
+            direction: PressDirection::Down,
+            button: PointerButton::Primary,
+        }));
+        app.update();
+        assert_eq!(
+            listeners::<Down>(&mut app),
+            [(child, child), (child, parent)]
+        );
+        assert!(listeners::<Over>(&mut app).is_empty());
+        let mut pointers = app.world_mut().query::<&PointerPress>();
+        let press = pointers.single(app.world());
+        assert!(press.is_pressed(PointerButton::Primary));
+
+        app.world_mut().send_event(input(PointerAction::Pressed {
+            direction: PressDirection::Up,
+            button: PointerButton::Primary,
+        }));
+        app.update();
+        assert_eq!(listeners::<Up>(&mut app), [(child, child), (child, parent)]);
+        assert_eq!(
+            listeners::<Click>(&mut app),
+            [(child, child), (child, parent)]
+        );
+        assert!(listeners::<DragEnd>(&mut app).is_empty());
+
+        app.world_mut().send_event(input(PointerAction::Canceled));
+        app.update();
+        assert_eq!(
+            listeners::<Out>(&mut app),
+            [(child, child), (child, parent)]
+        );
+    }
+
+    #[test]
+    fn drag_and_drop() {
+        let mut app = App::new();
+        app.add_plugins(PickingPlugin)
+            .init_resource::<Time>()
+            .add_systems(PreUpdate, test_backend.in_set(PickSet::Backend));
+
+        let dragged = app.world_mut().spawn_empty().id();
+        let target = app.world_mut().spawn_empty().id();
+        app.insert_resource(Target(dragged));
+        app.world_mut().spawn(PointerBundle::new(POINTER));
+
+        let moved =
+            |position: Vec2, delta: Vec2| input_at(position, PointerAction::Moved { delta });
+        let pressed = |position: Vec2, direction: PressDirection| {
+            input_at(
+                position,
+                PointerAction::Pressed {
+                    direction,
+                    button: PointerButton::Primary,
+                },
+            )
+        };
+
+        app.world_mut()
+            .send_event(moved(Vec2::new(10.0, 20.0), Vec2::ZERO));
+        app.update();
+        app.world_mut()
+            .send_event(pressed(Vec2::new(10.0, 20.0), PressDirection::Down));
+        app.update();
+        assert_eq!(listeners::<Down>(&mut app), [(dragged, dragged)]);
+
+        // Moving with the button held down starts dragging the pressed entity
+        app.world_mut()
+            .send_event(moved(Vec2::new(15.0, 20.0), Vec2::new(5.0, 0.0)));
+        app.update();
+        assert_eq!(listeners::<DragStart>(&mut app), [(dragged, dragged)]);
+        let drags = drain::<Drag>(&mut app);
+        assert_eq!(drags.len(), 1);
+        assert_eq!(drags[0].target, dragged);
+        assert_eq!(drags[0].distance, Vec2::new(5.0, 0.0));
+        assert_eq!(drags[0].delta, Vec2::new(5.0, 0.0));
+        // The dragged entity isn't dragged over itself
+        assert!(listeners::<DragEnter>(&mut app).is_empty());
+        assert!(listeners::<DragOver>(&mut app).is_empty());
+
+        // Dragging over another entity
+        app.insert_resource(Target(target));
+        app.world_mut()
+            .send_event(moved(Vec2::new(15.0, 30.0), Vec2::new(0.0, 10.0)));
+        app.update();
+        assert_eq!(listeners::<Out>(&mut app), [(dragged, dragged)]);
+        let enters = drain::<DragEnter>(&mut app);
+        assert_eq!(enters.len(), 1);
+        assert_eq!((enters[0].target, enters[0].dragged), (target, dragged));
+        let drags = drain::<Drag>(&mut app);
+        assert_eq!(drags.len(), 1);
+        assert_eq!(drags[0].distance, Vec2::new(5.0, 10.0));
+        assert_eq!(drags[0].delta, Vec2::new(0.0, 10.0));
+        let overs = drain::<DragOver>(&mut app);
+        assert_eq!(overs.len(), 1);
+        assert_eq!((overs[0].target, overs[0].dragged), (target, dragged));
+
+        // Releasing the button drops the dragged entity on the hovered one
+        app.world_mut()
+            .send_event(pressed(Vec2::new(15.0, 30.0), PressDirection::Up));
+        app.update();
+        assert_eq!(listeners::<Up>(&mut app), [(target, target)]);
+        assert!(listeners::<Click>(&mut app).is_empty());
+        let drops = drain::<Drop>(&mut app);
+        assert_eq!(drops.len(), 1);
+        assert_eq!((drops[0].target, drops[0].dropped), (target, dragged));
+        let drag_ends = drain::<DragEnd>(&mut app);
+        assert_eq!(drag_ends.len(), 1);
+        assert_eq!(drag_ends[0].target, dragged);
+        assert_eq!(drag_ends[0].distance, Vec2::new(5.0, 10.0));
+        let leaves = drain::<DragLeave>(&mut app);
+        assert_eq!(leaves.len(), 1);
+        assert_eq!((leaves[0].target, leaves[0].dragged), (target, dragged));
+
+        // The drag ended, so moving doesn't drag anymore
+        app.world_mut()
+            .send_event(moved(Vec2::new(20.0, 30.0), Vec2::new(5.0, 0.0)));
+        app.update();
+        assert!(listeners::<Drag>(&mut app).is_empty());
+        assert!(listeners::<DragOver>(&mut app).is_empty());
+    }
+}
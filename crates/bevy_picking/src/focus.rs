@@ -0,0 +1,168 @@
+//! Merges the [`PointerHits`] of all the backends to find the entities each pointer hovers.
+
+use bevy_derive::{Deref, DerefMut};
+use bevy_ecs::prelude::*;
+use bevy_math::FloatOrd;
+use bevy_reflect::prelude::*;
+use bevy_utils::HashMap;
+
+use crate::{
+    backend::{HitData, PointerHits},
+    pointer::{PointerId, PointerInteraction},
+    PickingBehavior,
+};
+
+/// The entities hovered by each pointer, with where they were hit.
+#[derive(Debug, Clone, Default, Deref, DerefMut, Resource, Reflect)]
+#[reflect(Resource, Default)]
+pub struct HoverMap(pub HashMap<PointerId, HashMap<Entity, HitData>>);
+
+/// The [`HoverMap`] of the previous frame, used to find which entities the pointers started or
+/// stopped hovering.
+#[derive(Debug, Clone, Default, Deref, DerefMut, Resource, Reflect)]
+#[reflect(Resource, Default)]
+pub struct PreviousHoverMap(pub HashMap<PointerId, HashMap<Entity, HitData>>);
+
+/// Updates the [`HoverMap`] and the [`PointerInteraction`] of the pointers from the
+/// [`PointerHits`] sent this frame.
+///
+/// The hits are sorted from the highest [`PointerHits::order`] to the lowest, then from the
+/// closest to the furthest, and entities are hovered until one of them blocks the ones behind it
+/// with [`PickingBehavior::should_block_lower`]. Entities without a [`PickingBehavior`] block the
+/// entities behind them and are hoverable.
+pub fn update_focus(
+    mut hits: EventReader<PointerHits>,
+    behaviors: Query<&PickingBehavior>,
+    mut pointers: Query<(&PointerId, &mut PointerInteraction)>,
+    mut hover_map: ResMut<HoverMap>,
+    mut previous_hover_map: ResMut<PreviousHoverMap>,
+    mut sorted_hits: Local<HashMap<PointerId, Vec<(FloatOrd, Entity, HitData)>>>,
+) {
+    previous_hover_map.0 = std::mem::take(&mut hover_map.0);
+
+    sorted_hits.values_mut().for_each(Vec::clear);
+    for pointer_hits in hits.read() {
+        let order = FloatOrd(pointer_hits.order);
+        sorted_hits.entry(pointer_hits.pointer).or_default().extend(
+            pointer_hits
+                .picks
+                .iter()
+                .map(|&(entity, hit)| (order, entity, hit)),
+        );
+    }
+
+    for (&pointer_id, mut interaction) in &mut pointers {
+        let hovered = hover_map.entry(pointer_id).or_default();
+        interaction.sorted_entities.clear();
+        let Some(hits) = sorted_hits.get_mut(&pointer_id) else {
+            continue;
+        };
+        hits.sort_by(|(order_a, _, hit_a), (order_b, _, hit_b)| {
+            order_b
+                .cmp(order_a)
+                .then(hit_a.depth.total_cmp(&hit_b.depth))
+        });
+
+        for &(_, entity, hit) in hits.iter() {
+            if hovered.contains_key(&entity) {
+                continue;
+            }
+            let behavior = behaviors.get(entity).copied().unwrap_or_default();
+            if behavior.is_hoverable {
+                hovered.insert(entity, hit);
+                interaction.sorted_entities.push((entity, hit));
+            }
+            if behavior.should_block_lower {
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_ecs::{event::Events, system::RunSystemOnce};
+
+    use super::*;
+    use crate::pointer::PointerBundle;
+
+    const POINTER: PointerId = PointerId::Custom(0);
+
+    fn hovered(world: &mut World) -> Vec<(Entity, f32)> {
+        let mut pointers = world.query::<(&PointerId, &PointerInteraction)>();
+        let (_, interaction) = pointers
+            .iter(world)
+            .find(|(&pointer_id, _)| pointer_id == POINTER)
+            .unwrap();
+        let sorted: Vec<_> = interaction
+            .sorted_entities
+            .iter()
+            .map(|(entity, hit)| (*entity, hit.depth))
+            .collect();
+        let hover_map = &world.resource::<HoverMap>()[&POINTER];
+        assert_eq!(hover_map.len(), sorted.len());
+        assert!(sorted
+            .iter()
+            .all(|(entity, _)| hover_map.contains_key(entity)));
+        sorted
+    }
+
+    fn send_hits(world: &mut World, camera: Entity, order: f32, picks: &[(Entity, f32)]) {
+        let picks = picks
+            .iter()
+            .map(|&(entity, depth)| (entity, HitData::new(camera, depth, None, None)))
+            .collect();
+        world.send_event(PointerHits::new(POINTER, picks, order));
+    }
+
+    #[test]
+    fn hits_are_sorted_by_order_then_depth() {
+        let mut world = World::new();
+        world.init_resource::<HoverMap>();
+        world.init_resource::<PreviousHoverMap>();
+        world.init_resource::<Events<PointerHits>>();
+        world.spawn(PointerBundle::new(POINTER));
+        world.spawn(PointerBundle::new(PointerId::Mouse));
+
+        let back_camera = world.spawn_empty().id();
+        let front_camera = world.spawn_empty().id();
+        let overlay = world.spawn(PickingBehavior::IGNORE).id();
+        let near = world
+            .spawn(PickingBehavior {
+                should_block_lower: false,
+                is_hoverable: true,
+            })
+            .id();
+        let far = world.spawn_empty().id();
+        let behind = world.spawn_empty().id();
+
+        // The hits of the camera rendered first are sent first, and each backend sends its hits
+        // in its own order
+        send_hits(
+            &mut world,
+            back_camera,
+            0.0,
+            &[(behind, 7.0), (far, 5.0), (near, 1.0)],
+        );
+        send_hits(&mut world, front_camera, 1.0, &[(overlay, 10.0)]);
+        world.run_system_once(update_focus);
+        // The overlay of the camera in front is neither hovered nor blocking, and `far` blocks
+        // `behind`
+        assert_eq!(hovered(&mut world), [(near, 1.0), (far, 5.0)]);
+        assert!(world.resource::<HoverMap>()[&PointerId::Mouse].is_empty());
+
+        // An invisible wall in front of everything
+        world.resource_mut::<Events<PointerHits>>().clear();
+        let wall = world
+            .spawn(PickingBehavior {
+                should_block_lower: true,
+                is_hoverable: false,
+            })
+            .id();
+        send_hits(&mut world, back_camera, 0.0, &[(far, 5.0), (near, 1.0)]);
+        send_hits(&mut world, front_camera, 1.0, &[(wall, 20.0)]);
+        world.run_system_once(update_focus);
+        assert!(hovered(&mut world).is_empty());
+        assert_eq!(world.resource::<PreviousHoverMap>()[&POINTER].len(), 2);
+    }
+}
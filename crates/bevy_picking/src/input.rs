@@ -0,0 +1,213 @@
+//! Turns mouse and touch input into [`PointerInput`] events.
+//!
+//! Other pointers, such as a cursor driven by a gamepad, are added by spawning a
+//! [`PointerBundle`] with a [`PointerId::Custom`] id and sending [`PointerInput`] events for it.
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_input::{
+    mouse::MouseButtonInput,
+    prelude::*,
+    touch::{TouchInput, TouchPhase},
+    ButtonState,
+};
+use bevy_math::Vec2;
+use bevy_reflect::prelude::*;
+use bevy_render::camera::NormalizedRenderTarget;
+use bevy_utils::HashMap;
+use bevy_window::{CursorLeft, CursorMoved, WindowRef};
+
+use crate::{
+    pointer::{
+        Location, PointerAction, PointerBundle, PointerButton, PointerId, PointerInput,
+        PressDirection,
+    },
+    PickSet,
+};
+
+/// Sends [`PointerInput`] events for the mouse and touches.
+#[derive(Clone, Debug, Resource, Reflect)]
+#[reflect(Resource, Default)]
+pub struct PointerInputPlugin {
+    /// Whether the mouse pointer is updated from the mouse.
+    pub is_mouse_enabled: bool,
+    /// Whether touch pointers are spawned and updated from touches.
+    pub is_touch_enabled: bool,
+}
+
+impl Default for PointerInputPlugin {
+    fn default() -> Self {
+        Self {
+            is_mouse_enabled: true,
+            is_touch_enabled: true,
+        }
+    }
+}
+
+impl Plugin for PointerInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(self.clone())
+            .add_systems(Startup, spawn_mouse_pointer)
+            .add_systems(
+                PreUpdate,
+                (
+                    mouse_pick_events.run_if(|settings: Res<Self>| settings.is_mouse_enabled),
+                    touch_pick_events.run_if(|settings: Res<Self>| settings.is_touch_enabled),
+                )
+                    .chain()
+                    .in_set(PickSet::Input),
+            )
+            .register_type::<Self>();
+    }
+}
+
+/// Spawns the mouse pointer.
+pub fn spawn_mouse_pointer(mut commands: Commands) {
+    commands.spawn(PointerBundle::new(PointerId::Mouse));
+}
+
+fn window_location(window: Entity, position: Vec2) -> Option<Location> {
+    Some(Location {
+        target: NormalizedRenderTarget::Window(WindowRef::Entity(window).normalize(None)?),
+        position,
+    })
+}
+
+/// Sends [`PointerInput`] events for the mouse pointer from the cursor and mouse buttons.
+pub fn mouse_pick_events(
+    mut cursor_moved: EventReader<CursorMoved>,
+    mut cursor_left: EventReader<CursorLeft>,
+    mut mouse_buttons: EventReader<MouseButtonInput>,
+    mut cursor_location: Local<Option<Location>>,
+    mut pointer_inputs: EventWriter<PointerInput>,
+) {
+    for event in cursor_moved.read() {
+        let Some(location) = window_location(event.window, event.position) else {
+            continue;
+        };
+        // The delta of the cursor is only known within the same window
+        let delta = cursor_location
+            .as_ref()
+            .filter(|previous| previous.target == location.target)
+            .map_or(Vec2::ZERO, |previous| location.position - previous.position);
+        pointer_inputs.send(PointerInput::new(
+            PointerId::Mouse,
+            location.clone(),
+            PointerAction::Moved { delta },
+        ));
+        *cursor_location = Some(location);
+    }
+
+    for event in mouse_buttons.read() {
+        let Some(location) = cursor_location.clone() else {
+            continue;
+        };
+        let button = match event.button {
+            MouseButton::Left => PointerButton::Primary,
+            MouseButton::Right => PointerButton::Secondary,
+            MouseButton::Middle => PointerButton::Middle,
+            _ => continue,
+        };
+        let direction = match event.state {
+            ButtonState::Pressed => PressDirection::Down,
+            ButtonState::Released => PressDirection::Up,
+        };
+        pointer_inputs.send(PointerInput::new(
+            PointerId::Mouse,
+            location,
+            PointerAction::Pressed { direction, button },
+        ));
+    }
+
+    for event in cursor_left.read() {
+        let Some(location) = cursor_location
+            .take()
+            .or_else(|| window_location(event.window, Vec2::ZERO))
+        else {
+            continue;
+        };
+        pointer_inputs.send(PointerInput::new(
+            PointerId::Mouse,
+            location,
+            PointerAction::Canceled,
+        ));
+    }
+}
+
+/// Sends [`PointerInput`] events for touches, spawning a pointer when a touch starts and
+/// despawning it the frame after it ends.
+pub fn touch_pick_events(
+    mut commands: Commands,
+    mut touches: EventReader<TouchInput>,
+    pointers: Query<(Entity, &PointerId)>,
+    mut touch_locations: Local<HashMap<u64, Location>>,
+    mut ended_touches: Local<Vec<u64>>,
+    mut pointer_inputs: EventWriter<PointerInput>,
+) {
+    // Touches that ended in the previous frame had their events sent by now
+    for (entity, pointer_id) in &pointers {
+        if pointer_id
+            .get_touch_id()
+            .is_some_and(|id| ended_touches.contains(&id))
+        {
+            commands.entity(entity).despawn();
+        }
+    }
+    ended_touches.clear();
+
+    for touch in touches.read() {
+        let pointer_id = PointerId::Touch(touch.id);
+        let Some(location) = window_location(touch.window, touch.position) else {
+            continue;
+        };
+        match touch.phase {
+            TouchPhase::Started => {
+                commands.spawn(PointerBundle::new(pointer_id));
+                pointer_inputs.send(PointerInput::new(
+                    pointer_id,
+                    location.clone(),
+                    PointerAction::Pressed {
+                        direction: PressDirection::Down,
+                        button: PointerButton::Primary,
+                    },
+                ));
+                touch_locations.insert(touch.id, location);
+            }
+            TouchPhase::Moved => {
+                let delta = touch_locations
+                    .get(&touch.id)
+                    .map_or(Vec2::ZERO, |previous| location.position - previous.position);
+                if delta == Vec2::ZERO {
+                    continue;
+                }
+                pointer_inputs.send(PointerInput::new(
+                    pointer_id,
+                    location.clone(),
+                    PointerAction::Moved { delta },
+                ));
+                touch_locations.insert(touch.id, location);
+            }
+            TouchPhase::Ended => {
+                pointer_inputs.send(PointerInput::new(
+                    pointer_id,
+                    location,
+                    PointerAction::Pressed {
+                        direction: PressDirection::Up,
+                        button: PointerButton::Primary,
+                    },
+                ));
+                touch_locations.remove(&touch.id);
+                ended_touches.push(touch.id);
+            }
+            TouchPhase::Canceled => {
+                pointer_inputs.send(PointerInput::new(
+                    pointer_id,
+                    location,
+                    PointerAction::Canceled,
+                ));
+                touch_locations.remove(&touch.id);
+                ended_touches.push(touch.id);
+            }
+        }
+    }
+}
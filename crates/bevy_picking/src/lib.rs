@@ -0,0 +1,175 @@
+#![cfg_attr(docsrs, feature(doc_auto_cfg))]
+#![doc(
+    html_logo_url = "https://bevyengine.org/assets/icon.png",
+    html_favicon_url = "https://bevyengine.org/assets/icon.png"
+)]
+
+//! This crate provides picking: finding the entities under pointers such as the mouse, touches
+//! or a gamepad driven cursor, and sending events when pointers interact with them.
+//!
+//! Picking runs in [`PreUpdate`], in the [`PickSet`]s:
+//! 1. [`PickSet::Input`]: the [`PointerInputPlugin`](input::PointerInputPlugin) turns mouse and
+//!    touch input into [`PointerInput`](pointer::PointerInput) events. Applications can send
+//!    their own to drive custom pointers or simulate input in tests.
+//! 2. [`PickSet::ProcessInput`]: pointers are updated from their input.
+//! 3. [`PickSet::Backend`]: backends find the entities under each pointer and send
+//!    [`PointerHits`](backend::PointerHits). `bevy_ui` and `bevy_sprite` provide backends when
+//!    their `bevy_picking` feature is enabled, and the [`MeshPickingPlugin`](mesh_picking::MeshPickingPlugin)
+//!    picks meshes.
+//! 4. [`PickSet::Focus`]: the hits of all the backends are sorted across cameras to find the
+//!    hovered entities, see [`HoverMap`](focus::HoverMap).
+//! 5. [`PickSet::Events`]: [`Pointer`](events::Pointer) events such as
+//!    [`Pointer<Click>`](events::Click) are sent, bubbling up the hierarchy.
+//!
+//! # Example
+//! ```
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_picking::prelude::*;
+//! fn on_click(mut clicks: EventReader<Pointer<Click>>) {
+//!     for click in clicks.read() {
+//!         println!("{:?} clicked {:?}", click.pointer_id, click.target);
+//!     }
+//! }
+//! # bevy_ecs::system::assert_is_system(on_click);
+//! ```
+
+pub mod backend;
+pub mod events;
+pub mod focus;
+pub mod input;
+pub mod mesh_picking;
+pub mod pointer;
+
+/// The `bevy_picking` prelude.
+pub mod prelude {
+    // `Drop` is left out so that it doesn't shadow the trait of the standard prelude
+    #[doc(hidden)]
+    pub use crate::{
+        events::{
+            Click, Down, Drag, DragEnd, DragEnter, DragLeave, DragOver, DragStart, Move, Out, Over,
+            Pointer, Up,
+        },
+        input::PointerInputPlugin,
+        mesh_picking::{MeshPickingPlugin, MeshPickingSettings, RayCastPickable},
+        pointer::{PointerButton, PointerId, PointerInteraction, PointerLocation},
+        PickSet, PickingBehavior, PickingPlugin,
+    };
+}
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_reflect::prelude::*;
+
+use backend::{ray::RayMap, PointerHits};
+use events::*;
+use focus::{update_focus, HoverMap, PreviousHoverMap};
+use pointer::{
+    update_pointer_map, update_pointer_state, Location, PointerId, PointerInput,
+    PointerInteraction, PointerLocation, PointerMap, PointerPress,
+};
+
+/// How an entity behaves when picked. Entities without this component block the entities
+/// behind them and are hoverable.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component, Default, PartialEq)]
+pub struct PickingBehavior {
+    /// Whether the entities behind this one, from any backend, can't be hovered. Disable it for
+    /// entities such as transparent overlays that pointers should reach through.
+    pub should_block_lower: bool,
+    /// Whether this entity can be hovered and receive [`Pointer`] events. Disabling it while
+    /// keeping [`PickingBehavior::should_block_lower`] makes the entity an invisible wall.
+    pub is_hoverable: bool,
+}
+
+impl PickingBehavior {
+    /// Neither blocks the entities behind it nor can be hovered.
+    pub const IGNORE: Self = Self {
+        should_block_lower: false,
+        is_hoverable: false,
+    };
+}
+
+impl Default for PickingBehavior {
+    fn default() -> Self {
+        Self {
+            should_block_lower: true,
+            is_hoverable: true,
+        }
+    }
+}
+
+/// The system sets of picking, run in this order in [`PreUpdate`].
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone)]
+pub enum PickSet {
+    /// Sends [`PointerInput`] events.
+    Input,
+    /// Updates the pointers from their input.
+    ProcessInput,
+    /// Sends [`PointerHits`] events.
+    Backend,
+    /// Finds the hovered entities from the [`PointerHits`].
+    Focus,
+    /// Sends the [`Pointer`] events.
+    Events,
+}
+
+/// Adds the picking pipeline, without any backend or input.
+///
+/// Most applications also need the [`PointerInputPlugin`](input::PointerInputPlugin), added to
+/// the `DefaultPlugins` along with this plugin.
+#[derive(Default)]
+pub struct PickingPlugin;
+
+impl Plugin for PickingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PointerMap>()
+            .init_resource::<HoverMap>()
+            .init_resource::<PreviousHoverMap>()
+            .init_resource::<RayMap>()
+            .add_event::<PointerInput>()
+            .add_event::<PointerHits>()
+            .add_event::<Pointer<Over>>()
+            .add_event::<Pointer<Out>>()
+            .add_event::<Pointer<Down>>()
+            .add_event::<Pointer<Up>>()
+            .add_event::<Pointer<Click>>()
+            .add_event::<Pointer<Move>>()
+            .add_event::<Pointer<DragStart>>()
+            .add_event::<Pointer<Drag>>()
+            .add_event::<Pointer<DragEnd>>()
+            .add_event::<Pointer<DragEnter>>()
+            .add_event::<Pointer<DragOver>>()
+            .add_event::<Pointer<DragLeave>>()
+            .add_event::<Pointer<Drop>>()
+            .configure_sets(
+                PreUpdate,
+                (
+                    PickSet::Input,
+                    PickSet::ProcessInput,
+                    PickSet::Backend,
+                    PickSet::Focus,
+                    PickSet::Events,
+                )
+                    .chain()
+                    .after(bevy_input::InputSystem),
+            )
+            .add_systems(
+                PreUpdate,
+                (
+                    (update_pointer_map, update_pointer_state, RayMap::repopulate)
+                        .chain()
+                        .in_set(PickSet::ProcessInput),
+                    update_focus.in_set(PickSet::Focus),
+                    pointer_events.in_set(PickSet::Events),
+                ),
+            )
+            .register_type::<PickingBehavior>()
+            .register_type::<PointerId>()
+            .register_type::<PointerLocation>()
+            .register_type::<PointerPress>()
+            .register_type::<PointerInteraction>()
+            .register_type::<Location>()
+            .register_type::<HoverMap>()
+            .register_type::<PreviousHoverMap>();
+    }
+}
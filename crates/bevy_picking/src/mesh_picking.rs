@@ -0,0 +1,254 @@
+//! A picking backend for [`Mesh`](bevy_render::mesh::Mesh) entities, casting the rays of the
+//! [`RayMap`] against their triangles with a [`MeshRayCast`].
+//!
+//! It isn't part of the [`DefaultPlugins`](https://docs.rs/bevy/latest/bevy/struct.DefaultPlugins.html)
+//! since ray casting against many meshes is costly: add the [`MeshPickingPlugin`] to enable it.
+
+use bevy_app::prelude::*;
+use bevy_ecs::prelude::*;
+use bevy_reflect::prelude::*;
+use bevy_render::{
+    camera::Camera,
+    mesh::ray_cast::{MeshRayCast, RayCastSettings, RayCastVisibility},
+    view::RenderLayers,
+};
+
+use crate::{
+    backend::{ray::RayMap, HitData, PointerHits},
+    PickSet, PickingBehavior,
+};
+
+/// Adds the mesh picking backend.
+#[derive(Clone, Default)]
+pub struct MeshPickingPlugin;
+
+impl Plugin for MeshPickingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MeshPickingSettings>()
+            .add_event::<PointerHits>()
+            .add_systems(PreUpdate, update_hits.in_set(PickSet::Backend))
+            .register_type::<RayCastPickable>()
+            .register_type::<MeshPickingSettings>();
+    }
+}
+
+/// Settings of the mesh picking backend.
+#[derive(Resource, Debug, Clone, Reflect)]
+#[reflect(Resource, Default)]
+pub struct MeshPickingSettings {
+    /// When `true`, only cameras and meshes with a [`RayCastPickable`] take part in picking.
+    pub require_markers: bool,
+    /// Which meshes can be picked depending on their visibility.
+    pub ray_cast_visibility: RayCastVisibility,
+}
+
+impl Default for MeshPickingSettings {
+    fn default() -> Self {
+        Self {
+            require_markers: false,
+            ray_cast_visibility: RayCastVisibility::VisibleInView,
+        }
+    }
+}
+
+/// Marks the cameras and meshes taking part in mesh picking when
+/// [`MeshPickingSettings::require_markers`] is set.
+#[derive(Component, Debug, Clone, Copy, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct RayCastPickable;
+
+/// Casts the rays of the [`RayMap`] against the meshes, sending the hits of each ray as
+/// [`PointerHits`].
+///
+/// Only meshes on the [`RenderLayers`] of the camera can be hit, and the ray stops at the first
+/// mesh that [blocks](PickingBehavior::should_block_lower) the meshes behind it.
+pub fn update_hits(
+    settings: Res<MeshPickingSettings>,
+    ray_map: Res<RayMap>,
+    cameras: Query<(&Camera, Option<&RenderLayers>, Has<RayCastPickable>)>,
+    pickables: Query<(
+        Option<&PickingBehavior>,
+        Has<RayCastPickable>,
+        Option<&RenderLayers>,
+    )>,
+    mut ray_cast: MeshRayCast,
+    mut hits: EventWriter<PointerHits>,
+) {
+    for (&ray_id, &ray) in ray_map.iter() {
+        let Ok((camera, camera_layers, camera_marked)) = cameras.get(ray_id.camera) else {
+            continue;
+        };
+        if settings.require_markers && !camera_marked {
+            continue;
+        }
+
+        let camera_layers = camera_layers.copied().unwrap_or_default();
+        let filter = |entity: Entity| {
+            pickables.get(entity).is_ok_and(|(_, marked, layers)| {
+                (marked || !settings.require_markers)
+                    && camera_layers.intersects(&layers.copied().unwrap_or_default())
+            })
+        };
+        let early_exit_test = |entity: Entity| {
+            pickables
+                .get(entity)
+                .ok()
+                .and_then(|(behavior, ..)| behavior)
+                .map_or(true, |behavior| behavior.should_block_lower)
+        };
+        let ray_cast_settings = RayCastSettings::default()
+            .with_visibility(settings.ray_cast_visibility)
+            .with_filter(&filter)
+            .with_early_exit_test(&early_exit_test);
+
+        let picks = ray_cast
+            .cast_ray(ray, &ray_cast_settings)
+            .iter()
+            .map(|(entity, hit)| {
+                let hit_data = HitData::new(
+                    ray_id.camera,
+                    hit.distance,
+                    Some(hit.point),
+                    Some(hit.normal),
+                );
+                (*entity, hit_data)
+            })
+            .collect::<Vec<_>>();
+        if !picks.is_empty() {
+            hits.send(PointerHits::new(ray_id.pointer, picks, camera.order as f32));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_asset::{AssetEvent, Assets};
+    use bevy_ecs::{event::Events, system::RunSystemOnce};
+    use bevy_math::{primitives::Cuboid, Vec2, Vec3};
+    use bevy_render::{
+        camera::{
+            camera_system, ManualTextureViews, NormalizedRenderTarget, PerspectiveProjection,
+        },
+        mesh::{skinning::SkinnedMeshInverseBindposes, Mesh},
+        texture::Image,
+        view::ViewVisibility,
+    };
+    use bevy_transform::components::{GlobalTransform, Transform};
+    use bevy_window::{
+        PrimaryWindow, Window, WindowCreated, WindowRef, WindowResized, WindowResolution,
+        WindowScaleFactorChanged,
+    };
+
+    use super::*;
+    use crate::pointer::{Location, PointerBundle, PointerId, PointerLocation};
+
+    fn spawn_cube(world: &mut World, z: f32) -> EntityWorldMut {
+        let mesh = world
+            .resource_mut::<Assets<Mesh>>()
+            .add(Mesh::from(Cuboid::default()));
+        let mut view_visibility = ViewVisibility::default();
+        view_visibility.set();
+        world.spawn((
+            mesh,
+            GlobalTransform::from_xyz(0.0, 0.0, z),
+            view_visibility,
+        ))
+    }
+
+    #[test]
+    fn meshes_are_picked_through_each_camera() {
+        let mut world = World::new();
+        world.init_resource::<Events<WindowResized>>();
+        world.init_resource::<Events<WindowCreated>>();
+        world.init_resource::<Events<WindowScaleFactorChanged>>();
+        world.init_resource::<Events<AssetEvent<Image>>>();
+        world.init_resource::<Assets<Image>>();
+        world.init_resource::<ManualTextureViews>();
+        world.init_resource::<Assets<Mesh>>();
+        world.init_resource::<Assets<SkinnedMeshInverseBindposes>>();
+        world.init_resource::<RayMap>();
+        world.init_resource::<MeshPickingSettings>();
+        world.init_resource::<Events<PointerHits>>();
+
+        let window = world
+            .spawn((
+                Window {
+                    resolution: WindowResolution::new(800.0, 600.0),
+                    ..Default::default()
+                },
+                PrimaryWindow,
+            ))
+            .id();
+        let camera_transform = GlobalTransform::from(
+            Transform::from_xyz(0.0, 0.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
+        );
+        let camera = world
+            .spawn((
+                Camera::default(),
+                PerspectiveProjection::default(),
+                camera_transform,
+            ))
+            .id();
+        let layer_camera = world
+            .spawn((
+                Camera {
+                    order: 1,
+                    ..Default::default()
+                },
+                PerspectiveProjection::default(),
+                camera_transform,
+                RenderLayers::layer(1),
+            ))
+            .id();
+        // A pointer at the center of the window
+        let location = Location {
+            target: NormalizedRenderTarget::Window(
+                WindowRef::Primary.normalize(Some(window)).unwrap(),
+            ),
+            position: Vec2::new(400.0, 300.0),
+        };
+        world.spawn(PointerBundle {
+            location: PointerLocation::new(location),
+            ..PointerBundle::new(PointerId::Mouse)
+        });
+
+        let cube = spawn_cube(&mut world, 0.0).id();
+        let overlay = spawn_cube(&mut world, 2.0)
+            .insert(PickingBehavior {
+                should_block_lower: false,
+                is_hoverable: true,
+            })
+            .id();
+        // Hidden behind the cube
+        spawn_cube(&mut world, -3.0);
+        let layer_cube = spawn_cube(&mut world, -6.0)
+            .insert(RenderLayers::layer(1))
+            .id();
+
+        world.run_system_once(camera_system::<PerspectiveProjection>);
+        world.run_system_once(RayMap::repopulate);
+        assert_eq!(world.resource::<RayMap>().map().len(), 2);
+        world.run_system_once(update_hits);
+
+        let mut hits: Vec<_> = world
+            .resource_mut::<Events<PointerHits>>()
+            .drain()
+            .collect();
+        hits.sort_by(|a, b| a.order.total_cmp(&b.order));
+        assert_eq!(hits.len(), 2);
+        // The rays start on the near plane of the cameras
+        let assert_picks = |hits: &PointerHits, expected: &[(Entity, Entity, f32)]| {
+            assert_eq!(hits.picks.len(), expected.len());
+            for ((entity, hit), &(expected_entity, expected_camera, expected_depth)) in
+                hits.picks.iter().zip(expected)
+            {
+                assert_eq!((*entity, hit.camera), (expected_entity, expected_camera));
+                assert!((hit.depth - expected_depth).abs() < 1e-4);
+            }
+        };
+        assert_eq!(hits[0].order, 0.0);
+        assert_picks(&hits[0], &[(overlay, camera, 7.4), (cube, camera, 9.4)]);
+        assert_eq!(hits[1].order, 1.0);
+        assert_picks(&hits[1], &[(layer_cube, layer_camera, 15.4)]);
+    }
+}
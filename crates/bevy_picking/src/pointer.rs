@@ -0,0 +1,309 @@
+//! Pointers are the sources of picking, such as the mouse, a touch or a gamepad driven cursor.
+//!
+//! Each pointer is an entity with a [`PointerBundle`]. Its state is only updated from
+//! [`PointerInput`] events, which are sent by the [`PointerInputPlugin`](crate::input::PointerInputPlugin)
+//! for the mouse and touches, and can be sent by the application to drive custom pointers or to
+//! simulate input in tests.
+
+use bevy_ecs::prelude::*;
+use bevy_math::Vec2;
+use bevy_reflect::prelude::*;
+use bevy_render::camera::{Camera, NormalizedRenderTarget};
+use bevy_utils::HashMap;
+
+use crate::backend::HitData;
+
+/// Identifies a pointer, stored as a component on the pointer's entity.
+#[derive(Component, Debug, Default, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord, Reflect)]
+#[reflect(Component, Default, PartialEq, Hash)]
+pub enum PointerId {
+    /// The mouse pointer.
+    #[default]
+    Mouse,
+    /// A touch, identified by the id the windowing backend gives it.
+    Touch(u64),
+    /// A pointer driven by the application, such as a cursor controlled with a gamepad, or a
+    /// pointer simulating input in tests.
+    Custom(u64),
+}
+
+impl PointerId {
+    /// Returns `true` if this is the mouse pointer.
+    pub fn is_mouse(&self) -> bool {
+        matches!(self, PointerId::Mouse)
+    }
+
+    /// Returns `true` if this is a touch pointer.
+    pub fn is_touch(&self) -> bool {
+        matches!(self, PointerId::Touch(_))
+    }
+
+    /// Returns `true` if this is a custom pointer.
+    pub fn is_custom(&self) -> bool {
+        matches!(self, PointerId::Custom(_))
+    }
+
+    /// Returns the id of the touch, if this is a touch pointer.
+    pub fn get_touch_id(&self) -> Option<u64> {
+        match self {
+            PointerId::Touch(id) => Some(*id),
+            _ => None,
+        }
+    }
+}
+
+/// Maps each [`PointerId`] to its entity.
+#[derive(Debug, Clone, Default, Resource)]
+pub struct PointerMap {
+    inner: HashMap<PointerId, Entity>,
+}
+
+impl PointerMap {
+    /// Returns the entity of the given pointer.
+    pub fn get_entity(&self, pointer_id: PointerId) -> Option<Entity> {
+        self.inner.get(&pointer_id).copied()
+    }
+}
+
+/// Rebuilds the [`PointerMap`] from the pointer entities.
+pub fn update_pointer_map(pointers: Query<(Entity, &PointerId)>, mut map: ResMut<PointerMap>) {
+    map.inner.clear();
+    for (entity, id) in &pointers {
+        map.inner.insert(*id, entity);
+    }
+}
+
+/// The buttons of a pointer that are currently pressed.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Eq, Reflect)]
+#[reflect(Component, Default, PartialEq)]
+pub struct PointerPress {
+    primary: bool,
+    secondary: bool,
+    middle: bool,
+}
+
+impl PointerPress {
+    /// Returns `true` if the given button is pressed.
+    pub fn is_pressed(&self, button: PointerButton) -> bool {
+        match button {
+            PointerButton::Primary => self.primary,
+            PointerButton::Secondary => self.secondary,
+            PointerButton::Middle => self.middle,
+        }
+    }
+
+    /// Returns `true` if any button is pressed.
+    pub fn is_any_pressed(&self) -> bool {
+        self.primary || self.secondary || self.middle
+    }
+
+    fn set(&mut self, button: PointerButton, pressed: bool) {
+        match button {
+            PointerButton::Primary => self.primary = pressed,
+            PointerButton::Secondary => self.secondary = pressed,
+            PointerButton::Middle => self.middle = pressed,
+        }
+    }
+}
+
+/// A button of a pointer. Touches only have a [`PointerButton::Primary`] button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+#[reflect(PartialEq, Hash)]
+pub enum PointerButton {
+    /// The left mouse button, or a touch.
+    Primary,
+    /// The right mouse button.
+    Secondary,
+    /// The middle mouse button.
+    Middle,
+}
+
+impl PointerButton {
+    /// Iterates over all the pointer buttons.
+    pub fn iter() -> impl Iterator<Item = PointerButton> {
+        [Self::Primary, Self::Secondary, Self::Middle].into_iter()
+    }
+}
+
+/// Whether a [`PointerButton`] was pressed or released.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Reflect)]
+#[reflect(PartialEq, Hash)]
+pub enum PressDirection {
+    /// The button was pressed.
+    Down,
+    /// The button was released.
+    Up,
+}
+
+/// A position on a render target, such as a window.
+#[derive(Debug, Clone, PartialEq, Reflect)]
+#[reflect(PartialEq)]
+pub struct Location {
+    /// The render target the pointer is on.
+    pub target: NormalizedRenderTarget,
+    /// The logical position on the render target, from its top left corner.
+    pub position: Vec2,
+}
+
+impl Location {
+    /// Returns `true` if this location is within the viewport of the `camera`.
+    pub fn is_in_viewport(&self, camera: &Camera, primary_window: Option<Entity>) -> bool {
+        camera.target.normalize(primary_window).as_ref() == Some(&self.target)
+            && camera
+                .logical_viewport_rect()
+                .is_some_and(|viewport| viewport.contains(self.position))
+    }
+}
+
+/// The current location of a pointer, `None` when it isn't over any render target.
+#[derive(Component, Debug, Default, Clone, PartialEq, Reflect)]
+#[reflect(Component, Default, PartialEq)]
+pub struct PointerLocation {
+    /// The location of the pointer.
+    pub location: Option<Location>,
+}
+
+impl PointerLocation {
+    /// Creates a pointer location.
+    pub fn new(location: Location) -> Self {
+        Self {
+            location: Some(location),
+        }
+    }
+
+    /// Returns the location of the pointer.
+    pub fn location(&self) -> Option<&Location> {
+        self.location.as_ref()
+    }
+}
+
+/// The entities a pointer is over, from the closest to the furthest, updated by
+/// [`update_focus`](crate::focus::update_focus).
+#[derive(Component, Debug, Default, Clone, PartialEq, Reflect)]
+#[reflect(Component, Default, PartialEq)]
+pub struct PointerInteraction {
+    /// The hovered entities and where they were hit.
+    pub sorted_entities: Vec<(Entity, HitData)>,
+}
+
+impl PointerInteraction {
+    /// Returns the closest hovered entity.
+    pub fn get_nearest_hit(&self) -> Option<&(Entity, HitData)> {
+        self.sorted_entities.first()
+    }
+}
+
+/// The components of a pointer entity.
+#[derive(Bundle, Debug, Clone)]
+pub struct PointerBundle {
+    /// The id of the pointer.
+    pub id: PointerId,
+    /// Where the pointer is.
+    pub location: PointerLocation,
+    /// The pressed buttons of the pointer.
+    pub press: PointerPress,
+    /// The entities under the pointer.
+    pub interaction: PointerInteraction,
+}
+
+impl PointerBundle {
+    /// Creates the components of a pointer with the given id.
+    pub fn new(id: PointerId) -> Self {
+        Self {
+            id,
+            location: PointerLocation::default(),
+            press: PointerPress::default(),
+            interaction: PointerInteraction::default(),
+        }
+    }
+}
+
+/// An action of a pointer, see [`PointerInput`].
+#[derive(Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(PartialEq)]
+pub enum PointerAction {
+    /// A button was pressed or released.
+    Pressed {
+        /// Whether the button was pressed or released.
+        direction: PressDirection,
+        /// The button.
+        button: PointerButton,
+    },
+    /// The pointer moved.
+    Moved {
+        /// How far the pointer moved since its last location.
+        delta: Vec2,
+    },
+    /// The pointer was canceled, for example when a touch is interrupted or the mouse leaves the
+    /// window. Its location is cleared and its buttons are released.
+    Canceled,
+}
+
+/// An input of a pointer, the only way to update the state of pointers.
+#[derive(Event, Debug, Clone, PartialEq, Reflect)]
+#[reflect(PartialEq)]
+pub struct PointerInput {
+    /// The pointer the input comes from.
+    pub pointer_id: PointerId,
+    /// Where the pointer is.
+    pub location: Location,
+    /// What the pointer did.
+    pub action: PointerAction,
+}
+
+impl PointerInput {
+    /// Creates a pointer input.
+    pub fn new(pointer_id: PointerId, location: Location, action: PointerAction) -> Self {
+        Self {
+            pointer_id,
+            location,
+            action,
+        }
+    }
+
+    /// Returns `true` if this input presses the given button.
+    pub fn button_just_pressed(&self, button: PointerButton) -> bool {
+        self.action
+            == PointerAction::Pressed {
+                direction: PressDirection::Down,
+                button,
+            }
+    }
+
+    /// Returns `true` if this input releases the given button.
+    pub fn button_just_released(&self, button: PointerButton) -> bool {
+        self.action
+            == PointerAction::Pressed {
+                direction: PressDirection::Up,
+                button,
+            }
+    }
+}
+
+/// Updates the [`PointerLocation`] and [`PointerPress`] of pointers from [`PointerInput`]
+/// events.
+pub fn update_pointer_state(
+    mut inputs: EventReader<PointerInput>,
+    mut pointers: Query<(&PointerId, &mut PointerLocation, &mut PointerPress)>,
+) {
+    for input in inputs.read() {
+        for (_, mut location, mut press) in pointers
+            .iter_mut()
+            .filter(|(id, ..)| **id == input.pointer_id)
+        {
+            match input.action {
+                PointerAction::Pressed { direction, button } => {
+                    press.set(button, direction == PressDirection::Down);
+                    location.location = Some(input.location.clone());
+                }
+                PointerAction::Moved { .. } => {
+                    location.location = Some(input.location.clone());
+                }
+                PointerAction::Canceled => {
+                    location.location = None;
+                    *press = PointerPress::default();
+                }
+            }
+        }
+    }
+}
@@ -8,7 +8,8 @@ use crate::{
     render_resource::TextureView,
     texture::GpuImage,
     view::{
-        ColorGrading, ExtractedView, ExtractedWindows, GpuCulling, RenderLayers, VisibleEntities,
+        ColorGrading, ExtractedView, ExtractedWindows, GpuCulling, OcclusionCulling, RenderLayers,
+        VisibleEntities,
     },
     Extract,
 };
@@ -842,6 +843,7 @@ pub fn extract_cameras(
             Option<&RenderLayers>,
             Option<&Projection>,
             Has<GpuCulling>,
+            Has<OcclusionCulling>,
         )>,
     >,
     primary_window: Extract<Query<Entity, With<PrimaryWindow>>>,
@@ -861,6 +863,7 @@ pub fn extract_cameras(
         render_layers,
         projection,
         gpu_culling,
+        occlusion_culling,
     ) in query.iter()
     {
         let color_grading = color_grading.unwrap_or(&ColorGrading::default()).clone();
@@ -942,6 +945,16 @@ pub fn extract_cameras(
                     );
                 }
             }
+
+            if occlusion_culling {
+                if gpu_culling && *gpu_preprocessing_support == GpuPreprocessingSupport::Culling {
+                    commands.insert(OcclusionCulling);
+                } else {
+                    warn_once!(
+                        "Occlusion culling requires GPU culling; ignoring `OcclusionCulling`."
+                    );
+                }
+            }
         }
     }
 }
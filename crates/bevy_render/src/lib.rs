@@ -43,7 +43,12 @@ pub mod prelude {
             Camera, ClearColor, ClearColorConfig, OrthographicProjection, PerspectiveProjection,
             Projection,
         },
-        mesh::{morph::MorphWeights, primitives::Meshable, Mesh},
+        mesh::{
+            morph::MorphWeights,
+            primitives::{MeshBuilder, Meshable},
+            ray_cast::{MeshRayCast, RayCastSettings},
+            Mesh,
+        },
         render_resource::Shader,
         spatial_bundle::SpatialBundle,
         texture::{image_texture_conversion::IntoDynamicImageError, Image, ImagePlugin},
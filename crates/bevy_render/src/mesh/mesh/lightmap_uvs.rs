@@ -0,0 +1,215 @@
+use super::{processing::MeshProcessingError, Mesh};
+use bevy_math::{Vec2, Vec3};
+use bevy_utils::{Entry, HashMap};
+
+impl Mesh {
+    /// Generates non-overlapping UVs in [`Mesh::ATTRIBUTE_UV_1`], to sample lightmaps and other
+    /// textures baked per mesh with `UvChannel::Uv1`, turning the mesh into an indexed
+    /// [`PrimitiveTopology::TriangleList`](super::PrimitiveTopology::TriangleList).
+    ///
+    /// Triangles are grouped in charts of connected triangles facing the same axis, each chart
+    /// is projected on the plane of its axis, and the charts are packed in the unit square with a
+    /// uniform scale, so that texels have the same size everywhere on the mesh. Vertices shared
+    /// by several charts are duplicated.
+    ///
+    /// `padding` is the space left around each chart, as a fraction of the size of the texture,
+    /// to keep the texels of neighboring charts from bleeding into each other when filtered.
+    /// For a lightmap of `N` texels, a padding of at least `2.0 / N` is recommended.
+    pub fn generate_lightmap_uvs(&mut self, padding: f32) -> Result<(), MeshProcessingError> {
+        if self.morph_targets.is_some() {
+            return Err(MeshProcessingError::MorphTargets);
+        }
+        let triangles: Vec<[usize; 3]> = self.triangle_indices()?.collect();
+        let positions: Vec<Vec3> = self
+            .positions()?
+            .iter()
+            .map(|position| Vec3::from(*position))
+            .collect();
+
+        // The axis closest to the normal of each triangle, as an index in the components of a
+        // `Vec3` with a sign
+        let axes: Vec<(usize, bool)> = triangles
+            .iter()
+            .map(|triangle| {
+                let [a, b, c] = triangle.map(|vertex| positions[vertex]);
+                let normal = (b - a).cross(c - a);
+                let abs = normal.abs();
+                let axis = if abs.x >= abs.y && abs.x >= abs.z {
+                    0
+                } else if abs.y >= abs.z {
+                    1
+                } else {
+                    2
+                };
+                (axis, normal[axis] >= 0.0)
+            })
+            .collect();
+
+        // Join the triangles sharing an edge and an axis in charts
+        let mut charts = UnionFind::new(triangles.len());
+        let mut edges: HashMap<(usize, usize), usize> = HashMap::new();
+        for (triangle_index, triangle) in triangles.iter().enumerate() {
+            for i in 0..3 {
+                let (a, b) = (triangle[i], triangle[(i + 1) % 3]);
+                match edges.entry((a.min(b), a.max(b))) {
+                    Entry::Occupied(entry) => {
+                        if axes[*entry.get()] == axes[triangle_index] {
+                            charts.union(*entry.get(), triangle_index);
+                        }
+                    }
+                    Entry::Vacant(entry) => {
+                        entry.insert(triangle_index);
+                    }
+                }
+            }
+        }
+
+        // Split the vertices between the charts and project them on the plane of their chart
+        let mut chart_indices: HashMap<usize, usize> = HashMap::new();
+        let mut chart_bounds: Vec<(Vec2, Vec2)> = Vec::new();
+        let mut vertices: HashMap<(usize, usize), u32> = HashMap::new();
+        let mut new_to_old = Vec::new();
+        let mut projected = Vec::new();
+        let mut vertex_charts = Vec::new();
+        let mut indices = Vec::with_capacity(triangles.len() * 3);
+        for (triangle_index, triangle) in triangles.iter().enumerate() {
+            let root = charts.find(triangle_index);
+            let next_chart = chart_indices.len();
+            let chart = *chart_indices.entry(root).or_insert(next_chart);
+            let (axis, _) = axes[root];
+            for &vertex in triangle {
+                let index = *vertices.entry((chart, vertex)).or_insert_with(|| {
+                    let position = positions[vertex];
+                    let uv = match axis {
+                        0 => Vec2::new(position.z, position.y),
+                        1 => Vec2::new(position.x, position.z),
+                        _ => Vec2::new(position.x, position.y),
+                    };
+                    if chart == chart_bounds.len() {
+                        chart_bounds.push((uv, uv));
+                    }
+                    let (min, max) = &mut chart_bounds[chart];
+                    *min = min.min(uv);
+                    *max = max.max(uv);
+                    new_to_old.push(vertex as u32);
+                    projected.push(uv);
+                    vertex_charts.push(chart);
+                    new_to_old.len() as u32 - 1
+                });
+                indices.push(index);
+            }
+        }
+
+        let sizes: Vec<Vec2> = chart_bounds.iter().map(|(min, max)| *max - *min).collect();
+        let (offsets, scale) = pack_charts(&sizes, padding);
+        let uvs: Vec<[f32; 2]> = projected
+            .iter()
+            .zip(&vertex_charts)
+            .map(|(uv, &chart)| ((*uv - chart_bounds[chart].0 + offsets[chart]) * scale).into())
+            .collect();
+
+        self.remap_vertices(&new_to_old, indices);
+        self.insert_attribute(Mesh::ATTRIBUTE_UV_1, uvs);
+        Ok(())
+    }
+
+    /// Consumes the mesh and returns it with lightmap UVs, see [`Mesh::generate_lightmap_uvs`].
+    pub fn with_generated_lightmap_uvs(
+        mut self,
+        padding: f32,
+    ) -> Result<Self, MeshProcessingError> {
+        self.generate_lightmap_uvs(padding)?;
+        Ok(self)
+    }
+}
+
+/// Packs rectangles of the given sizes in rows, from the tallest to the shortest, and returns
+/// their offsets and the scale fitting them all in the unit square, with `padding` in the scaled
+/// space between them and around them.
+fn pack_charts(sizes: &[Vec2], padding: f32) -> (Vec<Vec2>, f32) {
+    let mut order: Vec<usize> = (0..sizes.len()).collect();
+    order.sort_by(|&a, &b| sizes[b].y.total_cmp(&sizes[a].y));
+
+    let mut offsets = vec![Vec2::ZERO; sizes.len()];
+    let mut scale = 1.0;
+    // The padding depends on the scale, which depends on the packing, so the packing is repeated
+    // until the scale converges
+    for _ in 0..8 {
+        let gap = padding / scale;
+        let area: f32 = sizes
+            .iter()
+            .map(|size| (size.x + gap) * (size.y + gap))
+            .sum();
+        let widest = sizes.iter().map(|size| size.x).fold(0.0, f32::max);
+        let row_width = area.sqrt().max(widest + gap);
+
+        let mut cursor = Vec2::splat(gap);
+        let mut row_height = 0.0f32;
+        let mut extent = Vec2::ZERO;
+        for &chart in &order {
+            let size = sizes[chart];
+            if cursor.x > gap && cursor.x + size.x > row_width {
+                cursor = Vec2::new(gap, cursor.y + row_height + gap);
+                row_height = 0.0;
+            }
+            offsets[chart] = cursor;
+            cursor.x += size.x + gap;
+            row_height = row_height.max(size.y);
+            extent = extent.max(cursor + Vec2::new(0.0, row_height + gap));
+        }
+
+        let new_scale = 1.0 / extent.max_element().max(f32::EPSILON);
+        let converged = (new_scale - scale).abs() <= scale * 1e-3;
+        scale = new_scale;
+        if converged {
+            break;
+        }
+    }
+    (offsets, scale)
+}
+
+/// A disjoint-set forest over the triangles, to find the charts they belong to.
+struct UnionFind(Vec<usize>);
+
+impl UnionFind {
+    fn new(len: usize) -> Self {
+        Self((0..len).collect())
+    }
+
+    fn find(&mut self, mut i: usize) -> usize {
+        while self.0[i] != i {
+            self.0[i] = self.0[self.0[i]];
+            i = self.0[i];
+        }
+        i
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        self.0[a.max(b)] = a.min(b);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mesh::VertexAttributeValues;
+    use bevy_math::primitives::Cuboid;
+
+    #[test]
+    fn lightmap_uvs_in_unit_square() {
+        let mut mesh = Mesh::from(Cuboid::new(1.0, 2.0, 3.0));
+        mesh.generate_lightmap_uvs(0.01).unwrap();
+
+        // Each face of the cuboid is a chart
+        assert_eq!(mesh.count_vertices(), 24);
+        let Some(VertexAttributeValues::Float32x2(uvs)) = mesh.attribute(Mesh::ATTRIBUTE_UV_1)
+        else {
+            panic!("lightmap UVs should be generated");
+        };
+        assert!(uvs
+            .iter()
+            .flatten()
+            .all(|coordinate| (0.0..=1.0).contains(coordinate)));
+    }
+}
@@ -0,0 +1,272 @@
+use super::{
+    Indices, Mesh, MeshVertexAttribute, MeshVertexAttributeId, PrimitiveTopology,
+    VertexAttributeValues,
+};
+use crate::render_asset::RenderAssetUsages;
+use bevy_asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext};
+use bytemuck::Pod;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use wgpu::VertexFormat;
+
+/// The magic bytes at the start of every binary mesh file.
+pub(crate) const MESH_FILE_MAGIC: [u8; 4] = *b"BMSH";
+/// The version of the binary mesh format written by the [`MeshSaver`].
+pub(crate) const MESH_FILE_VERSION: u32 = 1;
+
+/// Converts values made of `component_size` byte components between little endian and the native
+/// byte order. The conversion is its own inverse, so it is used for both reading and writing.
+pub(crate) fn convert_little_endian(bytes: &mut [u8], component_size: usize) {
+    if cfg!(target_endian = "big") {
+        for component in bytes.chunks_exact_mut(component_size) {
+            component.reverse();
+        }
+    }
+}
+
+/// The size in bytes of a single component of a vertex format, given by its name (e.g. 4 for `Float32x3`).
+pub(crate) fn component_size(format: &str) -> usize {
+    if format.contains("8x") {
+        1
+    } else if format.contains("16x") {
+        2
+    } else {
+        4
+    }
+}
+
+/// Loads [`Mesh`]es saved in Bevy's binary mesh format (`.mesh`) by the [`MeshSaver`].
+///
+/// Vertex attributes are stored by id, so the loader has to know every attribute it may
+/// encounter. The built-in [`Mesh`] attributes are always known, custom attributes can be added
+/// with [`MeshLoader::with_attribute`].
+#[derive(Clone)]
+pub struct MeshLoader {
+    attributes: Vec<MeshVertexAttribute>,
+}
+
+impl Default for MeshLoader {
+    fn default() -> Self {
+        Self {
+            attributes: vec![
+                Mesh::ATTRIBUTE_POSITION,
+                Mesh::ATTRIBUTE_NORMAL,
+                Mesh::ATTRIBUTE_UV_0,
+                Mesh::ATTRIBUTE_UV_1,
+                Mesh::ATTRIBUTE_TANGENT,
+                Mesh::ATTRIBUTE_COLOR,
+                Mesh::ATTRIBUTE_JOINT_WEIGHT,
+                Mesh::ATTRIBUTE_JOINT_INDEX,
+            ],
+        }
+    }
+}
+
+impl MeshLoader {
+    /// Registers a custom vertex attribute that can be found in loaded meshes.
+    #[must_use]
+    pub fn with_attribute(mut self, attribute: MeshVertexAttribute) -> Self {
+        self.attributes.push(attribute);
+        self
+    }
+}
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct MeshLoaderSettings {
+    pub asset_usage: RenderAssetUsages,
+}
+
+/// Possible errors that can be produced by [`MeshLoader`]
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum MeshLoaderError {
+    #[error("Could not read mesh file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Not a binary mesh file")]
+    InvalidMagic,
+    #[error("Unsupported binary mesh version {0}, expected {MESH_FILE_VERSION}")]
+    UnsupportedVersion(u32),
+    #[error("Unexpected end of mesh file")]
+    UnexpectedEof,
+    #[error("Invalid primitive topology {0}")]
+    InvalidTopology(u8),
+    #[error("Invalid index format {0}")]
+    InvalidIndexFormat(u8),
+    #[error("Unknown vertex attribute id {0}, register it with `MeshLoader::with_attribute`")]
+    UnknownAttribute(u64),
+    #[error("Unknown vertex format {0}")]
+    UnknownVertexFormat(String),
+    #[error("Vertex attribute {name} has format {found:?} but {expected:?} was expected")]
+    AttributeFormatMismatch {
+        name: &'static str,
+        expected: VertexFormat,
+        found: VertexFormat,
+    },
+}
+
+impl AssetLoader for MeshLoader {
+    type Asset = Mesh;
+    type Settings = MeshLoaderSettings;
+    type Error = MeshLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        settings: &'a MeshLoaderSettings,
+        _load_context: &'a mut LoadContext<'_>,
+    ) -> Result<Mesh, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        self.read_mesh(&bytes, settings.asset_usage)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["mesh"]
+    }
+}
+
+impl MeshLoader {
+    pub(crate) fn read_mesh(
+        &self,
+        bytes: &[u8],
+        asset_usage: RenderAssetUsages,
+    ) -> Result<Mesh, MeshLoaderError> {
+        let mut reader = ByteReader(bytes);
+        if reader.take(MESH_FILE_MAGIC.len())? != MESH_FILE_MAGIC {
+            return Err(MeshLoaderError::InvalidMagic);
+        }
+        let version = reader.u32()?;
+        if version != MESH_FILE_VERSION {
+            return Err(MeshLoaderError::UnsupportedVersion(version));
+        }
+
+        let topology = match reader.u8()? {
+            0 => PrimitiveTopology::PointList,
+            1 => PrimitiveTopology::LineList,
+            2 => PrimitiveTopology::LineStrip,
+            3 => PrimitiveTopology::TriangleList,
+            4 => PrimitiveTopology::TriangleStrip,
+            topology => return Err(MeshLoaderError::InvalidTopology(topology)),
+        };
+        let mut mesh = Mesh::new(topology, asset_usage);
+
+        match reader.u8()? {
+            0 => {}
+            1 => {
+                let count = reader.u32()? as usize;
+                mesh.insert_indices(Indices::U16(reader.values(count, 2)?));
+            }
+            2 => {
+                let count = reader.u32()? as usize;
+                mesh.insert_indices(Indices::U32(reader.values(count, 4)?));
+            }
+            format => return Err(MeshLoaderError::InvalidIndexFormat(format)),
+        }
+
+        let attribute_count = reader.u32()?;
+        for _ in 0..attribute_count {
+            let id = reader.u64()?;
+            let format_len = reader.u8()? as usize;
+            let format = std::str::from_utf8(reader.take(format_len)?)
+                .map_err(|_| MeshLoaderError::UnexpectedEof)?;
+            let count = reader.u32()? as usize;
+            let values = reader.attribute_values(format, count)?;
+
+            let attribute = self
+                .attributes
+                .iter()
+                .find(|attribute| attribute.id == MeshVertexAttributeId(id as usize))
+                .ok_or(MeshLoaderError::UnknownAttribute(id))?;
+            let found = VertexFormat::from(&values);
+            if found != attribute.format {
+                return Err(MeshLoaderError::AttributeFormatMismatch {
+                    name: attribute.name,
+                    expected: attribute.format,
+                    found,
+                });
+            }
+            mesh.insert_attribute(attribute.clone(), values);
+        }
+
+        Ok(mesh)
+    }
+}
+
+struct ByteReader<'a>(&'a [u8]);
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], MeshLoaderError> {
+        if self.0.len() < len {
+            return Err(MeshLoaderError::UnexpectedEof);
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn u8(&mut self) -> Result<u8, MeshLoaderError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, MeshLoaderError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, MeshLoaderError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    /// Reads `count` values of `T`, made of little endian components of `component_size` bytes.
+    fn values<T: Pod>(
+        &mut self,
+        count: usize,
+        component_size: usize,
+    ) -> Result<Vec<T>, MeshLoaderError> {
+        let size = std::mem::size_of::<T>();
+        let mut bytes = self.take(count * size)?.to_vec();
+        convert_little_endian(&mut bytes, component_size);
+        Ok(bytes
+            .chunks_exact(size)
+            .map(bytemuck::pod_read_unaligned)
+            .collect())
+    }
+
+    fn attribute_values(
+        &mut self,
+        format: &str,
+        count: usize,
+    ) -> Result<VertexAttributeValues, MeshLoaderError> {
+        let size = component_size(format);
+        Ok(match format {
+            "Float32" => VertexAttributeValues::Float32(self.values(count, size)?),
+            "Sint32" => VertexAttributeValues::Sint32(self.values(count, size)?),
+            "Uint32" => VertexAttributeValues::Uint32(self.values(count, size)?),
+            "Float32x2" => VertexAttributeValues::Float32x2(self.values(count, size)?),
+            "Sint32x2" => VertexAttributeValues::Sint32x2(self.values(count, size)?),
+            "Uint32x2" => VertexAttributeValues::Uint32x2(self.values(count, size)?),
+            "Float32x3" => VertexAttributeValues::Float32x3(self.values(count, size)?),
+            "Sint32x3" => VertexAttributeValues::Sint32x3(self.values(count, size)?),
+            "Uint32x3" => VertexAttributeValues::Uint32x3(self.values(count, size)?),
+            "Float32x4" => VertexAttributeValues::Float32x4(self.values(count, size)?),
+            "Sint32x4" => VertexAttributeValues::Sint32x4(self.values(count, size)?),
+            "Uint32x4" => VertexAttributeValues::Uint32x4(self.values(count, size)?),
+            "Sint16x2" => VertexAttributeValues::Sint16x2(self.values(count, size)?),
+            "Snorm16x2" => VertexAttributeValues::Snorm16x2(self.values(count, size)?),
+            "Uint16x2" => VertexAttributeValues::Uint16x2(self.values(count, size)?),
+            "Unorm16x2" => VertexAttributeValues::Unorm16x2(self.values(count, size)?),
+            "Sint16x4" => VertexAttributeValues::Sint16x4(self.values(count, size)?),
+            "Snorm16x4" => VertexAttributeValues::Snorm16x4(self.values(count, size)?),
+            "Uint16x4" => VertexAttributeValues::Uint16x4(self.values(count, size)?),
+            "Unorm16x4" => VertexAttributeValues::Unorm16x4(self.values(count, size)?),
+            "Sint8x2" => VertexAttributeValues::Sint8x2(self.values(count, size)?),
+            "Snorm8x2" => VertexAttributeValues::Snorm8x2(self.values(count, size)?),
+            "Uint8x2" => VertexAttributeValues::Uint8x2(self.values(count, size)?),
+            "Unorm8x2" => VertexAttributeValues::Unorm8x2(self.values(count, size)?),
+            "Sint8x4" => VertexAttributeValues::Sint8x4(self.values(count, size)?),
+            "Snorm8x4" => VertexAttributeValues::Snorm8x4(self.values(count, size)?),
+            "Uint8x4" => VertexAttributeValues::Uint8x4(self.values(count, size)?),
+            "Unorm8x4" => VertexAttributeValues::Unorm8x4(self.values(count, size)?),
+            format => return Err(MeshLoaderError::UnknownVertexFormat(format.to_string())),
+        })
+    }
+}
@@ -0,0 +1,177 @@
+use super::{
+    mesh_loader::{component_size, convert_little_endian, MESH_FILE_MAGIC, MESH_FILE_VERSION},
+    Indices, Mesh, MeshLoader, MeshLoaderSettings, PrimitiveTopology,
+};
+use bevy_asset::saver::{AssetSaver, SavedAsset};
+use futures_lite::AsyncWriteExt;
+use thiserror::Error;
+
+/// Saves a [`Mesh`] in Bevy's binary mesh format (`.mesh`), which can be read back by the [`MeshLoader`].
+///
+/// The topology, the indices and every vertex attribute are saved, with all values stored little endian.
+/// Meshes with morph targets can't be saved, as their displacements are stored in a separate
+/// [`Image`](crate::texture::Image) asset.
+#[derive(Clone, Default)]
+pub struct MeshSaver;
+
+/// Possible errors that can be produced by [`MeshSaver`]
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum MeshSaverError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("meshes with morph targets can't be saved")]
+    MorphTargets,
+}
+
+impl AssetSaver for MeshSaver {
+    type Asset = Mesh;
+    type Settings = ();
+    type OutputLoader = MeshLoader;
+    type Error = MeshSaverError;
+
+    async fn save<'a>(
+        &'a self,
+        writer: &'a mut bevy_asset::io::Writer,
+        mesh: SavedAsset<'a, Self::Asset>,
+        _settings: &'a Self::Settings,
+    ) -> Result<MeshLoaderSettings, Self::Error> {
+        if mesh.has_morph_targets() {
+            return Err(MeshSaverError::MorphTargets);
+        }
+        writer.write_all(&mesh.write_bytes()).await?;
+        Ok(MeshLoaderSettings {
+            asset_usage: mesh.asset_usage,
+        })
+    }
+}
+
+impl Mesh {
+    /// Serializes the mesh in the binary format read by the [`MeshLoader`].
+    pub(crate) fn write_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(&MESH_FILE_MAGIC);
+        bytes.extend_from_slice(&MESH_FILE_VERSION.to_le_bytes());
+        bytes.push(match self.primitive_topology {
+            PrimitiveTopology::PointList => 0,
+            PrimitiveTopology::LineList => 1,
+            PrimitiveTopology::LineStrip => 2,
+            PrimitiveTopology::TriangleList => 3,
+            PrimitiveTopology::TriangleStrip => 4,
+        });
+
+        match &self.indices {
+            None => bytes.push(0),
+            Some(Indices::U16(indices)) => {
+                bytes.push(1);
+                bytes.extend_from_slice(&(indices.len() as u32).to_le_bytes());
+                bytes.extend(indices.iter().flat_map(|index| index.to_le_bytes()));
+            }
+            Some(Indices::U32(indices)) => {
+                bytes.push(2);
+                bytes.extend_from_slice(&(indices.len() as u32).to_le_bytes());
+                bytes.extend(indices.iter().flat_map(|index| index.to_le_bytes()));
+            }
+        }
+
+        bytes.extend_from_slice(&(self.attributes.len() as u32).to_le_bytes());
+        for data in self.attributes.values() {
+            let format = data.values.enum_variant_name();
+            bytes.extend_from_slice(&(data.attribute.id.0 as u64).to_le_bytes());
+            bytes.push(format.len() as u8);
+            bytes.extend_from_slice(format.as_bytes());
+            bytes.extend_from_slice(&(data.values.len() as u32).to_le_bytes());
+            let mut values = data.values.get_bytes().to_vec();
+            convert_little_endian(&mut values, component_size(format));
+            bytes.extend_from_slice(&values);
+        }
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        mesh::{MeshLoaderError, MeshVertexAttribute, VertexAttributeValues},
+        render_asset::RenderAssetUsages,
+    };
+    use bevy_asset::{Handle, LoadedAsset};
+    use wgpu::VertexFormat;
+
+    #[test]
+    fn round_trip() {
+        const ATTRIBUTE_CUSTOM: MeshVertexAttribute =
+            MeshVertexAttribute::new("Custom", 988540917, VertexFormat::Uint8x4);
+
+        let mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            vec![[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]],
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, vec![[0., 0.], [1., 0.], [0., 1.]])
+        .with_inserted_attribute(
+            ATTRIBUTE_CUSTOM,
+            VertexAttributeValues::Uint8x4(vec![[1, 2, 3, 4]; 3]),
+        )
+        .with_inserted_indices(Indices::U16(vec![0, 1, 2]));
+        let bytes = mesh.write_bytes();
+
+        assert!(matches!(
+            MeshLoader::default().read_mesh(&bytes, RenderAssetUsages::default()),
+            Err(MeshLoaderError::UnknownAttribute(988540917))
+        ));
+
+        let loaded = MeshLoader::default()
+            .with_attribute(ATTRIBUTE_CUSTOM)
+            .read_mesh(&bytes, RenderAssetUsages::default())
+            .unwrap();
+        assert_eq!(loaded.primitive_topology(), PrimitiveTopology::TriangleList);
+        assert!(matches!(loaded.indices(), Some(Indices::U16(indices)) if indices == &[0, 1, 2]));
+        assert_eq!(loaded.attributes().count(), 3);
+        for (id, values) in mesh.attributes() {
+            assert_eq!(
+                loaded.attribute(id).unwrap().get_bytes(),
+                values.get_bytes()
+            );
+        }
+    }
+
+    #[test]
+    fn values_are_little_endian() {
+        let mesh = Mesh::new(PrimitiveTopology::PointList, RenderAssetUsages::default())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, vec![[1., 2., 3.]])
+            .with_inserted_indices(Indices::U32(vec![258]));
+        let bytes = mesh.write_bytes();
+
+        // Magic, version, topology, index format and index count come first
+        assert_eq!(bytes[14..18], 258u32.to_le_bytes());
+        let positions = [1f32, 2., 3.].map(f32::to_le_bytes).concat();
+        assert!(bytes.ends_with(&positions));
+    }
+
+    #[test]
+    fn morph_targets_are_an_error() {
+        let mesh = LoadedAsset::from(
+            Mesh::new(
+                PrimitiveTopology::TriangleList,
+                RenderAssetUsages::default(),
+            )
+            .with_morph_targets(Handle::weak_from_u128(
+                296212763105473619281740935218163718291,
+            )),
+        )
+        .into();
+        let mut bytes = Vec::new();
+        let result = futures_lite::future::block_on(MeshSaver.save(
+            &mut bytes,
+            SavedAsset::from_loaded(&mesh).unwrap(),
+            &(),
+        ));
+        assert!(matches!(result, Err(MeshSaverError::MorphTargets)));
+        assert!(bytes.is_empty());
+    }
+}
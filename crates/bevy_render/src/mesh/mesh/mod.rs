@@ -1,7 +1,15 @@
 mod conversions;
+mod lightmap_uvs;
+mod mesh_loader;
+mod mesh_saver;
+mod processing;
+mod simplify;
 pub mod skinning;
 use bevy_transform::components::Transform;
 use bitflags::bitflags;
+pub use mesh_loader::*;
+pub use mesh_saver::*;
+pub use processing::*;
 pub use wgpu::PrimitiveTopology;
 
 use crate::{
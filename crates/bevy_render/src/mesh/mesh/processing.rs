@@ -0,0 +1,506 @@
+use super::{Indices, Mesh, PrimitiveTopology, VertexAttributeValues};
+use bevy_asset::transformer::{AssetTransformer, TransformedAsset};
+use bevy_math::{primitives::Triangle3d, IVec3, Vec3};
+use bevy_utils::HashMap;
+use bytemuck::cast_slice;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// An error from an operation on the triangles of a [`Mesh`], such as [`Mesh::weld_vertices`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MeshProcessingError {
+    #[error("the mesh has the {0:?} topology, but triangles are required")]
+    WrongTopology(PrimitiveTopology),
+    #[error("the mesh has no `Mesh::ATTRIBUTE_POSITION` attribute")]
+    MissingPositions,
+    #[error("the `Mesh::ATTRIBUTE_POSITION` attribute of the mesh isn't of type `Float32x3`")]
+    InvalidPositions,
+    #[error("the mesh has an index out of bounds of its vertices")]
+    InvalidIndices,
+    #[error("the mesh has morph targets, which can't be kept when its vertices change")]
+    MorphTargets,
+}
+
+/// An iterator over the vertex indices of the triangles of a [`Mesh`], see
+/// [`Mesh::triangle_indices`].
+#[derive(Clone)]
+pub struct TriangleIndices<'a> {
+    indices: Option<&'a Indices>,
+    len: usize,
+    strip: bool,
+    next: usize,
+}
+
+impl TriangleIndices<'_> {
+    fn index(&self, i: usize) -> usize {
+        match self.indices {
+            Some(Indices::U16(indices)) => indices[i] as usize,
+            Some(Indices::U32(indices)) => indices[i] as usize,
+            None => i,
+        }
+    }
+}
+
+impl Iterator for TriangleIndices<'_> {
+    type Item = [usize; 3];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.next;
+        if first + 2 >= self.len {
+            return None;
+        }
+        let triangle = [
+            self.index(first),
+            self.index(first + 1),
+            self.index(first + 2),
+        ];
+        if !self.strip {
+            self.next += 3;
+            return Some(triangle);
+        }
+
+        self.next += 1;
+        // Every other triangle of a strip is wound the other way
+        Some(if first % 2 == 0 {
+            triangle
+        } else {
+            [triangle[1], triangle[0], triangle[2]]
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len.saturating_sub(self.next + 2);
+        let len = if self.strip {
+            remaining
+        } else {
+            remaining.div_ceil(3)
+        };
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for TriangleIndices<'_> {}
+
+impl Mesh {
+    /// Returns an iterator over the vertex indices of the triangles of the mesh, with the
+    /// winding of [`PrimitiveTopology::TriangleStrip`]s made consistent.
+    ///
+    /// Returns an error if the mesh doesn't have a [`PrimitiveTopology::TriangleList`] or
+    /// [`PrimitiveTopology::TriangleStrip`] topology, or if an index is out of bounds of the
+    /// [`Mesh::ATTRIBUTE_POSITION`]s.
+    pub fn triangle_indices(&self) -> Result<TriangleIndices<'_>, MeshProcessingError> {
+        let strip = match self.primitive_topology {
+            PrimitiveTopology::TriangleList => false,
+            PrimitiveTopology::TriangleStrip => true,
+            topology => return Err(MeshProcessingError::WrongTopology(topology)),
+        };
+        let vertex_count = self.positions()?.len();
+        let len = match &self.indices {
+            Some(indices) => {
+                if indices.iter().any(|index| index >= vertex_count) {
+                    return Err(MeshProcessingError::InvalidIndices);
+                }
+                indices.len()
+            }
+            None => vertex_count,
+        };
+        Ok(TriangleIndices {
+            indices: self.indices.as_ref(),
+            len,
+            strip,
+            next: 0,
+        })
+    }
+
+    /// Returns an iterator over the triangles of the mesh, see [`Mesh::triangle_indices`].
+    pub fn triangles(
+        &self,
+    ) -> Result<impl ExactSizeIterator<Item = Triangle3d> + '_, MeshProcessingError> {
+        let positions = self.positions()?;
+        Ok(self.triangle_indices()?.map(|[a, b, c]| {
+            Triangle3d::new(
+                positions[a].into(),
+                positions[b].into(),
+                positions[c].into(),
+            )
+        }))
+    }
+
+    pub(super) fn positions(&self) -> Result<&[[f32; 3]], MeshProcessingError> {
+        self.attribute(Mesh::ATTRIBUTE_POSITION)
+            .ok_or(MeshProcessingError::MissingPositions)?
+            .as_float3()
+            .ok_or(MeshProcessingError::InvalidPositions)
+    }
+
+    /// Merges the vertices that have the same attributes, turning the mesh into an indexed
+    /// [`PrimitiveTopology::TriangleList`]. This is the inverse of [`Mesh::duplicate_vertices`].
+    ///
+    /// Vertices are merged when their positions are within `tolerance` of each other and each
+    /// component of their other floating point attributes differs by at most `tolerance`. Other
+    /// attributes, such as joint indices, must be equal. Triangles left with less than 3 distinct
+    /// vertices are removed.
+    pub fn weld_vertices(&mut self, tolerance: f32) -> Result<(), MeshProcessingError> {
+        if self.morph_targets.is_some() {
+            return Err(MeshProcessingError::MorphTargets);
+        }
+        let triangles: Vec<[usize; 3]> = self.triangle_indices()?.collect();
+        let positions = self.positions()?;
+        let attributes: Vec<AttributeView> = self
+            .attributes
+            .iter()
+            .filter(|(id, _)| **id != Mesh::ATTRIBUTE_POSITION.id)
+            .map(|(_, data)| AttributeView::new(&data.values))
+            .collect();
+
+        // Vertices are hashed in cells the size of the tolerance, so that vertices to merge are
+        // always in neighboring cells
+        let cell_size = tolerance.max(f32::EPSILON);
+        let cell = |position: Vec3| (position / cell_size).floor().as_ivec3();
+        let mut cells: HashMap<_, Vec<usize>> = HashMap::new();
+        let mut new_to_old = Vec::new();
+        let mut old_to_new = vec![0; positions.len()];
+
+        for (vertex, position) in positions.iter().enumerate() {
+            let position = Vec3::from(*position);
+            let center = cell(position);
+            let mut welded = None;
+            'search: for x in -1..=1 {
+                for y in -1..=1 {
+                    for z in -1..=1 {
+                        let Some(candidates) = cells.get(&(center + IVec3::new(x, y, z))) else {
+                            continue;
+                        };
+                        welded = candidates.iter().copied().find(|&candidate: &usize| {
+                            let other = Vec3::from(positions[new_to_old[candidate] as usize]);
+                            position.distance(other) <= tolerance
+                                && attributes.iter().all(|attribute| {
+                                    attribute.matches(
+                                        vertex,
+                                        new_to_old[candidate] as usize,
+                                        tolerance,
+                                    )
+                                })
+                        });
+                        if welded.is_some() {
+                            break 'search;
+                        }
+                    }
+                }
+            }
+            old_to_new[vertex] = welded.unwrap_or_else(|| {
+                new_to_old.push(vertex as u32);
+                cells.entry(center).or_default().push(new_to_old.len() - 1);
+                new_to_old.len() - 1
+            }) as u32;
+        }
+
+        let indices: Vec<u32> = triangles
+            .iter()
+            .map(|triangle| triangle.map(|vertex| old_to_new[vertex]))
+            .filter(|[a, b, c]| a != b && b != c && a != c)
+            .flatten()
+            .collect();
+        self.remap_vertices(&new_to_old, indices);
+        Ok(())
+    }
+
+    /// Consumes the mesh and returns it with its vertices welded, see [`Mesh::weld_vertices`].
+    pub fn with_welded_vertices(mut self, tolerance: f32) -> Result<Self, MeshProcessingError> {
+        self.weld_vertices(tolerance)?;
+        Ok(self)
+    }
+
+    /// Calculates the [`Mesh::ATTRIBUTE_NORMAL`] of a mesh, averaging the normals of the
+    /// triangles sharing each vertex weighted by the angle of their corner at the vertex.
+    ///
+    /// Only vertices shared through [`Indices`] are smoothed, [`Mesh::weld_vertices`] can merge
+    /// the vertices at the same position beforehand.
+    ///
+    /// # Panics
+    /// Panics if [`Mesh::ATTRIBUTE_POSITION`] is not of type `float3`, if the mesh has any other
+    /// topology than [`PrimitiveTopology::TriangleList`] or [`PrimitiveTopology::TriangleStrip`],
+    /// or if an index is out of bounds.
+    pub fn compute_smooth_normals(&mut self) {
+        let positions = self
+            .positions()
+            .expect("`compute_smooth_normals` requires `float3` positions");
+        let mut normals = vec![Vec3::ZERO; positions.len()];
+        for triangle in self
+            .triangle_indices()
+            .expect("`compute_smooth_normals` can only work on triangles")
+        {
+            let corners = triangle.map(|vertex| Vec3::from(positions[vertex]));
+            let normal = (corners[1] - corners[0])
+                .cross(corners[2] - corners[0])
+                .normalize_or_zero();
+            if normal == Vec3::ZERO {
+                continue;
+            }
+            for i in 0..3 {
+                let corner = corners[i];
+                let angle =
+                    (corners[(i + 1) % 3] - corner).angle_between(corners[(i + 2) % 3] - corner);
+                normals[triangle[i]] += normal * angle;
+            }
+        }
+
+        let normals: Vec<[f32; 3]> = normals
+            .into_iter()
+            .map(|normal| normal.normalize_or_zero().into())
+            .collect();
+        self.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+    }
+
+    /// Consumes the mesh and returns it with smooth normals, see
+    /// [`Mesh::compute_smooth_normals`].
+    ///
+    /// # Panics
+    /// Panics in the same cases as [`Mesh::compute_smooth_normals`].
+    #[must_use]
+    pub fn with_computed_smooth_normals(mut self) -> Self {
+        self.compute_smooth_normals();
+        self
+    }
+
+    /// Replaces the vertices with the ones at the `new_to_old` indices and the indices with a
+    /// triangle list of the new vertices.
+    pub(super) fn remap_vertices(&mut self, new_to_old: &[u32], indices: Vec<u32>) {
+        for data in self.attributes.values_mut() {
+            data.values = data.values.remapped(new_to_old);
+        }
+        self.primitive_topology = PrimitiveTopology::TriangleList;
+        self.indices = Some(if new_to_old.len() <= u16::MAX as usize + 1 {
+            Indices::U16(indices.into_iter().map(|index| index as u16).collect())
+        } else {
+            Indices::U32(indices)
+        });
+    }
+}
+
+impl VertexAttributeValues {
+    /// Returns the values at the `new_to_old` indices.
+    fn remapped(&self, new_to_old: &[u32]) -> Self {
+        fn remap<T: Copy>(values: &[T], new_to_old: &[u32]) -> Vec<T> {
+            new_to_old.iter().map(|&i| values[i as usize]).collect()
+        }
+
+        use VertexAttributeValues::*;
+        match self {
+            Float32(values) => Float32(remap(values, new_to_old)),
+            Sint32(values) => Sint32(remap(values, new_to_old)),
+            Uint32(values) => Uint32(remap(values, new_to_old)),
+            Float32x2(values) => Float32x2(remap(values, new_to_old)),
+            Sint32x2(values) => Sint32x2(remap(values, new_to_old)),
+            Uint32x2(values) => Uint32x2(remap(values, new_to_old)),
+            Float32x3(values) => Float32x3(remap(values, new_to_old)),
+            Sint32x3(values) => Sint32x3(remap(values, new_to_old)),
+            Uint32x3(values) => Uint32x3(remap(values, new_to_old)),
+            Float32x4(values) => Float32x4(remap(values, new_to_old)),
+            Sint32x4(values) => Sint32x4(remap(values, new_to_old)),
+            Uint32x4(values) => Uint32x4(remap(values, new_to_old)),
+            Sint16x2(values) => Sint16x2(remap(values, new_to_old)),
+            Snorm16x2(values) => Snorm16x2(remap(values, new_to_old)),
+            Uint16x2(values) => Uint16x2(remap(values, new_to_old)),
+            Unorm16x2(values) => Unorm16x2(remap(values, new_to_old)),
+            Sint16x4(values) => Sint16x4(remap(values, new_to_old)),
+            Snorm16x4(values) => Snorm16x4(remap(values, new_to_old)),
+            Uint16x4(values) => Uint16x4(remap(values, new_to_old)),
+            Unorm16x4(values) => Unorm16x4(remap(values, new_to_old)),
+            Sint8x2(values) => Sint8x2(remap(values, new_to_old)),
+            Snorm8x2(values) => Snorm8x2(remap(values, new_to_old)),
+            Uint8x2(values) => Uint8x2(remap(values, new_to_old)),
+            Unorm8x2(values) => Unorm8x2(remap(values, new_to_old)),
+            Sint8x4(values) => Sint8x4(remap(values, new_to_old)),
+            Snorm8x4(values) => Snorm8x4(remap(values, new_to_old)),
+            Uint8x4(values) => Uint8x4(remap(values, new_to_old)),
+            Unorm8x4(values) => Unorm8x4(remap(values, new_to_old)),
+        }
+    }
+}
+
+/// The values of a vertex attribute, compared per vertex when welding.
+enum AttributeView<'a> {
+    /// Floating point components, compared with a tolerance.
+    Float { values: &'a [f32], stride: usize },
+    /// Any other format, compared bit for bit.
+    Bytes { values: &'a [u8], stride: usize },
+}
+
+impl<'a> AttributeView<'a> {
+    fn new(values: &'a VertexAttributeValues) -> Self {
+        let float = |values: &'a [f32], stride| AttributeView::Float { values, stride };
+        match values {
+            VertexAttributeValues::Float32(values) => float(values, 1),
+            VertexAttributeValues::Float32x2(values) => float(cast_slice(values.as_slice()), 2),
+            VertexAttributeValues::Float32x3(values) => float(cast_slice(values.as_slice()), 3),
+            VertexAttributeValues::Float32x4(values) => float(cast_slice(values.as_slice()), 4),
+            values => AttributeView::Bytes {
+                values: values.get_bytes(),
+                stride: values.get_bytes().len() / values.len().max(1),
+            },
+        }
+    }
+
+    fn matches(&self, a: usize, b: usize, tolerance: f32) -> bool {
+        match *self {
+            AttributeView::Float { values, stride } => values[a * stride..(a + 1) * stride]
+                .iter()
+                .zip(&values[b * stride..(b + 1) * stride])
+                .all(|(a, b)| (a - b).abs() <= tolerance),
+            AttributeView::Bytes { values, stride } => {
+                values[a * stride..(a + 1) * stride] == values[b * stride..(b + 1) * stride]
+            }
+        }
+    }
+}
+
+/// An [`AssetTransformer`] running the processing operations of [`Mesh`] in an asset
+/// processor, ahead of the [`MeshSaver`](super::MeshSaver).
+///
+/// The enabled steps run in the order of the fields of [`MeshTransformerSettings`].
+#[derive(Default)]
+pub struct MeshTransformer;
+
+/// Settings for the [`MeshTransformer`], with every step disabled by default.
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct MeshTransformerSettings {
+    /// Welds the vertices with this tolerance, see [`Mesh::weld_vertices`].
+    pub weld_tolerance: Option<f32>,
+    /// Simplifies the mesh to this fraction of its triangles, see [`Mesh::simplify`].
+    pub simplify_ratio: Option<f32>,
+    /// Replaces the normals with smooth normals, see [`Mesh::compute_smooth_normals`].
+    pub smooth_normals: bool,
+    /// Generates lightmap UVs with this padding, see [`Mesh::generate_lightmap_uvs`].
+    pub lightmap_uv_padding: Option<f32>,
+}
+
+impl AssetTransformer for MeshTransformer {
+    type AssetInput = Mesh;
+    type AssetOutput = Mesh;
+    type Settings = MeshTransformerSettings;
+    type Error = MeshProcessingError;
+
+    async fn transform<'a>(
+        &'a self,
+        mut mesh: TransformedAsset<Mesh>,
+        settings: &'a Self::Settings,
+    ) -> Result<TransformedAsset<Mesh>, Self::Error> {
+        if let Some(tolerance) = settings.weld_tolerance {
+            mesh.weld_vertices(tolerance)?;
+        }
+        if let Some(ratio) = settings.simplify_ratio {
+            let triangle_count = mesh.triangle_indices()?.len();
+            mesh.simplify((triangle_count as f32 * ratio) as usize)?;
+        }
+        if settings.smooth_normals {
+            // Checks the mesh first, as `compute_smooth_normals` panics on invalid meshes
+            mesh.triangle_indices()?;
+            mesh.compute_smooth_normals();
+        }
+        if let Some(padding) = settings.lightmap_uv_padding {
+            mesh.generate_lightmap_uvs(padding)?;
+        }
+        Ok(mesh)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render_asset::RenderAssetUsages;
+
+    /// A unit quad on the XY plane made of two triangles without shared vertices.
+    fn quad() -> Mesh {
+        let positions = vec![
+            [0.0, 0.0, 0.0],
+            [1.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0],
+            [1.0, 1.0, 0.0],
+            [0.0, 1.0, 0.0],
+        ];
+        let uvs: Vec<[f32; 2]> = positions.iter().map(|p| [p[0], 1.0 - p[1]]).collect();
+        Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    }
+
+    #[test]
+    fn weld_shared_vertices() {
+        let mut mesh = quad();
+        mesh.weld_vertices(1e-4).unwrap();
+        assert_eq!(mesh.count_vertices(), 4);
+        assert_eq!(mesh.indices().unwrap().len(), 6);
+        assert_eq!(mesh.triangles().unwrap().len(), 2);
+
+        // Vertices with different UVs are kept apart
+        let mut mesh = quad();
+        if let Some(VertexAttributeValues::Float32x2(uvs)) =
+            mesh.attribute_mut(Mesh::ATTRIBUTE_UV_0)
+        {
+            uvs[3] = [0.5, 0.5];
+        }
+        mesh.weld_vertices(1e-4).unwrap();
+        assert_eq!(mesh.count_vertices(), 5);
+    }
+
+    #[test]
+    fn triangle_strip_winding() {
+        let mesh = Mesh::new(
+            PrimitiveTopology::TriangleStrip,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            vec![
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [1.0, 1.0, 0.0],
+            ],
+        );
+        let triangles: Vec<_> = mesh.triangle_indices().unwrap().collect();
+        assert_eq!(triangles, [[0, 1, 2], [2, 1, 3]]);
+        assert!(mesh
+            .triangles()
+            .unwrap()
+            .all(|triangle| triangle.normal().unwrap() == bevy_math::Dir3::Z));
+    }
+
+    #[test]
+    fn smooth_normals_weight_by_angle() {
+        // A corner of a cube, with the -Z face split in two triangles at the corner
+        let mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(
+            Mesh::ATTRIBUTE_POSITION,
+            vec![
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [1.0, 1.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [0.0, 0.0, 1.0],
+            ],
+        )
+        .with_inserted_indices(Indices::U32(vec![
+            0, 2, 1, 0, 3, 2, // -Z
+            0, 1, 4, // -Y
+            0, 4, 3, // -X
+        ]))
+        .with_computed_smooth_normals();
+        let Some(VertexAttributeValues::Float32x3(normals)) =
+            mesh.attribute(Mesh::ATTRIBUTE_NORMAL)
+        else {
+            panic!("normals should be computed");
+        };
+        // Splitting a face doesn't change its weight
+        let expected = Vec3::NEG_ONE.normalize();
+        assert!(Vec3::from(normals[0]).abs_diff_eq(expected, 1e-5));
+    }
+}
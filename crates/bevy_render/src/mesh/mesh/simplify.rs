@@ -0,0 +1,325 @@
+use super::{processing::MeshProcessingError, Mesh};
+use bevy_math::{DVec3, Vec3};
+use bevy_utils::HashMap;
+use std::{cmp::Ordering, collections::BinaryHeap};
+
+/// The smallest cosine of the angle a triangle can rotate by when simplifying, to avoid folds.
+const MIN_NORMAL_DOT: f64 = 0.2;
+
+impl Mesh {
+    /// Reduces the number of triangles of the mesh down to `target_triangle_count`, or as close
+    /// as possible, turning it into an indexed [`PrimitiveTopology::TriangleList`](super::PrimitiveTopology::TriangleList).
+    ///
+    /// Edges are collapsed one at a time, picking the collapse that moves the surface the least
+    /// according to quadric error metrics. An edge collapses into one of its vertices, which
+    /// keeps all its attributes, so UVs, colors and skinning weights are preserved without being
+    /// interpolated.
+    ///
+    /// Vertices on edges used by a single triangle are never moved, which keeps the borders of
+    /// the mesh and the seams between vertices with different attributes in place. Vertices
+    /// should therefore be shared between triangles, see [`Mesh::weld_vertices`], otherwise every
+    /// edge is a border and the mesh can't be simplified.
+    ///
+    /// Normals and tangents are kept from the remaining vertices, recomputing them afterwards may
+    /// give better results.
+    pub fn simplify(&mut self, target_triangle_count: usize) -> Result<(), MeshProcessingError> {
+        if self.morph_targets.is_some() {
+            return Err(MeshProcessingError::MorphTargets);
+        }
+        let mut triangles: Vec<[usize; 3]> = self.triangle_indices()?.collect();
+        let positions: Vec<DVec3> = self
+            .positions()?
+            .iter()
+            .map(|position| Vec3::from(*position).as_dvec3())
+            .collect();
+        let vertex_count = positions.len();
+
+        let mut quadrics = vec![Quadric::default(); vertex_count];
+        let mut vertex_triangles = vec![Vec::new(); vertex_count];
+        let mut edge_uses: HashMap<(usize, usize), u32> = HashMap::new();
+        for (triangle_index, triangle) in triangles.iter().enumerate() {
+            let [a, b, c] = triangle.map(|vertex| positions[vertex]);
+            // Weighting the plane by the area of the triangle keeps small triangles from
+            // affecting the error as much as large ones
+            let cross = (b - a).cross(c - a);
+            let area = cross.length() * 0.5;
+            if area > 0.0 {
+                let normal = cross / (area * 2.0);
+                let quadric = Quadric::from_plane(normal, -normal.dot(a)) * area;
+                for &vertex in triangle {
+                    quadrics[vertex] += quadric;
+                }
+            }
+            for i in 0..3 {
+                vertex_triangles[triangle[i]].push(triangle_index);
+                *edge_uses
+                    .entry(edge(triangle[i], triangle[(i + 1) % 3]))
+                    .or_default() += 1;
+            }
+        }
+
+        let mut locked = vec![false; vertex_count];
+        for (&(a, b), &uses) in &edge_uses {
+            if uses == 1 {
+                locked[a] = true;
+                locked[b] = true;
+            }
+        }
+
+        let mut removed_vertices = vec![false; vertex_count];
+        let mut removed_triangles = vec![false; triangles.len()];
+        let mut versions = vec![0u32; vertex_count];
+        let mut live_triangles = triangles.len();
+
+        let mut heap = BinaryHeap::new();
+        let push_collapse = |heap: &mut BinaryHeap<Collapse>,
+                             quadrics: &[Quadric],
+                             versions: &[u32],
+                             from: usize,
+                             to: usize| {
+            if !locked[from] {
+                heap.push(Collapse {
+                    cost: (quadrics[from] + quadrics[to]).error(positions[to]),
+                    from,
+                    to,
+                    versions: [versions[from], versions[to]],
+                });
+            }
+        };
+        for &(a, b) in edge_uses.keys() {
+            push_collapse(&mut heap, &quadrics, &versions, a, b);
+            push_collapse(&mut heap, &quadrics, &versions, b, a);
+        }
+
+        while live_triangles > target_triangle_count {
+            let Some(Collapse {
+                from,
+                to,
+                versions: collapse_versions,
+                ..
+            }) = heap.pop()
+            else {
+                break;
+            };
+            if removed_vertices[from]
+                || removed_vertices[to]
+                || collapse_versions != [versions[from], versions[to]]
+            {
+                continue;
+            }
+
+            // Moving `from` onto `to` must not flip or flatten the triangles that remain
+            let flips = vertex_triangles[from].iter().any(|&triangle_index| {
+                let triangle = triangles[triangle_index];
+                if removed_triangles[triangle_index] || triangle.contains(&to) {
+                    return false;
+                }
+                let [a, b, c] = triangle.map(|vertex| positions[vertex]);
+                let [new_a, new_b, new_c] =
+                    triangle.map(|vertex| positions[if vertex == from { to } else { vertex }]);
+                let old_normal = (b - a).cross(c - a).normalize_or_zero();
+                let new_normal = (new_b - new_a).cross(new_c - new_a).normalize_or_zero();
+                old_normal.dot(new_normal) < MIN_NORMAL_DOT
+            });
+            if flips {
+                continue;
+            }
+
+            for triangle_index in std::mem::take(&mut vertex_triangles[from]) {
+                if removed_triangles[triangle_index] {
+                    continue;
+                }
+                let triangle = &mut triangles[triangle_index];
+                if triangle.contains(&to) {
+                    removed_triangles[triangle_index] = true;
+                    live_triangles -= 1;
+                } else {
+                    for vertex in triangle.iter_mut().filter(|vertex| **vertex == from) {
+                        *vertex = to;
+                    }
+                    vertex_triangles[to].push(triangle_index);
+                }
+            }
+            removed_vertices[from] = true;
+            let quadric = quadrics[from];
+            quadrics[to] += quadric;
+            versions[to] += 1;
+
+            vertex_triangles[to].retain(|&triangle_index| !removed_triangles[triangle_index]);
+            let mut neighbors: Vec<usize> = vertex_triangles[to]
+                .iter()
+                .flat_map(|&triangle_index| triangles[triangle_index])
+                .filter(|&vertex| vertex != to)
+                .collect();
+            neighbors.sort_unstable();
+            neighbors.dedup();
+            for neighbor in neighbors {
+                push_collapse(&mut heap, &quadrics, &versions, neighbor, to);
+                push_collapse(&mut heap, &quadrics, &versions, to, neighbor);
+            }
+        }
+
+        // Only keep the vertices still used by a triangle
+        let mut old_to_new = vec![u32::MAX; vertex_count];
+        let mut new_to_old = Vec::new();
+        let indices: Vec<u32> = triangles
+            .iter()
+            .zip(&removed_triangles)
+            .filter(|(_, removed)| !**removed)
+            .flat_map(|(triangle, _)| *triangle)
+            .map(|vertex| {
+                if old_to_new[vertex] == u32::MAX {
+                    old_to_new[vertex] = new_to_old.len() as u32;
+                    new_to_old.push(vertex as u32);
+                }
+                old_to_new[vertex]
+            })
+            .collect();
+        self.remap_vertices(&new_to_old, indices);
+        Ok(())
+    }
+}
+
+fn edge(a: usize, b: usize) -> (usize, usize) {
+    (a.min(b), a.max(b))
+}
+
+/// The collapse of the vertex `from` onto the vertex `to`, ordered by lowest cost first.
+struct Collapse {
+    cost: f64,
+    from: usize,
+    to: usize,
+    /// The versions of the vertices when the cost was computed, which is outdated once either
+    /// vertex changes.
+    versions: [u32; 2],
+}
+
+impl PartialEq for Collapse {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Collapse {}
+
+impl PartialOrd for Collapse {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Collapse {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+/// The sum of the squared distances to a set of planes, stored as the upper triangle of a
+/// symmetric 4×4 matrix.
+#[derive(Clone, Copy, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    fn from_plane(normal: DVec3, d: f64) -> Self {
+        let [a, b, c] = normal.to_array();
+        Self([
+            a * a,
+            a * b,
+            a * c,
+            a * d,
+            b * b,
+            b * c,
+            b * d,
+            c * c,
+            c * d,
+            d * d,
+        ])
+    }
+
+    fn error(&self, p: DVec3) -> f64 {
+        let [a2, ab, ac, ad, b2, bc, bd, c2, cd, d2] = self.0;
+        let DVec3 { x, y, z } = p;
+        a2 * x * x
+            + 2.0 * ab * x * y
+            + 2.0 * ac * x * z
+            + 2.0 * ad * x
+            + b2 * y * y
+            + 2.0 * bc * y * z
+            + 2.0 * bd * y
+            + c2 * z * z
+            + 2.0 * cd * z
+            + d2
+    }
+}
+
+impl std::ops::Add for Quadric {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        self += rhs;
+        self
+    }
+}
+
+impl std::ops::AddAssign for Quadric {
+    fn add_assign(&mut self, rhs: Self) {
+        for (value, rhs) in self.0.iter_mut().zip(rhs.0) {
+            *value += rhs;
+        }
+    }
+}
+
+impl std::ops::Mul<f64> for Quadric {
+    type Output = Self;
+
+    fn mul(self, rhs: f64) -> Self {
+        Self(self.0.map(|value| value * rhs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{mesh::Indices, render_asset::RenderAssetUsages};
+    use bevy_math::primitives::Measured2d;
+    use wgpu::PrimitiveTopology;
+
+    #[test]
+    fn simplify_flat_grid() {
+        // A 2×2 grid on the XZ plane with 8×8 cells
+        const CELLS: u32 = 8;
+        let mut positions = Vec::new();
+        let mut indices = Vec::new();
+        for z in 0..=CELLS {
+            for x in 0..=CELLS {
+                positions.push([
+                    x as f32 / CELLS as f32 * 2.0,
+                    0.0,
+                    z as f32 / CELLS as f32 * 2.0,
+                ]);
+                if x < CELLS && z < CELLS {
+                    let i = z * (CELLS + 1) + x;
+                    let below = i + CELLS + 1;
+                    indices.extend([i, below, i + 1, i + 1, below, below + 1]);
+                }
+            }
+        }
+        let mut mesh = Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_indices(Indices::U32(indices));
+        let triangle_count = mesh.triangles().unwrap().len();
+        mesh.simplify(triangle_count / 4).unwrap();
+
+        assert!(mesh.triangles().unwrap().len() < triangle_count / 2);
+        // The borders of the grid are locked, so the area of the plane is kept
+        let area: f32 = mesh
+            .triangles()
+            .unwrap()
+            .map(|triangle| triangle.area())
+            .sum();
+        assert!((area - 4.0).abs() < 1e-4);
+    }
+}
@@ -2,6 +2,7 @@
 mod mesh;
 pub mod morph;
 pub mod primitives;
+pub mod ray_cast;
 
 use bevy_utils::HashSet;
 pub use mesh::*;
@@ -23,12 +24,28 @@ impl Plugin for MeshPlugin {
     fn build(&self, app: &mut App) {
         app.init_asset::<Mesh>()
             .init_asset::<skinning::SkinnedMeshInverseBindposes>()
+            .init_asset_loader::<MeshLoader>()
             .register_asset_reflect::<Mesh>()
             .register_type::<skinning::SkinnedMesh>()
+            .register_type::<ray_cast::RayCastBackfaces>()
             .register_type::<Vec<Entity>>()
             // 'Mesh' must be prepared after 'Image' as meshes rely on the morph target image being ready
             .add_plugins(RenderAssetPlugin::<GpuMesh, GpuImage>::default());
 
+        if let Some(processor) = app
+            .world()
+            .get_resource::<bevy_asset::processor::AssetProcessor>()
+        {
+            processor.register_processor::<bevy_asset::processor::LoadTransformAndSave<
+                MeshLoader,
+                MeshTransformer,
+                MeshSaver,
+            >>(bevy_asset::processor::LoadTransformAndSave::new(
+                MeshTransformer,
+                MeshSaver,
+            ));
+        }
+
         let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
             return;
         };
@@ -4,10 +4,13 @@ use crate::{
     render_asset::RenderAssetUsages,
 };
 
-use super::Meshable;
-use bevy_math::primitives::{
-    Annulus, Capsule2d, Circle, Ellipse, Rectangle, RegularPolygon, Triangle2d, Triangle3d,
-    WindingOrder,
+use super::{MeshBuilder, Meshable};
+use bevy_math::{
+    primitives::{
+        Annulus, BoxedPolygon, Capsule2d, Circle, Ellipse, Polygon, Rectangle, RegularPolygon,
+        Triangle2d, Triangle3d, WindingOrder,
+    },
+    Vec2,
 };
 use wgpu::PrimitiveTopology;
 
@@ -48,10 +51,14 @@ impl CircleMeshBuilder {
         self.resolution = resolution;
         self
     }
+}
 
+impl MeshBuilder for CircleMeshBuilder {
     /// Builds a [`Mesh`] based on the configuration in `self`.
-    pub fn build(&self) -> Mesh {
-        RegularPolygon::new(self.circle.radius, self.resolution).mesh()
+    fn build(&self) -> Mesh {
+        RegularPolygon::new(self.circle.radius, self.resolution)
+            .mesh()
+            .build()
     }
 }
 
@@ -72,27 +79,48 @@ impl From<Circle> for Mesh {
     }
 }
 
-impl From<CircleMeshBuilder> for Mesh {
-    fn from(circle: CircleMeshBuilder) -> Self {
-        circle.build()
+/// A builder used for creating a [`Mesh`] with a [`RegularPolygon`] shape.
+#[derive(Clone, Copy, Debug)]
+pub struct RegularPolygonMeshBuilder {
+    /// The [`RegularPolygon`] shape.
+    pub polygon: RegularPolygon,
+}
+
+impl RegularPolygonMeshBuilder {
+    /// Creates a new [`RegularPolygonMeshBuilder`] from the radius of a circumcircle and a number
+    /// of sides.
+    #[inline]
+    pub fn new(circumradius: f32, sides: usize) -> Self {
+        Self {
+            polygon: RegularPolygon::new(circumradius, sides),
+        }
+    }
+}
+
+impl MeshBuilder for RegularPolygonMeshBuilder {
+    fn build(&self) -> Mesh {
+        // The ellipse mesh is just a regular polygon with two radii
+        Ellipse::new(
+            self.polygon.circumcircle.radius,
+            self.polygon.circumcircle.radius,
+        )
+        .mesh()
+        .resolution(self.polygon.sides)
+        .build()
     }
 }
 
 impl Meshable for RegularPolygon {
-    type Output = Mesh;
+    type Output = RegularPolygonMeshBuilder;
 
     fn mesh(&self) -> Self::Output {
-        // The ellipse mesh is just a regular polygon with two radii
-        Ellipse::new(self.circumcircle.radius, self.circumcircle.radius)
-            .mesh()
-            .resolution(self.sides)
-            .build()
+        RegularPolygonMeshBuilder { polygon: *self }
     }
 }
 
 impl From<RegularPolygon> for Mesh {
     fn from(polygon: RegularPolygon) -> Self {
-        polygon.mesh()
+        polygon.mesh().build()
     }
 }
 
@@ -133,9 +161,11 @@ impl EllipseMeshBuilder {
         self.resolution = resolution;
         self
     }
+}
 
+impl MeshBuilder for EllipseMeshBuilder {
     /// Builds a [`Mesh`] based on the configuration in `self`.
-    pub fn build(&self) -> Mesh {
+    fn build(&self) -> Mesh {
         let mut indices = Vec::with_capacity((self.resolution - 2) * 3);
         let mut positions = Vec::with_capacity(self.resolution);
         let normals = vec![[0.0, 0.0, 1.0]; self.resolution];
@@ -188,12 +218,6 @@ impl From<Ellipse> for Mesh {
     }
 }
 
-impl From<EllipseMeshBuilder> for Mesh {
-    fn from(ellipse: EllipseMeshBuilder) -> Self {
-        ellipse.build()
-    }
-}
-
 /// A builder for creating a [`Mesh`] with an [`Annulus`] shape.
 pub struct AnnulusMeshBuilder {
     /// The [`Annulus`] shape.
@@ -229,9 +253,11 @@ impl AnnulusMeshBuilder {
         self.resolution = resolution;
         self
     }
+}
 
+impl MeshBuilder for AnnulusMeshBuilder {
     /// Builds a [`Mesh`] based on the configuration in `self`.
-    pub fn build(&self) -> Mesh {
+    fn build(&self) -> Mesh {
         let inner_radius = self.annulus.inner_circle.radius;
         let outer_radius = self.annulus.outer_circle.radius;
 
@@ -306,17 +332,16 @@ impl From<Annulus> for Mesh {
     }
 }
 
-impl From<AnnulusMeshBuilder> for Mesh {
-    fn from(builder: AnnulusMeshBuilder) -> Self {
-        builder.build()
-    }
+/// A builder used for creating a [`Mesh`] with a [`Triangle2d`] shape.
+#[derive(Clone, Copy, Debug)]
+pub struct Triangle2dMeshBuilder {
+    /// The [`Triangle2d`] shape.
+    pub triangle: Triangle2d,
 }
 
-impl Meshable for Triangle2d {
-    type Output = Mesh;
-
-    fn mesh(&self) -> Self::Output {
-        let vertices_3d = self.vertices.map(|v| v.extend(0.));
+impl MeshBuilder for Triangle2dMeshBuilder {
+    fn build(&self) -> Mesh {
+        let vertices_3d = self.triangle.vertices.map(|v| v.extend(0.));
 
         let positions: Vec<_> = vertices_3d.into();
         let normals = vec![[0.0, 0.0, 1.0]; 3];
@@ -328,7 +353,7 @@ impl Meshable for Triangle2d {
         ))
         .into();
 
-        let is_ccw = self.winding_order() == WindingOrder::CounterClockwise;
+        let is_ccw = self.triangle.winding_order() == WindingOrder::CounterClockwise;
         let indices = if is_ccw {
             Indices::U32(vec![0, 1, 2])
         } else {
@@ -346,17 +371,40 @@ impl Meshable for Triangle2d {
     }
 }
 
+impl Meshable for Triangle2d {
+    type Output = Triangle2dMeshBuilder;
+
+    fn mesh(&self) -> Self::Output {
+        Triangle2dMeshBuilder { triangle: *self }
+    }
+}
+
 impl From<Triangle2d> for Mesh {
     fn from(triangle: Triangle2d) -> Self {
-        triangle.mesh()
+        triangle.mesh().build()
     }
 }
 
-impl Meshable for Rectangle {
-    type Output = Mesh;
+/// A builder used for creating a [`Mesh`] with a [`Rectangle`] shape.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct RectangleMeshBuilder {
+    /// The [`Rectangle`] shape.
+    pub rectangle: Rectangle,
+}
 
-    fn mesh(&self) -> Self::Output {
-        let [hw, hh] = [self.half_size.x, self.half_size.y];
+impl RectangleMeshBuilder {
+    /// Creates a new [`RectangleMeshBuilder`] from a full width and height.
+    #[inline]
+    pub fn new(width: f32, height: f32) -> Self {
+        Self {
+            rectangle: Rectangle::new(width, height),
+        }
+    }
+}
+
+impl MeshBuilder for RectangleMeshBuilder {
+    fn build(&self) -> Mesh {
+        let [hw, hh] = [self.rectangle.half_size.x, self.rectangle.half_size.y];
         let positions = vec![
             [hw, hh, 0.0],
             [-hw, hh, 0.0],
@@ -378,9 +426,130 @@ impl Meshable for Rectangle {
     }
 }
 
+impl Meshable for Rectangle {
+    type Output = RectangleMeshBuilder;
+
+    fn mesh(&self) -> Self::Output {
+        RectangleMeshBuilder { rectangle: *self }
+    }
+}
+
 impl From<Rectangle> for Mesh {
     fn from(rectangle: Rectangle) -> Self {
-        rectangle.mesh()
+        rectangle.mesh().build()
+    }
+}
+
+/// A builder used for creating a [`Mesh`] with a [`Polygon`] or [`BoxedPolygon`] shape.
+///
+/// The polygon can be convex or concave and wound in either direction, but its edges must not
+/// intersect each other.
+#[derive(Clone, Debug, Default)]
+pub struct PolygonMeshBuilder {
+    /// The vertices of the polygon, in order.
+    pub vertices: Vec<Vec2>,
+}
+
+impl PolygonMeshBuilder {
+    /// Creates a new [`PolygonMeshBuilder`] from the vertices of a polygon.
+    #[inline]
+    pub fn new(vertices: impl IntoIterator<Item = Vec2>) -> Self {
+        Self {
+            vertices: vertices.into_iter().collect(),
+        }
+    }
+}
+
+impl MeshBuilder for PolygonMeshBuilder {
+    fn build(&self) -> Mesh {
+        let vertices = &self.vertices;
+        let positions: Vec<[f32; 3]> = vertices.iter().map(|v| [v.x, v.y, 0.0]).collect();
+        let normals = vec![[0.0, 0.0, 1.0]; vertices.len()];
+
+        // Map the bounding rectangle of the polygon to the UV square, like a `Rectangle`
+        let min = vertices.iter().copied().fold(Vec2::INFINITY, Vec2::min);
+        let max = vertices.iter().copied().fold(Vec2::NEG_INFINITY, Vec2::max);
+        let size = (max - min).max(Vec2::splat(f32::EPSILON));
+        let uvs: Vec<[f32; 2]> = vertices
+            .iter()
+            .map(|v| [(v.x - min.x) / size.x, (max.y - v.y) / size.y])
+            .collect();
+
+        // Triangulate by clipping ears, walking the polygon counterclockwise
+        let signed_area: f32 = (0..vertices.len())
+            .map(|i| vertices[i].perp_dot(vertices[(i + 1) % vertices.len()]))
+            .sum();
+        let mut remaining: Vec<u32> = (0..vertices.len() as u32).collect();
+        if signed_area < 0.0 {
+            remaining.reverse();
+        }
+        let mut indices = Vec::with_capacity(vertices.len().saturating_sub(2) * 3);
+        while remaining.len() >= 3 {
+            let len = remaining.len();
+            let corner = |i: usize| {
+                [
+                    remaining[(i + len - 1) % len],
+                    remaining[i],
+                    remaining[(i + 1) % len],
+                ]
+            };
+            let is_ear = |i: usize| {
+                let [a, b, c] = corner(i).map(|index| vertices[index as usize]);
+                (b - a).perp_dot(c - b) > 0.0
+                    && !remaining.iter().any(|&other| {
+                        !corner(i).contains(&other)
+                            && triangle_contains(a, b, c, vertices[other as usize])
+                    })
+            };
+            // Degenerate polygons may have no ear left, in which case any corner is clipped
+            let ear = (0..len).find(|&i| is_ear(i)).unwrap_or(0);
+            indices.extend_from_slice(&corner(ear));
+            remaining.remove(ear);
+        }
+
+        Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+        .with_inserted_indices(Indices::U32(indices))
+    }
+}
+
+/// Returns whether `point` is in the counterclockwise triangle `abc` or on its edges.
+fn triangle_contains(a: Vec2, b: Vec2, c: Vec2, point: Vec2) -> bool {
+    (b - a).perp_dot(point - a) >= 0.0
+        && (c - b).perp_dot(point - b) >= 0.0
+        && (a - c).perp_dot(point - c) >= 0.0
+}
+
+impl<const N: usize> Meshable for Polygon<N> {
+    type Output = PolygonMeshBuilder;
+
+    fn mesh(&self) -> Self::Output {
+        PolygonMeshBuilder::new(self.vertices)
+    }
+}
+
+impl<const N: usize> From<Polygon<N>> for Mesh {
+    fn from(polygon: Polygon<N>) -> Self {
+        polygon.mesh().build()
+    }
+}
+
+impl Meshable for BoxedPolygon {
+    type Output = PolygonMeshBuilder;
+
+    fn mesh(&self) -> Self::Output {
+        PolygonMeshBuilder::new(self.vertices.iter().copied())
+    }
+}
+
+impl From<BoxedPolygon> for Mesh {
+    fn from(polygon: BoxedPolygon) -> Self {
+        polygon.mesh().build()
     }
 }
 
@@ -423,9 +592,11 @@ impl Capsule2dMeshBuilder {
         self.resolution = resolution;
         self
     }
+}
 
+impl MeshBuilder for Capsule2dMeshBuilder {
     /// Builds a [`Mesh`] based on the configuration in `self`.
-    pub fn build(&self) -> Mesh {
+    fn build(&self) -> Mesh {
         // The resolution is the number of vertices for one semicircle
         let resolution = self.resolution as u32;
         let vertex_count = 2 * self.resolution;
@@ -517,15 +688,12 @@ impl From<Capsule2d> for Mesh {
     }
 }
 
-impl From<Capsule2dMeshBuilder> for Mesh {
-    fn from(capsule: Capsule2dMeshBuilder) -> Self {
-        capsule.build()
-    }
-}
-
 #[cfg(test)]
 mod tests {
-    use bevy_math::primitives::RegularPolygon;
+    use bevy_math::{
+        primitives::{Polygon, RegularPolygon},
+        Vec2,
+    };
 
     use crate::mesh::{Mesh, VertexAttributeValues};
 
@@ -580,4 +748,32 @@ mod tests {
 
         assert_eq!(&[[0.0, 0.0, 1.0]; 4], &normals[..]);
     }
+
+    #[test]
+    fn test_concave_polygon() {
+        // An L shape wound clockwise, with a reflex corner at (1, 1)
+        let polygon = Polygon::<6>::new([
+            Vec2::new(0.0, 0.0),
+            Vec2::new(0.0, 2.0),
+            Vec2::new(1.0, 2.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(2.0, 0.0),
+        ]);
+        let mesh = Mesh::from(polygon);
+
+        let area: f32 = mesh
+            .triangles()
+            .unwrap()
+            .map(|triangle| {
+                let [a, b, c] = triangle.vertices;
+                // Every triangle must face the camera
+                let cross = (b - a).cross(c - a);
+                assert!(cross.z > 0.0);
+                cross.length() / 2.0
+            })
+            .sum();
+        assert_eq!(mesh.triangles().unwrap().len(), 4);
+        assert!((area - 3.0).abs() < 1e-5);
+    }
 }
@@ -1,5 +1,5 @@
 use crate::{
-    mesh::{Indices, Mesh, Meshable},
+    mesh::{Indices, Mesh, MeshBuilder, Meshable},
     render_asset::RenderAssetUsages,
 };
 use bevy_math::{primitives::Capsule3d, Vec2, Vec3};
@@ -91,9 +91,11 @@ impl Capsule3dMeshBuilder {
         self.uv_profile = uv_profile;
         self
     }
+}
 
+impl MeshBuilder for Capsule3dMeshBuilder {
     /// Builds a [`Mesh`] based on the configuration in `self`.
-    pub fn build(&self) -> Mesh {
+    fn build(&self) -> Mesh {
         // code adapted from https://behreajj.medium.com/making-a-capsule-mesh-via-script-in-five-3d-environments-c2214abf02db
         let Capsule3dMeshBuilder {
             capsule,
@@ -437,9 +439,3 @@ impl From<Capsule3d> for Mesh {
         capsule.mesh().build()
     }
 }
-
-impl From<Capsule3dMeshBuilder> for Mesh {
-    fn from(capsule: Capsule3dMeshBuilder) -> Self {
-        capsule.build()
-    }
-}
@@ -2,16 +2,31 @@ use bevy_math::primitives::Cuboid;
 use wgpu::PrimitiveTopology;
 
 use crate::{
-    mesh::{Indices, Mesh, Meshable},
+    mesh::{Indices, Mesh, MeshBuilder, Meshable},
     render_asset::RenderAssetUsages,
 };
 
-impl Meshable for Cuboid {
-    type Output = Mesh;
+/// A builder used for creating a [`Mesh`] with a [`Cuboid`] shape.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CuboidMeshBuilder {
+    /// The [`Cuboid`] shape.
+    pub cuboid: Cuboid,
+}
 
-    fn mesh(&self) -> Self::Output {
-        let min = -self.half_size;
-        let max = self.half_size;
+impl CuboidMeshBuilder {
+    /// Creates a new [`CuboidMeshBuilder`] from a full width, height and depth.
+    #[inline]
+    pub fn new(x_length: f32, y_length: f32, z_length: f32) -> Self {
+        Self {
+            cuboid: Cuboid::new(x_length, y_length, z_length),
+        }
+    }
+}
+
+impl MeshBuilder for CuboidMeshBuilder {
+    fn build(&self) -> Mesh {
+        let min = -self.cuboid.half_size;
+        let max = self.cuboid.half_size;
 
         // Suppose Y-up right hand, and camera look from +Z to -Z
         let vertices = &[
@@ -71,8 +86,16 @@ impl Meshable for Cuboid {
     }
 }
 
+impl Meshable for Cuboid {
+    type Output = CuboidMeshBuilder;
+
+    fn mesh(&self) -> Self::Output {
+        CuboidMeshBuilder { cuboid: *self }
+    }
+}
+
 impl From<Cuboid> for Mesh {
     fn from(cuboid: Cuboid) -> Self {
-        cuboid.mesh()
+        cuboid.mesh().build()
     }
 }
@@ -2,7 +2,7 @@ use bevy_math::primitives::Cylinder;
 use wgpu::PrimitiveTopology;
 
 use crate::{
-    mesh::{Indices, Mesh, Meshable},
+    mesh::{Indices, Mesh, MeshBuilder, Meshable},
     render_asset::RenderAssetUsages,
 };
 
@@ -58,9 +58,11 @@ impl CylinderMeshBuilder {
         self.segments = segments;
         self
     }
+}
 
+impl MeshBuilder for CylinderMeshBuilder {
     /// Builds a [`Mesh`] based on the configuration in `self`.
-    pub fn build(&self) -> Mesh {
+    fn build(&self) -> Mesh {
         let resolution = self.resolution;
         let segments = self.segments;
 
@@ -176,9 +178,3 @@ impl From<Cylinder> for Mesh {
         cylinder.mesh().build()
     }
 }
-
-impl From<CylinderMeshBuilder> for Mesh {
-    fn from(cylinder: CylinderMeshBuilder) -> Self {
-        cylinder.build()
-    }
-}
@@ -1,15 +1,21 @@
 mod capsule;
 mod cone;
+mod conical_frustum;
 mod cuboid;
 mod cylinder;
 mod plane;
 mod sphere;
+mod tetrahedron;
 mod torus;
 pub(crate) mod triangle3d;
 
 pub use capsule::*;
 pub use cone::*;
+pub use conical_frustum::*;
+pub use cuboid::*;
 pub use cylinder::*;
 pub use plane::*;
 pub use sphere::*;
+pub use tetrahedron::*;
 pub use torus::*;
+pub use triangle3d::Triangle3dMeshBuilder;
@@ -0,0 +1,67 @@
+use bevy_math::primitives::{Tetrahedron, Triangle3d};
+use wgpu::PrimitiveTopology;
+
+use super::triangle3d;
+use crate::{
+    mesh::{Indices, Mesh, MeshBuilder, Meshable},
+    render_asset::RenderAssetUsages,
+};
+
+/// A builder used for creating a [`Mesh`] with a [`Tetrahedron`] shape.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TetrahedronMeshBuilder {
+    /// The [`Tetrahedron`] shape.
+    pub tetrahedron: Tetrahedron,
+}
+
+impl MeshBuilder for TetrahedronMeshBuilder {
+    fn build(&self) -> Mesh {
+        let mut faces = [[0, 1, 2], [0, 1, 3], [0, 2, 3], [1, 2, 3]];
+
+        // Wind each face so that its normal points away from the opposite vertex
+        let vertices = self.tetrahedron.vertices;
+        for (face, opposite) in faces.iter_mut().zip([3, 2, 1, 0]) {
+            let [a, b, c] = face.map(|i| vertices[i]);
+            if (b - a).cross(c - a).dot(vertices[opposite] - a) > 0.0 {
+                face.swap(1, 2);
+            }
+        }
+
+        // Each face has its own vertices, for flat shading
+        let mut positions = Vec::with_capacity(12);
+        let mut normals = Vec::with_capacity(12);
+        let mut uvs = Vec::with_capacity(12);
+        for face in faces {
+            let triangle = Triangle3d {
+                vertices: face.map(|i| vertices[i]),
+            };
+            let normal = triangle.normal().map_or([0.0; 3], |n| n.to_array());
+            positions.extend(triangle.vertices.map(|v| v.to_array()));
+            normals.extend([normal; 3]);
+            uvs.extend(triangle3d::uv_coords(&triangle));
+        }
+
+        Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_indices(Indices::U32((0..12).collect()))
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    }
+}
+
+impl Meshable for Tetrahedron {
+    type Output = TetrahedronMeshBuilder;
+
+    fn mesh(&self) -> Self::Output {
+        TetrahedronMeshBuilder { tetrahedron: *self }
+    }
+}
+
+impl From<Tetrahedron> for Mesh {
+    fn from(tetrahedron: Tetrahedron) -> Self {
+        tetrahedron.mesh().build()
+    }
+}
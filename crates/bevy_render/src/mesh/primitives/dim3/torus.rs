@@ -2,7 +2,7 @@ use bevy_math::{primitives::Torus, Vec3};
 use wgpu::PrimitiveTopology;
 
 use crate::{
-    mesh::{Indices, Mesh, Meshable},
+    mesh::{Indices, Mesh, MeshBuilder, Meshable},
     render_asset::RenderAssetUsages,
 };
 
@@ -65,9 +65,11 @@ impl TorusMeshBuilder {
         self.major_resolution = resolution;
         self
     }
+}
 
+impl MeshBuilder for TorusMeshBuilder {
     /// Builds a [`Mesh`] according to the configuration in `self`.
-    pub fn build(&self) -> Mesh {
+    fn build(&self) -> Mesh {
         // code adapted from http://apparat-engine.blogspot.com/2013/04/procedural-meshes-torus.html
 
         let n_vertices = (self.major_resolution + 1) * (self.minor_resolution + 1);
@@ -158,9 +160,3 @@ impl From<Torus> for Mesh {
         torus.mesh().build()
     }
 }
-
-impl From<TorusMeshBuilder> for Mesh {
-    fn from(torus: TorusMeshBuilder) -> Self {
-        torus.build()
-    }
-}
@@ -2,19 +2,24 @@ use bevy_math::{primitives::Triangle3d, Vec3};
 use wgpu::PrimitiveTopology;
 
 use crate::{
-    mesh::{Indices, Mesh, Meshable},
+    mesh::{Indices, Mesh, MeshBuilder, Meshable},
     render_asset::RenderAssetUsages,
 };
 
-impl Meshable for Triangle3d {
-    type Output = Mesh;
+/// A builder used for creating a [`Mesh`] with a [`Triangle3d`] shape.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Triangle3dMeshBuilder {
+    /// The [`Triangle3d`] shape.
+    pub triangle: Triangle3d,
+}
 
-    fn mesh(&self) -> Self::Output {
-        let positions: Vec<_> = self.vertices.into();
-        let uvs: Vec<_> = uv_coords(self).into();
+impl MeshBuilder for Triangle3dMeshBuilder {
+    fn build(&self) -> Mesh {
+        let positions: Vec<_> = self.triangle.vertices.into();
+        let uvs: Vec<_> = uv_coords(&self.triangle).into();
 
         // Every vertex has the normal of the face of the triangle (or zero if the triangle is degenerate).
-        let normal: Vec3 = self.normal().map_or(Vec3::ZERO, |n| n.into());
+        let normal: Vec3 = self.triangle.normal().map_or(Vec3::ZERO, |n| n.into());
         let normals = vec![normal; 3];
 
         let indices = Indices::U32(vec![0, 1, 2]);
@@ -30,6 +35,14 @@ impl Meshable for Triangle3d {
     }
 }
 
+impl Meshable for Triangle3d {
+    type Output = Triangle3dMeshBuilder;
+
+    fn mesh(&self) -> Self::Output {
+        Triangle3dMeshBuilder { triangle: *self }
+    }
+}
+
 /// Unskewed uv-coordinates for a [`Triangle3d`].
 #[inline]
 pub(crate) fn uv_coords(triangle: &Triangle3d) -> [[f32; 2]; 3] {
@@ -80,7 +93,7 @@ pub(crate) fn uv_coords(triangle: &Triangle3d) -> [[f32; 2]; 3] {
 
 impl From<Triangle3d> for Mesh {
     fn from(triangle: Triangle3d) -> Self {
-        triangle.mesh()
+        triangle.mesh().build()
     }
 }
 
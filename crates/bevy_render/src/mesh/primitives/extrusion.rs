@@ -0,0 +1,447 @@
+use bevy_math::{
+    primitives::{Annulus, Capsule2d, Circle, Ellipse, Extrusion, Primitive2d, WindingOrder},
+    Vec2,
+};
+use wgpu::PrimitiveTopology;
+
+use super::{
+    AnnulusMeshBuilder, Capsule2dMeshBuilder, CircleMeshBuilder, EllipseMeshBuilder, MeshBuilder,
+    Meshable, PolygonMeshBuilder, RectangleMeshBuilder, RegularPolygonMeshBuilder,
+    Triangle2dMeshBuilder,
+};
+use crate::{
+    mesh::{Indices, Mesh, VertexAttributeValues},
+    render_asset::RenderAssetUsages,
+};
+
+/// A part of the perimeter of an [`Extrudable`] shape, as indices of the vertices of its mesh.
+///
+/// The vertices are ordered so that the shape is on the left of the perimeter, which is
+/// counterclockwise around the outside of the shape and clockwise around its holes.
+#[derive(Clone, Debug)]
+pub enum PerimeterSegment {
+    /// A curved part of the perimeter, such as the circumference of a circle, whose lateral faces
+    /// are shaded smoothly.
+    ///
+    /// The normals of the vertices at the ends are given, the normals of the other vertices are
+    /// perpendicular to the line between their neighbors.
+    Smooth {
+        /// The outward normal of the first vertex.
+        first_normal: Vec2,
+        /// The outward normal of the last vertex.
+        last_normal: Vec2,
+        /// The indices of the vertices, in order.
+        indices: Vec<u32>,
+    },
+    /// A part of the perimeter made of straight edges, such as the sides of a rectangle, whose
+    /// lateral faces are shaded flat.
+    Flat {
+        /// The indices of the vertices, in order.
+        indices: Vec<u32>,
+    },
+}
+
+/// A [`MeshBuilder`] of a 2D shape that can be extruded into a 3D shape with an [`Extrusion`].
+///
+/// The mesh built must be flat on the XY plane and facing `+Z`.
+pub trait Extrudable: MeshBuilder {
+    /// Returns the perimeter of the shape, see [`PerimeterSegment`].
+    fn perimeter(&self) -> Vec<PerimeterSegment>;
+}
+
+/// A builder used for creating a [`Mesh`] with an [`Extrusion`] shape.
+///
+/// The front and back faces are the mesh of the base shape, at `+Z` and `-Z`. The UVs of the
+/// lateral faces go around the perimeter along `U` and from the front to the back along `V`.
+pub struct ExtrusionBuilder<T>
+where
+    T: Primitive2d + Meshable,
+    T::Output: Extrudable,
+{
+    /// The builder of the base shape.
+    pub base_builder: T::Output,
+    /// Half of the depth of the extrusion.
+    pub half_depth: f32,
+    /// The number of segments along the depth of the extrusion.
+    /// Must be greater than `0` for geometry to be generated.
+    ///
+    /// The default is `1`.
+    pub segments: usize,
+}
+
+impl<T> ExtrusionBuilder<T>
+where
+    T: Primitive2d + Meshable,
+    T::Output: Extrudable,
+{
+    /// Creates a new [`ExtrusionBuilder`] from a base shape and the full depth of the extrusion.
+    pub fn new(base_shape: &T, depth: f32) -> Self {
+        Self {
+            base_builder: base_shape.mesh(),
+            half_depth: depth / 2.0,
+            segments: 1,
+        }
+    }
+
+    /// Sets the number of segments along the depth of the extrusion.
+    /// Must be greater than `0` for geometry to be generated.
+    pub fn segments(mut self, segments: usize) -> Self {
+        self.segments = segments;
+        self
+    }
+}
+
+impl ExtrusionBuilder<Circle> {
+    /// Sets the number of vertices used for the circle of the extrusion.
+    pub fn resolution(mut self, resolution: usize) -> Self {
+        self.base_builder.resolution = resolution;
+        self
+    }
+}
+
+impl ExtrusionBuilder<Ellipse> {
+    /// Sets the number of vertices used for the ellipse of the extrusion.
+    pub fn resolution(mut self, resolution: usize) -> Self {
+        self.base_builder.resolution = resolution;
+        self
+    }
+}
+
+impl ExtrusionBuilder<Annulus> {
+    /// Sets the number of vertices used for each circle of the annulus of the extrusion.
+    pub fn resolution(mut self, resolution: usize) -> Self {
+        self.base_builder.resolution = resolution;
+        self
+    }
+}
+
+impl ExtrusionBuilder<Capsule2d> {
+    /// Sets the number of vertices used for each hemicircle of the capsule of the extrusion.
+    pub fn resolution(mut self, resolution: usize) -> Self {
+        self.base_builder.resolution = resolution;
+        self
+    }
+}
+
+impl<T> MeshBuilder for ExtrusionBuilder<T>
+where
+    T: Primitive2d + Meshable,
+    T::Output: Extrudable,
+{
+    fn build(&self) -> Mesh {
+        let base = self.base_builder.build();
+        let Some(VertexAttributeValues::Float32x3(base_positions)) =
+            base.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("The mesh of an extrudable shape must have `Float32x3` positions");
+        };
+        let base_uvs = match base.attribute(Mesh::ATTRIBUTE_UV_0) {
+            Some(VertexAttributeValues::Float32x2(uvs)) => uvs.clone(),
+            _ => vec![[0.0; 2]; base_positions.len()],
+        };
+        let base_indices: Vec<u32> = match base.indices() {
+            Some(indices) => indices.iter().map(|i| i as u32).collect(),
+            None => (0..base_positions.len() as u32).collect(),
+        };
+        let point = |index: u32| Vec2::from_slice(&base_positions[index as usize]);
+
+        let mut positions = Vec::new();
+        let mut normals = Vec::new();
+        let mut uvs = Vec::new();
+        let mut indices = Vec::new();
+
+        // front and back
+
+        for (z, normal_z) in [(self.half_depth, 1.0), (-self.half_depth, -1.0)] {
+            let offset = positions.len() as u32;
+            for (position, uv) in base_positions.iter().zip(&base_uvs) {
+                positions.push([position[0], position[1], z]);
+                normals.push([0.0, 0.0, normal_z]);
+                uvs.push(*uv);
+            }
+            for triangle in base_indices.chunks_exact(3) {
+                let [a, b, c] = [triangle[0], triangle[1], triangle[2]].map(|i| i + offset);
+                // The back is seen from the other side, so its winding is reversed
+                indices.extend_from_slice(&if normal_z > 0.0 { [a, b, c] } else { [a, c, b] });
+            }
+        }
+
+        // lateral faces
+
+        let perimeter = self.base_builder.perimeter();
+        let mut perimeter_length = 0.0;
+        for segment in &perimeter {
+            let (PerimeterSegment::Smooth { indices, .. } | PerimeterSegment::Flat { indices }) =
+                segment;
+            for edge in indices.windows(2) {
+                perimeter_length += point(edge[0]).distance(point(edge[1]));
+            }
+        }
+        let perimeter_length = f32::max(perimeter_length, f32::EPSILON);
+        let step_z = 2.0 * self.half_depth / self.segments as f32;
+        let mut distance = 0.0;
+
+        // Adds the vertices of a column along the depth, and the faces between it and the
+        // previous column if `connect` is set.
+        let mut add_column = |position: Vec2, normal: Vec2, u: f32, connect: bool| {
+            let offset = positions.len() as u32;
+            for ring in 0..=self.segments {
+                positions.push([
+                    position.x,
+                    position.y,
+                    self.half_depth - ring as f32 * step_z,
+                ]);
+                normals.push([normal.x, normal.y, 0.0]);
+                uvs.push([u, ring as f32 / self.segments as f32]);
+            }
+            if connect {
+                let previous = offset - self.segments as u32 - 1;
+                for ring in 0..self.segments as u32 {
+                    indices.extend_from_slice(&[
+                        previous + ring,
+                        previous + ring + 1,
+                        offset + ring,
+                        offset + ring,
+                        previous + ring + 1,
+                        offset + ring + 1,
+                    ]);
+                }
+            }
+        };
+
+        for segment in &perimeter {
+            match segment {
+                PerimeterSegment::Flat { indices } => {
+                    for edge in indices.windows(2) {
+                        let (a, b) = (point(edge[0]), point(edge[1]));
+                        let normal = outward(b - a);
+                        let u = distance / perimeter_length;
+                        distance += a.distance(b);
+                        add_column(a, normal, u, false);
+                        add_column(b, normal, distance / perimeter_length, true);
+                    }
+                }
+                PerimeterSegment::Smooth {
+                    first_normal,
+                    last_normal,
+                    indices,
+                } => {
+                    for (i, &index) in indices.iter().enumerate() {
+                        let normal = if i == 0 {
+                            *first_normal
+                        } else if i == indices.len() - 1 {
+                            *last_normal
+                        } else {
+                            outward(point(indices[i + 1]) - point(indices[i - 1]))
+                        };
+                        if i > 0 {
+                            distance += point(indices[i - 1]).distance(point(index));
+                        }
+                        add_column(point(index), normal, distance / perimeter_length, i > 0);
+                    }
+                }
+            }
+        }
+
+        Mesh::new(
+            PrimitiveTopology::TriangleList,
+            RenderAssetUsages::default(),
+        )
+        .with_inserted_indices(Indices::U32(indices))
+        .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, normals)
+        .with_inserted_attribute(Mesh::ATTRIBUTE_UV_0, uvs)
+    }
+}
+
+/// Returns the outward normal of a direction along the perimeter, which is on its right.
+fn outward(direction: Vec2) -> Vec2 {
+    Vec2::new(direction.y, -direction.x).normalize_or_zero()
+}
+
+impl<T> Meshable for Extrusion<T>
+where
+    T: Primitive2d + Meshable,
+    T::Output: Extrudable,
+{
+    type Output = ExtrusionBuilder<T>;
+
+    fn mesh(&self) -> Self::Output {
+        ExtrusionBuilder {
+            base_builder: self.base_shape.mesh(),
+            half_depth: self.half_depth,
+            segments: 1,
+        }
+    }
+}
+
+impl<T> From<Extrusion<T>> for Mesh
+where
+    T: Primitive2d + Meshable,
+    T::Output: Extrudable,
+{
+    fn from(extrusion: Extrusion<T>) -> Self {
+        extrusion.mesh().build()
+    }
+}
+
+/// The perimeter of the regular polygon meshes used for circles and ellipses, whose first vertex
+/// is at the top and which are wound counterclockwise.
+fn closed_loop(vertex_count: usize) -> Vec<u32> {
+    (0..vertex_count as u32).chain([0]).collect()
+}
+
+impl Extrudable for CircleMeshBuilder {
+    fn perimeter(&self) -> Vec<PerimeterSegment> {
+        vec![PerimeterSegment::Smooth {
+            first_normal: Vec2::Y,
+            last_normal: Vec2::Y,
+            indices: closed_loop(self.resolution),
+        }]
+    }
+}
+
+impl Extrudable for EllipseMeshBuilder {
+    fn perimeter(&self) -> Vec<PerimeterSegment> {
+        vec![PerimeterSegment::Smooth {
+            first_normal: Vec2::Y,
+            last_normal: Vec2::Y,
+            indices: closed_loop(self.resolution),
+        }]
+    }
+}
+
+impl Extrudable for RegularPolygonMeshBuilder {
+    fn perimeter(&self) -> Vec<PerimeterSegment> {
+        vec![PerimeterSegment::Flat {
+            indices: closed_loop(self.polygon.sides),
+        }]
+    }
+}
+
+impl Extrudable for AnnulusMeshBuilder {
+    fn perimeter(&self) -> Vec<PerimeterSegment> {
+        // The inner and outer vertices alternate, and the vertices at the top are duplicated
+        let vertex_count = 2 * (self.resolution as u32 + 1);
+        vec![
+            PerimeterSegment::Smooth {
+                first_normal: Vec2::Y,
+                last_normal: Vec2::Y,
+                indices: (1..vertex_count).step_by(2).collect(),
+            },
+            PerimeterSegment::Smooth {
+                first_normal: Vec2::NEG_Y,
+                last_normal: Vec2::NEG_Y,
+                indices: (0..vertex_count).step_by(2).rev().collect(),
+            },
+        ]
+    }
+}
+
+impl Extrudable for Capsule2dMeshBuilder {
+    fn perimeter(&self) -> Vec<PerimeterSegment> {
+        // Mirrors the vertices placed by `Capsule2dMeshBuilder::build`
+        let resolution = self.resolution as u32;
+        let vertex_count = 2 * resolution;
+        let step = std::f32::consts::TAU / vertex_count as f32;
+        let start_angle = if vertex_count % 2 == 0 {
+            step / 2.0
+        } else {
+            0.0
+        };
+        let normal = |i: u32| Vec2::from_angle(start_angle + i as f32 * step);
+
+        vec![
+            PerimeterSegment::Smooth {
+                first_normal: normal(0),
+                last_normal: normal(resolution - 1),
+                indices: (0..resolution).collect(),
+            },
+            PerimeterSegment::Flat {
+                indices: vec![resolution - 1, resolution],
+            },
+            PerimeterSegment::Smooth {
+                first_normal: normal(resolution),
+                last_normal: normal(vertex_count - 1),
+                indices: (resolution..vertex_count).collect(),
+            },
+            PerimeterSegment::Flat {
+                indices: vec![vertex_count - 1, 0],
+            },
+        ]
+    }
+}
+
+impl Extrudable for RectangleMeshBuilder {
+    fn perimeter(&self) -> Vec<PerimeterSegment> {
+        vec![PerimeterSegment::Flat {
+            indices: vec![0, 1, 2, 3, 0],
+        }]
+    }
+}
+
+impl Extrudable for Triangle2dMeshBuilder {
+    fn perimeter(&self) -> Vec<PerimeterSegment> {
+        let indices = if self.triangle.winding_order() == WindingOrder::Clockwise {
+            vec![2, 1, 0, 2]
+        } else {
+            vec![0, 1, 2, 0]
+        };
+        vec![PerimeterSegment::Flat { indices }]
+    }
+}
+
+impl Extrudable for PolygonMeshBuilder {
+    fn perimeter(&self) -> Vec<PerimeterSegment> {
+        let signed_area: f32 = (0..self.vertices.len())
+            .map(|i| self.vertices[i].perp_dot(self.vertices[(i + 1) % self.vertices.len()]))
+            .sum();
+        let mut indices = closed_loop(self.vertices.len());
+        if signed_area < 0.0 {
+            indices.reverse();
+        }
+        vec![PerimeterSegment::Flat { indices }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_math::primitives::{Annulus, Circle, Extrusion, Measured3d, Rectangle};
+
+    use crate::mesh::{Mesh, MeshBuilder, Meshable};
+
+    /// Returns the volume enclosed by a closed mesh with outward facing triangles.
+    fn volume(mesh: &Mesh) -> f32 {
+        mesh.triangles()
+            .unwrap()
+            .map(|triangle| {
+                let [a, b, c] = triangle.vertices;
+                a.dot(b.cross(c)) / 6.0
+            })
+            .sum()
+    }
+
+    #[test]
+    fn extruded_rectangle_is_a_cuboid() {
+        let extrusion = Extrusion::new(Rectangle::new(2.0, 1.0), 3.0);
+        let mesh = extrusion.mesh().segments(2).build();
+        assert!((volume(&mesh) - extrusion.volume()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn extruded_shapes_are_closed() {
+        let circle = Extrusion::new(Circle::new(1.0), 2.0)
+            .mesh()
+            .resolution(64)
+            .build();
+        assert!((volume(&circle) - Extrusion::new(Circle::new(1.0), 2.0).volume()).abs() < 0.05);
+
+        // The hole of the annulus is wound the other way, removing its volume
+        let annulus = Extrusion::new(Annulus::new(0.5, 1.0), 1.0)
+            .mesh()
+            .resolution(64)
+            .build();
+        assert!(volume(&annulus) > 0.0);
+        assert!(volume(&annulus) < Extrusion::new(Circle::new(1.0), 1.0).volume());
+    }
+}
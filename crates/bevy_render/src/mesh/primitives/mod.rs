@@ -1,8 +1,15 @@
 //! Mesh generation for [primitive shapes](bevy_math::primitives).
 //!
-//! Primitives that support meshing implement the [`Meshable`] trait.
-//! Calling [`mesh`](Meshable::mesh) will return either a [`Mesh`](super::Mesh) or a builder
-//! that can be used to specify shape-specific configuration for creating the [`Mesh`](super::Mesh).
+//! Every bounded primitive implements the [`Meshable`] trait.
+//! Calling [`mesh`](Meshable::mesh) will return a [`MeshBuilder`] that can be used to specify
+//! shape-specific configuration before [building](MeshBuilder::build) the [`Mesh`].
+//! Unbounded primitives, such as [`Plane2d`](bevy_math::primitives::Plane2d),
+//! [`Line3d`](bevy_math::primitives::Line3d) and [`InfinitePlane3d`](bevy_math::primitives::InfinitePlane3d),
+//! can't be meshed.
+//!
+//! Segments and polylines are meshed as lines, with a [`PrimitiveTopology::LineStrip`](wgpu::PrimitiveTopology::LineStrip),
+//! and any 2D primitive whose builder is [`Extrudable`] can be extruded into 3D with an
+//! [`Extrusion`](bevy_math::primitives::Extrusion).
 //!
 //! ```
 //! # use bevy_asset::Assets;
@@ -20,17 +27,46 @@
 //! ```
 
 mod dim2;
-pub use dim2::{CircleMeshBuilder, EllipseMeshBuilder};
+pub use dim2::*;
 
 mod dim3;
 pub use dim3::*;
 
-/// A trait for shapes that can be turned into a [`Mesh`](super::Mesh).
+mod extrusion;
+pub use extrusion::*;
+
+mod polyline;
+pub use polyline::*;
+
+use super::Mesh;
+
+/// A trait for shapes that can be turned into a [`Mesh`].
+///
+/// This lets shapes be meshed generically, for example to place any primitive in a level editor:
+///
+/// ```
+/// # use bevy_asset::Assets;
+/// # use bevy_render::prelude::*;
+/// fn add_mesh<T: Meshable>(meshes: &mut Assets<Mesh>, shape: &T) -> bevy_asset::Handle<Mesh> {
+///     meshes.add(shape.mesh())
+/// }
+/// ```
 pub trait Meshable {
-    /// The output of [`Self::mesh`]. This can either be a [`Mesh`](super::Mesh)
-    /// or a builder used for creating a [`Mesh`](super::Mesh).
-    type Output;
+    /// The builder returned by [`Self::mesh`], used for configuring and creating a [`Mesh`].
+    type Output: MeshBuilder;
 
-    /// Creates a [`Mesh`](super::Mesh) for a shape.
+    /// Creates a [`MeshBuilder`] for a shape.
     fn mesh(&self) -> Self::Output;
 }
+
+/// A builder used for creating a [`Mesh`], usually returned by [`Meshable::mesh`].
+pub trait MeshBuilder {
+    /// Builds a [`Mesh`] based on the configuration in `self`.
+    fn build(&self) -> Mesh;
+}
+
+impl<T: MeshBuilder> From<T> for Mesh {
+    fn from(builder: T) -> Self {
+        builder.build()
+    }
+}
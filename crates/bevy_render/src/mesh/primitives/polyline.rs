@@ -0,0 +1,124 @@
+use bevy_math::{
+    primitives::{BoxedPolyline2d, BoxedPolyline3d, Polyline2d, Polyline3d, Segment2d, Segment3d},
+    Vec3,
+};
+use wgpu::PrimitiveTopology;
+
+use crate::{
+    mesh::{Mesh, MeshBuilder, Meshable},
+    render_asset::RenderAssetUsages,
+};
+
+/// A builder used for creating a [`Mesh`] made of connected lines, with a
+/// [`PrimitiveTopology::LineStrip`].
+///
+/// It is the output of meshing segments and polylines, in 2D or 3D. The vertices of 2D shapes
+/// are on the XY plane.
+#[derive(Clone, Debug, Default)]
+pub struct PolylineMeshBuilder {
+    /// The vertices of the lines, in order.
+    pub vertices: Vec<Vec3>,
+}
+
+impl PolylineMeshBuilder {
+    /// Creates a new [`PolylineMeshBuilder`] from the vertices of the lines.
+    #[inline]
+    pub fn new(vertices: impl IntoIterator<Item = Vec3>) -> Self {
+        Self {
+            vertices: vertices.into_iter().collect(),
+        }
+    }
+}
+
+impl MeshBuilder for PolylineMeshBuilder {
+    fn build(&self) -> Mesh {
+        let positions: Vec<[f32; 3]> = self.vertices.iter().map(|v| v.to_array()).collect();
+
+        Mesh::new(PrimitiveTopology::LineStrip, RenderAssetUsages::default())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, positions)
+    }
+}
+
+impl Meshable for Segment2d {
+    type Output = PolylineMeshBuilder;
+
+    fn mesh(&self) -> Self::Output {
+        PolylineMeshBuilder::new([self.point1().extend(0.0), self.point2().extend(0.0)])
+    }
+}
+
+impl From<Segment2d> for Mesh {
+    fn from(segment: Segment2d) -> Self {
+        segment.mesh().build()
+    }
+}
+
+impl<const N: usize> Meshable for Polyline2d<N> {
+    type Output = PolylineMeshBuilder;
+
+    fn mesh(&self) -> Self::Output {
+        PolylineMeshBuilder::new(self.vertices.iter().map(|v| v.extend(0.0)))
+    }
+}
+
+impl<const N: usize> From<Polyline2d<N>> for Mesh {
+    fn from(polyline: Polyline2d<N>) -> Self {
+        polyline.mesh().build()
+    }
+}
+
+impl Meshable for BoxedPolyline2d {
+    type Output = PolylineMeshBuilder;
+
+    fn mesh(&self) -> Self::Output {
+        PolylineMeshBuilder::new(self.vertices.iter().map(|v| v.extend(0.0)))
+    }
+}
+
+impl From<BoxedPolyline2d> for Mesh {
+    fn from(polyline: BoxedPolyline2d) -> Self {
+        polyline.mesh().build()
+    }
+}
+
+impl Meshable for Segment3d {
+    type Output = PolylineMeshBuilder;
+
+    fn mesh(&self) -> Self::Output {
+        PolylineMeshBuilder::new([self.point1(), self.point2()])
+    }
+}
+
+impl From<Segment3d> for Mesh {
+    fn from(segment: Segment3d) -> Self {
+        segment.mesh().build()
+    }
+}
+
+impl<const N: usize> Meshable for Polyline3d<N> {
+    type Output = PolylineMeshBuilder;
+
+    fn mesh(&self) -> Self::Output {
+        PolylineMeshBuilder::new(self.vertices)
+    }
+}
+
+impl<const N: usize> From<Polyline3d<N>> for Mesh {
+    fn from(polyline: Polyline3d<N>) -> Self {
+        polyline.mesh().build()
+    }
+}
+
+impl Meshable for BoxedPolyline3d {
+    type Output = PolylineMeshBuilder;
+
+    fn mesh(&self) -> Self::Output {
+        PolylineMeshBuilder::new(self.vertices.iter().copied())
+    }
+}
+
+impl From<BoxedPolyline3d> for Mesh {
+    fn from(polyline: BoxedPolyline3d) -> Self {
+        polyline.mesh().build()
+    }
+}
@@ -0,0 +1,231 @@
+use crate::mesh::{Indices, Mesh, PrimitiveTopology, VertexAttributeValues};
+use bevy_math::{Mat4, Ray3d, Vec3};
+use bevy_reflect::Reflect;
+
+/// A hit of a ray against a triangle of a [`Mesh`].
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+pub struct RayMeshHit {
+    /// The point of intersection, in world space.
+    pub point: Vec3,
+    /// The normal at the point of intersection, in world space. It is interpolated from the
+    /// vertex normals when the mesh has them, or the normal of the triangle otherwise.
+    pub normal: Vec3,
+    /// The barycentric coordinates of the point of intersection within the triangle, weighting
+    /// each of the vertices of [`RayMeshHit::triangle`].
+    pub barycentric_coords: Vec3,
+    /// The distance from the origin of the ray to the point of intersection.
+    pub distance: f32,
+    /// The vertices of the triangle that was hit, in world space.
+    pub triangle: [Vec3; 3],
+    /// The index of the triangle that was hit, in the mesh's triangle list.
+    pub triangle_index: usize,
+}
+
+/// Whether a ray cast hits the back faces of triangles, which are the faces whose vertices are
+/// in clockwise order as seen by the ray, like the default
+/// [`FrontFace::Ccw`](wgpu::FrontFace::Ccw) of mesh pipelines.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+pub enum Backfaces {
+    /// Ignores back faces, like the default back face culling of materials.
+    #[default]
+    Cull,
+    /// Hits both front and back faces.
+    Include,
+}
+
+/// Casts a ray against the triangles of a [`Mesh`] placed with the given `transform`, returning
+/// the closest hit.
+///
+/// Only meshes with a [`PrimitiveTopology::TriangleList`] topology and
+/// [`VertexAttributeValues::Float32x3`] positions can be hit.
+pub fn ray_mesh_intersection(
+    ray: Ray3d,
+    mesh: &Mesh,
+    transform: &Mat4,
+    backfaces: Backfaces,
+) -> Option<RayMeshHit> {
+    if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+        return None;
+    }
+    let positions = mesh.attribute(Mesh::ATTRIBUTE_POSITION)?.as_float3()?;
+    let normals = mesh
+        .attribute(Mesh::ATTRIBUTE_NORMAL)
+        .and_then(VertexAttributeValues::as_float3);
+    ray_triangles_intersection(
+        ray,
+        transform,
+        positions,
+        normals,
+        mesh.indices(),
+        backfaces,
+    )
+}
+
+/// Casts a ray against a triangle list, returning the closest hit.
+pub(super) fn ray_triangles_intersection(
+    ray: Ray3d,
+    transform: &Mat4,
+    positions: &[[f32; 3]],
+    normals: Option<&[[f32; 3]]>,
+    indices: Option<&Indices>,
+    backfaces: Backfaces,
+) -> Option<RayMeshHit> {
+    // Intersect in the space of the mesh rather than transforming every vertex. The direction
+    // isn't normalized so that distances along the ray are the same in both spaces.
+    let world_to_mesh = transform.inverse();
+    let origin = world_to_mesh.transform_point3(ray.origin);
+    let direction = world_to_mesh.transform_vector3(*ray.direction);
+    // Mirroring transforms flip the winding of triangles once rendered
+    let flip_winding = transform.determinant() < 0.0;
+
+    let mut closest: Option<(usize, [usize; 3], TriangleHit)> = None;
+    let mut test_triangle = |triangle_index: usize, vertices: [usize; 3]| {
+        if vertices.iter().any(|&vertex| vertex >= positions.len()) {
+            return;
+        }
+        let triangle = vertices.map(|vertex| Vec3::from(positions[vertex]));
+        let Some(hit) = ray_triangle_intersection(origin, direction, triangle)
+            .filter(|hit| hit.front_face != flip_winding || backfaces == Backfaces::Include)
+        else {
+            return;
+        };
+        if closest
+            .as_ref()
+            .map_or(true, |(_, _, closest)| hit.distance < closest.distance)
+        {
+            closest = Some((triangle_index, vertices, hit));
+        }
+    };
+    match indices {
+        Some(indices) => {
+            let mut indices = indices.iter();
+            let mut triangle_index = 0;
+            while let (Some(a), Some(b), Some(c)) = (indices.next(), indices.next(), indices.next())
+            {
+                test_triangle(triangle_index, [a, b, c]);
+                triangle_index += 1;
+            }
+        }
+        None => {
+            for triangle_index in 0..positions.len() / 3 {
+                let first = triangle_index * 3;
+                test_triangle(triangle_index, [first, first + 1, first + 2]);
+            }
+        }
+    }
+
+    let (triangle_index, vertices, hit) = closest?;
+    let barycentric_coords = Vec3::new(1.0 - hit.u - hit.v, hit.u, hit.v);
+    let triangle = vertices.map(|vertex| transform.transform_point3(positions[vertex].into()));
+    let normal = match normals.filter(|normals| vertices.iter().all(|&v| v < normals.len())) {
+        Some(normals) => {
+            let normal = vertices
+                .iter()
+                .zip(barycentric_coords.to_array())
+                .map(|(&vertex, weight)| Vec3::from(normals[vertex]) * weight)
+                .sum::<Vec3>();
+            world_to_mesh.transpose().transform_vector3(normal)
+        }
+        None => (triangle[1] - triangle[0]).cross(triangle[2] - triangle[0]),
+    };
+
+    Some(RayMeshHit {
+        point: ray.get_point(hit.distance),
+        normal: normal.normalize_or_zero(),
+        barycentric_coords,
+        distance: hit.distance,
+        triangle,
+        triangle_index,
+    })
+}
+
+struct TriangleHit {
+    distance: f32,
+    u: f32,
+    v: f32,
+    front_face: bool,
+}
+
+/// Intersects a ray with a triangle using the Möller–Trumbore algorithm.
+fn ray_triangle_intersection(
+    origin: Vec3,
+    direction: Vec3,
+    triangle: [Vec3; 3],
+) -> Option<TriangleHit> {
+    let edge_1 = triangle[1] - triangle[0];
+    let edge_2 = triangle[2] - triangle[0];
+    let p = direction.cross(edge_2);
+    // Positive when the ray sees the vertices in counter-clockwise order
+    let determinant = edge_1.dot(p);
+    if determinant.abs() < f32::EPSILON {
+        return None;
+    }
+
+    let inverse_determinant = determinant.recip();
+    let s = origin - triangle[0];
+    let u = s.dot(p) * inverse_determinant;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = s.cross(edge_1);
+    let v = direction.dot(q) * inverse_determinant;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let distance = edge_2.dot(q) * inverse_determinant;
+    (distance >= 0.0).then_some(TriangleHit {
+        distance,
+        u,
+        v,
+        front_face: determinant > 0.0,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_math::Quat;
+
+    const TRIANGLE: [[f32; 3]; 3] = [[-1.0, -1.0, 0.0], [1.0, -1.0, 0.0], [0.0, 1.0, 0.0]];
+
+    fn cast(ray: Ray3d, transform: Mat4, backfaces: Backfaces) -> Option<RayMeshHit> {
+        ray_triangles_intersection(ray, &transform, &TRIANGLE, None, None, backfaces)
+    }
+
+    #[test]
+    fn ray_triangle_hit() {
+        let ray = Ray3d::new(Vec3::new(0.0, 0.0, 5.0), Vec3::NEG_Z);
+        let hit = cast(ray, Mat4::IDENTITY, Backfaces::Cull).unwrap();
+        assert!((hit.distance - 5.0).abs() < 1e-5);
+        assert!(hit.point.abs_diff_eq(Vec3::ZERO, 1e-5));
+        assert!(hit.normal.abs_diff_eq(Vec3::Z, 1e-5));
+        assert!((hit.barycentric_coords.element_sum() - 1.0).abs() < 1e-5);
+        assert_eq!(hit.triangle_index, 0);
+
+        // The ray misses the triangle once moved aside
+        let transform = Mat4::from_translation(Vec3::new(3.0, 0.0, 0.0));
+        assert!(cast(ray, transform, Backfaces::Cull).is_none());
+    }
+
+    #[test]
+    fn ray_triangle_backfaces() {
+        let ray = Ray3d::new(Vec3::new(0.0, 0.0, -5.0), Vec3::Z);
+        assert!(cast(ray, Mat4::IDENTITY, Backfaces::Cull).is_none());
+        assert!(cast(ray, Mat4::IDENTITY, Backfaces::Include).is_some());
+
+        // Rotated to face the ray, at a scaled distance
+        let transform = Mat4::from_scale_rotation_translation(
+            Vec3::splat(2.0),
+            Quat::from_rotation_y(std::f32::consts::PI),
+            Vec3::new(0.0, 0.0, 1.0),
+        );
+        let hit = cast(ray, transform, Backfaces::Cull).unwrap();
+        assert!((hit.distance - 6.0).abs() < 1e-5);
+        assert!(hit.normal.abs_diff_eq(Vec3::NEG_Z, 1e-5));
+
+        // Mirroring flips the winding
+        let transform = Mat4::from_scale(Vec3::new(-1.0, 1.0, 1.0));
+        let ray = Ray3d::new(Vec3::new(0.0, 0.0, 5.0), Vec3::NEG_Z);
+        assert!(cast(ray, transform, Backfaces::Cull).is_none());
+    }
+}
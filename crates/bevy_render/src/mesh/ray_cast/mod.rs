@@ -0,0 +1,311 @@
+//! Ray casting against the triangles of [`Mesh`] entities, see [`MeshRayCast`].
+
+mod intersections;
+
+pub use intersections::*;
+
+use crate::{
+    mesh::{
+        skinning::{SkinnedMesh, SkinnedMeshInverseBindposes},
+        Mesh, PrimitiveTopology, VertexAttributeValues,
+    },
+    primitives::Aabb,
+    view::{InheritedVisibility, ViewVisibility},
+};
+use bevy_asset::{Assets, Handle};
+use bevy_ecs::{
+    component::Component,
+    entity::Entity,
+    prelude::ReflectComponent,
+    query::Has,
+    system::{Local, Query, Res, SystemParam},
+};
+use bevy_math::{
+    bounding::{Aabb3d, RayCast3d},
+    Dir3, FloatOrd, Mat3, Mat4, Ray3d, Vec3,
+};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use bevy_transform::components::GlobalTransform;
+
+/// Which meshes a [`MeshRayCast`] can hit depending on their visibility.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+pub enum RayCastVisibility {
+    /// Hits meshes whether they are visible or not.
+    Any,
+    /// Only hits meshes that are visible in the hierarchy, see [`InheritedVisibility`].
+    Visible,
+    /// Only hits meshes that were visible in a view during the last frame, see
+    /// [`ViewVisibility`].
+    #[default]
+    VisibleInView,
+}
+
+/// Settings of a [`MeshRayCast::cast_ray`].
+#[derive(Clone, Copy)]
+pub struct RayCastSettings<'a> {
+    /// Which meshes can be hit depending on their visibility.
+    pub visibility: RayCastVisibility,
+    /// Only entities for which this returns `true` can be hit. Use it with a [`Query`] to only
+    /// hit entities with or without some components.
+    pub filter: &'a dyn Fn(Entity) -> bool,
+    /// Called on each entity that was hit, in no particular order. When it returns `true`, the
+    /// entity blocks the ray and hits further away from it are discarded. The default stops at
+    /// the first hit.
+    pub early_exit_test: &'a dyn Fn(Entity) -> bool,
+}
+
+impl<'a> RayCastSettings<'a> {
+    /// Sets which meshes can be hit depending on their visibility.
+    pub fn with_visibility(mut self, visibility: RayCastVisibility) -> Self {
+        self.visibility = visibility;
+        self
+    }
+
+    /// Sets the filter of the entities that can be hit.
+    pub fn with_filter(mut self, filter: &'a impl Fn(Entity) -> bool) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Sets the test of the entities blocking the ray.
+    pub fn with_early_exit_test(mut self, early_exit_test: &'a impl Fn(Entity) -> bool) -> Self {
+        self.early_exit_test = early_exit_test;
+        self
+    }
+
+    /// Returns every hit along the ray instead of only the closest one.
+    pub fn never_early_exit(self) -> Self {
+        self.with_early_exit_test(&|_| false)
+    }
+}
+
+impl<'a> Default for RayCastSettings<'a> {
+    fn default() -> Self {
+        Self {
+            visibility: RayCastVisibility::default(),
+            filter: &|_| true,
+            early_exit_test: &|_| true,
+        }
+    }
+}
+
+/// Makes a [`MeshRayCast`] hit the back faces of the triangles of a mesh entity, for meshes
+/// rendered without back face culling.
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct RayCastBackfaces;
+
+type MeshRayCastQueryData = (
+    Entity,
+    &'static Handle<Mesh>,
+    &'static GlobalTransform,
+    Option<&'static Aabb>,
+    Option<&'static SkinnedMesh>,
+    Has<RayCastBackfaces>,
+    Option<&'static InheritedVisibility>,
+    Option<&'static ViewVisibility>,
+);
+
+/// A [`SystemParam`] casting rays against the triangles of the entities with a
+/// [`Handle<Mesh>`] and a [`GlobalTransform`].
+///
+/// Meshes are first tested against their [`Aabb`], then against their triangles from the
+/// closest to the furthest [`Aabb`]. [`SkinnedMesh`]es are posed on the CPU with the current
+/// transforms of their joints, while morph targets are ignored.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_math::{Ray3d, Vec3};
+/// # use bevy_render::mesh::ray_cast::{MeshRayCast, RayCastSettings};
+/// #[derive(Component)]
+/// struct Pickable;
+///
+/// fn cast_ray(mut ray_cast: MeshRayCast, pickables: Query<(), With<Pickable>>) {
+///     let ray = Ray3d::new(Vec3::new(0.0, 1.0, 5.0), Vec3::NEG_Z);
+///     let filter = |entity| pickables.contains(entity);
+///     let settings = RayCastSettings::default().with_filter(&filter);
+///     if let Some((entity, hit)) = ray_cast.cast_ray(ray, &settings).first() {
+///         println!("{entity:?} hit at {}", hit.point);
+///     }
+/// }
+/// # bevy_ecs::system::assert_is_system(cast_ray);
+/// ```
+#[derive(SystemParam)]
+pub struct MeshRayCast<'w, 's> {
+    meshes: Res<'w, Assets<Mesh>>,
+    inverse_bindposes: Res<'w, Assets<SkinnedMeshInverseBindposes>>,
+    mesh_query: Query<'w, 's, MeshRayCastQueryData>,
+    joints: Query<'w, 's, &'static GlobalTransform>,
+    candidates: Local<'s, Vec<(FloatOrd, Entity)>>,
+    hits: Local<'s, Vec<(Entity, RayMeshHit)>>,
+    skinned_positions: Local<'s, Vec<[f32; 3]>>,
+    skinned_normals: Local<'s, Vec<[f32; 3]>>,
+}
+
+impl<'w, 's> MeshRayCast<'w, 's> {
+    /// Casts a ray against the meshes allowed by the `settings`, returning the hits sorted from
+    /// the closest to the furthest.
+    pub fn cast_ray(&mut self, ray: Ray3d, settings: &RayCastSettings) -> &[(Entity, RayMeshHit)] {
+        self.candidates.clear();
+        self.hits.clear();
+
+        // Broad phase against the bounding boxes
+        for (entity, _, transform, aabb, skinned_mesh, _, inherited, view) in &self.mesh_query {
+            let visible = match settings.visibility {
+                RayCastVisibility::Any => true,
+                RayCastVisibility::Visible => inherited.is_some_and(|v| v.get()),
+                RayCastVisibility::VisibleInView => view.is_some_and(|v| v.get()),
+            };
+            if !visible || !(settings.filter)(entity) {
+                continue;
+            }
+            // The bounding box of skinned meshes is computed in their bind pose
+            let distance = match aabb.filter(|_| skinned_mesh.is_none()) {
+                Some(aabb) => match ray_aabb_intersection(ray, aabb, transform) {
+                    Some(distance) => distance,
+                    None => continue,
+                },
+                None => 0.0,
+            };
+            self.candidates.push((FloatOrd(distance), entity));
+        }
+        self.candidates
+            .sort_unstable_by_key(|(distance, _)| *distance);
+
+        // Narrow phase against the triangles
+        let mut max_distance = f32::MAX;
+        for &(FloatOrd(aabb_distance), entity) in self.candidates.iter() {
+            if aabb_distance > max_distance {
+                break;
+            }
+            let Ok((_, mesh, transform, _, skinned_mesh, backfaces, ..)) =
+                self.mesh_query.get(entity)
+            else {
+                continue;
+            };
+            let Some(mesh) = self.meshes.get(mesh) else {
+                continue;
+            };
+            let backfaces = if backfaces {
+                Backfaces::Include
+            } else {
+                Backfaces::Cull
+            };
+
+            let hit = match skinned_mesh {
+                Some(skinned_mesh) => {
+                    let skinned = skin_mesh(
+                        mesh,
+                        skinned_mesh,
+                        &self.inverse_bindposes,
+                        &self.joints,
+                        &mut self.skinned_positions,
+                        &mut self.skinned_normals,
+                    );
+                    skinned.and_then(|has_normals| {
+                        ray_triangles_intersection(
+                            ray,
+                            &Mat4::IDENTITY,
+                            &self.skinned_positions,
+                            has_normals.then_some(&self.skinned_normals[..]),
+                            mesh.indices(),
+                            backfaces,
+                        )
+                    })
+                }
+                None => ray_mesh_intersection(ray, mesh, &transform.compute_matrix(), backfaces),
+            };
+            let Some(hit) = hit.filter(|hit| hit.distance <= max_distance) else {
+                continue;
+            };
+            if (settings.early_exit_test)(entity) {
+                max_distance = hit.distance;
+            }
+            self.hits.push((entity, hit));
+        }
+
+        self.hits.retain(|(_, hit)| hit.distance <= max_distance);
+        self.hits
+            .sort_by(|(_, a), (_, b)| a.distance.total_cmp(&b.distance));
+        &self.hits
+    }
+}
+
+/// Returns the distance along the ray to the [`Aabb`] of a mesh, if it's hit.
+fn ray_aabb_intersection(ray: Ray3d, aabb: &Aabb, transform: &GlobalTransform) -> Option<f32> {
+    let world_to_mesh = transform.affine().inverse();
+    let local_ray = Ray3d {
+        origin: world_to_mesh.transform_point3(ray.origin),
+        direction: Dir3::new(world_to_mesh.transform_vector3(*ray.direction)).ok()?,
+    };
+    let aabb = Aabb3d {
+        min: aabb.min(),
+        max: aabb.max(),
+    };
+    let distance = RayCast3d::from_ray(local_ray, f32::MAX).aabb_intersection_at(&aabb)?;
+    // Distances are scaled in the space of the mesh
+    Some(
+        transform
+            .transform_point(local_ray.get_point(distance))
+            .distance(ray.origin),
+    )
+}
+
+/// Writes the world space positions and normals of a skinned mesh in its current pose, returning
+/// whether it has normals.
+fn skin_mesh(
+    mesh: &Mesh,
+    skinned_mesh: &SkinnedMesh,
+    inverse_bindposes: &Assets<SkinnedMeshInverseBindposes>,
+    joints: &Query<&GlobalTransform>,
+    positions: &mut Vec<[f32; 3]>,
+    normals: &mut Vec<[f32; 3]>,
+) -> Option<bool> {
+    if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+        return None;
+    }
+    let inverse_bindposes = inverse_bindposes.get(&skinned_mesh.inverse_bindposes)?;
+    let joint_matrices = skinned_mesh
+        .joints
+        .iter()
+        .zip(inverse_bindposes.iter())
+        .map(|(joint, inverse_bindpose)| {
+            Some(joints.get(*joint).ok()?.compute_matrix() * *inverse_bindpose)
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let mesh_positions = mesh.attribute(Mesh::ATTRIBUTE_POSITION)?.as_float3()?;
+    let mesh_normals = mesh
+        .attribute(Mesh::ATTRIBUTE_NORMAL)
+        .and_then(VertexAttributeValues::as_float3)
+        .filter(|normals| normals.len() == mesh_positions.len());
+    let Some(VertexAttributeValues::Uint16x4(joint_indices)) =
+        mesh.attribute(Mesh::ATTRIBUTE_JOINT_INDEX)
+    else {
+        return None;
+    };
+    let Some(VertexAttributeValues::Float32x4(joint_weights)) =
+        mesh.attribute(Mesh::ATTRIBUTE_JOINT_WEIGHT)
+    else {
+        return None;
+    };
+
+    positions.clear();
+    normals.clear();
+    for (vertex, position) in mesh_positions.iter().enumerate() {
+        let indices = joint_indices.get(vertex)?;
+        let weights = joint_weights.get(vertex)?;
+        let skin = indices
+            .iter()
+            .zip(weights)
+            .fold(Mat4::ZERO, |skin, (&index, &weight)| {
+                skin + *joint_matrices.get(index as usize).unwrap_or(&Mat4::ZERO) * weight
+            });
+        positions.push(skin.transform_point3((*position).into()).into());
+        if let Some(mesh_normals) = mesh_normals {
+            let normal_matrix = Mat3::from_mat4(skin).inverse().transpose();
+            normals.push((normal_matrix * Vec3::from(mesh_normals[vertex])).into());
+        }
+    }
+    Some(mesh_normals.is_some())
+}
@@ -59,6 +59,7 @@ impl AssetSaver for CompressedImageSaver {
             is_srgb,
             sampler: image.sampler.clone(),
             asset_usage: image.asset_usage,
+            generate_mipmaps: None,
         })
     }
 }
@@ -0,0 +1,118 @@
+use crate::texture::{
+    image_texture_conversion::IntoDynamicImageError, ExrTextureLoader, ExrTextureLoaderSettings,
+    Image, TextureFormatPixelInfo,
+};
+use bevy_asset::saver::{AssetSaver, SavedAsset};
+use futures_lite::AsyncWriteExt;
+use thiserror::Error;
+use wgpu::TextureFormat;
+
+/// Saves [`TextureFormat::Rgba32Float`] images as EXR files that can be read back by the [`ExrTextureLoader`].
+///
+/// Only the first mip level of the first layer is saved.
+#[derive(Clone, Default)]
+pub struct ExrTextureSaver;
+
+/// Possible errors that can be produced by [`ExrTextureSaver`]
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum ExrTextureSaverError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("only `Rgba32Float` images can be saved as EXR, found `{0:?}`")]
+    UnsupportedTextureFormat(TextureFormat),
+    #[error(transparent)]
+    IntoDynamicImage(#[from] IntoDynamicImageError),
+    #[error(transparent)]
+    ImageError(#[from] image::ImageError),
+}
+
+impl AssetSaver for ExrTextureSaver {
+    type Asset = Image;
+    type Settings = ();
+    type OutputLoader = ExrTextureLoader;
+    type Error = ExrTextureSaverError;
+
+    async fn save<'a>(
+        &'a self,
+        writer: &'a mut bevy_asset::io::Writer,
+        image: SavedAsset<'a, Self::Asset>,
+        _settings: &'a Self::Settings,
+    ) -> Result<ExrTextureLoaderSettings, Self::Error> {
+        let format = image.texture_descriptor.format;
+        if format != TextureFormat::Rgba32Float {
+            return Err(ExrTextureSaverError::UnsupportedTextureFormat(format));
+        }
+        let level_bytes = image.width() as usize * image.height() as usize * format.pixel_size();
+        let mut first_level = image.clone();
+        first_level.data.truncate(level_bytes);
+
+        let mut bytes = Vec::new();
+        first_level.try_into_dynamic()?.write_to(
+            &mut std::io::Cursor::new(&mut bytes),
+            image::ImageFormat::OpenExr,
+        )?;
+        writer.write_all(&bytes).await?;
+        Ok(ExrTextureLoaderSettings {
+            asset_usage: image.asset_usage,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render_asset::RenderAssetUsages;
+    use bevy_asset::LoadedAsset;
+    use wgpu::{Extent3d, TextureDimension};
+
+    fn save(image: Image) -> Result<Vec<u8>, ExrTextureSaverError> {
+        let image = LoadedAsset::from(image).into();
+        let mut bytes = Vec::new();
+        futures_lite::future::block_on(ExrTextureSaver.save(
+            &mut bytes,
+            SavedAsset::from_loaded(&image).unwrap(),
+            &(),
+        ))?;
+        Ok(bytes)
+    }
+
+    #[test]
+    fn saves_the_first_mip_level() {
+        let pixels = [[0.25f32, 0.5, 1.5, 1.], [2., 0., 0.75, 0.5]];
+        let mut image = Image::new(
+            Extent3d {
+                width: 2,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            bytemuck::cast_slice(&pixels).to_vec(),
+            TextureFormat::Rgba32Float,
+            RenderAssetUsages::default(),
+        );
+        // A second mip level that mustn't end up in the file
+        image.texture_descriptor.mip_level_count = 2;
+        image
+            .data
+            .extend_from_slice(bytemuck::cast_slice(&[1f32; 4]));
+
+        let bytes = save(image).unwrap();
+        let saved = image::load_from_memory_with_format(&bytes, image::ImageFormat::OpenExr)
+            .unwrap()
+            .into_rgba32f();
+        assert_eq!(saved.dimensions(), (2, 1));
+        assert_eq!(saved.as_raw(), &pixels.concat());
+    }
+
+    #[test]
+    fn rejects_other_formats() {
+        let image = Image::default();
+        assert!(matches!(
+            save(image),
+            Err(ExrTextureSaverError::UnsupportedTextureFormat(
+                TextureFormat::Rgba8UnormSrgb
+            ))
+        ));
+    }
+}
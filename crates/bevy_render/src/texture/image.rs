@@ -7,15 +7,19 @@ use super::ktx2::*;
 
 use crate::{
     render_asset::{PrepareAssetError, RenderAsset, RenderAssetUsages},
-    render_resource::{Sampler, Texture, TextureView},
+    render_resource::{CommandEncoderDescriptor, PipelineCache, Sampler, Texture, TextureView},
     renderer::{RenderDevice, RenderQueue},
-    texture::BevyDefault,
+    texture::{
+        mipmap_generator::create_texture_with_first_mip_level, BevyDefault, MipmapGenerator,
+        TextureAccessError,
+    },
 };
 use bevy_asset::Asset;
 use bevy_derive::{Deref, DerefMut};
 use bevy_ecs::system::{lifetimeless::SRes, Resource, SystemParamItem};
 use bevy_math::{AspectRatio, UVec2, Vec2};
 use bevy_reflect::prelude::*;
+use bevy_utils::warn_once;
 use serde::{Deserialize, Serialize};
 use std::hash::Hash;
 use thiserror::Error;
@@ -121,6 +125,15 @@ pub struct Image {
     pub sampler: ImageSampler,
     pub texture_view_descriptor: Option<TextureViewDescriptor<'static>>,
     pub asset_usage: RenderAssetUsages,
+    /// Generates the mip levels of this image on the GPU from its first mip level, every time it
+    /// is uploaded and, for images used as a camera's render target, every frame after rendering.
+    ///
+    /// This is meant for images changing at runtime. `data` may either only contain the first mip
+    /// level of each layer, or the full mip chain whose other mip levels are then overwritten.
+    /// Only has an effect on 2D images with a `mip_level_count` above 1 using one of the formats
+    /// supported by the [`MipmapGenerator`](super::MipmapGenerator). Other images are uploaded as
+    /// is, with a single mip level if `data` only contains the first one.
+    pub gpu_mipmaps: bool,
 }
 
 /// Used in [`Image`], this determines what image sampler to use when rendering. The default setting,
@@ -478,6 +491,7 @@ impl Default for Image {
             sampler: ImageSampler::Default,
             texture_view_descriptor: None,
             asset_usage: RenderAssetUsages::default(),
+            gpu_mipmaps: false,
         }
     }
 }
@@ -761,6 +775,8 @@ pub enum TextureError {
     /// Only cubemaps with six faces are supported.
     #[error("only cubemaps with six faces are supported")]
     IncompleteCubemap,
+    #[error("failed to generate mipmaps: {0}")]
+    MipmapGeneration(#[from] TextureAccessError),
 }
 
 /// The type of a raw image buffer.
@@ -832,6 +848,8 @@ impl RenderAsset for GpuImage {
         SRes<RenderDevice>,
         SRes<RenderQueue>,
         SRes<DefaultImageSampler>,
+        SRes<MipmapGenerator>,
+        SRes<PipelineCache>,
     );
 
     #[inline]
@@ -847,24 +865,67 @@ impl RenderAsset for GpuImage {
     /// Converts the extracted image into a [`GpuImage`].
     fn prepare_asset(
         image: Self::SourceAsset,
-        (render_device, render_queue, default_sampler): &mut SystemParamItem<Self::Param>,
+        param: &mut SystemParamItem<Self::Param>,
     ) -> Result<Self, PrepareAssetError<Self::SourceAsset>> {
-        let texture = render_device.create_texture_with_data(
-            render_queue,
-            &image.texture_descriptor,
-            // TODO: Is this correct? Do we need to use `MipMajor` if it's a ktx2 file?
-            wgpu::util::TextureDataOrder::default(),
-            &image.data,
-        );
+        let (render_device, render_queue, default_sampler, mipmap_generator, pipeline_cache) =
+            param;
+        let format = image.texture_descriptor.format;
+        let gpu_mipmaps = image.gpu_mipmaps && image.texture_descriptor.mip_level_count > 1;
+        let mut texture_view_descriptor = image.texture_view_descriptor.clone().unwrap_or_default();
+
+        let can_generate_mipmaps = image.texture_descriptor.dimension == TextureDimension::D2
+            && MipmapGenerator::storage_format(format).is_some();
+        if gpu_mipmaps && !can_generate_mipmaps {
+            warn_once!(
+                "Mip levels can't be generated on the GPU for {:?} {:?} images",
+                image.texture_descriptor.dimension,
+                format
+            );
+        }
+
+        let texture = if gpu_mipmaps && can_generate_mipmaps {
+            if !mipmap_generator.is_ready(format, pipeline_cache) {
+                return Err(PrepareAssetError::RetryNextUpdate(image));
+            }
+
+            let texture = create_texture_with_first_mip_level(render_device, render_queue, &image);
+            let mut command_encoder =
+                render_device.create_command_encoder(&CommandEncoderDescriptor {
+                    label: Some("image_mipmaps"),
+                });
+            if mipmap_generator.generate_mipmaps(
+                render_device,
+                pipeline_cache,
+                &mut command_encoder,
+                &texture,
+                format,
+            ) {
+                render_queue.submit([command_encoder.finish()]);
+            }
+            // The texture may use another format, see `MipmapGenerator::storage_format`
+            texture_view_descriptor.format.get_or_insert(format);
+            texture
+        } else {
+            // Images that only hold their first mip level can't have the others generated, so they
+            // are uploaded without them.
+            let mut texture_descriptor = image.texture_descriptor.clone();
+            if gpu_mipmaps
+                && first_mip_level_size(&texture_descriptor)
+                    .is_some_and(|size| image.data.len() <= size)
+            {
+                texture_descriptor.mip_level_count = 1;
+            }
+            render_device.create_texture_with_data(
+                render_queue,
+                &texture_descriptor,
+                // TODO: Is this correct? Do we need to use `MipMajor` if it's a ktx2 file?
+                wgpu::util::TextureDataOrder::default(),
+                &image.data,
+            )
+        };
 
         let size = image.size();
-        let texture_view = texture.create_view(
-            image
-                .texture_view_descriptor
-                .or_else(|| Some(TextureViewDescriptor::default()))
-                .as_ref()
-                .unwrap(),
-        );
+        let texture_view = texture.create_view(&texture_view_descriptor);
         let sampler = match image.sampler {
             ImageSampler::Default => (***default_sampler).clone(),
             ImageSampler::Descriptor(descriptor) => {
@@ -873,16 +934,30 @@ impl RenderAsset for GpuImage {
         };
 
         Ok(GpuImage {
+            mip_level_count: texture.mip_level_count(),
             texture,
             texture_view,
-            texture_format: image.texture_descriptor.format,
+            texture_format: format,
             sampler,
             size,
-            mip_level_count: image.texture_descriptor.mip_level_count,
         })
     }
 }
 
+/// Returns the size in bytes of the first mip level of every layer of a texture, or `None` if its
+/// format has no single block size.
+fn first_mip_level_size(descriptor: &wgpu::TextureDescriptor) -> Option<usize> {
+    let block_size = descriptor.format.block_copy_size(None)? as usize;
+    let (block_width, block_height) = descriptor.format.block_dimensions();
+    let size = descriptor.size;
+    Some(
+        size.width.div_ceil(block_width) as usize
+            * size.height.div_ceil(block_height) as usize
+            * size.depth_or_array_layers as usize
+            * block_size,
+    )
+}
+
 bitflags::bitflags! {
     #[derive(Default, Clone, Copy, Eq, PartialEq, Debug)]
     #[repr(transparent)]
@@ -945,6 +1020,36 @@ impl CompressedImageFormats {
 mod test {
     use super::*;
 
+    #[test]
+    fn first_mip_level_size_covers_every_layer() {
+        let mut descriptor = wgpu::TextureDescriptor {
+            label: None,
+            size: Extent3d {
+                width: 16,
+                height: 8,
+                depth_or_array_layers: 6,
+            },
+            mip_level_count: 4,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        };
+        assert_eq!(first_mip_level_size(&descriptor), Some(16 * 8 * 6 * 4));
+
+        descriptor.dimension = TextureDimension::D3;
+        assert_eq!(first_mip_level_size(&descriptor), Some(16 * 8 * 6 * 4));
+
+        // 4x4 blocks of 8 bytes, rounded up to whole blocks.
+        descriptor.size.width = 18;
+        descriptor.format = TextureFormat::Bc1RgbaUnorm;
+        assert_eq!(first_mip_level_size(&descriptor), Some(5 * 2 * 6 * 8));
+
+        descriptor.format = TextureFormat::Depth24PlusStencil8;
+        assert_eq!(first_mip_level_size(&descriptor), None);
+    }
+
     #[test]
     fn image_size() {
         let size = Extent3d {
@@ -0,0 +1,674 @@
+use crate::texture::Image;
+use bevy_color::{Color, ColorToComponents, LinearRgba, Srgba};
+use bevy_math::{URect, UVec2, UVec3};
+use thiserror::Error;
+use wgpu::{Extent3d, TextureDimension, TextureFormat};
+
+/// An error that occurs when accessing the pixels of an [`Image`] on the CPU.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TextureAccessError {
+    #[error("pixel (x: {x}, y: {y}, z: {z}) of mip level {mip_level} is out of bounds")]
+    OutOfBounds {
+        x: u32,
+        y: u32,
+        z: u32,
+        mip_level: u32,
+    },
+    #[error("rectangle {0:?} is out of bounds")]
+    RectOutOfBounds(URect),
+    #[error("unsupported texture format: {0:?}")]
+    UnsupportedTextureFormat(TextureFormat),
+    #[error("the operation requires a {0:?} texture")]
+    WrongDimension(TextureDimension),
+}
+
+/// How each channel of a pixel is stored.
+#[derive(Clone, Copy)]
+enum ChannelEncoding {
+    Unorm8,
+    Snorm8,
+    Unorm16,
+    Snorm16,
+    Float16,
+    Float32,
+    /// Red, green and blue in 10 bits each and alpha in 2 bits, packed in a `u32`.
+    Unorm10_10_10_2,
+}
+
+impl ChannelEncoding {
+    fn size(self) -> usize {
+        match self {
+            ChannelEncoding::Unorm8 | ChannelEncoding::Snorm8 => 1,
+            ChannelEncoding::Unorm16 | ChannelEncoding::Snorm16 | ChannelEncoding::Float16 => 2,
+            ChannelEncoding::Float32 | ChannelEncoding::Unorm10_10_10_2 => 4,
+        }
+    }
+
+    fn decode(self, bytes: &[u8]) -> f32 {
+        match self {
+            ChannelEncoding::Unorm8 => bytes[0] as f32 / u8::MAX as f32,
+            ChannelEncoding::Snorm8 => (bytes[0] as i8 as f32 / i8::MAX as f32).max(-1.0),
+            ChannelEncoding::Unorm16 => {
+                u16::from_le_bytes([bytes[0], bytes[1]]) as f32 / u16::MAX as f32
+            }
+            ChannelEncoding::Snorm16 => {
+                (i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / i16::MAX as f32).max(-1.0)
+            }
+            ChannelEncoding::Float16 => f16_to_f32(u16::from_le_bytes([bytes[0], bytes[1]])),
+            ChannelEncoding::Float32 => {
+                f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+            }
+            ChannelEncoding::Unorm10_10_10_2 => unreachable!("packed formats are decoded whole"),
+        }
+    }
+
+    fn encode(self, value: f32, bytes: &mut [u8]) {
+        match self {
+            ChannelEncoding::Unorm8 => {
+                bytes[0] = (value.clamp(0.0, 1.0) * u8::MAX as f32).round() as u8;
+            }
+            ChannelEncoding::Snorm8 => {
+                bytes[0] = (value.clamp(-1.0, 1.0) * i8::MAX as f32).round() as i8 as u8;
+            }
+            ChannelEncoding::Unorm16 => bytes[..2].copy_from_slice(
+                &((value.clamp(0.0, 1.0) * u16::MAX as f32).round() as u16).to_le_bytes(),
+            ),
+            ChannelEncoding::Snorm16 => bytes[..2].copy_from_slice(
+                &((value.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16).to_le_bytes(),
+            ),
+            ChannelEncoding::Float16 => {
+                bytes[..2].copy_from_slice(&f32_to_f16(value).to_le_bytes());
+            }
+            ChannelEncoding::Float32 => bytes[..4].copy_from_slice(&value.to_le_bytes()),
+            ChannelEncoding::Unorm10_10_10_2 => unreachable!("packed formats are encoded whole"),
+        }
+    }
+}
+
+/// The layout of a pixel of a format supported by pixel access.
+struct PixelLayout {
+    encoding: ChannelEncoding,
+    channels: usize,
+    /// Whether the red and blue channels are swapped.
+    bgra: bool,
+    /// Whether the color channels are sRGB encoded.
+    srgb: bool,
+}
+
+fn pixel_layout(format: TextureFormat) -> Option<PixelLayout> {
+    use ChannelEncoding::*;
+    let (encoding, channels, bgra, srgb) = match format {
+        TextureFormat::R8Unorm => (Unorm8, 1, false, false),
+        TextureFormat::Rg8Unorm => (Unorm8, 2, false, false),
+        TextureFormat::Rgba8Unorm => (Unorm8, 4, false, false),
+        TextureFormat::Rgba8UnormSrgb => (Unorm8, 4, false, true),
+        TextureFormat::Bgra8Unorm => (Unorm8, 4, true, false),
+        TextureFormat::Bgra8UnormSrgb => (Unorm8, 4, true, true),
+        TextureFormat::R8Snorm => (Snorm8, 1, false, false),
+        TextureFormat::Rg8Snorm => (Snorm8, 2, false, false),
+        TextureFormat::Rgba8Snorm => (Snorm8, 4, false, false),
+        TextureFormat::R16Unorm => (Unorm16, 1, false, false),
+        TextureFormat::Rg16Unorm => (Unorm16, 2, false, false),
+        TextureFormat::Rgba16Unorm => (Unorm16, 4, false, false),
+        TextureFormat::R16Snorm => (Snorm16, 1, false, false),
+        TextureFormat::Rg16Snorm => (Snorm16, 2, false, false),
+        TextureFormat::Rgba16Snorm => (Snorm16, 4, false, false),
+        TextureFormat::R16Float => (Float16, 1, false, false),
+        TextureFormat::Rg16Float => (Float16, 2, false, false),
+        TextureFormat::Rgba16Float => (Float16, 4, false, false),
+        TextureFormat::R32Float => (Float32, 1, false, false),
+        TextureFormat::Rg32Float => (Float32, 2, false, false),
+        TextureFormat::Rgba32Float => (Float32, 4, false, false),
+        TextureFormat::Rgb10a2Unorm => (Unorm10_10_10_2, 4, false, false),
+        _ => return None,
+    };
+    Some(PixelLayout {
+        encoding,
+        channels,
+        bgra,
+        srgb,
+    })
+}
+
+/// Returns whether the pixels of images of `format` can be accessed as colors.
+pub(super) fn supports_pixel_access(format: TextureFormat) -> bool {
+    pixel_layout(format).is_some()
+}
+
+/// Returns the size of a pixel of `format` in bytes, or `None` for compressed and depth-stencil
+/// formats, which don't have one.
+pub(super) fn pixel_size(format: TextureFormat) -> Option<usize> {
+    match format.block_dimensions() {
+        (1, 1) => format.block_copy_size(None).map(|size| size as usize),
+        _ => None,
+    }
+}
+
+/// Decodes the pixel `bytes` of an image of `format`.
+///
+/// Colors are [`Color::Srgba`] for sRGB formats and [`Color::LinearRgba`] otherwise. Missing
+/// channels are zero, except for single channel formats, which read as gray, and alpha, which is
+/// one.
+pub(super) fn decode_pixel(format: TextureFormat, bytes: &[u8]) -> Option<Color> {
+    let layout = pixel_layout(format)?;
+    let mut components = [0.0, 0.0, 0.0, 1.0];
+    if let ChannelEncoding::Unorm10_10_10_2 = layout.encoding {
+        let packed = u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        components = [
+            (packed & 0x3ff) as f32 / 1023.0,
+            ((packed >> 10) & 0x3ff) as f32 / 1023.0,
+            ((packed >> 20) & 0x3ff) as f32 / 1023.0,
+            (packed >> 30) as f32 / 3.0,
+        ];
+    } else {
+        let size = layout.encoding.size();
+        for (component, bytes) in components
+            .iter_mut()
+            .zip(bytes.chunks_exact(size))
+            .take(layout.channels)
+        {
+            *component = layout.encoding.decode(bytes);
+        }
+    }
+    if layout.bgra {
+        components.swap(0, 2);
+    }
+    if layout.channels == 1 {
+        components[1] = components[0];
+        components[2] = components[0];
+    }
+
+    Some(if layout.srgb {
+        Color::Srgba(Srgba::from_f32_array(components))
+    } else {
+        Color::LinearRgba(LinearRgba::from_f32_array(components))
+    })
+}
+
+/// Encodes `color` into the pixel `bytes` of an image of `format`, dropping the channels it
+/// doesn't have.
+pub(super) fn encode_pixel(format: TextureFormat, color: Color, bytes: &mut [u8]) -> Option<()> {
+    let layout = pixel_layout(format)?;
+    let mut components = if layout.srgb {
+        Srgba::from(color).to_f32_array()
+    } else {
+        LinearRgba::from(color).to_f32_array()
+    };
+    if layout.bgra {
+        components.swap(0, 2);
+    }
+    if let ChannelEncoding::Unorm10_10_10_2 = layout.encoding {
+        let [r, g, b, a] = components.map(|component| component.clamp(0.0, 1.0));
+        let packed = (r * 1023.0).round() as u32
+            | ((g * 1023.0).round() as u32) << 10
+            | ((b * 1023.0).round() as u32) << 20
+            | ((a * 3.0).round() as u32) << 30;
+        bytes[..4].copy_from_slice(&packed.to_le_bytes());
+    } else {
+        let size = layout.encoding.size();
+        for (component, bytes) in components
+            .into_iter()
+            .zip(bytes.chunks_exact_mut(size))
+            .take(layout.channels)
+        {
+            layout.encoding.encode(component, bytes);
+        }
+    }
+    Some(())
+}
+
+/// Converts the bits of an IEEE 754 half precision float to an `f32`.
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = ((bits as u32) & 0x8000) << 16;
+    let exponent = ((bits >> 10) & 0x1f) as u32;
+    let mantissa = (bits & 0x3ff) as u32;
+    match exponent {
+        // Zero and subnormals
+        0 => {
+            let value = mantissa as f32 * 2f32.powi(-24);
+            if sign == 0 {
+                value
+            } else {
+                -value
+            }
+        }
+        // Infinity and NaN
+        0x1f => f32::from_bits(sign | 0x7f80_0000 | (mantissa << 13)),
+        _ => f32::from_bits(sign | ((exponent + 112) << 23) | (mantissa << 13)),
+    }
+}
+
+/// Converts an `f32` to the bits of the nearest IEEE 754 half precision float.
+fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    // Infinity and NaN
+    if exponent == 0xff {
+        return sign | 0x7c00 | if mantissa != 0 { 0x200 } else { 0 };
+    }
+
+    let half_exponent = exponent - 127 + 15;
+    if half_exponent >= 0x1f {
+        // Too large, round to infinity
+        return sign | 0x7c00;
+    }
+    if half_exponent <= 0 {
+        if half_exponent < -10 {
+            // Too small, round to zero
+            return sign;
+        }
+        // Subnormal, with the implicit leading one made explicit. Rounds to nearest even.
+        let mantissa = mantissa | 0x80_0000;
+        let shift = (14 - half_exponent) as u32;
+        let rounded = mantissa + (1 << (shift - 1)) - 1 + ((mantissa >> shift) & 1);
+        return sign | (rounded >> shift) as u16;
+    }
+
+    // Rounds to nearest even, a carry out of the mantissa increments the exponent
+    let rounded = mantissa + 0xfff + ((mantissa >> 13) & 1);
+    sign | (((half_exponent as u32) << 10) + (rounded >> 13)) as u16
+}
+
+impl Image {
+    /// Returns the size of mip level `mip_level`, where the depth is the number of array layers
+    /// for 1D and 2D textures.
+    fn mip_level_extent(&self, mip_level: u32) -> Extent3d {
+        let size = self.texture_descriptor.size;
+        let dimension = self.texture_descriptor.dimension;
+        Extent3d {
+            width: (size.width >> mip_level).max(1),
+            height: match dimension {
+                TextureDimension::D1 => 1,
+                _ => (size.height >> mip_level).max(1),
+            },
+            depth_or_array_layers: match dimension {
+                TextureDimension::D3 => (size.depth_or_array_layers >> mip_level).max(1),
+                _ => size.depth_or_array_layers,
+            },
+        }
+    }
+
+    /// Returns the offset in [`Image::data`] of mip level `mip_level` of array layer `layer`.
+    ///
+    /// Each layer stores all of its mip levels, from the largest one, before the next layer.
+    /// 3D textures have a single layer.
+    pub(super) fn mip_level_offset(&self, layer: u32, mip_level: u32) -> usize {
+        let pixel_size = pixel_size(self.texture_descriptor.format).unwrap_or(0);
+        let is_3d = self.texture_descriptor.dimension == TextureDimension::D3;
+        let mip_level_size = |mip_level| {
+            let extent = self.mip_level_extent(mip_level);
+            let slices = if is_3d {
+                extent.depth_or_array_layers
+            } else {
+                1
+            };
+            (extent.width * extent.height * slices) as usize * pixel_size
+        };
+        let layer_size: usize = (0..self.texture_descriptor.mip_level_count)
+            .map(mip_level_size)
+            .sum();
+        layer as usize * layer_size + (0..mip_level).map(mip_level_size).sum::<usize>()
+    }
+
+    /// Returns the offset in [`Image::data`] of the pixel at `coords` of mip level `mip_level`,
+    /// or `None` if it's out of bounds or the format is compressed.
+    ///
+    /// `coords.z` is the depth slice for 3D textures, and the array layer otherwise.
+    pub fn pixel_data_offset(&self, coords: UVec3, mip_level: u32) -> Option<usize> {
+        let pixel_size = pixel_size(self.texture_descriptor.format)?;
+        if mip_level >= self.texture_descriptor.mip_level_count {
+            return None;
+        }
+        let extent = self.mip_level_extent(mip_level);
+        if coords.x >= extent.width
+            || coords.y >= extent.height
+            || coords.z >= extent.depth_or_array_layers
+        {
+            return None;
+        }
+
+        let (layer, slice) = match self.texture_descriptor.dimension {
+            TextureDimension::D3 => (0, coords.z),
+            _ => (coords.z, 0),
+        };
+        let offset = self.mip_level_offset(layer, mip_level)
+            + ((slice * extent.height + coords.y) * extent.width + coords.x) as usize * pixel_size;
+        (offset + pixel_size <= self.data.len()).then_some(offset)
+    }
+
+    /// Returns the bytes of the pixel at `coords` of the largest mip level, or `None` if it's out
+    /// of bounds or the format is compressed.
+    pub fn pixel_bytes(&self, coords: UVec3) -> Option<&[u8]> {
+        let offset = self.pixel_data_offset(coords, 0)?;
+        let pixel_size = pixel_size(self.texture_descriptor.format)?;
+        Some(&self.data[offset..offset + pixel_size])
+    }
+
+    /// Returns the mutable bytes of the pixel at `coords` of the largest mip level, or `None` if
+    /// it's out of bounds or the format is compressed.
+    pub fn pixel_bytes_mut(&mut self, coords: UVec3) -> Option<&mut [u8]> {
+        let offset = self.pixel_data_offset(coords, 0)?;
+        let pixel_size = pixel_size(self.texture_descriptor.format)?;
+        Some(&mut self.data[offset..offset + pixel_size])
+    }
+
+    /// Reads the color of the pixel at `x`, `y` of a 1D or 2D image.
+    ///
+    /// See [`Image::get_color_at_mip`] for the supported formats.
+    #[inline]
+    pub fn get_color_at(&self, x: u32, y: u32) -> Result<Color, TextureAccessError> {
+        self.get_color_at_mip(x, y, 0, 0)
+    }
+
+    /// Reads the color of the pixel at `x`, `y` of depth slice or array layer `z`.
+    ///
+    /// See [`Image::get_color_at_mip`] for the supported formats.
+    #[inline]
+    pub fn get_color_at_3d(&self, x: u32, y: u32, z: u32) -> Result<Color, TextureAccessError> {
+        self.get_color_at_mip(x, y, z, 0)
+    }
+
+    /// Reads the color of the pixel at `x`, `y` of depth slice or array layer `z` of mip level
+    /// `mip_level`.
+    ///
+    /// The color is a [`Color::Srgba`] for sRGB formats, and a [`Color::LinearRgba`] otherwise.
+    /// Single channel formats read as gray, other missing color channels read as zero, and a
+    /// missing alpha channel reads as one.
+    ///
+    /// Supported formats are the 8 and 16 bit normalized formats, including the sRGB and BGRA
+    /// ones, the 16 and 32 bit float formats and [`TextureFormat::Rgb10a2Unorm`].
+    pub fn get_color_at_mip(
+        &self,
+        x: u32,
+        y: u32,
+        z: u32,
+        mip_level: u32,
+    ) -> Result<Color, TextureAccessError> {
+        let format = self.texture_descriptor.format;
+        let offset = self.pixel_data_offset(UVec3::new(x, y, z), mip_level);
+        match (pixel_layout(format), offset) {
+            (None, _) => Err(TextureAccessError::UnsupportedTextureFormat(format)),
+            (Some(_), None) => Err(TextureAccessError::OutOfBounds { x, y, z, mip_level }),
+            (Some(_), Some(offset)) => {
+                Ok(decode_pixel(format, &self.data[offset..]).expect("the format is supported"))
+            }
+        }
+    }
+
+    /// Writes `color` to the pixel at `x`, `y` of a 1D or 2D image.
+    ///
+    /// See [`Image::set_color_at_mip`] for the supported formats.
+    #[inline]
+    pub fn set_color_at(&mut self, x: u32, y: u32, color: Color) -> Result<(), TextureAccessError> {
+        self.set_color_at_mip(x, y, 0, 0, color)
+    }
+
+    /// Writes `color` to the pixel at `x`, `y` of depth slice or array layer `z`.
+    ///
+    /// See [`Image::set_color_at_mip`] for the supported formats.
+    #[inline]
+    pub fn set_color_at_3d(
+        &mut self,
+        x: u32,
+        y: u32,
+        z: u32,
+        color: Color,
+    ) -> Result<(), TextureAccessError> {
+        self.set_color_at_mip(x, y, z, 0, color)
+    }
+
+    /// Writes `color` to the pixel at `x`, `y` of depth slice or array layer `z` of mip level
+    /// `mip_level`.
+    ///
+    /// The color is converted to sRGB for sRGB formats and to linear RGB otherwise, and the
+    /// channels the format doesn't have are dropped, so single channel formats store the red
+    /// channel. Values are clamped to the range of normalized formats.
+    ///
+    /// The supported formats are the same as [`Image::get_color_at_mip`].
+    pub fn set_color_at_mip(
+        &mut self,
+        x: u32,
+        y: u32,
+        z: u32,
+        mip_level: u32,
+        color: Color,
+    ) -> Result<(), TextureAccessError> {
+        let format = self.texture_descriptor.format;
+        let offset = self.pixel_data_offset(UVec3::new(x, y, z), mip_level);
+        match (pixel_layout(format), offset) {
+            (None, _) => Err(TextureAccessError::UnsupportedTextureFormat(format)),
+            (Some(_), None) => Err(TextureAccessError::OutOfBounds { x, y, z, mip_level }),
+            (Some(_), Some(offset)) => {
+                encode_pixel(format, color, &mut self.data[offset..])
+                    .expect("the format is supported");
+                Ok(())
+            }
+        }
+    }
+
+    /// Sets every pixel of every mip level and layer of the image to `color`.
+    pub fn fill(&mut self, color: Color) -> Result<(), TextureAccessError> {
+        let format = self.texture_descriptor.format;
+        let mut pixel = vec![0; pixel_size(format).unwrap_or(0)];
+        encode_pixel(format, color, &mut pixel)
+            .ok_or(TextureAccessError::UnsupportedTextureFormat(format))?;
+        for current_pixel in self.data.chunks_exact_mut(pixel.len()) {
+            current_pixel.copy_from_slice(&pixel);
+        }
+        Ok(())
+    }
+
+    /// Returns a new 2D image with the pixels of `rect` of the largest mip level of the first
+    /// layer.
+    pub fn sub_image(&self, rect: URect) -> Result<Image, TextureAccessError> {
+        let mut texture_descriptor = self.texture_descriptor.clone();
+        texture_descriptor.dimension = TextureDimension::D2;
+        texture_descriptor.mip_level_count = 1;
+        let mut sub_image = Image {
+            data: Vec::new(),
+            texture_descriptor,
+            sampler: self.sampler.clone(),
+            texture_view_descriptor: None,
+            asset_usage: self.asset_usage,
+            gpu_mipmaps: false,
+        };
+        sub_image.resize(Extent3d {
+            width: rect.width(),
+            height: rect.height(),
+            depth_or_array_layers: 1,
+        });
+        sub_image.copy_sub_image(self, rect, UVec2::ZERO)?;
+        Ok(sub_image)
+    }
+
+    /// Copies the pixels of `source_rect` of `source` to the rectangle of the same size at
+    /// `destination`, in the largest mip level of the first layer of both images.
+    ///
+    /// The pixels are converted when the images have different formats, otherwise they're
+    /// copied exactly. See [`Image::blit`] to blend them instead.
+    pub fn copy_sub_image(
+        &mut self,
+        source: &Image,
+        source_rect: URect,
+        destination: UVec2,
+    ) -> Result<(), TextureAccessError> {
+        let destination_rect = self.check_copy_rects(source, source_rect, destination)?;
+        let source_format = source.texture_descriptor.format;
+        let destination_format = self.texture_descriptor.format;
+
+        if source_format == destination_format {
+            let row_size = source_rect.width() as usize * pixel_size(source_format).unwrap_or(0);
+            for row in 0..source_rect.height() {
+                let (Some(source_offset), Some(destination_offset)) = (
+                    source.pixel_data_offset(source_rect.min.extend(0) + UVec3::Y * row, 0),
+                    self.pixel_data_offset(destination_rect.min.extend(0) + UVec3::Y * row, 0),
+                ) else {
+                    return Err(TextureAccessError::RectOutOfBounds(source_rect));
+                };
+                self.data[destination_offset..destination_offset + row_size]
+                    .copy_from_slice(&source.data[source_offset..source_offset + row_size]);
+            }
+            return Ok(());
+        }
+
+        for y in 0..source_rect.height() {
+            for x in 0..source_rect.width() {
+                let color = source.get_color_at(source_rect.min.x + x, source_rect.min.y + y)?;
+                self.set_color_at(destination.x + x, destination.y + y, color)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Blends the pixels of `source_rect` of `source` over the rectangle of the same size at
+    /// `destination`, in the largest mip level of the first layer of both images.
+    ///
+    /// The colors are blended in linear space, using the alpha of the source pixels.
+    pub fn blit(
+        &mut self,
+        source: &Image,
+        source_rect: URect,
+        destination: UVec2,
+    ) -> Result<(), TextureAccessError> {
+        self.check_copy_rects(source, source_rect, destination)?;
+
+        for y in 0..source_rect.height() {
+            for x in 0..source_rect.width() {
+                let (destination_x, destination_y) = (destination.x + x, destination.y + y);
+                let source_color = LinearRgba::from(
+                    source.get_color_at(source_rect.min.x + x, source_rect.min.y + y)?,
+                );
+                let destination_color =
+                    LinearRgba::from(self.get_color_at(destination_x, destination_y)?);
+
+                let alpha =
+                    source_color.alpha + destination_color.alpha * (1.0 - source_color.alpha);
+                let blended = if alpha > 0.0 {
+                    let blend = |source: f32, destination: f32| {
+                        (source * source_color.alpha
+                            + destination * destination_color.alpha * (1.0 - source_color.alpha))
+                            / alpha
+                    };
+                    LinearRgba::new(
+                        blend(source_color.red, destination_color.red),
+                        blend(source_color.green, destination_color.green),
+                        blend(source_color.blue, destination_color.blue),
+                        alpha,
+                    )
+                } else {
+                    LinearRgba::NONE
+                };
+                self.set_color_at(destination_x, destination_y, blended.into())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that `source_rect` fits in `source` and that the rectangle of the same size at
+    /// `destination` fits in `self`, and returns it.
+    fn check_copy_rects(
+        &self,
+        source: &Image,
+        source_rect: URect,
+        destination: UVec2,
+    ) -> Result<URect, TextureAccessError> {
+        for image in [self, source] {
+            if image.texture_descriptor.dimension == TextureDimension::D3 {
+                return Err(TextureAccessError::WrongDimension(TextureDimension::D2));
+            }
+        }
+        if source_rect.max.cmpgt(source.size()).any() {
+            return Err(TextureAccessError::RectOutOfBounds(source_rect));
+        }
+        let destination_rect = URect::from_corners(destination, destination + source_rect.size());
+        if destination_rect.max.cmpgt(self.size()).any() {
+            return Err(TextureAccessError::RectOutOfBounds(destination_rect));
+        }
+        Ok(destination_rect)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render_asset::RenderAssetUsages;
+
+    fn image(size: UVec2, format: TextureFormat) -> Image {
+        Image::new_fill(
+            Extent3d {
+                width: size.x,
+                height: size.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &vec![0; pixel_size(format).unwrap()],
+            format,
+            RenderAssetUsages::MAIN_WORLD,
+        )
+    }
+
+    #[test]
+    fn color_roundtrip() {
+        for format in [
+            TextureFormat::Rgba8UnormSrgb,
+            TextureFormat::Bgra8Unorm,
+            TextureFormat::Rgba16Float,
+            TextureFormat::Rgba32Float,
+        ] {
+            let mut image = image(UVec2::new(4, 2), format);
+            let color = Color::srgba(0.2, 0.4, 0.6, 0.8);
+            image.set_color_at(3, 1, color).unwrap();
+
+            let read = Srgba::from(image.get_color_at(3, 1).unwrap()).to_f32_array();
+            for (read, expected) in read.iter().zip(Srgba::from(color).to_f32_array()) {
+                assert!(
+                    (read - expected).abs() < 0.01,
+                    "{format:?}: {read} != {expected}"
+                );
+            }
+            assert_eq!(
+                image.get_color_at(4, 1),
+                Err(TextureAccessError::OutOfBounds {
+                    x: 4,
+                    y: 1,
+                    z: 0,
+                    mip_level: 0
+                })
+            );
+        }
+    }
+
+    #[test]
+    fn f16_conversion() {
+        for value in [0.0, 1.0, -2.5, 0.333, 65504.0] {
+            let converted = f16_to_f32(f32_to_f16(value));
+            assert!((converted - value).abs() <= value.abs() / 1024.0, "{value}");
+        }
+        assert_eq!(f32_to_f16(1.0), 0x3c00);
+        assert_eq!(f16_to_f32(0x7c00), f32::INFINITY);
+        // Smallest subnormal
+        assert_eq!(f32_to_f16(2f32.powi(-24)), 1);
+    }
+
+    #[test]
+    fn copy_sub_image() {
+        let mut source = image(UVec2::new(4, 4), TextureFormat::Rgba8Unorm);
+        source.set_color_at(2, 3, Color::WHITE).unwrap();
+        let mut destination = image(UVec2::new(3, 3), TextureFormat::R8Unorm);
+
+        destination
+            .copy_sub_image(&source, URect::new(1, 2, 3, 4), UVec2::new(1, 1))
+            .unwrap();
+        assert_eq!(
+            destination.pixel_bytes(UVec3::new(2, 2, 0)),
+            Some(&[255][..])
+        );
+        assert_eq!(destination.pixel_bytes(UVec3::new(1, 2, 0)), Some(&[0][..]));
+        assert!(destination
+            .copy_sub_image(&source, URect::new(0, 0, 3, 3), UVec2::new(1, 0))
+            .is_err());
+    }
+}
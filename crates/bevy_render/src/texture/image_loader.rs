@@ -8,7 +8,7 @@ use crate::{
     texture::{Image, ImageFormat, ImageType, TextureError},
 };
 
-use super::{CompressedImageFormats, ImageSampler};
+use super::{CompressedImageFormats, ImageSampler, MipmapFilter};
 use serde::{Deserialize, Serialize};
 
 /// Loader for images that can be read by the `image` crate.
@@ -59,6 +59,10 @@ pub struct ImageLoaderSettings {
     pub is_srgb: bool,
     pub sampler: ImageSampler,
     pub asset_usage: RenderAssetUsages,
+    /// Generates a full mip chain on the CPU with the given filter when the loaded image only has
+    /// a single mip level. Compressed images are left untouched.
+    #[serde(default)]
+    pub generate_mipmaps: Option<MipmapFilter>,
 }
 
 impl Default for ImageLoaderSettings {
@@ -68,6 +72,7 @@ impl Default for ImageLoaderSettings {
             is_srgb: true,
             sampler: ImageSampler::Default,
             asset_usage: RenderAssetUsages::default(),
+            generate_mipmaps: None,
         }
     }
 }
@@ -101,7 +106,11 @@ impl AssetLoader for ImageLoader {
             }
             ImageFormatSetting::Format(format) => ImageType::Format(format),
         };
-        Ok(Image::from_buffer(
+        let file_texture_error = |error| FileTextureError {
+            error,
+            path: format!("{}", load_context.path().display()),
+        };
+        let mut image = Image::from_buffer(
             #[cfg(all(debug_assertions, feature = "dds"))]
             load_context.path().display().to_string(),
             &bytes,
@@ -111,10 +120,15 @@ impl AssetLoader for ImageLoader {
             settings.sampler.clone(),
             settings.asset_usage,
         )
-        .map_err(|err| FileTextureError {
-            error: err,
-            path: format!("{}", load_context.path().display()),
-        })?)
+        .map_err(file_texture_error)?;
+        if let Some(filter) = settings.generate_mipmaps {
+            if image.texture_descriptor.mip_level_count == 1 && !image.is_compressed() {
+                image
+                    .generate_mipmaps(filter)
+                    .map_err(|err| file_texture_error(err.into()))?;
+            }
+        }
+        Ok(image)
     }
 
     fn extensions(&self) -> &[&str] {
@@ -0,0 +1,271 @@
+use crate::texture::{
+    image_access::{decode_pixel, encode_pixel, pixel_size, supports_pixel_access},
+    Image, TextureAccessError,
+};
+use bevy_asset::transformer::{AssetTransformer, TransformedAsset};
+use bevy_color::LinearRgba;
+use bevy_math::UVec2;
+use serde::{Deserialize, Serialize};
+use std::f32::consts::PI;
+use wgpu::TextureDimension;
+
+/// The filter [`Image::generate_mipmaps`] downsamples each mip level with.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MipmapFilter {
+    /// Averages each 2×2 block of pixels. Fast, but blurrier and more prone to aliasing than
+    /// [`MipmapFilter::Kaiser`].
+    #[default]
+    Box,
+    /// A Kaiser windowed sinc filter, which keeps distant mip levels sharper at the cost of
+    /// slower generation, and may slightly ring around hard edges.
+    Kaiser,
+}
+
+/// An [`AssetTransformer`] generating a full mip chain on the CPU for images that only have a
+/// single mip level, to be used in an asset processor ahead of an image saver keeping mip levels,
+/// such as the [`ImageSaver`](super::ImageSaver) with [`ImageSaverFormat::Ktx2`](super::ImageSaverFormat::Ktx2).
+///
+/// Compressed images, and images that already have mip levels, are left untouched.
+#[derive(Default)]
+pub struct ImageMipmapGenerator;
+
+/// Settings for the [`ImageMipmapGenerator`].
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct ImageMipmapGeneratorSettings {
+    pub filter: MipmapFilter,
+}
+
+impl AssetTransformer for ImageMipmapGenerator {
+    type AssetInput = Image;
+    type AssetOutput = Image;
+    type Settings = ImageMipmapGeneratorSettings;
+    type Error = TextureAccessError;
+
+    async fn transform<'a>(
+        &'a self,
+        mut image: TransformedAsset<Image>,
+        settings: &'a Self::Settings,
+    ) -> Result<TransformedAsset<Image>, Self::Error> {
+        if image.texture_descriptor.mip_level_count == 1 && !image.is_compressed() {
+            image.generate_mipmaps(settings.filter)?;
+        }
+        Ok(image)
+    }
+}
+
+/// The half width of the Kaiser filter, in pixels of the downsampled mip level.
+const KAISER_WIDTH: f32 = 3.0;
+/// The shape of the Kaiser window, higher values trade sharpness for less ringing.
+const KAISER_ALPHA: f32 = 4.0;
+
+impl Image {
+    /// Replaces the mip levels of a 2D image with a full mip chain generated on the CPU from its
+    /// largest mip level, down to a 1×1 pixel mip level.
+    ///
+    /// Colors are filtered in linear space with premultiplied alpha, so sRGB formats are decoded
+    /// before filtering and encoded again after.
+    ///
+    /// See [`Image::get_color_at_mip`] for the supported formats.
+    pub fn generate_mipmaps(&mut self, filter: MipmapFilter) -> Result<(), TextureAccessError> {
+        let format = self.texture_descriptor.format;
+        if self.texture_descriptor.dimension != TextureDimension::D2 {
+            return Err(TextureAccessError::WrongDimension(TextureDimension::D2));
+        }
+        let pixel_size = pixel_size(format)
+            .filter(|_| supports_pixel_access(format))
+            .ok_or(TextureAccessError::UnsupportedTextureFormat(format))?;
+
+        let size = self.size();
+        let mip_level_count = size.max_element().ilog2() + 1;
+        let layers = self.texture_descriptor.size.depth_or_array_layers;
+        let mut data = Vec::with_capacity(self.data.len() * 4 / 3 + pixel_size);
+
+        for layer in 0..layers {
+            // The largest mip level is kept as is
+            let offset = self.mip_level_offset(layer, 0);
+            let level_bytes =
+                &self.data[offset..offset + size.element_product() as usize * pixel_size];
+            data.extend_from_slice(level_bytes);
+
+            let mut level: Vec<LinearRgba> = level_bytes
+                .chunks_exact(pixel_size)
+                .map(|pixel| premultiply(decode_pixel(format, pixel).unwrap().into()))
+                .collect();
+            let mut level_size = size;
+            for _ in 1..mip_level_count {
+                let next_level_size = (level_size / 2).max(UVec2::ONE);
+                level = match filter {
+                    MipmapFilter::Box => downsample_box(&level, level_size, next_level_size),
+                    MipmapFilter::Kaiser => downsample_kaiser(&level, level_size, next_level_size),
+                };
+                level_size = next_level_size;
+
+                let mut pixel = vec![0; pixel_size];
+                for color in &level {
+                    encode_pixel(format, unpremultiply(*color).into(), &mut pixel);
+                    data.extend_from_slice(&pixel);
+                }
+            }
+        }
+
+        self.data = data;
+        self.texture_descriptor.mip_level_count = mip_level_count;
+        Ok(())
+    }
+}
+
+fn premultiply(color: LinearRgba) -> LinearRgba {
+    LinearRgba::new(
+        color.red * color.alpha,
+        color.green * color.alpha,
+        color.blue * color.alpha,
+        color.alpha,
+    )
+}
+
+fn unpremultiply(color: LinearRgba) -> LinearRgba {
+    if color.alpha <= 0.0 {
+        return LinearRgba::NONE;
+    }
+    LinearRgba::new(
+        color.red / color.alpha,
+        color.green / color.alpha,
+        color.blue / color.alpha,
+        color.alpha,
+    )
+}
+
+fn downsample_box(level: &[LinearRgba], size: UVec2, next_size: UVec2) -> Vec<LinearRgba> {
+    let pixel = |x: u32, y: u32| level[(y.min(size.y - 1) * size.x + x.min(size.x - 1)) as usize];
+    (0..next_size.y)
+        .flat_map(|y| (0..next_size.x).map(move |x| (x * 2, y * 2)))
+        .map(|(x, y)| {
+            (pixel(x, y) + pixel(x + 1, y) + pixel(x, y + 1) + pixel(x + 1, y + 1)) * 0.25
+        })
+        .collect()
+}
+
+fn downsample_kaiser(level: &[LinearRgba], size: UVec2, next_size: UVec2) -> Vec<LinearRgba> {
+    // The filter is separable, so filter the rows then the columns
+    let horizontal_weights = kaiser_weights(size.x, next_size.x);
+    let vertical_weights = kaiser_weights(size.y, next_size.y);
+
+    let mut rows = Vec::with_capacity((next_size.x * size.y) as usize);
+    for y in 0..size.y {
+        let row = &level[(y * size.x) as usize..((y + 1) * size.x) as usize];
+        rows.extend(horizontal_weights.iter().map(|weights| {
+            weights
+                .iter()
+                .fold(LinearRgba::NONE, |sum, &(x, weight)| sum + row[x] * weight)
+        }));
+    }
+
+    let mut next_level = Vec::with_capacity(next_size.element_product() as usize);
+    for weights in &vertical_weights {
+        next_level.extend((0..next_size.x as usize).map(|x| {
+            weights.iter().fold(LinearRgba::NONE, |sum, &(y, weight)| {
+                sum + rows[y * next_size.x as usize + x] * weight
+            })
+        }));
+    }
+    next_level
+}
+
+/// Returns the source pixels and normalized weights of each downsampled pixel, clamping at the
+/// edges.
+fn kaiser_weights(size: u32, next_size: u32) -> Vec<Vec<(usize, f32)>> {
+    let scale = size as f32 / next_size as f32;
+    let radius = KAISER_WIDTH * scale;
+    (0..next_size)
+        .map(|i| {
+            let center = (i as f32 + 0.5) * scale;
+            let mut weights: Vec<(usize, f32)> = Vec::new();
+            for source in (center - radius).floor() as i64..(center + radius).ceil() as i64 {
+                // Distance in downsampled pixels
+                let distance = (source as f32 + 0.5 - center) / scale;
+                let weight = sinc(distance) * kaiser_window(distance / KAISER_WIDTH);
+                let source = source.clamp(0, size as i64 - 1) as usize;
+                match weights.iter_mut().find(|(index, _)| *index == source) {
+                    Some((_, total)) => *total += weight,
+                    None => weights.push((source, weight)),
+                }
+            }
+            let total: f32 = weights.iter().map(|(_, weight)| weight).sum();
+            for (_, weight) in &mut weights {
+                *weight /= total;
+            }
+            weights
+        })
+        .collect()
+}
+
+fn sinc(x: f32) -> f32 {
+    if x.abs() < 1e-6 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+fn kaiser_window(x: f32) -> f32 {
+    if x.abs() >= 1.0 {
+        return 0.0;
+    }
+    bessel_i0(KAISER_ALPHA * (1.0 - x * x).sqrt()) / bessel_i0(KAISER_ALPHA)
+}
+
+/// The zeroth order modified Bessel function of the first kind.
+fn bessel_i0(x: f32) -> f32 {
+    let mut sum = 1.0;
+    let mut term = 1.0;
+    let half_x_squared = x * x / 4.0;
+    for k in 1..32 {
+        term *= half_x_squared / (k * k) as f32;
+        sum += term;
+        if term < sum * 1e-7 {
+            break;
+        }
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render_asset::RenderAssetUsages;
+    use bevy_color::{Color, ColorToComponents};
+    use wgpu::{Extent3d, TextureFormat};
+
+    #[test]
+    fn generate_mipmaps() {
+        for filter in [MipmapFilter::Box, MipmapFilter::Kaiser] {
+            let mut image = Image::new_fill(
+                Extent3d {
+                    width: 8,
+                    height: 4,
+                    depth_or_array_layers: 1,
+                },
+                TextureDimension::D2,
+                &[0, 0, 0, 255],
+                TextureFormat::Rgba8Unorm,
+                RenderAssetUsages::MAIN_WORLD,
+            );
+            // A checkerboard averages to gray
+            for y in 0..4 {
+                for x in 0..8 {
+                    if (x + y) % 2 == 0 {
+                        image.set_color_at(x, y, Color::WHITE).unwrap();
+                    }
+                }
+            }
+
+            image.generate_mipmaps(filter).unwrap();
+            assert_eq!(image.texture_descriptor.mip_level_count, 4);
+            assert_eq!(image.data.len(), (32 + 8 + 2 + 1) * 4);
+            let color = LinearRgba::from(image.get_color_at_mip(0, 0, 0, 3).unwrap());
+            for component in color.to_f32_array_no_alpha() {
+                assert!((component - 0.5).abs() < 0.01, "{filter:?}: {color:?}");
+            }
+        }
+    }
+}
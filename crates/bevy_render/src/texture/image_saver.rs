@@ -0,0 +1,602 @@
+use crate::texture::{
+    Image, ImageFormat, ImageFormatSetting, ImageLoader, ImageLoaderSettings, MipmapFilter,
+};
+use bevy_asset::saver::{AssetSaver, SavedAsset};
+use futures_lite::AsyncWriteExt;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use wgpu::TextureFormat;
+
+use super::image_texture_conversion::IntoDynamicImageError;
+
+/// Saves an [`Image`] as an uncompressed PNG or KTX2 file that can be read back by the [`ImageLoader`].
+///
+/// PNG output only contains the first mip level of the first layer, while KTX2 output keeps the
+/// full mip chain, every array layer and cube faces. When a PNG is saved from an image with
+/// several mip levels, the returned settings regenerate them when the PNG is loaded.
+#[derive(Default)]
+pub struct ImageSaver;
+
+/// The file format written by the [`ImageSaver`].
+#[derive(Serialize, Deserialize, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImageSaverFormat {
+    /// Requires the `png` feature.
+    #[default]
+    Png,
+    /// Requires the `ktx2` feature.
+    Ktx2,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+pub struct ImageSaverSettings {
+    pub format: ImageSaverFormat,
+}
+
+/// Possible errors that can be produced by [`ImageSaver`]
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum ImageSaverError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("the `{0:?}` image format is not enabled, enable the matching cargo feature")]
+    FormatNotEnabled(ImageSaverFormat),
+    #[error("the `{0:?}` texture format can't be saved as `{1:?}`")]
+    UnsupportedTextureFormat(TextureFormat, ImageSaverFormat),
+    #[error(transparent)]
+    IntoDynamicImage(#[from] IntoDynamicImageError),
+    #[error(transparent)]
+    Encode(#[from] image::ImageError),
+}
+
+impl AssetSaver for ImageSaver {
+    type Asset = Image;
+    type Settings = ImageSaverSettings;
+    type OutputLoader = ImageLoader;
+    type Error = ImageSaverError;
+
+    async fn save<'a>(
+        &'a self,
+        writer: &'a mut bevy_asset::io::Writer,
+        image: SavedAsset<'a, Self::Asset>,
+        settings: &'a Self::Settings,
+    ) -> Result<ImageLoaderSettings, Self::Error> {
+        let (bytes, format) = match settings.format {
+            ImageSaverFormat::Png => (encode_png(&image)?, ImageFormat::Png),
+            ImageSaverFormat::Ktx2 => (encode_ktx2(&image)?, ImageFormat::Ktx2),
+        };
+        writer.write_all(&bytes).await?;
+        Ok(ImageLoaderSettings {
+            format: ImageFormatSetting::Format(format),
+            is_srgb: image.texture_descriptor.format.is_srgb(),
+            sampler: image.sampler.clone(),
+            asset_usage: image.asset_usage,
+            generate_mipmaps: (matches!(format, ImageFormat::Png)
+                && image.texture_descriptor.mip_level_count > 1)
+                .then(MipmapFilter::default),
+        })
+    }
+}
+
+#[cfg(feature = "png")]
+fn encode_png(image: &Image) -> Result<Vec<u8>, ImageSaverError> {
+    use super::TextureFormatPixelInfo;
+
+    let format = image.texture_descriptor.format;
+    if image.is_compressed() {
+        return Err(ImageSaverError::UnsupportedTextureFormat(
+            format,
+            ImageSaverFormat::Png,
+        ));
+    }
+    // Only keep the first mip level of the first layer, the `image` crate expects the buffer to
+    // exactly match the image dimensions.
+    let level_bytes = image.width() as usize * image.height() as usize * format.pixel_size();
+    let mut first_level = image.clone();
+    first_level.data.truncate(level_bytes);
+
+    let mut bytes = Vec::new();
+    first_level.try_into_dynamic()?.write_to(
+        &mut std::io::Cursor::new(&mut bytes),
+        image::ImageFormat::Png,
+    )?;
+    Ok(bytes)
+}
+
+#[cfg(not(feature = "png"))]
+fn encode_png(_image: &Image) -> Result<Vec<u8>, ImageSaverError> {
+    Err(ImageSaverError::FormatNotEnabled(ImageSaverFormat::Png))
+}
+
+#[cfg(feature = "ktx2")]
+const KTX2_IDENTIFIER: [u8; 12] = [
+    0xAB, 0x4B, 0x54, 0x58, 0x20, 0x32, 0x30, 0xBB, 0x0D, 0x0A, 0x1A, 0x0A,
+];
+
+#[cfg(feature = "ktx2")]
+fn encode_ktx2(image: &Image) -> Result<Vec<u8>, ImageSaverError> {
+    use wgpu::{TextureDimension, TextureViewDimension};
+
+    let descriptor = &image.texture_descriptor;
+    let format = descriptor.format;
+    let (vk_format, type_size) = texture_format_to_ktx2_format(format).ok_or(
+        ImageSaverError::UnsupportedTextureFormat(format, ImageSaverFormat::Ktx2),
+    )?;
+
+    let view_dimension = image
+        .texture_view_descriptor
+        .as_ref()
+        .and_then(|descriptor| descriptor.dimension);
+    let (width, height) = (descriptor.size.width, descriptor.size.height);
+    let (depth, layer_count, face_count) = match (descriptor.dimension, view_dimension) {
+        (TextureDimension::D3, _) => (descriptor.size.depth_or_array_layers, 1, 1),
+        (_, Some(TextureViewDimension::Cube | TextureViewDimension::CubeArray)) => {
+            (1, descriptor.size.depth_or_array_layers / 6, 6)
+        }
+        _ => (1, descriptor.size.depth_or_array_layers, 1),
+    };
+    let level_count = descriptor.mip_level_count.max(1);
+
+    // Bevy stores every mip level of a layer (or face) next to each other, while KTX2 stores every
+    // layer (and face) of a mip level next to each other.
+    let (block_width, block_height) = format.block_dimensions();
+    let block_bytes = format.block_copy_size(None).unwrap() as usize;
+    let level_sizes = (0..level_count)
+        .map(|level| {
+            let blocks_x = ((width >> level).max(1)).div_ceil(block_width) as usize;
+            let blocks_y = ((height >> level).max(1)).div_ceil(block_height) as usize;
+            blocks_x * blocks_y * (depth >> level).max(1) as usize * block_bytes
+        })
+        .collect::<Vec<_>>();
+    let layer_bytes = level_sizes.iter().sum::<usize>();
+    let images = (layer_count * face_count) as usize;
+    if image.data.len() < layer_bytes * images {
+        return Err(ImageSaverError::UnsupportedTextureFormat(
+            format,
+            ImageSaverFormat::Ktx2,
+        ));
+    }
+    let mut levels = vec![Vec::new(); level_count as usize];
+    for image_data in image.data.chunks_exact(layer_bytes).take(images) {
+        let mut offset = 0;
+        for (level, size) in level_sizes.iter().enumerate() {
+            levels[level].extend_from_slice(&image_data[offset..offset + size]);
+            offset += size;
+        }
+    }
+
+    let dfd = ktx2_data_format_descriptor(format, type_size);
+    let level_index_start = KTX2_IDENTIFIER.len() + 9 * 4 + 4 * 4 + 2 * 8;
+    let dfd_offset = level_index_start + level_count as usize * 3 * 8;
+    let dfd_length = dfd.len();
+    let alignment = block_bytes.max(4);
+
+    // Levels are stored from the smallest to the largest, as recommended by the specification.
+    let mut level_offsets = vec![0; level_count as usize];
+    let mut offset = dfd_offset + dfd_length;
+    for level in (0..level_count as usize).rev() {
+        offset = offset.next_multiple_of(alignment);
+        level_offsets[level] = offset;
+        offset += levels[level].len();
+    }
+
+    let mut bytes = Vec::with_capacity(offset);
+    bytes.extend_from_slice(&KTX2_IDENTIFIER);
+    for value in [
+        vk_format,
+        type_size,
+        width,
+        height,
+        if depth > 1 { depth } else { 0 },
+        if layer_count > 1 { layer_count } else { 0 },
+        face_count,
+        level_count,
+        // No supercompression
+        0,
+        dfd_offset as u32,
+        dfd_length as u32,
+        // No key/value data
+        0,
+        0,
+    ] {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    // No supercompression global data
+    bytes.extend_from_slice(&0u64.to_le_bytes());
+    bytes.extend_from_slice(&0u64.to_le_bytes());
+    for (level_offset, level) in level_offsets.iter().zip(&levels) {
+        bytes.extend_from_slice(&(*level_offset as u64).to_le_bytes());
+        bytes.extend_from_slice(&(level.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&(level.len() as u64).to_le_bytes());
+    }
+    bytes.extend_from_slice(&dfd);
+    for level in (0..level_count as usize).rev() {
+        bytes.resize(level_offsets[level], 0);
+        bytes.extend_from_slice(&levels[level]);
+    }
+    Ok(bytes)
+}
+
+#[cfg(not(feature = "ktx2"))]
+fn encode_ktx2(_image: &Image) -> Result<Vec<u8>, ImageSaverError> {
+    Err(ImageSaverError::FormatNotEnabled(ImageSaverFormat::Ktx2))
+}
+
+/// Returns the `vkFormat` and the `typeSize` of the KTX2 format matching `format`.
+#[cfg(feature = "ktx2")]
+fn texture_format_to_ktx2_format(format: TextureFormat) -> Option<(u32, u32)> {
+    use ktx2::Format;
+
+    let (ktx2_format, type_size) = match format {
+        TextureFormat::R8Unorm => (Format::R8_UNORM, 1),
+        TextureFormat::R8Snorm => (Format::R8_SNORM, 1),
+        TextureFormat::R8Uint => (Format::R8_UINT, 1),
+        TextureFormat::R8Sint => (Format::R8_SINT, 1),
+        TextureFormat::Rg8Unorm => (Format::R8G8_UNORM, 1),
+        TextureFormat::Rg8Snorm => (Format::R8G8_SNORM, 1),
+        TextureFormat::Rg8Uint => (Format::R8G8_UINT, 1),
+        TextureFormat::Rg8Sint => (Format::R8G8_SINT, 1),
+        TextureFormat::Rgba8Unorm => (Format::R8G8B8A8_UNORM, 1),
+        TextureFormat::Rgba8UnormSrgb => (Format::R8G8B8A8_SRGB, 1),
+        TextureFormat::Rgba8Snorm => (Format::R8G8B8A8_SNORM, 1),
+        TextureFormat::Rgba8Uint => (Format::R8G8B8A8_UINT, 1),
+        TextureFormat::Rgba8Sint => (Format::R8G8B8A8_SINT, 1),
+        TextureFormat::Bgra8Unorm => (Format::B8G8R8A8_UNORM, 1),
+        TextureFormat::Bgra8UnormSrgb => (Format::B8G8R8A8_SRGB, 1),
+        TextureFormat::R16Unorm => (Format::R16_UNORM, 2),
+        TextureFormat::R16Snorm => (Format::R16_SNORM, 2),
+        TextureFormat::R16Uint => (Format::R16_UINT, 2),
+        TextureFormat::R16Sint => (Format::R16_SINT, 2),
+        TextureFormat::R16Float => (Format::R16_SFLOAT, 2),
+        TextureFormat::Rg16Unorm => (Format::R16G16_UNORM, 2),
+        TextureFormat::Rg16Snorm => (Format::R16G16_SNORM, 2),
+        TextureFormat::Rg16Uint => (Format::R16G16_UINT, 2),
+        TextureFormat::Rg16Sint => (Format::R16G16_SINT, 2),
+        TextureFormat::Rg16Float => (Format::R16G16_SFLOAT, 2),
+        TextureFormat::Rgba16Unorm => (Format::R16G16B16A16_UNORM, 2),
+        TextureFormat::Rgba16Snorm => (Format::R16G16B16A16_SNORM, 2),
+        TextureFormat::Rgba16Uint => (Format::R16G16B16A16_UINT, 2),
+        TextureFormat::Rgba16Sint => (Format::R16G16B16A16_SINT, 2),
+        TextureFormat::Rgba16Float => (Format::R16G16B16A16_SFLOAT, 2),
+        TextureFormat::R32Uint => (Format::R32_UINT, 4),
+        TextureFormat::R32Sint => (Format::R32_SINT, 4),
+        TextureFormat::R32Float => (Format::R32_SFLOAT, 4),
+        TextureFormat::Rg32Uint => (Format::R32G32_UINT, 4),
+        TextureFormat::Rg32Sint => (Format::R32G32_SINT, 4),
+        TextureFormat::Rg32Float => (Format::R32G32_SFLOAT, 4),
+        TextureFormat::Rgba32Uint => (Format::R32G32B32A32_UINT, 4),
+        TextureFormat::Rgba32Sint => (Format::R32G32B32A32_SINT, 4),
+        TextureFormat::Rgba32Float => (Format::R32G32B32A32_SFLOAT, 4),
+        TextureFormat::Rgb9e5Ufloat => (Format::E5B9G9R9_UFLOAT_PACK32, 4),
+        TextureFormat::Rg11b10Float => (Format::B10G11R11_UFLOAT_PACK32, 4),
+        TextureFormat::Bc1RgbaUnorm => (Format::BC1_RGBA_UNORM_BLOCK, 1),
+        TextureFormat::Bc1RgbaUnormSrgb => (Format::BC1_RGBA_SRGB_BLOCK, 1),
+        TextureFormat::Bc2RgbaUnorm => (Format::BC2_UNORM_BLOCK, 1),
+        TextureFormat::Bc2RgbaUnormSrgb => (Format::BC2_SRGB_BLOCK, 1),
+        TextureFormat::Bc3RgbaUnorm => (Format::BC3_UNORM_BLOCK, 1),
+        TextureFormat::Bc3RgbaUnormSrgb => (Format::BC3_SRGB_BLOCK, 1),
+        TextureFormat::Bc4RUnorm => (Format::BC4_UNORM_BLOCK, 1),
+        TextureFormat::Bc4RSnorm => (Format::BC4_SNORM_BLOCK, 1),
+        TextureFormat::Bc5RgUnorm => (Format::BC5_UNORM_BLOCK, 1),
+        TextureFormat::Bc5RgSnorm => (Format::BC5_SNORM_BLOCK, 1),
+        TextureFormat::Bc6hRgbUfloat => (Format::BC6H_UFLOAT_BLOCK, 1),
+        TextureFormat::Bc6hRgbFloat => (Format::BC6H_SFLOAT_BLOCK, 1),
+        TextureFormat::Bc7RgbaUnorm => (Format::BC7_UNORM_BLOCK, 1),
+        TextureFormat::Bc7RgbaUnormSrgb => (Format::BC7_SRGB_BLOCK, 1),
+        _ => return None,
+    };
+    Some((ktx2_format.0.get(), type_size))
+}
+
+#[cfg(feature = "ktx2")]
+const KHR_DF_MODEL_RGBSDA: u8 = 1;
+#[cfg(feature = "ktx2")]
+const KHR_DF_MODEL_BC1A: u8 = 128;
+#[cfg(feature = "ktx2")]
+const KHR_DF_MODEL_BC2: u8 = 129;
+#[cfg(feature = "ktx2")]
+const KHR_DF_MODEL_BC3: u8 = 130;
+#[cfg(feature = "ktx2")]
+const KHR_DF_MODEL_BC4: u8 = 131;
+#[cfg(feature = "ktx2")]
+const KHR_DF_MODEL_BC5: u8 = 132;
+#[cfg(feature = "ktx2")]
+const KHR_DF_MODEL_BC6H: u8 = 133;
+#[cfg(feature = "ktx2")]
+const KHR_DF_MODEL_BC7: u8 = 134;
+
+#[cfg(feature = "ktx2")]
+const KHR_DF_CHANNEL_ALPHA: u8 = 15;
+
+#[cfg(feature = "ktx2")]
+const KHR_DF_SAMPLE_LINEAR: u8 = 1 << 0;
+#[cfg(feature = "ktx2")]
+const KHR_DF_SAMPLE_EXPONENT: u8 = 1 << 1;
+#[cfg(feature = "ktx2")]
+const KHR_DF_SAMPLE_SIGNED: u8 = 1 << 2;
+#[cfg(feature = "ktx2")]
+const KHR_DF_SAMPLE_FLOAT: u8 = 1 << 3;
+
+/// A sample of a KTX2 basic data format descriptor, describing a channel of a texel block.
+#[cfg(feature = "ktx2")]
+struct Ktx2Sample {
+    channel: u8,
+    qualifiers: u8,
+    bit_offset: u32,
+    bit_length: u32,
+    lower: u32,
+    upper: u32,
+}
+
+/// Returns the data format descriptor of a KTX2 file holding `format`, as a single basic
+/// descriptor block preceded by the total size of the descriptor.
+///
+/// The samples follow the ones the Khronos `dfdutils` library derives from the `vkFormat`, so
+/// that strict readers accept the file.
+#[cfg(feature = "ktx2")]
+fn ktx2_data_format_descriptor(format: TextureFormat, type_size: u32) -> Vec<u8> {
+    const ONE: u32 = 0x3F80_0000;
+    const MINUS_ONE: u32 = 0xBF80_0000;
+
+    let (signed, float, integer) = match format {
+        TextureFormat::R8Snorm
+        | TextureFormat::Rg8Snorm
+        | TextureFormat::Rgba8Snorm
+        | TextureFormat::R16Snorm
+        | TextureFormat::Rg16Snorm
+        | TextureFormat::Rgba16Snorm
+        | TextureFormat::Bc4RSnorm
+        | TextureFormat::Bc5RgSnorm => (true, false, false),
+        TextureFormat::R16Float
+        | TextureFormat::Rg16Float
+        | TextureFormat::Rgba16Float
+        | TextureFormat::R32Float
+        | TextureFormat::Rg32Float
+        | TextureFormat::Rgba32Float
+        | TextureFormat::Bc6hRgbFloat => (true, true, false),
+        TextureFormat::Rgb9e5Ufloat
+        | TextureFormat::Rg11b10Float
+        | TextureFormat::Bc6hRgbUfloat => (false, true, false),
+        _ => match format.sample_type(None, None) {
+            Some(wgpu::TextureSampleType::Sint) => (true, false, true),
+            Some(wgpu::TextureSampleType::Uint) => (false, false, true),
+            _ => (false, false, false),
+        },
+    };
+    let qualifiers =
+        if signed { KHR_DF_SAMPLE_SIGNED } else { 0 } | if float { KHR_DF_SAMPLE_FLOAT } else { 0 };
+    let sample = |channel: u8, bit_offset: u32, bit_length: u32| {
+        let (lower, upper) = match (float, integer, signed) {
+            (true, _, true) => (MINUS_ONE, ONE),
+            (true, _, false) => (0, ONE),
+            (false, true, true) => (u32::MAX, 1),
+            (false, true, false) => (0, 1),
+            (false, false, true) => {
+                let upper = (1u32 << (bit_length.min(32) - 1)) - 1;
+                (upper.wrapping_neg(), upper)
+            }
+            (false, false, false) => (0, u32::MAX >> (32 - bit_length.min(32))),
+        };
+        Ktx2Sample {
+            channel,
+            qualifiers,
+            bit_offset,
+            bit_length,
+            lower,
+            upper,
+        }
+    };
+
+    let block_bytes = format.block_copy_size(None).unwrap();
+    let (color_model, mut samples) = match format {
+        TextureFormat::Rg11b10Float => (
+            KHR_DF_MODEL_RGBSDA,
+            vec![sample(0, 0, 11), sample(1, 11, 11), sample(2, 22, 10)],
+        ),
+        TextureFormat::Rgb9e5Ufloat => {
+            let mut samples = Vec::new();
+            for channel in 0..3 {
+                samples.push(Ktx2Sample {
+                    qualifiers: 0,
+                    lower: 0,
+                    upper: 8448,
+                    ..sample(channel, channel as u32 * 9, 9)
+                });
+            }
+            for channel in 0..3 {
+                samples.push(Ktx2Sample {
+                    qualifiers: KHR_DF_SAMPLE_EXPONENT,
+                    lower: 15,
+                    upper: 31,
+                    ..sample(channel, 27, 5)
+                });
+            }
+            (KHR_DF_MODEL_RGBSDA, samples)
+        }
+        TextureFormat::Bc1RgbaUnorm | TextureFormat::Bc1RgbaUnormSrgb => {
+            (KHR_DF_MODEL_BC1A, vec![sample(1, 0, 64)])
+        }
+        TextureFormat::Bc2RgbaUnorm | TextureFormat::Bc2RgbaUnormSrgb => (
+            KHR_DF_MODEL_BC2,
+            vec![sample(KHR_DF_CHANNEL_ALPHA, 0, 64), sample(0, 64, 64)],
+        ),
+        TextureFormat::Bc3RgbaUnorm | TextureFormat::Bc3RgbaUnormSrgb => (
+            KHR_DF_MODEL_BC3,
+            vec![sample(KHR_DF_CHANNEL_ALPHA, 0, 64), sample(0, 64, 64)],
+        ),
+        TextureFormat::Bc4RUnorm | TextureFormat::Bc4RSnorm => {
+            (KHR_DF_MODEL_BC4, vec![sample(0, 0, 64)])
+        }
+        TextureFormat::Bc5RgUnorm | TextureFormat::Bc5RgSnorm => {
+            (KHR_DF_MODEL_BC5, vec![sample(0, 0, 64), sample(1, 64, 64)])
+        }
+        TextureFormat::Bc6hRgbUfloat | TextureFormat::Bc6hRgbFloat => {
+            (KHR_DF_MODEL_BC6H, vec![sample(0, 0, 128)])
+        }
+        TextureFormat::Bc7RgbaUnorm | TextureFormat::Bc7RgbaUnormSrgb => {
+            (KHR_DF_MODEL_BC7, vec![sample(0, 0, 128)])
+        }
+        _ => {
+            let channels: &[u8] = match format.components() {
+                1 => &[0],
+                2 => &[0, 1],
+                _ if matches!(
+                    format,
+                    TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb
+                ) =>
+                {
+                    &[2, 1, 0, KHR_DF_CHANNEL_ALPHA]
+                }
+                _ => &[0, 1, 2, KHR_DF_CHANNEL_ALPHA],
+            };
+            let bits = type_size * 8;
+            let samples = channels
+                .iter()
+                .enumerate()
+                .map(|(index, &channel)| sample(channel, index as u32 * bits, bits))
+                .collect();
+            (KHR_DF_MODEL_RGBSDA, samples)
+        }
+    };
+    let srgb = format.is_srgb();
+    if srgb {
+        // The alpha of sRGB formats is still stored linearly
+        for sample in &mut samples {
+            if sample.channel == KHR_DF_CHANNEL_ALPHA && color_model == KHR_DF_MODEL_RGBSDA {
+                sample.qualifiers |= KHR_DF_SAMPLE_LINEAR;
+            }
+        }
+    }
+
+    let (block_width, block_height) = format.block_dimensions();
+    let block_size = 24 + 16 * samples.len() as u32;
+    let transfer_function = if srgb { 2 } else { 1 };
+    let mut words = vec![
+        // Khronos vendor and basic descriptor type
+        0,
+        // Version 1.3 of the specification
+        2 | (block_size << 16),
+        // BT.709 primaries and straight alpha
+        color_model as u32 | (1 << 8) | (transfer_function << 16),
+        (block_width - 1) | ((block_height - 1) << 8),
+        block_bytes,
+        0,
+    ];
+    for sample in &samples {
+        words.push(
+            sample.bit_offset
+                | ((sample.bit_length - 1) << 16)
+                | ((sample.channel as u32) << 24)
+                | ((sample.qualifiers as u32) << 28),
+        );
+        words.push(0);
+        words.push(sample.lower);
+        words.push(sample.upper);
+    }
+
+    let mut bytes = Vec::with_capacity(4 + words.len() * 4);
+    bytes.extend_from_slice(&(4 + block_size).to_le_bytes());
+    for word in words {
+        bytes.extend_from_slice(&word.to_le_bytes());
+    }
+    bytes
+}
+
+#[cfg(all(test, any(feature = "png", feature = "ktx2")))]
+mod tests {
+    use super::*;
+    use crate::render_asset::RenderAssetUsages;
+    use wgpu::{Extent3d, TextureDimension};
+
+    #[cfg(feature = "ktx2")]
+    #[test]
+    fn ktx2_round_trip_keeps_mips() {
+        use crate::texture::{ktx2_buffer_to_image, CompressedImageFormats};
+
+        let mut image = Image::new_fill(
+            Extent3d {
+                width: 4,
+                height: 4,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[255, 0, 0, 255],
+            TextureFormat::Rgba8Unorm,
+            RenderAssetUsages::default(),
+        );
+        image.texture_descriptor.mip_level_count = 3;
+        // 4x4, 2x2 and 1x1 mip levels
+        image.data = (0..(16 + 4 + 1) * 4).map(|byte| byte as u8).collect();
+
+        let bytes = encode_ktx2(&image).unwrap();
+        let loaded = ktx2_buffer_to_image(&bytes, CompressedImageFormats::NONE, false).unwrap();
+        assert_eq!(loaded.texture_descriptor.format, TextureFormat::Rgba8Unorm);
+        assert_eq!(loaded.texture_descriptor.mip_level_count, 3);
+        assert_eq!(loaded.size(), image.size());
+        assert_eq!(loaded.data, image.data);
+    }
+
+    #[cfg(feature = "ktx2")]
+    #[test]
+    fn ktx2_has_a_basic_data_format_descriptor() {
+        use ktx2::{
+            BasicDataFormatDescriptor, ChannelTypeQualifiers, ColorModel,
+            DataFormatDescriptorHeader, TransferFunction,
+        };
+
+        let image = Image::new_fill(
+            Extent3d {
+                width: 2,
+                height: 2,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0; 4],
+            TextureFormat::Rg16Float,
+            RenderAssetUsages::default(),
+        );
+        let bytes = encode_ktx2(&image).unwrap();
+        let reader = ktx2::Reader::new(&bytes).unwrap();
+        let descriptor = reader.data_format_descriptors().next().unwrap();
+        assert_eq!(descriptor.header, DataFormatDescriptorHeader::BASIC);
+
+        let basic = BasicDataFormatDescriptor::parse(descriptor.data).unwrap();
+        assert_eq!(basic.color_model, Some(ColorModel::RGBSDA));
+        assert_eq!(basic.transfer_function, Some(TransferFunction::Linear));
+        assert_eq!(basic.bytes_planes[0], 4);
+        let samples = basic.sample_information().collect::<Vec<_>>();
+        assert_eq!(samples.len(), 2);
+        for (channel, sample) in samples.iter().enumerate() {
+            assert_eq!(sample.channel_type, channel as u32);
+            assert_eq!(sample.bit_offset, channel as u32 * 16);
+            assert_eq!(sample.bit_length, 16);
+            assert_eq!(
+                sample.channel_type_qualifiers,
+                ChannelTypeQualifiers::FLOAT | ChannelTypeQualifiers::SIGNED
+            );
+            assert_eq!(sample.upper, 1.0f32.to_bits());
+        }
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn png_only_keeps_the_first_mip() {
+        let mut image = Image::new_fill(
+            Extent3d {
+                width: 2,
+                height: 2,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0, 255, 0, 255],
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        );
+        image.texture_descriptor.mip_level_count = 2;
+        image.data.extend_from_slice(&[0, 0, 255, 255]);
+
+        let bytes = encode_png(&image).unwrap();
+        let loaded = image::load_from_memory(&bytes).unwrap().into_rgba8();
+        assert_eq!(loaded.dimensions(), (2, 2));
+        assert_eq!(loaded.into_raw(), image.data[..16]);
+    }
+}
@@ -167,8 +167,10 @@ impl Image {
     /// error if the format is unsupported. Supported formats are:
     /// - `TextureFormat::R8Unorm`
     /// - `TextureFormat::Rg8Unorm`
+    /// - `TextureFormat::Rgba8Unorm`
     /// - `TextureFormat::Rgba8UnormSrgb`
     /// - `TextureFormat::Bgra8UnormSrgb`
+    /// - `TextureFormat::Rgba32Float`
     ///
     /// To convert [`Image`] to a different format see: [`Image::convert`].
     pub fn try_into_dynamic(self) -> Result<DynamicImage, IntoDynamicImageError> {
@@ -179,10 +181,19 @@ impl Image {
                 ImageBuffer::from_raw(self.width(), self.height(), self.data)
                     .map(DynamicImage::ImageLumaA8)
             }
-            TextureFormat::Rgba8UnormSrgb => {
+            TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => {
                 ImageBuffer::from_raw(self.width(), self.height(), self.data)
                     .map(DynamicImage::ImageRgba8)
             }
+            TextureFormat::Rgba32Float => ImageBuffer::from_raw(
+                self.width(),
+                self.height(),
+                self.data
+                    .chunks_exact(4)
+                    .map(|bytes| f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+                    .collect(),
+            )
+            .map(DynamicImage::ImageRgba32F),
             // This format is commonly used as the format for the swapchain texture
             // This conversion is added here to support screenshots
             TextureFormat::Bgra8UnormSrgb | TextureFormat::Bgra8Unorm => {
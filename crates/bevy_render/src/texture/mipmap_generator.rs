@@ -0,0 +1,278 @@
+use crate::{
+    camera::{ExtractedCamera, NormalizedRenderTarget},
+    render_asset::RenderAssets,
+    render_resource::{
+        binding_types::{texture_2d, texture_storage_2d},
+        BindGroupEntries, BindGroupLayout, BindGroupLayoutEntries, CachedComputePipelineId,
+        CommandEncoder, CommandEncoderDescriptor, ComputePassDescriptor, ComputePipelineDescriptor,
+        Extent3d, ImageCopyTexture, ImageDataLayout, Origin3d, PipelineCache, Shader, ShaderDefVal,
+        ShaderStages, StorageTextureAccess, Texture, TextureAspect, TextureDimension,
+        TextureFormat, TextureSampleType, TextureUsages, TextureViewDescriptor,
+        TextureViewDimension,
+    },
+    renderer::{RenderDevice, RenderQueue},
+    texture::{GpuImage, Image, TextureFormatPixelInfo},
+};
+use bevy_asset::Handle;
+use bevy_ecs::{
+    system::{Query, Res, Resource},
+    world::{FromWorld, World},
+};
+use bevy_utils::{HashMap, HashSet};
+
+pub const MIPMAP_GENERATOR_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(16340125431783250562);
+
+/// Generates the mip levels of [`Image`]s with [`Image::gpu_mipmaps`] set on the GPU, by
+/// averaging each 2×2 block of pixels of the previous mip level in a compute shader.
+#[derive(Resource)]
+pub struct MipmapGenerator {
+    /// Keyed by the storage format mip levels are written with.
+    layouts: HashMap<TextureFormat, BindGroupLayout>,
+    /// Keyed by the format of the image.
+    pipelines: HashMap<TextureFormat, CachedComputePipelineId>,
+}
+
+impl MipmapGenerator {
+    /// The image formats mip levels can be generated for on the GPU.
+    pub const SUPPORTED_FORMATS: [TextureFormat; 6] = [
+        TextureFormat::Rgba8Unorm,
+        TextureFormat::Rgba8UnormSrgb,
+        TextureFormat::Rgba8Snorm,
+        TextureFormat::Rgba16Float,
+        TextureFormat::Rgba32Float,
+        TextureFormat::R32Float,
+    ];
+
+    /// Returns the format of the textures created for images of the given format, which mip
+    /// levels are written to as storage textures, or `None` if the format isn't supported.
+    ///
+    /// sRGB formats can't be used as storage textures, so the texture is created with the linear
+    /// equivalent and viewed with the sRGB format when sampled or rendered to.
+    pub fn storage_format(format: TextureFormat) -> Option<TextureFormat> {
+        match format {
+            TextureFormat::Rgba8UnormSrgb => Some(TextureFormat::Rgba8Unorm),
+            format if Self::SUPPORTED_FORMATS.contains(&format) => Some(format),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` once the pipeline generating mip levels for images of the given format has
+    /// been compiled.
+    pub fn is_ready(&self, format: TextureFormat, pipeline_cache: &PipelineCache) -> bool {
+        self.pipelines
+            .get(&format)
+            .is_some_and(|id| pipeline_cache.get_compute_pipeline(*id).is_some())
+    }
+
+    /// Records the compute passes regenerating all the mip levels of each layer of `texture`
+    /// from its first mip level, where `format` is the format of the image, which may differ
+    /// from the format of `texture` as described in [`MipmapGenerator::storage_format`].
+    ///
+    /// Returns `false` without recording anything if the format isn't supported or its pipeline
+    /// isn't compiled yet.
+    pub fn generate_mipmaps(
+        &self,
+        render_device: &RenderDevice,
+        pipeline_cache: &PipelineCache,
+        command_encoder: &mut CommandEncoder,
+        texture: &Texture,
+        format: TextureFormat,
+    ) -> bool {
+        let Some(storage_format) = Self::storage_format(format) else {
+            return false;
+        };
+        let Some(pipeline) = self
+            .pipelines
+            .get(&format)
+            .and_then(|id| pipeline_cache.get_compute_pipeline(*id))
+        else {
+            return false;
+        };
+        let layout = &self.layouts[&storage_format];
+
+        for layer in 0..texture.depth_or_array_layers() {
+            for mip_level in 1..texture.mip_level_count() {
+                let view = |format, mip_level| {
+                    texture.create_view(&TextureViewDescriptor {
+                        label: Some("mipmap_generator_view"),
+                        format: Some(format),
+                        dimension: Some(TextureViewDimension::D2),
+                        aspect: TextureAspect::All,
+                        base_mip_level: mip_level,
+                        mip_level_count: Some(1),
+                        base_array_layer: layer,
+                        array_layer_count: Some(1),
+                    })
+                };
+                let source = view(format, mip_level - 1);
+                let destination = view(storage_format, mip_level);
+                let bind_group = render_device.create_bind_group(
+                    "mipmap_generator_bind_group",
+                    layout,
+                    &BindGroupEntries::sequential((&source, &destination)),
+                );
+
+                let width = (texture.width() >> mip_level).max(1);
+                let height = (texture.height() >> mip_level).max(1);
+                let mut pass = command_encoder.begin_compute_pass(&ComputePassDescriptor {
+                    label: Some("generate_mipmaps"),
+                    timestamp_writes: None,
+                });
+                pass.set_pipeline(pipeline);
+                pass.set_bind_group(0, &bind_group, &[]);
+                pass.dispatch_workgroups(width.div_ceil(8), height.div_ceil(8), 1);
+            }
+        }
+        true
+    }
+}
+
+impl FromWorld for MipmapGenerator {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+
+        let mut layouts = HashMap::new();
+        let mut pipelines = HashMap::new();
+        for format in Self::SUPPORTED_FORMATS {
+            let storage_format = Self::storage_format(format).unwrap();
+            let layout = layouts
+                .entry(storage_format)
+                .or_insert_with(|| {
+                    render_device.create_bind_group_layout(
+                        "mipmap_generator_bind_group_layout",
+                        &BindGroupLayoutEntries::sequential(
+                            ShaderStages::COMPUTE,
+                            (
+                                texture_2d(TextureSampleType::Float { filterable: false }),
+                                texture_storage_2d(storage_format, StorageTextureAccess::WriteOnly),
+                            ),
+                        ),
+                    )
+                })
+                .clone();
+
+            let mut shader_defs: Vec<ShaderDefVal> =
+                vec![format!("{storage_format:?}").to_uppercase().into()];
+            if format.is_srgb() {
+                shader_defs.push("SRGB".into());
+            }
+            let id = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+                label: Some("mipmap_generator_pipeline".into()),
+                layout: vec![layout],
+                push_constant_ranges: vec![],
+                shader: MIPMAP_GENERATOR_SHADER_HANDLE,
+                shader_defs,
+                entry_point: "downsample".into(),
+            });
+            pipelines.insert(format, id);
+        }
+
+        Self { layouts, pipelines }
+    }
+}
+
+/// Creates the texture of an image with [`Image::gpu_mipmaps`] set, only uploading the first mip
+/// level of each layer.
+pub(super) fn create_texture_with_first_mip_level(
+    render_device: &RenderDevice,
+    render_queue: &RenderQueue,
+    image: &Image,
+) -> Texture {
+    let format = image.texture_descriptor.format;
+    let mut descriptor = image.texture_descriptor.clone();
+    if image.texture_descriptor.dimension == TextureDimension::D2 {
+        if let Some(storage_format) = MipmapGenerator::storage_format(format) {
+            descriptor.format = storage_format;
+            descriptor.usage |= TextureUsages::STORAGE_BINDING;
+            if storage_format != format {
+                descriptor.view_formats = &[TextureFormat::Rgba8UnormSrgb];
+            }
+        }
+    }
+    let texture = render_device.create_texture(&descriptor);
+
+    // `data` holds either the first mip level or the full mip chain of each layer
+    let layers = descriptor.size.depth_or_array_layers as usize;
+    let bytes_per_row = descriptor.size.width as usize * format.pixel_size();
+    let mip_level_size = bytes_per_row * descriptor.size.height as usize;
+    if image.data.len() >= mip_level_size * layers {
+        let layer_stride = image.data.len() / layers;
+        for layer in 0..layers {
+            let offset = layer * layer_stride;
+            render_queue.write_texture(
+                ImageCopyTexture {
+                    texture: &texture,
+                    mip_level: 0,
+                    origin: Origin3d {
+                        x: 0,
+                        y: 0,
+                        z: layer as u32,
+                    },
+                    aspect: TextureAspect::All,
+                },
+                &image.data[offset..offset + mip_level_size],
+                ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: Some(bytes_per_row as u32),
+                    rows_per_image: None,
+                },
+                Extent3d {
+                    depth_or_array_layers: 1,
+                    ..descriptor.size
+                },
+            );
+        }
+    }
+    texture
+}
+
+/// Regenerates the mip levels of the [`Image`]s with [`Image::gpu_mipmaps`] set that cameras
+/// rendered to this frame.
+pub fn generate_render_target_mipmaps(
+    cameras: Query<&ExtractedCamera>,
+    images: Res<RenderAssets<GpuImage>>,
+    mipmap_generator: Res<MipmapGenerator>,
+    pipeline_cache: Res<PipelineCache>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+) {
+    let mut command_encoder = None;
+    let mut generated = HashSet::new();
+    for camera in &cameras {
+        let Some(NormalizedRenderTarget::Image(image)) = &camera.target else {
+            continue;
+        };
+        if !generated.insert(image.id()) {
+            continue;
+        }
+        // Only the textures of images with `gpu_mipmaps` set are created with storage binding
+        let Some(gpu_image) = images.get(image).filter(|gpu_image| {
+            gpu_image.mip_level_count > 1
+                && gpu_image
+                    .texture
+                    .usage()
+                    .contains(TextureUsages::STORAGE_BINDING)
+        }) else {
+            continue;
+        };
+
+        let command_encoder = command_encoder.get_or_insert_with(|| {
+            render_device.create_command_encoder(&CommandEncoderDescriptor {
+                label: Some("render_target_mipmaps"),
+            })
+        });
+        mipmap_generator.generate_mipmaps(
+            &render_device,
+            &pipeline_cache,
+            command_encoder,
+            &gpu_image.texture,
+            gpu_image.texture_format,
+        );
+    }
+
+    if let Some(command_encoder) = command_encoder {
+        render_queue.submit([command_encoder.finish()]);
+    }
+}
@@ -0,0 +1,45 @@
+// Generates a mip level by averaging each 2×2 block of pixels of the previous mip level.
+
+@group(0) @binding(0) var source: texture_2d<f32>;
+#ifdef RGBA8UNORM
+@group(0) @binding(1) var destination: texture_storage_2d<rgba8unorm, write>;
+#else ifdef RGBA8SNORM
+@group(0) @binding(1) var destination: texture_storage_2d<rgba8snorm, write>;
+#else ifdef RGBA16FLOAT
+@group(0) @binding(1) var destination: texture_storage_2d<rgba16float, write>;
+#else ifdef RGBA32FLOAT
+@group(0) @binding(1) var destination: texture_storage_2d<rgba32float, write>;
+#else ifdef R32FLOAT
+@group(0) @binding(1) var destination: texture_storage_2d<r32float, write>;
+#endif
+
+// The destination is written through a linear view of the sRGB texture, so encode manually
+fn linear_to_srgb(color: vec3<f32>) -> vec3<f32> {
+    let low = color * 12.92;
+    let high = 1.055 * pow(color, vec3(1.0 / 2.4)) - 0.055;
+    return select(high, low, color <= vec3(0.0031308));
+}
+
+@compute
+@workgroup_size(8, 8, 1)
+fn downsample(@builtin(global_invocation_id) global_id: vec3<u32>) {
+    if any(global_id.xy >= textureDimensions(destination)) {
+        return;
+    }
+
+    // Odd sized mip levels clamp the last row and column of the block
+    let source_max = textureDimensions(source) - 1u;
+    let texel = global_id.xy * 2u;
+    // sRGB sources are decoded to linear when loaded
+    let a = textureLoad(source, min(texel, source_max), 0);
+    let b = textureLoad(source, min(texel + vec2(1u, 0u), source_max), 0);
+    let c = textureLoad(source, min(texel + vec2(0u, 1u), source_max), 0);
+    let d = textureLoad(source, min(texel + vec2(1u, 1u), source_max), 0);
+    var color = (a + b + c + d) * 0.25;
+
+#ifdef SRGB
+    color = vec4(linear_to_srgb(color.rgb), color.a);
+#endif
+
+    textureStore(destination, global_id.xy, color);
+}
@@ -6,14 +6,20 @@ mod compressed_image_saver;
 mod dds;
 #[cfg(feature = "exr")]
 mod exr_texture_loader;
+#[cfg(feature = "exr")]
+mod exr_texture_saver;
 mod fallback_image;
 #[cfg(feature = "hdr")]
 mod hdr_texture_loader;
 #[allow(clippy::module_inception)]
 mod image;
+mod image_access;
 mod image_loader;
+mod image_mipmaps;
+mod image_saver;
 #[cfg(feature = "ktx2")]
 mod ktx2;
+mod mipmap_generator;
 mod texture_attachment;
 mod texture_cache;
 
@@ -26,21 +32,32 @@ pub use self::ktx2::*;
 pub use dds::*;
 #[cfg(feature = "exr")]
 pub use exr_texture_loader::*;
+#[cfg(feature = "exr")]
+pub use exr_texture_saver::*;
 #[cfg(feature = "hdr")]
 pub use hdr_texture_loader::*;
+pub use image_access::TextureAccessError;
+pub use image_mipmaps::{ImageMipmapGenerator, ImageMipmapGeneratorSettings, MipmapFilter};
 
 #[cfg(feature = "basis-universal")]
 pub use compressed_image_saver::*;
 pub use fallback_image::*;
 pub use image_loader::*;
+pub use image_saver::*;
+pub use mipmap_generator::{
+    generate_render_target_mipmaps, MipmapGenerator, MIPMAP_GENERATOR_SHADER_HANDLE,
+};
 pub use texture_attachment::*;
 pub use texture_cache::*;
 
 use crate::{
-    render_asset::RenderAssetPlugin, renderer::RenderDevice, Render, RenderApp, RenderSet,
+    render_asset::RenderAssetPlugin,
+    render_resource::Shader,
+    renderer::{render_system, RenderDevice},
+    Render, RenderApp, RenderSet,
 };
 use bevy_app::{App, Plugin};
-use bevy_asset::{AssetApp, Assets, Handle};
+use bevy_asset::{load_internal_asset, AssetApp, Assets, Handle};
 use bevy_ecs::prelude::*;
 
 // TODO: replace Texture names with Image names?
@@ -84,6 +101,13 @@ impl Plugin for ImagePlugin {
             app.init_asset_loader::<HdrTextureLoader>();
         }
 
+        load_internal_asset!(
+            app,
+            MIPMAP_GENERATOR_SHADER_HANDLE,
+            "mipmap_generator.wgsl",
+            Shader::from_wgsl
+        );
+
         app.add_plugins(RenderAssetPlugin::<GpuImage>::default())
             .register_type::<Image>()
             .init_asset::<Image>()
@@ -103,11 +127,30 @@ impl Plugin for ImagePlugin {
             processor
                 .set_default_processor::<bevy_asset::processor::LoadAndSave<ImageLoader, CompressedImageSaver>>("png");
         }
+        // `CompressedImageSaver` already generates mip levels, this processor keeps images uncompressed
+        if let Some(processor) = app
+            .world()
+            .get_resource::<bevy_asset::processor::AssetProcessor>()
+        {
+            processor.register_processor::<bevy_asset::processor::LoadTransformAndSave<
+                ImageLoader,
+                ImageMipmapGenerator,
+                ImageSaver,
+            >>(bevy_asset::processor::LoadTransformAndSave::new(
+                ImageMipmapGenerator,
+                ImageSaver,
+            ));
+        }
 
         if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
             render_app.init_resource::<TextureCache>().add_systems(
                 Render,
-                update_texture_cache_system.in_set(RenderSet::Cleanup),
+                (
+                    generate_render_target_mipmaps
+                        .in_set(RenderSet::Render)
+                        .after(render_system),
+                    update_texture_cache_system.in_set(RenderSet::Cleanup),
+                ),
             );
         }
 
@@ -151,7 +194,8 @@ impl Plugin for ImagePlugin {
                 .init_resource::<FallbackImage>()
                 .init_resource::<FallbackImageZero>()
                 .init_resource::<FallbackImageCubemap>()
-                .init_resource::<FallbackImageFormatMsaaCache>();
+                .init_resource::<FallbackImageFormatMsaaCache>()
+                .init_resource::<MipmapGenerator>();
         }
     }
 }
@@ -546,6 +546,15 @@ pub struct GpuCulling;
 #[derive(Component)]
 pub struct NoCpuCulling;
 
+/// Add this component to a camera with [`GpuCulling`] to also skip drawing meshes that were hidden
+/// behind other geometry.
+///
+/// Each mesh's bounding box is tested on the GPU against a hierarchical depth buffer built from
+/// the previous frame, so meshes that become visible may appear one frame late. The first frame,
+/// and the frame after the viewport is resized, aren't occlusion culled.
+#[derive(Component, Clone, Copy, Default)]
+pub struct OcclusionCulling;
+
 impl ViewTarget {
     pub const TEXTURE_FORMAT_HDR: TextureFormat = TextureFormat::Rgba16Float;
 
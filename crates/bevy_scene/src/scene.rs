@@ -1,11 +1,13 @@
 use crate::{DynamicScene, InstanceInfo, SceneSpawnError};
 use bevy_asset::Asset;
-use bevy_ecs::entity::EntityHashMap;
+use bevy_ecs::entity::{Entity, EntityHashMap};
 use bevy_ecs::{
     reflect::{AppTypeRegistry, ReflectComponent, ReflectMapEntities, ReflectResource},
     world::World,
 };
 use bevy_reflect::TypePath;
+use bevy_utils::HashMap;
+use std::any::TypeId;
 
 /// To spawn a scene, you can use either:
 /// * [`SceneSpawner::spawn`](crate::SceneSpawner::spawn)
@@ -59,7 +61,20 @@ impl Scene {
         let mut instance_info = InstanceInfo {
             entity_map: EntityHashMap::default(),
         };
+        self.write_to_world_with_entity_map(world, &mut instance_info.entity_map, type_registry)?;
+        Ok(instance_info)
+    }
 
+    /// Write the entities and their corresponding components to the given world, reusing the entities
+    /// already present in `entity_map` and adding newly spawned ones to it.
+    ///
+    /// This is used to patch existing scene instances in place when their [`Scene`] is modified.
+    pub(crate) fn write_to_world_with_entity_map(
+        &self,
+        world: &mut World,
+        entity_map: &mut EntityHashMap<Entity>,
+        type_registry: &AppTypeRegistry,
+    ) -> Result<(), SceneSpawnError> {
         let type_registry = type_registry.read();
 
         // Resources archetype
@@ -92,10 +107,12 @@ impl Scene {
             reflect_resource.copy(&self.world, world, &type_registry);
         }
 
+        // Only the components copied from the scene are mapped, so that the entities they reference
+        // outside of the scene, such as the parent of an instance patched in place, are kept.
+        let mut scene_mappings: HashMap<TypeId, Vec<Entity>> = HashMap::default();
         for archetype in self.world.archetypes().iter() {
             for scene_entity in archetype.entities() {
-                let entity = *instance_info
-                    .entity_map
+                let entity = *entity_map
                     .entry(scene_entity.id())
                     .or_insert_with(|| world.spawn_empty().id());
                 for component_id in archetype.components() {
@@ -105,18 +122,21 @@ impl Scene {
                         .get_info(component_id)
                         .expect("component_ids in archetypes should have ComponentInfo");
 
-                    let reflect_component = type_registry
-                        .get(component_info.type_id().unwrap())
-                        .ok_or_else(|| SceneSpawnError::UnregisteredType {
+                    let type_id = component_info.type_id().unwrap();
+                    let registration = type_registry.get(type_id).ok_or_else(|| {
+                        SceneSpawnError::UnregisteredType {
                             std_type_name: component_info.name().to_string(),
-                        })
-                        .and_then(|registration| {
-                            registration.data::<ReflectComponent>().ok_or_else(|| {
-                                SceneSpawnError::UnregisteredComponent {
-                                    type_path: registration.type_info().type_path().to_string(),
-                                }
-                            })
+                        }
+                    })?;
+                    let reflect_component =
+                        registration.data::<ReflectComponent>().ok_or_else(|| {
+                            SceneSpawnError::UnregisteredComponent {
+                                type_path: registration.type_info().type_path().to_string(),
+                            }
                         })?;
+                    if registration.data::<ReflectMapEntities>().is_some() {
+                        scene_mappings.entry(type_id).or_default().push(entity);
+                    }
                     reflect_component.copy(
                         &self.world,
                         world,
@@ -128,12 +148,15 @@ impl Scene {
             }
         }
 
-        for registration in type_registry.iter() {
+        for (type_id, entities) in scene_mappings {
+            let registration = type_registry.get(type_id).expect(
+                "we should be getting TypeId from this TypeRegistration in the first place",
+            );
             if let Some(map_entities_reflect) = registration.data::<ReflectMapEntities>() {
-                map_entities_reflect.map_all_entities(world, &mut instance_info.entity_map);
+                map_entities_reflect.map_entities(world, entity_map, &entities);
             }
         }
 
-        Ok(instance_info)
+        Ok(())
     }
 }
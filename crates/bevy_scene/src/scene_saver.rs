@@ -0,0 +1,105 @@
+use crate::ron;
+#[cfg(feature = "serialize")]
+use crate::{DynamicScene, SceneLoader};
+#[cfg(feature = "serialize")]
+use bevy_asset::{
+    saver::{AssetSaver, SavedAsset},
+    AsyncWriteExt,
+};
+use bevy_ecs::reflect::AppTypeRegistry;
+use bevy_ecs::world::{FromWorld, World};
+use bevy_reflect::TypeRegistryArc;
+use thiserror::Error;
+
+/// Asset saver for a Bevy [`DynamicScene`](crate::DynamicScene) (`.scn` / `.scn.ron`).
+///
+/// The scene is written as RON with [`DynamicScene::serialize`](crate::DynamicScene::serialize),
+/// so it can be read back by the [`SceneLoader`](crate::SceneLoader).
+#[derive(Debug)]
+pub struct SceneSaver {
+    type_registry: TypeRegistryArc,
+}
+
+impl FromWorld for SceneSaver {
+    fn from_world(world: &mut World) -> Self {
+        let type_registry = world.resource::<AppTypeRegistry>();
+        SceneSaver {
+            type_registry: type_registry.0.clone(),
+        }
+    }
+}
+
+/// Possible errors that can be produced by [`SceneSaver`]
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum SceneSaverError {
+    /// An [IO Error](std::io::Error)
+    #[error("Error while trying to write the scene file: {0}")]
+    Io(#[from] std::io::Error),
+    /// A [RON Error](ron::Error)
+    #[error("Could not serialize RON: {0}")]
+    Ron(#[from] ron::Error),
+}
+
+#[cfg(feature = "serialize")]
+impl AssetSaver for SceneSaver {
+    type Asset = DynamicScene;
+    type Settings = ();
+    type OutputLoader = SceneLoader;
+    type Error = SceneSaverError;
+
+    async fn save<'a>(
+        &'a self,
+        writer: &'a mut bevy_asset::io::Writer,
+        scene: SavedAsset<'a, Self::Asset>,
+        _settings: &'a Self::Settings,
+    ) -> Result<(), Self::Error> {
+        let serialized = scene.serialize(&self.type_registry.read())?;
+        writer.write_all(serialized.as_bytes()).await?;
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "serialize"))]
+mod tests {
+    use super::*;
+    use crate::serde::SceneDeserializer;
+    use bevy_asset::LoadedAsset;
+    use bevy_ecs::{component::Component, reflect::ReflectComponent};
+    use bevy_reflect::Reflect;
+    use serde::de::DeserializeSeed;
+
+    #[derive(Component, Reflect, Default)]
+    #[reflect(Component)]
+    struct Foo(i32);
+
+    #[test]
+    fn saved_scene_can_be_deserialized() {
+        let mut world = World::new();
+        world.init_resource::<AppTypeRegistry>();
+        world
+            .resource::<AppTypeRegistry>()
+            .write()
+            .register::<Foo>();
+        world.spawn(Foo(123));
+
+        let scene = LoadedAsset::from(DynamicScene::from_world(&world)).into();
+        let saver = SceneSaver::from_world(&mut world);
+        let mut bytes = Vec::new();
+        bevy_tasks::block_on(saver.save(&mut bytes, SavedAsset::from_loaded(&scene).unwrap(), &()))
+            .unwrap();
+
+        let mut deserializer = ron::de::Deserializer::from_bytes(&bytes).unwrap();
+        let scene = SceneDeserializer {
+            type_registry: &saver.type_registry.read(),
+        }
+        .deserialize(&mut deserializer)
+        .unwrap();
+        assert_eq!(scene.entities.len(), 1);
+        let components = &scene.entities[0].components;
+        assert_eq!(components.len(), 1);
+        assert!(components[0]
+            .reflect_partial_eq(&Foo(123))
+            .unwrap_or_default());
+    }
+}
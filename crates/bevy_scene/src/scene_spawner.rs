@@ -47,6 +47,7 @@ impl InstanceId {
 /// - [`despawn_sync`](Self::despawn_sync)
 /// - [`despawn_instance_sync`](Self::despawn_instance_sync)
 /// - [`update_spawned_scenes`](Self::update_spawned_scenes)
+/// - [`update_spawned_real_scenes`](Self::update_spawned_real_scenes)
 /// - [`spawn_queued_scenes`](Self::spawn_queued_scenes)
 /// - [`despawn_queued_scenes`](Self::despawn_queued_scenes)
 /// - [`despawn_queued_instances`](Self::despawn_queued_instances)
@@ -61,13 +62,18 @@ impl InstanceId {
 #[derive(Default, Resource)]
 pub struct SceneSpawner {
     pub(crate) spawned_dynamic_scenes: HashMap<AssetId<DynamicScene>, HashSet<InstanceId>>,
+    pub(crate) spawned_scenes: HashMap<AssetId<Scene>, HashSet<InstanceId>>,
     pub(crate) spawned_instances: HashMap<InstanceId, InstanceInfo>,
     scene_asset_event_reader: ManualEventReader<AssetEvent<DynamicScene>>,
+    real_scene_asset_event_reader: ManualEventReader<AssetEvent<Scene>>,
     dynamic_scenes_to_spawn: Vec<(Handle<DynamicScene>, InstanceId)>,
     scenes_to_spawn: Vec<(Handle<Scene>, InstanceId)>,
     scenes_to_despawn: Vec<AssetId<DynamicScene>>,
     instances_to_despawn: Vec<InstanceId>,
     scenes_with_parent: Vec<(InstanceId, Entity)>,
+    /// The parents of the spawned instances that were spawned as children, to also parent the
+    /// entities added to their scene.
+    instance_parents: HashMap<InstanceId, Entity>,
 }
 
 /// Errors that can occur when spawning a scene.
@@ -185,6 +191,11 @@ impl SceneSpawner {
 
     /// Immediately despawns a scene instance, removing all its entities from the world.
     pub fn despawn_instance_sync(&mut self, world: &mut World, instance_id: &InstanceId) {
+        self.instance_parents.remove(instance_id);
+        self.spawned_scenes.retain(|_, instance_ids| {
+            instance_ids.remove(instance_id);
+            !instance_ids.is_empty()
+        });
         if let Some(instance) = self.spawned_instances.remove(instance_id) {
             for &entity in instance.entity_map.values() {
                 if let Some(mut entity_mut) = world.get_entity_mut(entity) {
@@ -249,6 +260,10 @@ impl SceneSpawner {
                 scene.write_to_world_with(world, &world.resource::<AppTypeRegistry>().clone())?;
 
             self.spawned_instances.insert(instance_id, instance_info);
+            self.spawned_scenes
+                .entry(id)
+                .or_default()
+                .insert(instance_id);
             Ok(instance_id)
         })
     }
@@ -273,6 +288,73 @@ impl SceneSpawner {
         Ok(())
     }
 
+    /// Iterate through all instances of the provided scenes and patch those immediately.
+    ///
+    /// Entities of an instance are matched to the entities of the modified scene. Matching entities
+    /// have the scene's components copied over them, new entities are spawned, and entities that
+    /// were removed from the scene are despawned. Components removed from a scene entity are kept
+    /// on the instance entity.
+    pub fn update_spawned_real_scenes(
+        &mut self,
+        world: &mut World,
+        scene_ids: &[AssetId<Scene>],
+    ) -> Result<(), SceneSpawnError> {
+        world.resource_scope(|world, scenes: Mut<Assets<Scene>>| {
+            let type_registry = world.resource::<AppTypeRegistry>().clone();
+            for id in scene_ids {
+                let Some(spawned_instances) = self.spawned_scenes.get(id) else {
+                    continue;
+                };
+                let scene = scenes
+                    .get(*id)
+                    .ok_or(SceneSpawnError::NonExistentRealScene { id: *id })?;
+                for instance_id in spawned_instances {
+                    let Some(instance_info) = self.spawned_instances.get_mut(instance_id) else {
+                        continue;
+                    };
+                    instance_info.entity_map.retain(|scene_entity, entity| {
+                        let retain = scene.world.get_entity(*scene_entity).is_some();
+                        if !retain {
+                            if let Some(mut entity_mut) = world.get_entity_mut(*entity) {
+                                entity_mut.remove_parent();
+                                entity_mut.despawn_recursive();
+                            }
+                        }
+                        retain
+                    });
+                    let previous_entity_count = instance_info.entity_map.len();
+                    scene.write_to_world_with_entity_map(
+                        world,
+                        &mut instance_info.entity_map,
+                        &type_registry,
+                    )?;
+
+                    // New root entities of the scene become children of the instance parent, like
+                    // the ones spawned with the instance
+                    let Some(&parent) = self.instance_parents.get(instance_id) else {
+                        continue;
+                    };
+                    if instance_info.entity_map.len() == previous_entity_count {
+                        continue;
+                    }
+                    for &entity in instance_info.entity_map.values() {
+                        if world
+                            .get_entity(entity)
+                            .is_some_and(|entity| !entity.contains::<Parent>())
+                        {
+                            PushChild {
+                                parent,
+                                child: entity,
+                            }
+                            .apply(world);
+                        }
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
     /// Immediately despawns all scenes scheduled for despawn by despawning their instances.
     pub fn despawn_queued_scenes(&mut self, world: &mut World) -> Result<(), SceneSpawnError> {
         let scenes_to_despawn = std::mem::take(&mut self.scenes_to_despawn);
@@ -336,6 +418,7 @@ impl SceneSpawner {
 
         for (instance_id, parent) in scenes_with_parent {
             if let Some(instance) = self.spawned_instances.get(&instance_id) {
+                self.instance_parents.insert(instance_id, parent);
                 for &entity in instance.entity_map.values() {
                     // Add the `Parent` component to the scene root, and update the `Children` component of
                     // the scene parent
@@ -423,6 +506,20 @@ pub fn scene_spawner_system(world: &mut World) {
             }
         }
 
+        let scene_asset_events = world.resource::<Events<AssetEvent<Scene>>>();
+
+        let mut updated_spawned_real_scenes = Vec::new();
+        for event in scene_spawner
+            .real_scene_asset_event_reader
+            .read(scene_asset_events)
+        {
+            if let AssetEvent::Modified { id } = event {
+                if scene_spawner.spawned_scenes.contains_key(id) {
+                    updated_spawned_real_scenes.push(*id);
+                }
+            }
+        }
+
         scene_spawner.despawn_queued_scenes(world).unwrap();
         scene_spawner.despawn_queued_instances(world);
         scene_spawner
@@ -431,6 +528,9 @@ pub fn scene_spawner_system(world: &mut World) {
         scene_spawner
             .update_spawned_scenes(world, &updated_spawned_scenes)
             .unwrap();
+        scene_spawner
+            .update_spawned_real_scenes(world, &updated_spawned_real_scenes)
+            .unwrap();
         scene_spawner.set_scene_instance_parent_sync(world);
     });
 }
@@ -444,6 +544,7 @@ mod tests {
     use bevy_ecs::query::With;
     use bevy_ecs::system::{Commands, Res, ResMut, RunSystemOnce};
     use bevy_ecs::{component::Component, system::Query};
+    use bevy_hierarchy::Children;
     use bevy_reflect::Reflect;
 
     use crate::{DynamicSceneBuilder, ScenePlugin};
@@ -590,4 +691,111 @@ mod tests {
         app.update();
         check(app.world_mut(), 0);
     }
+
+    #[test]
+    fn update_modified_scene() {
+        let mut app = App::new();
+        app.add_plugins((AssetPlugin::default(), ScenePlugin));
+        app.register_type::<A>();
+
+        let mut scene_world = World::new();
+        let kept = scene_world.spawn(A(1)).id();
+        let removed = scene_world.spawn(A(2)).id();
+        let scene = app
+            .world()
+            .resource::<AssetServer>()
+            .add(Scene::new(scene_world));
+
+        let instance_id = app
+            .world_mut()
+            .resource_mut::<SceneSpawner>()
+            .spawn(scene.clone());
+        app.update();
+
+        let instance_values = |app: &mut App| {
+            let entities = app
+                .world()
+                .resource::<SceneSpawner>()
+                .iter_instance_entities(instance_id)
+                .collect::<Vec<_>>();
+            let mut values = entities
+                .iter()
+                .map(|entity| app.world().get::<A>(*entity).unwrap().0)
+                .collect::<Vec<_>>();
+            values.sort();
+            values
+        };
+        assert_eq!(instance_values(&mut app), vec![1, 2]);
+
+        // Modify the scene: change a component, remove an entity and add a new one.
+        {
+            let mut scenes = app.world_mut().resource_mut::<Assets<Scene>>();
+            let scene_world = &mut scenes.get_mut(&scene).unwrap().world;
+            scene_world.entity_mut(kept).insert(A(3));
+            scene_world.despawn(removed);
+            scene_world.spawn(A(4));
+        }
+        app.update();
+        app.update();
+
+        assert_eq!(instance_values(&mut app), vec![3, 4]);
+        let mut query = app.world_mut().query::<&A>();
+        assert_eq!(query.iter(app.world()).count(), 2);
+    }
+
+    #[test]
+    fn update_modified_scene_spawned_as_child() {
+        let mut app = App::new();
+        app.add_plugins((AssetPlugin::default(), ScenePlugin));
+        app.register_type::<A>()
+            .register_type::<Parent>()
+            .register_type::<Children>();
+
+        let mut scene_world = World::new();
+        scene_world.spawn(A(1));
+        let scene = app
+            .world()
+            .resource::<AssetServer>()
+            .add(Scene::new(scene_world));
+
+        let parent = app.world_mut().spawn_empty().id();
+        let instance_id = app
+            .world_mut()
+            .resource_mut::<SceneSpawner>()
+            .spawn_as_child(scene.clone(), parent);
+        app.update();
+
+        // Add a root entity and a child to the scene
+        {
+            let mut scenes = app.world_mut().resource_mut::<Assets<Scene>>();
+            let scene_world = &mut scenes.get_mut(&scene).unwrap().world;
+            scene_world.spawn(A(2)).with_children(|builder| {
+                builder.spawn(A(3));
+            });
+        }
+        app.update();
+        app.update();
+
+        let mut query = app.world_mut().query::<(&A, &Parent)>();
+        let mut parents = query
+            .iter(app.world())
+            .map(|(a, entity_parent)| (a.0, entity_parent.get()))
+            .collect::<Vec<_>>();
+        parents.sort_by_key(|(value, _)| *value);
+        assert_eq!(parents[0], (1, parent));
+        assert_eq!(parents[1], (2, parent));
+        assert_ne!(parents[2].1, parent);
+
+        // Despawning the instance forgets about it
+        app.world_mut()
+            .resource_mut::<SceneSpawner>()
+            .despawn_instance(instance_id);
+        app.update();
+        assert!(app
+            .world()
+            .resource::<SceneSpawner>()
+            .spawned_scenes
+            .is_empty());
+        assert!(app.world().entity(parent).get::<Children>().is_none());
+    }
 }
@@ -10,12 +10,18 @@
 //! Provides 2D sprite rendering functionality.
 mod bundle;
 mod dynamic_texture_atlas_builder;
+mod light2d;
 mod mesh2d;
+mod packed_texture_atlas;
+#[cfg(feature = "bevy_picking")]
+pub mod picking_backend;
 mod render;
 mod sprite;
+mod sprite_material;
 mod texture_atlas;
 mod texture_atlas_builder;
 mod texture_slice;
+mod tilemap;
 
 pub mod prelude {
     #[allow(deprecated)]
@@ -25,9 +31,16 @@ pub mod prelude {
     #[doc(hidden)]
     pub use crate::{
         bundle::SpriteBundle,
+        light2d::{
+            AmbientLight2d, LightOccluder2d, LightOccluder2dBundle, PointLight2d,
+            PointLight2dBundle, SpotLight2d, SpotLight2dBundle, SpriteNormalMap,
+        },
+        packed_texture_atlas::PackedTextureAtlas,
         sprite::{ImageScaleMode, Sprite},
+        sprite_material::{SpriteMaterial, SpriteMaterialPlugin},
         texture_atlas::{TextureAtlas, TextureAtlasLayout},
         texture_slice::{BorderRect, SliceScaleMode, TextureSlice, TextureSlicer},
+        tilemap::{Tile, TileAnimation, Tilemap, TilemapBundle, TilemapGrid},
         ColorMaterial, ColorMesh2dBundle, TextureAtlasBuilder,
     };
 }
@@ -35,12 +48,16 @@ pub mod prelude {
 use bevy_reflect::{std_traits::ReflectDefault, Reflect};
 pub use bundle::*;
 pub use dynamic_texture_atlas_builder::*;
+pub use light2d::*;
 pub use mesh2d::*;
+pub use packed_texture_atlas::*;
 pub use render::*;
 pub use sprite::*;
+pub use sprite_material::*;
 pub use texture_atlas::*;
 pub use texture_atlas_builder::*;
 pub use texture_slice::*;
+pub use tilemap::*;
 
 use bevy_app::prelude::*;
 use bevy_asset::{load_internal_asset, AssetApp, Assets, Handle};
@@ -62,6 +79,10 @@ use bevy_render::{
 pub struct SpritePlugin;
 
 pub const SPRITE_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(2763343953151597127);
+pub const SPRITE_VERTEX_OUTPUT_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(7264091853328745117);
+pub const SPRITE_VIEW_BINDINGS_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(1857329164509732631);
 
 /// System set for sprite rendering.
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
@@ -94,7 +115,22 @@ impl Plugin for SpritePlugin {
             "render/sprite.wgsl",
             Shader::from_wgsl
         );
+        load_internal_asset!(
+            app,
+            SPRITE_VERTEX_OUTPUT_SHADER_HANDLE,
+            "render/sprite_vertex_output.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            SPRITE_VIEW_BINDINGS_SHADER_HANDLE,
+            "render/sprite_view_bindings.wgsl",
+            Shader::from_wgsl
+        );
         app.init_asset::<TextureAtlasLayout>()
+            .init_asset::<PackedTextureAtlas>()
+            .init_asset_loader::<TextureAtlasManifestLoader>()
+            .init_asset_loader::<PackedTextureAtlasLoader>()
             .register_asset_reflect::<TextureAtlasLayout>()
             .register_type::<Sprite>()
             .register_type::<ImageScaleMode>()
@@ -106,6 +142,8 @@ impl Plugin for SpritePlugin {
             .add_plugins((
                 Mesh2dRenderPlugin,
                 ColorMaterialPlugin,
+                Light2dPlugin,
+                TilemapPlugin,
                 ExtractComponentPlugin::<SpriteSource>::default(),
             ))
             .add_systems(
@@ -125,12 +163,35 @@ impl Plugin for SpritePlugin {
                 ),
             );
 
+        if let Some(processor) = app
+            .world()
+            .get_resource::<bevy_asset::processor::AssetProcessor>()
+        {
+            processor.register_processor::<bevy_asset::processor::LoadAndSave<
+                TextureAtlasManifestLoader,
+                PackedTextureAtlasSaver,
+            >>(PackedTextureAtlasSaver.into());
+            processor.set_default_processor::<bevy_asset::processor::LoadAndSave<
+                TextureAtlasManifestLoader,
+                PackedTextureAtlasSaver,
+            >>("atlas.ron");
+        }
+
+        #[cfg(feature = "bevy_picking")]
+        app.add_event::<bevy_picking::backend::PointerHits>()
+            .add_systems(
+                PreUpdate,
+                picking_backend::sprite_picking.in_set(bevy_picking::PickSet::Backend),
+            );
+
         if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
             render_app
                 .init_resource::<ImageBindGroups>()
                 .init_resource::<SpecializedRenderPipelines<SpritePipeline>>()
                 .init_resource::<SpriteMeta>()
                 .init_resource::<ExtractedSprites>()
+                .init_resource::<SpriteMaterialInstances>()
+                .init_resource::<SpriteNormalMapInstances>()
                 .init_resource::<SpriteAssetEvents>()
                 .add_render_command::<Transparent2d, DrawSprite>()
                 .add_systems(
@@ -0,0 +1,150 @@
+#import bevy_render::view::View
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+struct Light2d {
+    // The color of the light multiplied by its intensity
+    color: vec4<f32>,
+    position: vec2<f32>,
+    direction: vec2<f32>,
+    range: f32,
+    radius: f32,
+    height: f32,
+    spot_scale: f32,
+    spot_offset: f32,
+    flags: u32,
+}
+
+const LIGHT_2D_FLAGS_SHADOWS_ENABLED_BIT: u32 = 1u;
+
+// A rounded rectangle
+struct LightOccluder2d {
+    center: vec2<f32>,
+    // The cosine and the sine of the rotation of the occluder
+    rotation: vec2<f32>,
+    half_size: vec2<f32>,
+    radius: f32,
+}
+
+struct Lights2d {
+    ambient: vec4<f32>,
+    lights: array<Light2d, #{MAX_LIGHTS_2D}u>,
+    occluders: array<LightOccluder2d, #{MAX_LIGHT_OCCLUDERS_2D}u>,
+    n_lights: u32,
+    n_occluders: u32,
+}
+
+@group(0) @binding(0) var<uniform> view: View;
+@group(0) @binding(1) var<uniform> lights: Lights2d;
+@group(0) @binding(2) var normal_texture: texture_2d<f32>;
+
+const SHADOW_MAX_STEPS: u32 = 32u;
+
+// The signed distance from a position to the edge of an occluder
+fn occluder_distance(occluder: LightOccluder2d, position: vec2<f32>) -> f32 {
+    let offset = position - occluder.center;
+    let local_position = vec2<f32>(
+        dot(offset, occluder.rotation),
+        dot(offset, vec2<f32>(-occluder.rotation.y, occluder.rotation.x)),
+    );
+    let q = abs(local_position) - occluder.half_size;
+    return length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - occluder.radius;
+}
+
+// The signed distance from a position to the closest occluder, ignoring the occluders set in the
+// bit mask
+fn scene_distance(position: vec2<f32>, ignored_occluders: vec2<u32>) -> f32 {
+    var distance = 3.4e38;
+    for (var i = 0u; i < lights.n_occluders; i += 1u) {
+        if ((ignored_occluders[i / 32u] >> (i % 32u)) & 1u) != 0u {
+            continue;
+        }
+        distance = min(distance, occluder_distance(lights.occluders[i], position));
+    }
+    return distance;
+}
+
+// How much of a light disk is visible from a position, by sphere tracing the occluders towards
+// the light. The penumbra widens away from the occluders like the cone from the position to the
+// light disk.
+fn light_visibility(
+    position: vec2<f32>,
+    light_position: vec2<f32>,
+    light_radius: f32,
+    ignored_occluders: vec2<u32>,
+) -> f32 {
+    let to_light = light_position - position;
+    let light_distance = length(to_light);
+    if light_distance <= 0.0 {
+        return 1.0;
+    }
+    let direction = to_light / light_distance;
+    let penumbra = light_distance / max(light_radius, 1e-3);
+    let min_step = light_distance / f32(SHADOW_MAX_STEPS);
+
+    var visibility = 1.0;
+    var t = min_step * 0.5;
+    for (var i = 0u; i < SHADOW_MAX_STEPS && t < light_distance; i += 1u) {
+        let distance = scene_distance(position + direction * t, ignored_occluders);
+        if distance <= 0.0 {
+            return 0.0;
+        }
+        visibility = min(visibility, penumbra * distance / t);
+        t += max(distance, min_step);
+    }
+    return smoothstep(0.0, 1.0, visibility);
+}
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    // Pixels outside of the viewport of the view aren't lit by it
+    let viewport_uv = (in.position.xy - view.viewport.xy) / view.viewport.zw;
+    if any(viewport_uv < vec2<f32>(0.0)) || any(viewport_uv > vec2<f32>(1.0)) {
+        return vec4<f32>(1.0);
+    }
+    let ndc = vec2<f32>(viewport_uv.x * 2.0 - 1.0, 1.0 - viewport_uv.y * 2.0);
+    let world_position = view.inverse_view_proj * vec4<f32>(ndc, 0.0, 1.0);
+    let position = world_position.xy / world_position.w;
+
+    let encoded_normal = textureLoad(normal_texture, vec2<i32>(in.position.xy), 0);
+    let normal_xy = encoded_normal.xy * 2.0 - 1.0;
+    let normal = vec3<f32>(normal_xy, sqrt(max(1.0 - dot(normal_xy, normal_xy), 0.0)));
+    let normal_weight = encoded_normal.z;
+
+    // The occluders covering the pixel don't shadow it
+    var ignored_occluders = vec2<u32>(0u);
+    for (var i = 0u; i < lights.n_occluders; i += 1u) {
+        if occluder_distance(lights.occluders[i], position) < 0.0 {
+            ignored_occluders[i / 32u] |= 1u << (i % 32u);
+        }
+    }
+
+    var light = lights.ambient.rgb;
+    for (var i = 0u; i < lights.n_lights; i += 1u) {
+        let light_2d = lights.lights[i];
+        let offset = light_2d.position - position;
+        let distance = length(offset);
+        if distance >= light_2d.range {
+            continue;
+        }
+
+        let falloff = 1.0 - (distance * distance) / (light_2d.range * light_2d.range);
+        let cd = dot(light_2d.direction, -offset / max(distance, 1e-4));
+        let spot_attenuation = saturate(cd * light_2d.spot_scale + light_2d.spot_offset);
+        // Pixels without a normal map are lit as if they were facing the light
+        let light_direction = normalize(vec3<f32>(offset, light_2d.height));
+        let diffuse = mix(1.0, saturate(dot(normal, light_direction)), normal_weight);
+
+        var attenuation = falloff * falloff * spot_attenuation * spot_attenuation * diffuse;
+        if attenuation > 0.0 && (light_2d.flags & LIGHT_2D_FLAGS_SHADOWS_ENABLED_BIT) != 0u {
+            attenuation *= light_visibility(
+                position,
+                light_2d.position,
+                light_2d.radius,
+                ignored_occluders,
+            );
+        }
+        light += light_2d.color.rgb * attenuation;
+    }
+
+    return vec4<f32>(light, 1.0);
+}
@@ -0,0 +1,12 @@
+#import bevy_core_pipeline::fullscreen_vertex_shader::FullscreenVertexOutput
+
+@group(0) @binding(0) var screen_texture: texture_2d<f32>;
+@group(0) @binding(1) var light_texture: texture_2d<f32>;
+
+@fragment
+fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    let coords = vec2<i32>(in.position.xy);
+    let color = textureLoad(screen_texture, coords, 0);
+    let light = textureLoad(light_texture, coords, 0);
+    return vec4<f32>(color.rgb * light.rgb, color.a);
+}
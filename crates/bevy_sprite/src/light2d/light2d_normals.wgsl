@@ -0,0 +1,71 @@
+#import bevy_render::maths::affine3_to_square
+#import bevy_sprite::sprite_view_bindings::{view, sprite_texture, sprite_sampler}
+
+#ifdef NORMAL_MAP
+@group(2) @binding(0) var normal_map_texture: texture_2d<f32>;
+@group(2) @binding(1) var normal_map_sampler: sampler;
+#endif
+
+struct VertexInput {
+    @builtin(vertex_index) index: u32,
+    // The instances of the sprite batches, see `sprite.wgsl`
+    @location(0) i_model_transpose_col0: vec4<f32>,
+    @location(1) i_model_transpose_col1: vec4<f32>,
+    @location(2) i_model_transpose_col2: vec4<f32>,
+    @location(3) i_color: vec4<f32>,
+    @location(4) i_uv_offset_scale: vec4<f32>,
+}
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) alpha: f32,
+    // The world directions of the X and Y axes of the normal map
+    @location(2) tangent: vec2<f32>,
+    @location(3) bitangent: vec2<f32>,
+}
+
+@vertex
+fn vertex(in: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+
+    let vertex_position = vec3<f32>(
+        f32(in.index & 0x1u),
+        f32((in.index & 0x2u) >> 1u),
+        0.0
+    );
+
+    out.clip_position = view.view_proj * affine3_to_square(mat3x4<f32>(
+        in.i_model_transpose_col0,
+        in.i_model_transpose_col1,
+        in.i_model_transpose_col2,
+    )) * vec4<f32>(vertex_position, 1.0);
+    out.uv = vec2<f32>(vertex_position.xy) * in.i_uv_offset_scale.zw + in.i_uv_offset_scale.xy;
+    out.alpha = in.i_color.a;
+
+    // The image is flipped when its UV scale is reversed. Its V axis points down, unlike the Y
+    // axis of the normal map.
+    let x_axis = vec2<f32>(in.i_model_transpose_col0.x, in.i_model_transpose_col1.x);
+    let y_axis = vec2<f32>(in.i_model_transpose_col0.y, in.i_model_transpose_col1.y);
+    out.tangent = normalize(x_axis) * sign(in.i_uv_offset_scale.z);
+    out.bitangent = normalize(y_axis) * -sign(in.i_uv_offset_scale.w);
+
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let coverage = in.alpha * textureSample(sprite_texture, sprite_sampler, in.uv).a;
+
+#ifdef NORMAL_MAP
+    let normal_map = textureSample(normal_map_texture, normal_map_sampler, in.uv).xyz * 2.0 - 1.0;
+    let normal = normalize(vec3<f32>(
+        normal_map.x * in.tangent + normal_map.y * in.bitangent,
+        normal_map.z,
+    ));
+    return vec4<f32>(normal.xy * 0.5 + 0.5, 1.0, coverage);
+#else
+    // Sprites without a normal map face the camera
+    return vec4<f32>(0.5, 0.5, 0.0, coverage);
+#endif
+}
@@ -0,0 +1,559 @@
+//! 2D lighting: point, spot and ambient lights, normal-mapped sprites and shadow casting
+//! occluders.
+//!
+//! Add an [`AmbientLight2d`] to a 2d camera to light what it renders. The light of every
+//! [`PointLight2d`] and [`SpotLight2d`] sharing a [`RenderLayers`] layer with the camera is
+//! accumulated in a light buffer, in which the [`LightOccluder2d`]s of the view cast soft shadows,
+//! and the buffer is then multiplied with the rendered view in the [`Node2d::Lighting`] node of
+//! the `Core2d` graph, before bloom and tonemapping.
+//!
+//! [`Node2d::Lighting`]: bevy_core_pipeline::core_2d::graph::Node2d::Lighting
+
+mod render;
+
+pub use render::*;
+
+use bevy_app::{App, Plugin};
+use bevy_asset::{load_internal_asset, Handle};
+use bevy_color::{Color, LinearRgba};
+use bevy_core_pipeline::core_2d::{
+    graph::{Core2d, Node2d},
+    Camera2d,
+};
+use bevy_ecs::prelude::*;
+use bevy_math::{
+    primitives::{Capsule2d, Circle, Rectangle},
+    Vec2, Vec3Swizzles,
+};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use bevy_render::{
+    extract_component::{ExtractComponent, ExtractComponentPlugin},
+    render_graph::{RenderGraph, RenderGraphApp, ViewNodeRunner},
+    render_resource::{Shader, SpecializedRenderPipelines},
+    texture::Image,
+    view::{InheritedVisibility, RenderLayers, ViewVisibility, Visibility},
+    Extract, ExtractSchedule, Render, RenderApp, RenderSet,
+};
+use bevy_transform::components::{GlobalTransform, Transform};
+
+use crate::{SpriteNormalMapInstances, SpriteSystem};
+
+pub const LIGHT_2D_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(5108296424176419843);
+pub const LIGHT_2D_NORMALS_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(2893650418270364551);
+pub const LIGHT_2D_COMPOSITE_SHADER_HANDLE: Handle<Shader> =
+    Handle::weak_from_u128(9376017265438217093);
+
+/// The maximum number of [`PointLight2d`]s and [`SpotLight2d`]s lighting a view.
+///
+/// The lights whose range doesn't reach the view are ignored, and the following ones are dropped.
+pub const MAX_LIGHTS_2D: usize = 64;
+/// The maximum number of [`LightOccluder2d`]s casting shadows in a view.
+pub const MAX_LIGHT_OCCLUDERS_2D: usize = 64;
+
+/// Adds support for 2D lights, normal-mapped sprites and shadow casting occluders.
+///
+/// This plugin is added by the [`SpritePlugin`](crate::SpritePlugin).
+#[derive(Default)]
+pub struct Light2dPlugin;
+
+impl Plugin for Light2dPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            LIGHT_2D_SHADER_HANDLE,
+            "light2d.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            LIGHT_2D_NORMALS_SHADER_HANDLE,
+            "light2d_normals.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            LIGHT_2D_COMPOSITE_SHADER_HANDLE,
+            "light2d_composite.wgsl",
+            Shader::from_wgsl
+        );
+
+        app.register_type::<AmbientLight2d>()
+            .register_type::<PointLight2d>()
+            .register_type::<SpotLight2d>()
+            .register_type::<SpriteNormalMap>()
+            .add_plugins(ExtractComponentPlugin::<AmbientLight2d>::default());
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .init_resource::<ExtractedLights2d>()
+            .init_resource::<Light2dMeta>()
+            .init_resource::<SpecializedRenderPipelines<Light2dPipeline>>()
+            .add_systems(
+                ExtractSchedule,
+                (
+                    extract_lights_2d,
+                    extract_sprite_normal_maps.after(SpriteSystem::ExtractSprites),
+                ),
+            )
+            .add_systems(
+                Render,
+                (
+                    prepare_light_2d_pipelines.in_set(RenderSet::Prepare),
+                    prepare_lights_2d.in_set(RenderSet::PrepareResources),
+                    prepare_light_2d_textures.in_set(RenderSet::PrepareResources),
+                    prepare_light_2d_bind_groups.in_set(RenderSet::PrepareBindGroups),
+                ),
+            )
+            .add_render_graph_node::<ViewNodeRunner<Light2dNode>>(Core2d, Node2d::Lighting)
+            .add_render_graph_edges(
+                Core2d,
+                (Node2d::EndMainPass, Node2d::Lighting, Node2d::Tonemapping),
+            );
+
+        // Bloom should spread the lit colors, so lighting runs before it when it's enabled
+        let has_bloom = render_app
+            .world()
+            .resource::<RenderGraph>()
+            .get_sub_graph(Core2d)
+            .is_some_and(|graph| graph.get_node_state(Node2d::Bloom).is_ok());
+        if has_bloom {
+            render_app.add_render_graph_edge(Core2d, Node2d::Lighting, Node2d::Bloom);
+        }
+    }
+
+    fn finish(&self, app: &mut App) {
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.init_resource::<Light2dPipeline>();
+        }
+    }
+}
+
+/// Enables 2D lighting for a 2d camera, and lights everything it renders uniformly.
+///
+/// Without this component, the view of a camera isn't lit at all, and [`PointLight2d`]s,
+/// [`SpotLight2d`]s and [`LightOccluder2d`]s have no effect on it.
+///
+/// Lighting multiplies every color rendered in the main pass of the camera, including the ones
+/// of [`Mesh2d`]es. Only [`Sprite`]s can be normal-mapped, and everything else is lit as if it was
+/// facing the camera.
+///
+/// [`Mesh2d`]: crate::Mesh2dHandle
+/// [`Sprite`]: crate::Sprite
+#[derive(Component, Reflect, ExtractComponent, Clone, Copy, Debug)]
+#[extract_component_filter(With<Camera2d>)]
+#[reflect(Component, Default)]
+pub struct AmbientLight2d {
+    pub color: Color,
+    /// A multiplier for the ambient light color.
+    pub brightness: f32,
+}
+
+impl Default for AmbientLight2d {
+    fn default() -> Self {
+        Self {
+            color: Color::WHITE,
+            brightness: 0.1,
+        }
+    }
+}
+
+/// A light that shines in every direction from a point in a 2D scene.
+///
+/// Its light fades from [`Self::intensity`] at its position to zero at [`Self::range`]. It only
+/// lights the views of the 2d cameras with an [`AmbientLight2d`] sharing a [`RenderLayers`] layer
+/// with it.
+#[derive(Component, Reflect, Clone, Copy, Debug)]
+#[reflect(Component, Default)]
+pub struct PointLight2d {
+    pub color: Color,
+    /// A multiplier for the light color.
+    pub intensity: f32,
+    /// The distance at which the light stops lighting.
+    pub range: f32,
+    /// The radius of the light source, which sets how soft the edges of its shadows are.
+    pub radius: f32,
+    /// The height of the light above the scene, which sets how steep the light hits
+    /// normal-mapped sprites.
+    pub height: f32,
+    /// Whether the [`LightOccluder2d`]s cast shadows from this light.
+    pub shadows_enabled: bool,
+}
+
+impl Default for PointLight2d {
+    fn default() -> Self {
+        Self {
+            color: Color::WHITE,
+            intensity: 1.0,
+            range: 200.0,
+            radius: 5.0,
+            height: 50.0,
+            shadows_enabled: false,
+        }
+    }
+}
+
+/// A light that shines in a cone from a point in a 2D scene, along the local Y axis of its
+/// transform.
+///
+/// Apart from its cone, it lights the scene like a [`PointLight2d`].
+#[derive(Component, Reflect, Clone, Copy, Debug)]
+#[reflect(Component, Default)]
+pub struct SpotLight2d {
+    pub color: Color,
+    /// A multiplier for the light color.
+    pub intensity: f32,
+    /// The distance at which the light stops lighting.
+    pub range: f32,
+    /// The radius of the light source, which sets how soft the edges of its shadows are.
+    pub radius: f32,
+    /// The height of the light above the scene, which sets how steep the light hits
+    /// normal-mapped sprites.
+    pub height: f32,
+    /// Whether the [`LightOccluder2d`]s cast shadows from this light.
+    pub shadows_enabled: bool,
+    /// Angle from the direction of the light to the edge of the fully lit inner cone, in radians.
+    pub inner_angle: f32,
+    /// Angle from the direction of the light to the edge of the cone, in radians. The light
+    /// fades from the inner cone to the outer cone.
+    pub outer_angle: f32,
+}
+
+impl Default for SpotLight2d {
+    fn default() -> Self {
+        Self {
+            color: Color::WHITE,
+            intensity: 1.0,
+            range: 200.0,
+            radius: 5.0,
+            height: 50.0,
+            shadows_enabled: false,
+            inner_angle: 0.0,
+            outer_angle: std::f32::consts::FRAC_PI_4,
+        }
+    }
+}
+
+/// A shape that casts shadows from the [`PointLight2d`]s and [`SpotLight2d`]s with
+/// shadows enabled, placed by the transform of its entity.
+///
+/// The pixels inside an occluder aren't shadowed by it, so a sprite drawn over its occluder is
+/// still lit. Occluders only shadow the views of the 2d cameras sharing a [`RenderLayers`] layer
+/// with them.
+#[derive(Component, Clone, Copy, Debug, PartialEq)]
+pub enum LightOccluder2d {
+    Circle(Circle),
+    Rectangle(Rectangle),
+    /// A capsule along the local Y axis.
+    Capsule(Capsule2d),
+}
+
+impl From<Circle> for LightOccluder2d {
+    fn from(circle: Circle) -> Self {
+        Self::Circle(circle)
+    }
+}
+
+impl From<Rectangle> for LightOccluder2d {
+    fn from(rectangle: Rectangle) -> Self {
+        Self::Rectangle(rectangle)
+    }
+}
+
+impl From<Capsule2d> for LightOccluder2d {
+    fn from(capsule: Capsule2d) -> Self {
+        Self::Capsule(capsule)
+    }
+}
+
+impl LightOccluder2d {
+    /// The occluder as a rectangle with the given half size, rounded by the given radius.
+    fn rounded_rectangle(&self) -> (Vec2, f32) {
+        match *self {
+            Self::Circle(circle) => (Vec2::ZERO, circle.radius),
+            Self::Rectangle(rectangle) => (rectangle.half_size, 0.0),
+            Self::Capsule(capsule) => (Vec2::new(0.0, capsule.half_length), capsule.radius),
+        }
+    }
+}
+
+/// The normal map of a [`Sprite`](crate::Sprite), used to light it when it's rendered by a 2d
+/// camera with an [`AmbientLight2d`].
+///
+/// The normal map is sampled with the same coordinates as the sprite image, so that texture
+/// atlases and flipped sprites are lit correctly. Its red and green channels are the X and Y
+/// components of the normals in the space of the sprite, with the Y axis pointing up.
+///
+/// Normal maps are rendered in the order of the sprites, but other 2D items such as
+/// [`Mesh2d`](crate::Mesh2dHandle)es don't hide the normal maps of the sprites under them.
+#[derive(Component, Reflect, Clone, Debug, Default)]
+#[reflect(Component, Default)]
+pub struct SpriteNormalMap(pub Handle<Image>);
+
+/// A component bundle for [`PointLight2d`] entities.
+#[derive(Bundle, Clone, Debug, Default)]
+pub struct PointLight2dBundle {
+    pub point_light: PointLight2d,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    /// Enables or disables the light
+    pub visibility: Visibility,
+    /// Inherited visibility of an entity.
+    pub inherited_visibility: InheritedVisibility,
+    /// Algorithmically-computed indication of whether an entity is visible and should be extracted for rendering
+    pub view_visibility: ViewVisibility,
+}
+
+/// A component bundle for [`SpotLight2d`] entities.
+#[derive(Bundle, Clone, Debug, Default)]
+pub struct SpotLight2dBundle {
+    pub spot_light: SpotLight2d,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    /// Enables or disables the light
+    pub visibility: Visibility,
+    /// Inherited visibility of an entity.
+    pub inherited_visibility: InheritedVisibility,
+    /// Algorithmically-computed indication of whether an entity is visible and should be extracted for rendering
+    pub view_visibility: ViewVisibility,
+}
+
+/// A component bundle for [`LightOccluder2d`] entities.
+#[derive(Bundle, Clone, Debug)]
+pub struct LightOccluder2dBundle {
+    pub occluder: LightOccluder2d,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    /// Enables or disables the occluder
+    pub visibility: Visibility,
+    /// Inherited visibility of an entity.
+    pub inherited_visibility: InheritedVisibility,
+    /// Algorithmically-computed indication of whether an entity is visible and should be extracted for rendering
+    pub view_visibility: ViewVisibility,
+}
+
+impl LightOccluder2dBundle {
+    pub fn new(occluder: impl Into<LightOccluder2d>, transform: Transform) -> Self {
+        Self {
+            occluder: occluder.into(),
+            transform,
+            global_transform: Default::default(),
+            visibility: Default::default(),
+            inherited_visibility: Default::default(),
+            view_visibility: Default::default(),
+        }
+    }
+}
+
+/// A [`PointLight2d`] or a [`SpotLight2d`] extracted to the render world.
+pub struct ExtractedLight2d {
+    pub position: Vec2,
+    /// The direction of a spot light.
+    pub direction: Vec2,
+    /// The color of the light multiplied by its intensity.
+    pub color: LinearRgba,
+    pub range: f32,
+    pub radius: f32,
+    pub height: f32,
+    pub shadows_enabled: bool,
+    /// The inner and outer angles of a spot light.
+    pub spot_angles: Option<(f32, f32)>,
+    pub render_layers: RenderLayers,
+}
+
+/// A [`LightOccluder2d`] extracted to the render world, as a rotated rounded rectangle.
+pub struct ExtractedLightOccluder2d {
+    pub center: Vec2,
+    /// The cosine and the sine of the rotation of the occluder.
+    pub rotation: Vec2,
+    pub half_size: Vec2,
+    pub radius: f32,
+    pub render_layers: RenderLayers,
+}
+
+#[derive(Resource, Default)]
+pub struct ExtractedLights2d {
+    pub lights: Vec<ExtractedLight2d>,
+    pub occluders: Vec<ExtractedLightOccluder2d>,
+}
+
+pub fn extract_lights_2d(
+    mut extracted_lights: ResMut<ExtractedLights2d>,
+    point_lights: Extract<
+        Query<(
+            &PointLight2d,
+            &GlobalTransform,
+            &InheritedVisibility,
+            Option<&RenderLayers>,
+        )>,
+    >,
+    spot_lights: Extract<
+        Query<(
+            &SpotLight2d,
+            &GlobalTransform,
+            &InheritedVisibility,
+            Option<&RenderLayers>,
+        )>,
+    >,
+    occluders: Extract<
+        Query<(
+            &LightOccluder2d,
+            &GlobalTransform,
+            &InheritedVisibility,
+            Option<&RenderLayers>,
+        )>,
+    >,
+) {
+    let ExtractedLights2d {
+        lights,
+        occluders: extracted_occluders,
+    } = &mut *extracted_lights;
+    lights.clear();
+    extracted_occluders.clear();
+
+    for (light, transform, visibility, render_layers) in &point_lights {
+        if !visibility.get() {
+            continue;
+        }
+        lights.push(ExtractedLight2d {
+            position: transform.translation().xy(),
+            direction: Vec2::Y,
+            color: LinearRgba::from(light.color) * light.intensity,
+            range: light.range,
+            radius: light.radius,
+            height: light.height,
+            shadows_enabled: light.shadows_enabled,
+            spot_angles: None,
+            render_layers: render_layers.copied().unwrap_or_default(),
+        });
+    }
+
+    for (light, transform, visibility, render_layers) in &spot_lights {
+        if !visibility.get() {
+            continue;
+        }
+        lights.push(ExtractedLight2d {
+            position: transform.translation().xy(),
+            direction: transform.up().xy().try_normalize().unwrap_or(Vec2::Y),
+            color: LinearRgba::from(light.color) * light.intensity,
+            range: light.range,
+            radius: light.radius,
+            height: light.height,
+            shadows_enabled: light.shadows_enabled,
+            spot_angles: Some((light.inner_angle, light.outer_angle)),
+            render_layers: render_layers.copied().unwrap_or_default(),
+        });
+    }
+
+    for (occluder, transform, visibility, render_layers) in &occluders {
+        if !visibility.get() {
+            continue;
+        }
+        let (scale, _, translation) = transform.to_scale_rotation_translation();
+        let (half_size, radius) = occluder.rounded_rectangle();
+        extracted_occluders.push(ExtractedLightOccluder2d {
+            center: translation.xy(),
+            rotation: transform.right().xy().try_normalize().unwrap_or(Vec2::X),
+            half_size: half_size * scale.xy().abs(),
+            radius: radius * scale.xy().abs().min_element(),
+            render_layers: render_layers.copied().unwrap_or_default(),
+        });
+    }
+}
+
+pub fn extract_sprite_normal_maps(
+    mut sprite_normal_map_instances: ResMut<SpriteNormalMapInstances>,
+    query: Extract<Query<(Entity, &ViewVisibility, &SpriteNormalMap)>>,
+) {
+    for (entity, view_visibility, normal_map) in &query {
+        if view_visibility.get() {
+            sprite_normal_map_instances.insert(entity, normal_map.0.id());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::system::RunSystemOnce;
+    use bevy_math::{Quat, Vec3};
+    use bevy_render::MainWorld;
+    use std::f32::consts::FRAC_PI_2;
+
+    fn extract_occluders(occluders: Vec<(LightOccluder2d, Transform, bool)>) -> ExtractedLights2d {
+        let mut main_world = MainWorld::default();
+        for (occluder, transform, visible) in occluders {
+            main_world.spawn((
+                occluder,
+                GlobalTransform::from(transform),
+                if visible {
+                    InheritedVisibility::VISIBLE
+                } else {
+                    InheritedVisibility::HIDDEN
+                },
+            ));
+        }
+        let mut render_world = World::new();
+        render_world.insert_resource(main_world);
+        render_world.init_resource::<ExtractedLights2d>();
+        render_world.run_system_once(extract_lights_2d);
+        render_world.remove_resource::<ExtractedLights2d>().unwrap()
+    }
+
+    fn find(extracted: &ExtractedLights2d, x: f32) -> &ExtractedLightOccluder2d {
+        extracted
+            .occluders
+            .iter()
+            .find(|occluder| occluder.center.x == x)
+            .unwrap()
+    }
+
+    #[test]
+    fn occluders_are_extracted_as_rounded_rectangles() {
+        let extracted = extract_occluders(vec![
+            (
+                Circle::new(2.0).into(),
+                Transform::from_xyz(0.0, 1.0, 0.0).with_scale(Vec3::new(3.0, 4.0, 1.0)),
+                true,
+            ),
+            (
+                Rectangle::new(4.0, 2.0).into(),
+                Transform::from_xyz(10.0, 0.0, 5.0)
+                    .with_rotation(Quat::from_rotation_z(FRAC_PI_2))
+                    .with_scale(Vec3::new(2.0, -1.0, 1.0)),
+                true,
+            ),
+            (
+                Capsule2d::new(1.0, 4.0).into(),
+                Transform::from_xyz(20.0, 0.0, 0.0).with_scale(Vec3::splat(2.0)),
+                true,
+            ),
+            (
+                Circle::new(1.0).into(),
+                Transform::from_xyz(30.0, 0.0, 0.0),
+                false,
+            ),
+        ]);
+        assert_eq!(extracted.occluders.len(), 3);
+
+        // A circle is a rectangle without size, and its radius is scaled by the smallest scale
+        let circle = find(&extracted, 0.0);
+        assert_eq!(circle.center, Vec2::new(0.0, 1.0));
+        assert_eq!(circle.half_size, Vec2::ZERO);
+        assert_eq!(circle.radius, 6.0);
+        assert_eq!(circle.rotation, Vec2::X);
+
+        let rectangle = find(&extracted, 10.0);
+        assert!(rectangle.half_size.abs_diff_eq(Vec2::new(4.0, 1.0), 1e-5));
+        assert_eq!(rectangle.radius, 0.0);
+        assert!(rectangle.rotation.abs_diff_eq(Vec2::Y, 1e-6));
+
+        // A capsule is a segment along the Y axis, rounded by its radius
+        let capsule = find(&extracted, 20.0);
+        assert_eq!(capsule.half_size, Vec2::new(0.0, 4.0));
+        assert_eq!(capsule.radius, 2.0);
+    }
+}
@@ -0,0 +1,787 @@
+use bevy_color::{ColorToComponents, LinearRgba};
+use bevy_core_pipeline::{
+    core_2d::Transparent2d, fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+};
+use bevy_ecs::{prelude::*, query::QueryItem};
+use bevy_math::{Rect, Vec2, Vec3Swizzles, Vec4};
+use bevy_render::{
+    camera::ExtractedCamera,
+    render_graph::{NodeRunError, RenderGraphContext, ViewNode},
+    render_phase::SortedRenderPhase,
+    render_resource::{
+        binding_types::{texture_2d, uniform_buffer},
+        *,
+    },
+    renderer::{RenderContext, RenderDevice, RenderQueue},
+    texture::{BevyDefault, CachedTexture, TextureCache},
+    view::{ExtractedView, RenderLayers, ViewTarget, ViewUniform, ViewUniformOffset, ViewUniforms},
+};
+use bevy_utils::warn_once;
+
+use super::{
+    AmbientLight2d, ExtractedLight2d, ExtractedLightOccluder2d, ExtractedLights2d,
+    LIGHT_2D_COMPOSITE_SHADER_HANDLE, LIGHT_2D_NORMALS_SHADER_HANDLE, LIGHT_2D_SHADER_HANDLE,
+    MAX_LIGHTS_2D, MAX_LIGHT_OCCLUDERS_2D,
+};
+use crate::{ImageBindGroups, SpriteBatch, SpriteMeta, SpritePipeline, SpritePipelineKey};
+
+/// The format of the texture the normals of the sprites are rendered to.
+///
+/// Its red and green channels are the X and Y components of the normals, and its blue channel
+/// is how much of the pixel is covered by normal-mapped sprites.
+pub const LIGHT_2D_NORMAL_TEXTURE_FORMAT: TextureFormat = TextureFormat::Rgba8Unorm;
+/// The format of the texture the light of a view is accumulated in.
+pub const LIGHT_2D_TEXTURE_FORMAT: TextureFormat = TextureFormat::Rgba16Float;
+
+const LIGHT_2D_FLAGS_SHADOWS_ENABLED_BIT: u32 = 1 << 0;
+
+#[derive(Clone, Copy, ShaderType, Default, Debug)]
+pub struct GpuLight2d {
+    color: Vec4,
+    position: Vec2,
+    direction: Vec2,
+    range: f32,
+    radius: f32,
+    height: f32,
+    spot_scale: f32,
+    spot_offset: f32,
+    flags: u32,
+}
+
+impl From<&ExtractedLight2d> for GpuLight2d {
+    fn from(light: &ExtractedLight2d) -> Self {
+        // Point lights have no cone: their spot attenuation is always 1
+        let (spot_scale, spot_offset) = match light.spot_angles {
+            Some((inner_angle, outer_angle)) => {
+                let cos_outer = outer_angle.cos();
+                let spot_scale = 1.0 / f32::max(inner_angle.cos() - cos_outer, 1e-4);
+                (spot_scale, -cos_outer * spot_scale)
+            }
+            None => (0.0, 1.0),
+        };
+
+        Self {
+            color: light.color.to_vec4(),
+            position: light.position,
+            direction: light.direction,
+            range: light.range,
+            radius: light.radius,
+            height: light.height,
+            spot_scale,
+            spot_offset,
+            flags: if light.shadows_enabled {
+                LIGHT_2D_FLAGS_SHADOWS_ENABLED_BIT
+            } else {
+                0
+            },
+        }
+    }
+}
+
+#[derive(Clone, Copy, ShaderType, Default, Debug)]
+pub struct GpuLightOccluder2d {
+    center: Vec2,
+    rotation: Vec2,
+    half_size: Vec2,
+    radius: f32,
+}
+
+impl From<&ExtractedLightOccluder2d> for GpuLightOccluder2d {
+    fn from(occluder: &ExtractedLightOccluder2d) -> Self {
+        Self {
+            center: occluder.center,
+            rotation: occluder.rotation,
+            half_size: occluder.half_size,
+            radius: occluder.radius,
+        }
+    }
+}
+
+/// The lights and the occluders of a view.
+#[derive(ShaderType)]
+pub struct GpuLights2d {
+    ambient: Vec4,
+    lights: [GpuLight2d; MAX_LIGHTS_2D],
+    occluders: [GpuLightOccluder2d; MAX_LIGHT_OCCLUDERS_2D],
+    n_lights: u32,
+    n_occluders: u32,
+}
+
+impl GpuLights2d {
+    /// The lights and the occluders of a view seeing the given rectangle of the 2D world.
+    ///
+    /// The lights that don't share a layer with the view or whose range doesn't reach it are
+    /// ignored, and only the first [`MAX_LIGHTS_2D`] lights are kept. The occluders are kept
+    /// where the kept lights reach.
+    pub fn new(
+        ambient: LinearRgba,
+        extracted: &ExtractedLights2d,
+        view_rect: Rect,
+        render_layers: &RenderLayers,
+    ) -> Self {
+        let mut gpu_lights = GpuLights2d {
+            ambient: ambient.to_vec4(),
+            lights: [GpuLight2d::default(); MAX_LIGHTS_2D],
+            occluders: [GpuLightOccluder2d::default(); MAX_LIGHT_OCCLUDERS_2D],
+            n_lights: 0,
+            n_occluders: 0,
+        };
+
+        let mut max_range = 0.0f32;
+        let lights = extracted.lights.iter().filter(|light| {
+            render_layers.intersects(&light.render_layers)
+                && circle_intersects_rect(light.position, light.range, view_rect)
+        });
+        for light in lights {
+            if gpu_lights.n_lights as usize == MAX_LIGHTS_2D {
+                warn_once!(
+                    "A view is lit by more than {MAX_LIGHTS_2D} 2d lights, the following ones are ignored"
+                );
+                break;
+            }
+            gpu_lights.lights[gpu_lights.n_lights as usize] = light.into();
+            gpu_lights.n_lights += 1;
+            max_range = max_range.max(light.range);
+        }
+
+        // The occluders only cast shadows where the lights of the view reach
+        let lit_rect = view_rect.inset(max_range);
+        let occluders = extracted.occluders.iter().filter(|occluder| {
+            render_layers.intersects(&occluder.render_layers)
+                && circle_intersects_rect(
+                    occluder.center,
+                    occluder.half_size.length() + occluder.radius,
+                    lit_rect,
+                )
+        });
+        for occluder in occluders {
+            if gpu_lights.n_occluders as usize == MAX_LIGHT_OCCLUDERS_2D {
+                warn_once!(
+                    "A view has more than {MAX_LIGHT_OCCLUDERS_2D} 2d light occluders, the following ones are ignored"
+                );
+                break;
+            }
+            gpu_lights.occluders[gpu_lights.n_occluders as usize] = occluder.into();
+            gpu_lights.n_occluders += 1;
+        }
+
+        gpu_lights
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct Light2dMeta {
+    pub view_lights: DynamicUniformBuffer<GpuLights2d>,
+}
+
+#[derive(Component)]
+pub struct ViewLights2dUniformOffset {
+    pub offset: u32,
+}
+
+/// The textures the normals and the light of a view are rendered to.
+#[derive(Component)]
+pub struct ViewLight2dTextures {
+    pub normals: CachedTexture,
+    pub lights: CachedTexture,
+}
+
+#[derive(Component)]
+pub struct ViewLight2dPipelines {
+    pub normals: CachedRenderPipelineId,
+    pub normal_mapped_normals: CachedRenderPipelineId,
+    pub lights: CachedRenderPipelineId,
+    pub composite: CachedRenderPipelineId,
+}
+
+#[derive(Component)]
+pub struct ViewLight2dBindGroup(pub BindGroup);
+
+#[derive(Resource)]
+pub struct Light2dPipeline {
+    sprite_pipeline: SpritePipeline,
+    lights_layout: BindGroupLayout,
+    composite_layout: BindGroupLayout,
+}
+
+impl FromWorld for Light2dPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let lights_layout = render_device.create_bind_group_layout(
+            "light_2d_lights_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    uniform_buffer::<ViewUniform>(true),
+                    uniform_buffer::<GpuLights2d>(true),
+                    texture_2d(TextureSampleType::Float { filterable: false }),
+                ),
+            ),
+        );
+
+        let composite_layout = render_device.create_bind_group_layout(
+            "light_2d_composite_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::FRAGMENT,
+                (
+                    texture_2d(TextureSampleType::Float { filterable: false }),
+                    texture_2d(TextureSampleType::Float { filterable: false }),
+                ),
+            ),
+        );
+
+        Light2dPipeline {
+            sprite_pipeline: world.resource::<SpritePipeline>().clone(),
+            lights_layout,
+            composite_layout,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum Light2dPipelineKey {
+    /// Renders the normals of sprite batches, sampling their normal map when they have one.
+    Normals { normal_mapped: bool },
+    /// Accumulates the light of a view.
+    Lights,
+    /// Multiplies the rendered view by its light.
+    Composite { hdr: bool },
+}
+
+impl SpecializedRenderPipeline for Light2dPipeline {
+    type Key = Light2dPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        match key {
+            Light2dPipelineKey::Normals { normal_mapped } => {
+                // The normals are drawn with the instances of the sprite batches
+                let mut descriptor = self
+                    .sprite_pipeline
+                    .specialize(SpritePipelineKey::from_msaa_samples(1));
+                let shader_defs = if normal_mapped {
+                    vec!["NORMAL_MAP".into()]
+                } else {
+                    Vec::new()
+                };
+                descriptor.vertex.shader = LIGHT_2D_NORMALS_SHADER_HANDLE;
+                descriptor.vertex.shader_defs.clone_from(&shader_defs);
+                descriptor.fragment = Some(FragmentState {
+                    shader: LIGHT_2D_NORMALS_SHADER_HANDLE,
+                    shader_defs,
+                    entry_point: "fragment".into(),
+                    targets: vec![Some(ColorTargetState {
+                        format: LIGHT_2D_NORMAL_TEXTURE_FORMAT,
+                        blend: Some(BlendState::ALPHA_BLENDING),
+                        write_mask: ColorWrites::ALL,
+                    })],
+                });
+                if normal_mapped {
+                    descriptor
+                        .layout
+                        .push(self.sprite_pipeline.material_layout.clone());
+                }
+                descriptor.label = Some("light_2d_normals_pipeline".into());
+                descriptor
+            }
+            Light2dPipelineKey::Lights => RenderPipelineDescriptor {
+                label: Some("light_2d_lights_pipeline".into()),
+                layout: vec![self.lights_layout.clone()],
+                vertex: fullscreen_shader_vertex_state(),
+                fragment: Some(FragmentState {
+                    shader: LIGHT_2D_SHADER_HANDLE,
+                    shader_defs: vec![
+                        ShaderDefVal::UInt("MAX_LIGHTS_2D".into(), MAX_LIGHTS_2D as u32),
+                        ShaderDefVal::UInt(
+                            "MAX_LIGHT_OCCLUDERS_2D".into(),
+                            MAX_LIGHT_OCCLUDERS_2D as u32,
+                        ),
+                    ],
+                    entry_point: "fragment".into(),
+                    targets: vec![Some(ColorTargetState {
+                        format: LIGHT_2D_TEXTURE_FORMAT,
+                        blend: None,
+                        write_mask: ColorWrites::ALL,
+                    })],
+                }),
+                primitive: PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: MultisampleState::default(),
+                push_constant_ranges: Vec::new(),
+            },
+            Light2dPipelineKey::Composite { hdr } => RenderPipelineDescriptor {
+                label: Some("light_2d_composite_pipeline".into()),
+                layout: vec![self.composite_layout.clone()],
+                vertex: fullscreen_shader_vertex_state(),
+                fragment: Some(FragmentState {
+                    shader: LIGHT_2D_COMPOSITE_SHADER_HANDLE,
+                    shader_defs: Vec::new(),
+                    entry_point: "fragment".into(),
+                    targets: vec![Some(ColorTargetState {
+                        format: if hdr {
+                            ViewTarget::TEXTURE_FORMAT_HDR
+                        } else {
+                            TextureFormat::bevy_default()
+                        },
+                        blend: None,
+                        write_mask: ColorWrites::ALL,
+                    })],
+                }),
+                primitive: PrimitiveState::default(),
+                depth_stencil: None,
+                multisample: MultisampleState::default(),
+                push_constant_ranges: Vec::new(),
+            },
+        }
+    }
+}
+
+pub fn prepare_light_2d_pipelines(
+    mut commands: Commands,
+    pipeline_cache: Res<PipelineCache>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<Light2dPipeline>>,
+    light_2d_pipeline: Res<Light2dPipeline>,
+    views: Query<(Entity, &ExtractedView), With<AmbientLight2d>>,
+) {
+    for (entity, view) in &views {
+        let mut specialize = |key| pipelines.specialize(&pipeline_cache, &light_2d_pipeline, key);
+        commands.entity(entity).insert(ViewLight2dPipelines {
+            normals: specialize(Light2dPipelineKey::Normals {
+                normal_mapped: false,
+            }),
+            normal_mapped_normals: specialize(Light2dPipelineKey::Normals {
+                normal_mapped: true,
+            }),
+            lights: specialize(Light2dPipelineKey::Lights),
+            composite: specialize(Light2dPipelineKey::Composite { hdr: view.hdr }),
+        });
+    }
+}
+
+pub fn prepare_lights_2d(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    mut light_meta: ResMut<Light2dMeta>,
+    extracted_lights: Res<ExtractedLights2d>,
+    views: Query<(
+        Entity,
+        &ExtractedView,
+        &AmbientLight2d,
+        Option<&RenderLayers>,
+    )>,
+) {
+    let Some(mut writer) =
+        light_meta
+            .view_lights
+            .get_writer(views.iter().len(), &render_device, &render_queue)
+    else {
+        return;
+    };
+
+    for (entity, view, ambient_light, render_layers) in &views {
+        let render_layers = render_layers.copied().unwrap_or_default();
+        let gpu_lights = GpuLights2d::new(
+            LinearRgba::from(ambient_light.color) * ambient_light.brightness,
+            &extracted_lights,
+            view_world_rect(view),
+            &render_layers,
+        );
+
+        commands.entity(entity).insert(ViewLights2dUniformOffset {
+            offset: writer.write(&gpu_lights),
+        });
+    }
+}
+
+/// The rectangle of the 2D world seen by a view.
+pub(crate) fn view_world_rect(view: &ExtractedView) -> Rect {
+    let clip_from_world = view
+        .view_projection
+        .unwrap_or_else(|| view.projection * view.transform.compute_matrix().inverse());
+    let world_from_clip = clip_from_world.inverse();
+    [
+        Vec2::new(-1.0, -1.0),
+        Vec2::new(1.0, -1.0),
+        Vec2::new(-1.0, 1.0),
+        Vec2::new(1.0, 1.0),
+    ]
+    .into_iter()
+    .map(|corner| world_from_clip.project_point3(corner.extend(0.5)).xy())
+    .fold(
+        Rect {
+            min: Vec2::MAX,
+            max: Vec2::MIN,
+        },
+        |rect, corner| rect.union_point(corner),
+    )
+}
+
+fn circle_intersects_rect(center: Vec2, radius: f32, rect: Rect) -> bool {
+    center.clamp(rect.min, rect.max).distance_squared(center) <= radius * radius
+}
+
+pub fn prepare_light_2d_textures(
+    mut commands: Commands,
+    mut texture_cache: ResMut<TextureCache>,
+    render_device: Res<RenderDevice>,
+    views: Query<(Entity, &ExtractedCamera), With<AmbientLight2d>>,
+) {
+    for (entity, camera) in &views {
+        let Some(target_size) = camera.physical_target_size else {
+            continue;
+        };
+        let size = Extent3d {
+            width: target_size.x,
+            height: target_size.y,
+            depth_or_array_layers: 1,
+        };
+        let mut get_texture = |label, format| {
+            texture_cache.get(
+                &render_device,
+                TextureDescriptor {
+                    label: Some(label),
+                    size,
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: TextureDimension::D2,
+                    format,
+                    usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+                    view_formats: &[],
+                },
+            )
+        };
+
+        commands.entity(entity).insert(ViewLight2dTextures {
+            normals: get_texture("light_2d_normal_texture", LIGHT_2D_NORMAL_TEXTURE_FORMAT),
+            lights: get_texture("light_2d_texture", LIGHT_2D_TEXTURE_FORMAT),
+        });
+    }
+}
+
+pub fn prepare_light_2d_bind_groups(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    light_2d_pipeline: Res<Light2dPipeline>,
+    light_meta: Res<Light2dMeta>,
+    view_uniforms: Res<ViewUniforms>,
+    views: Query<(Entity, &ViewLight2dTextures), With<ViewLights2dUniformOffset>>,
+) {
+    let (Some(view_binding), Some(lights_binding)) = (
+        view_uniforms.uniforms.binding(),
+        light_meta.view_lights.binding(),
+    ) else {
+        return;
+    };
+
+    for (entity, textures) in &views {
+        let bind_group = render_device.create_bind_group(
+            "light_2d_lights_bind_group",
+            &light_2d_pipeline.lights_layout,
+            &BindGroupEntries::sequential((
+                view_binding.clone(),
+                lights_binding.clone(),
+                &textures.normals.default_view,
+            )),
+        );
+        commands
+            .entity(entity)
+            .insert(ViewLight2dBindGroup(bind_group));
+    }
+}
+
+/// Renders the normals of the sprites of a view, accumulates the light of the view from its
+/// normals and its occluders, then multiplies the rendered view by its light.
+#[derive(Default)]
+pub struct Light2dNode;
+
+impl ViewNode for Light2dNode {
+    type ViewQuery = (
+        &'static ExtractedCamera,
+        &'static ViewTarget,
+        &'static SortedRenderPhase<Transparent2d>,
+        &'static ViewUniformOffset,
+        &'static ViewLights2dUniformOffset,
+        &'static ViewLight2dTextures,
+        &'static ViewLight2dPipelines,
+        &'static ViewLight2dBindGroup,
+    );
+
+    fn run<'w>(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext<'w>,
+        (
+            camera,
+            target,
+            transparent_phase,
+            view_uniform_offset,
+            lights_uniform_offset,
+            textures,
+            pipelines,
+            lights_bind_group,
+        ): QueryItem<'w, Self::ViewQuery>,
+        world: &'w World,
+    ) -> Result<(), NodeRunError> {
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let light_2d_pipeline = world.resource::<Light2dPipeline>();
+        let sprite_meta = world.resource::<SpriteMeta>();
+        let image_bind_groups = world.resource::<ImageBindGroups>();
+
+        let (
+            Some(normals_pipeline),
+            Some(normal_mapped_normals_pipeline),
+            Some(lights_pipeline),
+            Some(composite_pipeline),
+        ) = (
+            pipeline_cache.get_render_pipeline(pipelines.normals),
+            pipeline_cache.get_render_pipeline(pipelines.normal_mapped_normals),
+            pipeline_cache.get_render_pipeline(pipelines.lights),
+            pipeline_cache.get_render_pipeline(pipelines.composite),
+        )
+        else {
+            return Ok(());
+        };
+
+        {
+            let mut normal_pass = render_context.begin_tracked_render_pass(RenderPassDescriptor {
+                label: Some("light_2d_normal_pass"),
+                color_attachments: &[Some(RenderPassColorAttachment {
+                    view: &textures.normals.default_view,
+                    resolve_target: None,
+                    ops: Operations {
+                        // Pixels without sprites face the camera
+                        load: LoadOp::Clear(LinearRgba::new(0.5, 0.5, 0.0, 0.0).into()),
+                        store: StoreOp::Store,
+                    },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+            });
+            if let Some(viewport) = camera.viewport.as_ref() {
+                normal_pass.set_camera_viewport(viewport);
+            }
+
+            if let (Some(view_bind_group), Some(index_buffer), Some(instance_buffer)) = (
+                sprite_meta.view_bind_group.as_ref(),
+                sprite_meta.sprite_index_buffer.buffer(),
+                sprite_meta.sprite_instance_buffer(),
+            ) {
+                normal_pass.set_bind_group(0, view_bind_group, &[view_uniform_offset.offset]);
+                normal_pass.set_index_buffer(index_buffer.slice(..), 0, IndexFormat::Uint32);
+                normal_pass.set_vertex_buffer(0, instance_buffer.slice(..));
+
+                // Every sprite batch is drawn in order, so that sprites without a normal map
+                // hide the normals of the sprites under them
+                for item in &transparent_phase.items {
+                    let Some(batch) = world.get::<SpriteBatch>(item.entity) else {
+                        continue;
+                    };
+                    let Some(image_bind_group) =
+                        image_bind_groups.values.get(&batch.image_handle_id)
+                    else {
+                        continue;
+                    };
+
+                    match batch
+                        .normal_map_id
+                        .and_then(|id| image_bind_groups.values.get(&id))
+                    {
+                        Some(normal_map_bind_group) => {
+                            normal_pass.set_render_pipeline(normal_mapped_normals_pipeline);
+                            normal_pass.set_bind_group(2, normal_map_bind_group, &[]);
+                        }
+                        None => normal_pass.set_render_pipeline(normals_pipeline),
+                    }
+                    normal_pass.set_bind_group(1, image_bind_group, &[]);
+                    normal_pass.draw_indexed(0..6, 0, batch.range.clone());
+                }
+            }
+        }
+
+        {
+            let mut lights_pass =
+                render_context
+                    .command_encoder()
+                    .begin_render_pass(&RenderPassDescriptor {
+                        label: Some("light_2d_lights_pass"),
+                        color_attachments: &[Some(RenderPassColorAttachment {
+                            view: &textures.lights.default_view,
+                            resolve_target: None,
+                            ops: Operations {
+                                load: LoadOp::Clear(Default::default()),
+                                store: StoreOp::Store,
+                            },
+                        })],
+                        depth_stencil_attachment: None,
+                        timestamp_writes: None,
+                        occlusion_query_set: None,
+                    });
+            lights_pass.set_pipeline(lights_pipeline);
+            lights_pass.set_bind_group(
+                0,
+                &lights_bind_group.0,
+                &[view_uniform_offset.offset, lights_uniform_offset.offset],
+            );
+            lights_pass.draw(0..3, 0..1);
+        }
+
+        let post_process = target.post_process_write();
+        let composite_bind_group = render_context.render_device().create_bind_group(
+            "light_2d_composite_bind_group",
+            &light_2d_pipeline.composite_layout,
+            &BindGroupEntries::sequential((post_process.source, &textures.lights.default_view)),
+        );
+
+        let mut composite_pass =
+            render_context
+                .command_encoder()
+                .begin_render_pass(&RenderPassDescriptor {
+                    label: Some("light_2d_composite_pass"),
+                    color_attachments: &[Some(RenderPassColorAttachment {
+                        view: post_process.destination,
+                        resolve_target: None,
+                        ops: Operations {
+                            load: LoadOp::Clear(Default::default()),
+                            store: StoreOp::Store,
+                        },
+                    })],
+                    depth_stencil_attachment: None,
+                    timestamp_writes: None,
+                    occlusion_query_set: None,
+                });
+        composite_pass.set_pipeline(composite_pipeline);
+        composite_pass.set_bind_group(0, &composite_bind_group, &[]);
+        composite_pass.draw(0..3, 0..1);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn light(x: f32, range: f32, render_layers: RenderLayers) -> ExtractedLight2d {
+        ExtractedLight2d {
+            position: Vec2::new(x, 0.0),
+            direction: Vec2::Y,
+            color: LinearRgba::WHITE,
+            range,
+            radius: 0.0,
+            height: 0.0,
+            shadows_enabled: true,
+            spot_angles: None,
+            render_layers,
+        }
+    }
+
+    fn occluder(x: f32, half_size: Vec2) -> ExtractedLightOccluder2d {
+        ExtractedLightOccluder2d {
+            center: Vec2::new(x, 0.0),
+            rotation: Vec2::X,
+            half_size,
+            radius: 0.0,
+            render_layers: RenderLayers::default(),
+        }
+    }
+
+    fn positions(gpu_lights: &GpuLights2d) -> Vec<f32> {
+        gpu_lights.lights[..gpu_lights.n_lights as usize]
+            .iter()
+            .map(|light| light.position.x)
+            .collect()
+    }
+
+    const VIEW_RECT: Rect = Rect {
+        min: Vec2::splat(-100.0),
+        max: Vec2::splat(100.0),
+    };
+
+    #[test]
+    fn lights_are_filtered_by_range_and_render_layers() {
+        let extracted = ExtractedLights2d {
+            lights: vec![
+                light(0.0, 10.0, RenderLayers::default()),
+                // Outside of the view, but its range reaches it
+                light(150.0, 60.0, RenderLayers::default()),
+                // Its range doesn't reach the view
+                light(-300.0, 150.0, RenderLayers::default()),
+                light(50.0, 10.0, RenderLayers::layer(1)),
+            ],
+            occluders: Vec::new(),
+        };
+
+        let gpu_lights = GpuLights2d::new(
+            LinearRgba::BLACK,
+            &extracted,
+            VIEW_RECT,
+            &RenderLayers::default(),
+        );
+        assert_eq!(positions(&gpu_lights), [0.0, 150.0]);
+
+        let gpu_lights = GpuLights2d::new(
+            LinearRgba::BLACK,
+            &extracted,
+            VIEW_RECT,
+            &RenderLayers::from_layers(&[0, 1]),
+        );
+        assert_eq!(positions(&gpu_lights), [0.0, 150.0, 50.0]);
+
+        let gpu_lights = GpuLights2d::new(
+            LinearRgba::BLACK,
+            &extracted,
+            VIEW_RECT,
+            &RenderLayers::layer(2),
+        );
+        assert_eq!(gpu_lights.n_lights, 0);
+    }
+
+    #[test]
+    fn lights_are_capped() {
+        let extracted = ExtractedLights2d {
+            lights: (0..MAX_LIGHTS_2D + 8)
+                .map(|i| light(i as f32, 10.0, RenderLayers::default()))
+                .collect(),
+            occluders: Vec::new(),
+        };
+
+        let gpu_lights = GpuLights2d::new(
+            LinearRgba::BLACK,
+            &extracted,
+            VIEW_RECT,
+            &RenderLayers::default(),
+        );
+        assert_eq!(gpu_lights.n_lights as usize, MAX_LIGHTS_2D);
+        // The first lights are kept
+        assert_eq!(
+            positions(&gpu_lights),
+            (0..MAX_LIGHTS_2D).map(|i| i as f32).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn occluders_are_kept_where_the_lights_reach() {
+        let extracted = ExtractedLights2d {
+            lights: vec![light(90.0, 50.0, RenderLayers::default())],
+            occluders: vec![
+                occluder(0.0, Vec2::ONE),
+                // Outside of the view, but lit
+                occluder(140.0, Vec2::ONE),
+                // Its shape reaches where the light does
+                occluder(170.0, Vec2::new(30.0, 1.0)),
+                occluder(300.0, Vec2::ONE),
+            ],
+        };
+
+        let gpu_lights = GpuLights2d::new(
+            LinearRgba::BLACK,
+            &extracted,
+            VIEW_RECT,
+            &RenderLayers::default(),
+        );
+        assert_eq!(gpu_lights.n_occluders, 3);
+        assert_eq!(gpu_lights.occluders[2].center.x, 170.0);
+    }
+}
@@ -0,0 +1,137 @@
+use bevy_asset::{io::Reader, ron, AssetLoader, AsyncReadExt, LoadContext};
+use bevy_math::{URect, UVec2};
+use bevy_render::{
+    render_asset::RenderAssetUsages,
+    render_resource::{Extent3d, TextureDimension, TextureFormat},
+    texture::{Image, ImageSampler},
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{
+    PackedTextureAtlas, PACKED_TEXTURE_ATLAS_IMAGE_LABEL, PACKED_TEXTURE_ATLAS_LAYOUT_LABEL,
+};
+use crate::TextureAtlasLayout;
+
+/// The magic bytes at the start of every packed texture atlas file.
+pub(crate) const PACKED_TEXTURE_ATLAS_MAGIC: [u8; 4] = *b"BATL";
+/// The version of the packed texture atlas format written by the [`PackedTextureAtlasSaver`].
+///
+/// [`PackedTextureAtlasSaver`]: super::PackedTextureAtlasSaver
+pub(crate) const PACKED_TEXTURE_ATLAS_VERSION: u32 = 1;
+
+/// The description of the atlas stored in RON after the version, followed by the RGBA8 pixels
+/// of the atlas image.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct PackedTextureAtlasHeader {
+    pub(crate) size: UVec2,
+    pub(crate) is_srgb: bool,
+    /// The name and the rect of each texture, in the order of the layout.
+    pub(crate) textures: Vec<(String, URect)>,
+}
+
+/// Loads [`PackedTextureAtlas`]es saved by the [`PackedTextureAtlasSaver`].
+///
+/// [`PackedTextureAtlasSaver`]: super::PackedTextureAtlasSaver
+#[derive(Default)]
+pub struct PackedTextureAtlasLoader;
+
+#[derive(Serialize, Deserialize, Default, Debug)]
+pub struct PackedTextureAtlasLoaderSettings {
+    pub sampler: ImageSampler,
+    pub asset_usage: RenderAssetUsages,
+}
+
+/// Possible errors that can be produced by [`PackedTextureAtlasLoader`]
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum PackedTextureAtlasLoaderError {
+    #[error("Could not read packed texture atlas file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Not a packed texture atlas file")]
+    InvalidMagic,
+    #[error(
+        "Unsupported packed texture atlas version {0}, expected {PACKED_TEXTURE_ATLAS_VERSION}"
+    )]
+    UnsupportedVersion(u32),
+    #[error("Unexpected end of packed texture atlas file")]
+    UnexpectedEof,
+    #[error("Could not parse packed texture atlas header: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("The image data doesn't match the size of the packed texture atlas")]
+    InvalidImageSize,
+}
+
+impl AssetLoader for PackedTextureAtlasLoader {
+    type Asset = PackedTextureAtlas;
+    type Settings = PackedTextureAtlasLoaderSettings;
+    type Error = PackedTextureAtlasLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        settings: &'a PackedTextureAtlasLoaderSettings,
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<PackedTextureAtlas, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+
+        let read_u32 = |offset: usize| {
+            bytes
+                .get(offset..offset + 4)
+                .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+                .ok_or(PackedTextureAtlasLoaderError::UnexpectedEof)
+        };
+        if bytes.get(..4) != Some(&PACKED_TEXTURE_ATLAS_MAGIC[..]) {
+            return Err(PackedTextureAtlasLoaderError::InvalidMagic);
+        }
+        let version = read_u32(4)?;
+        if version != PACKED_TEXTURE_ATLAS_VERSION {
+            return Err(PackedTextureAtlasLoaderError::UnsupportedVersion(version));
+        }
+        let header_end = 12 + read_u32(8)? as usize;
+        let header: PackedTextureAtlasHeader = ron::de::from_bytes(
+            bytes
+                .get(12..header_end)
+                .ok_or(PackedTextureAtlasLoaderError::UnexpectedEof)?,
+        )?;
+        let data = &bytes[header_end..];
+        if data.len() != header.size.x as usize * header.size.y as usize * 4 {
+            return Err(PackedTextureAtlasLoaderError::InvalidImageSize);
+        }
+
+        let mut image = Image::new(
+            Extent3d {
+                width: header.size.x,
+                height: header.size.y,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data.to_vec(),
+            if header.is_srgb {
+                TextureFormat::Rgba8UnormSrgb
+            } else {
+                TextureFormat::Rgba8Unorm
+            },
+            settings.asset_usage,
+        );
+        image.sampler = settings.sampler.clone();
+
+        let mut layout = TextureAtlasLayout::new_empty(header.size);
+        let mut names = Vec::with_capacity(header.textures.len());
+        for (name, rect) in header.textures {
+            layout.add_texture(rect);
+            names.push(name);
+        }
+
+        let image =
+            load_context.add_labeled_asset(PACKED_TEXTURE_ATLAS_IMAGE_LABEL.to_string(), image);
+        let layout =
+            load_context.add_labeled_asset(PACKED_TEXTURE_ATLAS_LAYOUT_LABEL.to_string(), layout);
+        Ok(PackedTextureAtlas::new(image, layout, names))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["atlas"]
+    }
+}
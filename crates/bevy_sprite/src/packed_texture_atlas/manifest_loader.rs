@@ -0,0 +1,183 @@
+use bevy_asset::{
+    io::Reader, ron, AssetLoadError, AssetLoader, AssetPath, AsyncReadExt, LoadContext,
+    LoadDirectError, ParseAssetPathError, ReadAssetBytesError,
+};
+use bevy_math::UVec2;
+use bevy_render::{
+    render_asset::RenderAssetUsages,
+    texture::{Image, ImageSampler},
+};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::{
+    PackedTextureAtlas, PACKED_TEXTURE_ATLAS_IMAGE_LABEL, PACKED_TEXTURE_ATLAS_LAYOUT_LABEL,
+};
+use crate::{TextureAtlasBuilder, TextureAtlasBuilderError};
+
+/// The images to pack in a [`PackedTextureAtlas`], read from a `.atlas.ron` file.
+///
+/// Paths are relative to the manifest.
+///
+/// ```ron
+/// (
+///     images: ["player.png", "enemies/slime.png"],
+///     folder: Some("items"),
+/// )
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct TextureAtlasManifest {
+    /// The paths of the images to pack.
+    #[serde(default)]
+    pub images: Vec<String>,
+    /// The path of a folder whose images are all packed, after [`images`](Self::images).
+    ///
+    /// Sub-folders and files that aren't images are skipped. When the manifest is processed,
+    /// adding or removing an image in the folder processes it again.
+    #[serde(default)]
+    pub folder: Option<String>,
+}
+
+/// Loads a [`TextureAtlasManifest`] and packs its images in a [`PackedTextureAtlas`].
+#[derive(Default)]
+pub struct TextureAtlasManifestLoader;
+
+/// How the images of a [`TextureAtlasManifest`] are packed.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TextureAtlasManifestLoaderSettings {
+    /// The number of pixels left empty between two textures.
+    pub padding: u32,
+    /// The number of pixels the edges of each texture are repeated, see
+    /// [`TextureAtlasBuilder::extrusion`].
+    pub extrusion: u32,
+    /// The maximum size of the atlas image in pixels.
+    pub max_size: UVec2,
+    /// The sampler of the atlas image.
+    pub sampler: ImageSampler,
+    pub asset_usage: RenderAssetUsages,
+}
+
+impl Default for TextureAtlasManifestLoaderSettings {
+    fn default() -> Self {
+        Self {
+            padding: 0,
+            extrusion: 0,
+            max_size: UVec2::splat(2048),
+            sampler: ImageSampler::Default,
+            asset_usage: RenderAssetUsages::default(),
+        }
+    }
+}
+
+/// Possible errors that can be produced by [`TextureAtlasManifestLoader`]
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum TextureAtlasManifestLoaderError {
+    #[error("Could not read texture atlas manifest: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Could not parse texture atlas manifest: {0}")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("Invalid image path in texture atlas manifest: {0}")]
+    InvalidPath(#[from] ParseAssetPathError),
+    #[error("Could not read texture atlas folder: {0}")]
+    ReadFolder(#[from] ReadAssetBytesError),
+    #[error(transparent)]
+    LoadImage(#[from] LoadDirectError),
+    #[error("{0} is not an image")]
+    NotAnImage(AssetPath<'static>),
+    #[error("Several images are named {0:?} in the texture atlas")]
+    DuplicateName(String),
+    #[error(transparent)]
+    Pack(#[from] TextureAtlasBuilderError),
+}
+
+impl AssetLoader for TextureAtlasManifestLoader {
+    type Asset = PackedTextureAtlas;
+    type Settings = TextureAtlasManifestLoaderSettings;
+    type Error = TextureAtlasManifestLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        settings: &'a TextureAtlasManifestLoaderSettings,
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<PackedTextureAtlas, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let manifest: TextureAtlasManifest = ron::de::from_bytes(&bytes)?;
+
+        // Paths listed in the manifest must be images, while the files of the folder are
+        // skipped when they aren't
+        let mut paths = Vec::new();
+        for image in &manifest.images {
+            paths.push((load_context.asset_path().resolve_embed(image)?, true));
+        }
+        if let Some(folder) = &manifest.folder {
+            let folder = load_context.asset_path().resolve_embed(folder)?;
+            for path in load_context.read_folder(folder).await? {
+                // Don't load other manifests, or this one, from the folder
+                let is_manifest = path.get_full_extension().is_some_and(|extension| {
+                    self.extensions()
+                        .iter()
+                        .any(|manifest_extension| extension.ends_with(manifest_extension))
+                });
+                if !is_manifest {
+                    paths.push((path, false));
+                }
+            }
+        }
+
+        let mut names: Vec<String> = Vec::with_capacity(paths.len());
+        let mut images = Vec::with_capacity(paths.len());
+        for (path, required) in paths {
+            let loaded = match load_context.load_direct(path.clone()).await {
+                Ok(loaded) => loaded,
+                Err(LoadDirectError {
+                    error:
+                        AssetLoadError::MissingAssetLoader { .. }
+                        | AssetLoadError::MissingAssetLoaderForExtension(_),
+                    ..
+                }) if !required => continue,
+                Err(error) => return Err(error.into()),
+            };
+            let Some(image) = loaded.take::<Image>() else {
+                if required {
+                    return Err(TextureAtlasManifestLoaderError::NotAnImage(path));
+                }
+                continue;
+            };
+            let name = path
+                .path()
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            if names.contains(&name) {
+                return Err(TextureAtlasManifestLoaderError::DuplicateName(name));
+            }
+            names.push(name);
+            images.push(image);
+        }
+
+        let mut builder = TextureAtlasBuilder::default()
+            .padding(UVec2::splat(settings.padding))
+            .extrusion(settings.extrusion)
+            .initial_size(UVec2::splat(256).min(settings.max_size))
+            .max_size(settings.max_size);
+        for image in &images {
+            builder.add_texture(None, image);
+        }
+        let (layout, mut image) = builder.finish()?;
+        image.sampler = settings.sampler.clone();
+        image.asset_usage = settings.asset_usage;
+
+        let image =
+            load_context.add_labeled_asset(PACKED_TEXTURE_ATLAS_IMAGE_LABEL.to_string(), image);
+        let layout =
+            load_context.add_labeled_asset(PACKED_TEXTURE_ATLAS_LAYOUT_LABEL.to_string(), layout);
+        Ok(PackedTextureAtlas::new(image, layout, names))
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["atlas.ron"]
+    }
+}
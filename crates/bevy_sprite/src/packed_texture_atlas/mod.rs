@@ -0,0 +1,259 @@
+mod loader;
+mod manifest_loader;
+mod saver;
+
+pub use loader::*;
+pub use manifest_loader::*;
+pub use saver::*;
+
+use bevy_asset::{Asset, Handle};
+use bevy_reflect::TypePath;
+use bevy_render::texture::Image;
+use bevy_utils::HashMap;
+
+use crate::{TextureAtlas, TextureAtlasLayout};
+
+/// The label of the [`Image`] of a [`PackedTextureAtlas`].
+pub const PACKED_TEXTURE_ATLAS_IMAGE_LABEL: &str = "image";
+/// The label of the [`TextureAtlasLayout`] of a [`PackedTextureAtlas`].
+pub const PACKED_TEXTURE_ATLAS_LAYOUT_LABEL: &str = "layout";
+
+/// A texture atlas packed from a [`TextureAtlasManifest`] of images.
+///
+/// When the asset processor is enabled, each manifest is packed once and saved with its image
+/// and its layout by the [`PackedTextureAtlasSaver`], so loading it at runtime doesn't need to
+/// load and pack the individual images again. Without the asset processor, the manifest is
+/// packed by the [`TextureAtlasManifestLoader`] every time it is loaded.
+///
+/// The image and the layout are labeled assets, which can be loaded directly with the
+/// [`PACKED_TEXTURE_ATLAS_IMAGE_LABEL`] and [`PACKED_TEXTURE_ATLAS_LAYOUT_LABEL`] labels, such
+/// as `"sprites.atlas.ron#image"`.
+///
+/// Each texture of the atlas is named after the file name of its original image, such as
+/// `"player.png"`, which can be used to find its index in the [`TextureAtlasLayout`].
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct PackedTextureAtlas {
+    /// The image all the textures were packed in.
+    pub image: Handle<Image>,
+    /// The layout of the textures in the [`image`](Self::image).
+    pub layout: Handle<TextureAtlasLayout>,
+    /// The name of each texture, in the same order as the textures of the layout.
+    names: Vec<String>,
+    indices: HashMap<String, usize>,
+}
+
+impl PackedTextureAtlas {
+    /// Creates a new [`PackedTextureAtlas`] from the names of its textures, in the same order as
+    /// the textures of the layout.
+    pub fn new(
+        image: Handle<Image>,
+        layout: Handle<TextureAtlasLayout>,
+        names: Vec<String>,
+    ) -> Self {
+        let indices = names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| (name.clone(), index))
+            .collect();
+        Self {
+            image,
+            layout,
+            names,
+            indices,
+        }
+    }
+
+    /// The name of each texture, in the same order as the textures of the layout.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// Retrieves the index of the texture packed from the image with the given file name.
+    pub fn texture_index(&self, name: &str) -> Option<usize> {
+        self.indices.get(name).copied()
+    }
+
+    /// Creates a [`TextureAtlas`] component drawing the texture packed from the image with the
+    /// given file name.
+    pub fn texture_atlas(&self, name: &str) -> Option<TextureAtlas> {
+        Some(TextureAtlas {
+            layout: self.layout.clone(),
+            index: self.texture_index(name)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_app::App;
+    use bevy_asset::{
+        io::{
+            memory::{Dir, MemoryAssetReader},
+            AssetSource, AssetSourceId, Reader,
+        },
+        saver::{AssetSaver, SavedAsset},
+        transformer::TransformedAsset,
+        AssetApp, AssetLoader, AssetPlugin, AssetServer, Assets, AsyncReadExt, LoadContext,
+        LoadState, LoadedAsset,
+    };
+    use bevy_core::TaskPoolPlugin;
+    use bevy_render::{
+        render_asset::RenderAssetUsages,
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+    };
+    use std::path::Path;
+
+    /// Loads `.rgba` test images, made of their width and height followed by their pixels.
+    struct RgbaLoader;
+
+    impl AssetLoader for RgbaLoader {
+        type Asset = Image;
+        type Settings = ();
+        type Error = std::io::Error;
+
+        async fn load<'a>(
+            &'a self,
+            reader: &'a mut Reader<'_>,
+            _settings: &'a (),
+            _load_context: &'a mut LoadContext<'_>,
+        ) -> Result<Image, std::io::Error> {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            Ok(Image::new(
+                Extent3d {
+                    width: bytes[0] as u32,
+                    height: bytes[1] as u32,
+                    depth_or_array_layers: 1,
+                },
+                TextureDimension::D2,
+                bytes[2..].to_vec(),
+                TextureFormat::Rgba8UnormSrgb,
+                RenderAssetUsages::default(),
+            ))
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["rgba"]
+        }
+    }
+
+    fn rgba_image(width: u8, height: u8, value: u8) -> Vec<u8> {
+        let mut bytes = vec![value; 2 + width as usize * height as usize * 4];
+        bytes[0] = width;
+        bytes[1] = height;
+        bytes
+    }
+
+    fn load_atlas(app: &mut App, path: &'static str) -> PackedTextureAtlas {
+        let handle: Handle<PackedTextureAtlas> = app.world().resource::<AssetServer>().load(path);
+        for _ in 0..10000 {
+            app.update();
+            let asset_server = app.world().resource::<AssetServer>();
+            if asset_server.is_loaded_with_dependencies(&handle) {
+                return app
+                    .world()
+                    .resource::<Assets<PackedTextureAtlas>>()
+                    .get(&handle)
+                    .unwrap()
+                    .clone();
+            }
+            if let Some(LoadState::Failed(err)) = asset_server.get_load_state(&handle) {
+                panic!("Failed to load {path}: {err}");
+            }
+        }
+        panic!("{path} didn't load");
+    }
+
+    #[test]
+    fn packed_manifest_round_trips_through_the_saver() {
+        let dir = Dir::default();
+        dir.insert_asset_text(
+            Path::new("sprites/sprites.atlas.ron"),
+            r#"(images: ["player.rgba"], folder: Some("items"))"#,
+        );
+        dir.insert_asset(Path::new("sprites/player.rgba"), rgba_image(4, 8, 1));
+        dir.insert_asset(Path::new("sprites/items/gem.rgba"), rgba_image(3, 2, 3));
+        dir.insert_asset(Path::new("sprites/items/coin.rgba"), rgba_image(2, 2, 2));
+        dir.insert_asset_text(Path::new("sprites/items/notes.txt"), "not an image");
+
+        let mut app = App::new();
+        let root = dir.clone();
+        app.register_asset_source(
+            AssetSourceId::Default,
+            AssetSource::build()
+                .with_reader(move || Box::new(MemoryAssetReader { root: root.clone() })),
+        )
+        .add_plugins((TaskPoolPlugin::default(), AssetPlugin::default()))
+        .init_asset::<Image>()
+        .init_asset::<TextureAtlasLayout>()
+        .init_asset::<PackedTextureAtlas>()
+        .register_asset_loader(RgbaLoader)
+        .register_asset_loader(TextureAtlasManifestLoader)
+        .register_asset_loader(PackedTextureAtlasLoader);
+
+        let packed = load_atlas(&mut app, "sprites/sprites.atlas.ron");
+        // The listed images come first, then the images of the folder in alphabetical order
+        assert_eq!(packed.names(), ["player.rgba", "coin.rgba", "gem.rgba"]);
+        let image = app
+            .world()
+            .resource::<Assets<Image>>()
+            .get(&packed.image)
+            .unwrap()
+            .clone();
+        let layout = app
+            .world()
+            .resource::<Assets<TextureAtlasLayout>>()
+            .get(&packed.layout)
+            .unwrap()
+            .clone();
+
+        let mut asset = TransformedAsset::<PackedTextureAtlas>::from_loaded(
+            LoadedAsset::from(packed.clone()).into(),
+        )
+        .unwrap();
+        asset.insert_labeled(
+            PACKED_TEXTURE_ATLAS_IMAGE_LABEL,
+            packed.image.clone(),
+            LoadedAsset::from(image.clone()),
+        );
+        asset.insert_labeled(
+            PACKED_TEXTURE_ATLAS_LAYOUT_LABEL,
+            packed.layout.clone(),
+            LoadedAsset::from(layout.clone()),
+        );
+        let mut bytes = Vec::new();
+        bevy_tasks::block_on(PackedTextureAtlasSaver.save(
+            &mut bytes,
+            SavedAsset::from_transformed(&asset),
+            &(),
+        ))
+        .unwrap();
+        dir.insert_asset(Path::new("sprites/sprites.atlas"), bytes);
+
+        let loaded = load_atlas(&mut app, "sprites/sprites.atlas");
+        assert_eq!(loaded.names(), packed.names());
+        assert_eq!(
+            loaded.texture_index("coin.rgba"),
+            packed.texture_index("coin.rgba")
+        );
+        let loaded_image = &app
+            .world()
+            .resource::<Assets<Image>>()
+            .get(&loaded.image)
+            .unwrap();
+        assert_eq!(loaded_image.size(), image.size());
+        assert_eq!(
+            loaded_image.texture_descriptor.format,
+            TextureFormat::Rgba8UnormSrgb
+        );
+        assert_eq!(loaded_image.data, image.data);
+        let loaded_layout = &app
+            .world()
+            .resource::<Assets<TextureAtlasLayout>>()
+            .get(&loaded.layout)
+            .unwrap();
+        assert_eq!(loaded_layout.size, layout.size);
+        assert_eq!(loaded_layout.textures, layout.textures);
+    }
+}
@@ -0,0 +1,108 @@
+use bevy_asset::{
+    ron,
+    saver::{AssetSaver, SavedAsset},
+    AsyncWriteExt,
+};
+use bevy_render::{
+    render_resource::TextureFormat,
+    texture::{Image, TextureFormatPixelInfo},
+};
+use thiserror::Error;
+
+use super::{
+    loader::{PackedTextureAtlasHeader, PACKED_TEXTURE_ATLAS_MAGIC, PACKED_TEXTURE_ATLAS_VERSION},
+    PackedTextureAtlas, PackedTextureAtlasLoader, PackedTextureAtlasLoaderSettings,
+    PACKED_TEXTURE_ATLAS_IMAGE_LABEL, PACKED_TEXTURE_ATLAS_LAYOUT_LABEL,
+};
+use crate::TextureAtlasLayout;
+
+/// Saves a [`PackedTextureAtlas`] with its image and its layout in a single file, that can be
+/// read back by the [`PackedTextureAtlasLoader`].
+///
+/// The image is saved uncompressed, so that loading it is a single copy.
+#[derive(Default)]
+pub struct PackedTextureAtlasSaver;
+
+/// Possible errors that can be produced by [`PackedTextureAtlasSaver`]
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum PackedTextureAtlasSaverError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("The packed texture atlas has no {0:?} labeled asset")]
+    MissingLabeledAsset(&'static str),
+    #[error("The `{0:?}` texture format can't be saved in a packed texture atlas")]
+    UnsupportedTextureFormat(TextureFormat),
+    #[error("The packed texture atlas has {names} names for {textures} textures")]
+    TextureCountMismatch { names: usize, textures: usize },
+    #[error(transparent)]
+    Ron(#[from] ron::Error),
+}
+
+impl AssetSaver for PackedTextureAtlasSaver {
+    type Asset = PackedTextureAtlas;
+    type Settings = ();
+    type OutputLoader = PackedTextureAtlasLoader;
+    type Error = PackedTextureAtlasSaverError;
+
+    async fn save<'a>(
+        &'a self,
+        writer: &'a mut bevy_asset::io::Writer,
+        atlas: SavedAsset<'a, Self::Asset>,
+        _settings: &'a Self::Settings,
+    ) -> Result<PackedTextureAtlasLoaderSettings, Self::Error> {
+        let image = atlas
+            .get_labeled::<Image, _>(PACKED_TEXTURE_ATLAS_IMAGE_LABEL)
+            .ok_or(PackedTextureAtlasSaverError::MissingLabeledAsset(
+                PACKED_TEXTURE_ATLAS_IMAGE_LABEL,
+            ))?;
+        let layout = atlas
+            .get_labeled::<TextureAtlasLayout, _>(PACKED_TEXTURE_ATLAS_LAYOUT_LABEL)
+            .ok_or(PackedTextureAtlasSaverError::MissingLabeledAsset(
+                PACKED_TEXTURE_ATLAS_LAYOUT_LABEL,
+            ))?;
+
+        let format = image.texture_descriptor.format;
+        let is_srgb = match format {
+            TextureFormat::Rgba8UnormSrgb => true,
+            TextureFormat::Rgba8Unorm => false,
+            _ => {
+                return Err(PackedTextureAtlasSaverError::UnsupportedTextureFormat(
+                    format,
+                ))
+            }
+        };
+        if atlas.names().len() != layout.textures.len() {
+            return Err(PackedTextureAtlasSaverError::TextureCountMismatch {
+                names: atlas.names().len(),
+                textures: layout.textures.len(),
+            });
+        }
+
+        let header = ron::ser::to_string(&PackedTextureAtlasHeader {
+            size: image.size(),
+            is_srgb,
+            textures: atlas
+                .names()
+                .iter()
+                .cloned()
+                .zip(layout.textures.iter().copied())
+                .collect(),
+        })?;
+        // Only the first mip level is saved
+        let data_size = image.width() as usize * image.height() as usize * format.pixel_size();
+
+        let mut bytes = Vec::with_capacity(12 + header.len() + data_size);
+        bytes.extend_from_slice(&PACKED_TEXTURE_ATLAS_MAGIC);
+        bytes.extend_from_slice(&PACKED_TEXTURE_ATLAS_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(header.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(header.as_bytes());
+        bytes.extend_from_slice(&image.data[..data_size]);
+        writer.write_all(&bytes).await?;
+
+        Ok(PackedTextureAtlasLoaderSettings {
+            sampler: image.sampler.clone(),
+            asset_usage: image.asset_usage,
+        })
+    }
+}
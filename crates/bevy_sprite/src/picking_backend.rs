@@ -0,0 +1,243 @@
+//! A picking backend for [`Sprite`]s.
+//!
+//! Sprites are hit within their rectangle, sized like their [`Aabb`](bevy_render::primitives::Aabb)
+//! from their custom size, their rect, their [`TextureAtlas`] section or their image. Pointers are
+//! projected with [`Camera::viewport_to_world_2d`], so only cameras looking along the Z axis pick
+//! sprites correctly. Sprites are sorted from the highest Z to the lowest, until a sprite blocks
+//! the sprites behind it, which sprites do unless they have a [`PickingBehavior`] with
+//! [`should_block_lower`](PickingBehavior::should_block_lower) disabled.
+
+use crate::{Sprite, TextureAtlas, TextureAtlasLayout};
+use bevy_asset::{Assets, Handle};
+use bevy_ecs::prelude::*;
+use bevy_math::{FloatOrd, Rect};
+use bevy_picking::{
+    backend::{HitData, PointerHits},
+    pointer::{PointerId, PointerLocation},
+    PickingBehavior,
+};
+use bevy_render::{
+    camera::Camera,
+    texture::Image,
+    view::{RenderLayers, ViewVisibility},
+};
+use bevy_transform::components::GlobalTransform;
+use bevy_window::PrimaryWindow;
+
+/// Sends the sprites under each pointer as [`PointerHits`], one event per camera.
+#[allow(clippy::too_many_arguments)]
+pub fn sprite_picking(
+    pointers: Query<(&PointerId, &PointerLocation)>,
+    cameras: Query<(Entity, &Camera, &GlobalTransform, Option<&RenderLayers>)>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    images: Res<Assets<Image>>,
+    texture_atlas_layouts: Res<Assets<TextureAtlasLayout>>,
+    sprites: Query<(
+        Entity,
+        &Sprite,
+        &Handle<Image>,
+        Option<&TextureAtlas>,
+        &GlobalTransform,
+        &ViewVisibility,
+        Option<&PickingBehavior>,
+        Option<&RenderLayers>,
+    )>,
+    mut output: EventWriter<PointerHits>,
+) {
+    let primary_window = primary_window.get_single().ok();
+
+    // Sprites from the closest to the camera to the furthest
+    let mut sorted_sprites: Vec<_> = sprites
+        .iter()
+        .filter(|(.., view_visibility, _, _)| view_visibility.get())
+        .collect();
+    sorted_sprites.sort_by_key(|(_, _, _, _, transform, ..)| FloatOrd(-transform.translation().z));
+
+    for (&pointer_id, pointer_location) in &pointers {
+        let Some(location) = pointer_location.location() else {
+            continue;
+        };
+
+        for (camera_entity, camera, camera_transform, camera_layers) in &cameras {
+            if !camera.is_active || !location.is_in_viewport(camera, primary_window) {
+                continue;
+            }
+            let viewport_position = camera
+                .logical_viewport_rect()
+                .map(|rect| rect.min)
+                .unwrap_or_default();
+            let Some(cursor_position) = camera
+                .viewport_to_world_2d(camera_transform, location.position - viewport_position)
+            else {
+                continue;
+            };
+            let camera_layers = camera_layers.copied().unwrap_or_default();
+
+            let mut picks = Vec::new();
+            for (entity, sprite, image, atlas, transform, _, behavior, layers) in &sorted_sprites {
+                if !camera_layers.intersects(&layers.copied().unwrap_or_default()) {
+                    continue;
+                }
+                let Some(size) = sprite
+                    .custom_size
+                    .or_else(|| sprite.rect.map(|rect| rect.size()))
+                    .or_else(|| match atlas {
+                        None => images.get(*image).map(Image::size_f32),
+                        Some(atlas) => atlas
+                            .texture_rect(&texture_atlas_layouts)
+                            .map(|rect| rect.size().as_vec2()),
+                    })
+                else {
+                    continue;
+                };
+
+                // The cursor in the space of the sprite, on its plane
+                let world_position = cursor_position.extend(transform.translation().z);
+                let local_position = transform
+                    .affine()
+                    .inverse()
+                    .transform_point3(world_position)
+                    .truncate();
+                let bounds = Rect::from_center_size(-sprite.anchor.as_vec() * size, size);
+                if !bounds.contains(local_position) {
+                    continue;
+                }
+
+                let depth = camera_transform.translation().z - world_position.z;
+                let hit = HitData::new(camera_entity, depth, Some(world_position), None);
+                picks.push((*entity, hit));
+                if behavior.map_or(true, |behavior| behavior.should_block_lower) {
+                    break;
+                }
+            }
+
+            if !picks.is_empty() {
+                output.send(PointerHits::new(pointer_id, picks, camera.order as f32));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Anchor;
+    use bevy_asset::AssetEvent;
+    use bevy_core_pipeline::core_2d::Camera2dBundle;
+    use bevy_ecs::{event::Events, system::RunSystemOnce};
+    use bevy_math::Vec2;
+    use bevy_picking::pointer::{Location, PointerBundle};
+    use bevy_render::camera::{ManualTextureViews, NormalizedRenderTarget, OrthographicProjection};
+    use bevy_window::{
+        Window, WindowCreated, WindowRef, WindowResized, WindowResolution, WindowScaleFactorChanged,
+    };
+
+    fn spawn_sprite(world: &mut World, sprite: Sprite, x: f32, z: f32) -> EntityWorldMut {
+        let mut view_visibility = ViewVisibility::default();
+        view_visibility.set();
+        world.spawn((
+            sprite,
+            Handle::<Image>::default(),
+            GlobalTransform::from_xyz(x, 0.0, z),
+            view_visibility,
+        ))
+    }
+
+    fn square(size: f32) -> Sprite {
+        Sprite {
+            custom_size: Some(Vec2::splat(size)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn sprites_are_picked_from_front_to_back() {
+        let mut world = World::new();
+        world.init_resource::<Events<WindowResized>>();
+        world.init_resource::<Events<WindowCreated>>();
+        world.init_resource::<Events<WindowScaleFactorChanged>>();
+        world.init_resource::<Events<AssetEvent<Image>>>();
+        world.init_resource::<Assets<Image>>();
+        world.init_resource::<Assets<TextureAtlasLayout>>();
+        world.init_resource::<ManualTextureViews>();
+        world.init_resource::<Events<PointerHits>>();
+
+        let window = world
+            .spawn((
+                Window {
+                    resolution: WindowResolution::new(800.0, 600.0),
+                    ..Default::default()
+                },
+                PrimaryWindow,
+            ))
+            .id();
+        let camera = world.spawn(Camera2dBundle::default()).id();
+        let mut layer_camera = Camera2dBundle::default();
+        layer_camera.camera.order = 1;
+        let layer_camera = world.spawn((layer_camera, RenderLayers::layer(1))).id();
+        // A pointer at the center of the window, over the origin of the world
+        let location = Location {
+            target: NormalizedRenderTarget::Window(
+                WindowRef::Primary.normalize(Some(window)).unwrap(),
+            ),
+            position: Vec2::new(400.0, 300.0),
+        };
+        world.spawn(PointerBundle {
+            location: PointerLocation::new(location),
+            ..PointerBundle::new(PointerId::Mouse)
+        });
+
+        let see_through = PickingBehavior {
+            should_block_lower: false,
+            is_hoverable: true,
+        };
+        let blocking = spawn_sprite(&mut world, square(100.0), 0.0, 2.0).id();
+        let overlay = spawn_sprite(&mut world, square(100.0), 0.0, 3.0)
+            .insert(see_through)
+            .id();
+        // Only reaches the pointer thanks to its anchor
+        let anchored = Sprite {
+            anchor: Anchor::BottomLeft,
+            ..square(100.0)
+        };
+        let anchored = spawn_sprite(&mut world, anchored, -90.0, 3.5)
+            .insert(see_through)
+            .id();
+        // The same sprite, centered on its position, misses the pointer
+        spawn_sprite(&mut world, square(100.0), -90.0, 3.8);
+        // Behind the blocking sprite
+        spawn_sprite(&mut world, square(100.0), 0.0, 1.0);
+        spawn_sprite(&mut world, square(100.0), 0.0, 5.0).insert(ViewVisibility::HIDDEN);
+        let layer_sprite = spawn_sprite(&mut world, square(100.0), 0.0, 6.0)
+            .insert(RenderLayers::layer(1))
+            .id();
+
+        world.run_system_once(bevy_render::camera::camera_system::<OrthographicProjection>);
+        world.run_system_once(sprite_picking);
+
+        let mut hits: Vec<_> = world
+            .resource_mut::<Events<PointerHits>>()
+            .drain()
+            .collect();
+        hits.sort_by(|a, b| a.order.total_cmp(&b.order));
+        // The cameras are at the origin, so sprites with a higher Z are closer
+        let picks = |hits: &PointerHits| -> Vec<(Entity, Entity, f32)> {
+            hits.picks
+                .iter()
+                .map(|(entity, hit)| (*entity, hit.camera, hit.depth))
+                .collect()
+        };
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].order, 0.0);
+        assert_eq!(
+            picks(&hits[0]),
+            [
+                (anchored, camera, -3.5),
+                (overlay, camera, -3.0),
+                (blocking, camera, -2.0),
+            ]
+        );
+        assert_eq!(hits[1].order, 1.0);
+        assert_eq!(picks(&hits[1]), [(layer_sprite, layer_camera, -6.0)]);
+    }
+}
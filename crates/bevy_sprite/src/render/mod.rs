@@ -4,12 +4,13 @@ use crate::{
     texture_atlas::{TextureAtlas, TextureAtlasLayout},
     ComputedTextureSlices, Sprite, WithSprite, SPRITE_SHADER_HANDLE,
 };
-use bevy_asset::{AssetEvent, AssetId, Assets, Handle};
+use bevy_asset::{AssetEvent, AssetId, Assets, Handle, UntypedAssetId};
 use bevy_color::LinearRgba;
 use bevy_core_pipeline::{
     core_2d::Transparent2d,
     tonemapping::{DebandDither, Tonemapping},
 };
+use bevy_derive::{Deref, DerefMut};
 use bevy_ecs::entity::EntityHashMap;
 use bevy_ecs::{
     prelude::*,
@@ -41,10 +42,10 @@ use bevy_utils::HashMap;
 use bytemuck::{Pod, Zeroable};
 use fixedbitset::FixedBitSet;
 
-#[derive(Resource)]
+#[derive(Resource, Clone)]
 pub struct SpritePipeline {
-    view_layout: BindGroupLayout,
-    material_layout: BindGroupLayout,
+    pub(crate) view_layout: BindGroupLayout,
+    pub(crate) material_layout: BindGroupLayout,
     pub dummy_white_gpu_image: GpuImage,
 }
 
@@ -165,6 +166,40 @@ impl SpritePipelineKey {
             SpritePipelineKey::NONE
         }
     }
+
+    /// Creates the key of the sprites drawn in the given view.
+    pub fn from_view(
+        view: &ExtractedView,
+        msaa: &Msaa,
+        tonemapping: Option<&Tonemapping>,
+        dither: Option<&DebandDither>,
+    ) -> Self {
+        let mut view_key = Self::from_hdr(view.hdr) | Self::from_msaa_samples(msaa.samples());
+
+        if !view.hdr {
+            if let Some(tonemapping) = tonemapping {
+                view_key |= SpritePipelineKey::TONEMAP_IN_SHADER;
+                view_key |= match tonemapping {
+                    Tonemapping::None => SpritePipelineKey::TONEMAP_METHOD_NONE,
+                    Tonemapping::Reinhard => SpritePipelineKey::TONEMAP_METHOD_REINHARD,
+                    Tonemapping::ReinhardLuminance => {
+                        SpritePipelineKey::TONEMAP_METHOD_REINHARD_LUMINANCE
+                    }
+                    Tonemapping::AcesFitted => SpritePipelineKey::TONEMAP_METHOD_ACES_FITTED,
+                    Tonemapping::AgX => SpritePipelineKey::TONEMAP_METHOD_AGX,
+                    Tonemapping::SomewhatBoringDisplayTransform => {
+                        SpritePipelineKey::TONEMAP_METHOD_SOMEWHAT_BORING_DISPLAY_TRANSFORM
+                    }
+                    Tonemapping::TonyMcMapface => SpritePipelineKey::TONEMAP_METHOD_TONY_MC_MAPFACE,
+                    Tonemapping::BlenderFilmic => SpritePipelineKey::TONEMAP_METHOD_BLENDER_FILMIC,
+                };
+            }
+            if let Some(DebandDither::Enabled) = dither {
+                view_key |= SpritePipelineKey::DEBAND_DITHER;
+            }
+        }
+        view_key
+    }
 }
 
 impl SpecializedRenderPipeline for SpritePipeline {
@@ -304,8 +339,25 @@ pub struct ExtractedSprite {
 #[derive(Resource, Default)]
 pub struct ExtractedSprites {
     pub sprites: EntityHashMap<ExtractedSprite>,
+    /// The entities of the [`ExtractedSprite`]s of each sliced sprite, by sprite entity in the
+    /// main world. Sprites that aren't sliced are extracted with their own entity.
+    pub slices: EntityHashMap<Vec<Entity>>,
 }
 
+/// The [`SpriteMaterial`](crate::SpriteMaterial) of the sprites drawn with one, by sprite
+/// entity in the main world.
+///
+/// Sprites with a material are queued by the [`SpriteMaterialPlugin`](crate::SpriteMaterialPlugin)
+/// of their material type instead of [`queue_sprites`].
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct SpriteMaterialInstances(EntityHashMap<UntypedAssetId>);
+
+/// The normal map of the sprites lit with one, by sprite entity in the main world.
+///
+/// See [`SpriteNormalMap`](crate::SpriteNormalMap).
+#[derive(Resource, Default, Deref, DerefMut)]
+pub struct SpriteNormalMapInstances(EntityHashMap<AssetId<Image>>);
+
 #[derive(Resource, Default)]
 pub struct SpriteAssetEvents {
     pub images: Vec<AssetEvent<Image>>,
@@ -326,6 +378,8 @@ pub fn extract_sprite_events(
 pub fn extract_sprites(
     mut commands: Commands,
     mut extracted_sprites: ResMut<ExtractedSprites>,
+    mut sprite_material_instances: ResMut<SpriteMaterialInstances>,
+    mut sprite_normal_map_instances: ResMut<SpriteNormalMapInstances>,
     texture_atlases: Extract<Res<Assets<TextureAtlasLayout>>>,
     sprite_query: Extract<
         Query<(
@@ -339,18 +393,29 @@ pub fn extract_sprites(
         )>,
     >,
 ) {
-    extracted_sprites.sprites.clear();
-    for (entity, view_visibility, sprite, transform, handle, sheet, slices) in sprite_query.iter() {
+    let ExtractedSprites { sprites, slices } = &mut *extracted_sprites;
+    sprites.clear();
+    slices.clear();
+    // The materials are extracted after the sprites by each `SpriteMaterialPlugin`, and the
+    // normal maps by the `Light2dPlugin`
+    sprite_material_instances.clear();
+    sprite_normal_map_instances.clear();
+    for (entity, view_visibility, sprite, transform, handle, sheet, computed_slices) in
+        sprite_query.iter()
+    {
         if !view_visibility.get() {
             continue;
         }
 
-        if let Some(slices) = slices {
-            extracted_sprites.sprites.extend(
-                slices
-                    .extract_sprites(transform, entity, sprite, handle)
-                    .map(|e| (commands.spawn_empty().id(), e)),
-            );
+        if let Some(computed_slices) = computed_slices {
+            let slice_sprites = computed_slices.extract_sprites(transform, entity, sprite, handle);
+            let mut slice_entities = Vec::with_capacity(slice_sprites.len());
+            for slice in slice_sprites {
+                let slice_entity = commands.spawn_empty().id();
+                sprites.insert(slice_entity, slice);
+                slice_entities.push(slice_entity);
+            }
+            slices.insert(entity, slice_entities);
         } else {
             let atlas_rect = sheet.and_then(|s| s.texture_rect(&texture_atlases));
             let rect = match (atlas_rect, sprite.rect) {
@@ -366,7 +431,7 @@ pub fn extract_sprites(
             };
 
             // PERF: we don't check in this function that the `Image` asset is ready, since it should be in most cases and hashing the handle is expensive
-            extracted_sprites.sprites.insert(
+            sprites.insert(
                 entity,
                 ExtractedSprite {
                     color: sprite.color.into(),
@@ -412,11 +477,18 @@ impl SpriteInstance {
 
 #[derive(Resource)]
 pub struct SpriteMeta {
-    view_bind_group: Option<BindGroup>,
-    sprite_index_buffer: RawBufferVec<u32>,
+    pub(crate) view_bind_group: Option<BindGroup>,
+    pub(crate) sprite_index_buffer: RawBufferVec<u32>,
     sprite_instance_buffer: RawBufferVec<SpriteInstance>,
 }
 
+impl SpriteMeta {
+    /// The buffer of the instances of every sprite batch, if any sprite was prepared.
+    pub(crate) fn sprite_instance_buffer(&self) -> Option<&Buffer> {
+        self.sprite_instance_buffer.buffer()
+    }
+}
+
 impl Default for SpriteMeta {
     fn default() -> Self {
         Self {
@@ -429,13 +501,68 @@ impl Default for SpriteMeta {
 
 #[derive(Component, PartialEq, Eq, Clone)]
 pub struct SpriteBatch {
+    pub(crate) image_handle_id: AssetId<Image>,
+    /// The [`SpriteMaterial`](crate::SpriteMaterial) of the sprites of the batch, if any.
+    material_id: Option<UntypedAssetId>,
+    /// The [`SpriteNormalMap`](crate::SpriteNormalMap) of the sprites of the batch, if any.
+    pub(crate) normal_map_id: Option<AssetId<Image>>,
+    pub(crate) range: Range<u32>,
+}
+
+impl SpriteBatch {
+    /// The asset id of the [`SpriteMaterial`](crate::SpriteMaterial) of the sprites of the
+    /// batch, if any.
+    pub fn material_id(&self) -> Option<UntypedAssetId> {
+        self.material_id
+    }
+}
+
+/// What sprites must share to be drawn in the same [`SpriteBatch`].
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+struct SpriteBatchKey {
     image_handle_id: AssetId<Image>,
-    range: Range<u32>,
+    material_id: Option<UntypedAssetId>,
+    normal_map_id: Option<AssetId<Image>>,
+    /// The index of the pipeline of the sprites, which differs between the specializations of a
+    /// material.
+    pipeline: usize,
+}
+
+/// Adds a sprite to the current batch if it has the same key, or starts a new batch with it at
+/// the given instance index.
+///
+/// Returns the index of the phase item of the batch, which draws every sprite of the batch.
+fn batch_sprite(
+    batches: &mut Vec<(Entity, SpriteBatch)>,
+    current_batch: &mut Option<(usize, SpriteBatchKey)>,
+    item_index: usize,
+    entity: Entity,
+    key: SpriteBatchKey,
+    index: u32,
+) -> usize {
+    let batch_item_index = match *current_batch {
+        Some((batch_item_index, batch_key)) if batch_key == key => batch_item_index,
+        _ => {
+            *current_batch = Some((item_index, key));
+            batches.push((
+                entity,
+                SpriteBatch {
+                    image_handle_id: key.image_handle_id,
+                    material_id: key.material_id,
+                    normal_map_id: key.normal_map_id,
+                    range: index..index,
+                },
+            ));
+            item_index
+        }
+    };
+    batches.last_mut().unwrap().1.range.end += 1;
+    batch_item_index
 }
 
 #[derive(Resource, Default)]
 pub struct ImageBindGroups {
-    values: HashMap<AssetId<Image>, BindGroup>,
+    pub(crate) values: HashMap<AssetId<Image>, BindGroup>,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -447,6 +574,7 @@ pub fn queue_sprites(
     pipeline_cache: Res<PipelineCache>,
     msaa: Res<Msaa>,
     extracted_sprites: Res<ExtractedSprites>,
+    sprite_material_instances: Res<SpriteMaterialInstances>,
     mut views: Query<(
         &mut SortedRenderPhase<Transparent2d>,
         &VisibleEntities,
@@ -455,35 +583,10 @@ pub fn queue_sprites(
         Option<&DebandDither>,
     )>,
 ) {
-    let msaa_key = SpritePipelineKey::from_msaa_samples(msaa.samples());
-
     let draw_sprite_function = draw_functions.read().id::<DrawSprite>();
 
     for (mut transparent_phase, visible_entities, view, tonemapping, dither) in &mut views {
-        let mut view_key = SpritePipelineKey::from_hdr(view.hdr) | msaa_key;
-
-        if !view.hdr {
-            if let Some(tonemapping) = tonemapping {
-                view_key |= SpritePipelineKey::TONEMAP_IN_SHADER;
-                view_key |= match tonemapping {
-                    Tonemapping::None => SpritePipelineKey::TONEMAP_METHOD_NONE,
-                    Tonemapping::Reinhard => SpritePipelineKey::TONEMAP_METHOD_REINHARD,
-                    Tonemapping::ReinhardLuminance => {
-                        SpritePipelineKey::TONEMAP_METHOD_REINHARD_LUMINANCE
-                    }
-                    Tonemapping::AcesFitted => SpritePipelineKey::TONEMAP_METHOD_ACES_FITTED,
-                    Tonemapping::AgX => SpritePipelineKey::TONEMAP_METHOD_AGX,
-                    Tonemapping::SomewhatBoringDisplayTransform => {
-                        SpritePipelineKey::TONEMAP_METHOD_SOMEWHAT_BORING_DISPLAY_TRANSFORM
-                    }
-                    Tonemapping::TonyMcMapface => SpritePipelineKey::TONEMAP_METHOD_TONY_MC_MAPFACE,
-                    Tonemapping::BlenderFilmic => SpritePipelineKey::TONEMAP_METHOD_BLENDER_FILMIC,
-                };
-            }
-            if let Some(DebandDither::Enabled) = dither {
-                view_key |= SpritePipelineKey::DEBAND_DITHER;
-            }
-        }
+        let view_key = SpritePipelineKey::from_view(view, &msaa, tonemapping, dither);
 
         let pipeline = pipelines.specialize(&pipeline_cache, &sprite_pipeline, view_key);
 
@@ -499,9 +602,11 @@ pub fn queue_sprites(
             .reserve(extracted_sprites.sprites.len());
 
         for (entity, extracted_sprite) in extracted_sprites.sprites.iter() {
-            let index = extracted_sprite.original_entity.unwrap_or(*entity).index();
+            let main_entity = extracted_sprite.original_entity.unwrap_or(*entity);
 
-            if !view_entities.contains(index as usize) {
+            if !view_entities.contains(main_entity.index() as usize)
+                || sprite_material_instances.contains_key(&main_entity)
+            {
                 continue;
             }
 
@@ -534,6 +639,8 @@ pub fn prepare_sprites(
     mut image_bind_groups: ResMut<ImageBindGroups>,
     gpu_images: Res<RenderAssets<GpuImage>>,
     extracted_sprites: Res<ExtractedSprites>,
+    sprite_material_instances: Res<SpriteMaterialInstances>,
+    sprite_normal_map_instances: Res<SpriteNormalMapInstances>,
     mut phases: Query<&mut SortedRenderPhase<Transparent2d>>,
     events: Res<SpriteAssetEvents>,
 ) {
@@ -568,9 +675,8 @@ pub fn prepare_sprites(
         let image_bind_groups = &mut *image_bind_groups;
 
         for mut transparent_phase in &mut phases {
-            let mut batch_item_index = 0;
             let mut batch_image_size = Vec2::ZERO;
-            let mut batch_image_handle = AssetId::invalid();
+            let mut current_batch: Option<(usize, SpriteBatchKey)> = None;
 
             // Iterate through the phase items and detect when successive sprites that can be batched.
             // Spawn an entity with a `SpriteBatch` component for each possible batch.
@@ -579,23 +685,39 @@ pub fn prepare_sprites(
                 let item = &transparent_phase.items[item_index];
                 let Some(extracted_sprite) = extracted_sprites.sprites.get(&item.entity) else {
                     // If there is a phase item that is not a sprite, then we must start a new
-                    // batch to draw the other phase item(s) and to respect draw order. This can be
-                    // done by invalidating the batch_image_handle
-                    batch_image_handle = AssetId::invalid();
+                    // batch to draw the other phase item(s) and to respect draw order
+                    current_batch = None;
                     continue;
                 };
 
-                let batch_image_changed = batch_image_handle != extracted_sprite.image_handle_id;
+                // Sprites with different materials, or different specializations of a material,
+                // can't be drawn in the same batch
+                let main_entity = extracted_sprite.original_entity.unwrap_or(item.entity);
+                let material_id = sprite_material_instances.get(&main_entity).copied();
+                // Sprites with different normal maps are drawn in different batches of the normal
+                // pass, and sprites with a normal map that isn't loaded yet are lit as flat
+                let normal_map_id = sprite_normal_map_instances
+                    .get(&main_entity)
+                    .copied()
+                    .filter(|id| gpu_images.get(*id).is_some());
+                let key = SpriteBatchKey {
+                    image_handle_id: extracted_sprite.image_handle_id,
+                    material_id,
+                    normal_map_id,
+                    pipeline: item.pipeline.id(),
+                };
+                let batch_image_changed = current_batch
+                    .map(|(_, batch_key)| batch_key.image_handle_id)
+                    != Some(key.image_handle_id);
                 if batch_image_changed {
-                    let Some(gpu_image) = gpu_images.get(extracted_sprite.image_handle_id) else {
+                    let Some(gpu_image) = gpu_images.get(key.image_handle_id) else {
                         continue;
                     };
 
                     batch_image_size = gpu_image.size.as_vec2();
-                    batch_image_handle = extracted_sprite.image_handle_id;
                     image_bind_groups
                         .values
-                        .entry(batch_image_handle)
+                        .entry(key.image_handle_id)
                         .or_insert_with(|| {
                             render_device.create_bind_group(
                                 "sprite_material_bind_group",
@@ -657,22 +779,36 @@ pub fn prepare_sprites(
                         &uv_offset_scale,
                     ));
 
-                if batch_image_changed {
-                    batch_item_index = item_index;
-
-                    batches.push((
-                        item.entity,
-                        SpriteBatch {
-                            image_handle_id: batch_image_handle,
-                            range: index..index,
-                        },
-                    ));
+                let batch_item_index = batch_sprite(
+                    &mut batches,
+                    &mut current_batch,
+                    item_index,
+                    item.entity,
+                    key,
+                    index,
+                );
+                if batch_item_index == item_index {
+                    if let Some(normal_map_id) = normal_map_id {
+                        image_bind_groups
+                            .values
+                            .entry(normal_map_id)
+                            .or_insert_with(|| {
+                                let gpu_image = gpu_images.get(normal_map_id).unwrap();
+                                render_device.create_bind_group(
+                                    "sprite_material_bind_group",
+                                    &sprite_pipeline.material_layout,
+                                    &BindGroupEntries::sequential((
+                                        &gpu_image.texture_view,
+                                        &gpu_image.sampler,
+                                    )),
+                                )
+                            });
+                    }
                 }
 
                 transparent_phase.items[batch_item_index]
                     .batch_range_mut()
                     .end += 1;
-                batches.last_mut().unwrap().1.range.end += 1;
                 index += 1;
             }
         }
@@ -803,3 +939,83 @@ impl<P: PhaseItem> RenderCommand<P> for DrawSpriteBatch {
         RenderCommandResult::Success
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_asset::Asset;
+    use bevy_reflect::TypePath;
+
+    #[derive(Asset, TypePath)]
+    struct TestMaterial;
+
+    fn ranges(batches: &[(Entity, SpriteBatch)]) -> Vec<(Entity, Range<u32>)> {
+        batches
+            .iter()
+            .map(|(entity, batch)| (*entity, batch.range.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn batches_split_on_material_and_pipeline() {
+        let image = AssetId::<Image>::default();
+        let material_a = Some(Handle::<TestMaterial>::weak_from_u128(1).id().untyped());
+        let material_b = Some(Handle::<TestMaterial>::weak_from_u128(2).id().untyped());
+        let key = |material_id, pipeline| SpriteBatchKey {
+            image_handle_id: image,
+            material_id,
+            normal_map_id: None,
+            pipeline,
+        };
+        // The keys of the sprites of a phase, with `None` for the items that aren't sprites
+        let items = [
+            Some(key(None, 0)),
+            Some(key(None, 0)),
+            Some(key(material_a, 1)),
+            Some(key(material_a, 1)),
+            // The same material, specialized differently
+            Some(key(material_a, 2)),
+            Some(key(material_b, 2)),
+            Some(key(None, 0)),
+            None,
+            Some(key(None, 0)),
+        ];
+
+        let mut batches = Vec::new();
+        let mut current_batch = None;
+        let mut batch_item_indices = Vec::new();
+        let mut index = 0;
+        for (item_index, item_key) in items.into_iter().enumerate() {
+            let Some(item_key) = item_key else {
+                current_batch = None;
+                continue;
+            };
+            let entity = Entity::from_raw(item_index as u32);
+            batch_item_indices.push(batch_sprite(
+                &mut batches,
+                &mut current_batch,
+                item_index,
+                entity,
+                item_key,
+                index,
+            ));
+            index += 1;
+        }
+
+        assert_eq!(batch_item_indices, [0, 0, 2, 2, 4, 5, 6, 8]);
+        assert_eq!(
+            ranges(&batches),
+            [
+                (Entity::from_raw(0), 0..2),
+                (Entity::from_raw(2), 2..4),
+                (Entity::from_raw(4), 4..5),
+                (Entity::from_raw(5), 5..6),
+                (Entity::from_raw(6), 6..7),
+                (Entity::from_raw(8), 7..8),
+            ]
+        );
+        assert_eq!(batches[1].1.material_id(), material_a);
+        assert_eq!(batches[3].1.material_id(), material_b);
+        assert_eq!(batches[4].1.material_id(), None);
+    }
+}
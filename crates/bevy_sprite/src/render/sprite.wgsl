@@ -2,13 +2,12 @@
 #import bevy_core_pipeline::tonemapping
 #endif
 
-#import bevy_render::{
-    maths::affine3_to_square,
-    view::View,
+#import bevy_render::maths::affine3_to_square
+#import bevy_sprite::{
+    sprite_vertex_output::VertexOutput,
+    sprite_view_bindings::{view, sprite_texture, sprite_sampler},
 }
 
-@group(0) @binding(0) var<uniform> view: View;
-
 struct VertexInput {
     @builtin(vertex_index) index: u32,
     // NOTE: Instance-rate vertex buffer members prefixed with i_
@@ -21,12 +20,6 @@ struct VertexInput {
     @location(4) i_uv_offset_scale: vec4<f32>,
 }
 
-struct VertexOutput {
-    @builtin(position) clip_position: vec4<f32>,
-    @location(0) uv: vec2<f32>,
-    @location(1) @interpolate(flat) color: vec4<f32>,
-};
-
 @vertex
 fn vertex(in: VertexInput) -> VertexOutput {
     var out: VertexOutput;
@@ -48,9 +41,6 @@ fn vertex(in: VertexInput) -> VertexOutput {
     return out;
 }
 
-@group(1) @binding(0) var sprite_texture: texture_2d<f32>;
-@group(1) @binding(1) var sprite_sampler: sampler;
-
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
     var color = in.color * textureSample(sprite_texture, sprite_sampler, in.uv);
@@ -0,0 +1,7 @@
+#define_import_path bevy_sprite::sprite_vertex_output
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) @interpolate(flat) color: vec4<f32>,
+};
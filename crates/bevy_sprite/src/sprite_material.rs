@@ -0,0 +1,740 @@
+use bevy_app::{App, Plugin};
+use bevy_asset::{Asset, AssetApp, AssetServer, Handle};
+use bevy_core_pipeline::{
+    core_2d::Transparent2d,
+    tonemapping::{DebandDither, Tonemapping},
+};
+use bevy_ecs::{
+    prelude::*,
+    system::{lifetimeless::*, SystemParamItem},
+};
+use bevy_math::FloatOrd;
+use bevy_render::{
+    render_asset::{
+        prepare_assets, PrepareAssetError, RenderAsset, RenderAssetPlugin, RenderAssets,
+    },
+    render_phase::{
+        AddRenderCommand, DrawFunctions, PhaseItem, PhaseItemExtraIndex, RenderCommand,
+        RenderCommandResult, SetItemPipeline, SortedRenderPhase, TrackedRenderPass,
+    },
+    render_resource::{
+        AsBindGroup, AsBindGroupError, BindGroup, BindGroupLayout, OwnedBindingResource,
+        PipelineCache, RenderPipelineDescriptor, Shader, ShaderRef, SpecializedRenderPipeline,
+        SpecializedRenderPipelines,
+    },
+    renderer::RenderDevice,
+    texture::{FallbackImage, GpuImage},
+    view::{ExtractedView, Msaa, ViewVisibility, VisibleEntities},
+    Extract, ExtractSchedule, Render, RenderApp, RenderSet,
+};
+use fixedbitset::FixedBitSet;
+use std::{any::TypeId, hash::Hash, marker::PhantomData};
+
+use crate::{
+    DrawSpriteBatch, ExtractedSprites, SetSpriteTextureBindGroup, SetSpriteViewBindGroup, Sprite,
+    SpriteBatch, SpriteMaterialInstances, SpritePipeline, SpritePipelineKey, SpriteSystem,
+    WithSprite,
+};
+
+/// Sprite materials are used alongside [`SpriteMaterialPlugin`] to draw [`Sprite`]s with custom
+/// shader logic, by adding a [`Handle`] to the material to a sprite entity.
+///
+/// Sprites with a material keep everything the default sprite rendering supports: texture
+/// atlases, [`Anchor`](crate::Anchor), flipping and [`ImageScaleMode`](crate::ImageScaleMode)
+/// slicing. Successive sprites using the same material asset and the same image are drawn in a
+/// single instanced draw call.
+///
+/// The material bind group is bound at group 2, after the view bind group at group 0 and the
+/// sprite texture at group 1.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_sprite::{SpriteBundle, SpriteMaterial};
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_reflect::TypePath;
+/// # use bevy_render::render_resource::{AsBindGroup, ShaderRef};
+/// # use bevy_color::LinearRgba;
+/// # use bevy_asset::{Asset, AssetServer, Assets};
+///
+/// #[derive(AsBindGroup, Debug, Clone, Asset, TypePath)]
+/// pub struct FlashMaterial {
+///     /// The color the sprite flashes to, with the intensity of the flash as its alpha.
+///     #[uniform(0)]
+///     color: LinearRgba,
+/// }
+///
+/// impl SpriteMaterial for FlashMaterial {
+///     fn fragment_shader() -> ShaderRef {
+///         "shaders/flash.wgsl".into()
+///     }
+/// }
+///
+/// fn setup(
+///     mut commands: Commands,
+///     mut materials: ResMut<Assets<FlashMaterial>>,
+///     asset_server: Res<AssetServer>,
+/// ) {
+///     commands.spawn((
+///         SpriteBundle {
+///             texture: asset_server.load("player.png"),
+///             ..Default::default()
+///         },
+///         materials.add(FlashMaterial {
+///             color: LinearRgba::new(1.0, 1.0, 1.0, 0.5),
+///         }),
+///     ));
+/// }
+/// ```
+///
+/// The fragment shader receives the output of the sprite vertex shader, and can sample the
+/// texture of the sprite:
+///
+/// ```wgsl
+/// #import bevy_sprite::{
+///     sprite_vertex_output::VertexOutput,
+///     sprite_view_bindings::{sprite_texture, sprite_sampler},
+/// }
+///
+/// @group(2) @binding(0) var<uniform> flash_color: vec4<f32>;
+///
+/// @fragment
+/// fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+///     let color = in.color * textureSample(sprite_texture, sprite_sampler, in.uv);
+///     return vec4(mix(color.rgb, flash_color.rgb, flash_color.a), color.a);
+/// }
+/// ```
+pub trait SpriteMaterial: AsBindGroup + Asset + Clone + Sized {
+    /// Returns this material's vertex shader. If [`ShaderRef::Default`] is returned, the default
+    /// sprite vertex shader will be used.
+    fn vertex_shader() -> ShaderRef {
+        ShaderRef::Default
+    }
+
+    /// Returns this material's fragment shader. If [`ShaderRef::Default`] is returned, the
+    /// default sprite fragment shader will be used.
+    fn fragment_shader() -> ShaderRef {
+        ShaderRef::Default
+    }
+
+    /// Customizes the default [`RenderPipelineDescriptor`].
+    #[allow(unused_variables)]
+    #[inline]
+    fn specialize(descriptor: &mut RenderPipelineDescriptor, key: SpriteMaterialKey<Self>) {}
+}
+
+/// Adds the necessary ECS resources and render logic to draw sprites with the given
+/// [`SpriteMaterial`] asset type.
+pub struct SpriteMaterialPlugin<M: SpriteMaterial>(PhantomData<M>);
+
+impl<M: SpriteMaterial> Default for SpriteMaterialPlugin<M> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+impl<M: SpriteMaterial> Plugin for SpriteMaterialPlugin<M>
+where
+    M::Data: PartialEq + Eq + Hash + Clone,
+{
+    fn build(&self, app: &mut App) {
+        app.init_asset::<M>()
+            .add_plugins(RenderAssetPlugin::<PreparedSpriteMaterial<M>>::default());
+
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .add_render_command::<Transparent2d, DrawSpriteMaterial<M>>()
+                .init_resource::<SpecializedRenderPipelines<SpriteMaterialPipeline<M>>>()
+                .add_systems(
+                    ExtractSchedule,
+                    extract_sprite_materials::<M>.after(SpriteSystem::ExtractSprites),
+                )
+                .add_systems(
+                    Render,
+                    queue_material_sprites::<M>
+                        .in_set(RenderSet::Queue)
+                        .after(prepare_assets::<PreparedSpriteMaterial<M>>),
+                );
+        }
+    }
+
+    fn finish(&self, app: &mut App) {
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.init_resource::<SpriteMaterialPipeline<M>>();
+        }
+    }
+}
+
+fn extract_sprite_materials<M: SpriteMaterial>(
+    mut material_instances: ResMut<SpriteMaterialInstances>,
+    query: Extract<Query<(Entity, &ViewVisibility, &Handle<M>), With<Sprite>>>,
+) {
+    for (entity, view_visibility, handle) in &query {
+        if view_visibility.get() {
+            material_instances.insert(entity, handle.id().untyped());
+        }
+    }
+}
+
+/// Render pipeline data for a given [`SpriteMaterial`]
+#[derive(Resource)]
+pub struct SpriteMaterialPipeline<M: SpriteMaterial> {
+    pub sprite_pipeline: SpritePipeline,
+    pub material_layout: BindGroupLayout,
+    pub vertex_shader: Option<Handle<Shader>>,
+    pub fragment_shader: Option<Handle<Shader>>,
+    marker: PhantomData<M>,
+}
+
+pub struct SpriteMaterialKey<M: SpriteMaterial> {
+    pub sprite_key: SpritePipelineKey,
+    pub bind_group_data: M::Data,
+}
+
+impl<M: SpriteMaterial> Eq for SpriteMaterialKey<M> where M::Data: PartialEq {}
+
+impl<M: SpriteMaterial> PartialEq for SpriteMaterialKey<M>
+where
+    M::Data: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.sprite_key == other.sprite_key && self.bind_group_data == other.bind_group_data
+    }
+}
+
+impl<M: SpriteMaterial> Clone for SpriteMaterialKey<M>
+where
+    M::Data: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            sprite_key: self.sprite_key,
+            bind_group_data: self.bind_group_data.clone(),
+        }
+    }
+}
+
+impl<M: SpriteMaterial> Hash for SpriteMaterialKey<M>
+where
+    M::Data: Hash,
+{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.sprite_key.hash(state);
+        self.bind_group_data.hash(state);
+    }
+}
+
+impl<M: SpriteMaterial> Clone for SpriteMaterialPipeline<M> {
+    fn clone(&self) -> Self {
+        Self {
+            sprite_pipeline: self.sprite_pipeline.clone(),
+            material_layout: self.material_layout.clone(),
+            vertex_shader: self.vertex_shader.clone(),
+            fragment_shader: self.fragment_shader.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<M: SpriteMaterial> SpecializedRenderPipeline for SpriteMaterialPipeline<M>
+where
+    M::Data: PartialEq + Eq + Hash + Clone,
+{
+    type Key = SpriteMaterialKey<M>;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        let mut descriptor = self.sprite_pipeline.specialize(key.sprite_key);
+        if let Some(vertex_shader) = &self.vertex_shader {
+            descriptor.vertex.shader = vertex_shader.clone();
+        }
+
+        if let Some(fragment_shader) = &self.fragment_shader {
+            descriptor.fragment.as_mut().unwrap().shader = fragment_shader.clone();
+        }
+        descriptor.layout = vec![
+            self.sprite_pipeline.view_layout.clone(),
+            self.sprite_pipeline.material_layout.clone(),
+            self.material_layout.clone(),
+        ];
+        descriptor.label = Some("sprite_material_pipeline".into());
+
+        M::specialize(&mut descriptor, key);
+        descriptor
+    }
+}
+
+impl<M: SpriteMaterial> FromWorld for SpriteMaterialPipeline<M> {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        let render_device = world.resource::<RenderDevice>();
+        let material_layout = M::bind_group_layout(render_device);
+
+        SpriteMaterialPipeline {
+            sprite_pipeline: world.resource::<SpritePipeline>().clone(),
+            material_layout,
+            vertex_shader: match M::vertex_shader() {
+                ShaderRef::Default => None,
+                ShaderRef::Handle(handle) => Some(handle),
+                ShaderRef::Path(path) => Some(asset_server.load(path)),
+            },
+            fragment_shader: match M::fragment_shader() {
+                ShaderRef::Default => None,
+                ShaderRef::Handle(handle) => Some(handle),
+                ShaderRef::Path(path) => Some(asset_server.load(path)),
+            },
+            marker: PhantomData,
+        }
+    }
+}
+
+/// [`RenderCommand`] for sprite rendering with a [`SpriteMaterial`].
+pub type DrawSpriteMaterial<M> = (
+    SetItemPipeline,
+    SetSpriteViewBindGroup<0>,
+    SetSpriteTextureBindGroup<1>,
+    SetSpriteMaterialBindGroup<M, 2>,
+    DrawSpriteBatch,
+);
+
+pub struct SetSpriteMaterialBindGroup<M: SpriteMaterial, const I: usize>(PhantomData<M>);
+impl<P: PhaseItem, M: SpriteMaterial, const I: usize> RenderCommand<P>
+    for SetSpriteMaterialBindGroup<M, I>
+{
+    type Param = SRes<RenderAssets<PreparedSpriteMaterial<M>>>;
+    type ViewQuery = ();
+    type ItemQuery = Read<SpriteBatch>;
+
+    #[inline]
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        batch: Option<&'_ SpriteBatch>,
+        materials: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let materials = materials.into_inner();
+        let Some(material_id) = batch.and_then(SpriteBatch::material_id) else {
+            return RenderCommandResult::Failure;
+        };
+        let Some(material) = material_id
+            .try_typed::<M>()
+            .ok()
+            .and_then(|material_id| materials.get(material_id))
+        else {
+            return RenderCommandResult::Failure;
+        };
+        pass.set_bind_group(I, &material.bind_group, &[]);
+        RenderCommandResult::Success
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn queue_material_sprites<M: SpriteMaterial>(
+    mut view_entities: Local<FixedBitSet>,
+    draw_functions: Res<DrawFunctions<Transparent2d>>,
+    material_pipeline: Res<SpriteMaterialPipeline<M>>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<SpriteMaterialPipeline<M>>>,
+    pipeline_cache: Res<PipelineCache>,
+    msaa: Res<Msaa>,
+    extracted_sprites: Res<ExtractedSprites>,
+    material_instances: Res<SpriteMaterialInstances>,
+    render_materials: Res<RenderAssets<PreparedSpriteMaterial<M>>>,
+    mut views: Query<(
+        &mut SortedRenderPhase<Transparent2d>,
+        &VisibleEntities,
+        &ExtractedView,
+        Option<&Tonemapping>,
+        Option<&DebandDither>,
+    )>,
+) where
+    M::Data: PartialEq + Eq + Hash + Clone,
+{
+    if material_instances.is_empty() {
+        return;
+    }
+
+    let draw_sprite_material = draw_functions.read().id::<DrawSpriteMaterial<M>>();
+
+    for (mut transparent_phase, visible_entities, view, tonemapping, dither) in &mut views {
+        let view_key = SpritePipelineKey::from_view(view, &msaa, tonemapping, dither);
+
+        view_entities.clear();
+        view_entities.extend(
+            visible_entities
+                .iter::<WithSprite>()
+                .map(|e| e.index() as usize),
+        );
+
+        // Only the sprites using a material of type `M` are queued here
+        let instances = material_instances
+            .iter()
+            .filter(|(_, material_id)| material_id.type_id() == TypeId::of::<M>());
+        for (main_entity, material_id) in instances {
+            if !view_entities.contains(main_entity.index() as usize) {
+                continue;
+            }
+            let Some(material) = render_materials.get(material_id.typed_unchecked::<M>()) else {
+                continue;
+            };
+
+            let pipeline = pipelines.specialize(
+                &pipeline_cache,
+                &material_pipeline,
+                SpriteMaterialKey {
+                    sprite_key: view_key,
+                    bind_group_data: material.key.clone(),
+                },
+            );
+
+            let sprite_entities = extracted_sprites
+                .slices
+                .get(main_entity)
+                .map_or(std::slice::from_ref(main_entity), Vec::as_slice);
+            for entity in sprite_entities {
+                let Some(extracted_sprite) = extracted_sprites.sprites.get(entity) else {
+                    continue;
+                };
+                transparent_phase.add(Transparent2d {
+                    draw_function: draw_sprite_material,
+                    pipeline,
+                    entity: *entity,
+                    sort_key: FloatOrd(extracted_sprite.transform.translation().z),
+                    // batch_range and dynamic_offset will be calculated in prepare_sprites
+                    batch_range: 0..0,
+                    extra_index: PhaseItemExtraIndex::NONE,
+                });
+            }
+        }
+    }
+}
+
+/// Data prepared for a [`SpriteMaterial`] instance.
+pub struct PreparedSpriteMaterial<M: SpriteMaterial> {
+    pub bindings: Vec<(u32, OwnedBindingResource)>,
+    pub bind_group: BindGroup,
+    pub key: M::Data,
+}
+
+impl<M: SpriteMaterial> RenderAsset for PreparedSpriteMaterial<M> {
+    type SourceAsset = M;
+
+    type Param = (
+        SRes<RenderDevice>,
+        SRes<RenderAssets<GpuImage>>,
+        SRes<FallbackImage>,
+        SRes<SpriteMaterialPipeline<M>>,
+    );
+
+    fn prepare_asset(
+        material: Self::SourceAsset,
+        (render_device, images, fallback_image, pipeline): &mut SystemParamItem<Self::Param>,
+    ) -> Result<Self, PrepareAssetError<Self::SourceAsset>> {
+        match material.as_bind_group(
+            &pipeline.material_layout,
+            render_device,
+            images,
+            fallback_image,
+        ) {
+            Ok(prepared) => Ok(PreparedSpriteMaterial {
+                bindings: prepared.bindings,
+                bind_group: prepared.bind_group,
+                key: prepared.data,
+            }),
+            Err(AsBindGroupError::RetryNextUpdate) => {
+                Err(PrepareAssetError::RetryNextUpdate(material))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        compute_slices_on_sprite_change, extract_sprites, BorderRect, DrawSprite, ImageScaleMode,
+        SpriteBundle, SpriteNormalMapInstances, SpritePlugin, TextureAtlas, TextureAtlasLayout,
+        TextureSlicer,
+    };
+    use bevy_asset::{AssetPlugin, Assets};
+    use bevy_core::{FrameCountPlugin, TaskPoolPlugin};
+    use bevy_core_pipeline::{core_2d::Camera2dBundle, CorePipelinePlugin};
+    use bevy_ecs::{entity::EntityHashMap, system::RunSystemOnce};
+    use bevy_math::{UVec2, Vec2};
+    use bevy_reflect::TypePath;
+    use bevy_render::{
+        batching::gpu_preprocessing::IndirectParametersBuffer,
+        camera::{Camera, RenderTarget},
+        render_asset::RenderAssetUsages,
+        render_phase::DrawFunctionId,
+        render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+        settings::WgpuSettings,
+        texture::{Image, ImagePlugin},
+        view::Visibility,
+        MainWorld, RenderPlugin,
+    };
+    use bevy_time::TimePlugin;
+    use bevy_window::{ExitCondition, WindowPlugin};
+
+    #[derive(Asset, TypePath, AsBindGroup, Clone)]
+    struct TestMaterial {}
+
+    impl SpriteMaterial for TestMaterial {}
+
+    fn image() -> Image {
+        Image::new_fill(
+            Extent3d {
+                width: 16,
+                height: 16,
+                ..Default::default()
+            },
+            TextureDimension::D2,
+            &[255; 4],
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::default(),
+        )
+    }
+
+    /// The sprites drawn by [`sprites`]
+    struct Sprites {
+        plain: Entity,
+        material: Entity,
+        atlas: Entity,
+        sliced: Entity,
+        hidden: Entity,
+    }
+
+    /// Spawns a sprite without a material, and a plain, an atlas, a sliced and a hidden sprite
+    /// with a material.
+    fn sprites(world: &mut World, material: Handle<TestMaterial>) -> Sprites {
+        let texture = world.resource_mut::<Assets<Image>>().add(image());
+        let layout =
+            world
+                .resource_mut::<Assets<TextureAtlasLayout>>()
+                .add(TextureAtlasLayout::from_grid(
+                    UVec2::splat(8),
+                    2,
+                    2,
+                    None,
+                    None,
+                ));
+        let sprite = || SpriteBundle {
+            texture: texture.clone(),
+            view_visibility: ViewVisibility::HIDDEN,
+            ..Default::default()
+        };
+        Sprites {
+            plain: world.spawn(sprite()).id(),
+            material: world.spawn((sprite(), material.clone())).id(),
+            atlas: world
+                .spawn((
+                    sprite(),
+                    TextureAtlas {
+                        layout: layout.clone(),
+                        index: 3,
+                    },
+                    material.clone(),
+                ))
+                .id(),
+            sliced: world
+                .spawn((
+                    SpriteBundle {
+                        sprite: Sprite {
+                            custom_size: Some(Vec2::splat(64.0)),
+                            ..Default::default()
+                        },
+                        ..sprite()
+                    },
+                    ImageScaleMode::Sliced(TextureSlicer {
+                        border: BorderRect::square(4.0),
+                        ..Default::default()
+                    }),
+                    material.clone(),
+                ))
+                .id(),
+            hidden: world
+                .spawn((
+                    SpriteBundle {
+                        visibility: Visibility::Hidden,
+                        ..sprite()
+                    },
+                    material,
+                ))
+                .id(),
+        }
+    }
+
+    #[test]
+    fn sliced_and_atlas_sprites_keep_their_material() {
+        let mut main_world = MainWorld::default();
+        main_world.init_resource::<Assets<Image>>();
+        main_world.init_resource::<Assets<TextureAtlasLayout>>();
+        let material = Handle::<TestMaterial>::weak_from_u128(1);
+        let sprites = sprites(&mut main_world, material.clone());
+        main_world.run_system_once(compute_slices_on_sprite_change);
+        for entity in [
+            sprites.plain,
+            sprites.material,
+            sprites.atlas,
+            sprites.sliced,
+        ] {
+            main_world.get_mut::<ViewVisibility>(entity).unwrap().set();
+        }
+
+        // The render app reserves the entities of the main world, so that the entities spawned
+        // during extraction don't collide with them
+        let mut render_world = World::new();
+        for _ in 0..main_world.entities().len() {
+            render_world.spawn_empty();
+        }
+        render_world.insert_resource(main_world);
+        render_world.init_resource::<ExtractedSprites>();
+        render_world.init_resource::<SpriteMaterialInstances>();
+        render_world.init_resource::<SpriteNormalMapInstances>();
+        let mut extract = Schedule::new(ExtractSchedule);
+        extract.add_systems((
+            extract_sprites.in_set(SpriteSystem::ExtractSprites),
+            extract_sprite_materials::<TestMaterial>.after(SpriteSystem::ExtractSprites),
+        ));
+        extract.run(&mut render_world);
+
+        let instances = render_world.resource::<SpriteMaterialInstances>();
+        let material_id = material.id().untyped();
+        assert_eq!(instances.len(), 3);
+        for entity in [sprites.material, sprites.atlas, sprites.sliced] {
+            assert_eq!(instances.get(&entity), Some(&material_id));
+        }
+
+        // Sprites that aren't sliced are extracted with their own entity, and the atlas sprite
+        // with the rect of its index
+        let extracted = render_world.resource::<ExtractedSprites>();
+        assert_eq!(extracted.sprites.len(), 3 + 9);
+        for entity in [sprites.plain, sprites.material, sprites.atlas] {
+            assert_eq!(extracted.sprites[&entity].original_entity, None);
+        }
+        assert_eq!(
+            extracted.sprites[&sprites.atlas].rect,
+            Some(bevy_math::Rect::new(8.0, 8.0, 16.0, 16.0))
+        );
+        assert!(!extracted.sprites.contains_key(&sprites.hidden));
+
+        // The slices are extracted with new entities, mapped back to the sliced sprite
+        assert_eq!(extracted.slices.len(), 1);
+        let slices = &extracted.slices[&sprites.sliced];
+        assert_eq!(slices.len(), 9);
+        for slice in slices {
+            assert_eq!(
+                extracted.sprites[slice].original_entity,
+                Some(sprites.sliced)
+            );
+        }
+    }
+
+    /// The sprites queued in the [`Transparent2d`] phase of the only view, with their draw
+    /// function, and the slices of the extracted sprites.
+    #[derive(Resource, Default)]
+    struct Queued {
+        items: Vec<(Entity, DrawFunctionId)>,
+        slices: EntityHashMap<Vec<Entity>>,
+    }
+
+    fn record_queued(
+        mut queued: ResMut<Queued>,
+        extracted_sprites: Res<ExtractedSprites>,
+        phases: Query<&SortedRenderPhase<Transparent2d>>,
+    ) {
+        let phase = phases.single();
+        queued.items = phase
+            .items
+            .iter()
+            .map(|item| (item.entity, item.draw_function))
+            .collect();
+        queued.slices = extracted_sprites.slices.clone();
+    }
+
+    #[test]
+    fn material_sprites_are_only_queued_by_their_material() {
+        let settings = WgpuSettings::default();
+        let instance = wgpu::Instance::new(wgpu::InstanceDescriptor {
+            backends: settings.backends.unwrap(),
+            ..Default::default()
+        });
+        if bevy_tasks::block_on(instance.request_adapter(&Default::default())).is_none() {
+            // The sprites can't be queued without a GPU to create the pipelines with
+            return;
+        }
+
+        let mut app = App::new();
+        app.add_plugins((
+            TaskPoolPlugin::default(),
+            FrameCountPlugin,
+            TimePlugin,
+            AssetPlugin::default(),
+            WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                ..Default::default()
+            },
+            RenderPlugin {
+                render_creation: settings.into(),
+                synchronous_pipeline_compilation: true,
+            },
+            ImagePlugin::default(),
+            CorePipelinePlugin,
+            SpritePlugin,
+            SpriteMaterialPlugin::<TestMaterial>::default(),
+        ));
+        // The indirect parameters buffer is written by the render app even without meshes
+        app.sub_app_mut(RenderApp)
+            .insert_resource(IndirectParametersBuffer::new())
+            .init_resource::<Queued>()
+            .add_systems(
+                Render,
+                record_queued
+                    .after(RenderSet::Queue)
+                    .before(RenderSet::PhaseSort),
+            );
+        app.finish();
+        app.cleanup();
+
+        let mut target = image();
+        target.texture_descriptor.usage |= TextureUsages::RENDER_ATTACHMENT;
+        let target = app.world_mut().resource_mut::<Assets<Image>>().add(target);
+        app.world_mut().spawn(Camera2dBundle {
+            camera: Camera {
+                target: RenderTarget::Image(target),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let material = app
+            .world_mut()
+            .resource_mut::<Assets<TestMaterial>>()
+            .add(TestMaterial {});
+        let sprites = sprites(app.world_mut(), material);
+        app.update();
+
+        let render_world = app.sub_app(RenderApp).world();
+        let draw_functions = render_world
+            .resource::<DrawFunctions<Transparent2d>>()
+            .read();
+        let draw_sprite = draw_functions.id::<DrawSprite>();
+        let draw_material = draw_functions.id::<DrawSpriteMaterial<TestMaterial>>();
+        let queued = render_world.resource::<Queued>();
+        let queued_with = |draw_function| {
+            let mut entities: Vec<_> = queued
+                .items
+                .iter()
+                .filter(|(_, item_draw_function)| *item_draw_function == draw_function)
+                .map(|(entity, _)| *entity)
+                .collect();
+            entities.sort();
+            entities
+        };
+
+        assert_eq!(queued_with(draw_sprite), [sprites.plain]);
+        let mut expected = vec![sprites.material, sprites.atlas];
+        expected.extend(&queued.slices[&sprites.sliced]);
+        expected.sort();
+        assert_eq!(queued_with(draw_material), expected);
+    }
+}
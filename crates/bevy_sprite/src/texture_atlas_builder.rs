@@ -40,6 +40,8 @@ pub struct TextureAtlasBuilder<'a> {
     auto_format_conversion: bool,
     /// The amount of padding in pixels to add along the right and bottom edges of the texture rects.
     padding: UVec2,
+    /// The number of times the edge pixels of each texture are repeated around it.
+    extrusion: u32,
 }
 
 impl Default for TextureAtlasBuilder<'_> {
@@ -51,6 +53,7 @@ impl Default for TextureAtlasBuilder<'_> {
             format: TextureFormat::Rgba8UnormSrgb,
             auto_format_conversion: true,
             padding: UVec2::ZERO,
+            extrusion: 0,
         }
     }
 }
@@ -98,26 +101,53 @@ impl<'a> TextureAtlasBuilder<'a> {
         self
     }
 
+    /// Sets the number of pixels to extrude around each texture in the texture atlas.
+    ///
+    /// The edge pixels of each texture are repeated `extrusion` times on every side, so that
+    /// filtering or rounding errors at the edges of a texture sample its own color instead of
+    /// bleeding a neighboring texture. The extruded pixels are not part of the rects of the
+    /// [`TextureAtlasLayout`].
+    pub fn extrusion(mut self, extrusion: u32) -> Self {
+        self.extrusion = extrusion;
+        self
+    }
+
     fn copy_texture_to_atlas(
         atlas_texture: &mut Image,
         texture: &Image,
         packed_location: &PackedLocation,
         padding: UVec2,
+        extrusion: u32,
     ) {
-        let rect_width = (packed_location.width() - padding.x) as usize;
-        let rect_height = (packed_location.height() - padding.y) as usize;
+        let extrusion = extrusion as usize;
+        let rect_width = packed_location.width() as usize - padding.x as usize - 2 * extrusion;
+        let rect_height = packed_location.height() as usize - padding.y as usize - 2 * extrusion;
+        if rect_width == 0 || rect_height == 0 {
+            return;
+        }
         let rect_x = packed_location.x() as usize;
         let rect_y = packed_location.y() as usize;
         let atlas_width = atlas_texture.width() as usize;
         let format_size = atlas_texture.texture_descriptor.format.pixel_size();
+        let row_size = rect_width * format_size;
 
-        for (texture_y, bound_y) in (rect_y..rect_y + rect_height).enumerate() {
-            let begin = (bound_y * atlas_width + rect_x) * format_size;
-            let end = begin + rect_width * format_size;
-            let texture_begin = texture_y * rect_width * format_size;
-            let texture_end = texture_begin + rect_width * format_size;
-            atlas_texture.data[begin..end]
-                .copy_from_slice(&texture.data[texture_begin..texture_end]);
+        for bound_y in 0..rect_height + 2 * extrusion {
+            // The extruded rows repeat the first and last rows of the texture
+            let texture_y = bound_y.saturating_sub(extrusion).min(rect_height - 1);
+            let texture_row = &texture.data[texture_y * row_size..(texture_y + 1) * row_size];
+            let begin = ((rect_y + bound_y) * atlas_width + rect_x) * format_size;
+            let row =
+                &mut atlas_texture.data[begin..begin + row_size + 2 * extrusion * format_size];
+
+            let (left, rest) = row.split_at_mut(extrusion * format_size);
+            let (middle, right) = rest.split_at_mut(row_size);
+            middle.copy_from_slice(texture_row);
+            for pixel in left.chunks_exact_mut(format_size) {
+                pixel.copy_from_slice(&texture_row[..format_size]);
+            }
+            for pixel in right.chunks_exact_mut(format_size) {
+                pixel.copy_from_slice(&texture_row[row_size - format_size..]);
+            }
         }
     }
 
@@ -128,7 +158,13 @@ impl<'a> TextureAtlasBuilder<'a> {
         packed_location: &PackedLocation,
     ) {
         if self.format == texture.texture_descriptor.format {
-            Self::copy_texture_to_atlas(atlas_texture, texture, packed_location, self.padding);
+            Self::copy_texture_to_atlas(
+                atlas_texture,
+                texture,
+                packed_location,
+                self.padding,
+                self.extrusion,
+            );
         } else if let Some(converted_texture) = texture.convert(self.format) {
             debug!(
                 "Converting texture from '{:?}' to '{:?}'",
@@ -139,6 +175,7 @@ impl<'a> TextureAtlasBuilder<'a> {
                 &converted_texture,
                 packed_location,
                 self.padding,
+                self.extrusion,
             );
         } else {
             error!(
@@ -200,8 +237,8 @@ impl<'a> TextureAtlasBuilder<'a> {
                 index,
                 None,
                 RectToInsert::new(
-                    texture.width() + self.padding.x,
-                    texture.height() + self.padding.y,
+                    texture.width() + self.padding.x + 2 * self.extrusion,
+                    texture.height() + self.padding.y + 2 * self.extrusion,
                     1,
                 ),
             );
@@ -259,9 +296,9 @@ impl<'a> TextureAtlasBuilder<'a> {
         for (index, (image_id, texture)) in self.textures_to_place.iter().enumerate() {
             let (_, packed_location) = rect_placements.packed_locations().get(&index).unwrap();
 
-            let min = UVec2::new(packed_location.x(), packed_location.y());
-            let max =
-                min + UVec2::new(packed_location.width(), packed_location.height()) - self.padding;
+            let min =
+                UVec2::new(packed_location.x(), packed_location.y()) + UVec2::splat(self.extrusion);
+            let max = min + UVec2::new(texture.width(), texture.height());
             if let Some(image_id) = image_id {
                 texture_ids.insert(*image_id, index);
             }
@@ -286,3 +323,43 @@ impl<'a> TextureAtlasBuilder<'a> {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extruded_texture() {
+        // A 2×1 texture with a red and a green pixel
+        let texture = Image::new(
+            Extent3d {
+                width: 2,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            vec![255, 0, 0, 255, 0, 255, 0, 255],
+            TextureFormat::Rgba8UnormSrgb,
+            RenderAssetUsages::MAIN_WORLD,
+        );
+        let mut builder = TextureAtlasBuilder::default()
+            .initial_size(UVec2::splat(8))
+            .extrusion(1);
+        builder.add_texture(None, &texture);
+        let (layout, atlas) = builder.finish().unwrap();
+
+        let rect = layout.textures[0];
+        assert_eq!(rect.size(), UVec2::new(2, 1));
+        let pixel = |x: u32, y: u32| {
+            let offset = ((y * atlas.width() + x) * 4) as usize;
+            &atlas.data[offset..offset + 4]
+        };
+        // Every pixel around the texture repeats its closest edge pixel
+        for y in rect.min.y - 1..=rect.max.y {
+            assert_eq!(pixel(rect.min.x - 1, y), [255, 0, 0, 255]);
+            assert_eq!(pixel(rect.min.x, y), [255, 0, 0, 255]);
+            assert_eq!(pixel(rect.max.x - 1, y), [0, 255, 0, 255]);
+            assert_eq!(pixel(rect.max.x, y), [0, 255, 0, 255]);
+        }
+    }
+}
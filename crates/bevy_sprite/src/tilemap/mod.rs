@@ -0,0 +1,514 @@
+//! Chunked tilemaps drawing a grid of tiles from a tileset [`TextureAtlasLayout`].
+//!
+//! A [`Tilemap`] stores its tiles by chunks of [`Tilemap::chunk_size`] tiles. Each chunk is
+//! uploaded to the GPU as a texture holding the atlas index, flips, color and animation of its
+//! tiles, and drawn with a single instanced draw call. Only the chunks whose tiles changed since
+//! they were last uploaded are written again, and the chunks outside of a view aren't drawn.
+
+mod render;
+#[cfg(feature = "tmx")]
+mod tmx;
+
+pub use render::*;
+#[cfg(feature = "tmx")]
+pub use tmx::*;
+
+use std::{
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+
+use bevy_app::{App, Plugin};
+use bevy_asset::{load_internal_asset, Handle};
+use bevy_color::Color;
+use bevy_core_pipeline::core_2d::Transparent2d;
+use bevy_ecs::prelude::*;
+use bevy_math::{UVec2, Vec2};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use bevy_render::{
+    render_phase::AddRenderCommand,
+    render_resource::{Shader, SpecializedRenderPipelines},
+    texture::Image,
+    view::{InheritedVisibility, ViewVisibility, Visibility},
+    ExtractSchedule, Render, RenderApp, RenderSet,
+};
+use bevy_transform::components::{GlobalTransform, Transform};
+
+use crate::TextureAtlasLayout;
+
+pub const TILEMAP_SHADER_HANDLE: Handle<Shader> = Handle::weak_from_u128(6039184712853301947);
+
+/// Every change of a chunk of any tilemap gets a new number, so that replacing a tilemap with
+/// another one of the same size is noticed too.
+static NEXT_CHUNK_CHANGE: AtomicU32 = AtomicU32::new(0);
+
+fn next_chunk_change() -> u32 {
+    NEXT_CHUNK_CHANGE.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Adds support for drawing [`Tilemap`]s.
+///
+/// This plugin is added by the [`SpritePlugin`](crate::SpritePlugin).
+#[derive(Default)]
+pub struct TilemapPlugin;
+
+impl Plugin for TilemapPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            TILEMAP_SHADER_HANDLE,
+            "tilemap.wgsl",
+            Shader::from_wgsl
+        );
+
+        app.register_type::<Tilemap>()
+            .register_type::<TilemapGrid>()
+            .register_type::<Tile>()
+            .register_type::<TileAnimation>();
+
+        #[cfg(feature = "tmx")]
+        {
+            use bevy_asset::AssetApp;
+            app.init_asset::<TiledMap>()
+                .init_asset_loader::<TiledMapLoader>();
+        }
+
+        let Some(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+
+        render_app
+            .init_resource::<RenderTilemaps>()
+            .init_resource::<SpecializedRenderPipelines<TilemapPipeline>>()
+            .add_render_command::<Transparent2d, DrawTilemap>()
+            .add_systems(ExtractSchedule, extract_tilemaps)
+            .add_systems(
+                Render,
+                (
+                    queue_tilemaps.in_set(RenderSet::Queue),
+                    prepare_tilemaps.in_set(RenderSet::PrepareResources),
+                    prepare_tilemap_bind_groups.in_set(RenderSet::PrepareBindGroups),
+                ),
+            );
+    }
+
+    fn finish(&self, app: &mut App) {
+        if let Some(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.init_resource::<TilemapPipeline>();
+        }
+    }
+}
+
+/// How the cells of a [`Tilemap`] are laid out.
+///
+/// Whatever the grid, the image of a tile is drawn at the size of its rect in the tileset, standing
+/// on the bottom of its cell and centered horizontally, so tiles can be taller than their cell.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Default, PartialEq)]
+pub enum TilemapGrid {
+    /// Rows and columns of rectangular cells.
+    ///
+    /// Tile `(0, 0)` is the bottom left one, +X goes right and +Y goes up.
+    #[default]
+    Square,
+    /// Diamond shaped cells of an isometric map.
+    ///
+    /// Tile `(0, 0)` is the top corner of the map, at the origin. +X goes down and to the right,
+    /// +Y goes down and to the left, so that the tiles with a greater `x + y` are in front.
+    Isometric,
+    /// Hexagonal cells, with every other row or column shifted by half a cell.
+    ///
+    /// Tile `(0, 0)` is the bottom left one, +X goes right and +Y goes up.
+    Hexagonal {
+        /// Whether the rows or the columns are shifted.
+        stagger_axis: HexStaggerAxis,
+        /// Which of the rows or columns are shifted.
+        stagger_index: HexStaggerIndex,
+        /// The length of the flat sides of the hexagons along the stagger axis, in world units.
+        ///
+        /// Rows or columns overlap by the size of the cell minus this length, halved.
+        side_length: f32,
+    },
+}
+
+/// The axis along which the rows or columns of a [`TilemapGrid::Hexagonal`] grid are shifted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Default, PartialEq, Hash)]
+pub enum HexStaggerAxis {
+    /// Every other column is shifted up by half a cell, for hexagons with a flat top.
+    X,
+    /// Every other row is shifted right by half a cell, for hexagons with a pointy top.
+    #[default]
+    Y,
+}
+
+/// Which rows or columns of a [`TilemapGrid::Hexagonal`] grid are shifted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+#[reflect(Default, PartialEq, Hash)]
+pub enum HexStaggerIndex {
+    /// The rows or columns with an odd index are shifted.
+    #[default]
+    Odd,
+    /// The rows or columns with an even index are shifted.
+    Even,
+}
+
+impl TilemapGrid {
+    /// Returns the center of the cell at `position`, in the local space of the tilemap, for cells
+    /// of `tile_size`.
+    pub fn tile_center(&self, position: UVec2, tile_size: Vec2) -> Vec2 {
+        let cell = position.as_vec2();
+        match *self {
+            TilemapGrid::Square => (cell + 0.5) * tile_size,
+            TilemapGrid::Isometric => Vec2::new(
+                (cell.x - cell.y) * tile_size.x * 0.5,
+                -(cell.x + cell.y + 1.0) * tile_size.y * 0.5,
+            ),
+            TilemapGrid::Hexagonal {
+                stagger_axis,
+                stagger_index,
+                side_length,
+            } => {
+                let is_staggered =
+                    |index: u32| (index % 2 == 1) == (stagger_index == HexStaggerIndex::Odd);
+                match stagger_axis {
+                    HexStaggerAxis::X => {
+                        let shift = if is_staggered(position.x) { 0.5 } else { 0.0 };
+                        Vec2::new(
+                            cell.x * (tile_size.x + side_length) * 0.5 + tile_size.x * 0.5,
+                            (cell.y + 0.5 + shift) * tile_size.y,
+                        )
+                    }
+                    HexStaggerAxis::Y => {
+                        let shift = if is_staggered(position.y) { 0.5 } else { 0.0 };
+                        Vec2::new(
+                            (cell.x + 0.5 + shift) * tile_size.x,
+                            cell.y * (tile_size.y + side_length) * 0.5 + tile_size.y * 0.5,
+                        )
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A tile of a [`Tilemap`].
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(Default, PartialEq)]
+pub struct Tile {
+    /// The index of the image of the tile in the [`TextureAtlasLayout`] of the tileset.
+    pub index: usize,
+    /// Flips the tile horizontally.
+    pub flip_x: bool,
+    /// Flips the tile vertically.
+    pub flip_y: bool,
+    /// Flips the tile along its top left to bottom right diagonal, before the other flips.
+    ///
+    /// Combined with [`flip_x`](Self::flip_x) and [`flip_y`](Self::flip_y), this rotates the
+    /// tile by quarter turns. It's only meaningful for square tile images.
+    pub flip_diagonal: bool,
+    /// The color the image of the tile is multiplied with.
+    ///
+    /// Its components are clamped between 0 and 1.
+    pub color: Color,
+    /// Cycles through the images following [`index`](Self::index) in the tileset.
+    pub animation: Option<TileAnimation>,
+}
+
+impl Tile {
+    /// Creates a tile showing the image at `index` in the tileset.
+    pub fn new(index: usize) -> Self {
+        Self {
+            index,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for Tile {
+    fn default() -> Self {
+        Self {
+            index: 0,
+            flip_x: false,
+            flip_y: false,
+            flip_diagonal: false,
+            color: Color::WHITE,
+            animation: None,
+        }
+    }
+}
+
+/// Animates a [`Tile`] by showing `frame_count` consecutive images of the tileset in a loop,
+/// starting at [`Tile::index`].
+///
+/// Animations are played on the GPU from the time since startup, so every tile with the same
+/// animation shows the same frame.
+#[derive(Clone, Copy, Debug, PartialEq, Reflect)]
+#[reflect(PartialEq)]
+pub struct TileAnimation {
+    /// The number of images of the animation, up to 255.
+    pub frame_count: u8,
+    /// How long each image is shown, with a millisecond precision and up to about 65 seconds.
+    pub frame_duration: Duration,
+}
+
+/// A grid of [`Tile`]s, drawn with the [`Handle<Image>`] of the entity as tileset texture and the
+/// [`Handle<TextureAtlasLayout>`] of the entity as the rects of the tiles in it.
+///
+/// The tiles are grouped in chunks of [`chunk_size`](Self::chunk_size) tiles. Changing a tile
+/// uploads its whole chunk to the GPU again, so smaller chunks make changes cheaper at the cost of
+/// a draw call per visible chunk.
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component)]
+pub struct Tilemap {
+    /// The size of a cell of the grid, in world units.
+    pub tile_size: Vec2,
+    /// How the cells are laid out.
+    pub grid: TilemapGrid,
+    size: UVec2,
+    chunk_size: UVec2,
+    tiles: Vec<Option<Tile>>,
+    chunk_changes: Vec<u32>,
+}
+
+impl Tilemap {
+    /// The default number of tiles of a chunk along each axis.
+    pub const DEFAULT_CHUNK_SIZE: UVec2 = UVec2::splat(32);
+
+    /// Creates an empty tilemap of `size` tiles.
+    pub fn new(size: UVec2, tile_size: Vec2, grid: TilemapGrid) -> Self {
+        Self {
+            tile_size,
+            grid,
+            size,
+            chunk_size: UVec2::ONE,
+            tiles: vec![None; size.x as usize * size.y as usize],
+            chunk_changes: Vec::new(),
+        }
+        .with_chunk_size(Self::DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Changes the number of tiles of the chunks along each axis.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero along an axis.
+    pub fn with_chunk_size(mut self, chunk_size: UVec2) -> Self {
+        assert!(
+            chunk_size.cmpgt(UVec2::ZERO).all(),
+            "The chunks of a tilemap can't be empty"
+        );
+        self.chunk_size = chunk_size;
+        let chunk_count = self.chunk_count();
+        self.chunk_changes =
+            vec![next_chunk_change(); chunk_count.x as usize * chunk_count.y as usize];
+        self
+    }
+
+    /// The number of tiles of the tilemap along each axis.
+    #[inline]
+    pub fn size(&self) -> UVec2 {
+        self.size
+    }
+
+    /// The number of tiles of the chunks along each axis.
+    #[inline]
+    pub fn chunk_size(&self) -> UVec2 {
+        self.chunk_size
+    }
+
+    /// The number of chunks of the tilemap along each axis.
+    #[inline]
+    pub fn chunk_count(&self) -> UVec2 {
+        (self.size + self.chunk_size - UVec2::ONE) / self.chunk_size
+    }
+
+    /// Returns the tile at `position`, if there is one.
+    pub fn get(&self, position: UVec2) -> Option<&Tile> {
+        let index = self.tile_index(position)?;
+        self.tiles[index].as_ref()
+    }
+
+    /// Returns the tile at `position` mutably, if there is one.
+    ///
+    /// Its chunk is uploaded again whether or not the tile is changed.
+    pub fn get_mut(&mut self, position: UVec2) -> Option<&mut Tile> {
+        let index = self.tile_index(position)?;
+        self.tiles[index].as_ref()?;
+        self.mark_changed(position);
+        self.tiles[index].as_mut()
+    }
+
+    /// Replaces the tile at `position`, or removes it with `None`, and returns the previous one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `position` is outside of the tilemap.
+    pub fn set(&mut self, position: UVec2, tile: impl Into<Option<Tile>>) -> Option<Tile> {
+        let Some(index) = self.tile_index(position) else {
+            panic!(
+                "Tile {position} is outside of a tilemap of size {}",
+                self.size
+            );
+        };
+        self.mark_changed(position);
+        std::mem::replace(&mut self.tiles[index], tile.into())
+    }
+
+    /// Replaces every tile of the tilemap, or removes them with `None`.
+    pub fn fill(&mut self, tile: impl Into<Option<Tile>>) {
+        self.tiles.fill(tile.into());
+        self.chunk_changes.fill(next_chunk_change());
+    }
+
+    /// Returns an iterator over the tiles of the tilemap and their positions.
+    pub fn iter(&self) -> impl Iterator<Item = (UVec2, &Tile)> {
+        let width = self.size.x as usize;
+        self.tiles
+            .iter()
+            .enumerate()
+            .filter_map(move |(index, tile)| {
+                let position = UVec2::new((index % width) as u32, (index / width) as u32);
+                tile.as_ref().map(|tile| (position, tile))
+            })
+    }
+
+    /// Returns the center of the cell at `position`, in the local space of the tilemap.
+    pub fn tile_center(&self, position: UVec2) -> Vec2 {
+        self.grid.tile_center(position, self.tile_size)
+    }
+
+    /// Returns the first tile of the chunk at `chunk`, and its number of tiles along each axis,
+    /// which is smaller than [`chunk_size`](Self::chunk_size) on the edges of the tilemap.
+    pub(crate) fn chunk_extent(&self, chunk: UVec2) -> (UVec2, UVec2) {
+        let origin = chunk * self.chunk_size;
+        (origin, self.chunk_size.min(self.size - origin))
+    }
+
+    /// A number that changes every time a tile of the chunk at `chunk_index` may have changed.
+    pub(crate) fn chunk_change(&self, chunk_index: usize) -> u32 {
+        self.chunk_changes[chunk_index]
+    }
+
+    fn tile_index(&self, position: UVec2) -> Option<usize> {
+        position
+            .cmplt(self.size)
+            .all()
+            .then(|| position.y as usize * self.size.x as usize + position.x as usize)
+    }
+
+    fn mark_changed(&mut self, position: UVec2) {
+        let chunk = position / self.chunk_size;
+        let chunk_index = (chunk.y * self.chunk_count().x + chunk.x) as usize;
+        self.chunk_changes[chunk_index] = next_chunk_change();
+    }
+}
+
+/// A bundle of components for drawing a [`Tilemap`].
+#[derive(Bundle, Clone, Debug)]
+pub struct TilemapBundle {
+    pub tilemap: Tilemap,
+    /// The rects of the tiles in the [`texture`](Self::texture).
+    pub layout: Handle<TextureAtlasLayout>,
+    /// The texture of the tileset.
+    pub texture: Handle<Image>,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    /// User indication of whether an entity is visible
+    pub visibility: Visibility,
+    /// Inherited visibility of an entity.
+    pub inherited_visibility: InheritedVisibility,
+    /// Algorithmically-computed indication of whether an entity is visible and should be extracted for rendering
+    pub view_visibility: ViewVisibility,
+}
+
+impl TilemapBundle {
+    /// Creates a bundle drawing `tilemap` with the given tileset.
+    pub fn new(
+        tilemap: Tilemap,
+        texture: Handle<Image>,
+        layout: Handle<TextureAtlasLayout>,
+    ) -> Self {
+        Self {
+            tilemap,
+            layout,
+            texture,
+            transform: Transform::default(),
+            global_transform: GlobalTransform::default(),
+            visibility: Visibility::default(),
+            inherited_visibility: InheritedVisibility::default(),
+            view_visibility: ViewVisibility::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_math::{UVec2, Vec2};
+
+    use super::{HexStaggerAxis, HexStaggerIndex, Tile, Tilemap, TilemapGrid};
+
+    #[test]
+    fn tile_changes_mark_their_chunk() {
+        let mut tilemap = Tilemap::new(UVec2::new(10, 5), Vec2::splat(16.0), TilemapGrid::Square)
+            .with_chunk_size(UVec2::splat(4));
+        assert_eq!(tilemap.chunk_count(), UVec2::new(3, 2));
+        assert_eq!(
+            tilemap.chunk_extent(UVec2::new(2, 1)),
+            (UVec2::new(8, 4), UVec2::new(2, 1))
+        );
+
+        let changes = |tilemap: &Tilemap| -> Vec<u32> {
+            (0..6).map(|chunk| tilemap.chunk_change(chunk)).collect()
+        };
+        let initial_changes = changes(&tilemap);
+
+        assert_eq!(tilemap.set(UVec2::new(9, 4), Tile::new(3)), None);
+        assert_eq!(tilemap.get(UVec2::new(9, 4)), Some(&Tile::new(3)));
+        let new_changes = changes(&tilemap);
+        assert_eq!(new_changes[..5], initial_changes[..5]);
+        assert_ne!(new_changes[5], initial_changes[5]);
+
+        // Empty tiles can't be changed, so their chunk isn't uploaded again
+        assert!(tilemap.get_mut(UVec2::new(0, 0)).is_none());
+        assert_eq!(tilemap.chunk_change(0), initial_changes[0]);
+        assert!(tilemap.get(UVec2::new(10, 0)).is_none());
+
+        assert_eq!(
+            tilemap.iter().collect::<Vec<_>>(),
+            vec![(UVec2::new(9, 4), &Tile::new(3))]
+        );
+    }
+
+    #[test]
+    fn tile_centers() {
+        let tile_size = Vec2::new(32.0, 16.0);
+        let position = UVec2::new(1, 2);
+        assert_eq!(
+            TilemapGrid::Square.tile_center(position, tile_size),
+            Vec2::new(48.0, 40.0)
+        );
+        assert_eq!(
+            TilemapGrid::Isometric.tile_center(position, tile_size),
+            Vec2::new(-16.0, -32.0)
+        );
+
+        let hexagonal = |stagger_axis, stagger_index| TilemapGrid::Hexagonal {
+            stagger_axis,
+            stagger_index,
+            side_length: 8.0,
+        };
+        assert_eq!(
+            hexagonal(HexStaggerAxis::Y, HexStaggerIndex::Odd).tile_center(position, tile_size),
+            Vec2::new(48.0, 32.0)
+        );
+        assert_eq!(
+            hexagonal(HexStaggerAxis::Y, HexStaggerIndex::Even).tile_center(position, tile_size),
+            Vec2::new(64.0, 32.0)
+        );
+        assert_eq!(
+            hexagonal(HexStaggerAxis::X, HexStaggerIndex::Odd).tile_center(position, tile_size),
+            Vec2::new(36.0, 48.0)
+        );
+    }
+}
@@ -0,0 +1,733 @@
+use bevy_asset::{AssetEvent, AssetId, Assets, Handle};
+use bevy_color::LinearRgba;
+use bevy_core_pipeline::{
+    core_2d::Transparent2d,
+    tonemapping::{DebandDither, Tonemapping},
+};
+use bevy_ecs::{
+    entity::EntityHashMap,
+    prelude::*,
+    system::{lifetimeless::*, SystemParamItem},
+};
+use bevy_math::{FloatOrd, Mat4, Rect, UVec2, Vec2, Vec3Swizzles};
+use bevy_render::{
+    globals::{GlobalsBuffer, GlobalsUniform},
+    render_asset::RenderAssets,
+    render_phase::{
+        DrawFunctions, PhaseItem, PhaseItemExtraIndex, RenderCommand, RenderCommandResult,
+        SetItemPipeline, SortedRenderPhase, TrackedRenderPass,
+    },
+    render_resource::{
+        binding_types::{sampler, texture_2d, uniform_buffer},
+        *,
+    },
+    renderer::{RenderDevice, RenderQueue},
+    texture::{GpuImage, Image},
+    view::{ExtractedView, InheritedVisibility, Msaa, RenderLayers},
+    Extract,
+};
+use bevy_transform::components::GlobalTransform;
+use bevy_utils::HashMap;
+
+use super::{HexStaggerAxis, HexStaggerIndex, Tile, Tilemap, TilemapGrid, TILEMAP_SHADER_HANDLE};
+use crate::{
+    light2d::view_world_rect, SetSpriteViewBindGroup, SpriteMeta, SpritePipeline,
+    SpritePipelineKey, TextureAtlasLayout,
+};
+
+/// The number of tile rects in a row of the texture holding the rects of a tileset.
+const TILESET_RECTS_TEXTURE_WIDTH: u32 = 256;
+
+const TILE_FLIP_X_BIT: u32 = 1 << 0;
+const TILE_FLIP_Y_BIT: u32 = 1 << 1;
+const TILE_FLIP_DIAGONAL_BIT: u32 = 1 << 2;
+
+const TILEMAP_GRID_SQUARE: u32 = 0;
+const TILEMAP_GRID_ISOMETRIC: u32 = 1;
+const TILEMAP_GRID_HEXAGONAL_STAGGER_X: u32 = 2;
+const TILEMAP_GRID_HEXAGONAL_STAGGER_Y: u32 = 3;
+
+/// A visible [`Tilemap`], extracted to its render world entity.
+///
+/// Its tiles aren't extracted every frame: the chunks whose tiles changed are kept in
+/// [`RenderTilemaps`] until they are uploaded.
+#[derive(Component, Clone, Debug)]
+pub struct ExtractedTilemap {
+    pub transform: GlobalTransform,
+    pub tile_size: Vec2,
+    pub grid: TilemapGrid,
+    pub size: UVec2,
+    pub chunk_size: UVec2,
+    pub texture: AssetId<Image>,
+    pub layout: AssetId<TextureAtlasLayout>,
+    pub render_layers: RenderLayers,
+}
+
+impl ExtractedTilemap {
+    fn chunk_count(&self) -> UVec2 {
+        (self.size + self.chunk_size - UVec2::ONE) / self.chunk_size
+    }
+}
+
+#[derive(Clone, Copy, ShaderType, Debug)]
+pub struct TilemapUniform {
+    world_from_local: Mat4,
+    tile_size: Vec2,
+    hex_side_length: f32,
+    grid: u32,
+    stagger_odd: u32,
+}
+
+impl From<&ExtractedTilemap> for TilemapUniform {
+    fn from(tilemap: &ExtractedTilemap) -> Self {
+        let (grid, hex_side_length, stagger_odd) = match tilemap.grid {
+            TilemapGrid::Square => (TILEMAP_GRID_SQUARE, 0.0, false),
+            TilemapGrid::Isometric => (TILEMAP_GRID_ISOMETRIC, 0.0, false),
+            TilemapGrid::Hexagonal {
+                stagger_axis,
+                stagger_index,
+                side_length,
+            } => (
+                match stagger_axis {
+                    HexStaggerAxis::X => TILEMAP_GRID_HEXAGONAL_STAGGER_X,
+                    HexStaggerAxis::Y => TILEMAP_GRID_HEXAGONAL_STAGGER_Y,
+                },
+                side_length,
+                stagger_index == HexStaggerIndex::Odd,
+            ),
+        };
+        TilemapUniform {
+            world_from_local: tilemap.transform.compute_matrix(),
+            tile_size: tilemap.tile_size,
+            hex_side_length,
+            grid,
+            stagger_odd: stagger_odd as u32,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ShaderType, Debug)]
+struct TilemapChunkUniform {
+    origin: UVec2,
+    size: UVec2,
+}
+
+/// The GPU data of the tilemaps and of their tilesets.
+///
+/// It's kept from frame to frame, so that only the chunks whose tiles changed are uploaded again.
+#[derive(Resource, Default)]
+pub struct RenderTilemaps {
+    tilemaps: EntityHashMap<GpuTilemap>,
+    tilesets: HashMap<AssetId<TextureAtlasLayout>, GpuTileset>,
+    uniforms: DynamicUniformBuffer<TilemapUniform>,
+}
+
+struct GpuTileset {
+    /// The rects of the tiles in the tileset texture, in pixels, waiting to be uploaded.
+    pending_rects: Option<Vec<[f32; 4]>>,
+    rects_texture: Option<TextureView>,
+    /// The size of the largest tile, which may overflow its cell.
+    max_tile_size: Vec2,
+}
+
+impl GpuTileset {
+    fn new(layout: &TextureAtlasLayout) -> Self {
+        let rects = layout
+            .textures
+            .iter()
+            .map(|rect| rect.as_rect())
+            .map(|rect| [rect.min.x, rect.min.y, rect.max.x, rect.max.y])
+            .collect();
+        let max_tile_size = layout
+            .textures
+            .iter()
+            .fold(Vec2::ZERO, |size, rect| size.max(rect.size().as_vec2()));
+        Self {
+            pending_rects: Some(rects),
+            rects_texture: None,
+            max_tile_size,
+        }
+    }
+}
+
+struct GpuTilemap {
+    size: UVec2,
+    chunk_size: UVec2,
+    chunks: Vec<GpuTilemapChunk>,
+    uniform_offset: u32,
+    bind_group: Option<BindGroup>,
+}
+
+impl GpuTilemap {
+    fn new(tilemap: &Tilemap) -> Self {
+        let chunk_count = tilemap.chunk_count();
+        let chunks = (0..chunk_count.y)
+            .flat_map(|y| (0..chunk_count.x).map(move |x| UVec2::new(x, y)))
+            .map(|chunk| {
+                let (origin, size) = tilemap.chunk_extent(chunk);
+                GpuTilemapChunk {
+                    origin,
+                    size,
+                    change: None,
+                    pending_tiles: None,
+                    bindings: None,
+                }
+            })
+            .collect();
+        Self {
+            size: tilemap.size(),
+            chunk_size: tilemap.chunk_size(),
+            chunks,
+            uniform_offset: 0,
+            bind_group: None,
+        }
+    }
+}
+
+struct GpuTilemapChunk {
+    origin: UVec2,
+    size: UVec2,
+    /// The change counter of the chunk when its tiles were last extracted.
+    change: Option<u32>,
+    /// The encoded tiles of the chunk, waiting to be uploaded.
+    pending_tiles: Option<Vec<[u32; 4]>>,
+    bindings: Option<GpuTilemapChunkBindings>,
+}
+
+struct GpuTilemapChunkBindings {
+    texture: Texture,
+    bind_group: BindGroup,
+}
+
+impl GpuTilemapChunk {
+    fn upload(
+        &mut self,
+        tiles: &[[u32; 4]],
+        render_device: &RenderDevice,
+        render_queue: &RenderQueue,
+        chunk_layout: &BindGroupLayout,
+    ) {
+        let size = Extent3d {
+            width: self.size.x,
+            height: self.size.y,
+            depth_or_array_layers: 1,
+        };
+        let bindings = self.bindings.get_or_insert_with(|| {
+            let texture = render_device.create_texture(&TextureDescriptor {
+                label: Some("tilemap_chunk_texture"),
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Rgba32Uint,
+                usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+                view_formats: &[],
+            });
+            let mut uniform = UniformBuffer::from(TilemapChunkUniform {
+                origin: self.origin,
+                size: self.size,
+            });
+            uniform.write_buffer(render_device, render_queue);
+            let bind_group = render_device.create_bind_group(
+                "tilemap_chunk_bind_group",
+                chunk_layout,
+                &BindGroupEntries::sequential((
+                    uniform.binding().unwrap(),
+                    &texture.create_view(&TextureViewDescriptor::default()),
+                )),
+            );
+            GpuTilemapChunkBindings {
+                texture,
+                bind_group,
+            }
+        });
+        render_queue.write_texture(
+            bindings.texture.as_image_copy(),
+            bytemuck::cast_slice(tiles),
+            ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(self.size.x * 16),
+                rows_per_image: None,
+            },
+            size,
+        );
+    }
+}
+
+/// The chunks of each tilemap drawn in a view, back to front.
+#[derive(Component, Default)]
+pub struct ViewTilemapChunks(EntityHashMap<Vec<usize>>);
+
+/// Encodes a tile as the texel of a chunk texture.
+///
+/// The red channel is the atlas index plus one, or zero for empty tiles. The green channel holds
+/// the flip bits, the animation frame count in bits 8 to 15 and the frame duration in
+/// milliseconds in bits 16 to 31. The blue and alpha channels hold the color as 16 bit unorms.
+fn encode_tile(tile: Option<&Tile>) -> [u32; 4] {
+    let Some(tile) = tile else {
+        return [0; 4];
+    };
+
+    let mut flags = 0;
+    if tile.flip_x {
+        flags |= TILE_FLIP_X_BIT;
+    }
+    if tile.flip_y {
+        flags |= TILE_FLIP_Y_BIT;
+    }
+    if tile.flip_diagonal {
+        flags |= TILE_FLIP_DIAGONAL_BIT;
+    }
+    if let Some(animation) = tile.animation {
+        let frame_duration = animation
+            .frame_duration
+            .as_millis()
+            .clamp(1, u16::MAX as u128) as u32;
+        flags |= (animation.frame_count as u32) << 8 | frame_duration << 16;
+    }
+
+    let color = LinearRgba::from(tile.color);
+    let pack_unorm16x2 = |low: f32, high: f32| {
+        let unorm16 = |value: f32| (value.clamp(0.0, 1.0) * u16::MAX as f32).round() as u32;
+        unorm16(low) | unorm16(high) << 16
+    };
+    [
+        tile.index as u32 + 1,
+        flags,
+        pack_unorm16x2(color.red, color.green),
+        pack_unorm16x2(color.blue, color.alpha),
+    ]
+}
+
+pub fn extract_tilemaps(
+    mut commands: Commands,
+    mut previous_len: Local<usize>,
+    mut render_tilemaps: ResMut<RenderTilemaps>,
+    layouts: Extract<Res<Assets<TextureAtlasLayout>>>,
+    mut layout_events: Extract<EventReader<AssetEvent<TextureAtlasLayout>>>,
+    tilemaps: Extract<
+        Query<(
+            Entity,
+            &Tilemap,
+            &Handle<Image>,
+            &Handle<TextureAtlasLayout>,
+            &GlobalTransform,
+            &InheritedVisibility,
+            Option<&RenderLayers>,
+        )>,
+    >,
+) {
+    let RenderTilemaps {
+        tilemaps: gpu_tilemaps,
+        tilesets,
+        ..
+    } = &mut *render_tilemaps;
+
+    for event in layout_events.read() {
+        if let AssetEvent::Modified { id } | AssetEvent::Removed { id } = event {
+            tilesets.remove(id);
+        }
+    }
+
+    gpu_tilemaps.retain(|entity, _| tilemaps.contains(*entity));
+
+    let mut extracted_tilemaps = Vec::with_capacity(*previous_len);
+    for (entity, tilemap, texture, layout, transform, visibility, render_layers) in &tilemaps {
+        // The tiles of hidden tilemaps are still uploaded, so that they can be shown right away
+        let gpu_tilemap = gpu_tilemaps
+            .entry(entity)
+            .or_insert_with(|| GpuTilemap::new(tilemap));
+        if gpu_tilemap.size != tilemap.size() || gpu_tilemap.chunk_size != tilemap.chunk_size() {
+            *gpu_tilemap = GpuTilemap::new(tilemap);
+        }
+        for (chunk_index, chunk) in gpu_tilemap.chunks.iter_mut().enumerate() {
+            let change = tilemap.chunk_change(chunk_index);
+            if chunk.change == Some(change) {
+                continue;
+            }
+            chunk.change = Some(change);
+            chunk.pending_tiles = Some(
+                (0..chunk.size.y)
+                    .flat_map(|y| (0..chunk.size.x).map(move |x| UVec2::new(x, y)))
+                    .map(|position| encode_tile(tilemap.get(chunk.origin + position)))
+                    .collect(),
+            );
+        }
+
+        if !visibility.get() {
+            continue;
+        }
+        if !tilesets.contains_key(&layout.id()) {
+            if let Some(atlas_layout) = layouts.get(layout) {
+                tilesets.insert(layout.id(), GpuTileset::new(atlas_layout));
+            }
+        }
+
+        extracted_tilemaps.push((
+            entity,
+            ExtractedTilemap {
+                transform: *transform,
+                tile_size: tilemap.tile_size,
+                grid: tilemap.grid,
+                size: tilemap.size(),
+                chunk_size: tilemap.chunk_size(),
+                texture: texture.id(),
+                layout: layout.id(),
+                render_layers: render_layers.copied().unwrap_or_default(),
+            },
+        ));
+    }
+    *previous_len = extracted_tilemaps.len();
+    commands.insert_or_spawn_batch(extracted_tilemaps);
+}
+
+#[derive(Resource)]
+pub struct TilemapPipeline {
+    sprite_pipeline: SpritePipeline,
+    tilemap_layout: BindGroupLayout,
+    chunk_layout: BindGroupLayout,
+}
+
+impl FromWorld for TilemapPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let tilemap_layout = render_device.create_bind_group_layout(
+            "tilemap_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::VERTEX_FRAGMENT,
+                (
+                    uniform_buffer::<TilemapUniform>(true),
+                    uniform_buffer::<GlobalsUniform>(false),
+                    texture_2d(TextureSampleType::Float { filterable: true }),
+                    sampler(SamplerBindingType::Filtering),
+                    texture_2d(TextureSampleType::Float { filterable: false }),
+                ),
+            ),
+        );
+        let chunk_layout = render_device.create_bind_group_layout(
+            "tilemap_chunk_layout",
+            &BindGroupLayoutEntries::sequential(
+                ShaderStages::VERTEX,
+                (
+                    uniform_buffer::<TilemapChunkUniform>(false),
+                    texture_2d(TextureSampleType::Uint),
+                ),
+            ),
+        );
+
+        TilemapPipeline {
+            sprite_pipeline: world.resource::<SpritePipeline>().clone(),
+            tilemap_layout,
+            chunk_layout,
+        }
+    }
+}
+
+impl SpecializedRenderPipeline for TilemapPipeline {
+    type Key = SpritePipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        // Tiles are sprites whose instance data is read from the chunk textures
+        let mut descriptor = self.sprite_pipeline.specialize(key);
+        descriptor.vertex.shader = TILEMAP_SHADER_HANDLE;
+        descriptor.vertex.buffers.clear();
+        descriptor.fragment.as_mut().unwrap().shader = TILEMAP_SHADER_HANDLE;
+        descriptor.layout = vec![
+            self.sprite_pipeline.view_layout.clone(),
+            self.tilemap_layout.clone(),
+            self.chunk_layout.clone(),
+        ];
+        descriptor.label = Some("tilemap_pipeline".into());
+        descriptor
+    }
+}
+
+fn bounding_rect(points: impl IntoIterator<Item = Vec2>) -> Rect {
+    points.into_iter().fold(
+        Rect {
+            min: Vec2::MAX,
+            max: Vec2::MIN,
+        },
+        |rect, point| rect.union_point(point),
+    )
+}
+
+/// Returns the indices of the chunks of `tilemap` intersecting `view_rect`, back to front.
+fn visible_chunks(tilemap: &ExtractedTilemap, max_tile_size: Vec2, view_rect: Rect) -> Vec<usize> {
+    let chunk_count = tilemap.chunk_count();
+    // The images of the tiles may overflow their cells, up to the size of the largest one
+    let margin = tilemap.tile_size.max(max_tile_size);
+    let is_isometric = tilemap.grid == TilemapGrid::Isometric;
+
+    let mut chunks = Vec::new();
+    for row in 0..chunk_count.y {
+        // Rows further up are behind the ones below, except on isometric maps
+        let y = if is_isometric {
+            row
+        } else {
+            chunk_count.y - 1 - row
+        };
+        for x in 0..chunk_count.x {
+            let origin = UVec2::new(x, y) * tilemap.chunk_size;
+            let last = (origin + tilemap.chunk_size).min(tilemap.size) - UVec2::ONE;
+            let local_rect = bounding_rect(
+                [
+                    origin,
+                    UVec2::new(last.x, origin.y),
+                    UVec2::new(origin.x, last.y),
+                    last,
+                ]
+                .map(|position| tilemap.grid.tile_center(position, tilemap.tile_size)),
+            );
+            let world_rect = bounding_rect(
+                [
+                    local_rect.min - margin,
+                    Vec2::new(local_rect.max.x + margin.x, local_rect.min.y - margin.y),
+                    Vec2::new(local_rect.min.x - margin.x, local_rect.max.y + margin.y),
+                    local_rect.max + margin,
+                ]
+                .map(|corner| tilemap.transform.transform_point(corner.extend(0.0)).xy()),
+            );
+            if !world_rect.intersect(view_rect).is_empty() {
+                chunks.push((y * chunk_count.x + x) as usize);
+            }
+        }
+    }
+
+    // On isometric maps, the tiles with a greater `x + y` are in front
+    if is_isometric {
+        let chunk_width = chunk_count.x as usize;
+        chunks.sort_by_key(|&chunk| chunk % chunk_width + chunk / chunk_width);
+    }
+    chunks
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn queue_tilemaps(
+    mut commands: Commands,
+    draw_functions: Res<DrawFunctions<Transparent2d>>,
+    tilemap_pipeline: Res<TilemapPipeline>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<TilemapPipeline>>,
+    pipeline_cache: Res<PipelineCache>,
+    msaa: Res<Msaa>,
+    render_tilemaps: Res<RenderTilemaps>,
+    tilemaps: Query<(Entity, &ExtractedTilemap)>,
+    mut views: Query<(
+        Entity,
+        &mut SortedRenderPhase<Transparent2d>,
+        &ExtractedView,
+        Option<&RenderLayers>,
+        Option<&Tonemapping>,
+        Option<&DebandDither>,
+    )>,
+) {
+    if tilemaps.is_empty() {
+        return;
+    }
+    let draw_tilemap_function = draw_functions.read().id::<DrawTilemap>();
+
+    for (view_entity, mut transparent_phase, view, render_layers, tonemapping, dither) in &mut views
+    {
+        let view_key = SpritePipelineKey::from_view(view, &msaa, tonemapping, dither);
+        let pipeline = pipelines.specialize(&pipeline_cache, &tilemap_pipeline, view_key);
+        let render_layers = render_layers.copied().unwrap_or_default();
+        let view_rect = view_world_rect(view);
+
+        let mut view_chunks = ViewTilemapChunks::default();
+        for (entity, tilemap) in &tilemaps {
+            if !render_layers.intersects(&tilemap.render_layers) {
+                continue;
+            }
+            let Some(tileset) = render_tilemaps.tilesets.get(&tilemap.layout) else {
+                continue;
+            };
+            let chunks = visible_chunks(tilemap, tileset.max_tile_size, view_rect);
+            if chunks.is_empty() {
+                continue;
+            }
+
+            transparent_phase.add(Transparent2d {
+                draw_function: draw_tilemap_function,
+                pipeline,
+                entity,
+                sort_key: FloatOrd(tilemap.transform.translation().z),
+                batch_range: 0..1,
+                extra_index: PhaseItemExtraIndex::NONE,
+            });
+            view_chunks.0.insert(entity, chunks);
+        }
+        commands.entity(view_entity).insert(view_chunks);
+    }
+}
+
+pub fn prepare_tilemaps(
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    tilemap_pipeline: Res<TilemapPipeline>,
+    mut render_tilemaps: ResMut<RenderTilemaps>,
+    tilemaps: Query<(Entity, &ExtractedTilemap)>,
+) {
+    let RenderTilemaps {
+        tilemaps: gpu_tilemaps,
+        tilesets,
+        uniforms,
+    } = &mut *render_tilemaps;
+
+    for tileset in tilesets.values_mut() {
+        let Some(mut rects) = tileset.pending_rects.take() else {
+            continue;
+        };
+        if rects.is_empty() {
+            continue;
+        }
+        let width = (rects.len() as u32).min(TILESET_RECTS_TEXTURE_WIDTH);
+        let height = (rects.len() as u32).div_ceil(width);
+        rects.resize((width * height) as usize, [0.0; 4]);
+        let texture = render_device.create_texture_with_data(
+            &render_queue,
+            &TextureDescriptor {
+                label: Some("tileset_rects_texture"),
+                size: Extent3d {
+                    width,
+                    height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: TextureFormat::Rgba32Float,
+                usage: TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            },
+            TextureDataOrder::default(),
+            bytemuck::cast_slice(&rects),
+        );
+        tileset.rects_texture = Some(texture.create_view(&TextureViewDescriptor::default()));
+    }
+
+    // Only the chunks whose tiles changed since they were last uploaded are written
+    for gpu_tilemap in gpu_tilemaps.values_mut() {
+        for chunk in &mut gpu_tilemap.chunks {
+            if let Some(tiles) = chunk.pending_tiles.take() {
+                chunk.upload(
+                    &tiles,
+                    &render_device,
+                    &render_queue,
+                    &tilemap_pipeline.chunk_layout,
+                );
+            }
+        }
+    }
+
+    let Some(mut writer) =
+        uniforms.get_writer(tilemaps.iter().len(), &render_device, &render_queue)
+    else {
+        return;
+    };
+    for (entity, tilemap) in &tilemaps {
+        if let Some(gpu_tilemap) = gpu_tilemaps.get_mut(&entity) {
+            gpu_tilemap.uniform_offset = writer.write(&TilemapUniform::from(tilemap));
+        }
+    }
+}
+
+pub fn prepare_tilemap_bind_groups(
+    render_device: Res<RenderDevice>,
+    tilemap_pipeline: Res<TilemapPipeline>,
+    mut render_tilemaps: ResMut<RenderTilemaps>,
+    gpu_images: Res<RenderAssets<GpuImage>>,
+    globals_buffer: Res<GlobalsBuffer>,
+    tilemaps: Query<(Entity, &ExtractedTilemap)>,
+) {
+    let RenderTilemaps {
+        tilemaps: gpu_tilemaps,
+        tilesets,
+        uniforms,
+    } = &mut *render_tilemaps;
+
+    for (entity, tilemap) in &tilemaps {
+        let Some(gpu_tilemap) = gpu_tilemaps.get_mut(&entity) else {
+            continue;
+        };
+        gpu_tilemap.bind_group = None;
+
+        let (Some(uniforms_binding), Some(globals_binding), Some(image), Some(rects_texture)) = (
+            uniforms.binding(),
+            globals_buffer.buffer.binding(),
+            gpu_images.get(tilemap.texture),
+            tilesets
+                .get(&tilemap.layout)
+                .and_then(|tileset| tileset.rects_texture.as_ref()),
+        ) else {
+            continue;
+        };
+        gpu_tilemap.bind_group = Some(render_device.create_bind_group(
+            "tilemap_bind_group",
+            &tilemap_pipeline.tilemap_layout,
+            &BindGroupEntries::sequential((
+                uniforms_binding,
+                globals_binding,
+                &image.texture_view,
+                &image.sampler,
+                rects_texture,
+            )),
+        ));
+    }
+}
+
+/// [`RenderCommand`] for tilemap rendering.
+pub type DrawTilemap = (
+    SetItemPipeline,
+    SetSpriteViewBindGroup<0>,
+    DrawTilemapChunks,
+);
+
+/// Draws the chunks of a tilemap visible in the view, with an instance per tile.
+pub struct DrawTilemapChunks;
+impl<P: PhaseItem> RenderCommand<P> for DrawTilemapChunks {
+    type Param = (SRes<RenderTilemaps>, SRes<SpriteMeta>);
+    type ViewQuery = Read<ViewTilemapChunks>;
+    type ItemQuery = ();
+
+    fn render<'w>(
+        item: &P,
+        view_chunks: &'_ ViewTilemapChunks,
+        _entity: Option<()>,
+        (render_tilemaps, sprite_meta): SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let render_tilemaps = render_tilemaps.into_inner();
+        let (Some(gpu_tilemap), Some(chunks)) = (
+            render_tilemaps.tilemaps.get(&item.entity()),
+            view_chunks.0.get(&item.entity()),
+        ) else {
+            return RenderCommandResult::Failure;
+        };
+        let Some(bind_group) = &gpu_tilemap.bind_group else {
+            return RenderCommandResult::Failure;
+        };
+
+        // The quads of the tiles are the ones of the sprites
+        pass.set_index_buffer(
+            sprite_meta
+                .into_inner()
+                .sprite_index_buffer
+                .buffer()
+                .unwrap()
+                .slice(..),
+            0,
+            IndexFormat::Uint32,
+        );
+        pass.set_bind_group(1, bind_group, &[gpu_tilemap.uniform_offset]);
+        for &chunk_index in chunks {
+            let chunk = &gpu_tilemap.chunks[chunk_index];
+            let Some(bindings) = &chunk.bindings else {
+                continue;
+            };
+            pass.set_bind_group(2, &bindings.bind_group, &[]);
+            pass.draw_indexed(0..6, 0, 0..chunk.size.x * chunk.size.y);
+        }
+        RenderCommandResult::Success
+    }
+}
@@ -0,0 +1,147 @@
+#ifdef TONEMAP_IN_SHADER
+#import bevy_core_pipeline::tonemapping
+#endif
+
+#import bevy_render::{globals::Globals, view::View}
+
+struct Tilemap {
+    world_from_local: mat4x4<f32>,
+    tile_size: vec2<f32>,
+    hex_side_length: f32,
+    grid: u32,
+    stagger_odd: u32,
+}
+
+struct TilemapChunk {
+    origin: vec2<u32>,
+    size: vec2<u32>,
+}
+
+const TILE_FLIP_X_BIT: u32 = 1u;
+const TILE_FLIP_Y_BIT: u32 = 2u;
+const TILE_FLIP_DIAGONAL_BIT: u32 = 4u;
+
+const TILEMAP_GRID_ISOMETRIC: u32 = 1u;
+const TILEMAP_GRID_HEXAGONAL_STAGGER_X: u32 = 2u;
+const TILEMAP_GRID_HEXAGONAL_STAGGER_Y: u32 = 3u;
+
+@group(0) @binding(0) var<uniform> view: View;
+
+@group(1) @binding(0) var<uniform> tilemap: Tilemap;
+@group(1) @binding(1) var<uniform> globals: Globals;
+@group(1) @binding(2) var tileset_texture: texture_2d<f32>;
+@group(1) @binding(3) var tileset_sampler: sampler;
+// The rects of the tiles in the tileset texture, in pixels: min in xy, max in zw
+@group(1) @binding(4) var tileset_rects: texture_2d<f32>;
+
+@group(2) @binding(0) var<uniform> chunk: TilemapChunk;
+// See `encode_tile` in bevy_sprite/src/tilemap/render.rs for the layout of the texels
+@group(2) @binding(1) var chunk_tiles: texture_2d<u32>;
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+fn is_staggered(index: u32) -> bool {
+    return (index & 1u) == tilemap.stagger_odd;
+}
+
+// Must match `TilemapGrid::tile_center`
+fn tile_center(position: vec2<u32>) -> vec2<f32> {
+    let cell = vec2<f32>(position);
+    let size = tilemap.tile_size;
+    var center = (cell + 0.5) * size;
+    if tilemap.grid == TILEMAP_GRID_ISOMETRIC {
+        center = vec2<f32>(
+            (cell.x - cell.y) * size.x * 0.5,
+            -(cell.x + cell.y + 1.0) * size.y * 0.5,
+        );
+    } else if tilemap.grid == TILEMAP_GRID_HEXAGONAL_STAGGER_X {
+        let shift = select(0.0, 0.5, is_staggered(position.x));
+        center = vec2<f32>(
+            cell.x * (size.x + tilemap.hex_side_length) * 0.5 + size.x * 0.5,
+            (cell.y + 0.5 + shift) * size.y,
+        );
+    } else if tilemap.grid == TILEMAP_GRID_HEXAGONAL_STAGGER_Y {
+        let shift = select(0.0, 0.5, is_staggered(position.y));
+        center = vec2<f32>(
+            (cell.x + 0.5 + shift) * size.x,
+            cell.y * (size.y + tilemap.hex_side_length) * 0.5 + size.y * 0.5,
+        );
+    }
+    return center;
+}
+
+@vertex
+fn vertex(
+    @builtin(vertex_index) vertex_index: u32,
+    @builtin(instance_index) instance_index: u32,
+) -> VertexOutput {
+    var out: VertexOutput;
+
+    // Draw the tiles back to front: rows further up are behind the ones below them, except on
+    // isometric maps where they are further down
+    var local = vec2<u32>(instance_index % chunk.size.x, instance_index / chunk.size.x);
+    if tilemap.grid != TILEMAP_GRID_ISOMETRIC {
+        local.y = chunk.size.y - 1u - local.y;
+    }
+
+    let tile = textureLoad(chunk_tiles, vec2<i32>(local), 0);
+    if tile.x == 0u {
+        // Empty tiles collapse to a point
+        out.clip_position = vec4<f32>(0.0, 0.0, 0.0, 1.0);
+        return out;
+    }
+
+    var index = tile.x - 1u;
+    let frame_count = (tile.y >> 8u) & 0xffu;
+    if frame_count > 1u {
+        let frame_duration = f32(tile.y >> 16u) / 1000.0;
+        index += u32(globals.time / frame_duration) % frame_count;
+    }
+    let rects_width = textureDimensions(tileset_rects).x;
+    let rect = textureLoad(
+        tileset_rects,
+        vec2<i32>(i32(index % rects_width), i32(index / rects_width)),
+        0
+    );
+    let rect_size = rect.zw - rect.xy;
+
+    // The same quad corners as sprites, from the two lowest bits of the index
+    let corner = vec2<f32>(f32(vertex_index & 0x1u), f32((vertex_index & 0x2u) >> 1u));
+
+    // Tile images stand on the bottom of their cell, centered horizontally
+    let cell_bottom = tile_center(chunk.origin + local) - vec2<f32>(0.0, tilemap.tile_size.y * 0.5);
+    let position = cell_bottom + vec2<f32>(corner.x - 0.5, corner.y) * rect_size;
+    out.clip_position = view.view_proj * tilemap.world_from_local * vec4<f32>(position, 0.0, 1.0);
+
+    // Images are flipped diagonally first, then horizontally and vertically, so the sampled
+    // coordinates are flipped in the opposite order
+    var uv = vec2<f32>(corner.x, 1.0 - corner.y);
+    if (tile.y & TILE_FLIP_Y_BIT) != 0u {
+        uv.y = 1.0 - uv.y;
+    }
+    if (tile.y & TILE_FLIP_X_BIT) != 0u {
+        uv.x = 1.0 - uv.x;
+    }
+    if (tile.y & TILE_FLIP_DIAGONAL_BIT) != 0u {
+        uv = uv.yx;
+    }
+    out.uv = (rect.xy + uv * rect_size) / vec2<f32>(textureDimensions(tileset_texture));
+    out.color = vec4<f32>(unpack2x16unorm(tile.z), unpack2x16unorm(tile.w));
+
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    var color = in.color * textureSample(tileset_texture, tileset_sampler, in.uv);
+
+#ifdef TONEMAP_IN_SHADER
+    color = tonemapping::tone_mapping(color, view.color_grading);
+#endif
+
+    return color;
+}
@@ -0,0 +1,712 @@
+//! Loading of [Tiled](https://www.mapeditor.org) TMX maps.
+
+use std::{str::FromStr, time::Duration};
+
+use base64::Engine;
+use bevy_asset::{
+    io::Reader, Asset, AssetLoader, AssetPath, AsyncReadExt, Handle, LoadContext,
+    ParseAssetPathError, ReadAssetBytesError,
+};
+use bevy_color::{Alpha, Color};
+use bevy_math::{UVec2, Vec2};
+use bevy_reflect::TypePath;
+use bevy_render::{texture::Image, view::Visibility};
+use bevy_transform::components::Transform;
+use bevy_utils::{tracing::warn, HashMap};
+use roxmltree::{Document, Node};
+use thiserror::Error;
+
+use super::{
+    HexStaggerAxis, HexStaggerIndex, Tile, TileAnimation, Tilemap, TilemapBundle, TilemapGrid,
+};
+use crate::TextureAtlasLayout;
+
+const TILED_FLIP_X_FLAG: u32 = 0x8000_0000;
+const TILED_FLIP_Y_FLAG: u32 = 0x4000_0000;
+const TILED_FLIP_DIAGONAL_FLAG: u32 = 0x2000_0000;
+/// Also covers the 120° rotation flag of hexagonal maps, which isn't supported.
+const TILED_FLAGS_MASK: u32 = 0xf000_0000;
+
+/// The tile layers of a [Tiled](https://www.mapeditor.org) map, loaded from a `.tmx` file.
+///
+/// Square maps have their rows flipped, so that the top row of the map in Tiled is the last row of
+/// the [`Tilemap`]s. Isometric maps keep the coordinates of their tiles. Tile layers inside of
+/// group layers are flattened, object and image layers are skipped.
+///
+/// Tiles wider than their cell are centered on it, where Tiled aligns them to the left of the cell.
+#[derive(Asset, TypePath, Debug)]
+pub struct TiledMap {
+    /// The tile layers of the map, from the bottom one to the top one.
+    ///
+    /// A layer using tiles of several tilesets is split in one [`TiledMapLayer`] per tileset.
+    pub layers: Vec<TiledMapLayer>,
+}
+
+/// A tile layer of a [`TiledMap`], with the tiles of a single tileset.
+#[derive(Clone, Debug)]
+pub struct TiledMapLayer {
+    /// The name of the layer in Tiled.
+    pub name: String,
+    /// The offset of the layer, and of the group layers containing it, in world units.
+    pub offset: Vec2,
+    /// Whether the layer, and the group layers containing it, are visible in Tiled.
+    pub visible: bool,
+    /// The tiles of the layer.
+    ///
+    /// The opacity of the layer is multiplied in the alpha of their color.
+    pub tilemap: Tilemap,
+    /// The texture of the tileset of the layer.
+    pub texture: Handle<Image>,
+    /// The rects of the tiles in the [`texture`](Self::texture).
+    pub layout: Handle<TextureAtlasLayout>,
+}
+
+impl TiledMap {
+    /// Returns a [`TilemapBundle`] per layer, placed relative to `transform` and stacked one unit
+    /// apart along Z.
+    pub fn bundles(&self, transform: Transform) -> impl Iterator<Item = TilemapBundle> + '_ {
+        self.layers.iter().enumerate().map(move |(index, layer)| {
+            let layer_transform = Transform::from_translation(layer.offset.extend(index as f32));
+            TilemapBundle {
+                transform: transform * layer_transform,
+                visibility: if layer.visible {
+                    Visibility::Inherited
+                } else {
+                    Visibility::Hidden
+                },
+                ..TilemapBundle::new(
+                    layer.tilemap.clone(),
+                    layer.texture.clone(),
+                    layer.layout.clone(),
+                )
+            }
+        })
+    }
+}
+
+/// Loads a [`TiledMap`] from a `.tmx` file.
+///
+/// Tilesets are embedded in the map or read from `.tsx` files, and must use a single image.
+/// Their animations become [`TileAnimation`]s when their frames are consecutive tiles shown for
+/// the same duration, and are dropped otherwise. Layer data can be encoded in CSV, uncompressed
+/// base64 or XML. Infinite maps and staggered maps aren't supported.
+#[derive(Default)]
+pub struct TiledMapLoader;
+
+/// An error when loading a [`TiledMap`].
+#[non_exhaustive]
+#[derive(Debug, Error)]
+pub enum TiledMapLoaderError {
+    #[error("Could not read TMX file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("TMX file is not valid UTF-8: {0}")]
+    Utf8(#[from] std::string::FromUtf8Error),
+    #[error("Could not parse TMX file: {0}")]
+    Xml(#[from] roxmltree::Error),
+    #[error("Missing `{attribute}` attribute on a `<{element}>` element")]
+    MissingAttribute {
+        element: String,
+        attribute: &'static str,
+    },
+    #[error("Invalid `{attribute}` attribute on a `<{element}>` element: {value:?}")]
+    InvalidAttribute {
+        element: String,
+        attribute: &'static str,
+        value: String,
+    },
+    #[error("Unsupported {0:?} map orientation")]
+    UnsupportedOrientation(String),
+    #[error("Infinite maps are not supported")]
+    InfiniteMap,
+    #[error("Tileset {0:?} doesn't use a single image")]
+    ImageCollectionTileset(String),
+    #[error("Unsupported {0:?} layer data encoding")]
+    UnsupportedEncoding(String),
+    #[error("Unsupported {0:?} layer data compression")]
+    UnsupportedCompression(String),
+    #[error("Invalid tile data in layer {0:?}")]
+    InvalidLayerData(String),
+    #[error("Tile {0} doesn't belong to any tileset")]
+    InvalidTile(u32),
+    #[error("Invalid path in TMX file: {0}")]
+    InvalidPath(#[from] ParseAssetPathError),
+    #[error("Could not read tileset: {0}")]
+    ReadTileset(#[from] ReadAssetBytesError),
+}
+
+impl AssetLoader for TiledMapLoader {
+    type Asset = TiledMap;
+    type Settings = ();
+    type Error = TiledMapLoaderError;
+
+    async fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader<'_>,
+        _settings: &'a (),
+        load_context: &'a mut LoadContext<'_>,
+    ) -> Result<TiledMap, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let text = String::from_utf8(bytes)?;
+        let document = Document::parse(&text)?;
+        let map = document.root_element();
+
+        if parse_attribute_or(map, "infinite", 0u32)? != 0 {
+            return Err(TiledMapLoaderError::InfiniteMap);
+        }
+        let size = UVec2::new(
+            parse_attribute(map, "width")?,
+            parse_attribute(map, "height")?,
+        );
+        let tile_size = Vec2::new(
+            parse_attribute(map, "tilewidth")?,
+            parse_attribute(map, "tileheight")?,
+        );
+        let grid = match attribute(map, "orientation")? {
+            "orthogonal" => TilemapGrid::Square,
+            "isometric" => TilemapGrid::Isometric,
+            "hexagonal" => {
+                let stagger_axis = match attribute(map, "staggeraxis")? {
+                    "x" => HexStaggerAxis::X,
+                    "y" => HexStaggerAxis::Y,
+                    value => return Err(invalid_attribute(map, "staggeraxis", value)),
+                };
+                let odd = match attribute(map, "staggerindex")? {
+                    "odd" => true,
+                    "even" => false,
+                    value => return Err(invalid_attribute(map, "staggerindex", value)),
+                };
+                // Flipping the rows turns the shifted columns down into unshifted ones up, and
+                // swaps the parity of the rows when there's an even number of them
+                let flips_parity = match stagger_axis {
+                    HexStaggerAxis::X => true,
+                    HexStaggerAxis::Y => size.y % 2 == 0,
+                };
+                TilemapGrid::Hexagonal {
+                    stagger_axis,
+                    stagger_index: if odd != flips_parity {
+                        HexStaggerIndex::Odd
+                    } else {
+                        HexStaggerIndex::Even
+                    },
+                    side_length: parse_attribute(map, "hexsidelength")?,
+                }
+            }
+            orientation => {
+                return Err(TiledMapLoaderError::UnsupportedOrientation(
+                    orientation.to_string(),
+                ))
+            }
+        };
+
+        let mut tilesets = Vec::new();
+        for node in map.children().filter(|node| node.has_tag_name("tileset")) {
+            let first_gid = parse_attribute(node, "firstgid")?;
+            let label = format!("Tileset{}", tilesets.len());
+            let tileset = match node.attribute("source") {
+                Some(source) => {
+                    let path = load_context.asset_path().resolve_embed(source)?;
+                    let bytes = load_context.read_asset_bytes(path.clone()).await?;
+                    let text = String::from_utf8(bytes)?;
+                    let document = Document::parse(&text)?;
+                    load_tileset(
+                        document.root_element(),
+                        first_gid,
+                        &path,
+                        label,
+                        load_context,
+                    )?
+                }
+                None => {
+                    let path = load_context.asset_path().clone_owned();
+                    load_tileset(node, first_gid, &path, label, load_context)?
+                }
+            };
+            tilesets.push(tileset);
+        }
+        tilesets.sort_by_key(|tileset| tileset.first_gid);
+
+        let mut tile_layers = Vec::new();
+        collect_tile_layers(map, Vec2::ZERO, 1.0, true, &mut tile_layers)?;
+
+        let mut layers = Vec::new();
+        for tile_layer in tile_layers {
+            let gids = read_layer_data(tile_layer.node)?;
+            if gids.len() != size.x as usize * size.y as usize {
+                return Err(TiledMapLoaderError::InvalidLayerData(tile_layer.name));
+            }
+
+            // One tilemap per tileset used by the layer
+            let mut tilemaps: Vec<Option<Tilemap>> = vec![None; tilesets.len()];
+            for (index, &gid) in gids.iter().enumerate() {
+                let id = gid & !TILED_FLAGS_MASK;
+                if id == 0 {
+                    continue;
+                }
+                let Some(tileset_index) =
+                    tilesets.iter().rposition(|tileset| tileset.first_gid <= id)
+                else {
+                    return Err(TiledMapLoaderError::InvalidTile(id));
+                };
+                let tileset = &tilesets[tileset_index];
+                let local_id = id - tileset.first_gid;
+                if local_id >= tileset.tile_count {
+                    return Err(TiledMapLoaderError::InvalidTile(id));
+                }
+
+                let (column, row) = (index as u32 % size.x, index as u32 / size.x);
+                let position = match grid {
+                    TilemapGrid::Isometric => UVec2::new(column, row),
+                    _ => UVec2::new(column, size.y - 1 - row),
+                };
+                let (index, animation) = match tileset.animations.get(&local_id) {
+                    Some(&(first_frame, animation)) => (first_frame, Some(animation)),
+                    None => (local_id, None),
+                };
+                tilemaps[tileset_index]
+                    .get_or_insert_with(|| Tilemap::new(size, tile_size, grid))
+                    .set(
+                        position,
+                        Tile {
+                            index: index as usize,
+                            flip_x: gid & TILED_FLIP_X_FLAG != 0,
+                            flip_y: gid & TILED_FLIP_Y_FLAG != 0,
+                            flip_diagonal: gid & TILED_FLIP_DIAGONAL_FLAG != 0,
+                            color: Color::WHITE.with_alpha(tile_layer.opacity),
+                            animation,
+                        },
+                    );
+            }
+
+            for (tileset, tilemap) in tilesets.iter().zip(tilemaps) {
+                let Some(tilemap) = tilemap else {
+                    continue;
+                };
+                layers.push(TiledMapLayer {
+                    name: tile_layer.name.clone(),
+                    offset: tile_layer.offset,
+                    visible: tile_layer.visible,
+                    tilemap,
+                    texture: tileset.texture.clone(),
+                    layout: tileset.layout.clone(),
+                });
+            }
+        }
+
+        Ok(TiledMap { layers })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["tmx"]
+    }
+}
+
+struct TiledTileset {
+    first_gid: u32,
+    tile_count: u32,
+    texture: Handle<Image>,
+    layout: Handle<TextureAtlasLayout>,
+    /// The first frame and the animation of the animated tiles, by tile id.
+    animations: HashMap<u32, (u32, TileAnimation)>,
+}
+
+fn load_tileset(
+    node: Node,
+    first_gid: u32,
+    path: &AssetPath,
+    label: String,
+    load_context: &mut LoadContext,
+) -> Result<TiledTileset, TiledMapLoaderError> {
+    let name = node.attribute("name").unwrap_or_default();
+    let tile_size = UVec2::new(
+        parse_attribute(node, "tilewidth")?,
+        parse_attribute(node, "tileheight")?,
+    );
+    let spacing = parse_attribute_or(node, "spacing", 0)?;
+    let margin = parse_attribute_or(node, "margin", 0)?;
+    let tile_count: u32 = parse_attribute(node, "tilecount")?;
+    let columns: u32 = parse_attribute(node, "columns")?;
+    let Some(image) = node.children().find(|child| child.has_tag_name("image")) else {
+        return Err(TiledMapLoaderError::ImageCollectionTileset(
+            name.to_string(),
+        ));
+    };
+    if columns == 0 {
+        return Err(invalid_attribute(node, "columns", "0"));
+    }
+
+    let texture = load_context.load(path.resolve_embed(attribute(image, "source")?)?);
+    let mut layout = TextureAtlasLayout::from_grid(
+        tile_size,
+        columns,
+        tile_count.div_ceil(columns),
+        Some(UVec2::splat(spacing)),
+        Some(UVec2::splat(margin)),
+    );
+    layout.textures.truncate(tile_count as usize);
+    let layout = load_context.add_labeled_asset(label, layout);
+
+    let mut animations = HashMap::new();
+    for tile in node.children().filter(|child| child.has_tag_name("tile")) {
+        let Some(animation) = tile
+            .children()
+            .find(|child| child.has_tag_name("animation"))
+        else {
+            continue;
+        };
+        let id: u32 = parse_attribute(tile, "id")?;
+        let frames = animation
+            .children()
+            .filter(|child| child.has_tag_name("frame"))
+            .map(|frame| {
+                Ok((
+                    parse_attribute::<u32>(frame, "tileid")?,
+                    parse_attribute::<u64>(frame, "duration")?,
+                ))
+            })
+            .collect::<Result<Vec<_>, TiledMapLoaderError>>()?;
+
+        let Some(&(first_frame, frame_duration)) = frames.first() else {
+            continue;
+        };
+        let is_supported = frames.len() <= u8::MAX as usize
+            && frames
+                .iter()
+                .enumerate()
+                .all(|(index, &(frame, duration))| {
+                    frame == first_frame + index as u32 && duration == frame_duration
+                });
+        if !is_supported {
+            warn!(
+                "The animation of tile {id} of tileset {name:?} isn't made of consecutive tiles \
+                shown for the same duration, it won't be played"
+            );
+            continue;
+        }
+        animations.insert(
+            id,
+            (
+                first_frame,
+                TileAnimation {
+                    frame_count: frames.len() as u8,
+                    frame_duration: Duration::from_millis(frame_duration),
+                },
+            ),
+        );
+    }
+
+    Ok(TiledTileset {
+        first_gid,
+        tile_count,
+        texture,
+        layout,
+        animations,
+    })
+}
+
+struct TiledTileLayer<'a, 'input> {
+    node: Node<'a, 'input>,
+    name: String,
+    offset: Vec2,
+    opacity: f32,
+    visible: bool,
+}
+
+/// Collects the tile layers of `node` and of its group layers, accumulating their offsets,
+/// opacities and visibilities.
+fn collect_tile_layers<'a, 'input>(
+    node: Node<'a, 'input>,
+    offset: Vec2,
+    opacity: f32,
+    visible: bool,
+    tile_layers: &mut Vec<TiledTileLayer<'a, 'input>>,
+) -> Result<(), TiledMapLoaderError> {
+    for child in node.children() {
+        let is_layer = child.has_tag_name("layer");
+        if !is_layer && !child.has_tag_name("group") {
+            continue;
+        }
+        // Tiled offsets go down
+        let offset = offset
+            + Vec2::new(
+                parse_attribute_or(child, "offsetx", 0.0f32)?,
+                -parse_attribute_or(child, "offsety", 0.0f32)?,
+            );
+        let opacity = opacity * parse_attribute_or(child, "opacity", 1.0f32)?;
+        let visible = visible && parse_attribute_or(child, "visible", 1u32)? != 0;
+        if is_layer {
+            tile_layers.push(TiledTileLayer {
+                node: child,
+                name: child.attribute("name").unwrap_or_default().to_string(),
+                offset,
+                opacity,
+                visible,
+            });
+        } else {
+            collect_tile_layers(child, offset, opacity, visible, tile_layers)?;
+        }
+    }
+    Ok(())
+}
+
+/// Reads the global tile ids of a tile layer, with their flip flags.
+fn read_layer_data(layer: Node) -> Result<Vec<u32>, TiledMapLoaderError> {
+    let name = layer.attribute("name").unwrap_or_default();
+    let invalid_data = || TiledMapLoaderError::InvalidLayerData(name.to_string());
+    let Some(data) = layer.children().find(|child| child.has_tag_name("data")) else {
+        return Err(invalid_data());
+    };
+
+    if let Some(compression) = data.attribute("compression") {
+        return Err(TiledMapLoaderError::UnsupportedCompression(
+            compression.to_string(),
+        ));
+    }
+    let text = data.text().unwrap_or_default();
+    match data.attribute("encoding") {
+        Some("csv") => text
+            .split(',')
+            .map(|gid| gid.trim().parse().map_err(|_| invalid_data()))
+            .collect(),
+        Some("base64") => {
+            let text: String = text.split_whitespace().collect();
+            let bytes = base64::engine::general_purpose::STANDARD
+                .decode(text)
+                .map_err(|_| invalid_data())?;
+            if bytes.len() % 4 != 0 {
+                return Err(invalid_data());
+            }
+            Ok(bytes
+                .chunks_exact(4)
+                .map(|gid| u32::from_le_bytes(gid.try_into().unwrap()))
+                .collect())
+        }
+        Some(encoding) => Err(TiledMapLoaderError::UnsupportedEncoding(
+            encoding.to_string(),
+        )),
+        None => data
+            .children()
+            .filter(|child| child.has_tag_name("tile"))
+            .map(|tile| parse_attribute_or(tile, "gid", 0))
+            .collect(),
+    }
+}
+
+fn attribute<'a>(node: Node<'a, '_>, name: &'static str) -> Result<&'a str, TiledMapLoaderError> {
+    node.attribute(name)
+        .ok_or_else(|| TiledMapLoaderError::MissingAttribute {
+            element: node.tag_name().name().to_string(),
+            attribute: name,
+        })
+}
+
+fn invalid_attribute(node: Node, name: &'static str, value: &str) -> TiledMapLoaderError {
+    TiledMapLoaderError::InvalidAttribute {
+        element: node.tag_name().name().to_string(),
+        attribute: name,
+        value: value.to_string(),
+    }
+}
+
+fn parse_attribute<T: FromStr>(node: Node, name: &'static str) -> Result<T, TiledMapLoaderError> {
+    let value = attribute(node, name)?;
+    value
+        .parse()
+        .map_err(|_| invalid_attribute(node, name, value))
+}
+
+fn parse_attribute_or<T: FromStr>(
+    node: Node,
+    name: &'static str,
+    default: T,
+) -> Result<T, TiledMapLoaderError> {
+    match node.attribute(name) {
+        Some(value) => value
+            .parse()
+            .map_err(|_| invalid_attribute(node, name, value)),
+        None => Ok(default),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use bevy_app::App;
+    use bevy_asset::{
+        io::{
+            memory::{Dir, MemoryAssetReader},
+            AssetSource, AssetSourceId,
+        },
+        AssetApp, AssetPlugin, AssetServer, Assets, LoadState,
+    };
+    use bevy_core::TaskPoolPlugin;
+
+    use super::*;
+
+    /// A map of `width` by `height` tiles using a 2 by 2 tileset.
+    fn map(orientation: &str, width: u32, height: u32, layers: &str) -> String {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<map version="1.10" {orientation} width="{width}" height="{height}" tilewidth="16" tileheight="16" infinite="0">
+ <tileset firstgid="1" name="tiles" tilewidth="16" tileheight="16" tilecount="4" columns="2">
+  <image source="tiles.png" width="32" height="32"/>
+ </tileset>
+ {layers}
+</map>"#
+        )
+    }
+
+    fn load_map(tmx: String) -> TiledMap {
+        let dir = Dir::default();
+        dir.insert_asset_text(Path::new("map.tmx"), &tmx);
+
+        let mut app = App::new();
+        app.register_asset_source(
+            AssetSourceId::Default,
+            AssetSource::build()
+                .with_reader(move || Box::new(MemoryAssetReader { root: dir.clone() })),
+        )
+        .add_plugins((TaskPoolPlugin::default(), AssetPlugin::default()))
+        .init_asset::<Image>()
+        .init_asset::<TextureAtlasLayout>()
+        .init_asset::<TiledMap>()
+        .register_asset_loader(TiledMapLoader);
+
+        // The tileset image has no loader, so only the map itself is waited for
+        let handle: Handle<TiledMap> = app.world().resource::<AssetServer>().load("map.tmx");
+        for _ in 0..10000 {
+            app.update();
+            match app.world().resource::<AssetServer>().load_state(&handle) {
+                LoadState::Loaded => {
+                    let mut maps = app.world_mut().resource_mut::<Assets<TiledMap>>();
+                    return maps.remove(&handle).unwrap();
+                }
+                LoadState::Failed(err) => panic!("Failed to load the map: {err}"),
+                _ => {}
+            }
+        }
+        panic!("The map didn't load");
+    }
+
+    fn data(layer: &str) -> Result<Vec<u32>, TiledMapLoaderError> {
+        let document = Document::parse(layer).unwrap();
+        read_layer_data(document.root_element())
+    }
+
+    #[test]
+    fn csv_and_base64_layers_with_flip_flags() {
+        let gids = [
+            1,
+            2 | TILED_FLIP_X_FLAG,
+            3 | TILED_FLIP_Y_FLAG | TILED_FLIP_DIAGONAL_FLAG,
+            0,
+        ];
+        let csv = gids.map(|gid| gid.to_string()).join(",\n");
+        let bytes: Vec<u8> = gids.iter().flat_map(|gid| gid.to_le_bytes()).collect();
+        let base64 = base64::engine::general_purpose::STANDARD.encode(bytes);
+        let layers = format!(
+            r#"<layer name="csv" width="2" height="2">
+  <data encoding="csv">
+{csv}
+  </data>
+ </layer>
+ <group name="group" offsetx="8" opacity="0.5">
+  <layer name="base64" width="2" height="2" offsety="4">
+   <data encoding="base64">
+    {base64}
+   </data>
+  </layer>
+ </group>"#
+        );
+
+        let map = load_map(map(r#"orientation="orthogonal""#, 2, 2, &layers));
+        assert_eq!(map.layers.len(), 2);
+        assert_eq!(map.layers[0].name, "csv");
+        assert_eq!(map.layers[0].offset, Vec2::ZERO);
+        assert_eq!(map.layers[1].name, "base64");
+        assert_eq!(map.layers[1].offset, Vec2::new(8.0, -4.0));
+
+        for layer in &map.layers {
+            let tilemap = &layer.tilemap;
+            assert_eq!(tilemap.grid, TilemapGrid::Square);
+            assert_eq!(tilemap.size(), UVec2::new(2, 2));
+            // The top row in Tiled is the last row of the tilemap
+            let top_left = tilemap.get(UVec2::new(0, 1)).unwrap();
+            assert_eq!(top_left.index, 0);
+            assert!(!top_left.flip_x && !top_left.flip_y && !top_left.flip_diagonal);
+            let top_right = tilemap.get(UVec2::new(1, 1)).unwrap();
+            assert_eq!(top_right.index, 1);
+            assert!(top_right.flip_x && !top_right.flip_y && !top_right.flip_diagonal);
+            let bottom_left = tilemap.get(UVec2::new(0, 0)).unwrap();
+            assert_eq!(bottom_left.index, 2);
+            assert!(!bottom_left.flip_x && bottom_left.flip_y && bottom_left.flip_diagonal);
+            assert!(tilemap.get(UVec2::new(1, 0)).is_none());
+        }
+        let color = map.layers[1].tilemap.get(UVec2::ZERO).unwrap().color;
+        assert_eq!(color.alpha(), 0.5);
+    }
+
+    #[test]
+    fn hexagonal_stagger() {
+        for (stagger_axis, stagger_index, height, grid_axis, grid_index) in [
+            ("y", "odd", 3, HexStaggerAxis::Y, HexStaggerIndex::Odd),
+            ("y", "odd", 2, HexStaggerAxis::Y, HexStaggerIndex::Even),
+            ("y", "even", 2, HexStaggerAxis::Y, HexStaggerIndex::Odd),
+            ("x", "odd", 3, HexStaggerAxis::X, HexStaggerIndex::Even),
+            ("x", "even", 2, HexStaggerAxis::X, HexStaggerIndex::Odd),
+        ] {
+            let orientation = format!(
+                r#"orientation="hexagonal" hexsidelength="8" staggeraxis="{stagger_axis}" staggerindex="{stagger_index}""#
+            );
+            let csv = ["1"; 2].join(",") + &",0,0".repeat(height - 1);
+            let layer = format!(r#"<layer name="hex"><data encoding="csv">{csv}</data></layer>"#);
+
+            let map = load_map(map(&orientation, 2, height as u32, &layer));
+            assert_eq!(
+                map.layers[0].tilemap.grid,
+                TilemapGrid::Hexagonal {
+                    stagger_axis: grid_axis,
+                    stagger_index: grid_index,
+                    side_length: 8.0,
+                },
+                "{stagger_axis} axis, {stagger_index} index, {height} rows"
+            );
+        }
+    }
+
+    #[test]
+    fn layer_data_encodings() {
+        assert_eq!(
+            data(r#"<layer><data><tile gid="3"/><tile/><tile gid="2147483649"/></data></layer>"#)
+                .unwrap(),
+            [3, 0, 1 | TILED_FLIP_X_FLAG]
+        );
+        assert_eq!(
+            data(
+                r#"<layer><data encoding="csv"> 1, 0,
+ 4 </data></layer>"#
+            )
+            .unwrap(),
+            [1, 0, 4]
+        );
+
+        assert!(matches!(
+            data(r#"<layer name="l"><data encoding="csv">1,a</data></layer>"#),
+            Err(TiledMapLoaderError::InvalidLayerData(name)) if name == "l"
+        ));
+        // Five bytes aren't a whole number of tile ids
+        assert!(matches!(
+            data(r#"<layer><data encoding="base64">AQAAAAI=</data></layer>"#),
+            Err(TiledMapLoaderError::InvalidLayerData(_))
+        ));
+        assert!(matches!(
+            data(r#"<layer><data encoding="base64" compression="zlib">eJw=</data></layer>"#),
+            Err(TiledMapLoaderError::UnsupportedCompression(compression)) if compression == "zlib"
+        ));
+        assert!(matches!(
+            data(r#"<layer><data encoding="json">[]</data></layer>"#),
+            Err(TiledMapLoaderError::UnsupportedEncoding(encoding)) if encoding == "json"
+        ));
+    }
+}
@@ -13,6 +13,8 @@
 
 pub mod measurement;
 pub mod node_bundles;
+#[cfg(feature = "bevy_picking")]
+pub mod picking_backend;
 pub mod ui_material;
 pub mod update;
 pub mod widget;
@@ -178,6 +180,13 @@ impl Plugin for UiPlugin {
         #[cfg(feature = "bevy_text")]
         build_text_interop(app);
 
+        #[cfg(feature = "bevy_picking")]
+        app.add_event::<bevy_picking::backend::PointerHits>()
+            .add_systems(
+                PreUpdate,
+                picking_backend::ui_picking.in_set(bevy_picking::PickSet::Backend),
+            );
+
         build_ui_render(app);
     }
 
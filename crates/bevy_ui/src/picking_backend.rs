@@ -0,0 +1,261 @@
+//! A picking backend for UI nodes.
+//!
+//! Nodes are hit from the top of the [`UiStack`] to the bottom, until a node blocks the nodes
+//! below it, which nodes do unless they have a [`PickingBehavior`] with
+//! [`should_block_lower`](PickingBehavior::should_block_lower) disabled. [`FocusPolicy`](crate::FocusPolicy)
+//! only affects [`Interaction`](crate::Interaction).
+//!
+//! The hits of a camera are sent with an order of half a step above the [`Camera::order`], so
+//! that nodes are in front of the world rendered by the same camera and behind the cameras
+//! rendered after it.
+
+use crate::{CalculatedClip, DefaultUiCamera, Node, TargetCamera, UiScale, UiStack};
+use bevy_ecs::{prelude::*, query::QueryData};
+use bevy_math::Vec2;
+use bevy_picking::{
+    backend::{HitData, PointerHits},
+    pointer::{PointerId, PointerLocation},
+    PickingBehavior,
+};
+use bevy_render::{camera::Camera, view::ViewVisibility};
+use bevy_transform::prelude::GlobalTransform;
+use bevy_utils::HashMap;
+use bevy_window::PrimaryWindow;
+
+/// Main query for [`ui_picking`]
+#[derive(QueryData)]
+pub struct NodeQuery {
+    entity: Entity,
+    node: &'static Node,
+    global_transform: &'static GlobalTransform,
+    picking_behavior: Option<&'static PickingBehavior>,
+    calculated_clip: Option<&'static CalculatedClip>,
+    view_visibility: Option<&'static ViewVisibility>,
+    target_camera: Option<&'static TargetCamera>,
+}
+
+/// Sends the UI nodes under each pointer as [`PointerHits`], one event per camera.
+#[allow(clippy::too_many_arguments)]
+pub fn ui_picking(
+    pointers: Query<(&PointerId, &PointerLocation)>,
+    cameras: Query<(Entity, &Camera)>,
+    default_ui_camera: DefaultUiCamera,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    ui_scale: Res<UiScale>,
+    ui_stack: Res<UiStack>,
+    node_query: Query<NodeQuery>,
+    mut output: EventWriter<PointerHits>,
+) {
+    let primary_window = primary_window.get_single().ok();
+    let default_camera = default_ui_camera.get();
+
+    for (&pointer_id, pointer_location) in &pointers {
+        let Some(location) = pointer_location.location() else {
+            continue;
+        };
+
+        // The pointer position in the logical UI viewport coordinates of each camera
+        let camera_positions: HashMap<Entity, Vec2> = cameras
+            .iter()
+            .filter(|(_, camera)| {
+                camera.is_active && location.is_in_viewport(camera, primary_window)
+            })
+            .map(|(entity, camera)| {
+                let viewport_position = camera
+                    .logical_viewport_rect()
+                    .map(|rect| rect.min)
+                    .unwrap_or_default();
+                (entity, (location.position - viewport_position) / ui_scale.0)
+            })
+            .collect();
+        if camera_positions.is_empty() {
+            continue;
+        }
+
+        // The hits of each camera, and whether a node blocked the nodes below it
+        let mut camera_hits: HashMap<Entity, (Vec<(Entity, HitData)>, bool)> = HashMap::new();
+        // Traverse the stack from the closest nodes to the furthest
+        for entity in ui_stack.uinodes.iter().rev() {
+            let Ok(node) = node_query.get(*entity) else {
+                continue;
+            };
+            // Nodes that are not rendered should not be pickable
+            if !node
+                .view_visibility
+                .is_some_and(|visibility| visibility.get())
+            {
+                continue;
+            }
+            let Some(camera_entity) = node
+                .target_camera
+                .map(TargetCamera::entity)
+                .or(default_camera)
+            else {
+                continue;
+            };
+            let Some(position) = camera_positions.get(&camera_entity) else {
+                continue;
+            };
+            let (hits, blocked) = camera_hits.entry(camera_entity).or_default();
+            if *blocked {
+                continue;
+            }
+
+            let node_rect = node.node.logical_rect(node.global_transform);
+            // Empty nodes can't be hit, even when the pointer is on their edge
+            if node_rect.size().cmple(Vec2::ZERO).any() {
+                continue;
+            }
+            let visible_rect = node
+                .calculated_clip
+                .map(|clip| node_rect.intersect(clip.clip))
+                .unwrap_or(node_rect);
+            if !visible_rect.contains(*position) {
+                continue;
+            }
+
+            // Nodes are ordered by the stack rather than by a distance to the camera
+            let depth = hits.len() as f32;
+            hits.push((*entity, HitData::new(camera_entity, depth, None, None)));
+            *blocked = node
+                .picking_behavior
+                .map_or(true, |behavior| behavior.should_block_lower);
+        }
+
+        for (camera_entity, (hits, _)) in camera_hits {
+            if hits.is_empty() {
+                continue;
+            }
+            let Ok((_, camera)) = cameras.get(camera_entity) else {
+                continue;
+            };
+            let order = camera.order as f32 + 0.5;
+            output.send(PointerHits::new(pointer_id, hits, order));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IsDefaultUiCamera;
+    use bevy_asset::{AssetEvent, Assets};
+    use bevy_core_pipeline::core_2d::Camera2dBundle;
+    use bevy_ecs::{event::Events, system::RunSystemOnce};
+    use bevy_math::Rect;
+    use bevy_picking::pointer::{Location, PointerBundle};
+    use bevy_render::{
+        camera::{ManualTextureViews, NormalizedRenderTarget, OrthographicProjection},
+        texture::Image,
+    };
+    use bevy_window::{
+        Window, WindowCreated, WindowRef, WindowResized, WindowResolution, WindowScaleFactorChanged,
+    };
+
+    fn spawn_node(world: &mut World, visible: bool) -> EntityWorldMut {
+        let mut view_visibility = ViewVisibility::default();
+        if visible {
+            view_visibility.set();
+        }
+        world.spawn((
+            Node {
+                calculated_size: Vec2::splat(100.0),
+                ..Node::DEFAULT
+            },
+            GlobalTransform::from_xyz(400.0, 300.0, 0.0),
+            view_visibility,
+        ))
+    }
+
+    #[test]
+    fn nodes_are_picked_from_the_top_of_the_stack() {
+        let mut world = World::new();
+        world.init_resource::<Events<WindowResized>>();
+        world.init_resource::<Events<WindowCreated>>();
+        world.init_resource::<Events<WindowScaleFactorChanged>>();
+        world.init_resource::<Events<AssetEvent<Image>>>();
+        world.init_resource::<Assets<Image>>();
+        world.init_resource::<ManualTextureViews>();
+        world.init_resource::<UiScale>();
+        world.init_resource::<Events<PointerHits>>();
+
+        let window = world
+            .spawn((
+                Window {
+                    resolution: WindowResolution::new(800.0, 600.0),
+                    ..Default::default()
+                },
+                PrimaryWindow,
+            ))
+            .id();
+        let camera = world
+            .spawn((Camera2dBundle::default(), IsDefaultUiCamera))
+            .id();
+        let mut other_camera = Camera2dBundle::default();
+        other_camera.camera.order = 1;
+        let other_camera = world.spawn(other_camera).id();
+        // A pointer at the center of the window
+        let location = Location {
+            target: NormalizedRenderTarget::Window(
+                WindowRef::Primary.normalize(Some(window)).unwrap(),
+            ),
+            position: Vec2::new(400.0, 300.0),
+        };
+        world.spawn(PointerBundle {
+            location: PointerLocation::new(location),
+            ..PointerBundle::new(PointerId::Mouse)
+        });
+
+        let below = spawn_node(&mut world, true).id();
+        let blocking = spawn_node(&mut world, true).id();
+        let clipped = spawn_node(&mut world, true)
+            .insert(CalculatedClip {
+                clip: Rect::new(0.0, 0.0, 100.0, 100.0),
+            })
+            .id();
+        let see_through = spawn_node(&mut world, true)
+            .insert(PickingBehavior {
+                should_block_lower: false,
+                is_hoverable: true,
+            })
+            .id();
+        let hidden = spawn_node(&mut world, false).id();
+        let other_camera_node = spawn_node(&mut world, true)
+            .insert(TargetCamera(other_camera))
+            .id();
+        world.insert_resource(UiStack {
+            uinodes: vec![
+                below,
+                blocking,
+                clipped,
+                see_through,
+                hidden,
+                other_camera_node,
+            ],
+        });
+
+        world.run_system_once(bevy_render::camera::camera_system::<OrthographicProjection>);
+        world.run_system_once(ui_picking);
+
+        let mut hits: Vec<_> = world
+            .resource_mut::<Events<PointerHits>>()
+            .drain()
+            .collect();
+        hits.sort_by(|a, b| a.order.total_cmp(&b.order));
+        let picks = |hits: &PointerHits| -> Vec<(Entity, Entity, f32)> {
+            hits.picks
+                .iter()
+                .map(|(entity, hit)| (*entity, hit.camera, hit.depth))
+                .collect()
+        };
+        assert_eq!(hits.len(), 2);
+        // The nodes are in front of the world rendered by their camera
+        assert_eq!(hits[0].order, 0.5);
+        assert_eq!(
+            picks(&hits[0]),
+            [(see_through, camera, 0.0), (blocking, camera, 1.0)]
+        );
+        assert_eq!(hits[1].order, 1.5);
+        assert_eq!(picks(&hits[1]), [(other_camera_node, other_camera, 0.0)]);
+    }
+}
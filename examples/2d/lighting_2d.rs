@@ -0,0 +1,155 @@
+//! Lights a 2D scene with point, spot and ambient lights, normal-mapped sprites and occluders
+//! casting soft shadows.
+//!
+//! Move the mouse to move the point light.
+
+use bevy::{
+    prelude::*,
+    render::texture::ImageLoaderSettings,
+    sprite::{MaterialMesh2dBundle, Mesh2dHandle},
+};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_systems(Startup, setup)
+        .add_systems(Update, (move_point_light, rotate_spot_light))
+        .run();
+}
+
+#[derive(Component)]
+struct MovingLight;
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    // Lighting is enabled for the cameras with an ambient light
+    commands.spawn((
+        Camera2dBundle::default(),
+        AmbientLight2d {
+            color: Color::WHITE,
+            brightness: 0.05,
+        },
+    ));
+
+    // The floor
+    commands.spawn(SpriteBundle {
+        sprite: Sprite {
+            color: Color::srgb(0.6, 0.6, 0.6),
+            custom_size: Some(Vec2::new(1280.0, 720.0)),
+            ..default()
+        },
+        transform: Transform::from_xyz(0.0, 0.0, -1.0),
+        ..default()
+    });
+
+    // Normal maps hold directions, not colors, so they aren't loaded as sRGB
+    let normal_map = asset_server.load_with_settings(
+        "textures/BlueNoise-Normal.png",
+        |settings: &mut ImageLoaderSettings| settings.is_srgb = false,
+    );
+    for x in [-400.0, 400.0] {
+        commands.spawn((
+            SpriteBundle {
+                texture: asset_server.load("branding/icon.png"),
+                sprite: Sprite {
+                    custom_size: Some(Vec2::splat(160.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(x, 150.0, 0.0),
+                ..default()
+            },
+            SpriteNormalMap(normal_map.clone()),
+        ));
+    }
+
+    // Each occluder is drawn with a mesh of the same shape
+    let material = materials.add(Color::srgb(0.2, 0.2, 0.3));
+    let shapes: [(LightOccluder2d, Mesh, Vec3); 3] = [
+        (
+            Circle::new(40.0).into(),
+            Circle::new(40.0).into(),
+            Vec3::new(-200.0, -100.0, 0.0),
+        ),
+        (
+            Rectangle::new(60.0, 120.0).into(),
+            Rectangle::new(60.0, 120.0).into(),
+            Vec3::new(0.0, -150.0, 0.0),
+        ),
+        (
+            Capsule2d::new(25.0, 80.0).into(),
+            Capsule2d::new(25.0, 80.0).into(),
+            Vec3::new(200.0, -100.0, 0.0),
+        ),
+    ];
+    for (occluder, mesh, translation) in shapes {
+        commands.spawn((
+            MaterialMesh2dBundle {
+                mesh: Mesh2dHandle(meshes.add(mesh)),
+                material: material.clone(),
+                transform: Transform::from_translation(translation),
+                ..default()
+            },
+            occluder,
+        ));
+    }
+
+    commands.spawn((
+        PointLight2dBundle {
+            point_light: PointLight2d {
+                color: Color::srgb(1.0, 0.9, 0.7),
+                intensity: 1.5,
+                range: 500.0,
+                radius: 10.0,
+                shadows_enabled: true,
+                ..default()
+            },
+            ..default()
+        },
+        MovingLight,
+    ));
+
+    commands.spawn(SpotLight2dBundle {
+        spot_light: SpotLight2d {
+            color: Color::srgb(0.4, 0.6, 1.0),
+            intensity: 2.0,
+            range: 700.0,
+            radius: 4.0,
+            shadows_enabled: true,
+            inner_angle: 0.2,
+            outer_angle: 0.4,
+            ..default()
+        },
+        transform: Transform::from_xyz(0.0, 300.0, 0.0),
+        ..default()
+    });
+}
+
+fn move_point_light(
+    camera_query: Query<(&Camera, &GlobalTransform)>,
+    windows: Query<&Window>,
+    mut lights: Query<&mut Transform, With<MovingLight>>,
+) {
+    let (camera, camera_transform) = camera_query.single();
+    let Some(cursor_position) = windows.single().cursor_position() else {
+        return;
+    };
+    let Some(point) = camera.viewport_to_world_2d(camera_transform, cursor_position) else {
+        return;
+    };
+
+    for mut transform in &mut lights {
+        transform.translation = point.extend(transform.translation.z);
+    }
+}
+
+fn rotate_spot_light(time: Res<Time>, mut lights: Query<&mut Transform, With<SpotLight2d>>) {
+    for mut transform in &mut lights {
+        // Sweep the cone back and forth under the light
+        transform.rotation =
+            Quat::from_rotation_z(std::f32::consts::PI + time.elapsed_seconds().sin() * 0.8);
+    }
+}
@@ -0,0 +1,66 @@
+//! Draws sprites with a custom [`SpriteMaterial`] that makes them flash, as when they are hit.
+
+use bevy::{
+    prelude::*,
+    render::render_resource::{AsBindGroup, ShaderRef},
+};
+
+fn main() {
+    App::new()
+        .add_plugins((
+            DefaultPlugins,
+            SpriteMaterialPlugin::<FlashMaterial>::default(),
+        ))
+        .add_systems(Startup, setup)
+        .add_systems(Update, flash)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<FlashMaterial>>,
+) {
+    commands.spawn(Camera2dBundle::default());
+
+    let texture = asset_server.load("branding/icon.png");
+    // Sprites sharing a material and an image are drawn in a single batch
+    let material = materials.add(FlashMaterial {
+        color: LinearRgba::new(1.0, 0.2, 0.2, 0.0),
+    });
+    for (x, flip_x) in [(-300.0, false), (0.0, true), (300.0, false)] {
+        commands.spawn((
+            SpriteBundle {
+                texture: texture.clone(),
+                sprite: Sprite {
+                    flip_x,
+                    custom_size: Some(Vec2::splat(200.0)),
+                    ..default()
+                },
+                transform: Transform::from_xyz(x, 0.0, 0.0),
+                ..default()
+            },
+            material.clone(),
+        ));
+    }
+}
+
+fn flash(time: Res<Time>, mut materials: ResMut<Assets<FlashMaterial>>) {
+    for (_, material) in materials.iter_mut() {
+        material.color.alpha = (time.elapsed_seconds() * 4.0).sin().max(0.0);
+    }
+}
+
+// This struct defines the data that will be passed to the shader
+#[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
+struct FlashMaterial {
+    /// The color the sprite flashes to, with the intensity of the flash as its alpha.
+    #[uniform(0)]
+    color: LinearRgba,
+}
+
+impl SpriteMaterial for FlashMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/sprite_flash.wgsl".into()
+    }
+}
@@ -0,0 +1,110 @@
+//! Draws a large tilemap from a tileset, with flipped, tinted and animated tiles.
+//!
+//! A column of tiles is replaced every frame, which only uploads the chunks it crosses again.
+//! Press space to change the grid of the tilemap.
+
+use std::time::Duration;
+
+use bevy::prelude::*;
+
+const MAP_SIZE: UVec2 = UVec2::new(200, 120);
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_systems(Startup, setup)
+        .add_systems(Update, (sweep_tiles, cycle_grid))
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    commands.spawn(Camera2dBundle::default());
+
+    // The four images of the texture are stacked vertically
+    let layout = layouts.add(TextureAtlasLayout::from_grid(
+        UVec2::splat(250),
+        1,
+        4,
+        None,
+        None,
+    ));
+
+    let mut tilemap = Tilemap::new(MAP_SIZE, Vec2::splat(250.0), TilemapGrid::Square);
+    for y in 0..MAP_SIZE.y {
+        for x in 0..MAP_SIZE.x {
+            // A cheap hash of the position picks the tile
+            let tile = match (x.wrapping_mul(73_856_093) ^ y.wrapping_mul(19_349_663)) % 16 {
+                0 => Tile {
+                    animation: Some(TileAnimation {
+                        frame_count: 4,
+                        frame_duration: Duration::from_millis(250),
+                    }),
+                    ..Tile::new(0)
+                },
+                1..=4 => Tile {
+                    flip_x: true,
+                    ..Tile::new(1)
+                },
+                5..=8 => Tile {
+                    color: Color::srgb(0.7, 0.8, 1.0),
+                    ..Tile::new(2)
+                },
+                _ => Tile::new(3),
+            };
+            tilemap.set(UVec2::new(x, y), tile);
+        }
+    }
+
+    commands.spawn(TilemapBundle {
+        transform: Transform::from_xyz(-640.0, -360.0, 0.0).with_scale(Vec3::splat(0.05)),
+        ..TilemapBundle::new(
+            tilemap,
+            asset_server.load("textures/array_texture.png"),
+            layout,
+        )
+    });
+}
+
+fn sweep_tiles(time: Res<Time>, mut tilemaps: Query<&mut Tilemap>) {
+    for mut tilemap in &mut tilemaps {
+        let size = tilemap.size();
+        let column = (time.elapsed_seconds() * 20.0) as u32 % size.x;
+        for y in 0..size.y {
+            let tile = Tile {
+                color: Color::hsl((column * 3 + y) as f32 % 360.0, 0.8, 0.7),
+                ..Tile::new(3)
+            };
+            tilemap.set(UVec2::new(column, y), tile);
+        }
+    }
+}
+
+fn cycle_grid(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    mut tilemaps: Query<(&mut Tilemap, &mut Transform)>,
+) {
+    if !keyboard.just_pressed(KeyCode::Space) {
+        return;
+    }
+
+    for (mut tilemap, mut transform) in &mut tilemaps {
+        tilemap.grid = match tilemap.grid {
+            TilemapGrid::Square => TilemapGrid::Isometric,
+            TilemapGrid::Isometric => TilemapGrid::Hexagonal {
+                stagger_axis: default(),
+                stagger_index: default(),
+                side_length: 125.0,
+            },
+            TilemapGrid::Hexagonal { .. } => TilemapGrid::Square,
+        };
+        // Isometric maps start from their top corner and go down
+        transform.translation = match tilemap.grid {
+            TilemapGrid::Isometric => Vec3::new(0.0, 360.0, 0.0),
+            _ => Vec3::new(-640.0, -360.0, 0.0),
+        };
+    }
+}
@@ -0,0 +1,183 @@
+//! Renders a physically based sky, with aerial perspective and an environment map lit by it.
+
+use bevy::{
+    core_pipeline::{bloom::BloomSettings, tonemapping::Tonemapping},
+    pbr::{
+        Atmosphere, AtmosphereEnvironmentMap, AtmosphereEnvironmentMapLight, AtmosphereSettings,
+        CascadeShadowConfigBuilder,
+    },
+    prelude::*,
+    render::camera::Exposure,
+};
+use std::f32::consts::PI;
+
+const INSTRUCTIONS: &str = "\
+Controls
+--------
+Move the sun: Left and Right
+Toggle sky lighting: Space";
+
+fn main() {
+    App::new()
+        .insert_resource(AmbientLight::NONE)
+        .add_plugins(DefaultPlugins)
+        .add_systems(Startup, setup)
+        .add_systems(Update, (move_sun, toggle_environment_map_light))
+        .run();
+}
+
+#[derive(Component)]
+struct Sun;
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // The sky is much brighter than artificial lights, so the camera needs an HDR target and a
+    // physically based exposure
+    commands.spawn((
+        Camera3dBundle {
+            camera: Camera {
+                hdr: true,
+                ..default()
+            },
+            tonemapping: Tonemapping::AcesFitted,
+            exposure: Exposure::SUNLIGHT,
+            transform: Transform::from_xyz(-2.4, 0.4, 2.0)
+                .looking_at(Vec3::new(0.0, 0.6, 0.0), Vec3::Y),
+            ..default()
+        },
+        Atmosphere::EARTH,
+        // Scene units are kilometers, so that the mountains fade into the distance
+        AtmosphereSettings {
+            scene_units_to_m: 1_000.0,
+            ..default()
+        },
+        AtmosphereEnvironmentMapLight::default(),
+        BloomSettings::NATURAL,
+    ));
+
+    // The illuminance of the sun above the atmosphere, which dims and reddens it near the horizon
+    commands.spawn((
+        DirectionalLightBundle {
+            directional_light: DirectionalLight {
+                illuminance: light_consts::lux::DIRECT_SUNLIGHT,
+                shadows_enabled: true,
+                ..default()
+            },
+            cascade_shadow_config: CascadeShadowConfigBuilder {
+                first_cascade_far_bound: 0.3,
+                maximum_distance: 3.0,
+                ..default()
+            }
+            .build(),
+            ..default()
+        },
+        Sun,
+    ));
+
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Plane3d::default().mesh().size(200.0, 200.0)),
+        material: materials.add(StandardMaterial {
+            base_color: Color::srgb(0.3, 0.4, 0.25),
+            perceptual_roughness: 1.0,
+            ..default()
+        }),
+        ..default()
+    });
+
+    // A range of mountains, a few kilometers away
+    let mountain = meshes.add(Cone {
+        radius: 1.0,
+        height: 1.0,
+    });
+    let rock = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.5, 0.45, 0.4),
+        perceptual_roughness: 1.0,
+        ..default()
+    });
+    for i in 0..12 {
+        let angle = i as f32 / 12.0 * PI - PI / 2.0;
+        let distance = 4.0 + (i % 3) as f32 * 3.0;
+        let size = 1.0 + (i % 4) as f32 * 0.5;
+        commands.spawn(PbrBundle {
+            mesh: mountain.clone(),
+            material: rock.clone(),
+            transform: Transform::from_xyz(
+                angle.sin() * distance,
+                size / 2.0,
+                -angle.cos() * distance,
+            )
+            .with_scale(Vec3::new(size * 1.5, size, size * 1.5)),
+            ..default()
+        });
+    }
+
+    // The spheres are lit by the sky through the environment map
+    let sphere = meshes.add(Sphere::new(0.1).mesh().uv(64, 32));
+    for (i, roughness) in [0.0, 0.5, 1.0].into_iter().enumerate() {
+        commands.spawn(PbrBundle {
+            mesh: sphere.clone(),
+            material: materials.add(StandardMaterial {
+                base_color: Color::WHITE,
+                metallic: 1.0 - roughness,
+                perceptual_roughness: roughness,
+                ..default()
+            }),
+            transform: Transform::from_xyz(-0.5 + i as f32 * 0.5, 0.1, 0.0),
+            ..default()
+        });
+    }
+
+    commands.spawn(
+        TextBundle::from_section(INSTRUCTIONS, TextStyle::default()).with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        }),
+    );
+}
+
+fn move_sun(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    mut sun: Query<&mut Transform, With<Sun>>,
+    mut elevation: Local<Option<f32>>,
+) {
+    let elevation = elevation.get_or_insert(PI / 8.0);
+    if keyboard.pressed(KeyCode::ArrowLeft) {
+        *elevation = (*elevation - 0.3 * time.delta_seconds()).max(-PI / 16.0);
+    }
+    if keyboard.pressed(KeyCode::ArrowRight) {
+        *elevation = (*elevation + 0.3 * time.delta_seconds()).min(PI / 2.0);
+    }
+    for mut transform in &mut sun {
+        *transform =
+            Transform::from_rotation(Quat::from_euler(EulerRot::YXZ, PI, -*elevation, 0.0));
+    }
+}
+
+fn toggle_environment_map_light(
+    mut commands: Commands,
+    keyboard: Res<ButtonInput<KeyCode>>,
+    cameras: Query<(Entity, Has<AtmosphereEnvironmentMapLight>), With<Atmosphere>>,
+) {
+    if !keyboard.just_pressed(KeyCode::Space) {
+        return;
+    }
+    for (entity, has_environment_map_light) in &cameras {
+        if has_environment_map_light {
+            commands.entity(entity).remove::<(
+                AtmosphereEnvironmentMapLight,
+                AtmosphereEnvironmentMap,
+                EnvironmentMapLight,
+            )>();
+        } else {
+            commands
+                .entity(entity)
+                .insert(AtmosphereEnvironmentMapLight::default());
+        }
+    }
+}
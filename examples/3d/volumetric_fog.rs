@@ -0,0 +1,165 @@
+//! Demonstrates volumetric fog and the light shafts it produces around shadow casters.
+
+use bevy::{
+    pbr::{VolumetricFogBundle, VolumetricFogSettings, VolumetricLight},
+    prelude::*,
+};
+use std::f32::consts::PI;
+
+const INSTRUCTIONS: &str = "\
+Controls
+--------
+Fog density: Up and Down
+Scattering asymmetry: Left and Right
+Toggle spot light: Space";
+
+fn main() {
+    App::new()
+        .insert_resource(AmbientLight::NONE)
+        .add_plugins(DefaultPlugins)
+        .add_systems(Startup, setup)
+        .add_systems(Update, (rotate_sun, update_fog))
+        .run();
+}
+
+#[derive(Component)]
+struct Sun;
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // The fog needs the depth prepass, which the bundle adds, to know where to stop marching
+    commands.spawn((
+        Camera3dBundle {
+            camera: Camera {
+                hdr: true,
+                ..default()
+            },
+            transform: Transform::from_xyz(-8.0, 3.0, 10.0)
+                .looking_at(Vec3::new(0.0, 2.0, 0.0), Vec3::Y),
+            ..default()
+        },
+        VolumetricFogBundle {
+            settings: VolumetricFogSettings {
+                ambient_intensity: 0.02,
+                density: 0.15,
+                ..default()
+            },
+            ..default()
+        },
+    ));
+
+    let material = materials.add(StandardMaterial {
+        base_color: Color::srgb(0.6, 0.6, 0.6),
+        perceptual_roughness: 1.0,
+        ..default()
+    });
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Plane3d::default().mesh().size(40.0, 40.0)),
+        material: material.clone(),
+        ..default()
+    });
+
+    // A row of pillars under a roof, which cut the sunlight into shafts
+    let pillar = meshes.add(Cuboid::new(0.6, 5.0, 0.6));
+    for i in 0..6 {
+        commands.spawn(PbrBundle {
+            mesh: pillar.clone(),
+            material: material.clone(),
+            transform: Transform::from_xyz(i as f32 * 2.0 - 5.0, 2.5, -2.0),
+            ..default()
+        });
+    }
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Cuboid::new(12.0, 0.4, 4.0)),
+        material: material.clone(),
+        transform: Transform::from_xyz(0.0, 5.2, -1.0),
+        ..default()
+    });
+
+    // Only lights with a `VolumetricLight` scatter light in the fog, and they need shadows to
+    // cast light shafts
+    commands.spawn((
+        DirectionalLightBundle {
+            directional_light: DirectionalLight {
+                illuminance: light_consts::lux::OVERCAST_DAY,
+                shadows_enabled: true,
+                ..default()
+            },
+            ..default()
+        },
+        VolumetricLight,
+        Sun,
+    ));
+    commands.spawn((
+        SpotLightBundle {
+            spot_light: SpotLight {
+                color: Color::srgb(1.0, 0.6, 0.3),
+                intensity: 500_000.0,
+                range: 20.0,
+                shadows_enabled: true,
+                outer_angle: PI / 8.0,
+                inner_angle: PI / 10.0,
+                ..default()
+            },
+            transform: Transform::from_xyz(6.0, 6.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        VolumetricLight,
+    ));
+
+    commands.spawn(
+        TextBundle::from_section("", TextStyle::default()).with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        }),
+    );
+}
+
+fn rotate_sun(time: Res<Time>, mut sun: Query<&mut Transform, With<Sun>>) {
+    let angle = time.elapsed_seconds() * 0.2;
+    for mut transform in &mut sun {
+        *transform =
+            Transform::from_rotation(Quat::from_euler(EulerRot::YXZ, angle, -PI / 5.0, 0.0));
+    }
+}
+
+fn update_fog(
+    keyboard: Res<ButtonInput<KeyCode>>,
+    time: Res<Time>,
+    mut fog: Query<&mut VolumetricFogSettings>,
+    mut spot_lights: Query<&mut Visibility, With<SpotLight>>,
+    mut text: Query<&mut Text>,
+) {
+    let mut settings = fog.single_mut();
+    let delta = time.delta_seconds();
+    if keyboard.pressed(KeyCode::ArrowUp) {
+        settings.density = (settings.density + 0.1 * delta).min(1.0);
+    }
+    if keyboard.pressed(KeyCode::ArrowDown) {
+        settings.density = (settings.density - 0.1 * delta).max(0.0);
+    }
+    if keyboard.pressed(KeyCode::ArrowRight) {
+        settings.scattering_asymmetry = (settings.scattering_asymmetry + delta).min(0.99);
+    }
+    if keyboard.pressed(KeyCode::ArrowLeft) {
+        settings.scattering_asymmetry = (settings.scattering_asymmetry - delta).max(-0.99);
+    }
+    if keyboard.just_pressed(KeyCode::Space) {
+        for mut visibility in &mut spot_lights {
+            *visibility = match *visibility {
+                Visibility::Hidden => Visibility::Inherited,
+                _ => Visibility::Hidden,
+            };
+        }
+    }
+
+    text.single_mut().sections[0].value = format!(
+        "{INSTRUCTIONS}\n\nDensity: {:.2}\nScattering asymmetry: {:.2}",
+        settings.density, settings.scattering_asymmetry
+    );
+}
@@ -461,16 +461,16 @@ fn spawn_primitive_2d(
     let material: Handle<ColorMaterial> = materials.add(Color::WHITE);
     let camera_mode = CameraActive::Dim2;
     [
-        Some(RECTANGLE.mesh()),
+        Some(RECTANGLE.mesh().build()),
         Some(CIRCLE.mesh().build()),
         Some(ELLIPSE.mesh().build()),
-        Some(TRIANGLE.mesh()),
+        Some(TRIANGLE.mesh().build()),
         None, // plane
         None, // line
-        None, // segment
-        None, // polyline
-        None, // polygon
-        Some(REGULAR_POLYGON.mesh()),
+        Some(SEGMENT_2D.mesh().build()),
+        Some(POLYLINE_2D.mesh().build()),
+        Some(POLYGON_2D.mesh().build()),
+        Some(REGULAR_POLYGON.mesh().build()),
         Some(CAPSULE_2D.mesh().build()),
         None, // cylinder
         None, // cone
@@ -507,20 +507,20 @@ fn spawn_primitive_3d(
     let material: Handle<StandardMaterial> = materials.add(Color::WHITE);
     let camera_mode = CameraActive::Dim3;
     [
-        Some(CUBOID.mesh()),
+        Some(CUBOID.mesh().build()),
         Some(SPHERE.mesh().build()),
         None, // ellipse
         None, // triangle
         Some(PLANE_3D.mesh().build()),
         None, // line
-        None, // segment
-        None, // polyline
+        Some(SEGMENT_3D.mesh().build()),
+        Some(POLYLINE_3D.mesh().build()),
         None, // polygon
         None, // regular polygon
         Some(CAPSULE_3D.mesh().build()),
         Some(CYLINDER.mesh().build()),
-        None, // cone
-        None, // conical frustum
+        Some(CONE.mesh().build()),
+        Some(CONICAL_FRUSTUM.mesh().build()),
         Some(TORUS.mesh().build()),
     ]
     .into_iter()
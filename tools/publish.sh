@@ -31,6 +31,7 @@ crates=(
     bevy_render
     bevy_core_pipeline
     bevy_input
+    bevy_picking
     bevy_gilrs
     bevy_animation
     bevy_pbr